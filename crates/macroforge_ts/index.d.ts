@@ -32,6 +32,11 @@ export declare class NativeMapper {
    * See [`NativePositionMapper::generated_by`] for details.
    */
   generatedBy(pos: number): string | null
+  /**
+   * Returns the original span replaced by the generated code at the given position.
+   * See [`NativePositionMapper::replaced_span`] for details.
+   */
+  replacedSpan(pos: number): SpanResult | null
   /**
    * Maps a span from expanded source to original source.
    * See [`NativePositionMapper::map_span_to_original`] for details.
@@ -299,6 +304,23 @@ export declare class PositionMapper {
    * `None` if the position is in original (non-generated) code.
    */
   generatedBy(pos: number): string | null
+  /**
+   * Returns the original span replaced by the generated code at the given position.
+   *
+   * Call macro invocations and other replacements map every position of
+   * their output to the code they replaced, so diagnostics inside the
+   * output can be reported at the invocation.
+   *
+   * # Arguments
+   *
+   * * `pos` - Byte offset in the expanded source
+   *
+   * # Returns
+   *
+   * `Some(SpanResult)` with the replaced span in original source,
+   * `None` if the position is not inside replacement code.
+   */
+  replacedSpan(pos: number): SpanResult | null
   /**
    * Maps a span (start + length) from expanded source to original source.
    *
@@ -674,6 +696,20 @@ export declare function __macroforgeRunPartialOrd(contextJson: string): string
  */
 export declare function __macroforgeRunSerialize(contextJson: string): string
 
+/**
+ * Declares the `buildEnv` call macro for TypeScript type checking.
+ *
+ * Calls are replaced during macro expansion; see `builtin::calls`.
+ */
+export declare function buildEnv<T = never>(name: string, fallback?: T): string | T
+
+/**
+ * Declares the `buildInfo` call macro for TypeScript type checking.
+ *
+ * Calls are replaced during macro expansion; see `builtin::calls`.
+ */
+export declare function buildInfo(): { name: string | undefined; version: string; timestamp: string }
+
 /**
  * Checks if the given TypeScript code has valid syntax.
  *
@@ -720,6 +756,13 @@ export declare function checkSyntax(code: string, filepath: string): SyntaxCheck
  */
 export declare function clearConfigCache(): void
 
+/**
+ * Declares the `concatIdents` call macro for TypeScript type checking.
+ *
+ * Calls are replaced during macro expansion; see `builtin::calls`.
+ */
+export declare function concatIdents(...parts: any[]): any
+
 /**
  * Entry for a registered decorator in the manifest.
  *
@@ -737,6 +780,14 @@ export interface DecoratorManifestEntry {
   docs: string
 }
 
+/**
+ * Declares the `deprecated` attribute macro for TypeScript type checking.
+ *
+ * Decorators and JSDoc tags using it are replaced during macro expansion;
+ * see `builtin::attributes`.
+ */
+export declare function deprecated(...args: any[]): any
+
 /**
  * The `@Derive` decorator function exported to JavaScript/TypeScript.
  *
@@ -846,6 +897,11 @@ export interface ExpandResult {
   diagnostics: Array<MacroDiagnostic>
   /** Source mapping for position translation between original and expanded code. */
   sourceMapping?: SourceMappingResult
+  /**
+   * Files read by macros (e.g. `includeStr`); the source must be expanded
+   * again when one of them changes.
+   */
+  dependencies: Array<string>
 }
 
 /**
@@ -906,6 +962,13 @@ export interface GeneratedRegionResult {
   end: number
   /** Name of the macro that generated this region (e.g., "Debug", "Clone"). */
   sourceMacro: string
+  /**
+   * Start of the original source range this region replaced, if it is a
+   * replacement (e.g., a call macro invocation).
+   */
+  originalStart?: number
+  /** End of the original source range this region replaced (exclusive). */
+  originalEnd?: number
 }
 
 /**
@@ -920,6 +983,20 @@ export interface ImportSourceResult {
   module: string
 }
 
+/**
+ * Declares the `includeJson` call macro for TypeScript type checking.
+ *
+ * Calls are replaced during macro expansion; see `builtin::calls`.
+ */
+export declare function includeJson<T = any>(path: string): T
+
+/**
+ * Declares the `includeStr` call macro for TypeScript type checking.
+ *
+ * Calls are replaced during macro expansion; see `builtin::calls`.
+ */
+export declare function includeStr(path: string): string
+
 /**
  * A diagnostic from the TypeScript/JavaScript compiler or IDE.
  *
@@ -1094,6 +1171,14 @@ export interface MappingSegmentResult {
   expandedEnd: number
 }
 
+/**
+ * Declares the `memoize` attribute macro for TypeScript type checking.
+ *
+ * Decorators and JSDoc tags using it are replaced during macro expansion;
+ * see `builtin::attributes`.
+ */
+export declare function memoize(...args: any[]): any
+
 /**
  * Parses import statements from TypeScript code and returns their sources.
  *
@@ -1156,6 +1241,14 @@ export interface ProcessFileOptions {
   configPath?: string
 }
 
+/**
+ * Declares the `retry` attribute macro for TypeScript type checking.
+ *
+ * Decorators and JSDoc tags using it are replaced during macro expansion;
+ * see `builtin::attributes`.
+ */
+export declare function retry(...args: any[]): any
+
 /**
  * Complete source mapping information for a macro expansion.
  *
@@ -1206,6 +1299,14 @@ export interface SyntaxCheckResult {
   error?: string
 }
 
+/**
+ * Declares the `trace` attribute macro for TypeScript type checking.
+ *
+ * Decorators and JSDoc tags using it are replaced during macro expansion;
+ * see `builtin::attributes`.
+ */
+export declare function trace(...args: any[]): any
+
 /**
  * Result of transforming TypeScript code through the macro system.
  *
@@ -591,10 +591,19 @@ module.exports.__macroforgeRunOrd = nativeBinding.__macroforgeRunOrd
 module.exports.__macroforgeRunPartialEq = nativeBinding.__macroforgeRunPartialEq
 module.exports.__macroforgeRunPartialOrd = nativeBinding.__macroforgeRunPartialOrd
 module.exports.__macroforgeRunSerialize = nativeBinding.__macroforgeRunSerialize
+module.exports.buildEnv = nativeBinding.buildEnv
+module.exports.buildInfo = nativeBinding.buildInfo
 module.exports.checkSyntax = nativeBinding.checkSyntax
 module.exports.clearConfigCache = nativeBinding.clearConfigCache
+module.exports.concatIdents = nativeBinding.concatIdents
+module.exports.deprecated = nativeBinding.deprecated
 module.exports.Derive = nativeBinding.Derive
 module.exports.expandSync = nativeBinding.expandSync
+module.exports.includeJson = nativeBinding.includeJson
+module.exports.includeStr = nativeBinding.includeStr
 module.exports.loadConfig = nativeBinding.loadConfig
+module.exports.memoize = nativeBinding.memoize
 module.exports.parseImportSources = nativeBinding.parseImportSources
+module.exports.retry = nativeBinding.retry
+module.exports.trace = nativeBinding.trace
 module.exports.transformSync = nativeBinding.transformSync
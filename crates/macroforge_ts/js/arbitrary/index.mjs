@@ -0,0 +1,402 @@
+// js/arbitrary/index.ts
+class Random {
+  seed;
+  state;
+  constructor(seed = Math.floor(Math.random() * 4294967296)) {
+    this.seed = seed >>> 0;
+    this.state = this.seed;
+  }
+  next() {
+    this.state = this.state + 1831565813 >>> 0;
+    let t = this.state;
+    t = Math.imul(t ^ t >>> 15, t | 1);
+    t ^= t + Math.imul(t ^ t >>> 7, t | 61);
+    return ((t ^ t >>> 14) >>> 0) / 4294967296;
+  }
+  int(min, max) {
+    return min + Math.floor(this.next() * (max - min + 1));
+  }
+  bool() {
+    return this.next() < 0.5;
+  }
+  chance(p) {
+    return this.next() < p;
+  }
+  pick(items) {
+    return items[this.int(0, items.length - 1)];
+  }
+}
+
+class ArbitraryError extends Error {
+  constructor(message) {
+    super(message);
+    this.name = "ArbitraryError";
+  }
+}
+var LOWER = "abcdefghijklmnopqrstuvwxyz";
+var UPPER = LOWER.toUpperCase();
+var DIGITS = "0123456789";
+var WORD = LOWER + UPPER + DIGITS + "_";
+var PRINTABLE = Array.from({ length: 95 }, (_, i) => String.fromCharCode(32 + i)).join("");
+function chars(rng, alphabet, length) {
+  let out = "";
+  for (let i = 0;i < length; i++) {
+    out += alphabet[rng.int(0, alphabet.length - 1)];
+  }
+  return out;
+}
+function size(rng, min = 0, max, spread = 8) {
+  return rng.int(min, max ?? min + spread);
+}
+function string(rng, options = {}) {
+  switch (options.format) {
+    case "email":
+      return `${chars(rng, LOWER, size(rng, 1, 10))}@${chars(rng, LOWER, size(rng, 1, 10))}.${rng.pick(["com", "org", "net", "io"])}`;
+    case "url":
+      return `https://${chars(rng, LOWER, size(rng, 1, 10))}.${rng.pick(["com", "org", "dev"])}/${chars(rng, LOWER + DIGITS, size(rng, 0, 8))}`;
+    case "uuid": {
+      const hex = (n) => chars(rng, "0123456789abcdef", n);
+      return `${hex(8)}-${hex(4)}-4${hex(3)}-${rng.pick(["8", "9", "a", "b"])}${hex(3)}-${hex(12)}`;
+    }
+  }
+  if (options.pattern !== undefined) {
+    return fromPattern(rng, options.pattern);
+  }
+  const prefix = options.startsWith ?? "";
+  const suffix = options.endsWith ?? "";
+  const infix = options.includes ?? "";
+  const fixed = prefix.length + suffix.length + infix.length;
+  const min = Math.max((options.minLength ?? 0) - fixed, 0);
+  const max = options.maxLength === undefined ? undefined : Math.max(options.maxLength - fixed, 0);
+  const alphabet = options.case === "lower" ? LOWER + DIGITS : options.case === "upper" ? UPPER + DIGITS : LOWER + UPPER + DIGITS;
+  let core = chars(rng, alphabet, size(rng, min, max));
+  if (options.case === "capitalized" && prefix === "") {
+    core = core.charAt(0).toUpperCase() + core.slice(1);
+  } else if (options.case === "uncapitalized" && prefix === "") {
+    core = core.charAt(0).toLowerCase() + core.slice(1);
+  }
+  const split = rng.int(0, core.length);
+  return prefix + core.slice(0, split) + infix + core.slice(split) + suffix;
+}
+function number(rng, options = {}) {
+  const lo = options.min ?? (options.max !== undefined ? Math.min(options.max, 0) - 1000 : -1000);
+  const hi = options.max ?? (options.min !== undefined ? Math.max(options.min, 0) + 1000 : 1000);
+  const inRange = (v) => (options.minExclusive ? v > lo : v >= lo) && (options.maxExclusive ? v < hi : v <= hi);
+  if (options.multipleOf !== undefined) {
+    const step = options.multipleOf;
+    let kmin = Math.ceil(lo / step);
+    let kmax = Math.floor(hi / step);
+    if (!inRange(kmin * step))
+      kmin++;
+    if (!inRange(kmax * step))
+      kmax--;
+    if (kmin > kmax) {
+      throw new ArbitraryError(`no multiple of ${step} in range [${lo}, ${hi}]`);
+    }
+    return rng.int(kmin, kmax) * step + 0;
+  }
+  if (options.int) {
+    let a = Math.ceil(lo);
+    let b = Math.floor(hi);
+    if (!inRange(a))
+      a++;
+    if (!inRange(b))
+      b--;
+    if (a > b) {
+      throw new ArbitraryError(`no integer in range [${lo}, ${hi}]`);
+    }
+    const edges2 = [a, b, 0].filter((v) => v >= a && v <= b);
+    return rng.chance(0.1) ? rng.pick(edges2) : rng.int(a, b);
+  }
+  const edges = [lo, hi, 0].filter(inRange);
+  if (edges.length > 0 && rng.chance(0.1)) {
+    return rng.pick(edges);
+  }
+  for (let i = 0;i < 100; i++) {
+    const v = lo + rng.next() * (hi - lo);
+    if (inRange(v)) {
+      return v;
+    }
+  }
+  throw new ArbitraryError(`no number in range (${lo}, ${hi})`);
+}
+function bigint(rng, options = {}) {
+  let lo = options.min ?? (options.max !== undefined ? options.max - 1000n : -1000n);
+  let hi = options.max ?? lo + 2000n;
+  if (options.min !== undefined && options.minExclusive)
+    lo += 1n;
+  if (options.max !== undefined && options.maxExclusive)
+    hi -= 1n;
+  if (lo > hi) {
+    throw new ArbitraryError(`no bigint in range [${lo}, ${hi}]`);
+  }
+  let offset = 0n;
+  for (let span = hi - lo + 1n;span > 1n; span >>= 32n) {
+    offset = offset << 32n | BigInt(rng.int(0, 4294967295));
+  }
+  return lo + offset % (hi - lo + 1n);
+}
+function date(rng, options = {}) {
+  const year = 365 * 24 * 60 * 60 * 1000;
+  const lo = options.min?.getTime() ?? (options.max !== undefined ? options.max.getTime() - 10 * year : Date.UTC(2000, 0, 1));
+  const hi = options.max?.getTime() ?? lo + 30 * year;
+  return new Date(number(rng, {
+    min: lo,
+    max: hi,
+    minExclusive: options.minExclusive,
+    maxExclusive: options.maxExclusive,
+    int: true
+  }));
+}
+function array(rng, item, options = {}) {
+  return Array.from({ length: size(rng, options.minItems, options.maxItems, 5) }, () => item(rng));
+}
+function set(rng, item, options = {}) {
+  const target = size(rng, options.minItems, options.maxItems, 5);
+  const out = new Set;
+  for (let tries = 0;out.size < target && tries < target * 10; tries++) {
+    out.add(item(rng));
+  }
+  return out;
+}
+function map(rng, key, value, options = {}) {
+  const out = new Map;
+  for (const k of set(rng, key, options)) {
+    out.set(k, value(rng));
+  }
+  return out;
+}
+function record(rng, key, value, options = {}) {
+  return Object.fromEntries(map(rng, key, value, options));
+}
+function oneOf(rng, options) {
+  return rng.pick(options)(rng);
+}
+function satisfying(rng, gen, predicate, tries = 100) {
+  for (let i = 0;i < tries; i++) {
+    const value = gen(rng);
+    if (predicate(value)) {
+      return value;
+    }
+  }
+  throw new ArbitraryError(`no value satisfied the constraints after ${tries} attempts`);
+}
+
+class PatternParser {
+  source;
+  pos = 0;
+  constructor(source) {
+    this.source = source;
+  }
+  parse() {
+    const node = this.alternation();
+    if (this.pos < this.source.length) {
+      this.fail(`unexpected "${this.source[this.pos]}"`);
+    }
+    return node;
+  }
+  fail(message) {
+    throw new ArbitraryError(`cannot generate from pattern /${this.source}/: ${message}`);
+  }
+  alternation() {
+    const options = [this.sequence()];
+    while (this.source[this.pos] === "|") {
+      this.pos++;
+      options.push(this.sequence());
+    }
+    return options.length === 1 ? options[0] : { kind: "alt", options };
+  }
+  sequence() {
+    const items = [];
+    while (this.pos < this.source.length && !"|)".includes(this.source[this.pos])) {
+      items.push(this.quantified(this.atom()));
+    }
+    return { kind: "seq", items };
+  }
+  quantified(node) {
+    const c = this.source[this.pos];
+    let min;
+    let max;
+    if (c === "*" || c === "+" || c === "?") {
+      this.pos++;
+      min = c === "+" ? 1 : 0;
+      max = c === "?" ? 1 : min + 5;
+    } else if (c === "{" && /^\{\d+(,\d*)?\}/.test(this.source.slice(this.pos))) {
+      const [match, lo, hi] = /^\{(\d+)(?:,(\d*))?\}/.exec(this.source.slice(this.pos));
+      this.pos += match.length;
+      min = Number(lo);
+      max = hi === undefined ? min : hi === "" ? min + 5 : Number(hi);
+    } else {
+      return node;
+    }
+    if (this.source[this.pos] === "?") {
+      this.pos++;
+    }
+    return { kind: "repeat", node, min, max };
+  }
+  atom() {
+    const c = this.source[this.pos++];
+    switch (c) {
+      case "^":
+      case "$":
+        return { kind: "empty" };
+      case ".":
+        return { kind: "text", choices: WORD };
+      case "[":
+        return { kind: "text", choices: this.charClass() };
+      case "\\":
+        return { kind: "text", choices: this.escape(false) };
+      case "(": {
+        if (this.source.startsWith("?:", this.pos)) {
+          this.pos += 2;
+        } else if (this.source.startsWith("?<", this.pos) && !/^\?<[=!]/.test(this.source.slice(this.pos))) {
+          this.pos = this.source.indexOf(">", this.pos) + 1;
+        } else if (this.source[this.pos] === "?") {
+          this.fail("lookaround assertions are not supported");
+        }
+        const node = this.alternation();
+        if (this.source[this.pos++] !== ")") {
+          this.fail("unterminated group");
+        }
+        return node;
+      }
+      default:
+        return { kind: "text", choices: c };
+    }
+  }
+  escape(inClass) {
+    const c = this.source[this.pos++];
+    switch (c) {
+      case "d":
+        return DIGITS;
+      case "w":
+        return WORD;
+      case "s":
+        return " ";
+      case "D":
+      case "W":
+      case "S": {
+        const excluded = this.classFor(c.toLowerCase());
+        return [...PRINTABLE].filter((ch) => !excluded.includes(ch)).join("");
+      }
+      case "n":
+        return `
+`;
+      case "t":
+        return "\t";
+      case "b":
+        if (inClass)
+          return "\b";
+        this.fail("word boundaries are not supported");
+      default:
+        if (c === undefined || /[1-9]/.test(c)) {
+          this.fail("backreferences are not supported");
+        }
+        return c;
+    }
+  }
+  classFor(c) {
+    return c === "d" ? DIGITS : c === "w" ? WORD : ` 	
+\r`;
+  }
+  charClass() {
+    const negate = this.source[this.pos] === "^";
+    if (negate)
+      this.pos++;
+    let set2 = "";
+    while (this.source[this.pos] !== "]") {
+      if (this.pos >= this.source.length) {
+        this.fail("unterminated character class");
+      }
+      let from = this.source[this.pos++];
+      if (from === "\\") {
+        const escaped = this.escape(true);
+        if (escaped.length > 1) {
+          set2 += escaped;
+          continue;
+        }
+        from = escaped;
+      }
+      if (this.source[this.pos] === "-" && this.source[this.pos + 1] !== "]") {
+        this.pos++;
+        let to = this.source[this.pos++];
+        if (to === "\\")
+          to = this.escape(true);
+        for (let code = from.charCodeAt(0);code <= to.charCodeAt(0); code++) {
+          set2 += String.fromCharCode(code);
+        }
+      } else {
+        set2 += from;
+      }
+    }
+    this.pos++;
+    return negate ? [...PRINTABLE].filter((ch) => !set2.includes(ch)).join("") : set2;
+  }
+}
+function generate(rng, node) {
+  switch (node.kind) {
+    case "text":
+      return node.choices[rng.int(0, node.choices.length - 1)];
+    case "empty":
+      return "";
+    case "seq":
+      return node.items.map((item) => generate(rng, item)).join("");
+    case "alt":
+      return generate(rng, rng.pick(node.options));
+    case "repeat": {
+      let out = "";
+      for (let i = rng.int(node.min, node.max);i > 0; i--) {
+        out += generate(rng, node.node);
+      }
+      return out;
+    }
+  }
+}
+function fromPattern(rng, source) {
+  return generate(rng, new PatternParser(source).parse());
+}
+function sample(gen, options = {}) {
+  const rng = new Random(options.seed);
+  return Array.from({ length: options.runs ?? 100 }, () => gen(rng));
+}
+function forAll(gen, property, options = {}) {
+  const seeds = new Random(options.seed);
+  for (let run = 0;run < (options.runs ?? 100); run++) {
+    const seed = run === 0 && options.seed !== undefined ? options.seed : seeds.int(0, 4294967295);
+    const value = gen(new Random(seed));
+    let failure = false;
+    try {
+      failure = property(value) === false;
+    } catch (error) {
+      failure = error;
+    }
+    if (failure !== false) {
+      const reason = failure === true ? "returned false" : `threw ${failure}`;
+      throw new ArbitraryError(`property ${reason} after ${run + 1} run(s) (seed ${seed}): ${describe(value)}`);
+    }
+  }
+}
+function describe(value) {
+  try {
+    return JSON.stringify(value, (_, v) => typeof v === "bigint" ? `${v}n` : v) ?? String(value);
+  } catch {
+    return String(value);
+  }
+}
+export {
+  string,
+  set,
+  satisfying,
+  sample,
+  record,
+  oneOf,
+  number,
+  map,
+  fromPattern,
+  forAll,
+  date,
+  bigint,
+  array,
+  Random,
+  ArbitraryError
+};
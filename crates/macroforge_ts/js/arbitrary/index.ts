@@ -0,0 +1,665 @@
+/**
+ * # Macroforge Arbitrary Module
+ *
+ * This module provides runtime helpers for the `Arbitrary` macro, which
+ * generates random values of a type for property-based testing.
+ *
+ * - **Seeded**: every generator draws from a `Random` instance, a small
+ *   deterministic PRNG. The same seed always produces the same values, so a
+ *   failing case can be replayed from the seed alone.
+ *
+ * - **Constraint-aware**: `string()`, `number()`, `bigint()`, `date()` and
+ *   the collection helpers accept the bounds that the serde validators
+ *   express (`minLength`, `between`, `email`, `pattern`, ...). Generated
+ *   values are meant to pass `deserialize`.
+ *
+ * - **Dependency-free**: generators are plain `(rng) => value` functions.
+ *   They can be driven by `forAll()` here or wrapped for fast-check with
+ *   `fc.integer().map((seed) => userArbitrary(new Random(seed)))`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Arbitrary)
+ * import { forAll } from "macroforge/arbitrary";
+ *
+ * forAll(userArbitrary, (user) => {
+ *     const result = userDeserialize(userSerialize(user));
+ *     return result.isOk() && userEquals(result.unwrap(), user);
+ * });
+ * ```
+ *
+ * @module macroforge/arbitrary
+ */
+
+/**
+ * A deterministic pseudo-random number generator (mulberry32).
+ */
+export class Random {
+  /**
+   * The seed this generator started from.
+   */
+  public readonly seed: number;
+
+  private state: number;
+
+  /**
+   * Creates a new generator.
+   * @param seed - 32-bit seed; a random one is chosen if omitted
+   */
+  constructor(seed: number = Math.floor(Math.random() * 0x100000000)) {
+    this.seed = seed >>> 0;
+    this.state = this.seed;
+  }
+
+  /**
+   * Returns a float in `[0, 1)`.
+   */
+  next(): number {
+    this.state = (this.state + 0x6d2b79f5) >>> 0;
+    let t = this.state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 0x100000000;
+  }
+
+  /**
+   * Returns an integer in `[min, max]`.
+   */
+  int(min: number, max: number): number {
+    return min + Math.floor(this.next() * (max - min + 1));
+  }
+
+  /**
+   * Returns `true` or `false` with equal probability.
+   */
+  bool(): boolean {
+    return this.next() < 0.5;
+  }
+
+  /**
+   * Returns `true` with probability `p`.
+   */
+  chance(p: number): boolean {
+    return this.next() < p;
+  }
+
+  /**
+   * Returns a random element of `items`.
+   */
+  pick<T>(items: readonly T[]): T {
+    return items[this.int(0, items.length - 1)];
+  }
+}
+
+/**
+ * A generator: produces a value from a `Random`. Generated `*Arbitrary`
+ * functions have this shape.
+ */
+export type Gen<T> = (rng: Random) => T;
+
+/**
+ * Constraints for {@link string}.
+ */
+export interface StringOptions {
+  minLength?: number;
+  maxLength?: number;
+  /** A well-known format; length options are ignored for formats */
+  format?: "email" | "url" | "uuid";
+  /** A regular expression source the string must match */
+  pattern?: string;
+  case?: "lower" | "upper" | "capitalized" | "uncapitalized";
+  startsWith?: string;
+  endsWith?: string;
+  includes?: string;
+}
+
+/**
+ * Bounds for {@link number}.
+ */
+export interface NumberOptions {
+  min?: number;
+  max?: number;
+  minExclusive?: boolean;
+  maxExclusive?: boolean;
+  /** Only generate integers */
+  int?: boolean;
+  /** Only generate multiples of this value */
+  multipleOf?: number;
+}
+
+/**
+ * Bounds for {@link bigint}.
+ */
+export interface BigIntOptions {
+  min?: bigint;
+  max?: bigint;
+  minExclusive?: boolean;
+  maxExclusive?: boolean;
+}
+
+/**
+ * Bounds for {@link date}.
+ */
+export interface DateOptions {
+  min?: Date;
+  max?: Date;
+  minExclusive?: boolean;
+  maxExclusive?: boolean;
+}
+
+/**
+ * Size bounds for collections.
+ */
+export interface SizeOptions {
+  minItems?: number;
+  maxItems?: number;
+}
+
+/**
+ * Error thrown when a generator cannot produce a value, or by
+ * {@link forAll} when a property fails.
+ */
+export class ArbitraryError extends Error {
+  /**
+   * Creates a new arbitrary error.
+   * @param message - What went wrong
+   */
+  constructor(message: string) {
+    super(message);
+    this.name = "ArbitraryError";
+  }
+}
+
+const LOWER = "abcdefghijklmnopqrstuvwxyz";
+const UPPER = LOWER.toUpperCase();
+const DIGITS = "0123456789";
+const WORD = LOWER + UPPER + DIGITS + "_";
+const PRINTABLE = Array.from({ length: 95 }, (_, i) =>
+  String.fromCharCode(32 + i),
+).join("");
+
+function chars(rng: Random, alphabet: string, length: number): string {
+  let out = "";
+  for (let i = 0; i < length; i++) {
+    out += alphabet[rng.int(0, alphabet.length - 1)];
+  }
+  return out;
+}
+
+/**
+ * Picks a size in `[min, max]`, defaulting the upper bound to `min + spread`.
+ */
+function size(rng: Random, min = 0, max?: number, spread = 8): number {
+  return rng.int(min, max ?? min + spread);
+}
+
+/**
+ * Generates a string satisfying `options`.
+ */
+export function string(rng: Random, options: StringOptions = {}): string {
+  switch (options.format) {
+    case "email":
+      return `${chars(rng, LOWER, size(rng, 1, 10))}@${chars(rng, LOWER, size(rng, 1, 10))}.${rng.pick(["com", "org", "net", "io"])}`;
+    case "url":
+      return `https://${chars(rng, LOWER, size(rng, 1, 10))}.${rng.pick(["com", "org", "dev"])}/${chars(rng, LOWER + DIGITS, size(rng, 0, 8))}`;
+    case "uuid": {
+      const hex = (n: number) => chars(rng, "0123456789abcdef", n);
+      return `${hex(8)}-${hex(4)}-4${hex(3)}-${rng.pick(["8", "9", "a", "b"])}${hex(3)}-${hex(12)}`;
+    }
+  }
+  if (options.pattern !== undefined) {
+    return fromPattern(rng, options.pattern);
+  }
+
+  const prefix = options.startsWith ?? "";
+  const suffix = options.endsWith ?? "";
+  const infix = options.includes ?? "";
+  const fixed = prefix.length + suffix.length + infix.length;
+  const min = Math.max((options.minLength ?? 0) - fixed, 0);
+  const max =
+    options.maxLength === undefined ? undefined : Math.max(options.maxLength - fixed, 0);
+  const alphabet =
+    options.case === "lower" ? LOWER + DIGITS : options.case === "upper" ? UPPER + DIGITS : LOWER + UPPER + DIGITS;
+  let core = chars(rng, alphabet, size(rng, min, max));
+  if (options.case === "capitalized" && prefix === "") {
+    core = core.charAt(0).toUpperCase() + core.slice(1);
+  } else if (options.case === "uncapitalized" && prefix === "") {
+    core = core.charAt(0).toLowerCase() + core.slice(1);
+  }
+  const split = rng.int(0, core.length);
+  return prefix + core.slice(0, split) + infix + core.slice(split) + suffix;
+}
+
+/**
+ * Generates a number satisfying `options`.
+ *
+ * Without bounds, values fall in `[-1000, 1000]`. About one value in ten is
+ * an edge case: an inclusive bound or zero.
+ */
+export function number(rng: Random, options: NumberOptions = {}): number {
+  const lo =
+    options.min ?? (options.max !== undefined ? Math.min(options.max, 0) - 1000 : -1000);
+  const hi =
+    options.max ?? (options.min !== undefined ? Math.max(options.min, 0) + 1000 : 1000);
+  const inRange = (v: number) =>
+    (options.minExclusive ? v > lo : v >= lo) && (options.maxExclusive ? v < hi : v <= hi);
+
+  if (options.multipleOf !== undefined) {
+    const step = options.multipleOf;
+    let kmin = Math.ceil(lo / step);
+    let kmax = Math.floor(hi / step);
+    if (!inRange(kmin * step)) kmin++;
+    if (!inRange(kmax * step)) kmax--;
+    if (kmin > kmax) {
+      throw new ArbitraryError(`no multiple of ${step} in range [${lo}, ${hi}]`);
+    }
+    return rng.int(kmin, kmax) * step + 0;
+  }
+
+  if (options.int) {
+    let a = Math.ceil(lo);
+    let b = Math.floor(hi);
+    if (!inRange(a)) a++;
+    if (!inRange(b)) b--;
+    if (a > b) {
+      throw new ArbitraryError(`no integer in range [${lo}, ${hi}]`);
+    }
+    const edges = [a, b, 0].filter((v) => v >= a && v <= b);
+    return rng.chance(0.1) ? rng.pick(edges) : rng.int(a, b);
+  }
+
+  const edges = [lo, hi, 0].filter(inRange);
+  if (edges.length > 0 && rng.chance(0.1)) {
+    return rng.pick(edges);
+  }
+  for (let i = 0; i < 100; i++) {
+    const v = lo + rng.next() * (hi - lo);
+    if (inRange(v)) {
+      return v;
+    }
+  }
+  throw new ArbitraryError(`no number in range (${lo}, ${hi})`);
+}
+
+/**
+ * Generates a bigint satisfying `options`. Without bounds, values fall in
+ * `[-1000n, 1000n]`.
+ */
+export function bigint(rng: Random, options: BigIntOptions = {}): bigint {
+  let lo = options.min ?? (options.max !== undefined ? options.max - 1000n : -1000n);
+  let hi = options.max ?? lo + 2000n;
+  if (options.min !== undefined && options.minExclusive) lo += 1n;
+  if (options.max !== undefined && options.maxExclusive) hi -= 1n;
+  if (lo > hi) {
+    throw new ArbitraryError(`no bigint in range [${lo}, ${hi}]`);
+  }
+  let offset = 0n;
+  for (let span = hi - lo + 1n; span > 1n; span >>= 32n) {
+    offset = (offset << 32n) | BigInt(rng.int(0, 0xffffffff));
+  }
+  return lo + (offset % (hi - lo + 1n));
+}
+
+/**
+ * Generates a valid `Date` satisfying `options`. Without bounds, dates fall
+ * between 2000 and 2030.
+ */
+export function date(rng: Random, options: DateOptions = {}): Date {
+  const year = 365 * 24 * 60 * 60 * 1000;
+  const lo =
+    options.min?.getTime() ??
+    (options.max !== undefined ? options.max.getTime() - 10 * year : Date.UTC(2000, 0, 1));
+  const hi = options.max?.getTime() ?? lo + 30 * year;
+  return new Date(
+    number(rng, {
+      min: lo,
+      max: hi,
+      minExclusive: options.minExclusive,
+      maxExclusive: options.maxExclusive,
+      int: true,
+    }),
+  );
+}
+
+/**
+ * Generates an array of values from `item`.
+ */
+export function array<T>(rng: Random, item: Gen<T>, options: SizeOptions = {}): T[] {
+  return Array.from({ length: size(rng, options.minItems, options.maxItems, 5) }, () =>
+    item(rng),
+  );
+}
+
+/**
+ * Generates a `Set` of distinct values from `item`. The set may be smaller
+ * than requested if `item` keeps repeating itself.
+ */
+export function set<T>(rng: Random, item: Gen<T>, options: SizeOptions = {}): Set<T> {
+  const target = size(rng, options.minItems, options.maxItems, 5);
+  const out = new Set<T>();
+  for (let tries = 0; out.size < target && tries < target * 10; tries++) {
+    out.add(item(rng));
+  }
+  return out;
+}
+
+/**
+ * Generates a `Map` with keys from `key` and values from `value`.
+ */
+export function map<K, V>(
+  rng: Random,
+  key: Gen<K>,
+  value: Gen<V>,
+  options: SizeOptions = {},
+): Map<K, V> {
+  const out = new Map<K, V>();
+  for (const k of set(rng, key, options)) {
+    out.set(k, value(rng));
+  }
+  return out;
+}
+
+/**
+ * Generates a plain object with keys from `key` and values from `value`.
+ */
+export function record<K extends PropertyKey, V>(
+  rng: Random,
+  key: Gen<K>,
+  value: Gen<V>,
+  options: SizeOptions = {},
+): Record<K, V> {
+  return Object.fromEntries(map(rng, key, value, options)) as Record<K, V>;
+}
+
+/**
+ * Generates a value from one of `options`, chosen uniformly.
+ */
+export function oneOf<T>(rng: Random, options: readonly Gen<T>[]): T {
+  return rng.pick(options)(rng);
+}
+
+/**
+ * Generates values from `gen` until one satisfies `predicate`.
+ *
+ * @throws {ArbitraryError} If no value satisfies `predicate` after `tries` attempts
+ */
+export function satisfying<T>(
+  rng: Random,
+  gen: Gen<T>,
+  predicate: (value: T) => boolean,
+  tries = 100,
+): T {
+  for (let i = 0; i < tries; i++) {
+    const value = gen(rng);
+    if (predicate(value)) {
+      return value;
+    }
+  }
+  throw new ArbitraryError(`no value satisfied the constraints after ${tries} attempts`);
+}
+
+// ============================================================================
+// Regular expressions
+// ============================================================================
+
+type RegexNode =
+  | { kind: "text"; choices: string }
+  | { kind: "empty" }
+  | { kind: "seq"; items: RegexNode[] }
+  | { kind: "alt"; options: RegexNode[] }
+  | { kind: "repeat"; node: RegexNode; min: number; max: number };
+
+/**
+ * Parses the subset of regular expression syntax that {@link fromPattern}
+ * supports.
+ */
+class PatternParser {
+  private pos = 0;
+
+  constructor(private readonly source: string) {}
+
+  parse(): RegexNode {
+    const node = this.alternation();
+    if (this.pos < this.source.length) {
+      this.fail(`unexpected "${this.source[this.pos]}"`);
+    }
+    return node;
+  }
+
+  private fail(message: string): never {
+    throw new ArbitraryError(`cannot generate from pattern /${this.source}/: ${message}`);
+  }
+
+  private alternation(): RegexNode {
+    const options = [this.sequence()];
+    while (this.source[this.pos] === "|") {
+      this.pos++;
+      options.push(this.sequence());
+    }
+    return options.length === 1 ? options[0] : { kind: "alt", options };
+  }
+
+  private sequence(): RegexNode {
+    const items: RegexNode[] = [];
+    while (this.pos < this.source.length && !"|)".includes(this.source[this.pos])) {
+      items.push(this.quantified(this.atom()));
+    }
+    return { kind: "seq", items };
+  }
+
+  private quantified(node: RegexNode): RegexNode {
+    const c = this.source[this.pos];
+    let min: number;
+    let max: number;
+    if (c === "*" || c === "+" || c === "?") {
+      this.pos++;
+      min = c === "+" ? 1 : 0;
+      max = c === "?" ? 1 : min + 5;
+    } else if (c === "{" && /^\{\d+(,\d*)?\}/.test(this.source.slice(this.pos))) {
+      const [match, lo, hi] = /^\{(\d+)(?:,(\d*))?\}/.exec(this.source.slice(this.pos))!;
+      this.pos += match.length;
+      min = Number(lo);
+      max = hi === undefined ? min : hi === "" ? min + 5 : Number(hi);
+    } else {
+      return node;
+    }
+    if (this.source[this.pos] === "?") {
+      this.pos++; // lazy quantifiers generate the same strings
+    }
+    return { kind: "repeat", node, min, max };
+  }
+
+  private atom(): RegexNode {
+    const c = this.source[this.pos++];
+    switch (c) {
+      case "^":
+      case "$":
+        return { kind: "empty" };
+      case ".":
+        return { kind: "text", choices: WORD };
+      case "[":
+        return { kind: "text", choices: this.charClass() };
+      case "\\":
+        return { kind: "text", choices: this.escape(false) };
+      case "(": {
+        if (this.source.startsWith("?:", this.pos)) {
+          this.pos += 2;
+        } else if (this.source.startsWith("?<", this.pos) && !/^\?<[=!]/.test(this.source.slice(this.pos))) {
+          this.pos = this.source.indexOf(">", this.pos) + 1;
+        } else if (this.source[this.pos] === "?") {
+          this.fail("lookaround assertions are not supported");
+        }
+        const node = this.alternation();
+        if (this.source[this.pos++] !== ")") {
+          this.fail("unterminated group");
+        }
+        return node;
+      }
+      default:
+        return { kind: "text", choices: c };
+    }
+  }
+
+  private escape(inClass: boolean): string {
+    const c = this.source[this.pos++];
+    switch (c) {
+      case "d":
+        return DIGITS;
+      case "w":
+        return WORD;
+      case "s":
+        return " ";
+      case "D":
+      case "W":
+      case "S": {
+        const excluded = this.classFor(c.toLowerCase());
+        return [...PRINTABLE].filter((ch) => !excluded.includes(ch)).join("");
+      }
+      case "n":
+        return "\n";
+      case "t":
+        return "\t";
+      case "b":
+        if (inClass) return "\b";
+        this.fail("word boundaries are not supported");
+      default:
+        if (c === undefined || /[1-9]/.test(c)) {
+          this.fail("backreferences are not supported");
+        }
+        return c;
+    }
+  }
+
+  private classFor(c: string): string {
+    return c === "d" ? DIGITS : c === "w" ? WORD : " \t\n\r";
+  }
+
+  private charClass(): string {
+    const negate = this.source[this.pos] === "^";
+    if (negate) this.pos++;
+    let set = "";
+    while (this.source[this.pos] !== "]") {
+      if (this.pos >= this.source.length) {
+        this.fail("unterminated character class");
+      }
+      let from = this.source[this.pos++];
+      if (from === "\\") {
+        const escaped = this.escape(true);
+        if (escaped.length > 1) {
+          set += escaped;
+          continue;
+        }
+        from = escaped;
+      }
+      if (this.source[this.pos] === "-" && this.source[this.pos + 1] !== "]") {
+        this.pos++;
+        let to = this.source[this.pos++];
+        if (to === "\\") to = this.escape(true);
+        for (let code = from.charCodeAt(0); code <= to.charCodeAt(0); code++) {
+          set += String.fromCharCode(code);
+        }
+      } else {
+        set += from;
+      }
+    }
+    this.pos++;
+    return negate ? [...PRINTABLE].filter((ch) => !set.includes(ch)).join("") : set;
+  }
+}
+
+function generate(rng: Random, node: RegexNode): string {
+  switch (node.kind) {
+    case "text":
+      return node.choices[rng.int(0, node.choices.length - 1)];
+    case "empty":
+      return "";
+    case "seq":
+      return node.items.map((item) => generate(rng, item)).join("");
+    case "alt":
+      return generate(rng, rng.pick(node.options));
+    case "repeat": {
+      let out = "";
+      for (let i = rng.int(node.min, node.max); i > 0; i--) {
+        out += generate(rng, node.node);
+      }
+      return out;
+    }
+  }
+}
+
+/**
+ * Generates a string matching the regular expression `source`.
+ *
+ * Supports literals, `.`, character classes and ranges, `\d \w \s` and their
+ * negations, groups, alternation, anchors and the `* + ? {n} {n,} {n,m}`
+ * quantifiers. Unbounded quantifiers repeat at most five extra times.
+ *
+ * @throws {ArbitraryError} For lookarounds, backreferences and word boundaries
+ */
+export function fromPattern(rng: Random, source: string): string {
+  return generate(rng, new PatternParser(source).parse());
+}
+
+// ============================================================================
+// Running properties
+// ============================================================================
+
+/**
+ * Options for {@link sample} and {@link forAll}.
+ */
+export interface RunOptions {
+  /** Seed for the first run; defaults to a random seed */
+  seed?: number;
+  /** Number of values to generate (default 100) */
+  runs?: number;
+}
+
+/**
+ * Generates `runs` values from `gen`.
+ */
+export function sample<T>(gen: Gen<T>, options: RunOptions = {}): T[] {
+  const rng = new Random(options.seed);
+  return Array.from({ length: options.runs ?? 100 }, () => gen(rng));
+}
+
+/**
+ * Checks that `property` holds for values generated by `gen`.
+ *
+ * Each run uses its own seed, derived from the starting seed, so a failure
+ * can be replayed with `forAll(gen, property, { seed, runs: 1 })`.
+ *
+ * @throws {ArbitraryError} If `property` returns `false` or throws; the
+ * message contains the seed and the failing value
+ */
+export function forAll<T>(
+  gen: Gen<T>,
+  property: (value: T) => boolean | void,
+  options: RunOptions = {},
+): void {
+  const seeds = new Random(options.seed);
+  for (let run = 0; run < (options.runs ?? 100); run++) {
+    const seed = run === 0 && options.seed !== undefined ? options.seed : seeds.int(0, 0xffffffff);
+    const value = gen(new Random(seed));
+    let failure: unknown = false;
+    try {
+      failure = property(value) === false;
+    } catch (error) {
+      failure = error;
+    }
+    if (failure !== false) {
+      const reason = failure === true ? "returned false" : `threw ${failure}`;
+      throw new ArbitraryError(
+        `property ${reason} after ${run + 1} run(s) (seed ${seed}): ${describe(value)}`,
+      );
+    }
+  }
+}
+
+function describe(value: unknown): string {
+  try {
+    return JSON.stringify(value, (_, v) => (typeof v === "bigint" ? `${v}n` : v)) ?? String(value);
+  } catch {
+    return String(value);
+  }
+}
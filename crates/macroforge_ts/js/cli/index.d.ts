@@ -0,0 +1,159 @@
+/**
+ * # Macroforge CLI Module
+ *
+ * This module provides the runtime for the `CliArgs` macro, which generates
+ * command-line parsers from classes and subcommands from discriminated
+ * unions.
+ *
+ * - **Descriptors**: the macro emits a `CliCommand` describing every
+ *   option; parsing and help rendering live here, so generated code stays
+ *   small.
+ *
+ * - **Familiar syntax**: `--long value`, `--long=value`, `-s value`,
+ *   clustered short flags (`-vq`), `--no-flag`, `--` and repeated options
+ *   for arrays.
+ *
+ * - **Deserialize-shaped results**: parse failures return
+ *   `{ success: false, errors: [{ field, message }] }`, like
+ *   `Deserialize`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(CliArgs)
+ * const result = ServeArgs.parseArgs(process.argv.slice(2));
+ * if (!result.success) {
+ *   console.error(result.display ?? result.errors.map((e) => e.message).join("\n"));
+ * }
+ *
+ * // Or print help / errors and exit
+ * const args = serveArgsCli.parseOrExit();
+ * ```
+ *
+ * @module macroforge/cli
+ */
+
+/**
+ * One parse failure, shaped like a `Deserialize` error.
+ */
+export interface CliError {
+  /** The field the argument belongs to, or `"_root"` for unexpected arguments. */
+  field: string;
+  message: string;
+}
+
+/**
+ * The result of parsing command-line arguments.
+ *
+ * `--help` and `--version` fail without errors and put the text to print in
+ * `display`.
+ */
+export type CliResult<T> =
+  | { success: true; value: T }
+  | { success: false; errors: CliError[]; display?: string };
+
+/**
+ * One option or positional argument of a command.
+ */
+export interface CliOption {
+  /** The property the value is stored in. */
+  field: string;
+  kind: "flag" | "string" | "number" | "bigint" | "choice" | "enum";
+  /** Long name without dashes, e.g. `"dry-run"`. */
+  long?: string;
+  /** Short name without dash, e.g. `"d"`. */
+  short?: string;
+  positional?: boolean;
+  /** Repeatable option or variadic positional collecting an array. */
+  multiple?: boolean;
+  required?: boolean;
+  /** Accepted values of a `"choice"` option. */
+  choices?: readonly string[];
+  /** The enum object of an `"enum"` option. */
+  enum?: Readonly<Record<string, string | number>>;
+  /** Placeholder in help, e.g. `"PORT"`. */
+  valueName: string;
+  help?: string;
+  /** Value used when the argument is absent. */
+  default?: () => unknown;
+  /** Accepted but left out of help. */
+  hidden?: boolean;
+}
+
+/**
+ * A field holding the subcommand of a command.
+ */
+export interface CliSubcommand {
+  field: string;
+  required?: boolean;
+  /** The subcommand group, usually a discriminated union. */
+  command: () => CliCommand<unknown>;
+}
+
+/**
+ * What `defineCommand` needs to parse a command.
+ */
+export interface CommandSpec<T> {
+  name: string;
+  about?: string;
+  version?: string;
+  options: readonly CliOption[];
+  subcommand?: CliSubcommand;
+  /** Builds the value from the parsed fields. */
+  create: (values: Record<string, unknown>) => T;
+}
+
+/**
+ * What `defineSubcommands` needs to dispatch to subcommands.
+ */
+export interface SubcommandsSpec<T> {
+  name: string;
+  about?: string;
+  version?: string;
+  /** The subcommands; a function so they may be defined later in the module. */
+  commands: () => readonly CliCommand<T>[];
+}
+
+/**
+ * A command-line parser generated by `@derive(CliArgs)`.
+ */
+export interface CliCommand<T> {
+  readonly name: string;
+  readonly about?: string;
+  /** The subcommands of a group. */
+  readonly commands?: () => readonly CliCommand<T>[];
+  /**
+   * Parses arguments.
+   * @param argv - Arguments without the program, defaults to `process.argv.slice(2)`
+   */
+  parse(argv?: readonly string[]): CliResult<T>;
+  /**
+   * Parses arguments, printing help or errors and exiting when that fails.
+   * @param argv - Arguments without the program, defaults to `process.argv.slice(2)`
+   */
+  parseOrExit(argv?: readonly string[]): T;
+  /** The help text. */
+  help(): string;
+  /** @internal Parses the arguments after the command path. */
+  parseAt(argv: readonly string[], path: readonly string[]): CliResult<T>;
+  /** @internal Renders help for the command path. */
+  helpAt(path: readonly string[]): string;
+}
+
+/**
+ * `process.argv` without the runtime and script, or no arguments where
+ * there is no `process`.
+ */
+export declare function processArgv(): string[];
+
+/**
+ * Creates the parser of a command. Called by the code generated for
+ * `@derive(CliArgs)` on classes.
+ */
+export declare function defineCommand<T>(spec: CommandSpec<T>): CliCommand<T>;
+
+/**
+ * Creates the parser of a group of subcommands. Called by the code
+ * generated for `@derive(CliArgs)` on discriminated unions.
+ */
+export declare function defineSubcommands<T>(spec: SubcommandsSpec<T>): CliCommand<T>;
@@ -0,0 +1,357 @@
+// js/cli/index.ts
+function currentProcess() {
+  return globalThis.process;
+}
+function processArgv() {
+  return currentProcess()?.argv?.slice(2) ?? [];
+}
+function exit(code, text) {
+  const process = currentProcess();
+  (code === 0 ? process?.stdout : process?.stderr)?.write(text + `
+`);
+  if (process?.exit) {
+    return process.exit(code);
+  }
+  throw new Error(text);
+}
+function command(name, about, commands, parseAt, helpAt) {
+  return {
+    name,
+    about,
+    commands,
+    parseAt,
+    helpAt,
+    parse: (argv = processArgv()) => parseAt(argv, [name]),
+    parseOrExit(argv = processArgv()) {
+      const result = parseAt(argv, [name]);
+      if (result.success) {
+        return result.value;
+      }
+      if (result.display !== undefined) {
+        return exit(0, result.display);
+      }
+      const errors = result.errors.map((error) => `error: ${error.message}`).join(`
+`);
+      return exit(2, `${errors}
+
+For more information, try '--help'.`);
+    },
+    help: () => helpAt([name])
+  };
+}
+function display(text) {
+  return { success: false, errors: [], display: text };
+}
+function looksLikeFlag(arg) {
+  return arg.length > 1 && arg.startsWith("-") && !/^-\d/.test(arg);
+}
+function label(option) {
+  if (option.positional) {
+    const name = option.required ? `<${option.valueName}>` : `[${option.valueName}]`;
+    return option.multiple ? `${name}...` : name;
+  }
+  const name = option.long !== undefined ? `--${option.long}` : `-${option.short}`;
+  return option.kind === "flag" ? name : `${name} <${option.valueName}>`;
+}
+function enumEntries(values) {
+  return Object.entries(values).filter(([key]) => Number.isNaN(Number(key)));
+}
+function possibleValues(option) {
+  if (option.kind === "choice") {
+    return [...option.choices ?? []];
+  }
+  if (option.kind === "enum") {
+    return enumEntries(option.enum ?? {}).map(([key, value]) => typeof value === "string" ? value : key);
+  }
+  return;
+}
+function expected(option) {
+  switch (option.kind) {
+    case "number":
+      return "a number";
+    case "bigint":
+      return "an integer";
+    default:
+      return `one of ${(possibleValues(option) ?? []).join(", ")}`;
+  }
+}
+function convert(option, raw) {
+  switch (option.kind) {
+    case "number": {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    }
+    case "bigint":
+      try {
+        return BigInt(raw);
+      } catch {
+        return;
+      }
+    case "choice":
+      return option.choices?.includes(raw) ? raw : undefined;
+    case "enum": {
+      const entry = enumEntries(option.enum ?? {}).find(([key, value]) => key === raw || value === raw);
+      return entry?.[1];
+    }
+    default:
+      return raw;
+  }
+}
+function shownDefault(option) {
+  const value = option.default?.();
+  if (Array.isArray(value)) {
+    return value.length > 0 ? value.join(", ") : undefined;
+  }
+  return ["string", "number", "bigint", "boolean"].includes(typeof value) ? String(value) : undefined;
+}
+function section(title, rows) {
+  if (rows.length === 0) {
+    return [];
+  }
+  const width = Math.max(...rows.map(([left]) => left.length));
+  return [
+    "",
+    `${title}:`,
+    ...rows.map(([left, right]) => `  ${left.padEnd(width)}  ${right}`.trimEnd())
+  ];
+}
+function describe(option) {
+  const parts = option.help ? [option.help] : [];
+  const fallback = shownDefault(option);
+  if (fallback !== undefined) {
+    parts.push(`[default: ${fallback}]`);
+  }
+  const values = possibleValues(option);
+  if (values) {
+    parts.push(`[possible values: ${values.join(", ")}]`);
+  }
+  return parts.join(" ");
+}
+function builtinRows(version) {
+  const rows = [["-h, --help", "Print help"]];
+  if (version !== undefined) {
+    rows.push(["-V, --version", "Print version"]);
+  }
+  return rows;
+}
+function commandRows(commands) {
+  return commands.map((c) => [c.name, c.about ?? ""]);
+}
+function dispatch(commands, argv, path) {
+  const name = argv[0];
+  if (name === undefined) {
+    return { success: false, errors: [{ field: "_root", message: "a subcommand is required" }] };
+  }
+  const match = commands.find((c) => c.name === name);
+  if (!match) {
+    const names = commands.map((c) => c.name).join(", ");
+    return {
+      success: false,
+      errors: [{ field: "_root", message: `unrecognized subcommand '${name}' (expected one of ${names})` }]
+    };
+  }
+  return match.parseAt(argv.slice(1), [...path, match.name]);
+}
+function defineCommand(spec) {
+  const named = spec.options.filter((o) => !o.positional);
+  const positionals = spec.options.filter((o) => o.positional);
+  const subcommands = () => {
+    const group = spec.subcommand?.command();
+    return group ? group.commands?.() ?? [group] : [];
+  };
+  const helpAt = (path) => {
+    const usage = [`Usage: ${path.join(" ")}`, "[OPTIONS]"];
+    usage.push(...positionals.filter((o) => !o.hidden).map(label));
+    if (spec.subcommand) {
+      usage.push(spec.subcommand.required ? "<COMMAND>" : "[COMMAND]");
+    }
+    const lines = spec.about ? [spec.about, ""] : [];
+    lines.push(usage.join(" "));
+    lines.push(...section("Commands", commandRows(subcommands())), ...section("Arguments", positionals.filter((o) => !o.hidden).map((o) => [label(o), describe(o)])), ...section("Options", [
+      ...named.filter((o) => !o.hidden).map((o) => {
+        const names = [o.short && `-${o.short}`, o.long && `--${o.long}`].filter(Boolean);
+        const indent = o.short === undefined ? "    " : "";
+        const value = o.kind === "flag" ? "" : ` <${o.valueName}>`;
+        return [indent + names.join(", ") + value, describe(o)];
+      }),
+      ...builtinRows(spec.version)
+    ]));
+    return lines.join(`
+`);
+  };
+  const parseAt = (argv, path) => {
+    const values = {};
+    const errors = [];
+    let next = 0;
+    let onlyPositionals = false;
+    let dispatched = false;
+    const set = (option, raw) => {
+      const value = convert(option, raw);
+      if (value === undefined) {
+        errors.push({
+          field: option.field,
+          message: `invalid value '${raw}' for '${label(option)}': expected ${expected(option)}`
+        });
+      } else if (option.multiple) {
+        (values[option.field] ??= []).push(value);
+      } else if (option.field in values) {
+        errors.push({ field: option.field, message: `'${label(option)}' cannot be used multiple times` });
+      } else {
+        values[option.field] = value;
+      }
+    };
+    const setFlag = (option, value) => {
+      if (option.field in values) {
+        errors.push({ field: option.field, message: `'${label(option)}' cannot be used multiple times` });
+      } else {
+        values[option.field] = value;
+      }
+    };
+    const unexpected = (arg) => {
+      errors.push({ field: "_root", message: `unexpected argument '${arg}'` });
+    };
+    for (let i = 0;i < argv.length; i++) {
+      const arg = argv[i];
+      if (!onlyPositionals && arg === "--") {
+        onlyPositionals = true;
+        continue;
+      }
+      if (!onlyPositionals && looksLikeFlag(arg)) {
+        if (arg === "--help" || arg === "-h") {
+          return display(helpAt(path));
+        }
+        if (spec.version !== undefined && (arg === "--version" || arg === "-V")) {
+          return display(`${path.join(" ")} ${spec.version}`);
+        }
+        const takeValue = (option) => {
+          const value = argv[i + 1];
+          if (value === undefined || looksLikeFlag(value)) {
+            errors.push({
+              field: option.field,
+              message: `a value is required for '${label(option)}' but none was supplied`
+            });
+            return;
+          }
+          i++;
+          return value;
+        };
+        if (arg.startsWith("--")) {
+          const eq = arg.indexOf("=");
+          const name = eq === -1 ? arg.slice(2) : arg.slice(2, eq);
+          const inline = eq === -1 ? undefined : arg.slice(eq + 1);
+          const option = named.find((o) => o.long === name);
+          if (!option) {
+            const negated = name.startsWith("no-") ? named.find((o) => o.long === name.slice(3) && o.kind === "flag") : undefined;
+            if (negated && inline === undefined) {
+              setFlag(negated, false);
+            } else {
+              unexpected(arg);
+            }
+            continue;
+          }
+          if (option.kind === "flag") {
+            if (inline !== undefined) {
+              errors.push({ field: option.field, message: `'--${name}' takes no value` });
+            } else {
+              setFlag(option, true);
+            }
+            continue;
+          }
+          const value = inline ?? takeValue(option);
+          if (value !== undefined) {
+            set(option, value);
+          }
+          continue;
+        }
+        for (let j = 1;j < arg.length; j++) {
+          const option = named.find((o) => o.short === arg[j]);
+          if (!option) {
+            unexpected(`-${arg[j]}`);
+            break;
+          }
+          if (option.kind === "flag") {
+            setFlag(option, true);
+            continue;
+          }
+          const attached = arg.slice(j + 1).replace(/^=/, "");
+          const value = attached !== "" ? attached : takeValue(option);
+          if (value !== undefined) {
+            set(option, value);
+          }
+          break;
+        }
+        continue;
+      }
+      const positional = positionals[next];
+      if (positional) {
+        set(positional, arg);
+        if (!positional.multiple) {
+          next++;
+        }
+        continue;
+      }
+      if (spec.subcommand) {
+        const field = spec.subcommand.field;
+        dispatched = true;
+        const result = dispatch(subcommands(), argv.slice(i), path);
+        if (result.success) {
+          values[field] = result.value;
+        } else if (result.display !== undefined) {
+          return display(result.display);
+        } else {
+          for (const error of result.errors) {
+            errors.push({
+              field: error.field === "_root" ? field : `${field}.${error.field}`,
+              message: error.message
+            });
+          }
+        }
+        break;
+      }
+      unexpected(arg);
+    }
+    for (const option of spec.options) {
+      if (option.field in values || errors.some((error) => error.field === option.field)) {
+        continue;
+      }
+      if (option.default) {
+        values[option.field] = option.default();
+      } else if (option.kind === "flag") {
+        values[option.field] = false;
+      } else if (option.multiple) {
+        values[option.field] = [];
+      } else if (option.required) {
+        errors.push({ field: option.field, message: `the argument '${label(option)}' is required` });
+      }
+    }
+    if (spec.subcommand?.required && !dispatched) {
+      errors.push({ field: spec.subcommand.field, message: "a subcommand is required" });
+    }
+    return errors.length > 0 ? { success: false, errors } : { success: true, value: spec.create(values) };
+  };
+  return command(spec.name, spec.about, undefined, parseAt, helpAt);
+}
+function defineSubcommands(spec) {
+  const helpAt = (path) => {
+    const lines = spec.about ? [spec.about, ""] : [];
+    lines.push(`Usage: ${path.join(" ")} <COMMAND>`, ...section("Commands", commandRows(spec.commands())), ...section("Options", builtinRows(spec.version)));
+    return lines.join(`
+`);
+  };
+  const parseAt = (argv, path) => {
+    const first = argv[0];
+    if (first === "--help" || first === "-h") {
+      return display(helpAt(path));
+    }
+    if (spec.version !== undefined && (first === "--version" || first === "-V")) {
+      return display(`${path.join(" ")} ${spec.version}`);
+    }
+    return dispatch(spec.commands(), argv, path);
+  };
+  return command(spec.name, spec.about, spec.commands, parseAt, helpAt);
+}
+export {
+  processArgv,
+  defineSubcommands,
+  defineCommand
+};
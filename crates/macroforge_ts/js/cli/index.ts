@@ -0,0 +1,569 @@
+/**
+ * # Macroforge CLI Module
+ *
+ * This module provides the runtime for the `CliArgs` macro, which generates
+ * command-line parsers from classes and subcommands from discriminated
+ * unions.
+ *
+ * - **Descriptors**: the macro emits a `CliCommand` describing every
+ *   option; parsing and help rendering live here, so generated code stays
+ *   small.
+ *
+ * - **Familiar syntax**: `--long value`, `--long=value`, `-s value`,
+ *   clustered short flags (`-vq`), `--no-flag`, `--` and repeated options
+ *   for arrays.
+ *
+ * - **Deserialize-shaped results**: parse failures return
+ *   `{ success: false, errors: [{ field, message }] }`, like
+ *   `Deserialize`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(CliArgs)
+ * const result = ServeArgs.parseArgs(process.argv.slice(2));
+ * if (!result.success) {
+ *   console.error(result.display ?? result.errors.map((e) => e.message).join("\n"));
+ * }
+ *
+ * // Or print help / errors and exit
+ * const args = serveArgsCli.parseOrExit();
+ * ```
+ *
+ * @module macroforge/cli
+ */
+
+/**
+ * One parse failure, shaped like a `Deserialize` error.
+ */
+export interface CliError {
+  /** The field the argument belongs to, or `"_root"` for unexpected arguments. */
+  field: string;
+  message: string;
+}
+
+/**
+ * The result of parsing command-line arguments.
+ *
+ * `--help` and `--version` fail without errors and put the text to print in
+ * `display`.
+ */
+export type CliResult<T> =
+  | { success: true; value: T }
+  | { success: false; errors: CliError[]; display?: string };
+
+/**
+ * One option or positional argument of a command.
+ */
+export interface CliOption {
+  /** The property the value is stored in. */
+  field: string;
+  kind: "flag" | "string" | "number" | "bigint" | "choice" | "enum";
+  /** Long name without dashes, e.g. `"dry-run"`. */
+  long?: string;
+  /** Short name without dash, e.g. `"d"`. */
+  short?: string;
+  positional?: boolean;
+  /** Repeatable option or variadic positional collecting an array. */
+  multiple?: boolean;
+  required?: boolean;
+  /** Accepted values of a `"choice"` option. */
+  choices?: readonly string[];
+  /** The enum object of an `"enum"` option. */
+  enum?: Readonly<Record<string, string | number>>;
+  /** Placeholder in help, e.g. `"PORT"`. */
+  valueName: string;
+  help?: string;
+  /** Value used when the argument is absent. */
+  default?: () => unknown;
+  /** Accepted but left out of help. */
+  hidden?: boolean;
+}
+
+/**
+ * A field holding the subcommand of a command.
+ */
+export interface CliSubcommand {
+  field: string;
+  required?: boolean;
+  /** The subcommand group, usually a discriminated union. */
+  command: () => CliCommand<unknown>;
+}
+
+/**
+ * What `defineCommand` needs to parse a command.
+ */
+export interface CommandSpec<T> {
+  name: string;
+  about?: string;
+  version?: string;
+  options: readonly CliOption[];
+  subcommand?: CliSubcommand;
+  /** Builds the value from the parsed fields. */
+  create: (values: Record<string, unknown>) => T;
+}
+
+/**
+ * What `defineSubcommands` needs to dispatch to subcommands.
+ */
+export interface SubcommandsSpec<T> {
+  name: string;
+  about?: string;
+  version?: string;
+  /** The subcommands; a function so they may be defined later in the module. */
+  commands: () => readonly CliCommand<T>[];
+}
+
+/**
+ * A command-line parser generated by `@derive(CliArgs)`.
+ */
+export interface CliCommand<T> {
+  readonly name: string;
+  readonly about?: string;
+  /** The subcommands of a group. */
+  readonly commands?: () => readonly CliCommand<T>[];
+  /**
+   * Parses arguments.
+   * @param argv - Arguments without the program, defaults to `process.argv.slice(2)`
+   */
+  parse(argv?: readonly string[]): CliResult<T>;
+  /**
+   * Parses arguments, printing help or errors and exiting when that fails.
+   * @param argv - Arguments without the program, defaults to `process.argv.slice(2)`
+   */
+  parseOrExit(argv?: readonly string[]): T;
+  /** The help text. */
+  help(): string;
+  /** @internal Parses the arguments after the command path. */
+  parseAt(argv: readonly string[], path: readonly string[]): CliResult<T>;
+  /** @internal Renders help for the command path. */
+  helpAt(path: readonly string[]): string;
+}
+
+type Process = {
+  argv?: string[];
+  exit?: (code: number) => never;
+  stdout?: { write(text: string): unknown };
+  stderr?: { write(text: string): unknown };
+};
+
+function currentProcess(): Process | undefined {
+  return (globalThis as { process?: Process }).process;
+}
+
+/**
+ * `process.argv` without the runtime and script, or no arguments where
+ * there is no `process`.
+ */
+export function processArgv(): string[] {
+  return currentProcess()?.argv?.slice(2) ?? [];
+}
+
+function exit(code: number, text: string): never {
+  const process = currentProcess();
+  (code === 0 ? process?.stdout : process?.stderr)?.write(text + "\n");
+  if (process?.exit) {
+    return process.exit(code);
+  }
+  throw new Error(text);
+}
+
+function command<T>(
+  name: string,
+  about: string | undefined,
+  commands: (() => readonly CliCommand<T>[]) | undefined,
+  parseAt: CliCommand<T>["parseAt"],
+  helpAt: CliCommand<T>["helpAt"],
+): CliCommand<T> {
+  return {
+    name,
+    about,
+    commands,
+    parseAt,
+    helpAt,
+    parse: (argv = processArgv()) => parseAt(argv, [name]),
+    parseOrExit(argv = processArgv()) {
+      const result = parseAt(argv, [name]);
+      if (result.success) {
+        return result.value;
+      }
+      if (result.display !== undefined) {
+        return exit(0, result.display);
+      }
+      const errors = result.errors.map((error) => `error: ${error.message}`).join("\n");
+      return exit(2, `${errors}\n\nFor more information, try '--help'.`);
+    },
+    help: () => helpAt([name]),
+  };
+}
+
+function display<T>(text: string): CliResult<T> {
+  return { success: false, errors: [], display: text };
+}
+
+function looksLikeFlag(arg: string): boolean {
+  return arg.length > 1 && arg.startsWith("-") && !/^-\d/.test(arg);
+}
+
+function label(option: CliOption): string {
+  if (option.positional) {
+    const name = option.required ? `<${option.valueName}>` : `[${option.valueName}]`;
+    return option.multiple ? `${name}...` : name;
+  }
+  const name = option.long !== undefined ? `--${option.long}` : `-${option.short}`;
+  return option.kind === "flag" ? name : `${name} <${option.valueName}>`;
+}
+
+function enumEntries(values: Readonly<Record<string, string | number>>): [string, string | number][] {
+  // Numeric enums map values back to names; those entries are not members.
+  return Object.entries(values).filter(([key]) => Number.isNaN(Number(key)));
+}
+
+function possibleValues(option: CliOption): string[] | undefined {
+  if (option.kind === "choice") {
+    return [...(option.choices ?? [])];
+  }
+  if (option.kind === "enum") {
+    return enumEntries(option.enum ?? {}).map(([key, value]) =>
+      typeof value === "string" ? value : key,
+    );
+  }
+  return undefined;
+}
+
+function expected(option: CliOption): string {
+  switch (option.kind) {
+    case "number":
+      return "a number";
+    case "bigint":
+      return "an integer";
+    default:
+      return `one of ${(possibleValues(option) ?? []).join(", ")}`;
+  }
+}
+
+function convert(option: CliOption, raw: string): unknown {
+  switch (option.kind) {
+    case "number": {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    }
+    case "bigint":
+      try {
+        return BigInt(raw);
+      } catch {
+        return undefined;
+      }
+    case "choice":
+      return option.choices?.includes(raw) ? raw : undefined;
+    case "enum": {
+      const entry = enumEntries(option.enum ?? {}).find(
+        ([key, value]) => key === raw || value === raw,
+      );
+      return entry?.[1];
+    }
+    default:
+      return raw;
+  }
+}
+
+function shownDefault(option: CliOption): string | undefined {
+  const value = option.default?.();
+  if (Array.isArray(value)) {
+    return value.length > 0 ? value.join(", ") : undefined;
+  }
+  return ["string", "number", "bigint", "boolean"].includes(typeof value)
+    ? String(value)
+    : undefined;
+}
+
+function section(title: string, rows: [string, string][]): string[] {
+  if (rows.length === 0) {
+    return [];
+  }
+  const width = Math.max(...rows.map(([left]) => left.length));
+  return [
+    "",
+    `${title}:`,
+    ...rows.map(([left, right]) => `  ${left.padEnd(width)}  ${right}`.trimEnd()),
+  ];
+}
+
+function describe(option: CliOption): string {
+  const parts = option.help ? [option.help] : [];
+  const fallback = shownDefault(option);
+  if (fallback !== undefined) {
+    parts.push(`[default: ${fallback}]`);
+  }
+  const values = possibleValues(option);
+  if (values) {
+    parts.push(`[possible values: ${values.join(", ")}]`);
+  }
+  return parts.join(" ");
+}
+
+function builtinRows(version: string | undefined): [string, string][] {
+  const rows: [string, string][] = [["-h, --help", "Print help"]];
+  if (version !== undefined) {
+    rows.push(["-V, --version", "Print version"]);
+  }
+  return rows;
+}
+
+function commandRows(commands: readonly CliCommand<unknown>[]): [string, string][] {
+  return commands.map((c): [string, string] => [c.name, c.about ?? ""]);
+}
+
+/** Parses `argv[0]` as a subcommand name and the rest as its arguments. */
+function dispatch<T>(
+  commands: readonly CliCommand<T>[],
+  argv: readonly string[],
+  path: readonly string[],
+): CliResult<T> {
+  const name = argv[0];
+  if (name === undefined) {
+    return { success: false, errors: [{ field: "_root", message: "a subcommand is required" }] };
+  }
+  const match = commands.find((c) => c.name === name);
+  if (!match) {
+    const names = commands.map((c) => c.name).join(", ");
+    return {
+      success: false,
+      errors: [{ field: "_root", message: `unrecognized subcommand '${name}' (expected one of ${names})` }],
+    };
+  }
+  return match.parseAt(argv.slice(1), [...path, match.name]);
+}
+
+/**
+ * Creates the parser of a command. Called by the code generated for
+ * `@derive(CliArgs)` on classes.
+ */
+export function defineCommand<T>(spec: CommandSpec<T>): CliCommand<T> {
+  const named = spec.options.filter((o) => !o.positional);
+  const positionals = spec.options.filter((o) => o.positional);
+  const subcommands = () => {
+    const group = spec.subcommand?.command();
+    return group ? (group.commands?.() ?? [group]) : [];
+  };
+
+  const helpAt = (path: readonly string[]): string => {
+    const usage = [`Usage: ${path.join(" ")}`, "[OPTIONS]"];
+    usage.push(...positionals.filter((o) => !o.hidden).map(label));
+    if (spec.subcommand) {
+      usage.push(spec.subcommand.required ? "<COMMAND>" : "[COMMAND]");
+    }
+    const lines = spec.about ? [spec.about, ""] : [];
+    lines.push(usage.join(" "));
+    lines.push(
+      ...section("Commands", commandRows(subcommands())),
+      ...section(
+        "Arguments",
+        positionals.filter((o) => !o.hidden).map((o): [string, string] => [label(o), describe(o)]),
+      ),
+      ...section("Options", [
+        ...named
+          .filter((o) => !o.hidden)
+          .map((o): [string, string] => {
+            const names = [o.short && `-${o.short}`, o.long && `--${o.long}`].filter(Boolean);
+            const indent = o.short === undefined ? "    " : "";
+            const value = o.kind === "flag" ? "" : ` <${o.valueName}>`;
+            return [indent + names.join(", ") + value, describe(o)];
+          }),
+        ...builtinRows(spec.version),
+      ]),
+    );
+    return lines.join("\n");
+  };
+
+  const parseAt = (argv: readonly string[], path: readonly string[]): CliResult<T> => {
+    const values: Record<string, unknown> = {};
+    const errors: CliError[] = [];
+    let next = 0;
+    let onlyPositionals = false;
+    let dispatched = false;
+
+    const set = (option: CliOption, raw: string) => {
+      const value = convert(option, raw);
+      if (value === undefined) {
+        errors.push({
+          field: option.field,
+          message: `invalid value '${raw}' for '${label(option)}': expected ${expected(option)}`,
+        });
+      } else if (option.multiple) {
+        ((values[option.field] ??= []) as unknown[]).push(value);
+      } else if (option.field in values) {
+        errors.push({ field: option.field, message: `'${label(option)}' cannot be used multiple times` });
+      } else {
+        values[option.field] = value;
+      }
+    };
+    const setFlag = (option: CliOption, value: boolean) => {
+      if (option.field in values) {
+        errors.push({ field: option.field, message: `'${label(option)}' cannot be used multiple times` });
+      } else {
+        values[option.field] = value;
+      }
+    };
+    const unexpected = (arg: string) => {
+      errors.push({ field: "_root", message: `unexpected argument '${arg}'` });
+    };
+
+    for (let i = 0; i < argv.length; i++) {
+      const arg = argv[i];
+      if (!onlyPositionals && arg === "--") {
+        onlyPositionals = true;
+        continue;
+      }
+      if (!onlyPositionals && looksLikeFlag(arg)) {
+        if (arg === "--help" || arg === "-h") {
+          return display(helpAt(path));
+        }
+        if (spec.version !== undefined && (arg === "--version" || arg === "-V")) {
+          return display(`${path.join(" ")} ${spec.version}`);
+        }
+        const takeValue = (option: CliOption): string | undefined => {
+          const value = argv[i + 1];
+          if (value === undefined || looksLikeFlag(value)) {
+            errors.push({
+              field: option.field,
+              message: `a value is required for '${label(option)}' but none was supplied`,
+            });
+            return undefined;
+          }
+          i++;
+          return value;
+        };
+
+        if (arg.startsWith("--")) {
+          const eq = arg.indexOf("=");
+          const name = eq === -1 ? arg.slice(2) : arg.slice(2, eq);
+          const inline = eq === -1 ? undefined : arg.slice(eq + 1);
+          const option = named.find((o) => o.long === name);
+          if (!option) {
+            const negated = name.startsWith("no-")
+              ? named.find((o) => o.long === name.slice(3) && o.kind === "flag")
+              : undefined;
+            if (negated && inline === undefined) {
+              setFlag(negated, false);
+            } else {
+              unexpected(arg);
+            }
+            continue;
+          }
+          if (option.kind === "flag") {
+            if (inline !== undefined) {
+              errors.push({ field: option.field, message: `'--${name}' takes no value` });
+            } else {
+              setFlag(option, true);
+            }
+            continue;
+          }
+          const value = inline ?? takeValue(option);
+          if (value !== undefined) {
+            set(option, value);
+          }
+          continue;
+        }
+
+        for (let j = 1; j < arg.length; j++) {
+          const option = named.find((o) => o.short === arg[j]);
+          if (!option) {
+            unexpected(`-${arg[j]}`);
+            break;
+          }
+          if (option.kind === "flag") {
+            setFlag(option, true);
+            continue;
+          }
+          const attached = arg.slice(j + 1).replace(/^=/, "");
+          const value = attached !== "" ? attached : takeValue(option);
+          if (value !== undefined) {
+            set(option, value);
+          }
+          break;
+        }
+        continue;
+      }
+
+      const positional = positionals[next];
+      if (positional) {
+        set(positional, arg);
+        if (!positional.multiple) {
+          next++;
+        }
+        continue;
+      }
+      if (spec.subcommand) {
+        const field = spec.subcommand.field;
+        dispatched = true;
+        const result = dispatch(subcommands(), argv.slice(i), path);
+        if (result.success) {
+          values[field] = result.value;
+        } else if (result.display !== undefined) {
+          return display(result.display);
+        } else {
+          for (const error of result.errors) {
+            errors.push({
+              field: error.field === "_root" ? field : `${field}.${error.field}`,
+              message: error.message,
+            });
+          }
+        }
+        break;
+      }
+      unexpected(arg);
+    }
+
+    for (const option of spec.options) {
+      if (option.field in values || errors.some((error) => error.field === option.field)) {
+        continue;
+      }
+      if (option.default) {
+        values[option.field] = option.default();
+      } else if (option.kind === "flag") {
+        values[option.field] = false;
+      } else if (option.multiple) {
+        values[option.field] = [];
+      } else if (option.required) {
+        errors.push({ field: option.field, message: `the argument '${label(option)}' is required` });
+      }
+    }
+    if (spec.subcommand?.required && !dispatched) {
+      errors.push({ field: spec.subcommand.field, message: "a subcommand is required" });
+    }
+
+    return errors.length > 0 ? { success: false, errors } : { success: true, value: spec.create(values) };
+  };
+
+  return command(spec.name, spec.about, undefined, parseAt, helpAt);
+}
+
+/**
+ * Creates the parser of a group of subcommands. Called by the code
+ * generated for `@derive(CliArgs)` on discriminated unions.
+ */
+export function defineSubcommands<T>(spec: SubcommandsSpec<T>): CliCommand<T> {
+  const helpAt = (path: readonly string[]): string => {
+    const lines = spec.about ? [spec.about, ""] : [];
+    lines.push(
+      `Usage: ${path.join(" ")} <COMMAND>`,
+      ...section("Commands", commandRows(spec.commands())),
+      ...section("Options", builtinRows(spec.version)),
+    );
+    return lines.join("\n");
+  };
+
+  const parseAt = (argv: readonly string[], path: readonly string[]): CliResult<T> => {
+    const first = argv[0];
+    if (first === "--help" || first === "-h") {
+      return display(helpAt(path));
+    }
+    if (spec.version !== undefined && (first === "--version" || first === "-V")) {
+      return display(`${path.join(" ")} ${spec.version}`);
+    }
+    return dispatch(spec.commands(), argv, path);
+  };
+
+  return command(spec.name, spec.about, spec.commands, parseAt, helpAt);
+}
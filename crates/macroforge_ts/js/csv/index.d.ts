@@ -0,0 +1,173 @@
+/**
+ * # Macroforge CSV Module
+ *
+ * This module provides the runtime for the `Csv` macro, which maps types
+ * to CSV rows.
+ *
+ * - **RFC 4180**: fields containing commas, quotes or line breaks are
+ *   quoted, quotes are doubled, and records end with CRLF.
+ *
+ * - **Streaming**: `csvLines` yields one line at a time, and `CsvReader`
+ *   accepts text in chunks and returns complete records as they arrive.
+ *
+ * - **Deserialize-shaped results**: parse failures return
+ *   `{ success: false, errors: [{ row, field, message }] }`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Csv)
+ * const text = userToCsv(users);
+ * const result = userParseCsv(text);
+ *
+ * // Streaming
+ * const reader = new CsvReader();
+ * for await (const chunk of stream) {
+ *   for (const record of reader.push(chunk)) {
+ *     const row = userFromCsvRow(record);
+ *   }
+ * }
+ * ```
+ *
+ * @module macroforge/csv
+ */
+
+/**
+ * One CSV record, keyed by column name.
+ */
+export type CsvRecord = Readonly<Record<string, string | undefined>>;
+
+/**
+ * One decoding failure, shaped like a `Deserialize` error.
+ */
+export interface CsvError {
+  /** The 1-based data row, set by `parseCsv`. */
+  row?: number;
+  /** The column name. */
+  field: string;
+  message: string;
+}
+
+/**
+ * The result of decoding, shaped like a `Deserialize` result.
+ */
+export type CsvResult<T> = { success: true; value: T } | { success: false; errors: CsvError[] };
+
+/**
+ * Parses one cell; returns `undefined` for invalid input.
+ */
+export interface CsvDecoder<T> {
+  parse: (raw: string) => T | undefined;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+/**
+ * Decoders used by the code generated for `@derive(Csv)`.
+ */
+export declare const csvDecoders: {
+  string: CsvDecoder<string>;
+  number: CsvDecoder<number>;
+  bigint: CsvDecoder<bigint>;
+  boolean: CsvDecoder<boolean>;
+  date: CsvDecoder<Date>;
+  json: CsvDecoder<unknown>;
+  map: CsvDecoder<Map<unknown, unknown>>;
+  set: CsvDecoder<Set<unknown>>;
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): CsvDecoder<T>;
+  /**
+   * A `deserializeWith` function; exceptions become the error message.
+   */
+  with<T>(deserialize: (raw: unknown) => T): CsvDecoder<T>;
+};
+
+/**
+ * How a missing or empty cell is decoded: an error, `undefined` or `null`.
+ */
+export type CsvMissing = "required" | "optional" | "nullable";
+
+/**
+ * Reads and decodes one cell. Called by the code generated for
+ * `@derive(Csv)`.
+ *
+ * An empty cell counts as missing, except for required strings.
+ *
+ * @param record - The record
+ * @param column - The column name
+ * @param decoder - How to parse the cell
+ * @param errors - Collects a problem if the cell is missing or invalid
+ * @param missing - What a missing cell decodes to
+ * @returns The value, or `undefined`/`null` if it is missing or invalid
+ */
+export declare function readCell<T>(record: CsvRecord, column: string, decoder: CsvDecoder<T>, errors: CsvError[], missing?: CsvMissing): T | null | undefined;
+
+/**
+ * Reads a nested value from the columns under `prefix`. Called by the
+ * code generated for `@derive(Csv)`.
+ *
+ * @param record - The record
+ * @param prefix - The column prefix, e.g. `"address."`
+ * @param fromRow - The generated `fromCsvRow` of the nested type
+ * @param errors - Collects the nested errors, with prefixed columns
+ * @param missing - What the value is when all its cells are empty
+ */
+export declare function readNested<T>(record: CsvRecord, prefix: string, fromRow: (record: CsvRecord) => CsvResult<T>, errors: CsvError[], missing?: CsvMissing): T | null | undefined;
+
+/**
+ * Encodes one cell. `null` and `undefined` become empty cells, dates ISO
+ * strings, maps and sets JSON arrays and other objects JSON.
+ */
+export declare function csvCell(value: unknown): string;
+
+/**
+ * The cells of a nested value, or empty cells when it is missing. Called by
+ * the code generated for `@derive(Csv)`.
+ */
+export declare function csvNested<T>(value: T | null | undefined, header: readonly string[], toRow: (value: T) => string[]): string[];
+
+/**
+ * Quotes a field if it contains a comma, quote or line break.
+ */
+export declare function escapeCsvField(field: string): string;
+
+/**
+ * Formats one record as a CSV line, without the line break.
+ */
+export declare function formatCsvLine(fields: readonly string[]): string;
+
+/**
+ * Yields the header line and one line per row, each ending with CRLF.
+ */
+export declare function csvLines<T>(header: readonly string[], rows: Iterable<T>, toRow: (value: T) => string[]): Generator<string>;
+
+/**
+ * Formats rows as a CSV document with a header line.
+ */
+export declare function toCsv<T>(header: readonly string[], rows: Iterable<T>, toRow: (value: T) => string[]): string;
+
+/**
+ * Incremental RFC 4180 reader. The first record is the header; later
+ * records are returned keyed by its columns. Empty lines are skipped.
+ */
+export declare class CsvReader {
+  #private;
+  /**
+   * Reads a chunk of text.
+   * @returns The records completed by this chunk
+   */
+  push(chunk: string): CsvRecord[];
+  /**
+   * Finishes reading.
+   * @returns The last record, if the text did not end with a line break
+   */
+  end(): CsvRecord[];
+}
+
+/**
+ * Parses a CSV document with a header line, decoding every row and
+ * collecting every error with its row number.
+ */
+export declare function parseCsv<T>(text: string, fromRow: (record: CsvRecord) => CsvResult<T>): CsvResult<T[]>;
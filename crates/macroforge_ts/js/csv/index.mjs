@@ -0,0 +1,257 @@
+// js/csv/index.ts
+var csvDecoders = {
+  string: { parse: (raw) => raw, expected: "a string" },
+  number: {
+    parse: (raw) => {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    },
+    expected: "a number"
+  },
+  bigint: {
+    parse: (raw) => {
+      try {
+        return BigInt(raw.trim());
+      } catch {
+        return;
+      }
+    },
+    expected: "an integer"
+  },
+  boolean: {
+    parse: (raw) => raw === "true" ? true : raw === "false" ? false : undefined,
+    expected: "true or false"
+  },
+  date: {
+    parse: (raw) => {
+      const date = new Date(raw);
+      return Number.isNaN(date.getTime()) ? undefined : date;
+    },
+    expected: "an ISO date"
+  },
+  json: {
+    parse: (raw) => {
+      try {
+        return JSON.parse(raw);
+      } catch {
+        return;
+      }
+    },
+    expected: "JSON"
+  },
+  map: {
+    parse: (raw) => {
+      try {
+        const entries = JSON.parse(raw);
+        return Array.isArray(entries) ? new Map(entries) : undefined;
+      } catch {
+        return;
+      }
+    },
+    expected: "a JSON array of entries"
+  },
+  set: {
+    parse: (raw) => {
+      try {
+        const items = JSON.parse(raw);
+        return Array.isArray(items) ? new Set(items) : undefined;
+      } catch {
+        return;
+      }
+    },
+    expected: "a JSON array"
+  },
+  oneOf(values) {
+    return {
+      parse: (raw) => values.includes(raw) ? raw : undefined,
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`
+    };
+  },
+  with(deserialize) {
+    return { parse: deserialize, expected: "a valid value" };
+  }
+};
+function readCell(record, column, decoder, errors, missing = "required") {
+  const raw = record[column];
+  if (raw === undefined || raw === "" && (missing !== "required" || decoder !== csvDecoders.string)) {
+    if (missing === "required") {
+      errors.push({ field: column, message: "is required" });
+    }
+    return missing === "nullable" ? null : undefined;
+  }
+  let value;
+  let message = `expected ${decoder.expected}`;
+  try {
+    value = decoder.parse(raw);
+  } catch (e) {
+    message = e instanceof Error ? e.message : String(e);
+  }
+  if (value === undefined) {
+    errors.push({ field: column, message });
+  }
+  return value;
+}
+function subRecord(record, prefix) {
+  const cells = {};
+  for (const [column, value] of Object.entries(record)) {
+    if (column.startsWith(prefix)) {
+      cells[column.slice(prefix.length)] = value;
+    }
+  }
+  return cells;
+}
+function readNested(record, prefix, fromRow, errors, missing = "required") {
+  const cells = subRecord(record, prefix);
+  if (missing !== "required" && Object.values(cells).every((cell) => cell === undefined || cell === "")) {
+    return missing === "nullable" ? null : undefined;
+  }
+  const result = fromRow(cells);
+  if (result.success) {
+    return result.value;
+  }
+  for (const error of result.errors) {
+    errors.push({ field: prefix + error.field, message: error.message });
+  }
+  return;
+}
+function csvCell(value) {
+  if (value === null || value === undefined) {
+    return "";
+  }
+  if (value instanceof Date) {
+    return value.toISOString();
+  }
+  if (value instanceof Map || value instanceof Set) {
+    return JSON.stringify([...value]);
+  }
+  return typeof value === "object" ? JSON.stringify(value) : String(value);
+}
+function csvNested(value, header, toRow) {
+  return value === null || value === undefined ? header.map(() => "") : toRow(value);
+}
+function escapeCsvField(field) {
+  return /[",\r\n]/.test(field) ? `"${field.replace(/"/g, '""')}"` : field;
+}
+function formatCsvLine(fields) {
+  return fields.map(escapeCsvField).join(",");
+}
+function* csvLines(header, rows, toRow) {
+  yield formatCsvLine(header) + `\r
+`;
+  for (const row of rows) {
+    yield formatCsvLine(toRow(row)) + `\r
+`;
+  }
+}
+function toCsv(header, rows, toRow) {
+  let text = "";
+  for (const line of csvLines(header, rows, toRow)) {
+    text += line;
+  }
+  return text;
+}
+
+class CsvReader {
+  #header;
+  #record = [];
+  #field = "";
+  #quoted = false;
+  #afterQuote = false;
+  #skipLineFeed = false;
+  push(chunk) {
+    const records = [];
+    for (const c of chunk) {
+      if (this.#skipLineFeed) {
+        this.#skipLineFeed = false;
+        if (c === `
+`) {
+          continue;
+        }
+      }
+      if (this.#quoted) {
+        if (this.#afterQuote) {
+          this.#afterQuote = false;
+          if (c === '"') {
+            this.#field += '"';
+            continue;
+          }
+          this.#quoted = false;
+        } else {
+          if (c === '"') {
+            this.#afterQuote = true;
+          } else {
+            this.#field += c;
+          }
+          continue;
+        }
+      }
+      if (c === '"' && this.#field === "") {
+        this.#quoted = true;
+      } else if (c === ",") {
+        this.#record.push(this.#field);
+        this.#field = "";
+      } else if (c === "\r" || c === `
+`) {
+        this.#skipLineFeed = c === "\r";
+        this.#endRecord(records);
+      } else {
+        this.#field += c;
+      }
+    }
+    return records;
+  }
+  end() {
+    const records = [];
+    if (this.#quoted || this.#field !== "" || this.#record.length > 0) {
+      this.#quoted = false;
+      this.#afterQuote = false;
+      this.#endRecord(records);
+    }
+    return records;
+  }
+  #endRecord(records) {
+    const fields = [...this.#record, this.#field];
+    this.#record = [];
+    this.#field = "";
+    if (fields.length === 1 && fields[0] === "") {
+      return;
+    }
+    if (!this.#header) {
+      this.#header = fields;
+      return;
+    }
+    const record = {};
+    this.#header.forEach((column, i) => {
+      record[column] = fields[i];
+    });
+    records.push(record);
+  }
+}
+function parseCsv(text, fromRow) {
+  const reader = new CsvReader;
+  const records = [...reader.push(text), ...reader.end()];
+  const values = [];
+  const errors = [];
+  records.forEach((record, i) => {
+    const result = fromRow(record);
+    if (result.success) {
+      values.push(result.value);
+    } else {
+      errors.push(...result.errors.map((error) => ({ row: i + 1, ...error })));
+    }
+  });
+  return errors.length > 0 ? { success: false, errors } : { success: true, value: values };
+}
+export {
+  toCsv,
+  readNested,
+  readCell,
+  parseCsv,
+  formatCsvLine,
+  escapeCsvField,
+  csvNested,
+  csvLines,
+  csvDecoders,
+  csvCell,
+  CsvReader
+};
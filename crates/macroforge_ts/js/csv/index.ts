@@ -0,0 +1,418 @@
+/**
+ * # Macroforge CSV Module
+ *
+ * This module provides the runtime for the `Csv` macro, which maps types
+ * to CSV rows.
+ *
+ * - **RFC 4180**: fields containing commas, quotes or line breaks are
+ *   quoted, quotes are doubled, and records end with CRLF.
+ *
+ * - **Streaming**: `csvLines` yields one line at a time, and `CsvReader`
+ *   accepts text in chunks and returns complete records as they arrive.
+ *
+ * - **Deserialize-shaped results**: parse failures return
+ *   `{ success: false, errors: [{ row, field, message }] }`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Csv)
+ * const text = userToCsv(users);
+ * const result = userParseCsv(text);
+ *
+ * // Streaming
+ * const reader = new CsvReader();
+ * for await (const chunk of stream) {
+ *   for (const record of reader.push(chunk)) {
+ *     const row = userFromCsvRow(record);
+ *   }
+ * }
+ * ```
+ *
+ * @module macroforge/csv
+ */
+
+/**
+ * One CSV record, keyed by column name.
+ */
+export type CsvRecord = Readonly<Record<string, string | undefined>>;
+
+/**
+ * One decoding failure, shaped like a `Deserialize` error.
+ */
+export interface CsvError {
+  /** The 1-based data row, set by `parseCsv`. */
+  row?: number;
+  /** The column name. */
+  field: string;
+  message: string;
+}
+
+/**
+ * The result of decoding, shaped like a `Deserialize` result.
+ */
+export type CsvResult<T> = { success: true; value: T } | { success: false; errors: CsvError[] };
+
+/**
+ * Parses one cell; returns `undefined` for invalid input.
+ */
+export interface CsvDecoder<T> {
+  parse: (raw: string) => T | undefined;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+/**
+ * Decoders used by the code generated for `@derive(Csv)`.
+ */
+export const csvDecoders = {
+  string: { parse: (raw) => raw, expected: "a string" } as CsvDecoder<string>,
+  number: {
+    parse: (raw) => {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    },
+    expected: "a number",
+  } as CsvDecoder<number>,
+  bigint: {
+    parse: (raw) => {
+      try {
+        return BigInt(raw.trim());
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "an integer",
+  } as CsvDecoder<bigint>,
+  boolean: {
+    parse: (raw) => (raw === "true" ? true : raw === "false" ? false : undefined),
+    expected: "true or false",
+  } as CsvDecoder<boolean>,
+  date: {
+    parse: (raw) => {
+      const date = new Date(raw);
+      return Number.isNaN(date.getTime()) ? undefined : date;
+    },
+    expected: "an ISO date",
+  } as CsvDecoder<Date>,
+  json: {
+    parse: (raw) => {
+      try {
+        return JSON.parse(raw) as unknown;
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "JSON",
+  } as CsvDecoder<unknown>,
+  map: {
+    parse: (raw) => {
+      try {
+        const entries: unknown = JSON.parse(raw);
+        return Array.isArray(entries) ? new Map(entries as [unknown, unknown][]) : undefined;
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "a JSON array of entries",
+  } as CsvDecoder<Map<unknown, unknown>>,
+  set: {
+    parse: (raw) => {
+      try {
+        const items: unknown = JSON.parse(raw);
+        return Array.isArray(items) ? new Set(items) : undefined;
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "a JSON array",
+  } as CsvDecoder<Set<unknown>>,
+
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): CsvDecoder<T> {
+    return {
+      parse: (raw) => ((values as readonly string[]).includes(raw) ? (raw as T) : undefined),
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`,
+    };
+  },
+
+  /**
+   * A `deserializeWith` function; exceptions become the error message.
+   */
+  with<T>(deserialize: (raw: unknown) => T): CsvDecoder<T> {
+    return { parse: deserialize, expected: "a valid value" };
+  },
+};
+
+/**
+ * How a missing or empty cell is decoded: an error, `undefined` or `null`.
+ */
+export type CsvMissing = "required" | "optional" | "nullable";
+
+/**
+ * Reads and decodes one cell. Called by the code generated for
+ * `@derive(Csv)`.
+ *
+ * An empty cell counts as missing, except for required strings.
+ *
+ * @param record - The record
+ * @param column - The column name
+ * @param decoder - How to parse the cell
+ * @param errors - Collects a problem if the cell is missing or invalid
+ * @param missing - What a missing cell decodes to
+ * @returns The value, or `undefined`/`null` if it is missing or invalid
+ */
+export function readCell<T>(
+  record: CsvRecord,
+  column: string,
+  decoder: CsvDecoder<T>,
+  errors: CsvError[],
+  missing: CsvMissing = "required",
+): T | null | undefined {
+  const raw = record[column];
+  if (raw === undefined || (raw === "" && (missing !== "required" || decoder !== csvDecoders.string))) {
+    if (missing === "required") {
+      errors.push({ field: column, message: "is required" });
+    }
+    return missing === "nullable" ? null : undefined;
+  }
+  let value: T | undefined;
+  let message = `expected ${decoder.expected}`;
+  try {
+    value = decoder.parse(raw);
+  } catch (e) {
+    message = e instanceof Error ? e.message : String(e);
+  }
+  if (value === undefined) {
+    errors.push({ field: column, message });
+  }
+  return value;
+}
+
+/**
+ * The cells of `record` under `prefix`, with the prefix removed.
+ */
+function subRecord(record: CsvRecord, prefix: string): Record<string, string | undefined> {
+  const cells: Record<string, string | undefined> = {};
+  for (const [column, value] of Object.entries(record)) {
+    if (column.startsWith(prefix)) {
+      cells[column.slice(prefix.length)] = value;
+    }
+  }
+  return cells;
+}
+
+/**
+ * Reads a nested value from the columns under `prefix`. Called by the
+ * code generated for `@derive(Csv)`.
+ *
+ * @param record - The record
+ * @param prefix - The column prefix, e.g. `"address."`
+ * @param fromRow - The generated `fromCsvRow` of the nested type
+ * @param errors - Collects the nested errors, with prefixed columns
+ * @param missing - What the value is when all its cells are empty
+ */
+export function readNested<T>(
+  record: CsvRecord,
+  prefix: string,
+  fromRow: (record: CsvRecord) => CsvResult<T>,
+  errors: CsvError[],
+  missing: CsvMissing = "required",
+): T | null | undefined {
+  const cells = subRecord(record, prefix);
+  if (missing !== "required" && Object.values(cells).every((cell) => cell === undefined || cell === "")) {
+    return missing === "nullable" ? null : undefined;
+  }
+  const result = fromRow(cells);
+  if (result.success) {
+    return result.value;
+  }
+  for (const error of result.errors) {
+    errors.push({ field: prefix + error.field, message: error.message });
+  }
+  return undefined;
+}
+
+/**
+ * Encodes one cell. `null` and `undefined` become empty cells, dates ISO
+ * strings, maps and sets JSON arrays and other objects JSON.
+ */
+export function csvCell(value: unknown): string {
+  if (value === null || value === undefined) {
+    return "";
+  }
+  if (value instanceof Date) {
+    return value.toISOString();
+  }
+  if (value instanceof Map || value instanceof Set) {
+    return JSON.stringify([...value]);
+  }
+  return typeof value === "object" ? JSON.stringify(value) : String(value);
+}
+
+/**
+ * The cells of a nested value, or empty cells when it is missing. Called by
+ * the code generated for `@derive(Csv)`.
+ */
+export function csvNested<T>(
+  value: T | null | undefined,
+  header: readonly string[],
+  toRow: (value: T) => string[],
+): string[] {
+  return value === null || value === undefined ? header.map(() => "") : toRow(value);
+}
+
+/**
+ * Quotes a field if it contains a comma, quote or line break.
+ */
+export function escapeCsvField(field: string): string {
+  return /[",\r\n]/.test(field) ? `"${field.replace(/"/g, '""')}"` : field;
+}
+
+/**
+ * Formats one record as a CSV line, without the line break.
+ */
+export function formatCsvLine(fields: readonly string[]): string {
+  return fields.map(escapeCsvField).join(",");
+}
+
+/**
+ * Yields the header line and one line per row, each ending with CRLF.
+ */
+export function* csvLines<T>(
+  header: readonly string[],
+  rows: Iterable<T>,
+  toRow: (value: T) => string[],
+): Generator<string> {
+  yield formatCsvLine(header) + "\r\n";
+  for (const row of rows) {
+    yield formatCsvLine(toRow(row)) + "\r\n";
+  }
+}
+
+/**
+ * Formats rows as a CSV document with a header line.
+ */
+export function toCsv<T>(
+  header: readonly string[],
+  rows: Iterable<T>,
+  toRow: (value: T) => string[],
+): string {
+  let text = "";
+  for (const line of csvLines(header, rows, toRow)) {
+    text += line;
+  }
+  return text;
+}
+
+/**
+ * Incremental RFC 4180 reader. The first record is the header; later
+ * records are returned keyed by its columns. Empty lines are skipped.
+ */
+export class CsvReader {
+  #header: string[] | undefined;
+  #record: string[] = [];
+  #field = "";
+  #quoted = false;
+  #afterQuote = false;
+  #skipLineFeed = false;
+
+  /**
+   * Reads a chunk of text.
+   * @returns The records completed by this chunk
+   */
+  push(chunk: string): CsvRecord[] {
+    const records: CsvRecord[] = [];
+    for (const c of chunk) {
+      if (this.#skipLineFeed) {
+        this.#skipLineFeed = false;
+        if (c === "\n") {
+          continue;
+        }
+      }
+      if (this.#quoted) {
+        if (this.#afterQuote) {
+          this.#afterQuote = false;
+          if (c === '"') {
+            this.#field += '"';
+            continue;
+          }
+          this.#quoted = false;
+        } else {
+          if (c === '"') {
+            this.#afterQuote = true;
+          } else {
+            this.#field += c;
+          }
+          continue;
+        }
+      }
+      if (c === '"' && this.#field === "") {
+        this.#quoted = true;
+      } else if (c === ",") {
+        this.#record.push(this.#field);
+        this.#field = "";
+      } else if (c === "\r" || c === "\n") {
+        this.#skipLineFeed = c === "\r";
+        this.#endRecord(records);
+      } else {
+        this.#field += c;
+      }
+    }
+    return records;
+  }
+
+  /**
+   * Finishes reading.
+   * @returns The last record, if the text did not end with a line break
+   */
+  end(): CsvRecord[] {
+    const records: CsvRecord[] = [];
+    if (this.#quoted || this.#field !== "" || this.#record.length > 0) {
+      this.#quoted = false;
+      this.#afterQuote = false;
+      this.#endRecord(records);
+    }
+    return records;
+  }
+
+  #endRecord(records: CsvRecord[]): void {
+    const fields = [...this.#record, this.#field];
+    this.#record = [];
+    this.#field = "";
+    if (fields.length === 1 && fields[0] === "") {
+      return;
+    }
+    if (!this.#header) {
+      this.#header = fields;
+      return;
+    }
+    const record: Record<string, string | undefined> = {};
+    this.#header.forEach((column, i) => {
+      record[column] = fields[i];
+    });
+    records.push(record);
+  }
+}
+
+/**
+ * Parses a CSV document with a header line, decoding every row and
+ * collecting every error with its row number.
+ */
+export function parseCsv<T>(text: string, fromRow: (record: CsvRecord) => CsvResult<T>): CsvResult<T[]> {
+  const reader = new CsvReader();
+  const records = [...reader.push(text), ...reader.end()];
+  const values: T[] = [];
+  const errors: CsvError[] = [];
+  records.forEach((record, i) => {
+    const result = fromRow(record);
+    if (result.success) {
+      values.push(result.value);
+    } else {
+      errors.push(...result.errors.map((error) => ({ row: i + 1, ...error })));
+    }
+  });
+  return errors.length > 0 ? { success: false, errors } : { success: true, value: values };
+}
@@ -0,0 +1,54 @@
+// js/debug/index.ts
+var debugDerived = Symbol.for("macroforge.debug");
+function debugContext(pretty, ctx) {
+  return ctx ?? {
+    seen: new Set,
+    depth: 0,
+    indent: "",
+    pretty: !!pretty,
+    maxDepth: Infinity,
+    maxItems: Infinity
+  };
+}
+function debugFormat(value, ctx) {
+  if (value === null || typeof value !== "object" || value instanceof Date) {
+    return String(value);
+  }
+  if (ctx.seen.has(value)) {
+    return "[Circular]";
+  }
+  const isArray = Array.isArray(value);
+  const ctor = value.constructor;
+  const head = isArray ? "" : value instanceof Map ? "Map " : value instanceof Set ? "Set " : typeof ctor === "function" && ctor !== Object && ctor.name ? ctor.name + " " : "";
+  if (ctx.depth > ctx.maxDepth) {
+    return head + (isArray ? "[…]" : "{ … }");
+  }
+  if (!isArray && typeof ctor === "function" && ctor[debugDerived] === true) {
+    return ctor.toString(value, ctx.pretty, ctx);
+  }
+  const inner = { ...ctx, depth: ctx.depth + 1, indent: ctx.pretty ? ctx.indent + "    " : "" };
+  ctx.seen.add(value);
+  const entries = isArray ? value.map((e) => ["", e]) : value instanceof Set ? Array.from(value, (e) => ["", e]) : value instanceof Map ? Array.from(value, ([k, e]) => [debugFormat(k, inner) + " => ", e]) : Object.keys(value).map((k) => [
+    k + ": ",
+    value[k]
+  ]);
+  const items = entries.slice(0, ctx.maxItems).map(([prefix, e]) => prefix + debugFormat(e, inner));
+  if (entries.length > ctx.maxItems) {
+    items.push("…" + (entries.length - ctx.maxItems) + " more");
+  }
+  ctx.seen.delete(value);
+  if (items.length === 0) {
+    return head + (isArray ? "[]" : "{}");
+  }
+  if (ctx.pretty) {
+    return head + (isArray ? "[" : "{") + `
+` + items.map((item) => inner.indent + item + `,
+`).join("") + ctx.indent + (isArray ? "]" : "}");
+  }
+  return head + (isArray ? "[" + items.join(", ") + "]" : "{ " + items.join(", ") + " }");
+}
+export {
+  debugFormat,
+  debugDerived,
+  debugContext
+};
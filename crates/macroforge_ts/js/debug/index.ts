@@ -0,0 +1,146 @@
+/**
+ * # Macroforge Debug Module
+ *
+ * This module provides the runtime for the `Debug` macro, which generates
+ * human-readable `toString` functions.
+ *
+ * - **Nested values**: arrays, maps, sets and plain objects are formatted
+ *   recursively, honoring the `maxDepth` / `maxItems` limits of the context.
+ *
+ * - **Nested classes**: an object whose class carries the `debugDerived`
+ *   marker is formatted with the class's generated static `toString`, which
+ *   receives the caller's context as its third argument. Its field options
+ *   apply and the depth, limits and seen objects carry over. Cycles, also
+ *   across classes, print as `[Circular]`. Other classes are formatted
+ *   field by field, whatever static methods they define.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Debug)
+ * export function userToString(value: User, pretty?: boolean, ctx?: DebugContext): string {
+ *     const __c = debugContext(pretty, ctx);
+ *     const __inner = { ...__c, depth: __c.depth + 1 };
+ *     // ...
+ *     parts.push("tags: " + debugFormat(value.tags, __inner));
+ * }
+ * ```
+ *
+ * @module macroforge/debug
+ */
+
+/**
+ * The formatting state threaded through nested values.
+ */
+export interface DebugContext {
+  /** Objects currently being formatted, to detect cycles. */
+  seen: Set<object>;
+  /** Nesting level of the value being formatted. */
+  depth: number;
+  /** Indentation of the current line in pretty output. */
+  indent: string;
+  /** Whether to produce indented multi-line output. */
+  pretty: boolean;
+  /** Values nested deeper than this are elided. */
+  maxDepth: number;
+  /** Collections show at most this many entries. */
+  maxItems: number;
+}
+
+/**
+ * Marks a class whose static `toString` was generated by `@derive(Debug)`.
+ *
+ * The key is registered globally, so classes compiled against separate
+ * copies of this module are still recognised.
+ */
+export const debugDerived: unique symbol = Symbol.for("macroforge.debug");
+
+/**
+ * Returns the context for a generated `toString` call.
+ *
+ * When the call formats a value nested in another `Debug` output, `ctx` is
+ * the caller's context and is returned as is; otherwise a fresh one.
+ *
+ * @param pretty - Whether a fresh context produces multi-line output
+ * @param ctx - The caller's context, if any
+ * @returns The formatting context
+ */
+export function debugContext(pretty?: boolean, ctx?: DebugContext): DebugContext {
+  return (
+    ctx ?? {
+      seen: new Set<object>(),
+      depth: 0,
+      indent: "",
+      pretty: !!pretty,
+      maxDepth: Infinity,
+      maxItems: Infinity,
+    }
+  );
+}
+
+/**
+ * Formats a field value for `Debug` output.
+ *
+ * @param value - The value to format
+ * @param ctx - The context of the value's nesting level
+ * @returns The formatted value
+ */
+export function debugFormat(value: unknown, ctx: DebugContext): string {
+  if (value === null || typeof value !== "object" || value instanceof Date) {
+    return String(value);
+  }
+  if (ctx.seen.has(value)) {
+    return "[Circular]";
+  }
+  const isArray = Array.isArray(value);
+  const ctor = value.constructor as Function & {
+    [debugDerived]?: true;
+    toString(value: unknown, pretty?: boolean, ctx?: DebugContext): string;
+  };
+  const head = isArray
+    ? ""
+    : value instanceof Map
+      ? "Map "
+      : value instanceof Set
+        ? "Set "
+        : typeof ctor === "function" && ctor !== Object && ctor.name
+          ? ctor.name + " "
+          : "";
+  if (ctx.depth > ctx.maxDepth) {
+    return head + (isArray ? "[…]" : "{ … }");
+  }
+  if (!isArray && typeof ctor === "function" && ctor[debugDerived] === true) {
+    return ctor.toString(value, ctx.pretty, ctx);
+  }
+  const inner = { ...ctx, depth: ctx.depth + 1, indent: ctx.pretty ? ctx.indent + "    " : "" };
+  ctx.seen.add(value);
+  const entries: Array<[string, unknown]> = isArray
+    ? (value as unknown[]).map((e): [string, unknown] => ["", e])
+    : value instanceof Set
+      ? Array.from(value, (e): [string, unknown] => ["", e])
+      : value instanceof Map
+        ? Array.from(value, ([k, e]): [string, unknown] => [debugFormat(k, inner) + " => ", e])
+        : Object.keys(value).map((k): [string, unknown] => [
+            k + ": ",
+            (value as Record<string, unknown>)[k],
+          ]);
+  const items = entries.slice(0, ctx.maxItems).map(([prefix, e]) => prefix + debugFormat(e, inner));
+  if (entries.length > ctx.maxItems) {
+    items.push("…" + (entries.length - ctx.maxItems) + " more");
+  }
+  ctx.seen.delete(value);
+  if (items.length === 0) {
+    return head + (isArray ? "[]" : "{}");
+  }
+  if (ctx.pretty) {
+    return (
+      head +
+      (isArray ? "[" : "{") +
+      "\n" +
+      items.map((item) => inner.indent + item + ",\n").join("") +
+      ctx.indent +
+      (isArray ? "]" : "}")
+    );
+  }
+  return head + (isArray ? "[" + items.join(", ") + "]" : "{ " + items.join(", ") + " }");
+}
@@ -0,0 +1,196 @@
+/**
+ * # Macroforge Diff Module
+ *
+ * This module provides runtime helpers for the `Diff` macro, which computes
+ * what changed between two versions of a value.
+ *
+ * - **Change lists**: `DiffChange` records each difference with its old and
+ *   new value, addressed by an RFC 6901 JSON Pointer (`/address/city`).
+ *
+ * - **JSON Patch**: `toJsonPatch()` turns a change list into RFC 6902
+ *   operations, and `applyPatch()` applies such a patch without mutating its
+ *   input. Only the containers along each patched path are copied; class
+ *   instances keep their prototype.
+ *
+ * - **Collections**: arrays are compared by index, or by an identity key so
+ *   that insertions and reorders become `add`/`move` operations instead of a
+ *   cascade of replacements. `Map` entries are addressed by `String(key)`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Diff)
+ * const { changes, patch } = userDiff(before, after);
+ * const restored = userApplyPatch(before, patch);
+ *
+ * // Or directly, for any value
+ * import { applyPatch } from "macroforge/diff";
+ * const next = applyPatch(state, [{ op: "replace", path: "/name", value: "Ada" }]);
+ * ```
+ *
+ * @module macroforge/diff
+ */
+
+/**
+ * A single difference between two values.
+ *
+ * `path` is a JSON Pointer relative to the diffed root. Changes are ordered
+ * so that applying them one after another turns the old value into the new
+ * one; array indices refer to the array as left by the preceding changes.
+ */
+export type DiffChange =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string; oldValue: unknown }
+  | { op: "replace"; path: string; oldValue: unknown; value: unknown }
+  | { op: "move"; from: string; path: string };
+
+/**
+ * An RFC 6902 JSON Patch operation.
+ */
+export type JsonPatchOperation =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: unknown }
+  | { op: "move"; from: string; path: string }
+  | { op: "copy"; from: string; path: string }
+  | { op: "test"; path: string; value: unknown };
+
+/**
+ * The result of a generated `diff` function.
+ */
+export interface Diff {
+  /** Every difference, with old and new values */
+  changes: DiffChange[];
+  /** The same differences as a JSON Patch */
+  patch: JsonPatchOperation[];
+}
+
+/**
+ * Computes the changes between two values of the same type, with paths
+ * prefixed by `path`. Generated `*DiffChanges` functions have this shape.
+ */
+export type Differ<T> = (a: T, b: T, path: string) => DiffChange[];
+
+/**
+ * Options for {@link diffArray}.
+ */
+export interface ArrayDiffOptions<T> {
+  /**
+   * Property that identifies an element across versions. Without it,
+   * elements are compared position by position.
+   */
+  key?: string;
+  /** Differ for elements present in both arrays */
+  diff?: Differ<T>;
+}
+
+/**
+ * Error thrown by {@link applyPatch} when an operation cannot be applied.
+ */
+export declare class PatchError extends Error {
+  /**
+   * The operation that failed.
+   */
+  readonly operation: JsonPatchOperation;
+
+  /**
+   * Creates a new patch error.
+   * @param operation - The operation that failed
+   * @param message - Why it failed
+   */
+  constructor(operation: JsonPatchOperation, message: string);
+}
+
+/**
+ * Appends `segment` to the JSON Pointer `path`, escaping `~` and `/`.
+ */
+export declare function pointer(path: string, segment: PropertyKey): string;
+
+/**
+ * Records `a` becoming `b` as a single change, without looking inside
+ * either value. An `undefined` side turns the change into an `add` or
+ * `remove`.
+ */
+export declare function diffLeaf(
+  a: unknown,
+  b: unknown,
+  path: string,
+  changes: DiffChange[],
+): void;
+
+/**
+ * Structural diff for values without a generated differ.
+ *
+ * Arrays are compared by index, `Map`s by key, and objects of the same
+ * prototype property by property; anything else is a leaf.
+ */
+export declare function diffValue(
+  a: unknown,
+  b: unknown,
+  path: string,
+  changes: DiffChange[],
+): void;
+
+/**
+ * Diffs two values with a generated differ, treating `null`/`undefined`
+ * on either side as a leaf change.
+ */
+export declare function diffNested<T>(
+  a: T | null | undefined,
+  b: T | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  diff: Differ<T>,
+): void;
+
+/**
+ * Diffs two arrays.
+ *
+ * Without `key`, elements are compared by index and length changes become
+ * trailing `add`/`remove` changes. With `key`, elements are matched by that
+ * property: missing elements are removed, new ones added and moved ones
+ * reported as `move`, then matched pairs are diffed in place.
+ */
+export declare function diffArray<T>(
+  a: readonly T[] | null | undefined,
+  b: readonly T[] | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  options?: ArrayDiffOptions<T>,
+): void;
+
+/**
+ * Diffs two `Map`s entry by entry. Entries are addressed by `String(key)`.
+ */
+export declare function diffMap<K, V>(
+  a: ReadonlyMap<K, V> | null | undefined,
+  b: ReadonlyMap<K, V> | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  diff?: Differ<V>,
+): void;
+
+/**
+ * Returns `true` if `a` and `b` are structurally equal.
+ */
+export declare function deepEqual(a: unknown, b: unknown): boolean;
+
+/**
+ * Converts a change list into RFC 6902 JSON Patch operations.
+ */
+export declare function toJsonPatch(changes: readonly DiffChange[]): JsonPatchOperation[];
+
+/**
+ * Applies a JSON Patch to `value` and returns the result.
+ *
+ * `value` itself is never mutated: only the containers along each patched
+ * path are copied, and class instances keep their prototype. Operations are
+ * applied in order, as RFC 6902 requires.
+ *
+ * @template T - The type of the patched value
+ * @param value - The value to patch
+ * @param patch - The operations to apply
+ * @returns The patched copy
+ * @throws {PatchError} If an operation's path does not exist or a `test` fails
+ */
+export declare function applyPatch<T>(value: T, patch: readonly JsonPatchOperation[]): T;
@@ -0,0 +1,318 @@
+// js/diff/index.ts
+class PatchError extends Error {
+  operation;
+  constructor(operation, message) {
+    super(`applyPatch: ${operation.op} ${operation.path}: ${message}`);
+    this.name = "PatchError";
+    this.operation = operation;
+  }
+}
+function pointer(path, segment) {
+  return `${path}/${String(segment).replace(/~/g, "~0").replace(/\//g, "~1")}`;
+}
+function parsePointer(path) {
+  if (path === "") {
+    return [];
+  }
+  if (!path.startsWith("/")) {
+    throw new TypeError(`invalid JSON Pointer "${path}"`);
+  }
+  return path.slice(1).split("/").map((s) => s.replace(/~1/g, "/").replace(/~0/g, "~"));
+}
+function isRecord(value) {
+  return value !== null && typeof value === "object" && !Array.isArray(value) && !(value instanceof Map) && !(value instanceof Set) && !(value instanceof Date) && !(value instanceof RegExp);
+}
+function sameValue(a, b) {
+  if (a === b || Number.isNaN(a) && Number.isNaN(b)) {
+    return true;
+  }
+  if (a instanceof Date && b instanceof Date) {
+    return a.getTime() === b.getTime();
+  }
+  if (typeof a?.equals === "function") {
+    return a.equals(b);
+  }
+  return false;
+}
+function diffLeaf(a, b, path, changes) {
+  if (a === undefined) {
+    changes.push({ op: "add", path, value: b });
+  } else if (b === undefined) {
+    changes.push({ op: "remove", path, oldValue: a });
+  } else {
+    changes.push({ op: "replace", path, oldValue: a, value: b });
+  }
+}
+function diffValue(a, b, path, changes) {
+  if (sameValue(a, b)) {
+    return;
+  }
+  if (Array.isArray(a) && Array.isArray(b)) {
+    diffArray(a, b, path, changes);
+  } else if (a instanceof Map && b instanceof Map) {
+    diffMap(a, b, path, changes);
+  } else if (isRecord(a) && isRecord(b) && Object.getPrototypeOf(a) === Object.getPrototypeOf(b)) {
+    for (const key of Object.keys(a)) {
+      if (a[key] === undefined) {
+        continue;
+      }
+      if (b[key] === undefined) {
+        changes.push({ op: "remove", path: pointer(path, key), oldValue: a[key] });
+      } else {
+        diffValue(a[key], b[key], pointer(path, key), changes);
+      }
+    }
+    for (const key of Object.keys(b)) {
+      if (a[key] === undefined && b[key] !== undefined) {
+        changes.push({ op: "add", path: pointer(path, key), value: b[key] });
+      }
+    }
+  } else {
+    diffLeaf(a, b, path, changes);
+  }
+}
+function diffNested(a, b, path, changes, diff) {
+  if (a === null || a === undefined || b === null || b === undefined) {
+    if (a !== b) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  changes.push(...diff(a, b, path));
+}
+function diffArray(a, b, path, changes, options = {}) {
+  if (!Array.isArray(a) || !Array.isArray(b)) {
+    if (!sameValue(a, b)) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  const { key, diff } = options;
+  const item = (x, y, p) => diff ? diffNested(x, y, p, changes, diff) : diffValue(x, y, p, changes);
+  if (key === undefined) {
+    const common = Math.min(a.length, b.length);
+    for (let i = 0;i < common; i++) {
+      item(a[i], b[i], pointer(path, i));
+    }
+    for (let i = a.length - 1;i >= b.length; i--) {
+      changes.push({ op: "remove", path: pointer(path, i), oldValue: a[i] });
+    }
+    for (let i = a.length;i < b.length; i++) {
+      changes.push({ op: "add", path: pointer(path, i), value: b[i] });
+    }
+    return;
+  }
+  const keyOf = (value) => value?.[key];
+  const wanted = new Set(b.map(keyOf));
+  const working = a.slice();
+  for (let i = working.length - 1;i >= 0; i--) {
+    if (!wanted.has(keyOf(working[i]))) {
+      changes.push({ op: "remove", path: pointer(path, i), oldValue: working[i] });
+      working.splice(i, 1);
+    }
+  }
+  for (let i = 0;i < b.length; i++) {
+    const id = keyOf(b[i]);
+    let found = -1;
+    for (let j = i;j < working.length; j++) {
+      if (keyOf(working[j]) === id) {
+        found = j;
+        break;
+      }
+    }
+    if (found === -1) {
+      changes.push({ op: "add", path: pointer(path, i), value: b[i] });
+      working.splice(i, 0, b[i]);
+      continue;
+    }
+    if (found !== i) {
+      changes.push({ op: "move", from: pointer(path, found), path: pointer(path, i) });
+      working.splice(i, 0, working.splice(found, 1)[0]);
+    }
+    item(working[i], b[i], pointer(path, i));
+  }
+  for (let i = working.length - 1;i >= b.length; i--) {
+    changes.push({ op: "remove", path: pointer(path, i), oldValue: working[i] });
+  }
+}
+function diffMap(a, b, path, changes, diff) {
+  if (!(a instanceof Map) || !(b instanceof Map)) {
+    if (!sameValue(a, b)) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  for (const [key, value] of a) {
+    const p = pointer(path, key);
+    if (!b.has(key)) {
+      changes.push({ op: "remove", path: p, oldValue: value });
+    } else if (diff) {
+      diffNested(value, b.get(key), p, changes, diff);
+    } else {
+      diffValue(value, b.get(key), p, changes);
+    }
+  }
+  for (const [key, value] of b) {
+    if (!a.has(key)) {
+      changes.push({ op: "add", path: pointer(path, key), value });
+    }
+  }
+}
+function deepEqual(a, b) {
+  const changes = [];
+  diffValue(a, b, "", changes);
+  return changes.length === 0;
+}
+function toJsonPatch(changes) {
+  return changes.map((change) => {
+    switch (change.op) {
+      case "add":
+        return { op: "add", path: change.path, value: change.value };
+      case "remove":
+        return { op: "remove", path: change.path };
+      case "replace":
+        return { op: "replace", path: change.path, value: change.value };
+      case "move":
+        return { op: "move", from: change.from, path: change.path };
+    }
+  });
+}
+function shallowCopy(value) {
+  if (Array.isArray(value)) {
+    return value.slice();
+  }
+  return Object.assign(Object.create(Object.getPrototypeOf(value)), value);
+}
+function mapKey(map, segment) {
+  for (const key of map.keys()) {
+    if (String(key) === segment) {
+      return key;
+    }
+  }
+  return segment;
+}
+function has(container, segment) {
+  if (container instanceof Map) {
+    return [...container.keys()].some((k) => String(k) === segment);
+  }
+  if (Array.isArray(container)) {
+    const index = Number(segment);
+    return Number.isInteger(index) && index >= 0 && index < container.length;
+  }
+  return isRecord(container) && Object.hasOwn(container, segment);
+}
+function child(container, segment) {
+  if (container instanceof Map) {
+    return container.get(mapKey(container, segment));
+  }
+  return container?.[segment];
+}
+function getAt(root, segments) {
+  return segments.reduce(child, root);
+}
+function editAt(root, segments, edit) {
+  if (root === null || typeof root !== "object") {
+    throw new TypeError(`cannot descend into ${root === null ? "null" : typeof root}`);
+  }
+  const [segment, ...rest] = segments;
+  const copy = root instanceof Map ? new Map(root) : shallowCopy(root);
+  if (rest.length === 0) {
+    edit(copy, segment);
+    return copy;
+  }
+  const next = editAt(child(root, segment), rest, edit);
+  if (copy instanceof Map) {
+    copy.set(mapKey(copy, segment), next);
+  } else {
+    copy[segment] = next;
+  }
+  return copy;
+}
+function arrayIndex(array, segment, allowEnd) {
+  const index = segment === "-" && allowEnd ? array.length : Number(segment);
+  const max = allowEnd ? array.length : array.length - 1;
+  if (!Number.isInteger(index) || index < 0 || index > max) {
+    throw new TypeError(`array index "${segment}" is out of bounds`);
+  }
+  return index;
+}
+function addAt(root, segments, value) {
+  if (segments.length === 0) {
+    return value;
+  }
+  return editAt(root, segments, (container, segment) => {
+    if (Array.isArray(container)) {
+      container.splice(arrayIndex(container, segment, true), 0, value);
+    } else if (container instanceof Map) {
+      container.set(mapKey(container, segment), value);
+    } else {
+      container[segment] = value;
+    }
+  });
+}
+function removeAt(root, segments) {
+  if (segments.length === 0) {
+    throw new TypeError("cannot remove the root");
+  }
+  return editAt(root, segments, (container, segment) => {
+    if (!has(container, segment)) {
+      throw new TypeError("path does not exist");
+    }
+    if (Array.isArray(container)) {
+      container.splice(arrayIndex(container, segment, false), 1);
+    } else if (container instanceof Map) {
+      container.delete(mapKey(container, segment));
+    } else {
+      delete container[segment];
+    }
+  });
+}
+function applyOperation(root, operation) {
+  const segments = parsePointer(operation.path);
+  switch (operation.op) {
+    case "add":
+      return addAt(root, segments, operation.value);
+    case "remove":
+      return removeAt(root, segments);
+    case "replace":
+      if (segments.length > 0 && !has(getAt(root, segments.slice(0, -1)), segments[segments.length - 1])) {
+        throw new TypeError("path does not exist");
+      }
+      return addAt(segments.length > 0 ? removeAt(root, segments) : root, segments, operation.value);
+    case "move": {
+      const from = parsePointer(operation.from);
+      const value = getAt(root, from);
+      return addAt(removeAt(root, from), segments, value);
+    }
+    case "copy":
+      return addAt(root, segments, getAt(root, parsePointer(operation.from)));
+    case "test":
+      if (!deepEqual(getAt(root, segments), operation.value)) {
+        throw new TypeError("test failed");
+      }
+      return root;
+  }
+}
+function applyPatch(value, patch) {
+  let result = value;
+  for (const operation of patch) {
+    try {
+      result = applyOperation(result, operation);
+    } catch (error) {
+      throw new PatchError(operation, error.message);
+    }
+  }
+  return result;
+}
+export {
+  toJsonPatch,
+  pointer,
+  diffValue,
+  diffNested,
+  diffMap,
+  diffLeaf,
+  diffArray,
+  deepEqual,
+  applyPatch,
+  PatchError
+};
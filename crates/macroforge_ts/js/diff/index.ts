@@ -0,0 +1,551 @@
+/**
+ * # Macroforge Diff Module
+ *
+ * This module provides runtime helpers for the `Diff` macro, which computes
+ * what changed between two versions of a value.
+ *
+ * - **Change lists**: `DiffChange` records each difference with its old and
+ *   new value, addressed by an RFC 6901 JSON Pointer (`/address/city`).
+ *
+ * - **JSON Patch**: `toJsonPatch()` turns a change list into RFC 6902
+ *   operations, and `applyPatch()` applies such a patch without mutating its
+ *   input. Only the containers along each patched path are copied; class
+ *   instances keep their prototype.
+ *
+ * - **Collections**: arrays are compared by index, or by an identity key so
+ *   that insertions and reorders become `add`/`move` operations instead of a
+ *   cascade of replacements. `Map` entries are addressed by `String(key)`.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Diff)
+ * const { changes, patch } = userDiff(before, after);
+ * const restored = userApplyPatch(before, patch);
+ *
+ * // Or directly, for any value
+ * import { applyPatch } from "macroforge/diff";
+ * const next = applyPatch(state, [{ op: "replace", path: "/name", value: "Ada" }]);
+ * ```
+ *
+ * @module macroforge/diff
+ */
+
+/**
+ * A single difference between two values.
+ *
+ * `path` is a JSON Pointer relative to the diffed root. Changes are ordered
+ * so that applying them one after another turns the old value into the new
+ * one; array indices refer to the array as left by the preceding changes.
+ */
+export type DiffChange =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string; oldValue: unknown }
+  | { op: "replace"; path: string; oldValue: unknown; value: unknown }
+  | { op: "move"; from: string; path: string };
+
+/**
+ * An RFC 6902 JSON Patch operation.
+ */
+export type JsonPatchOperation =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: unknown }
+  | { op: "move"; from: string; path: string }
+  | { op: "copy"; from: string; path: string }
+  | { op: "test"; path: string; value: unknown };
+
+/**
+ * The result of a generated `diff` function.
+ */
+export interface Diff {
+  /** Every difference, with old and new values */
+  changes: DiffChange[];
+  /** The same differences as a JSON Patch */
+  patch: JsonPatchOperation[];
+}
+
+/**
+ * Computes the changes between two values of the same type, with paths
+ * prefixed by `path`. Generated `*DiffChanges` functions have this shape.
+ */
+export type Differ<T> = (a: T, b: T, path: string) => DiffChange[];
+
+/**
+ * Options for {@link diffArray}.
+ */
+export interface ArrayDiffOptions<T> {
+  /**
+   * Property that identifies an element across versions. Without it,
+   * elements are compared position by position.
+   */
+  key?: string;
+  /** Differ for elements present in both arrays */
+  diff?: Differ<T>;
+}
+
+/**
+ * Error thrown by {@link applyPatch} when an operation cannot be applied.
+ */
+export class PatchError extends Error {
+  /**
+   * The operation that failed.
+   */
+  public readonly operation: JsonPatchOperation;
+
+  /**
+   * Creates a new patch error.
+   * @param operation - The operation that failed
+   * @param message - Why it failed
+   */
+  constructor(operation: JsonPatchOperation, message: string) {
+    super(`applyPatch: ${operation.op} ${operation.path}: ${message}`);
+    this.name = "PatchError";
+    this.operation = operation;
+  }
+}
+
+/**
+ * Appends `segment` to the JSON Pointer `path`, escaping `~` and `/`.
+ */
+export function pointer(path: string, segment: PropertyKey): string {
+  return `${path}/${String(segment).replace(/~/g, "~0").replace(/\//g, "~1")}`;
+}
+
+/**
+ * Splits a JSON Pointer into its unescaped segments.
+ */
+function parsePointer(path: string): string[] {
+  if (path === "") {
+    return [];
+  }
+  if (!path.startsWith("/")) {
+    throw new TypeError(`invalid JSON Pointer "${path}"`);
+  }
+  return path
+    .slice(1)
+    .split("/")
+    .map((s) => s.replace(/~1/g, "/").replace(/~0/g, "~"));
+}
+
+/**
+ * Objects that are diffed property by property: plain objects and class
+ * instances, but not arrays, `Map`, `Set`, `Date` or `RegExp`.
+ */
+function isRecord(value: unknown): value is Record<string, unknown> {
+  return (
+    value !== null &&
+    typeof value === "object" &&
+    !Array.isArray(value) &&
+    !(value instanceof Map) &&
+    !(value instanceof Set) &&
+    !(value instanceof Date) &&
+    !(value instanceof RegExp)
+  );
+}
+
+/**
+ * Leaf equality: `===` (with `NaN` equal to itself), `Date` by timestamp,
+ * and an `equals` method when the value has one, mirroring `PartialEq`.
+ */
+function sameValue(a: unknown, b: unknown): boolean {
+  if (a === b || (Number.isNaN(a) && Number.isNaN(b))) {
+    return true;
+  }
+  if (a instanceof Date && b instanceof Date) {
+    return a.getTime() === b.getTime();
+  }
+  if (typeof (a as { equals?: unknown })?.equals === "function") {
+    return (a as { equals(other: unknown): boolean }).equals(b);
+  }
+  return false;
+}
+
+/**
+ * Records `a` becoming `b` as a single change, without looking inside
+ * either value. An `undefined` side turns the change into an `add` or
+ * `remove`.
+ */
+export function diffLeaf(
+  a: unknown,
+  b: unknown,
+  path: string,
+  changes: DiffChange[],
+): void {
+  if (a === undefined) {
+    changes.push({ op: "add", path, value: b });
+  } else if (b === undefined) {
+    changes.push({ op: "remove", path, oldValue: a });
+  } else {
+    changes.push({ op: "replace", path, oldValue: a, value: b });
+  }
+}
+
+/**
+ * Structural diff for values without a generated differ.
+ *
+ * Arrays are compared by index, `Map`s by key, and objects of the same
+ * prototype property by property; anything else is a leaf.
+ */
+export function diffValue(
+  a: unknown,
+  b: unknown,
+  path: string,
+  changes: DiffChange[],
+): void {
+  if (sameValue(a, b)) {
+    return;
+  }
+  if (Array.isArray(a) && Array.isArray(b)) {
+    diffArray(a, b, path, changes);
+  } else if (a instanceof Map && b instanceof Map) {
+    diffMap(a, b, path, changes);
+  } else if (
+    isRecord(a) &&
+    isRecord(b) &&
+    Object.getPrototypeOf(a) === Object.getPrototypeOf(b)
+  ) {
+    for (const key of Object.keys(a)) {
+      if (a[key] === undefined) {
+        continue;
+      }
+      if (b[key] === undefined) {
+        changes.push({ op: "remove", path: pointer(path, key), oldValue: a[key] });
+      } else {
+        diffValue(a[key], b[key], pointer(path, key), changes);
+      }
+    }
+    for (const key of Object.keys(b)) {
+      if (a[key] === undefined && b[key] !== undefined) {
+        changes.push({ op: "add", path: pointer(path, key), value: b[key] });
+      }
+    }
+  } else {
+    diffLeaf(a, b, path, changes);
+  }
+}
+
+/**
+ * Diffs two values with a generated differ, treating `null`/`undefined`
+ * on either side as a leaf change.
+ */
+export function diffNested<T>(
+  a: T | null | undefined,
+  b: T | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  diff: Differ<T>,
+): void {
+  if (a === null || a === undefined || b === null || b === undefined) {
+    if (a !== b) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  changes.push(...diff(a, b, path));
+}
+
+/**
+ * Diffs two arrays.
+ *
+ * Without `key`, elements are compared by index and length changes become
+ * trailing `add`/`remove` changes. With `key`, elements are matched by that
+ * property: missing elements are removed, new ones added and moved ones
+ * reported as `move`, then matched pairs are diffed in place.
+ */
+export function diffArray<T>(
+  a: readonly T[] | null | undefined,
+  b: readonly T[] | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  options: ArrayDiffOptions<T> = {},
+): void {
+  if (!Array.isArray(a) || !Array.isArray(b)) {
+    if (!sameValue(a, b)) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  const { key, diff } = options;
+  const item = (x: T, y: T, p: string) =>
+    diff ? diffNested(x, y, p, changes, diff) : diffValue(x, y, p, changes);
+
+  if (key === undefined) {
+    const common = Math.min(a.length, b.length);
+    for (let i = 0; i < common; i++) {
+      item(a[i], b[i], pointer(path, i));
+    }
+    for (let i = a.length - 1; i >= b.length; i--) {
+      changes.push({ op: "remove", path: pointer(path, i), oldValue: a[i] });
+    }
+    for (let i = a.length; i < b.length; i++) {
+      changes.push({ op: "add", path: pointer(path, i), value: b[i] });
+    }
+    return;
+  }
+
+  const keyOf = (value: T): unknown =>
+    (value as Record<string, unknown> | null | undefined)?.[key];
+  const wanted = new Set(b.map(keyOf));
+  const working = a.slice();
+
+  for (let i = working.length - 1; i >= 0; i--) {
+    if (!wanted.has(keyOf(working[i]))) {
+      changes.push({ op: "remove", path: pointer(path, i), oldValue: working[i] });
+      working.splice(i, 1);
+    }
+  }
+
+  for (let i = 0; i < b.length; i++) {
+    const id = keyOf(b[i]);
+    let found = -1;
+    for (let j = i; j < working.length; j++) {
+      if (keyOf(working[j]) === id) {
+        found = j;
+        break;
+      }
+    }
+    if (found === -1) {
+      changes.push({ op: "add", path: pointer(path, i), value: b[i] });
+      working.splice(i, 0, b[i]);
+      continue;
+    }
+    if (found !== i) {
+      changes.push({ op: "move", from: pointer(path, found), path: pointer(path, i) });
+      working.splice(i, 0, working.splice(found, 1)[0]);
+    }
+    item(working[i], b[i], pointer(path, i));
+  }
+
+  // Duplicate keys in `a` that found no partner in `b`
+  for (let i = working.length - 1; i >= b.length; i--) {
+    changes.push({ op: "remove", path: pointer(path, i), oldValue: working[i] });
+  }
+}
+
+/**
+ * Diffs two `Map`s entry by entry. Entries are addressed by `String(key)`.
+ */
+export function diffMap<K, V>(
+  a: ReadonlyMap<K, V> | null | undefined,
+  b: ReadonlyMap<K, V> | null | undefined,
+  path: string,
+  changes: DiffChange[],
+  diff?: Differ<V>,
+): void {
+  if (!(a instanceof Map) || !(b instanceof Map)) {
+    if (!sameValue(a, b)) {
+      diffLeaf(a, b, path, changes);
+    }
+    return;
+  }
+  for (const [key, value] of a) {
+    const p = pointer(path, key as PropertyKey);
+    if (!b.has(key)) {
+      changes.push({ op: "remove", path: p, oldValue: value });
+    } else if (diff) {
+      diffNested(value, b.get(key), p, changes, diff);
+    } else {
+      diffValue(value, b.get(key), p, changes);
+    }
+  }
+  for (const [key, value] of b) {
+    if (!a.has(key)) {
+      changes.push({ op: "add", path: pointer(path, key as PropertyKey), value });
+    }
+  }
+}
+
+/**
+ * Returns `true` if `a` and `b` are structurally equal.
+ */
+export function deepEqual(a: unknown, b: unknown): boolean {
+  const changes: DiffChange[] = [];
+  diffValue(a, b, "", changes);
+  return changes.length === 0;
+}
+
+/**
+ * Converts a change list into RFC 6902 JSON Patch operations.
+ */
+export function toJsonPatch(changes: readonly DiffChange[]): JsonPatchOperation[] {
+  return changes.map((change): JsonPatchOperation => {
+    switch (change.op) {
+      case "add":
+        return { op: "add", path: change.path, value: change.value };
+      case "remove":
+        return { op: "remove", path: change.path };
+      case "replace":
+        return { op: "replace", path: change.path, value: change.value };
+      case "move":
+        return { op: "move", from: change.from, path: change.path };
+    }
+  });
+}
+
+/**
+ * Shallow copy that preserves the prototype, like `shallowCopy` in
+ * `macroforge/with`.
+ */
+function shallowCopy<T>(value: T): T {
+  if (Array.isArray(value)) {
+    return value.slice() as T;
+  }
+  return Object.assign(Object.create(Object.getPrototypeOf(value)), value);
+}
+
+/**
+ * Finds the `Map` key addressed by `segment`, falling back to the segment
+ * itself for keys that don't exist yet.
+ */
+function mapKey(map: Map<unknown, unknown>, segment: string): unknown {
+  for (const key of map.keys()) {
+    if (String(key) === segment) {
+      return key;
+    }
+  }
+  return segment;
+}
+
+function has(container: unknown, segment: string): boolean {
+  if (container instanceof Map) {
+    return [...container.keys()].some((k) => String(k) === segment);
+  }
+  if (Array.isArray(container)) {
+    const index = Number(segment);
+    return Number.isInteger(index) && index >= 0 && index < container.length;
+  }
+  return isRecord(container) && Object.hasOwn(container, segment);
+}
+
+function child(container: unknown, segment: string): unknown {
+  if (container instanceof Map) {
+    return container.get(mapKey(container, segment));
+  }
+  return (container as Record<string, unknown> | null | undefined)?.[segment];
+}
+
+function getAt(root: unknown, segments: readonly string[]): unknown {
+  return segments.reduce(child, root);
+}
+
+/**
+ * Copies every container along `segments` and calls `edit` on the copy of
+ * the last one with the final segment.
+ */
+function editAt(
+  root: unknown,
+  segments: readonly string[],
+  edit: (container: unknown, segment: string) => void,
+): unknown {
+  if (root === null || typeof root !== "object") {
+    throw new TypeError(`cannot descend into ${root === null ? "null" : typeof root}`);
+  }
+  const [segment, ...rest] = segments;
+  const copy = root instanceof Map ? new Map(root) : shallowCopy(root);
+  if (rest.length === 0) {
+    edit(copy, segment);
+    return copy;
+  }
+  const next = editAt(child(root, segment), rest, edit);
+  if (copy instanceof Map) {
+    copy.set(mapKey(copy, segment), next);
+  } else {
+    (copy as Record<string, unknown>)[segment] = next;
+  }
+  return copy;
+}
+
+function arrayIndex(array: unknown[], segment: string, allowEnd: boolean): number {
+  const index = segment === "-" && allowEnd ? array.length : Number(segment);
+  const max = allowEnd ? array.length : array.length - 1;
+  if (!Number.isInteger(index) || index < 0 || index > max) {
+    throw new TypeError(`array index "${segment}" is out of bounds`);
+  }
+  return index;
+}
+
+function addAt(root: unknown, segments: readonly string[], value: unknown): unknown {
+  if (segments.length === 0) {
+    return value;
+  }
+  return editAt(root, segments, (container, segment) => {
+    if (Array.isArray(container)) {
+      container.splice(arrayIndex(container, segment, true), 0, value);
+    } else if (container instanceof Map) {
+      container.set(mapKey(container, segment), value);
+    } else {
+      (container as Record<string, unknown>)[segment] = value;
+    }
+  });
+}
+
+function removeAt(root: unknown, segments: readonly string[]): unknown {
+  if (segments.length === 0) {
+    throw new TypeError("cannot remove the root");
+  }
+  return editAt(root, segments, (container, segment) => {
+    if (!has(container, segment)) {
+      throw new TypeError("path does not exist");
+    }
+    if (Array.isArray(container)) {
+      container.splice(arrayIndex(container, segment, false), 1);
+    } else if (container instanceof Map) {
+      container.delete(mapKey(container, segment));
+    } else {
+      delete (container as Record<string, unknown>)[segment];
+    }
+  });
+}
+
+function applyOperation(root: unknown, operation: JsonPatchOperation): unknown {
+  const segments = parsePointer(operation.path);
+  switch (operation.op) {
+    case "add":
+      return addAt(root, segments, operation.value);
+    case "remove":
+      return removeAt(root, segments);
+    case "replace":
+      if (segments.length > 0 && !has(getAt(root, segments.slice(0, -1)), segments[segments.length - 1])) {
+        throw new TypeError("path does not exist");
+      }
+      return addAt(segments.length > 0 ? removeAt(root, segments) : root, segments, operation.value);
+    case "move": {
+      const from = parsePointer(operation.from);
+      const value = getAt(root, from);
+      return addAt(removeAt(root, from), segments, value);
+    }
+    case "copy":
+      return addAt(root, segments, getAt(root, parsePointer(operation.from)));
+    case "test":
+      if (!deepEqual(getAt(root, segments), operation.value)) {
+        throw new TypeError("test failed");
+      }
+      return root;
+  }
+}
+
+/**
+ * Applies a JSON Patch to `value` and returns the result.
+ *
+ * `value` itself is never mutated: only the containers along each patched
+ * path are copied, and class instances keep their prototype. Operations are
+ * applied in order, as RFC 6902 requires.
+ *
+ * @template T - The type of the patched value
+ * @param value - The value to patch
+ * @param patch - The operations to apply
+ * @returns The patched copy
+ * @throws {PatchError} If an operation's path does not exist or a `test` fails
+ */
+export function applyPatch<T>(value: T, patch: readonly JsonPatchOperation[]): T {
+  let result: unknown = value;
+  for (const operation of patch) {
+    try {
+      result = applyOperation(result, operation);
+    } catch (error) {
+      throw new PatchError(operation, (error as Error).message);
+    }
+  }
+  return result as T;
+}
@@ -0,0 +1,124 @@
+/**
+ * # Macroforge Env Module
+ *
+ * This module provides the runtime for the `Env` macro, which loads typed
+ * configuration from environment variables.
+ *
+ * - **Coercion**: `envParsers` turn variable strings into numbers,
+ *   booleans, bigints, dates, URLs, literal unions and separated lists.
+ *
+ * - **All problems at once**: every missing or invalid variable is
+ *   collected, and a single `EnvError` lists them all, so a misconfigured
+ *   deployment is fixed in one round trip.
+ *
+ * - **Secrets**: values of secret variables never appear in messages.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Env)
+ * const config = appConfigLoadFromEnv();
+ *
+ * // Or with an explicit source, e.g. in tests
+ * const config = appConfigLoadFromEnv({ PORT: "8080", DATABASE_URL: "postgres://..." });
+ * ```
+ *
+ * @module macroforge/env
+ */
+
+/**
+ * Where variables are read from, e.g. `process.env`.
+ */
+export type EnvSource = Readonly<Record<string, string | undefined>>;
+
+/**
+ * One missing or invalid variable.
+ */
+export interface EnvIssue {
+  /** The variable name, e.g. `"DATABASE_URL"`. */
+  variable: string;
+  message: string;
+}
+
+/**
+ * Thrown by generated loaders when any variable is missing or invalid.
+ */
+export declare class EnvError extends Error {
+  readonly issues: EnvIssue[];
+  /**
+   * Creates a new env error.
+   * @param issues - Every missing or invalid variable
+   */
+  constructor(issues: EnvIssue[]);
+}
+
+/**
+ * Parses one variable; returns `undefined` for invalid input.
+ */
+export type EnvParser<T> = (raw: string) => T | undefined;
+
+/**
+ * A parser with a description of what it accepts, for messages.
+ */
+export interface EnvCoercion<T> {
+  parse: EnvParser<T>;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+/**
+ * Coercions used by the code generated for `@derive(Env)`.
+ */
+export declare const envParsers: {
+  string: EnvCoercion<string>;
+  number: EnvCoercion<number>;
+  bigint: EnvCoercion<bigint>;
+  boolean: EnvCoercion<boolean>;
+  date: EnvCoercion<Date>;
+  url: EnvCoercion<URL>;
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): EnvCoercion<T>;
+  /**
+   * A list of items separated by `separator`. Items are trimmed and empty
+   * items are dropped.
+   */
+  list<T>(item: EnvCoercion<T>, separator?: string): EnvCoercion<T[]>;
+};
+
+/**
+ * Options for `readEnv`.
+ */
+export interface ReadEnvOptions {
+  /** Missing variables are not an error. */
+  optional?: boolean;
+  /** Never show the value in messages. */
+  secret?: boolean;
+}
+
+/**
+ * Reads and coerces one variable. Called by the code generated for
+ * `@derive(Env)`.
+ *
+ * An empty variable counts as missing.
+ *
+ * @param env - The variable source
+ * @param variable - The variable name
+ * @param coercion - How to parse the value
+ * @param issues - Collects a problem if the variable is missing or invalid
+ * @param options - Whether the variable is optional or secret
+ * @returns The value, or `undefined` if it is missing or invalid
+ */
+export declare function readEnv<T>(
+  env: EnvSource,
+  variable: string,
+  coercion: EnvCoercion<T>,
+  issues: EnvIssue[],
+  options?: ReadEnvOptions,
+): T | undefined;
+
+/**
+ * `process.env` where it exists, an empty source elsewhere.
+ */
+export declare function processEnv(): EnvSource;
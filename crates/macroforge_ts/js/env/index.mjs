@@ -0,0 +1,107 @@
+// js/env/index.ts
+class EnvError extends Error {
+  issues;
+  constructor(issues) {
+    super(`Invalid environment (${issues.length} problem${issues.length === 1 ? "" : "s"}):
+` + issues.map((issue) => `  - ${issue.variable}: ${issue.message}`).join(`
+`));
+    this.issues = issues;
+    this.name = "EnvError";
+  }
+}
+var TRUE = ["true", "1", "yes", "on"];
+var FALSE = ["false", "0", "no", "off"];
+var envParsers = {
+  string: { parse: (raw) => raw, expected: "a string" },
+  number: {
+    parse: (raw) => {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    },
+    expected: "a number"
+  },
+  bigint: {
+    parse: (raw) => {
+      try {
+        return BigInt(raw.trim());
+      } catch {
+        return;
+      }
+    },
+    expected: "an integer"
+  },
+  boolean: {
+    parse: (raw) => {
+      const value = raw.trim().toLowerCase();
+      return TRUE.includes(value) ? true : FALSE.includes(value) ? false : undefined;
+    },
+    expected: "true/false, 1/0, yes/no or on/off"
+  },
+  date: {
+    parse: (raw) => {
+      const date = new Date(raw);
+      return Number.isNaN(date.getTime()) ? undefined : date;
+    },
+    expected: "an ISO date"
+  },
+  url: {
+    parse: (raw) => {
+      try {
+        return new URL(raw);
+      } catch {
+        return;
+      }
+    },
+    expected: "an absolute URL"
+  },
+  oneOf(values) {
+    return {
+      parse: (raw) => values.includes(raw) ? raw : undefined,
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`
+    };
+  },
+  list(item, separator = ",") {
+    return {
+      parse: (raw) => {
+        const items = [];
+        for (const part of raw.split(separator)) {
+          if (part.trim() === "") {
+            continue;
+          }
+          const value = item.parse(part.trim());
+          if (value === undefined) {
+            return;
+          }
+          items.push(value);
+        }
+        return items;
+      },
+      expected: `a "${separator}"-separated list of ${item.expected.replace(/^an? /, "")} values`
+    };
+  }
+};
+function readEnv(env, variable, coercion, issues, options = {}) {
+  const raw = env[variable];
+  if (raw === undefined || raw === "") {
+    if (!options.optional) {
+      issues.push({ variable, message: "is required but not set" });
+    }
+    return;
+  }
+  const value = coercion.parse(raw);
+  if (value === undefined) {
+    const got = options.secret ? " (value hidden)" : `, got ${JSON.stringify(raw)}`;
+    issues.push({ variable, message: `expected ${coercion.expected}${got}` });
+  }
+  return value;
+}
+function processEnv() {
+  const process2 = globalThis.process;
+  return process2?.env ?? {};
+}
+export {
+  readEnv,
+  processEnv,
+  envParsers,
+  EnvError
+};
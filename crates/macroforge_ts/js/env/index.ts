@@ -0,0 +1,210 @@
+/**
+ * # Macroforge Env Module
+ *
+ * This module provides the runtime for the `Env` macro, which loads typed
+ * configuration from environment variables.
+ *
+ * - **Coercion**: `envParsers` turn variable strings into numbers,
+ *   booleans, bigints, dates, URLs, literal unions and separated lists.
+ *
+ * - **All problems at once**: every missing or invalid variable is
+ *   collected, and a single `EnvError` lists them all, so a misconfigured
+ *   deployment is fixed in one round trip.
+ *
+ * - **Secrets**: values of secret variables never appear in messages.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Env)
+ * const config = appConfigLoadFromEnv();
+ *
+ * // Or with an explicit source, e.g. in tests
+ * const config = appConfigLoadFromEnv({ PORT: "8080", DATABASE_URL: "postgres://..." });
+ * ```
+ *
+ * @module macroforge/env
+ */
+
+/**
+ * Where variables are read from, e.g. `process.env`.
+ */
+export type EnvSource = Readonly<Record<string, string | undefined>>;
+
+/**
+ * One missing or invalid variable.
+ */
+export interface EnvIssue {
+  /** The variable name, e.g. `"DATABASE_URL"`. */
+  variable: string;
+  message: string;
+}
+
+/**
+ * Thrown by generated loaders when any variable is missing or invalid.
+ */
+export class EnvError extends Error {
+  /**
+   * Creates a new env error.
+   * @param issues - Every missing or invalid variable
+   */
+  constructor(public readonly issues: EnvIssue[]) {
+    super(
+      `Invalid environment (${issues.length} problem${issues.length === 1 ? "" : "s"}):\n` +
+        issues.map((issue) => `  - ${issue.variable}: ${issue.message}`).join("\n"),
+    );
+    this.name = "EnvError";
+  }
+}
+
+/**
+ * Parses one variable; returns `undefined` for invalid input.
+ */
+export type EnvParser<T> = (raw: string) => T | undefined;
+
+/**
+ * A parser with a description of what it accepts, for messages.
+ */
+export interface EnvCoercion<T> {
+  parse: EnvParser<T>;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+const TRUE = ["true", "1", "yes", "on"];
+const FALSE = ["false", "0", "no", "off"];
+
+/**
+ * Coercions used by the code generated for `@derive(Env)`.
+ */
+export const envParsers = {
+  string: { parse: (raw) => raw, expected: "a string" } as EnvCoercion<string>,
+  number: {
+    parse: (raw) => {
+      const n = Number(raw);
+      return raw.trim() === "" || Number.isNaN(n) ? undefined : n;
+    },
+    expected: "a number",
+  } as EnvCoercion<number>,
+  bigint: {
+    parse: (raw) => {
+      try {
+        return BigInt(raw.trim());
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "an integer",
+  } as EnvCoercion<bigint>,
+  boolean: {
+    parse: (raw) => {
+      const value = raw.trim().toLowerCase();
+      return TRUE.includes(value) ? true : FALSE.includes(value) ? false : undefined;
+    },
+    expected: "true/false, 1/0, yes/no or on/off",
+  } as EnvCoercion<boolean>,
+  date: {
+    parse: (raw) => {
+      const date = new Date(raw);
+      return Number.isNaN(date.getTime()) ? undefined : date;
+    },
+    expected: "an ISO date",
+  } as EnvCoercion<Date>,
+  url: {
+    parse: (raw) => {
+      try {
+        return new URL(raw);
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "an absolute URL",
+  } as EnvCoercion<URL>,
+
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): EnvCoercion<T> {
+    return {
+      parse: (raw) => (values as readonly string[]).includes(raw) ? (raw as T) : undefined,
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`,
+    };
+  },
+
+  /**
+   * A list of items separated by `separator`. Items are trimmed and empty
+   * items are dropped.
+   */
+  list<T>(item: EnvCoercion<T>, separator = ","): EnvCoercion<T[]> {
+    return {
+      parse: (raw) => {
+        const items: T[] = [];
+        for (const part of raw.split(separator)) {
+          if (part.trim() === "") {
+            continue;
+          }
+          const value = item.parse(part.trim());
+          if (value === undefined) {
+            return undefined;
+          }
+          items.push(value);
+        }
+        return items;
+      },
+      expected: `a "${separator}"-separated list of ${item.expected.replace(/^an? /, "")} values`,
+    };
+  },
+};
+
+/**
+ * Options for `readEnv`.
+ */
+export interface ReadEnvOptions {
+  /** Missing variables are not an error. */
+  optional?: boolean;
+  /** Never show the value in messages. */
+  secret?: boolean;
+}
+
+/**
+ * Reads and coerces one variable. Called by the code generated for
+ * `@derive(Env)`.
+ *
+ * An empty variable counts as missing.
+ *
+ * @param env - The variable source
+ * @param variable - The variable name
+ * @param coercion - How to parse the value
+ * @param issues - Collects a problem if the variable is missing or invalid
+ * @param options - Whether the variable is optional or secret
+ * @returns The value, or `undefined` if it is missing or invalid
+ */
+export function readEnv<T>(
+  env: EnvSource,
+  variable: string,
+  coercion: EnvCoercion<T>,
+  issues: EnvIssue[],
+  options: ReadEnvOptions = {},
+): T | undefined {
+  const raw = env[variable];
+  if (raw === undefined || raw === "") {
+    if (!options.optional) {
+      issues.push({ variable, message: "is required but not set" });
+    }
+    return undefined;
+  }
+  const value = coercion.parse(raw);
+  if (value === undefined) {
+    const got = options.secret ? " (value hidden)" : `, got ${JSON.stringify(raw)}`;
+    issues.push({ variable, message: `expected ${coercion.expected}${got}` });
+  }
+  return value;
+}
+
+/**
+ * `process.env` where it exists, an empty source elsewhere.
+ */
+export function processEnv(): EnvSource {
+  const process = (globalThis as { process?: { env?: EnvSource } }).process;
+  return process?.env ?? {};
+}
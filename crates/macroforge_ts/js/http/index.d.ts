@@ -0,0 +1,252 @@
+/**
+ * # Macroforge HTTP Module
+ *
+ * This module provides the runtime for the `HttpClient` macro, which turns
+ * a service interface into a fetch-based client and a server dispatcher.
+ *
+ * - **Envelopes**: both sides speak the same JSON envelopes. A success is
+ *   `{ data }` (or an empty `204` for `void`), a failure is
+ *   `{ error: { code, message, details? } }`. The client turns a failure
+ *   back into the `HttpError` that the server threw.
+ *
+ * - **Framework-agnostic**: a dispatcher is a plain
+ *   `(request: HttpRequest) => Promise<HttpResponse>` function. Wrap it
+ *   with `fetchHandler()` for runtimes built on the Fetch API (Bun, Deno,
+ *   Cloudflare Workers, SvelteKit, Hono, ...) or adapt it to Express-style
+ *   handlers in a few lines.
+ *
+ * - **Validated**: arguments and results are checked with the `expect*`
+ *   helpers and the `Deserialize` derive. Invalid input becomes a `400`
+ *   with one detail per offending field.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(HttpClient)
+ * const users = new UserServiceClient({ baseUrl: "https://api.example.com" });
+ * const user = await users.getUser("42");
+ *
+ * // Server side
+ * import { fetchHandler } from "macroforge/http";
+ * export default { fetch: fetchHandler(userServiceDispatcher(new UserServiceImpl())) };
+ * ```
+ *
+ * @module macroforge/http
+ */
+
+/**
+ * One invalid field, as reported by `Deserialize`.
+ */
+export interface HttpErrorDetail {
+  field: string;
+  message: string;
+}
+
+/**
+ * The body of every failed response.
+ */
+export interface ErrorEnvelope {
+  error: {
+    code: string;
+    message: string;
+    details?: HttpErrorDetail[];
+  };
+}
+
+/**
+ * An error with an HTTP status. Thrown by implementations to choose the
+ * response status, and thrown by clients for failed responses.
+ */
+export declare class HttpError extends Error {
+  readonly status: number;
+  readonly code: string;
+  readonly details: HttpErrorDetail[];
+  /**
+   * Creates a new HTTP error.
+   * @param status - HTTP status code
+   * @param code - Machine-readable code, e.g. `"not_found"`
+   * @param message - Human-readable message
+   * @param details - Invalid fields, for `400` responses
+   */
+  constructor(status: number, code: string, message: string, details?: HttpErrorDetail[]);
+  /**
+   * Returns the error envelope for this error.
+   */
+  toEnvelope(): ErrorEnvelope;
+}
+
+/**
+ * Options of a generated client.
+ */
+export interface HttpClientOptions {
+  /** Prepended to every route path, e.g. `"https://api.example.com/v1"`. */
+  baseUrl: string;
+  /** Extra request headers, e.g. for authentication. */
+  headers?: Record<string, string> | (() => Record<string, string> | Promise<Record<string, string>>);
+  /** The fetch implementation; defaults to the global `fetch`. */
+  fetch?: typeof fetch;
+}
+
+/**
+ * A request as seen by a dispatcher.
+ */
+export interface HttpRequest {
+  method: string;
+  /** The request path; a query string is allowed and merged into `query`. */
+  path: string;
+  query?: URLSearchParams | Record<string, string | undefined>;
+  /** The parsed JSON body. */
+  body?: unknown;
+}
+
+/**
+ * A response produced by a dispatcher. `body` is `undefined` for `204`.
+ */
+export interface HttpResponse {
+  status: number;
+  body?: { data: unknown } | ErrorEnvelope;
+}
+
+/**
+ * A route of a dispatcher. Called by the code generated for
+ * `@derive(HttpClient)`.
+ */
+export interface Route {
+  method: string;
+  path: string;
+  /**
+   * Handles a matched request. Returns the encoded result, or `undefined`
+   * for `void` methods.
+   */
+  handle(params: Record<string, string>, query: URLSearchParams, body: unknown): Promise<unknown>;
+}
+
+/**
+ * Fills the `:name` segments of a route path.
+ *
+ * @param pattern - The route path, e.g. `"/users/:id"`
+ * @param params - Values of the path parameters
+ * @returns The path, e.g. `"/users/42"`
+ */
+export declare function buildPath(pattern: string, params: Record<string, string | number | boolean>): string;
+
+/**
+ * Matches a path against a route path.
+ *
+ * @param pattern - The route path, e.g. `"/users/:id"`
+ * @param path - The request path without query string
+ * @returns The decoded path parameters, or `null` if the path doesn't match
+ */
+export declare function matchPath(pattern: string, path: string): Record<string, string> | null;
+
+/**
+ * Sends a request from a generated client and unwraps the envelope.
+ *
+ * @param options - The client options
+ * @param init - Method, path, query parameters and JSON body
+ * @param decode - Checks and converts `data`; omitted for `void` methods
+ * @returns The decoded `data`
+ * @throws HttpError for failed responses, and with status `502` for
+ *   responses that are not a valid envelope
+ */
+export declare function request<T>(
+  options: HttpClientOptions,
+  init: {
+    method: string;
+    path: string;
+    query?: Record<string, string | number | boolean | undefined>;
+    body?: Record<string, unknown>;
+  },
+  decode?: (data: unknown) => T,
+): Promise<T>;
+
+/**
+ * Creates a dispatcher from routes. Called by the code generated for
+ * `@derive(HttpClient)`.
+ *
+ * Unknown paths get a `404`, known paths with another method a `405`.
+ * `HttpError`s thrown by handlers keep their status; any other error
+ * becomes a `500` without its message, so internals don't leak.
+ *
+ * @param routes - The routes, tried in order
+ * @returns The dispatcher
+ */
+export declare function createDispatcher(routes: readonly Route[]): (request: HttpRequest) => Promise<HttpResponse>;
+
+/**
+ * Adapts a dispatcher to the Fetch API.
+ *
+ * @param dispatch - A generated dispatcher
+ * @returns A `(request: Request) => Promise<Response>` handler
+ */
+export declare function fetchHandler(
+  dispatch: (request: HttpRequest) => Promise<HttpResponse>,
+): (request: Request) => Promise<Response>;
+
+/**
+ * Checks the type of a JSON value.
+ *
+ * @param value - The value
+ * @param type - The expected `typeof`
+ * @param field - Field name for the error
+ * @returns The value
+ * @throws HttpError with status `400` if the type doesn't match
+ */
+export declare function expect<T>(value: unknown, type: "string" | "number" | "boolean", field: string): T;
+
+/**
+ * Parses a path or query parameter.
+ *
+ * @param value - The raw parameter
+ * @param type - The parameter's type
+ * @param field - Field name for the error
+ * @returns The parsed value
+ * @throws HttpError with status `400` if the parameter is missing or malformed
+ */
+export declare function fromString<T>(
+  value: string | null | undefined,
+  type: "string" | "number" | "boolean",
+  field: string,
+): T;
+
+/**
+ * Parses an ISO date string.
+ *
+ * @param value - The JSON value
+ * @param field - Field name for the error
+ * @returns The date
+ * @throws HttpError with status `400` if the value is not a valid date string
+ */
+export declare function expectDate(value: unknown, field: string): Date;
+
+/**
+ * Checks that a JSON value is an array.
+ *
+ * @param value - The JSON value
+ * @param field - Field name for the error
+ * @returns The array
+ * @throws HttpError with status `400` otherwise
+ */
+export declare function expectArray(value: unknown, field: string): unknown[];
+
+/**
+ * Checks that a request body is a JSON object.
+ *
+ * @param body - The parsed body
+ * @returns The body
+ * @throws HttpError with status `400` otherwise
+ */
+export declare function expectBody(body: unknown): Record<string, unknown>;
+
+/**
+ * Unwraps the result of a generated `Deserialize` function.
+ *
+ * @param result - The result
+ * @param field - Prefix for the reported fields
+ * @returns The deserialized value
+ * @throws HttpError with status `400` listing the invalid fields
+ */
+export declare function unwrap<T>(
+  result: { success: true; value: T } | { success: false; errors: HttpErrorDetail[] },
+  field: string,
+): T;
@@ -0,0 +1,220 @@
+// js/http/index.ts
+class HttpError extends Error {
+  status;
+  code;
+  details;
+  constructor(status, code, message, details = []) {
+    super(message);
+    this.status = status;
+    this.code = code;
+    this.details = details;
+    this.name = "HttpError";
+  }
+  toEnvelope() {
+    const error = { code: this.code, message: this.message };
+    if (this.details.length > 0) {
+      error.details = this.details;
+    }
+    return { error };
+  }
+}
+function buildPath(pattern, params) {
+  return pattern.replace(/:([A-Za-z_$][\w$]*)/g, (_, name) => encodeURIComponent(String(params[name])));
+}
+function matchPath(pattern, path) {
+  const expected = pattern.split("/").filter(Boolean);
+  const actual = path.split("/").filter(Boolean);
+  if (expected.length !== actual.length) {
+    return null;
+  }
+  const params = {};
+  for (let i = 0;i < expected.length; i++) {
+    if (expected[i].startsWith(":")) {
+      params[expected[i].slice(1)] = decodeURIComponent(actual[i]);
+    } else if (expected[i] !== actual[i]) {
+      return null;
+    }
+  }
+  return params;
+}
+function toSearchParams(query) {
+  if (query instanceof URLSearchParams) {
+    return query;
+  }
+  const params = new URLSearchParams;
+  for (const [key, value] of Object.entries(query ?? {})) {
+    if (value !== undefined) {
+      params.set(key, value);
+    }
+  }
+  return params;
+}
+async function request(options, init, decode) {
+  const query = new URLSearchParams;
+  for (const [key, value] of Object.entries(init.query ?? {})) {
+    if (value !== undefined) {
+      query.set(key, String(value));
+    }
+  }
+  const search = query.toString();
+  const url = options.baseUrl.replace(/\/+$/, "") + init.path + (search ? `?${search}` : "");
+  const headers = typeof options.headers === "function" ? await options.headers() : options.headers;
+  const response = await (options.fetch ?? fetch)(url, {
+    method: init.method,
+    headers: {
+      accept: "application/json",
+      ...init.body !== undefined ? { "content-type": "application/json" } : {},
+      ...headers
+    },
+    body: init.body !== undefined ? JSON.stringify(init.body) : undefined
+  });
+  const text = await response.text();
+  let envelope;
+  try {
+    envelope = text ? JSON.parse(text) : undefined;
+  } catch {
+    throw new HttpError(502, "invalid_response", `${init.method} ${init.path}: response is not JSON`);
+  }
+  if (!response.ok) {
+    const error = envelope?.error;
+    throw new HttpError(response.status, error?.code ?? "http_error", error?.message ?? response.statusText, error?.details ?? []);
+  }
+  if (!decode) {
+    return;
+  }
+  if (envelope === null || typeof envelope !== "object" || !("data" in envelope)) {
+    throw new HttpError(502, "invalid_response", `${init.method} ${init.path}: response has no data`);
+  }
+  try {
+    return decode(envelope.data);
+  } catch (e) {
+    if (e instanceof HttpError) {
+      throw new HttpError(502, "invalid_response", e.message, e.details);
+    }
+    throw e;
+  }
+}
+function createDispatcher(routes) {
+  return async (req) => {
+    const [path, search = ""] = req.path.split("?", 2);
+    const query = toSearchParams(req.query);
+    for (const [key, value] of new URLSearchParams(search)) {
+      query.append(key, value);
+    }
+    const method = req.method.toUpperCase();
+    let pathMatched = false;
+    try {
+      for (const route of routes) {
+        const params = matchPath(route.path, path);
+        if (!params) {
+          continue;
+        }
+        pathMatched = true;
+        if (route.method !== method) {
+          continue;
+        }
+        const data = await route.handle(params, query, req.body);
+        return data === undefined ? { status: 204 } : { status: 200, body: { data } };
+      }
+      throw pathMatched ? new HttpError(405, "method_not_allowed", `${method} is not allowed for ${path}`) : new HttpError(404, "not_found", `no route for ${method} ${path}`);
+    } catch (e) {
+      const error = e instanceof HttpError ? e : new HttpError(500, "internal", "internal server error");
+      return { status: error.status, body: error.toEnvelope() };
+    }
+  };
+}
+function fetchHandler(dispatch) {
+  return async (req) => {
+    const url = new URL(req.url);
+    let body;
+    const text = await req.text();
+    if (text) {
+      try {
+        body = JSON.parse(text);
+      } catch {
+        const error = new HttpError(400, "bad_request", "request body is not JSON");
+        return Response.json(error.toEnvelope(), { status: 400 });
+      }
+    }
+    const response = await dispatch({
+      method: req.method,
+      path: url.pathname,
+      query: url.searchParams,
+      body
+    });
+    return response.body === undefined ? new Response(null, { status: response.status }) : Response.json(response.body, { status: response.status });
+  };
+}
+function invalid(field, message) {
+  return new HttpError(400, "bad_request", `${field}: ${message}`, [{ field, message }]);
+}
+function expect(value, type, field) {
+  if (typeof value !== type) {
+    throw invalid(field, `expected a ${type}`);
+  }
+  return value;
+}
+function fromString(value, type, field) {
+  if (value === null || value === undefined) {
+    throw invalid(field, "missing parameter");
+  }
+  switch (type) {
+    case "number": {
+      const n = Number(value);
+      if (value.trim() === "" || Number.isNaN(n)) {
+        throw invalid(field, "expected a number");
+      }
+      return n;
+    }
+    case "boolean":
+      if (value !== "true" && value !== "false") {
+        throw invalid(field, "expected true or false");
+      }
+      return value === "true";
+    default:
+      return value;
+  }
+}
+function expectDate(value, field) {
+  const date = typeof value === "string" ? new Date(value) : undefined;
+  if (!date || Number.isNaN(date.getTime())) {
+    throw invalid(field, "expected an ISO date string");
+  }
+  return date;
+}
+function expectArray(value, field) {
+  if (!Array.isArray(value)) {
+    throw invalid(field, "expected an array");
+  }
+  return value;
+}
+function expectBody(body) {
+  if (body === null || typeof body !== "object" || Array.isArray(body)) {
+    throw new HttpError(400, "bad_request", "request body must be a JSON object");
+  }
+  return body;
+}
+function unwrap(result, field) {
+  if (result.success) {
+    return result.value;
+  }
+  const details = result.errors.map((error) => ({
+    field: error.field === "_root" ? field : `${field}.${error.field}`,
+    message: error.message
+  }));
+  throw new HttpError(400, "bad_request", `${field}: invalid value`, details);
+}
+export {
+  unwrap,
+  request,
+  matchPath,
+  fromString,
+  fetchHandler,
+  expectDate,
+  expectBody,
+  expectArray,
+  expect,
+  createDispatcher,
+  buildPath,
+  HttpError
+};
@@ -0,0 +1,455 @@
+/**
+ * # Macroforge HTTP Module
+ *
+ * This module provides the runtime for the `HttpClient` macro, which turns
+ * a service interface into a fetch-based client and a server dispatcher.
+ *
+ * - **Envelopes**: both sides speak the same JSON envelopes. A success is
+ *   `{ data }` (or an empty `204` for `void`), a failure is
+ *   `{ error: { code, message, details? } }`. The client turns a failure
+ *   back into the `HttpError` that the server threw.
+ *
+ * - **Framework-agnostic**: a dispatcher is a plain
+ *   `(request: HttpRequest) => Promise<HttpResponse>` function. Wrap it
+ *   with `fetchHandler()` for runtimes built on the Fetch API (Bun, Deno,
+ *   Cloudflare Workers, SvelteKit, Hono, ...) or adapt it to Express-style
+ *   handlers in a few lines.
+ *
+ * - **Validated**: arguments and results are checked with the `expect*`
+ *   helpers and the `Deserialize` derive. Invalid input becomes a `400`
+ *   with one detail per offending field.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(HttpClient)
+ * const users = new UserServiceClient({ baseUrl: "https://api.example.com" });
+ * const user = await users.getUser("42");
+ *
+ * // Server side
+ * import { fetchHandler } from "macroforge/http";
+ * export default { fetch: fetchHandler(userServiceDispatcher(new UserServiceImpl())) };
+ * ```
+ *
+ * @module macroforge/http
+ */
+
+/**
+ * One invalid field, as reported by `Deserialize`.
+ */
+export interface HttpErrorDetail {
+  field: string;
+  message: string;
+}
+
+/**
+ * The body of every failed response.
+ */
+export interface ErrorEnvelope {
+  error: {
+    code: string;
+    message: string;
+    details?: HttpErrorDetail[];
+  };
+}
+
+/**
+ * An error with an HTTP status. Thrown by implementations to choose the
+ * response status, and thrown by clients for failed responses.
+ */
+export class HttpError extends Error {
+  /**
+   * Creates a new HTTP error.
+   * @param status - HTTP status code
+   * @param code - Machine-readable code, e.g. `"not_found"`
+   * @param message - Human-readable message
+   * @param details - Invalid fields, for `400` responses
+   */
+  constructor(
+    public readonly status: number,
+    public readonly code: string,
+    message: string,
+    public readonly details: HttpErrorDetail[] = [],
+  ) {
+    super(message);
+    this.name = "HttpError";
+  }
+
+  /**
+   * Returns the error envelope for this error.
+   */
+  toEnvelope(): ErrorEnvelope {
+    const error: ErrorEnvelope["error"] = { code: this.code, message: this.message };
+    if (this.details.length > 0) {
+      error.details = this.details;
+    }
+    return { error };
+  }
+}
+
+/**
+ * Options of a generated client.
+ */
+export interface HttpClientOptions {
+  /** Prepended to every route path, e.g. `"https://api.example.com/v1"`. */
+  baseUrl: string;
+  /** Extra request headers, e.g. for authentication. */
+  headers?: Record<string, string> | (() => Record<string, string> | Promise<Record<string, string>>);
+  /** The fetch implementation; defaults to the global `fetch`. */
+  fetch?: typeof fetch;
+}
+
+/**
+ * A request as seen by a dispatcher.
+ */
+export interface HttpRequest {
+  method: string;
+  /** The request path; a query string is allowed and merged into `query`. */
+  path: string;
+  query?: URLSearchParams | Record<string, string | undefined>;
+  /** The parsed JSON body. */
+  body?: unknown;
+}
+
+/**
+ * A response produced by a dispatcher. `body` is `undefined` for `204`.
+ */
+export interface HttpResponse {
+  status: number;
+  body?: { data: unknown } | ErrorEnvelope;
+}
+
+/**
+ * A route of a dispatcher. Called by the code generated for
+ * `@derive(HttpClient)`.
+ */
+export interface Route {
+  method: string;
+  path: string;
+  /**
+   * Handles a matched request. Returns the encoded result, or `undefined`
+   * for `void` methods.
+   */
+  handle(params: Record<string, string>, query: URLSearchParams, body: unknown): Promise<unknown>;
+}
+
+/**
+ * Fills the `:name` segments of a route path.
+ *
+ * @param pattern - The route path, e.g. `"/users/:id"`
+ * @param params - Values of the path parameters
+ * @returns The path, e.g. `"/users/42"`
+ */
+export function buildPath(pattern: string, params: Record<string, string | number | boolean>): string {
+  return pattern.replace(/:([A-Za-z_$][\w$]*)/g, (_, name: string) =>
+    encodeURIComponent(String(params[name])),
+  );
+}
+
+/**
+ * Matches a path against a route path.
+ *
+ * @param pattern - The route path, e.g. `"/users/:id"`
+ * @param path - The request path without query string
+ * @returns The decoded path parameters, or `null` if the path doesn't match
+ */
+export function matchPath(pattern: string, path: string): Record<string, string> | null {
+  const expected = pattern.split("/").filter(Boolean);
+  const actual = path.split("/").filter(Boolean);
+  if (expected.length !== actual.length) {
+    return null;
+  }
+  const params: Record<string, string> = {};
+  for (let i = 0; i < expected.length; i++) {
+    if (expected[i].startsWith(":")) {
+      params[expected[i].slice(1)] = decodeURIComponent(actual[i]);
+    } else if (expected[i] !== actual[i]) {
+      return null;
+    }
+  }
+  return params;
+}
+
+function toSearchParams(query: HttpRequest["query"]): URLSearchParams {
+  if (query instanceof URLSearchParams) {
+    return query;
+  }
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(query ?? {})) {
+    if (value !== undefined) {
+      params.set(key, value);
+    }
+  }
+  return params;
+}
+
+/**
+ * Sends a request from a generated client and unwraps the envelope.
+ *
+ * @param options - The client options
+ * @param init - Method, path, query parameters and JSON body
+ * @param decode - Checks and converts `data`; omitted for `void` methods
+ * @returns The decoded `data`
+ * @throws HttpError for failed responses, and with status `502` for
+ *   responses that are not a valid envelope
+ */
+export async function request<T>(
+  options: HttpClientOptions,
+  init: {
+    method: string;
+    path: string;
+    query?: Record<string, string | number | boolean | undefined>;
+    body?: Record<string, unknown>;
+  },
+  decode?: (data: unknown) => T,
+): Promise<T> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(init.query ?? {})) {
+    if (value !== undefined) {
+      query.set(key, String(value));
+    }
+  }
+  const search = query.toString();
+  const url = options.baseUrl.replace(/\/+$/, "") + init.path + (search ? `?${search}` : "");
+  const headers = typeof options.headers === "function" ? await options.headers() : options.headers;
+  const response = await (options.fetch ?? fetch)(url, {
+    method: init.method,
+    headers: {
+      accept: "application/json",
+      ...(init.body !== undefined ? { "content-type": "application/json" } : {}),
+      ...headers,
+    },
+    body: init.body !== undefined ? JSON.stringify(init.body) : undefined,
+  });
+
+  const text = await response.text();
+  let envelope: unknown;
+  try {
+    envelope = text ? JSON.parse(text) : undefined;
+  } catch {
+    throw new HttpError(502, "invalid_response", `${init.method} ${init.path}: response is not JSON`);
+  }
+  if (!response.ok) {
+    const error = (envelope as Partial<ErrorEnvelope> | undefined)?.error;
+    throw new HttpError(
+      response.status,
+      error?.code ?? "http_error",
+      error?.message ?? response.statusText,
+      error?.details ?? [],
+    );
+  }
+  if (!decode) {
+    return undefined as T;
+  }
+  if (envelope === null || typeof envelope !== "object" || !("data" in envelope)) {
+    throw new HttpError(502, "invalid_response", `${init.method} ${init.path}: response has no data`);
+  }
+  try {
+    return decode(envelope.data);
+  } catch (e) {
+    if (e instanceof HttpError) {
+      throw new HttpError(502, "invalid_response", e.message, e.details);
+    }
+    throw e;
+  }
+}
+
+/**
+ * Creates a dispatcher from routes. Called by the code generated for
+ * `@derive(HttpClient)`.
+ *
+ * Unknown paths get a `404`, known paths with another method a `405`.
+ * `HttpError`s thrown by handlers keep their status; any other error
+ * becomes a `500` without its message, so internals don't leak.
+ *
+ * @param routes - The routes, tried in order
+ * @returns The dispatcher
+ */
+export function createDispatcher(routes: readonly Route[]): (request: HttpRequest) => Promise<HttpResponse> {
+  return async (req) => {
+    const [path, search = ""] = req.path.split("?", 2);
+    const query = toSearchParams(req.query);
+    for (const [key, value] of new URLSearchParams(search)) {
+      query.append(key, value);
+    }
+    const method = req.method.toUpperCase();
+
+    let pathMatched = false;
+    try {
+      for (const route of routes) {
+        const params = matchPath(route.path, path);
+        if (!params) {
+          continue;
+        }
+        pathMatched = true;
+        if (route.method !== method) {
+          continue;
+        }
+        const data = await route.handle(params, query, req.body);
+        return data === undefined ? { status: 204 } : { status: 200, body: { data } };
+      }
+      throw pathMatched
+        ? new HttpError(405, "method_not_allowed", `${method} is not allowed for ${path}`)
+        : new HttpError(404, "not_found", `no route for ${method} ${path}`);
+    } catch (e) {
+      const error =
+        e instanceof HttpError ? e : new HttpError(500, "internal", "internal server error");
+      return { status: error.status, body: error.toEnvelope() };
+    }
+  };
+}
+
+/**
+ * Adapts a dispatcher to the Fetch API.
+ *
+ * @param dispatch - A generated dispatcher
+ * @returns A `(request: Request) => Promise<Response>` handler
+ */
+export function fetchHandler(
+  dispatch: (request: HttpRequest) => Promise<HttpResponse>,
+): (request: Request) => Promise<Response> {
+  return async (req) => {
+    const url = new URL(req.url);
+    let body: unknown;
+    const text = await req.text();
+    if (text) {
+      try {
+        body = JSON.parse(text);
+      } catch {
+        const error = new HttpError(400, "bad_request", "request body is not JSON");
+        return Response.json(error.toEnvelope(), { status: 400 });
+      }
+    }
+    const response = await dispatch({
+      method: req.method,
+      path: url.pathname,
+      query: url.searchParams,
+      body,
+    });
+    return response.body === undefined
+      ? new Response(null, { status: response.status })
+      : Response.json(response.body, { status: response.status });
+  };
+}
+
+function invalid(field: string, message: string): HttpError {
+  return new HttpError(400, "bad_request", `${field}: ${message}`, [{ field, message }]);
+}
+
+/**
+ * Checks the type of a JSON value.
+ *
+ * @param value - The value
+ * @param type - The expected `typeof`
+ * @param field - Field name for the error
+ * @returns The value
+ * @throws HttpError with status `400` if the type doesn't match
+ */
+export function expect<T>(value: unknown, type: "string" | "number" | "boolean", field: string): T {
+  if (typeof value !== type) {
+    throw invalid(field, `expected a ${type}`);
+  }
+  return value as T;
+}
+
+/**
+ * Parses a path or query parameter.
+ *
+ * @param value - The raw parameter
+ * @param type - The parameter's type
+ * @param field - Field name for the error
+ * @returns The parsed value
+ * @throws HttpError with status `400` if the parameter is missing or malformed
+ */
+export function fromString<T>(
+  value: string | null | undefined,
+  type: "string" | "number" | "boolean",
+  field: string,
+): T {
+  if (value === null || value === undefined) {
+    throw invalid(field, "missing parameter");
+  }
+  switch (type) {
+    case "number": {
+      const n = Number(value);
+      if (value.trim() === "" || Number.isNaN(n)) {
+        throw invalid(field, "expected a number");
+      }
+      return n as T;
+    }
+    case "boolean":
+      if (value !== "true" && value !== "false") {
+        throw invalid(field, "expected true or false");
+      }
+      return (value === "true") as T;
+    default:
+      return value as T;
+  }
+}
+
+/**
+ * Parses an ISO date string.
+ *
+ * @param value - The JSON value
+ * @param field - Field name for the error
+ * @returns The date
+ * @throws HttpError with status `400` if the value is not a valid date string
+ */
+export function expectDate(value: unknown, field: string): Date {
+  const date = typeof value === "string" ? new Date(value) : undefined;
+  if (!date || Number.isNaN(date.getTime())) {
+    throw invalid(field, "expected an ISO date string");
+  }
+  return date;
+}
+
+/**
+ * Checks that a JSON value is an array.
+ *
+ * @param value - The JSON value
+ * @param field - Field name for the error
+ * @returns The array
+ * @throws HttpError with status `400` otherwise
+ */
+export function expectArray(value: unknown, field: string): unknown[] {
+  if (!Array.isArray(value)) {
+    throw invalid(field, "expected an array");
+  }
+  return value;
+}
+
+/**
+ * Checks that a request body is a JSON object.
+ *
+ * @param body - The parsed body
+ * @returns The body
+ * @throws HttpError with status `400` otherwise
+ */
+export function expectBody(body: unknown): Record<string, unknown> {
+  if (body === null || typeof body !== "object" || Array.isArray(body)) {
+    throw new HttpError(400, "bad_request", "request body must be a JSON object");
+  }
+  return body as Record<string, unknown>;
+}
+
+/**
+ * Unwraps the result of a generated `Deserialize` function.
+ *
+ * @param result - The result
+ * @param field - Prefix for the reported fields
+ * @returns The deserialized value
+ * @throws HttpError with status `400` listing the invalid fields
+ */
+export function unwrap<T>(
+  result: { success: true; value: T } | { success: false; errors: HttpErrorDetail[] },
+  field: string,
+): T {
+  if (result.success) {
+    return result.value;
+  }
+  const details = result.errors.map((error) => ({
+    field: error.field === "_root" ? field : `${field}.${error.field}`,
+    message: error.message,
+  }));
+  throw new HttpError(400, "bad_request", `${field}: invalid value`, details);
+}
@@ -0,0 +1,111 @@
+/**
+ * # Macroforge Mock Module
+ *
+ * This module provides the runtime for the `Mock` macro, which generates a
+ * test double class for an interface.
+ *
+ * - **Recording**: every call of a `MockFunction` is recorded with its
+ *   arguments, typed from the mocked method's parameters.
+ *
+ * - **Stubbing**: return values, resolved values, rejections and whole
+ *   implementations are set per method, either for every call or for the
+ *   next call only.
+ *
+ * - **Strict**: a method that returns a value throws a `MockError` when it is
+ *   called without a stub, so a forgotten stub fails loudly instead of
+ *   leaking `undefined`. Methods returning `void` or `Promise<void>` need no
+ *   stub.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Mock)
+ * const users = new UserServiceMock();
+ * users.getUser.resolves({ id: "1", name: "Ada" });
+ * users.save.rejectsOnce(new Error("conflict"));
+ *
+ * await handler(users);
+ * expect(users.getUser.calls()).toEqual([["1"]]);
+ * users.mockReset();
+ * ```
+ *
+ * @module macroforge/mock
+ */
+
+/**
+ * Any function type.
+ */
+export type AnyFunction = (...args: never[]) => unknown;
+
+/**
+ * What an unstubbed call does.
+ *
+ * - `"throw"` - throws a `MockError`
+ * - `"undefined"` - returns `undefined`, for methods returning `void`
+ * - `"resolve"` - returns a promise of `undefined`, for `Promise<void>`
+ */
+export type MockFallback = "throw" | "undefined" | "resolve";
+
+/**
+ * A recording, stubbable function with the signature of `F`.
+ *
+ * Stubbing methods return the mock, so they can be chained.
+ *
+ * @template F - The mocked function type
+ */
+export interface MockFunction<F extends AnyFunction> {
+  (...args: Parameters<F>): ReturnType<F>;
+
+  /** Arguments of every call so far, oldest first. */
+  calls(): readonly Parameters<F>[];
+  /** Number of calls so far. */
+  callCount(): number;
+  /** Arguments of the most recent call. */
+  lastCall(): Parameters<F> | undefined;
+
+  /** Returns `value` from every call. */
+  returns(value: ReturnType<F>): this;
+  /** Returns `value` from the next call only. */
+  returnsOnce(value: ReturnType<F>): this;
+  /** Returns a promise resolving to `value` from every call. */
+  resolves(value: Awaited<ReturnType<F>>): this;
+  /** Returns a promise resolving to `value` from the next call only. */
+  resolvesOnce(value: Awaited<ReturnType<F>>): this;
+  /** Returns a promise rejecting with `error` from every call. */
+  rejects(error: unknown): this;
+  /** Returns a promise rejecting with `error` from the next call only. */
+  rejectsOnce(error: unknown): this;
+  /** Throws `error` from every call. */
+  throws(error: unknown): this;
+  /** Calls `implementation` for every call. */
+  implementation(implementation: F): this;
+  /** Calls `implementation` for the next call only. */
+  implementationOnce(implementation: F): this;
+
+  /** Forgets recorded calls and all stubs. */
+  reset(): void;
+}
+
+/**
+ * Error thrown by a strict mock that was called without a stub.
+ */
+export declare class MockError extends Error {
+  /**
+   * Creates a new mock error.
+   * @param message - What went wrong
+   */
+  constructor(message: string);
+}
+
+/**
+ * Creates a mock function. Called by the code generated for
+ * `@derive(Mock)`, but usable on its own.
+ *
+ * Once-stubs are used in the order they were added, before the
+ * every-call stub.
+ *
+ * @param name - Name used in error messages, e.g. `"UserService.getUser"`
+ * @param fallback - What an unstubbed call does
+ * @returns The mock function
+ */
+export declare function mockFunction<F extends AnyFunction>(name: string, fallback?: MockFallback): MockFunction<F>;
@@ -0,0 +1,61 @@
+// js/mock/index.ts
+class MockError extends Error {
+  constructor(message) {
+    super(message);
+    this.name = "MockError";
+  }
+}
+function mockFunction(name, fallback = "throw") {
+  let calls = [];
+  let queue = [];
+  let stub;
+  const mock = function(...args) {
+    calls.push(args);
+    const implementation = queue.shift() ?? stub;
+    if (implementation) {
+      return implementation.apply(this, args);
+    }
+    switch (fallback) {
+      case "undefined":
+        return;
+      case "resolve":
+        return Promise.resolve(undefined);
+      default:
+        throw new MockError(`${name} was called without a stub`);
+    }
+  };
+  const always = (implementation) => {
+    stub = implementation;
+    return mock;
+  };
+  const once = (implementation) => {
+    queue.push(implementation);
+    return mock;
+  };
+  const returning = (value) => () => value;
+  const rejecting = (error) => () => Promise.reject(error);
+  mock.calls = () => calls;
+  mock.callCount = () => calls.length;
+  mock.lastCall = () => calls[calls.length - 1];
+  mock.returns = (value) => always(returning(value));
+  mock.returnsOnce = (value) => once(returning(value));
+  mock.resolves = (value) => always(() => Promise.resolve(value));
+  mock.resolvesOnce = (value) => once(() => Promise.resolve(value));
+  mock.rejects = (error) => always(rejecting(error));
+  mock.rejectsOnce = (error) => once(rejecting(error));
+  mock.throws = (error) => always(() => {
+    throw error;
+  });
+  mock.implementation = always;
+  mock.implementationOnce = once;
+  mock.reset = () => {
+    calls = [];
+    queue = [];
+    stub = undefined;
+  };
+  return mock;
+}
+export {
+  mockFunction,
+  MockError
+};
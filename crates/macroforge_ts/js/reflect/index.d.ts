@@ -0,0 +1,162 @@
+/**
+ * # Macroforge Reflect Module
+ *
+ * This module provides the metadata types and runtime helpers for the
+ * `Reflect` macro, which records the shape of a type at expansion time:
+ * its fields, methods, decorators and JSDoc. Generic tooling such as admin
+ * UIs, table renderers and ORM mappers can then read field lists at runtime
+ * without `reflect-metadata` or `emitDecoratorMetadata`.
+ *
+ * - **Frozen**: `defineReflection()` deep-freezes the metadata, so one
+ *   consumer cannot change what another sees.
+ *
+ * - **Tree-shakable**: generated metadata is a `/*#__PURE__*\/` call, so
+ *   bundlers drop it when nothing reads it.
+ *
+ * - **Typed**: `TypeReflection<T>` knows the field names of `T`, so
+ *   `fieldOf(reflection, "email")` is checked by the compiler.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(Reflect)
+ * const reflection = userReflect();
+ * for (const field of reflection.fields) {
+ *   console.log(field.name, field.type, field.optional);
+ * }
+ *
+ * import { fieldOf } from "macroforge/reflect";
+ * fieldOf(reflection, "email")?.doc;
+ * ```
+ *
+ * @module macroforge/reflect
+ */
+
+/**
+ * A decorator or JSDoc directive, e.g. `@serde({ rename: "id" })`.
+ */
+export interface DecoratorReflection {
+  /** Name without the `@`, e.g. `"serde"`. */
+  readonly name: string;
+  /** Source text of the arguments, e.g. `'{ rename: "id" }'`; empty without arguments. */
+  readonly args: string;
+}
+
+/**
+ * Member visibility; interface members are always `"public"`.
+ */
+export type Visibility = "public" | "protected" | "private";
+
+/**
+ * A field of a class, interface or object type alias.
+ *
+ * @template Name - The field name
+ */
+export interface FieldReflection<Name extends string = string> {
+  readonly name: Name;
+  /** The declared type as written, e.g. `"string | null"`. */
+  readonly type: string;
+  readonly optional: boolean;
+  readonly readonly: boolean;
+  readonly visibility: Visibility;
+  readonly decorators: readonly DecoratorReflection[];
+  /** The JSDoc description, without `@` directives. */
+  readonly doc?: string;
+}
+
+/**
+ * A method of a class or interface.
+ */
+export interface MethodReflection {
+  readonly name: string;
+  /** Type parameters as written, e.g. `"<T>"`; empty if none. */
+  readonly typeParams: string;
+  /** Parameters as written, e.g. `"id: string, force?: boolean"`. */
+  readonly params: string;
+  /** Return type as written, e.g. `"Promise<void>"`. */
+  readonly returnType: string;
+  readonly static: boolean;
+  readonly async: boolean;
+  readonly optional: boolean;
+  readonly visibility: Visibility;
+  readonly decorators: readonly DecoratorReflection[];
+  readonly doc?: string;
+}
+
+/**
+ * A variant of an enum.
+ */
+export interface VariantReflection {
+  readonly name: string;
+  /** The variant value; computed values are recorded as their source text. */
+  readonly value: string | number;
+  readonly decorators: readonly DecoratorReflection[];
+  readonly doc?: string;
+}
+
+/**
+ * Metadata for one type.
+ *
+ * @template T - The reflected type
+ */
+export interface TypeReflection<T = unknown> {
+  readonly name: string;
+  readonly kind: "class" | "interface" | "enum" | "typeAlias";
+  /** Type parameter names, e.g. `["T"]`. */
+  readonly typeParams: readonly string[];
+  /** `extends` / `implements` clauses as written. */
+  readonly heritage: readonly string[];
+  readonly abstract: boolean;
+  readonly decorators: readonly DecoratorReflection[];
+  readonly fields: readonly FieldReflection<keyof T & string>[];
+  readonly methods: readonly MethodReflection[];
+  /** Enum variants; empty for other kinds. */
+  readonly variants: readonly VariantReflection[];
+  /** The aliased type as written, for non-object type aliases. */
+  readonly type?: string;
+}
+
+/**
+ * Deep-freezes generated metadata. Called by the code generated for
+ * `@derive(Reflect)`.
+ *
+ * @param reflection - The metadata
+ * @returns The same object, frozen
+ */
+export declare function defineReflection<T>(reflection: TypeReflection<T>): TypeReflection<T>;
+
+/**
+ * Looks up a field by name.
+ *
+ * @param reflection - Metadata of the type
+ * @param name - The field name
+ * @returns The field, or `undefined` if the type has no such field
+ */
+export declare function fieldOf<T, K extends keyof T & string>(
+  reflection: TypeReflection<T>,
+  name: K,
+): FieldReflection<K> | undefined;
+
+/**
+ * Looks up a method by name. Overloads share a name; the first is returned.
+ *
+ * @param reflection - Metadata of the type
+ * @param name - The method name
+ * @returns The method, or `undefined` if the type has no such method
+ */
+export declare function methodOf<T>(
+  reflection: TypeReflection<T>,
+  name: string,
+): MethodReflection | undefined;
+
+/**
+ * Looks up a decorator by name, e.g. `decoratorOf(field, "serde")`.
+ *
+ * @param target - A type, field, method or variant
+ * @param name - The decorator name without the `@`
+ * @returns The first matching decorator, or `undefined`
+ */
+export declare function decoratorOf(
+  target: { readonly decorators: readonly DecoratorReflection[] },
+  name: string,
+): DecoratorReflection | undefined;
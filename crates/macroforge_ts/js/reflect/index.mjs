@@ -0,0 +1,28 @@
+// js/reflect/index.ts
+function deepFreeze(value) {
+  if (value !== null && typeof value === "object" && !Object.isFrozen(value)) {
+    Object.freeze(value);
+    for (const child of Object.values(value)) {
+      deepFreeze(child);
+    }
+  }
+  return value;
+}
+function defineReflection(reflection) {
+  return deepFreeze(reflection);
+}
+function fieldOf(reflection, name) {
+  return reflection.fields.find((field) => field.name === name);
+}
+function methodOf(reflection, name) {
+  return reflection.methods.find((method) => method.name === name);
+}
+function decoratorOf(target, name) {
+  return target.decorators.find((decorator) => decorator.name === name);
+}
+export {
+  methodOf,
+  fieldOf,
+  defineReflection,
+  decoratorOf
+};
@@ -0,0 +1,101 @@
+/**
+ * # Macroforge SQL Module
+ *
+ * This module provides the runtime for the `SqlRow` macro, which maps types
+ * to database rows. It never talks to a database: generated code turns the
+ * rows returned by a driver into typed values, and values into parameter
+ * arrays for the generated `INSERT` and `UPDATE` statements.
+ *
+ * - **Coercion**: `sqlDecoders` accept what drivers return for each column
+ *   type, e.g. `BIGINT` and `NUMERIC` as strings, timestamps as strings or
+ *   `Date`s, booleans as `t`/`f`, and JSON as text or parsed values.
+ *
+ * - **Parameters**: `sqlParam` turns `undefined` into `null`, bigints into
+ *   strings, and objects, arrays, maps and sets into JSON.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(SqlRow)
+ * const { rows } = await pool.query(`SELECT ${userColumns.join(", ")} FROM users`);
+ * const users = rows.map(userFromRow);
+ *
+ * await pool.query(userInsertSql, userToParams(user));
+ * ```
+ *
+ * @module macroforge/sql
+ */
+
+/**
+ * A row returned by a database driver, keyed by column name.
+ */
+export type SqlRow = Readonly<Record<string, unknown>>;
+
+/**
+ * Thrown by generated `fromRow` functions when a column is missing or has
+ * an unexpected value.
+ */
+export declare class SqlRowError extends Error {
+  /** The column name. */
+  readonly column: string;
+  /**
+   * Creates a new row error.
+   * @param column - The column that could not be read
+   * @param message - What is wrong with it
+   */
+  constructor(column: string, message: string);
+}
+
+/**
+ * Coerces one column value; returns `undefined` for invalid input.
+ */
+export interface SqlDecoder<T> {
+  parse: (value: unknown) => T | undefined;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+/**
+ * Decoders used by the code generated for `@derive(SqlRow)`.
+ */
+export declare const sqlDecoders: {
+  string: SqlDecoder<string>;
+  number: SqlDecoder<number>;
+  bigint: SqlDecoder<bigint>;
+  boolean: SqlDecoder<boolean>;
+  date: SqlDecoder<Date>;
+  json: SqlDecoder<unknown>;
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): SqlDecoder<T>;
+  /**
+   * A foreign type `deserialize` function; exceptions become the error
+   * message.
+   */
+  with<T>(deserialize: (value: unknown) => T): SqlDecoder<T>;
+};
+
+/**
+ * How a `NULL` column is read: an error, `undefined` or `null`.
+ */
+export type SqlMissing = "required" | "optional" | "nullable";
+
+/**
+ * Reads and coerces one column. Called by the code generated for
+ * `@derive(SqlRow)`.
+ *
+ * @param row - The row
+ * @param column - The column name
+ * @param decoder - How to coerce the value
+ * @param missing - What `NULL` is read as
+ * @throws SqlRowError if the column is missing, unexpectedly `NULL` or invalid
+ */
+export declare function readColumn<T>(row: SqlRow, column: string, decoder: SqlDecoder<T>, missing?: SqlMissing): T | null | undefined;
+
+/**
+ * Converts a value into a query parameter. `undefined` becomes `null`,
+ * bigints strings, maps and sets JSON arrays, and other objects JSON;
+ * dates and binary data are passed through.
+ */
+export declare function sqlParam(value: unknown): unknown;
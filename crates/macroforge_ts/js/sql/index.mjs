@@ -0,0 +1,119 @@
+// js/sql/index.ts
+class SqlRowError extends Error {
+  column;
+  constructor(column, message) {
+    super(`column "${column}" ${message}`);
+    this.name = "SqlRowError";
+    this.column = column;
+  }
+}
+var sqlDecoders = {
+  string: {
+    parse: (value) => typeof value === "string" ? value : undefined,
+    expected: "a string"
+  },
+  number: {
+    parse: (value) => {
+      const n = typeof value === "string" && value.trim() !== "" ? Number(value) : value;
+      return typeof n === "number" && !Number.isNaN(n) ? n : undefined;
+    },
+    expected: "a number"
+  },
+  bigint: {
+    parse: (value) => {
+      if (typeof value === "bigint") {
+        return value;
+      }
+      try {
+        return typeof value === "string" || typeof value === "number" && Number.isInteger(value) ? BigInt(value) : undefined;
+      } catch {
+        return;
+      }
+    },
+    expected: "an integer"
+  },
+  boolean: {
+    parse: (value) => {
+      if (typeof value === "boolean") {
+        return value;
+      }
+      if (value === "t" || value === "true" || value === 1) {
+        return true;
+      }
+      return value === "f" || value === "false" || value === 0 ? false : undefined;
+    },
+    expected: "a boolean"
+  },
+  date: {
+    parse: (value) => {
+      const date = value instanceof Date ? value : typeof value === "string" || typeof value === "number" ? new Date(value) : undefined;
+      return date && !Number.isNaN(date.getTime()) ? date : undefined;
+    },
+    expected: "a timestamp"
+  },
+  json: {
+    parse: (value) => {
+      if (typeof value !== "string") {
+        return value;
+      }
+      try {
+        return JSON.parse(value);
+      } catch {
+        return value;
+      }
+    },
+    expected: "JSON"
+  },
+  oneOf(values) {
+    return {
+      parse: (value) => typeof value === "string" && values.includes(value) ? value : undefined,
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`
+    };
+  },
+  with(deserialize) {
+    return { parse: deserialize, expected: "a valid value" };
+  }
+};
+function readColumn(row, column, decoder, missing = "required") {
+  if (!(column in row)) {
+    throw new SqlRowError(column, "is missing from the row");
+  }
+  const value = row[column];
+  if (value === null || value === undefined) {
+    if (missing === "required") {
+      throw new SqlRowError(column, "is NULL");
+    }
+    return missing === "nullable" ? null : undefined;
+  }
+  let parsed;
+  try {
+    parsed = decoder.parse(value);
+  } catch (e) {
+    throw new SqlRowError(column, e instanceof Error ? e.message : String(e));
+  }
+  if (parsed === undefined) {
+    throw new SqlRowError(column, `expected ${decoder.expected}, got ${typeof value}`);
+  }
+  return parsed;
+}
+function sqlParam(value) {
+  if (value === undefined || value === null) {
+    return null;
+  }
+  if (typeof value === "bigint") {
+    return value.toString();
+  }
+  if (value instanceof Date || value instanceof Uint8Array) {
+    return value;
+  }
+  if (value instanceof Map || value instanceof Set) {
+    return JSON.stringify([...value]);
+  }
+  return typeof value === "object" ? JSON.stringify(value) : value;
+}
+export {
+  sqlParam,
+  sqlDecoders,
+  readColumn,
+  SqlRowError
+};
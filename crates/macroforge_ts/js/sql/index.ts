@@ -0,0 +1,215 @@
+/**
+ * # Macroforge SQL Module
+ *
+ * This module provides the runtime for the `SqlRow` macro, which maps types
+ * to database rows. It never talks to a database: generated code turns the
+ * rows returned by a driver into typed values, and values into parameter
+ * arrays for the generated `INSERT` and `UPDATE` statements.
+ *
+ * - **Coercion**: `sqlDecoders` accept what drivers return for each column
+ *   type, e.g. `BIGINT` and `NUMERIC` as strings, timestamps as strings or
+ *   `Date`s, booleans as `t`/`f`, and JSON as text or parsed values.
+ *
+ * - **Parameters**: `sqlParam` turns `undefined` into `null`, bigints into
+ *   strings, and objects, arrays, maps and sets into JSON.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(SqlRow)
+ * const { rows } = await pool.query(`SELECT ${userColumns.join(", ")} FROM users`);
+ * const users = rows.map(userFromRow);
+ *
+ * await pool.query(userInsertSql, userToParams(user));
+ * ```
+ *
+ * @module macroforge/sql
+ */
+
+/**
+ * A row returned by a database driver, keyed by column name.
+ */
+export type SqlRow = Readonly<Record<string, unknown>>;
+
+/**
+ * Thrown by generated `fromRow` functions when a column is missing or has
+ * an unexpected value.
+ */
+export class SqlRowError extends Error {
+  /** The column name. */
+  readonly column: string;
+
+  /**
+   * Creates a new row error.
+   * @param column - The column that could not be read
+   * @param message - What is wrong with it
+   */
+  constructor(column: string, message: string) {
+    super(`column "${column}" ${message}`);
+    this.name = "SqlRowError";
+    this.column = column;
+  }
+}
+
+/**
+ * Coerces one column value; returns `undefined` for invalid input.
+ */
+export interface SqlDecoder<T> {
+  parse: (value: unknown) => T | undefined;
+  /** What a valid value looks like, e.g. `"a number"`. */
+  expected: string;
+}
+
+/**
+ * Decoders used by the code generated for `@derive(SqlRow)`.
+ */
+export const sqlDecoders = {
+  string: {
+    parse: (value) => (typeof value === "string" ? value : undefined),
+    expected: "a string",
+  } as SqlDecoder<string>,
+  number: {
+    parse: (value) => {
+      const n = typeof value === "string" && value.trim() !== "" ? Number(value) : value;
+      return typeof n === "number" && !Number.isNaN(n) ? n : undefined;
+    },
+    expected: "a number",
+  } as SqlDecoder<number>,
+  bigint: {
+    parse: (value) => {
+      if (typeof value === "bigint") {
+        return value;
+      }
+      try {
+        return typeof value === "string" || (typeof value === "number" && Number.isInteger(value))
+          ? BigInt(value)
+          : undefined;
+      } catch {
+        return undefined;
+      }
+    },
+    expected: "an integer",
+  } as SqlDecoder<bigint>,
+  boolean: {
+    parse: (value) => {
+      if (typeof value === "boolean") {
+        return value;
+      }
+      if (value === "t" || value === "true" || value === 1) {
+        return true;
+      }
+      return value === "f" || value === "false" || value === 0 ? false : undefined;
+    },
+    expected: "a boolean",
+  } as SqlDecoder<boolean>,
+  date: {
+    parse: (value) => {
+      const date =
+        value instanceof Date
+          ? value
+          : typeof value === "string" || typeof value === "number"
+            ? new Date(value)
+            : undefined;
+      return date && !Number.isNaN(date.getTime()) ? date : undefined;
+    },
+    expected: "a timestamp",
+  } as SqlDecoder<Date>,
+  json: {
+    parse: (value) => {
+      if (typeof value !== "string") {
+        return value;
+      }
+      try {
+        return JSON.parse(value) as unknown;
+      } catch {
+        return value;
+      }
+    },
+    expected: "JSON",
+  } as SqlDecoder<unknown>,
+
+  /**
+   * One of the given strings.
+   */
+  oneOf<const T extends string>(values: readonly T[]): SqlDecoder<T> {
+    return {
+      parse: (value) =>
+        typeof value === "string" && (values as readonly string[]).includes(value)
+          ? (value as T)
+          : undefined,
+      expected: `one of ${values.map((v) => JSON.stringify(v)).join(", ")}`,
+    };
+  },
+
+  /**
+   * A foreign type `deserialize` function; exceptions become the error
+   * message.
+   */
+  with<T>(deserialize: (value: unknown) => T): SqlDecoder<T> {
+    return { parse: deserialize, expected: "a valid value" };
+  },
+};
+
+/**
+ * How a `NULL` column is read: an error, `undefined` or `null`.
+ */
+export type SqlMissing = "required" | "optional" | "nullable";
+
+/**
+ * Reads and coerces one column. Called by the code generated for
+ * `@derive(SqlRow)`.
+ *
+ * @param row - The row
+ * @param column - The column name
+ * @param decoder - How to coerce the value
+ * @param missing - What `NULL` is read as
+ * @throws SqlRowError if the column is missing, unexpectedly `NULL` or invalid
+ */
+export function readColumn<T>(
+  row: SqlRow,
+  column: string,
+  decoder: SqlDecoder<T>,
+  missing: SqlMissing = "required",
+): T | null | undefined {
+  if (!(column in row)) {
+    throw new SqlRowError(column, "is missing from the row");
+  }
+  const value = row[column];
+  if (value === null || value === undefined) {
+    if (missing === "required") {
+      throw new SqlRowError(column, "is NULL");
+    }
+    return missing === "nullable" ? null : undefined;
+  }
+  let parsed: T | undefined;
+  try {
+    parsed = decoder.parse(value);
+  } catch (e) {
+    throw new SqlRowError(column, e instanceof Error ? e.message : String(e));
+  }
+  if (parsed === undefined) {
+    throw new SqlRowError(column, `expected ${decoder.expected}, got ${typeof value}`);
+  }
+  return parsed;
+}
+
+/**
+ * Converts a value into a query parameter. `undefined` becomes `null`,
+ * bigints strings, maps and sets JSON arrays, and other objects JSON;
+ * dates and binary data are passed through.
+ */
+export function sqlParam(value: unknown): unknown {
+  if (value === undefined || value === null) {
+    return null;
+  }
+  if (typeof value === "bigint") {
+    return value.toString();
+  }
+  if (value instanceof Date || value instanceof Uint8Array) {
+    return value;
+  }
+  if (value instanceof Map || value instanceof Set) {
+    return JSON.stringify([...value]);
+  }
+  return typeof value === "object" ? JSON.stringify(value) : value;
+}
@@ -65,9 +65,10 @@ export interface Debug<T> {
     /**
      * Returns a debug string representation of the value.
      * @param self - The value to format
+     * @param pretty - Produce indented multi-line output instead of a single line
      * @returns A human-readable string for debugging
      */
-    readonly toString: (self: T) => string;
+    readonly toString: (self: T, pretty?: boolean) => string;
 }
 /**
  * Trait for types with a default value.
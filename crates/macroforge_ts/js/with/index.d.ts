@@ -0,0 +1,128 @@
+/**
+ * # Macroforge With Module
+ *
+ * This module provides runtime helpers for the `With` macro, which generates
+ * immutable copy-on-write updaters.
+ *
+ * - **Path types**: `Path<T>` lists every property path into `T` as a tuple,
+ *   and `PathValue<T, P>` is the type found at that path. Generated `setIn`
+ *   functions use them so that both the path and the new value are checked.
+ *
+ * - **Structural sharing**: `setPath()` copies only the objects along the path
+ *   and reuses everything else. Class instances are copied with
+ *   `Object.create(Object.getPrototypeOf(value))`, the same way the `Clone`
+ *   macro does, so `instanceof` checks keep working.
+ *
+ * ## Usage
+ *
+ * ```typescript
+ * // Generated by @derive(With)
+ * const moved = User.setIn(user, ["address", "city"], "Berlin");
+ *
+ * // Or directly, for any value
+ * import { setIn } from "macroforge/with";
+ * const moved = setIn(user, ["address", "city"], "Berlin");
+ * ```
+ *
+ * @module macroforge/with
+ */
+
+/**
+ * Maximum nesting depth explored by `Path`. Deeper paths are not offered,
+ * which keeps recursive types (e.g. trees) from exploding.
+ */
+type MaxDepth = 5;
+
+/**
+ * Values that are replaced as a whole rather than updated by path.
+ */
+type Leaf =
+  | Date
+  | RegExp
+  | Map<unknown, unknown>
+  | Set<unknown>
+  | ((...args: never[]) => unknown);
+
+/**
+ * Every property path into `T`, as a tuple of keys.
+ *
+ * Arrays are indexed by `number`. Paths stop at primitives, `Date`, `Map`,
+ * `Set`, functions and after {@link MaxDepth} levels.
+ *
+ * @template T - The root type
+ *
+ * @example
+ * ```typescript
+ * type P = Path<{ name: string; address: { city: string } }>;
+ * // ["name"] | ["address"] | ["address", "city"]
+ * ```
+ */
+export type Path<T, Depth extends unknown[] = []> = Depth["length"] extends MaxDepth
+  ? never
+  : T extends Leaf
+    ? never
+    : T extends readonly (infer E)[]
+      ? [number] | [number, ...Path<NonNullable<E>, [...Depth, unknown]>]
+      : T extends object
+        ? {
+            [K in keyof T & string]-?:
+              | [K]
+              | [K, ...Path<NonNullable<T[K]>, [...Depth, unknown]>];
+          }[keyof T & string]
+        : never;
+
+/**
+ * The type of the value found at path `P` in `T`.
+ *
+ * @template T - The root type
+ * @template P - A path produced by {@link Path}
+ */
+export type PathValue<T, P extends readonly unknown[]> = P extends readonly [
+  infer K,
+  ...infer Rest,
+]
+  ? K extends keyof NonNullable<T>
+    ? PathValue<NonNullable<T>[K], Rest>
+    : never
+  : T;
+
+/**
+ * Creates a shallow copy of `value`, preserving its prototype.
+ *
+ * Arrays are sliced, objects (including class instances) are copied with
+ * `Object.create(Object.getPrototypeOf(value))`, and primitives are returned
+ * as-is.
+ */
+export declare function shallowCopy<T>(value: T): T;
+
+/**
+ * Untyped core of {@link setIn}, used by generated code.
+ *
+ * Copies every object along `path` and replaces the value at its end.
+ *
+ * @throws {TypeError} If an intermediate value on the path is `null` or `undefined`
+ */
+export declare function setPath(
+  root: unknown,
+  path: readonly PropertyKey[],
+  value: unknown,
+): unknown;
+
+/**
+ * Returns a copy of `root` with the value at `path` replaced.
+ *
+ * Only the objects along the path are copied; everything else is shared
+ * with the original.
+ *
+ * @template T - The root type
+ * @template P - The path being updated
+ * @param root - The value to update
+ * @param path - Keys leading to the value to replace
+ * @param value - The new value
+ * @returns A new root with the update applied
+ */
+export declare function setIn<T, P extends Path<T>>(
+  root: T,
+  path: P,
+  value: PathValue<T, P>,
+): T;
@@ -0,0 +1,30 @@
+// js/with/index.ts
+function shallowCopy(value) {
+  if (Array.isArray(value)) {
+    return value.slice();
+  }
+  if (value !== null && typeof value === "object") {
+    return Object.assign(Object.create(Object.getPrototypeOf(value)), value);
+  }
+  return value;
+}
+function setPath(root, path, value) {
+  if (path.length === 0) {
+    return value;
+  }
+  if (root === null || root === undefined) {
+    throw new TypeError(`setIn: cannot set "${path.map(String).join(".")}" on ${root}`);
+  }
+  const [key, ...rest] = path;
+  const copy = shallowCopy(root);
+  copy[key] = setPath(root[key], rest, value);
+  return copy;
+}
+function setIn(root, path, value) {
+  return setPath(root, path, value);
+}
+export {
+  shallowCopy,
+  setPath,
+  setIn
+};
@@ -0,0 +1,107 @@
+//! # Deprecated Attribute Implementation
+//!
+//! `@deprecated` logs a `console.warn` the first time the method or function
+//! is called, then runs the original body unchanged. The warning is shared by
+//! all instances of a class. A `/** @deprecated */` JSDoc tag is kept above the
+//! function so editors still flag its uses.
+//!
+//! ## Options
+//!
+//! | Option | Description |
+//! |--------|-------------|
+//! | `message` | Appended to the warning, also accepted as the only argument |
+//!
+//! ## Example
+//!
+//! ```typescript
+//! class Api {
+//!     @deprecated("use fetchUser instead")
+//!     getUser(id: string): User { ... }
+//! }
+//! // Api.getUser is deprecated: use fetchUser instead
+//! ```
+
+use super::{ABOVE_MARKER, BODY_MARKER, display_name};
+use crate::builtin::derive_common::{extract_exact_named_string, parse_string_literal};
+use crate::macros::ts_macro_attribute;
+use crate::ts_syn::{AttributeInput, FunctionIR, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Generates the flag declaration, the JSDoc tag and the replacement body.
+fn generate_deprecated(function: &FunctionIR, message: Option<&str>) -> [String; 3] {
+    let name = format!("__mf_deprecated_{}", function.name);
+    let (declaration, flag) = match &function.class_name {
+        Some(class_name) => (
+            format!("private static {name}: boolean = false;"),
+            format!("{class_name}.{name}"),
+        ),
+        None => (format!("let {name}: boolean = false;"), name),
+    };
+
+    let warning = match message {
+        Some(message) => format!("{} is deprecated: {message}", display_name(function)),
+        None => format!("{} is deprecated", display_name(function)),
+    };
+    let tag = match message {
+        Some(message) => format!("/** @deprecated {} */", message.replace("*/", "*\\/")),
+        None => "/** @deprecated */".to_string(),
+    };
+
+    let body = format!(
+        "if (!{flag}) {{\n\
+         {flag} = true;\n\
+         console.warn({});\n\
+         }}\n\
+         {}",
+        serde_json::to_string(&warning).unwrap(),
+        function.body_src.trim(),
+    );
+    [declaration, tag, body]
+}
+
+#[ts_macro_attribute(
+    deprecated,
+    description = "Warns once when a deprecated method or function is called. Options: message"
+)]
+pub fn deprecated_attribute(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as AttributeInput);
+
+    let args = input.args();
+    let message =
+        parse_string_literal(args).or_else(|| extract_exact_named_string(args, "message"));
+    let [declaration, tag, body] = generate_deprecated(input.function(), message.as_deref());
+    Ok(TsStream::from_string(format!(
+        "{ABOVE_MARKER}{declaration}\n{ABOVE_MARKER}{tag}\n{BODY_MARKER}{body}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::function;
+    use super::*;
+
+    #[test]
+    fn test_generate_deprecated_for_function() {
+        let [declaration, tag, body] =
+            generate_deprecated(&function("fib", None), Some("use fibFast"));
+        assert_eq!(declaration, "let __mf_deprecated_fib: boolean = false;");
+        assert_eq!(tag, "/** @deprecated use fibFast */");
+        assert_eq!(
+            body,
+            "if (!__mf_deprecated_fib) {\n__mf_deprecated_fib = true;\nconsole.warn(\"fib is deprecated: use fibFast\");\n}\nreturn n;"
+        );
+    }
+
+    #[test]
+    fn test_generate_deprecated_warns_once_per_class() {
+        let [declaration, tag, body] = generate_deprecated(&function("load", Some("Api")), None);
+        assert_eq!(
+            declaration,
+            "private static __mf_deprecated_load: boolean = false;"
+        );
+        assert_eq!(tag, "/** @deprecated */");
+        assert!(
+            body.starts_with("if (!Api.__mf_deprecated_load) {\nApi.__mf_deprecated_load = true;")
+        );
+        assert!(body.contains("console.warn(\"Api.load is deprecated\");"));
+    }
+}
@@ -0,0 +1,122 @@
+//! # Memoize Attribute Implementation
+//!
+//! `@memoize` caches results keyed by `JSON.stringify` of the arguments.
+//! Methods cache per instance (per class for static methods) in a private
+//! field; top-level functions use a module-level `Map`.
+//!
+//! For async functions the promise is cached and evicted again if it
+//! rejects, so failures are not remembered.
+//!
+//! ## Options
+//!
+//! | Option | Description |
+//! |--------|-------------|
+//! | `maxSize` | Evicts the oldest entry once the cache holds more entries |
+//!
+//! ## Example
+//!
+//! ```typescript
+//! import { memoize } from "macroforge";
+//!
+//! /** @memoize({ maxSize: 1000 }) */
+//! export function fib(n: number): number {
+//!     return n < 2 ? n : fib(n - 1) + fib(n - 2);
+//! }
+//! ```
+
+use super::{
+    ABOVE_MARKER, BODY_MARKER, call_original, declare_state, reject_generator, stored_type,
+};
+use crate::builtin::derive_common::extract_named_number;
+use crate::macros::ts_macro_attribute;
+use crate::ts_syn::{AttributeInput, FunctionIR, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Generates the cache declaration and the replacement body.
+fn generate_memoize(function: &FunctionIR, max_size: Option<u64>) -> (String, String) {
+    let (declaration, cache) = declare_state(
+        function,
+        "memo",
+        &format!("Map<string, {}>", stored_type(function)),
+        "new Map()",
+    );
+
+    let evict = match max_size {
+        Some(max_size) => format!(
+            "\nif ({cache}.size > {max_size}) {cache}.delete({cache}.keys().next().value!);"
+        ),
+        None => String::new(),
+    };
+    let forget_rejection = if function.is_async {
+        format!("\n__mf_result.catch(() => {cache}.delete(__mf_key));")
+    } else {
+        String::new()
+    };
+
+    let body = format!(
+        "const __mf_key = JSON.stringify(Array.from(arguments));\n\
+         if ({cache}.has(__mf_key)) return {cache}.get(__mf_key)!;\n\
+         const __mf_result = {call};\n\
+         {cache}.set(__mf_key, __mf_result);{evict}{forget_rejection}\n\
+         return __mf_result;",
+        call = call_original(function),
+    );
+    (declaration, body)
+}
+
+#[ts_macro_attribute(
+    memoize,
+    description = "Caches results of a method or function by its arguments. Options: maxSize"
+)]
+pub fn memoize_attribute(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as AttributeInput);
+    reject_generator(&input)?;
+
+    let max_size = match extract_named_number(input.args(), "maxSize") {
+        Some(size) if size >= 1.0 && size.fract() == 0.0 => Some(size as u64),
+        Some(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@memoize maxSize must be a positive integer",
+            ));
+        }
+        None => None,
+    };
+
+    let (declaration, body) = generate_memoize(input.function(), max_size);
+    Ok(TsStream::from_string(format!(
+        "{ABOVE_MARKER}{declaration}\n{BODY_MARKER}{body}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::function;
+    use super::*;
+
+    #[test]
+    fn test_generate_memoize_for_function() {
+        let (declaration, body) = generate_memoize(&function("fib", None), Some(100));
+        assert_eq!(
+            declaration,
+            "const __mf_memo_fib: Map<string, number> = new Map();"
+        );
+        assert!(
+            body.contains("if (__mf_memo_fib.has(__mf_key)) return __mf_memo_fib.get(__mf_key)!;")
+        );
+        assert!(body.contains("if (__mf_memo_fib.size > 100)"));
+        assert!(!body.contains(".catch("));
+    }
+
+    #[test]
+    fn test_generate_memoize_forgets_rejected_promises() {
+        let mut method = function("load", Some("Api"));
+        method.is_async = true;
+        method.return_type_src = "Promise<User>".into();
+        let (declaration, body) = generate_memoize(&method, None);
+        assert_eq!(
+            declaration,
+            "private readonly __mf_memo_load: Map<string, Promise<User>> = new Map();"
+        );
+        assert!(body.contains("__mf_result.catch(() => this.__mf_memo_load.delete(__mf_key));"));
+    }
+}
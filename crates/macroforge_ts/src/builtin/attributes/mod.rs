@@ -0,0 +1,191 @@
+//! # Built-in Attribute Macros
+//!
+//! Attribute macros wrap a single method or function instead of adding code
+//! next to a type. They are applied as decorators on methods, or as JSDoc
+//! directives on top-level functions (which cannot carry decorators):
+//!
+//! ```typescript
+//! import { memoize } from "macroforge";
+//!
+//! class Api {
+//!     @retry({ times: 3, delay: 100 })
+//!     @trace
+//!     async fetchUser(id: string): Promise<User> { ... }
+//! }
+//!
+//! /**
+//!  * Fibonacci numbers.
+//!  * @memoize
+//!  */
+//! export function fib(n: number): number { ... }
+//! ```
+//!
+//! A decorator needs no import unless its name is bound in the module, in
+//! which case only an import from `macroforge` makes it an attribute macro.
+//! JSDoc directives always need that import, so ordinary tags such as
+//! `@deprecated` stay documentation. Only the directive is taken out of the
+//! comment; the rest of it stays with the function.
+//!
+//! | Macro | Options | Description |
+//! |-------|---------|-------------|
+//! | `deprecated` | `message` | Warns with `console.warn` on the first call |
+//! | `memoize` | `maxSize` | Caches results by `JSON.stringify` of the arguments |
+//! | `retry` | `times`, `delay`, `backoff` | Re-runs the body when it throws |
+//! | `trace` | `label` | Logs calls, results and errors with `console.debug` |
+//!
+//! `deprecated` only prepends its warning to the body. The other macros replace
+//! the body with a wrapper that runs the original statements in an arrow
+//! function, so `this`, `arguments` and `super` keep their meaning. They reject
+//! generators because `yield` cannot cross that boundary.
+
+use crate::ts_syn::{AttributeInput, FunctionIR, MacroforgeError};
+
+/// Deprecated attribute implementation (warning on first use).
+mod deprecated;
+
+/// Memoize attribute implementation (result caching).
+mod memoize;
+
+/// Retry attribute implementation (re-running failed calls).
+mod retry;
+
+/// Trace attribute implementation (call logging).
+mod trace;
+
+/// Marker for code replacing the function body.
+const BODY_MARKER: &str = "/* @macroforge:body */";
+
+/// Marker for code inserted before the function.
+const ABOVE_MARKER: &str = "/* @macroforge:above */";
+
+/// An expression that runs the current body with the enclosing `this` and
+/// `arguments`, e.g. `((): number => { return n; })()`.
+///
+/// Async functions get an async arrow, so the expression is a promise.
+fn call_original(function: &FunctionIR) -> String {
+    let asyncness = if function.is_async { "async " } else { "" };
+    let return_type = if function.return_type_src.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", function.return_type_src)
+    };
+    format!(
+        "({asyncness}(){return_type} => {{\n{}\n}})()",
+        function.body_src.trim()
+    )
+}
+
+/// The name used in generated messages, e.g. `Api.fetchUser`.
+fn display_name(function: &FunctionIR) -> String {
+    match &function.class_name {
+        Some(class_name) => format!("{class_name}.{}", function.name),
+        None => function.name.clone(),
+    }
+}
+
+/// The type of a stored result: the declared return type, or `any` when it
+/// is inferred or may mention the function's own type parameters.
+fn stored_type(function: &FunctionIR) -> &str {
+    if function.return_type_src.is_empty() || !function.type_params_src.is_empty() {
+        "any"
+    } else {
+        &function.return_type_src
+    }
+}
+
+/// Declares per-function state next to the function.
+///
+/// Methods get a private field (static for static methods) referenced
+/// through `this`; functions get a module-level `const`. Returns the
+/// declaration and the expression referring to it.
+fn declare_state(function: &FunctionIR, prefix: &str, ty: &str, init: &str) -> (String, String) {
+    let name = format!("__mf_{prefix}_{}", function.name);
+    if function.is_method() {
+        let modifiers = if function.is_static {
+            "private static readonly"
+        } else {
+            "private readonly"
+        };
+        (
+            format!("{modifiers} {name}: {ty} = {init};"),
+            format!("this.{name}"),
+        )
+    } else {
+        (format!("const {name}: {ty} = {init};"), name)
+    }
+}
+
+/// Rejects generator functions, whose `yield` cannot be wrapped.
+fn reject_generator(input: &AttributeInput) -> Result<(), MacroforgeError> {
+    if input.function().is_generator {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!(
+                "@{} cannot be applied to generator '{}'",
+                input.attr.name(),
+                input.name()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    pub(super) fn function(name: &str, class_name: Option<&str>) -> FunctionIR {
+        FunctionIR {
+            name: name.into(),
+            span: SpanIR::new(1, 40),
+            body_span: SpanIR::new(20, 40),
+            body_src: " return n; ".into(),
+            type_params_src: String::new(),
+            params: Vec::new(),
+            params_src: "n: number".into(),
+            return_type_src: "number".into(),
+            is_async: false,
+            is_generator: false,
+            is_static: false,
+            is_exported: false,
+            class_name: class_name.map(Into::into),
+            decorators: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_call_original_keeps_return_type() {
+        let mut target = function("fib", None);
+        assert_eq!(call_original(&target), "((): number => {\nreturn n;\n})()");
+
+        target.is_async = true;
+        target.return_type_src = String::new();
+        assert_eq!(call_original(&target), "(async () => {\nreturn n;\n})()");
+    }
+
+    #[test]
+    fn test_declare_state_for_methods_and_functions() {
+        let (decl, reference) = declare_state(
+            &function("fib", None),
+            "memo",
+            "Map<string, number>",
+            "new Map()",
+        );
+        assert_eq!(
+            decl,
+            "const __mf_memo_fib: Map<string, number> = new Map();"
+        );
+        assert_eq!(reference, "__mf_memo_fib");
+
+        let mut method = function("load", Some("Api"));
+        method.is_static = true;
+        let (decl, reference) = declare_state(&method, "memo", "Map<string, any>", "new Map()");
+        assert_eq!(
+            decl,
+            "private static readonly __mf_memo_load: Map<string, any> = new Map();"
+        );
+        assert_eq!(reference, "this.__mf_memo_load");
+        assert_eq!(display_name(&method), "Api.load");
+    }
+}
@@ -0,0 +1,176 @@
+//! # Retry Attribute Implementation
+//!
+//! `@retry` re-runs the body when it throws (or, for async functions, when
+//! the returned promise rejects). The last error is rethrown once all
+//! retries are used up.
+//!
+//! ## Options
+//!
+//! | Option | Default | Description |
+//! |--------|---------|-------------|
+//! | `times` | `3` | Retries after the first failure |
+//! | `delay` | `0` | Milliseconds to wait before retrying (async only) |
+//! | `backoff` | `1` | Factor applied to `delay` after each retry |
+//!
+//! ## Example
+//!
+//! ```typescript
+//! class Api {
+//!     @retry({ times: 3, delay: 100, backoff: 2 })
+//!     async fetchUser(id: string): Promise<User> {
+//!         return http.get(`/users/${id}`);
+//!     }
+//! }
+//! ```
+
+use super::{BODY_MARKER, call_original, reject_generator};
+use crate::builtin::derive_common::extract_named_number;
+use crate::macros::ts_macro_attribute;
+use crate::ts_syn::{AttributeInput, FunctionIR, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Retry settings parsed from the decorator arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RetryOptions {
+    times: u32,
+    delay: f64,
+    backoff: f64,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            times: 3,
+            delay: 0.0,
+            backoff: 1.0,
+        }
+    }
+}
+
+/// Generates the replacement body: a loop that returns on the first success.
+fn generate_retry(function: &FunctionIR, options: RetryOptions) -> String {
+    let RetryOptions {
+        times,
+        delay,
+        backoff,
+    } = options;
+
+    if !function.is_async {
+        return format!(
+            "for (let __mf_attempt = 0; ; __mf_attempt++) {{\n\
+             try {{\n\
+             return {call};\n\
+             }} catch (__mf_error) {{\n\
+             if (__mf_attempt >= {times}) throw __mf_error;\n\
+             }}\n\
+             }}",
+            call = call_original(function),
+        );
+    }
+
+    let wait = if delay > 0.0 {
+        let grow = if backoff != 1.0 {
+            format!("\n__mf_delay *= {backoff};")
+        } else {
+            String::new()
+        };
+        format!("\nawait new Promise((resolve) => setTimeout(resolve, __mf_delay));{grow}")
+    } else {
+        String::new()
+    };
+    let declare_delay = if wait.is_empty() {
+        String::new()
+    } else {
+        format!("let __mf_delay = {delay};\n")
+    };
+
+    format!(
+        "{declare_delay}for (let __mf_attempt = 0; ; __mf_attempt++) {{\n\
+         try {{\n\
+         return await {call};\n\
+         }} catch (__mf_error) {{\n\
+         if (__mf_attempt >= {times}) throw __mf_error;{wait}\n\
+         }}\n\
+         }}",
+        call = call_original(function),
+    )
+}
+
+#[ts_macro_attribute(
+    retry,
+    description = "Re-runs a method or function when it throws. Options: times, delay, backoff"
+)]
+pub fn retry_attribute(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as AttributeInput);
+    reject_generator(&input)?;
+
+    let args = input.args();
+    let defaults = RetryOptions::default();
+    let times = extract_named_number(args, "times").unwrap_or(defaults.times as f64);
+    let delay = extract_named_number(args, "delay").unwrap_or(defaults.delay);
+    let backoff = extract_named_number(args, "backoff").unwrap_or(defaults.backoff);
+
+    if times < 0.0 || times.fract() != 0.0 || times > u32::MAX as f64 {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@retry times must be a non-negative integer",
+        ));
+    }
+    if delay < 0.0 || backoff <= 0.0 {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@retry delay must be non-negative and backoff positive",
+        ));
+    }
+    if delay > 0.0 && !input.function().is_async {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!(
+                "@retry delay requires an async function, but '{}' is synchronous",
+                input.name()
+            ),
+        ));
+    }
+
+    let options = RetryOptions {
+        times: times as u32,
+        delay,
+        backoff,
+    };
+    Ok(TsStream::from_string(format!(
+        "{BODY_MARKER}{}",
+        generate_retry(input.function(), options)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::function;
+    use super::*;
+
+    #[test]
+    fn test_generate_retry_sync() {
+        let body = generate_retry(&function("parse", None), RetryOptions::default());
+        assert!(body.contains("return ((): number => {"));
+        assert!(body.contains("if (__mf_attempt >= 3) throw __mf_error;"));
+        assert!(!body.contains("await"));
+    }
+
+    #[test]
+    fn test_generate_retry_async_with_backoff() {
+        let mut method = function("fetchUser", Some("Api"));
+        method.is_async = true;
+        method.return_type_src = "Promise<User>".into();
+        let body = generate_retry(
+            &method,
+            RetryOptions {
+                times: 2,
+                delay: 100.0,
+                backoff: 2.0,
+            },
+        );
+        assert!(body.starts_with("let __mf_delay = 100;"));
+        assert!(body.contains("return await (async (): Promise<User> => {"));
+        assert!(body.contains("setTimeout(resolve, __mf_delay)"));
+        assert!(body.contains("__mf_delay *= 2;"));
+    }
+}
@@ -0,0 +1,84 @@
+//! # Trace Attribute Implementation
+//!
+//! `@trace` logs every call with `console.debug`: the arguments on entry,
+//! then either the result or the error. Async functions are logged when the
+//! returned promise settles.
+//!
+//! ## Options
+//!
+//! | Option | Description |
+//! |--------|-------------|
+//! | `label` | Name used in the log lines (default `Class.method` or the function name) |
+//!
+//! ## Example
+//!
+//! ```typescript
+//! class Api {
+//!     @trace({ label: "users" })
+//!     async fetchUser(id: string): Promise<User> { ... }
+//! }
+//! // users( ["42"] )
+//! // users -> { id: "42", ... }
+//! ```
+
+use super::{BODY_MARKER, call_original, display_name, reject_generator};
+use crate::builtin::derive_common::extract_exact_named_string;
+use crate::macros::ts_macro_attribute;
+use crate::ts_syn::{AttributeInput, FunctionIR, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Generates the replacement body logging around the original call.
+fn generate_trace(function: &FunctionIR, label: &str) -> String {
+    let label = serde_json::to_string(label).unwrap();
+    let awaited = if function.is_async { "await " } else { "" };
+    format!(
+        "console.debug({label} + \"(\", Array.from(arguments), \")\");\n\
+         try {{\n\
+         const __mf_result = {awaited}{call};\n\
+         console.debug({label} + \" ->\", __mf_result);\n\
+         return __mf_result;\n\
+         }} catch (__mf_error) {{\n\
+         console.debug({label} + \" threw\", __mf_error);\n\
+         throw __mf_error;\n\
+         }}",
+        call = call_original(function),
+    )
+}
+
+#[ts_macro_attribute(
+    trace,
+    description = "Logs calls, results and errors of a method or function. Options: label"
+)]
+pub fn trace_attribute(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as AttributeInput);
+    reject_generator(&input)?;
+
+    let label = extract_exact_named_string(input.args(), "label")
+        .unwrap_or_else(|| display_name(input.function()));
+    Ok(TsStream::from_string(format!(
+        "{BODY_MARKER}{}",
+        generate_trace(input.function(), &label)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::function;
+    use super::*;
+
+    #[test]
+    fn test_generate_trace_sync() {
+        let body = generate_trace(&function("fib", None), "fib");
+        assert!(body.starts_with(r#"console.debug("fib" + "(", Array.from(arguments), ")");"#));
+        assert!(body.contains("const __mf_result = ((): number => {"));
+        assert!(body.contains(r#"console.debug("fib" + " threw", __mf_error);"#));
+    }
+
+    #[test]
+    fn test_generate_trace_awaits_async_calls() {
+        let mut method = function("fetchUser", Some("Api"));
+        method.is_async = true;
+        method.return_type_src = "Promise<User>".into();
+        let body = generate_trace(&method, "Api.fetchUser");
+        assert!(body.contains("const __mf_result = await (async (): Promise<User> => {"));
+    }
+}
@@ -0,0 +1,109 @@
+//! # buildEnv Implementation
+//!
+//! `buildEnv(name)` inlines an environment variable of the build process as
+//! a string literal. Without a fallback the variable is required and an
+//! unset variable is an error; with one, the fallback expression is used
+//! instead.
+//!
+//! Only the variables named in calls end up in the output, so secrets in
+//! the build environment are not leaked by accident.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! import { buildEnv } from "macroforge";
+//!
+//! const apiUrl = buildEnv("API_URL");               // required
+//! const port = buildEnv("PORT", "8080");            // optional, with default
+//! const dsn = buildEnv("SENTRY_DSN", undefined);    // optional, string | undefined
+//! ```
+
+use super::{check_arity, string_arg};
+use crate::macros::ts_macro_call;
+use crate::ts_syn::{CallInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Generates the expression for a variable with the given value.
+fn generate_build_env(input: &CallInput, value: Option<String>) -> Result<String, MacroforgeError> {
+    let (name, span) = string_arg(input, 0, "an environment variable name")?;
+    if let Some(value) = value {
+        return Ok(serde_json::to_string(&value).unwrap());
+    }
+
+    match input.args().get(1) {
+        Some(fallback) if fallback.is_spread => Err(MacroforgeError::new(
+            fallback.span,
+            "buildEnv() fallback cannot be a spread argument",
+        )),
+        Some(fallback) if is_simple_expression(&fallback.source) => Ok(fallback.source.clone()),
+        Some(fallback) => Ok(format!("({})", fallback.source)),
+        None => Err(MacroforgeError::new(
+            span,
+            format!(
+                "Environment variable '{name}' is not set. Set it for the build, or pass a \
+                 fallback such as buildEnv(\"{name}\", undefined)"
+            ),
+        )),
+    }
+}
+
+/// Whether an expression can be inlined without parentheses.
+fn is_simple_expression(source: &str) -> bool {
+    let bytes = source.as_bytes();
+    let quoted = bytes.len() >= 2
+        && matches!(bytes[0], b'"' | b'\'')
+        && bytes[bytes.len() - 1] == bytes[0]
+        && !source[1..source.len() - 1].contains(bytes[0] as char);
+    quoted
+        || source
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+}
+
+#[ts_macro_call(
+    buildEnv,
+    description = "Inlines a build-time environment variable; required unless a fallback is given"
+)]
+pub fn build_env_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as CallInput);
+    check_arity(&input, 2)?;
+
+    let (name, _) = string_arg(&input, 0, "an environment variable name")?;
+    let value = std::env::var(name).ok();
+    Ok(TsStream::from_string(generate_build_env(&input, value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::call;
+    use super::*;
+
+    #[test]
+    fn test_generate_build_env_required() {
+        let input = call("buildEnv", &["\"API_URL\""], "src/a.ts");
+        assert_eq!(
+            generate_build_env(&input, Some("https://api".into())).unwrap(),
+            "\"https://api\""
+        );
+
+        let err = generate_build_env(&input, None)
+            .unwrap_err()
+            .to_diagnostic();
+        assert!(
+            err.message
+                .starts_with("Environment variable 'API_URL' is not set")
+        );
+        assert_eq!(err.span, Some(input.args()[0].span));
+    }
+
+    #[test]
+    fn test_generate_build_env_fallback() {
+        let input = call("buildEnv", &["\"PORT\"", "\"8080\""], "src/a.ts");
+        assert_eq!(generate_build_env(&input, None).unwrap(), "\"8080\"");
+
+        let input = call("buildEnv", &["\"DSN\"", "undefined"], "src/a.ts");
+        assert_eq!(generate_build_env(&input, None).unwrap(), "undefined");
+
+        let input = call("buildEnv", &["\"PORT\"", "a ?? b"], "src/a.ts");
+        assert_eq!(generate_build_env(&input, None).unwrap(), "(a ?? b)");
+    }
+}
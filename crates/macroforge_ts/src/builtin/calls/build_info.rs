@@ -0,0 +1,192 @@
+//! # buildInfo Implementation
+//!
+//! `buildInfo()` inlines metadata about the build as an object literal:
+//!
+//! | Field | Source |
+//! |-------|--------|
+//! | `name` | `name` of the nearest `package.json` (`undefined` if absent) |
+//! | `version` | `version` of the nearest `package.json` |
+//! | `timestamp` | Build time as ISO 8601 UTC, from `SOURCE_DATE_EPOCH` if set |
+//!
+//! The nearest `package.json` is searched from the calling module's
+//! directory upwards. No version control information is read, so the
+//! output does not depend on a git checkout.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! import { buildInfo } from "macroforge";
+//!
+//! const info = buildInfo();
+//! // const info = ({ name: "web", version: "1.4.0", timestamp: "2026-10-18T09:30:00Z" });
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::check_arity;
+use crate::macros::ts_macro_call;
+use crate::ts_syn::{CallInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Finds the `package.json` closest to `directory`.
+fn find_package_json(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|dir| dir.join("package.json"))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+fn iso_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// The build time in seconds, honouring `SOURCE_DATE_EPOCH` for
+/// reproducible builds.
+fn build_time(input: &CallInput) -> Result<u64, MacroforgeError> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse().map_err(|_| {
+            MacroforgeError::new(
+                input.error_span(),
+                format!("buildInfo() cannot parse SOURCE_DATE_EPOCH '{epoch}' as seconds"),
+            )
+        }),
+        Err(_) => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)),
+    }
+}
+
+/// Generates the object literal from the contents of a `package.json`.
+fn generate_build_info(
+    input: &CallInput,
+    package_json: &Path,
+    contents: &str,
+    seconds: u64,
+) -> Result<String, MacroforgeError> {
+    let error = |message: String| MacroforgeError::new(input.error_span(), message);
+    let manifest: serde_json::Value = serde_json::from_str(contents).map_err(|err| {
+        error(format!(
+            "buildInfo() cannot parse '{}': {err}",
+            package_json.display()
+        ))
+    })?;
+
+    let Some(version) = manifest.get("version").and_then(|v| v.as_str()) else {
+        return Err(error(format!(
+            "buildInfo() found no \"version\" in '{}'",
+            package_json.display()
+        )));
+    };
+    let name = match manifest.get("name").and_then(|v| v.as_str()) {
+        Some(name) => serde_json::to_string(name).unwrap(),
+        None => "undefined".to_string(),
+    };
+
+    Ok(format!(
+        "({{ name: {name}, version: {}, timestamp: \"{}\" }})",
+        serde_json::to_string(version).unwrap(),
+        iso_timestamp(seconds)
+    ))
+}
+
+#[ts_macro_call(
+    buildInfo,
+    description = "Inlines the package name and version from package.json and the build time"
+)]
+pub fn build_info_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as CallInput);
+    check_arity(&input, 0)?;
+
+    let module =
+        std::path::absolute(input.file_name()).unwrap_or_else(|_| PathBuf::from(input.file_name()));
+    let directory = module.parent().unwrap_or(Path::new(""));
+    let Some(package_json) = find_package_json(directory) else {
+        return Err(MacroforgeError::new(
+            input.error_span(),
+            format!(
+                "buildInfo() found no package.json above '{}'",
+                input.file_name()
+            ),
+        ));
+    };
+    let contents = std::fs::read_to_string(&package_json).map_err(|err| {
+        MacroforgeError::new(
+            input.error_span(),
+            format!(
+                "buildInfo() cannot read '{}': {err}",
+                package_json.display()
+            ),
+        )
+    })?;
+
+    let info = generate_build_info(&input, &package_json, &contents, build_time(&input)?)?;
+    let mut output = TsStream::from_string(info);
+    output.add_dependency(package_json.to_string_lossy());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::call;
+    use super::*;
+
+    #[test]
+    fn test_iso_timestamp() {
+        assert_eq!(iso_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(iso_timestamp(1_792_323_045), "2026-10-18T11:30:45Z");
+    }
+
+    #[test]
+    fn test_generate_build_info() {
+        let input = call("buildInfo", &[], "src/a.ts");
+        let path = Path::new("package.json");
+        assert_eq!(
+            generate_build_info(&input, path, r#"{"name":"web","version":"1.4.0"}"#, 0).unwrap(),
+            r#"({ name: "web", version: "1.4.0", timestamp: "1970-01-01T00:00:00Z" })"#
+        );
+
+        let err = generate_build_info(&input, path, r#"{"name":"web"}"#, 0)
+            .unwrap_err()
+            .to_diagnostic();
+        assert_eq!(
+            err.message,
+            "buildInfo() found no \"version\" in 'package.json'"
+        );
+        assert_eq!(err.span, Some(input.error_span()));
+    }
+
+    #[test]
+    fn test_find_package_json_walks_up() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        std::fs::create_dir_all(dir.path().join("src/routes")).unwrap();
+
+        assert_eq!(
+            find_package_json(&dir.path().join("src/routes")),
+            Some(dir.path().join("package.json"))
+        );
+    }
+}
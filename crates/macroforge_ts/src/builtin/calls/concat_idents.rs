@@ -0,0 +1,96 @@
+//! # concatIdents Implementation
+//!
+//! `concatIdents(a, b, ...)` joins its arguments into a single identifier.
+//! Each argument is an identifier, taken by name, or a string literal, taken
+//! by value. The result must itself be a valid identifier.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! import { concatIdents } from "macroforge";
+//!
+//! const user = concatIdents(get, "User")(id);
+//! // const user = getUser(id);
+//! ```
+
+use crate::macros::ts_macro_call;
+use crate::ts_syn::{CallInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Whether `name` is a JavaScript identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Joins the arguments into an identifier.
+fn generate_concat_idents(input: &CallInput) -> Result<String, MacroforgeError> {
+    if input.args().is_empty() {
+        return Err(MacroforgeError::new(
+            input.span(),
+            "concatIdents() expects at least one identifier or string literal",
+        ));
+    }
+
+    let mut ident = String::new();
+    for arg in input.args() {
+        match &arg.string_value {
+            Some(value) if !arg.is_spread => ident.push_str(value),
+            None if !arg.is_spread && is_identifier(&arg.source) => ident.push_str(&arg.source),
+            _ => {
+                return Err(MacroforgeError::new(
+                    arg.span,
+                    format!(
+                        "concatIdents() arguments must be identifiers or string literals, found '{}'",
+                        arg.source
+                    ),
+                ));
+            }
+        }
+    }
+
+    if !is_identifier(&ident) {
+        return Err(MacroforgeError::new(
+            input.span(),
+            format!("concatIdents() produced '{ident}', which is not a valid identifier"),
+        ));
+    }
+    Ok(ident)
+}
+
+#[ts_macro_call(
+    concatIdents,
+    description = "Joins identifiers and string literals into a single identifier"
+)]
+pub fn concat_idents_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as CallInput);
+    Ok(TsStream::from_string(generate_concat_idents(&input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::call;
+    use super::*;
+
+    #[test]
+    fn test_generate_concat_idents() {
+        let input = call("concatIdents", &["get", "\"User\"", "_v2"], "src/a.ts");
+        assert_eq!(generate_concat_idents(&input).unwrap(), "getUser_v2");
+    }
+
+    #[test]
+    fn test_generate_concat_idents_rejects_expressions() {
+        let input = call("concatIdents", &["get", "a.b"], "src/a.ts");
+        let err = generate_concat_idents(&input).unwrap_err().to_diagnostic();
+        assert_eq!(err.span, Some(input.args()[1].span));
+
+        let input = call("concatIdents", &["\"1\"", "x"], "src/a.ts");
+        let err = generate_concat_idents(&input).unwrap_err().to_diagnostic();
+        assert_eq!(
+            err.message,
+            "concatIdents() produced '1x', which is not a valid identifier"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! # includeStr and includeJson Implementation
+//!
+//! `includeStr(path)` inlines a file as a string literal, and
+//! `includeJson(path)` inlines a JSON file as an object, array or scalar
+//! literal. The path must be a string literal and is resolved relative to
+//! the calling module.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! import { includeJson, includeStr } from "macroforge";
+//!
+//! const schema = includeStr("./schema.graphql");
+//! const config = includeJson<AppConfig>("../config/app.json");
+//! // const config = ({"port":8080} as AppConfig);
+//! ```
+//!
+//! A type argument is applied with `as`, so the literal is checked against
+//! it; without one the type checker infers the type from the literal.
+
+use std::path::PathBuf;
+
+use super::{check_arity, resolve_relative, string_arg};
+use crate::macros::ts_macro_call;
+use crate::ts_syn::{CallInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Reads the file named by the first argument.
+fn read_included(input: &CallInput) -> Result<(PathBuf, String), MacroforgeError> {
+    check_arity(input, 1)?;
+    let (path, span) = string_arg(input, 0, "a file path")?;
+    let resolved = resolve_relative(input.file_name(), path);
+    match std::fs::read_to_string(&resolved) {
+        Ok(contents) => Ok((resolved, contents)),
+        Err(err) => Err(MacroforgeError::new(
+            span,
+            format!(
+                "{}() cannot read '{path}' (resolved to '{}'): {err}",
+                input.name(),
+                resolved.display()
+            ),
+        )),
+    }
+}
+
+/// Renders parsed JSON as an expression, asserting the call's type argument.
+fn json_expression(value: &serde_json::Value, type_args_src: &str) -> String {
+    let literal = value.to_string();
+    let type_arg = type_args_src
+        .trim()
+        .strip_prefix('<')
+        .and_then(|rest| rest.strip_suffix('>'))
+        .map(str::trim)
+        .filter(|ty| !ty.is_empty());
+    match type_arg {
+        Some(ty) => format!("({literal} as {ty})"),
+        // An object literal at the start of a statement would be a block.
+        None if value.is_object() => format!("({literal})"),
+        None => literal,
+    }
+}
+
+#[ts_macro_call(
+    includeStr,
+    description = "Inlines a file, relative to the calling module, as a string literal"
+)]
+pub fn include_str_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as CallInput);
+    let (resolved, contents) = read_included(&input)?;
+
+    let mut output = TsStream::from_string(serde_json::to_string(&contents).unwrap());
+    output.add_dependency(resolved.to_string_lossy());
+    Ok(output)
+}
+
+#[ts_macro_call(
+    includeJson,
+    description = "Inlines a JSON file, relative to the calling module, as a literal"
+)]
+pub fn include_json_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as CallInput);
+    let (resolved, contents) = read_included(&input)?;
+
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(|err| {
+        MacroforgeError::new(
+            input.args()[0].span,
+            format!("includeJson() cannot parse '{}': {err}", resolved.display()),
+        )
+    })?;
+
+    let mut output = TsStream::from_string(json_expression(&value, &input.call().type_args_src));
+    output.add_dependency(resolved.to_string_lossy());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::call;
+    use super::*;
+
+    #[test]
+    fn test_read_included_resolves_against_module() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("schema.graphql"),
+            "type Query { ok: Boolean }",
+        )
+        .unwrap();
+        let module = dir.path().join("api.ts");
+
+        let input = call(
+            "includeStr",
+            &["\"./schema.graphql\""],
+            &module.to_string_lossy(),
+        );
+        let (resolved, contents) = read_included(&input).unwrap();
+        assert_eq!(resolved, dir.path().join("schema.graphql"));
+        assert_eq!(contents, "type Query { ok: Boolean }");
+    }
+
+    #[test]
+    fn test_read_included_reports_missing_files_at_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("api.ts");
+
+        let input = call(
+            "includeStr",
+            &["\"./missing.txt\""],
+            &module.to_string_lossy(),
+        );
+        let err = read_included(&input).unwrap_err().to_diagnostic();
+        assert!(
+            err.message
+                .starts_with("includeStr() cannot read './missing.txt' (resolved to '"),
+            "{}",
+            err.message
+        );
+        assert_eq!(err.span, Some(input.args()[0].span));
+    }
+
+    #[test]
+    fn test_json_expression() {
+        let object = serde_json::json!({ "port": 8080 });
+        assert_eq!(json_expression(&object, ""), r#"({"port":8080})"#);
+        assert_eq!(
+            json_expression(&object, "<AppConfig>"),
+            r#"({"port":8080} as AppConfig)"#
+        );
+        assert_eq!(json_expression(&serde_json::json!([1, 2]), ""), "[1,2]");
+    }
+}
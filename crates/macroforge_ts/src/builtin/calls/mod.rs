@@ -0,0 +1,151 @@
+//! # Built-in Call Macros
+//!
+//! Call macros are compile-time intrinsics invoked like functions. Their
+//! output replaces the call, so nothing of them is left at runtime:
+//!
+//! ```typescript
+//! import { buildEnv, includeStr } from "macroforge";
+//!
+//! const schema = includeStr("./schema.graphql");
+//! const apiUrl = buildEnv("API_URL");
+//! ```
+//!
+//! | Macro | Output |
+//! |-------|--------|
+//! | `includeStr(path)` | The file's contents as a string literal |
+//! | `includeJson<T>(path)` | The file's JSON as a literal, typed as `T` if given |
+//! | `buildEnv(name, fallback?)` | The build environment variable as a string literal |
+//! | `buildInfo()` | `{ name, version, timestamp }` from the nearest `package.json` |
+//! | `concatIdents(a, b, ...)` | The identifier formed by joining the parts |
+//!
+//! The `macroforge` package declares these for the type checker, and the
+//! import is removed once the calls are expanded. Paths are resolved
+//! relative to the calling module, and every file read is reported as a
+//! dependency of the module so that build tools expand it again when the
+//! file changes.
+
+use std::path::{Path, PathBuf};
+
+use crate::ts_syn::abi::SpanIR;
+use crate::ts_syn::{CallInput, MacroforgeError};
+
+/// buildEnv implementation (environment variables).
+mod build_env;
+
+/// buildInfo implementation (package version and build time).
+mod build_info;
+
+/// concatIdents implementation (identifier pasting).
+mod concat_idents;
+
+/// includeStr and includeJson implementation (file contents).
+mod include;
+
+/// The string literal argument at `index`, described as `what` in errors.
+fn string_arg<'a>(
+    input: &'a CallInput,
+    index: usize,
+    what: &str,
+) -> Result<(&'a str, SpanIR), MacroforgeError> {
+    let Some(arg) = input.args().get(index) else {
+        return Err(MacroforgeError::new(
+            input.span(),
+            format!("{}() expects {what}", input.name()),
+        ));
+    };
+    match &arg.string_value {
+        Some(value) if !arg.is_spread => Ok((value.as_str(), arg.span)),
+        _ => Err(MacroforgeError::new(
+            arg.span,
+            format!("{}() expects {what} as a string literal", input.name()),
+        )),
+    }
+}
+
+/// Rejects arguments after the first `max`.
+fn check_arity(input: &CallInput, max: usize) -> Result<(), MacroforgeError> {
+    match input.args().get(max) {
+        Some(extra) => Err(MacroforgeError::new(
+            extra.span,
+            match max {
+                0 => format!("{}() takes no arguments", input.name()),
+                1 => format!("{}() takes at most 1 argument", input.name()),
+                _ => format!("{}() takes at most {max} arguments", input.name()),
+            },
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Makes `path` absolute, resolving relative paths against the directory of
+/// the calling module.
+fn resolve_relative(file_name: &str, path: &str) -> PathBuf {
+    let directory = Path::new(file_name).parent().unwrap_or(Path::new(""));
+    let resolved = directory.join(path);
+    std::path::absolute(&resolved).unwrap_or(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::{CallArgIR, CallIR, MacroContextIR};
+
+    /// A call of `name` in `file_name` with the given argument sources.
+    pub(super) fn call(name: &str, args: &[&str], file_name: &str) -> CallInput {
+        let mut offset = name.len() as u32 + 2;
+        let args = args
+            .iter()
+            .map(|source| {
+                let span = SpanIR::new(offset, offset + source.len() as u32);
+                offset = span.end + 2;
+                CallArgIR {
+                    span,
+                    source: source.to_string(),
+                    string_value: serde_json::from_str(source).ok(),
+                    is_spread: false,
+                }
+            })
+            .collect();
+        let call = CallIR {
+            name: name.into(),
+            span: SpanIR::new(1, offset),
+            callee_span: SpanIR::new(1, name.len() as u32 + 1),
+            stmt_span: SpanIR::new(1, offset + 1),
+            type_args_src: String::new(),
+            args,
+            template: None,
+        };
+        CallInput::from_context(MacroContextIR::new_call(
+            name.into(),
+            "macroforge".into(),
+            file_name.into(),
+            call,
+            String::new(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_string_arg_requires_literals() {
+        let input = call("includeStr", &["\"./a.txt\"", "path"], "src/a.ts");
+        assert_eq!(string_arg(&input, 0, "a path").unwrap().0, "./a.txt");
+
+        let err = string_arg(&input, 1, "a path").unwrap_err().to_diagnostic();
+        assert_eq!(
+            err.message,
+            "includeStr() expects a path as a string literal"
+        );
+        assert_eq!(err.span, Some(input.args()[1].span));
+        assert!(check_arity(&input, 1).is_err());
+    }
+
+    #[test]
+    fn test_resolve_relative_to_module() {
+        let resolved = resolve_relative("/app/src/api.ts", "./schema.graphql");
+        assert_eq!(resolved, Path::new("/app/src/schema.graphql"));
+        assert_eq!(
+            resolve_relative("/app/src/api.ts", "/etc/schema.graphql"),
+            Path::new("/etc/schema.graphql")
+        );
+    }
+}
@@ -0,0 +1,792 @@
+//! # Arbitrary Macro Implementation
+//!
+//! The `Arbitrary` macro generates a random value generator per type for
+//! property-based testing. Generators are dependency-free: they draw from the
+//! seeded `Random` PRNG in `macroforge/arbitrary`, so any failure can be
+//! replayed from its seed.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameArbitrary(rng)` + `static arbitrary(rng)` | Instance created with the class prototype |
+//! | Enum | `enumNameArbitrary(rng)` | Picks one of the variants |
+//! | Interface | `interfaceNameArbitrary(rng)` | Object literal |
+//! | Type Alias | `typeNameArbitrary(rng)` | Object, union, tuple or aliased type |
+//!
+//! ## Type Support
+//!
+//! | Type | Generated Values |
+//! |------|------------------|
+//! | `string`, `number`, `bigint`, `boolean`, `Date` | Random values within the field's validators |
+//! | Literals and literal unions | One of the literals |
+//! | `T \| null`, `T \| undefined` | Occasionally `null`/`undefined` |
+//! | Optional fields (`x?: T`) | Present about half the time |
+//! | `T[]`, `Set<T>`, `Map<K, V>`, `Record<K, V>`, tuples | Collections of generated values |
+//! | Other named types | That type's generated `*Arbitrary` |
+//!
+//! Like `Default`, named field types are assumed to derive `Arbitrary` as well.
+//!
+//! ## Validators
+//!
+//! The serde validators on a field (`@serde({ validate: [...] })`) shape its
+//! values: `minLength`/`maxLength`, `email`/`url`/`uuid`, `pattern`, the
+//! case validators, `startsWith`/`endsWith`/`includes`, numeric bounds,
+//! `int`, `multipleOf`, date bounds and `minItems`/`maxItems`. Fields with
+//! validators are additionally filtered through the exact checks
+//! `Deserialize` performs, including custom validator functions, so generated
+//! values pass `deserialize`.
+//!
+//! ## Field-Level Options
+//!
+//! The `@arbitrary` decorator supports:
+//!
+//! - `with` - A custom generator `(rng) => value`, for types the macro
+//!   cannot generate (e.g. inline object types or generics)
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Arbitrary, Serialize, Deserialize) */
+//! interface User {
+//!     /** @serde({ validate: ["email"] }) */
+//!     email: string;
+//!     role: "admin" | "member";
+//!     nickname?: string;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export function userArbitrary(rng: __mf_Random): User {
+//!     return {
+//!         email: __mf_satisfying(rng, (rng) => __mf_string(rng, { format: "email" }), (value) => !(/* email check */)),
+//!         role: rng.pick(["admin", "member"]),
+//!         ...(rng.bool() ? { nickname: __mf_string(rng) } : {}),
+//!     };
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    extract_exact_named_string, is_nullable_type, split_top_level, strip_parens,
+};
+use crate::builtin::serde::derive_deserialize::generate_validation_condition;
+use crate::builtin::serde::{SerdeFieldOptions, Validator};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, InterfaceFieldIR, SpanIR, TypeMemberKind};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Probability that a nullable value is `null`/`undefined`.
+const NULL_CHANCE: &str = "0.2";
+
+/// Encodes `s` as a JavaScript string literal.
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn is_literal(ts_type: &str) -> bool {
+    let t = ts_type.trim();
+    t.starts_with('"')
+        || t.starts_with('\'')
+        || t.parse::<f64>().is_ok()
+        || matches!(t, "true" | "false")
+}
+
+/// A bound's JavaScript value and whether it is exclusive.
+type Bound = (String, bool);
+
+/// Keeps the tighter of two numeric bounds; `lower` selects which end.
+fn tighten(current: &mut Option<Bound>, value: f64, exclusive: bool, lower: bool) {
+    let replace = match current {
+        None => true,
+        Some((existing, was_exclusive)) => {
+            let existing: f64 = existing.parse().unwrap_or(value);
+            (lower && value > existing)
+                || (!lower && value < existing)
+                || (value == existing && exclusive && !*was_exclusive)
+        }
+    };
+    if replace {
+        *current = Some((value.to_string(), exclusive));
+    }
+}
+
+/// Builds the JavaScript expressions for random values of a type, recording
+/// the `macroforge/arbitrary` helpers they use.
+#[derive(Default)]
+struct Generator {
+    helpers: BTreeSet<&'static str>,
+}
+
+impl Generator {
+    fn helper(&mut self, name: &'static str) -> String {
+        self.helpers.insert(name);
+        format!("__mf_{name}")
+    }
+
+    /// Formats `{ key: value, ... }`, or `None` when there are no options.
+    fn options(entries: Vec<String>) -> Option<String> {
+        (!entries.is_empty()).then(|| format!("{{ {} }}", entries.join(", ")))
+    }
+
+    fn call(&mut self, name: &'static str, args: &str, options: Vec<String>) -> String {
+        let helper = self.helper(name);
+        match Self::options(options) {
+            Some(options) if args.is_empty() => format!("{helper}(rng, {options})"),
+            Some(options) => format!("{helper}(rng, {args}, {options})"),
+            None if args.is_empty() => format!("{helper}(rng)"),
+            None => format!("{helper}(rng, {args})"),
+        }
+    }
+
+    /// An expression producing a value of `ts_type` from `rng`.
+    fn expr(&mut self, ts_type: &str, validators: &[Validator]) -> Result<String, String> {
+        let ts_type = strip_parens(ts_type);
+
+        let members = split_top_level(ts_type, '|');
+        if members.len() > 1 {
+            return self.union(&members, validators);
+        }
+
+        if is_literal(ts_type) || matches!(ts_type, "null" | "undefined") {
+            return Ok(ts_type.to_string());
+        }
+
+        match ts_type {
+            "string" => return Ok(self.string(validators)),
+            "number" => return Ok(self.number(validators)),
+            "bigint" => return Ok(self.bigint(validators)),
+            "boolean" => return Ok("rng.bool()".to_string()),
+            "Date" => return Ok(self.date(validators)),
+            "unknown" | "any" => return Ok("null".to_string()),
+            _ => {}
+        }
+
+        if let Some(element) = ts_type.strip_suffix("[]")
+            && !element.is_empty()
+        {
+            return self.array(element, validators);
+        }
+
+        if let Some(inner) = ts_type.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            let items = split_top_level(inner, ',')
+                .into_iter()
+                .map(|item| self.expr(item, &[]))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(format!("[{}]", items.join(", ")));
+        }
+
+        if let Some((base, args)) = ts_type
+            .split_once('<')
+            .and_then(|(base, rest)| Some((base.trim(), rest.strip_suffix('>')?)))
+        {
+            let args = split_top_level(args, ',');
+            return match (base, args.as_slice()) {
+                ("Array" | "ReadonlyArray", [element]) => self.array(element, validators),
+                ("Set" | "ReadonlySet", [element]) => {
+                    let item = self.expr(element, &[])?;
+                    Ok(self.call("set", &format!("(rng) => {item}"), size_options(validators)))
+                }
+                ("Map" | "ReadonlyMap", [key, value]) => {
+                    let key = self.expr(key, &[])?;
+                    let value = self.expr(value, &[])?;
+                    Ok(self.call(
+                        "map",
+                        &format!("(rng) => {key}, (rng) => {value}"),
+                        size_options(validators),
+                    ))
+                }
+                ("Record", [key, value]) => self.record(key, value),
+                _ => Err(format!("generic type '{ts_type}'")),
+            };
+        }
+
+        let mut chars = ts_type.chars();
+        let named = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if named {
+            return Ok(format!("{}Arbitrary(rng)", ts_type.to_case(Case::Camel)));
+        }
+
+        Err(format!("type '{ts_type}'"))
+    }
+
+    fn union(&mut self, members: &[&str], validators: &[Validator]) -> Result<String, String> {
+        let (nullish, rest): (Vec<&str>, Vec<&str>) = members
+            .iter()
+            .partition(|m| matches!(**m, "null" | "undefined"));
+
+        let inner = if rest.is_empty() {
+            return Ok(format!("rng.pick([{}])", nullish.join(", ")));
+        } else if rest.iter().all(|m| is_literal(m)) {
+            format!("rng.pick([{}])", rest.join(", "))
+        } else if rest.len() == 1 {
+            self.expr(rest[0], validators)?
+        } else {
+            let options = rest
+                .iter()
+                .map(|m| self.expr(m, validators).map(|e| format!("(rng) => {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("{}(rng, [{}])", self.helper("oneOf"), options.join(", "))
+        };
+
+        Ok(match nullish.first() {
+            Some(empty) => format!("(rng.chance({NULL_CHANCE}) ? {empty} : {inner})"),
+            None => inner,
+        })
+    }
+
+    fn string(&mut self, validators: &[Validator]) -> String {
+        let mut min: Option<usize> = None;
+        let mut max: Option<usize> = None;
+        let mut options = Vec::new();
+        for validator in validators {
+            match validator {
+                Validator::Email => options.push("format: \"email\"".to_string()),
+                Validator::Url => options.push("format: \"url\"".to_string()),
+                Validator::Uuid => options.push("format: \"uuid\"".to_string()),
+                Validator::Pattern(pattern) => {
+                    options.push(format!("pattern: {}", js_string(pattern)))
+                }
+                Validator::MinLength(n) => min = min.max(Some(*n)),
+                Validator::MaxLength(n) => max = Some(max.map_or(*n, |m| m.min(*n))),
+                Validator::Length(n) => {
+                    min = Some(*n);
+                    max = Some(*n);
+                }
+                Validator::LengthRange(lo, hi) => {
+                    min = min.max(Some(*lo));
+                    max = Some(max.map_or(*hi, |m| m.min(*hi)));
+                }
+                Validator::NonEmpty => min = min.max(Some(1)),
+                Validator::Lowercase => options.push("case: \"lower\"".to_string()),
+                Validator::Uppercase => options.push("case: \"upper\"".to_string()),
+                Validator::Capitalized => options.push("case: \"capitalized\"".to_string()),
+                Validator::Uncapitalized => options.push("case: \"uncapitalized\"".to_string()),
+                Validator::StartsWith(s) => options.push(format!("startsWith: {}", js_string(s))),
+                Validator::EndsWith(s) => options.push(format!("endsWith: {}", js_string(s))),
+                Validator::Includes(s) => options.push(format!("includes: {}", js_string(s))),
+                _ => {}
+            }
+        }
+        if let Some(min) = min {
+            options.push(format!("minLength: {min}"));
+        }
+        if let Some(max) = max {
+            options.push(format!("maxLength: {max}"));
+        }
+        self.call("string", "", options)
+    }
+
+    fn number(&mut self, validators: &[Validator]) -> String {
+        let mut min: Option<Bound> = None;
+        let mut max: Option<Bound> = None;
+        let mut options = Vec::new();
+        for validator in validators {
+            match validator {
+                Validator::GreaterThan(n) => tighten(&mut min, *n, true, true),
+                Validator::GreaterThanOrEqualTo(n) => tighten(&mut min, *n, false, true),
+                Validator::LessThan(n) => tighten(&mut max, *n, true, false),
+                Validator::LessThanOrEqualTo(n) => tighten(&mut max, *n, false, false),
+                Validator::Between(lo, hi) => {
+                    tighten(&mut min, *lo, false, true);
+                    tighten(&mut max, *hi, false, false);
+                }
+                Validator::Positive => tighten(&mut min, 0.0, true, true),
+                Validator::NonNegative => tighten(&mut min, 0.0, false, true),
+                Validator::Negative => tighten(&mut max, 0.0, true, false),
+                Validator::NonPositive => tighten(&mut max, 0.0, false, false),
+                Validator::Int => options.push("int: true".to_string()),
+                Validator::Uint8 => {
+                    options.push("int: true".to_string());
+                    tighten(&mut min, 0.0, false, true);
+                    tighten(&mut max, 255.0, false, false);
+                }
+                Validator::MultipleOf(n) => options.push(format!("multipleOf: {n}")),
+                _ => {}
+            }
+        }
+        options.dedup();
+        push_bounds(&mut options, min, max);
+        self.call("number", "", options)
+    }
+
+    fn bigint(&mut self, validators: &[Validator]) -> String {
+        let mut min: Option<Bound> = None;
+        let mut max: Option<Bound> = None;
+        for validator in validators {
+            match validator {
+                Validator::GreaterThanBigInt(n) => min = Some((format!("BigInt({n})"), true)),
+                Validator::GreaterThanOrEqualToBigInt(n) => {
+                    min = Some((format!("BigInt({n})"), false))
+                }
+                Validator::LessThanBigInt(n) => max = Some((format!("BigInt({n})"), true)),
+                Validator::LessThanOrEqualToBigInt(n) => {
+                    max = Some((format!("BigInt({n})"), false))
+                }
+                Validator::BetweenBigInt(lo, hi) => {
+                    min = Some((format!("BigInt({lo})"), false));
+                    max = Some((format!("BigInt({hi})"), false));
+                }
+                Validator::PositiveBigInt => min = Some(("0n".to_string(), true)),
+                Validator::NonNegativeBigInt => min = Some(("0n".to_string(), false)),
+                Validator::NegativeBigInt => max = Some(("0n".to_string(), true)),
+                Validator::NonPositiveBigInt => max = Some(("0n".to_string(), false)),
+                _ => {}
+            }
+        }
+        let mut options = Vec::new();
+        push_bounds(&mut options, min, max);
+        self.call("bigint", "", options)
+    }
+
+    fn date(&mut self, validators: &[Validator]) -> String {
+        let mut min: Option<Bound> = None;
+        let mut max: Option<Bound> = None;
+        let date = |d: &str| format!("new Date({})", js_string(d));
+        for validator in validators {
+            match validator {
+                Validator::GreaterThanDate(d) => min = Some((date(d), true)),
+                Validator::GreaterThanOrEqualToDate(d) => min = Some((date(d), false)),
+                Validator::LessThanDate(d) => max = Some((date(d), true)),
+                Validator::LessThanOrEqualToDate(d) => max = Some((date(d), false)),
+                Validator::BetweenDate(lo, hi) => {
+                    min = Some((date(lo), false));
+                    max = Some((date(hi), false));
+                }
+                _ => {}
+            }
+        }
+        let mut options = Vec::new();
+        push_bounds(&mut options, min, max);
+        self.call("date", "", options)
+    }
+
+    fn array(&mut self, element: &str, validators: &[Validator]) -> Result<String, String> {
+        let item = self.expr(element, &[])?;
+        Ok(self.call(
+            "array",
+            &format!("(rng) => {item}"),
+            size_options(validators),
+        ))
+    }
+
+    fn record(&mut self, key: &str, value: &str) -> Result<String, String> {
+        let keys = split_top_level(strip_parens(key), '|');
+        if keys.iter().all(|k| is_literal(k)) {
+            let value = self.expr(value, &[])?;
+            let entries = keys
+                .iter()
+                .map(|k| format!("[{k}]: {value}"))
+                .collect::<Vec<_>>();
+            return Ok(format!("{{ {} }}", entries.join(", ")));
+        }
+        let key = match strip_parens(key) {
+            "string" => self.call("string", "", vec!["minLength: 1".to_string()]),
+            "number" => self.call("number", "", vec!["int: true".to_string()]),
+            other => return Err(format!("record key type '{other}'")),
+        };
+        let value = self.expr(value, &[])?;
+        Ok(self.call(
+            "record",
+            &format!("(rng) => {key}, (rng) => {value}"),
+            Vec::new(),
+        ))
+    }
+
+    /// Wraps `expr` so that it only yields values passing `validators`,
+    /// using the same checks as `Deserialize`.
+    fn checked(&mut self, expr: String, validators: &[Validator], nullable: bool) -> String {
+        let checks: Vec<String> = validators
+            .iter()
+            .filter_map(|validator| match validator {
+                // Pattern strings are generated from the pattern itself
+                Validator::Pattern(_) => None,
+                Validator::Custom(function) => Some(format!("{function}(value) !== false")),
+                other => Some(format!(
+                    "!({})",
+                    generate_validation_condition(other, "value")
+                )),
+            })
+            .collect();
+        if checks.is_empty() {
+            return expr;
+        }
+        let mut predicate = checks.join(" && ");
+        if nullable {
+            predicate = format!("value == null || ({predicate})");
+        }
+        let satisfying = self.helper("satisfying");
+        format!("{satisfying}(rng, (rng) => {expr}, (value: any) => {predicate})")
+    }
+}
+
+/// `minItems`/`maxItems` options from the array validators.
+fn size_options(validators: &[Validator]) -> Vec<String> {
+    let mut options = Vec::new();
+    for validator in validators {
+        match validator {
+            Validator::MinItems(n) => options.push(format!("minItems: {n}")),
+            Validator::MaxItems(n) => options.push(format!("maxItems: {n}")),
+            Validator::ItemsCount(n) => {
+                options.push(format!("minItems: {n}"));
+                options.push(format!("maxItems: {n}"));
+            }
+            _ => {}
+        }
+    }
+    options
+}
+
+fn push_bounds(options: &mut Vec<String>, min: Option<Bound>, max: Option<Bound>) {
+    if let Some((value, exclusive)) = min {
+        options.push(format!("min: {value}"));
+        if exclusive {
+            options.push("minExclusive: true".to_string());
+        }
+    }
+    if let Some((value, exclusive)) = max {
+        options.push(format!("max: {value}"));
+        if exclusive {
+            options.push("maxExclusive: true".to_string());
+        }
+    }
+}
+
+/// The generator expression for one field.
+fn field_expr(
+    generator: &mut Generator,
+    name: &str,
+    ts_type: &str,
+    decorators: &[DecoratorIR],
+    span: SpanIR,
+) -> Result<String, MacroforgeError> {
+    if let Some(with) = decorators
+        .iter()
+        .filter(|d| d.name.eq_ignore_ascii_case("arbitrary"))
+        .find_map(|d| extract_exact_named_string(d.args_src.trim(), "with"))
+    {
+        return Ok(format!("({with})(rng)"));
+    }
+
+    let validators: Vec<Validator> = SerdeFieldOptions::from_decorators(decorators, name)
+        .options
+        .validators
+        .into_iter()
+        .map(|spec| spec.validator)
+        .collect();
+
+    let expr = generator.expr(ts_type, &validators).map_err(|what| {
+        MacroforgeError::new(
+            span,
+            format!(
+                "@derive(Arbitrary) cannot generate values of {what} for field '{name}'; \
+                 add @arbitrary({{ with: \"(rng) => ...\" }}) to provide a generator"
+            ),
+        )
+    })?;
+    Ok(generator.checked(expr, &validators, is_nullable_type(ts_type)))
+}
+
+/// The object literal properties for `fields`. Optional fields are present
+/// about half the time.
+fn object_properties<'a>(
+    generator: &mut Generator,
+    fields: impl IntoIterator<Item = (&'a str, &'a str, bool, &'a [DecoratorIR], SpanIR)>,
+) -> Result<String, MacroforgeError> {
+    let mut properties = Vec::new();
+    for (name, ts_type, optional, decorators, span) in fields {
+        let expr = field_expr(generator, name, ts_type, decorators, span)?;
+        properties.push(if optional {
+            format!("...(rng.bool() ? {{ {name}: {expr} }} : {{}})")
+        } else {
+            format!("{name}: {expr}")
+        });
+    }
+    Ok(format!("{{ {} }}", properties.join(", ")))
+}
+
+fn interface_properties(
+    generator: &mut Generator,
+    fields: &[InterfaceFieldIR],
+) -> Result<String, MacroforgeError> {
+    object_properties(
+        generator,
+        fields.iter().map(|f| {
+            (
+                f.name.as_str(),
+                f.ts_type.as_str(),
+                f.optional,
+                f.decorators.as_slice(),
+                f.span,
+            )
+        }),
+    )
+}
+
+fn generate_arbitrary_function(type_name: &str, value: &str) -> String {
+    let fn_name = format!("{}Arbitrary", type_name.to_case(Case::Camel));
+    format!("export function {fn_name}(rng: __mf_Random): {type_name} {{\n    return {value};\n}}")
+}
+
+#[ts_macro_derive(
+    Arbitrary,
+    description = "Generates a seeded random value generator for property-based testing",
+    attributes((arbitrary, "Configure generation. Options: with (custom (rng) => value generator)"))
+)]
+pub fn derive_arbitrary_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let mut generator = Generator::default();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let properties = object_properties(
+                &mut generator,
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+            )?;
+            let standalone = generate_arbitrary_function(
+                type_name,
+                &format!("Object.assign(Object.create({type_name}.prototype), {properties})"),
+            );
+            let fn_name = format!("{}Arbitrary", type_name.to_case(Case::Camel));
+            let class_body = body! {
+                static arbitrary(rng: __mf_Random): @{type_name} {
+                    return @{fn_name}(rng);
+                }
+            };
+
+            // The standalone output (no marker) must come FIRST so it defaults to "below" (after class)
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Enum(enum_data) => {
+            if enum_data.variants().is_empty() {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    format!(
+                        "@derive(Arbitrary) on enum '{type_name}' requires at least one variant"
+                    ),
+                ));
+            }
+            let variants = enum_data
+                .variants()
+                .iter()
+                .map(|v| format!("{type_name}.{}", v.name))
+                .collect::<Vec<_>>();
+            TsStream::from_string(generate_arbitrary_function(
+                type_name,
+                &format!("rng.pick([{}])", variants.join(", ")),
+            ))
+        }
+        Data::Interface(interface) => {
+            let properties = interface_properties(&mut generator, interface.fields())?;
+            TsStream::from_string(generate_arbitrary_function(type_name, &properties))
+        }
+        Data::TypeAlias(type_alias) => {
+            let unsupported = |what: &str| {
+                MacroforgeError::new(
+                    input.decorator_span(),
+                    format!(
+                        "@derive(Arbitrary) cannot generate values of {what} in type alias '{type_name}'"
+                    ),
+                )
+            };
+            let value = if let Some(fields) = type_alias.as_object() {
+                interface_properties(&mut generator, fields)?
+            } else if let Some(members) = type_alias.as_union() {
+                let mut options = Vec::new();
+                for member in members {
+                    options.push(match &member.kind {
+                        TypeMemberKind::Literal(literal) => literal.clone(),
+                        TypeMemberKind::TypeRef(ts_type) => generator
+                            .expr(ts_type, &[])
+                            .map_err(|what| unsupported(&what))?,
+                        TypeMemberKind::Object { fields } => {
+                            interface_properties(&mut generator, fields)?
+                        }
+                    });
+                }
+                if members.iter().all(|m| m.is_literal()) {
+                    format!("rng.pick([{}])", options.join(", "))
+                } else {
+                    let options = options
+                        .iter()
+                        .map(|o| format!("(rng) => ({o})"))
+                        .collect::<Vec<_>>();
+                    format!(
+                        "{}(rng, [{}])",
+                        generator.helper("oneOf"),
+                        options.join(", ")
+                    )
+                }
+            } else if let Some(elements) = type_alias.as_tuple() {
+                let items = elements
+                    .iter()
+                    .map(|e| generator.expr(e, &[]))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|what| unsupported(&what))?;
+                format!("[{}]", items.join(", "))
+            } else if let Some(aliased) = type_alias.as_alias() {
+                generator
+                    .expr(aliased, &[])
+                    .map_err(|what| unsupported(&what))?
+            } else {
+                return Err(unsupported("an intersection or complex type"));
+            };
+            TsStream::from_string(generate_arbitrary_function(type_name, &value))
+        }
+    };
+
+    for helper in &generator.helpers {
+        output.add_aliased_import(helper, "macroforge/arbitrary");
+    }
+    output.add_aliased_type_import("Random", "macroforge/arbitrary");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expr(ts_type: &str, validators: &[Validator]) -> String {
+        Generator::default().expr(ts_type, validators).unwrap()
+    }
+
+    #[test]
+    fn test_primitives_and_literals() {
+        assert_eq!(expr("boolean", &[]), "rng.bool()");
+        assert_eq!(expr("string", &[]), "__mf_string(rng)");
+        assert_eq!(
+            expr("\"admin\" | \"member\"", &[]),
+            "rng.pick([\"admin\", \"member\"])"
+        );
+        assert_eq!(
+            expr("Date | null", &[]),
+            "(rng.chance(0.2) ? null : __mf_date(rng))"
+        );
+    }
+
+    #[test]
+    fn test_collections_and_nested_types() {
+        assert_eq!(
+            expr("Address[]", &[Validator::MinItems(1)]),
+            "__mf_array(rng, (rng) => addressArbitrary(rng), { minItems: 1 })"
+        );
+        assert_eq!(
+            expr("Map<string, number>", &[]),
+            "__mf_map(rng, (rng) => __mf_string(rng), (rng) => __mf_number(rng))"
+        );
+        assert_eq!(
+            expr("Record<\"a\" | \"b\", boolean>", &[]),
+            "{ [\"a\"]: rng.bool(), [\"b\"]: rng.bool() }"
+        );
+        assert_eq!(
+            expr("[string, number]", &[]),
+            "[__mf_string(rng), __mf_number(rng)]"
+        );
+        assert!(Generator::default().expr("Foo<Bar>", &[]).is_err());
+        assert!(Generator::default().expr("{ x: number }", &[]).is_err());
+    }
+
+    #[test]
+    fn test_string_validators() {
+        assert_eq!(
+            expr("string", &[Validator::Email]),
+            "__mf_string(rng, { format: \"email\" })"
+        );
+        assert_eq!(
+            expr(
+                "string",
+                &[
+                    Validator::MinLength(2),
+                    Validator::MaxLength(50),
+                    Validator::NonEmpty
+                ]
+            ),
+            "__mf_string(rng, { minLength: 2, maxLength: 50 })"
+        );
+        assert_eq!(
+            expr("string", &[Validator::Pattern("^\\d+$".into())]),
+            "__mf_string(rng, { pattern: \"^\\\\d+$\" })"
+        );
+    }
+
+    #[test]
+    fn test_number_bounds_keep_tightest() {
+        assert_eq!(
+            expr(
+                "number",
+                &[
+                    Validator::Between(0.0, 100.0),
+                    Validator::Positive,
+                    Validator::Int
+                ]
+            ),
+            "__mf_number(rng, { int: true, min: 0, minExclusive: true, max: 100 })"
+        );
+        assert_eq!(
+            expr("number", &[Validator::Uint8]),
+            "__mf_number(rng, { int: true, min: 0, max: 255 })"
+        );
+    }
+
+    #[test]
+    fn test_checked_reuses_deserialize_conditions() {
+        let mut generator = Generator::default();
+        let validators = [Validator::MaxLength(5), Validator::Custom("isSlug".into())];
+        let checked = generator.checked("__mf_string(rng)".into(), &validators, false);
+        assert_eq!(
+            checked,
+            "__mf_satisfying(rng, (rng) => __mf_string(rng), (value: any) => !(value.length > 5) && isSlug(value) !== false)"
+        );
+        assert!(generator.helpers.contains("satisfying"));
+        assert_eq!(
+            generator.checked("x".into(), &[Validator::Pattern("a".into())], false),
+            "x"
+        );
+    }
+
+    #[test]
+    fn test_optional_fields_and_custom_generators() {
+        let with = DecoratorIR {
+            name: "arbitrary".into(),
+            args_src: "{ with: \"genPoint\" }".into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        let code = object_properties(
+            &mut Generator::default(),
+            [
+                ("nick", "string", true, &[][..], SpanIR::new(0, 0)),
+                (
+                    "point",
+                    "{ x: number }",
+                    false,
+                    std::slice::from_ref(&with),
+                    SpanIR::new(0, 0),
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            code,
+            "{ ...(rng.bool() ? { nick: __mf_string(rng) } : {}), point: (genPoint)(rng) }"
+        );
+    }
+}
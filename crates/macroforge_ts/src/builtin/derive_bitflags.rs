@@ -0,0 +1,329 @@
+//! # Bitflags Macro Implementation
+//!
+//! The `Bitflags` macro treats a numeric enum as a set of flags, like Rust's
+//! `bitflags` crate. Every variant must be a distinct power of two, so any
+//! combination of variants can be stored in a single number.
+//!
+//! ## Generated Output
+//!
+//! For an enum `Permission`:
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `permissionHas(value, flags)` | Whether every flag in `flags` is set |
+//! | `permissionAdd(value, flags)` | `value` with `flags` set |
+//! | `permissionRemove(value, flags)` | `value` with `flags` cleared |
+//! | `permissionToggle(value, flags)` | `value` with `flags` flipped |
+//! | `permissionToArray(value)` | The individual flags that are set |
+//! | `permissionFromArray(flags)` | Combines flags into one value |
+//! | `permissionFormat(value)` | `"Read \| Write"`; unknown bits as hex, `"0"` when empty |
+//!
+//! All helpers return new values; numbers are immutable anyway.
+//!
+//! ## Validation
+//!
+//! Each variant must have a numeric value that is a power of two between
+//! `1` and `2^30` (JavaScript bitwise operators work on 32-bit signed
+//! integers), and no two variants may share a value. Offending variants are
+//! reported at their own span.
+//!
+//! ## Integration
+//!
+//! - `Debug` formats the value with `permissionFormat`, e.g. `Read | Write`
+//! - `Serialize` writes the number by default, or an array of flag names with
+//!   `@bitflags({ serializeAs: "names" })`
+//! - `Deserialize` accepts either form and rejects unknown bits
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Bitflags, Debug) */
+//! enum Permission {
+//!     Read = 1,
+//!     Write = 2,
+//!     Execute = 4,
+//! }
+//!
+//! const rw = permissionAdd(Permission.Read, Permission.Write);
+//! permissionHas(rw, Permission.Write); // true
+//! permissionFormat(rw); // "Read | Write"
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::extract_exact_named_string;
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, EnumValue, EnumVariantIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Largest flag value; `1 << 31` is negative in JavaScript.
+const MAX_FLAG: f64 = (1u32 << 30) as f64;
+
+/// Options parsed from the `@bitflags` container decorator.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct BitflagsOptions {
+    /// Serialize as an array of flag names instead of a number.
+    pub serialize_names: bool,
+}
+
+impl BitflagsOptions {
+    pub(crate) fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("bitflags") {
+                continue;
+            }
+            if let Some(mode) = extract_exact_named_string(decorator.args_src.trim(), "serializeAs")
+            {
+                opts.serialize_names = mode == "names";
+            }
+        }
+        opts
+    }
+}
+
+/// Resolves each variant's flag value, reporting variants that are not
+/// distinct powers of two.
+fn resolve_flags(
+    variants: &[EnumVariantIR],
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<(String, u32)> {
+    let mut flags: Vec<(String, u32)> = Vec::new();
+    // Value of the previous variant, for auto-incremented members
+    let mut previous: Option<f64> = None;
+    for variant in variants {
+        let value = match &variant.value {
+            EnumValue::Number(n) => Some(*n),
+            EnumValue::Auto => Some(previous.map_or(0.0, |p| p + 1.0)),
+            EnumValue::String(_) | EnumValue::Expr(_) => None,
+        };
+        previous = value;
+
+        let Some(value) = value else {
+            diagnostics.error(
+                variant.span,
+                format!(
+                    "@derive(Bitflags) variant '{}' must have a numeric literal value",
+                    variant.name
+                ),
+            );
+            continue;
+        };
+        let is_flag = value.fract() == 0.0
+            && (1.0..=MAX_FLAG).contains(&value)
+            && (value as u32).is_power_of_two();
+        if !is_flag {
+            diagnostics.error(
+                variant.span,
+                format!(
+                    "@derive(Bitflags) variant '{}' has value {value}, which is not a power of two between 1 and 2^30",
+                    variant.name
+                ),
+            );
+            continue;
+        }
+        let flag = value as u32;
+        if let Some((other, _)) = flags.iter().find(|(_, f)| *f == flag) {
+            diagnostics.error(
+                variant.span,
+                format!(
+                    "@derive(Bitflags) variant '{}' has the same value as '{other}'",
+                    variant.name
+                ),
+            );
+            continue;
+        }
+        flags.push((variant.name.clone(), flag));
+    }
+    flags
+}
+
+fn generate_bitflags(enum_name: &str, flag_names: &[String]) -> String {
+    let prefix = enum_name.to_case(Case::Camel);
+    let members = flag_names
+        .iter()
+        .map(|name| format!("{enum_name}.{name}"))
+        .collect::<Vec<_>>();
+    let named_members = flag_names
+        .iter()
+        .zip(&members)
+        .map(|(name, member)| format!("[{member}, {}]", serde_json::to_string(name).unwrap()))
+        .collect::<Vec<_>>();
+    let members = members.join(", ");
+    let named_members = named_members.join(", ");
+
+    format!(
+        r#"export function {prefix}Has(value: {enum_name}, flags: {enum_name}): boolean {{
+    return (value & flags) === flags;
+}}
+
+export function {prefix}Add(value: {enum_name}, flags: {enum_name}): {enum_name} {{
+    return value | flags;
+}}
+
+export function {prefix}Remove(value: {enum_name}, flags: {enum_name}): {enum_name} {{
+    return value & ~flags;
+}}
+
+export function {prefix}Toggle(value: {enum_name}, flags: {enum_name}): {enum_name} {{
+    return value ^ flags;
+}}
+
+export function {prefix}ToArray(value: {enum_name}): {enum_name}[] {{
+    return [{members}].filter((flag) => (value & flag) === flag);
+}}
+
+export function {prefix}FromArray(flags: readonly {enum_name}[]): {enum_name} {{
+    let value = 0;
+    for (const flag of flags) {{
+        value |= flag;
+    }}
+    return value;
+}}
+
+export function {prefix}Format(value: {enum_name}): string {{
+    const names: string[] = [];
+    let rest: number = value;
+    for (const [flag, name] of [{named_members}] as const) {{
+        if ((value & flag) === flag) {{
+            names.push(name);
+            rest &= ~flag;
+        }}
+    }}
+    if (rest !== 0) {{
+        names.push("0x" + (rest >>> 0).toString(16));
+    }}
+    return names.length > 0 ? names.join(" | ") : "0";
+}}"#
+    )
+}
+
+#[ts_macro_derive(
+    Bitflags,
+    description = "Generates flag-set helpers for numeric enums whose variants are powers of two",
+    attributes((bitflags, "Configure the flags. Options: serializeAs (\"number\" or \"names\")"))
+)]
+pub fn derive_bitflags_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let enum_name = input.name();
+
+    let Data::Enum(enum_data) = &input.data else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(Bitflags) can only be used on numeric enums",
+        ));
+    };
+    if enum_data.variants().is_empty() {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!("@derive(Bitflags) on enum '{enum_name}' requires at least one variant"),
+        ));
+    }
+
+    let mut diagnostics = DiagnosticCollector::new();
+    let flags = resolve_flags(enum_data.variants(), &mut diagnostics);
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    let flag_names = flags.into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+    Ok(TsStream::from_string(generate_bitflags(
+        enum_name,
+        &flag_names,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn variant(name: &str, value: EnumValue, start: u32) -> EnumVariantIR {
+        EnumVariantIR {
+            name: name.into(),
+            span: SpanIR::new(start, start + 1),
+            value,
+            decorators: vec![],
+        }
+    }
+
+    #[test]
+    fn test_resolve_flags_accepts_powers_of_two() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let flags = resolve_flags(
+            &[
+                variant("Read", EnumValue::Number(1.0), 0),
+                variant("Write", EnumValue::Auto, 1),
+                variant("Execute", EnumValue::Number(4.0), 2),
+            ],
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty());
+        assert_eq!(
+            flags,
+            [
+                ("Read".to_string(), 1),
+                ("Write".to_string(), 2),
+                ("Execute".to_string(), 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_flags_reports_offending_variants() {
+        let mut diagnostics = DiagnosticCollector::new();
+        resolve_flags(
+            &[
+                variant("None", EnumValue::Auto, 0),
+                variant("Read", EnumValue::Number(1.0), 10),
+                variant("Both", EnumValue::Number(3.0), 20),
+                variant("Alias", EnumValue::Number(1.0), 30),
+                variant("Named", EnumValue::String("x".into()), 40),
+                variant("Huge", EnumValue::Number(2147483648.0), 50),
+            ],
+            &mut diagnostics,
+        );
+        let diagnostics = diagnostics.into_vec();
+        let starts = diagnostics
+            .iter()
+            .map(|d| d.span.unwrap().start)
+            .collect::<Vec<_>>();
+        assert_eq!(starts, [0, 20, 30, 40, 50]);
+        assert!(diagnostics[2].message.contains("same value as 'Read'"));
+    }
+
+    #[test]
+    fn test_options() {
+        let decorator = |args: &str| DecoratorIR {
+            name: "bitflags".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        assert!(
+            BitflagsOptions::from_decorators(&[decorator("{ serializeAs: \"names\" }")])
+                .serialize_names
+        );
+        assert!(
+            !BitflagsOptions::from_decorators(&[decorator("{ serializeAs: \"number\" }")])
+                .serialize_names
+        );
+        assert!(!BitflagsOptions::from_decorators(&[]).serialize_names);
+    }
+
+    #[test]
+    fn test_generate_bitflags() {
+        let code = generate_bitflags("Permission", &["Read".into(), "Write".into()]);
+        assert!(code.contains(
+            "return [Permission.Read, Permission.Write].filter((flag) => (value & flag) === flag);"
+        ));
+        assert!(
+            code.contains("[[Permission.Read, \"Read\"], [Permission.Write, \"Write\"]] as const")
+        );
+        assert!(code.contains(
+            "export function permissionToggle(value: Permission, flags: Permission): Permission"
+        ));
+    }
+}
@@ -0,0 +1,269 @@
+//! # Branded Macro Implementation
+//!
+//! The `Branded` macro turns a primitive type alias into a nominal type.
+//! `type UserId = string` accepts any string; with `@derive(Branded)` the
+//! alias is rewritten to `string & { readonly __brand: "UserId" }`, so only
+//! values that went through one of the generated constructors type-check.
+//!
+//! ## Generated Output
+//!
+//! For an alias `UserId`:
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `userIdParse(raw)` | Checks the base type and validators; `{ success: true, value }` or `{ success: false, errors }` |
+//! | `userIdUnsafe(raw)` | Brands a value without checking it |
+//! | `userIdIs(raw)` | Whether `userIdParse` would succeed |
+//!
+//! With the convenience const these are available as `UserId.parse`,
+//! `UserId.unsafe` and `UserId.is`.
+//!
+//! ## Validation
+//!
+//! Validators from `@serde({ validate: [...] })` on the alias run inside
+//! `parse`, with the same messages as in `Deserialize`. The base type must
+//! be `string`, `number`, `bigint` or `boolean`, and the alias cannot be
+//! generic.
+//!
+//! ## Serde Integration
+//!
+//! Fields whose type is a branded alias are deserialized through its
+//! `parse`, so invalid values are reported like any other validation error.
+//! The alias gets `userIdDeserializeWithContext` and
+//! `userIdSerializeWithContext` for this unless it derives `Deserialize` or
+//! `Serialize` itself, in which case those derives check the brand.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Branded) @serde({ validate: ["uuid"] }) */
+//! export type UserId = string;
+//!
+//! const id = UserId.parse(request.params.id);
+//! if (id.success) {
+//!     loadUser(id.value); // loadUser(id: UserId)
+//! }
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::derives_macro;
+use crate::builtin::return_types::{
+    DESERIALIZE_ERROR, SERIALIZE_CONTEXT, deserialize_return_type, wrap_error, wrap_success,
+};
+use crate::builtin::serde::derive_deserialize::{
+    generate_validation_condition, get_validator_message,
+};
+use crate::builtin::serde::{SerdeFieldOptions, Validator, ValidatorSpec};
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Base types a brand can be applied to.
+const BRANDABLE_TYPES: &[&str] = &["string", "number", "bigint", "boolean"];
+
+/// The rewritten alias declaration.
+fn branded_declaration(type_name: &str, base: &str) -> String {
+    format!(
+        "type {type_name} = {base} & {{ readonly __brand: {} }};",
+        js_string(type_name)
+    )
+}
+
+/// Runs the serde validators on `raw`, collecting failures in `errors`.
+fn validations(validators: &[ValidatorSpec]) -> String {
+    validators
+        .iter()
+        .map(|spec| {
+            let condition = match &spec.validator {
+                Validator::Custom(function) => format!("{function}(raw) === false"),
+                other => generate_validation_condition(other, "raw"),
+            };
+            let message = spec
+                .custom_message
+                .clone()
+                .unwrap_or_else(|| get_validator_message(&spec.validator));
+            format!(
+                "\n    if ({condition}) {{\n        errors.push({{ field: \"_root\", message: {} }});\n    }}",
+                js_string(&message)
+            )
+        })
+        .collect()
+}
+
+fn generate_branded(type_name: &str, base: &str, validators: &[ValidatorSpec]) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let return_type = deserialize_return_type(type_name);
+    let type_error = wrap_error(&format!(
+        "[{{ field: \"_root\", message: {} }}]",
+        js_string(&format!("must be a {base}"))
+    ));
+    let errors = wrap_error("errors");
+    let success = wrap_success(&format!("raw as {type_name}"));
+    let checks = validations(validators);
+
+    format!(
+        r#"export function {prefix}Parse(raw: unknown): {return_type} {{
+    if (typeof raw !== "{base}") {{
+        return {type_error};
+    }}
+    const errors: Array<{{ field: string; message: string }}> = [];{checks}
+    if (errors.length > 0) {{
+        return {errors};
+    }}
+    return {success};
+}}
+
+export function {prefix}Unsafe(raw: {base}): {type_name} {{
+    return raw as {type_name};
+}}
+
+export function {prefix}Is(raw: unknown): raw is {type_name} {{
+    return {prefix}Parse(raw).success;
+}}"#
+    )
+}
+
+/// Serde entry points for aliases that do not derive `Serialize` or
+/// `Deserialize` themselves.
+fn generate_serde_functions(type_name: &str, serialize: bool, deserialize: bool) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let mut functions = Vec::new();
+    if deserialize {
+        functions.push(format!(
+            r#"export function {prefix}DeserializeWithContext(value: unknown, _ctx?: unknown): {type_name} {{
+    const result = {prefix}Parse(value);
+    if (!result.success) {{
+        throw new {DESERIALIZE_ERROR}(result.errors);
+    }}
+    return result.value;
+}}"#
+        ));
+    }
+    if serialize {
+        functions.push(format!(
+            r#"export function {prefix}SerializeWithContext(value: {type_name}, _ctx?: {SERIALIZE_CONTEXT}): unknown {{
+    return value;
+}}"#
+        ));
+    }
+    functions.join("\n\n")
+}
+
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[ts_macro_derive(
+    Branded,
+    description = "Turns a primitive type alias into a nominal type with parse, unsafe and is constructors",
+    attributes((serde, "Validators run by parse. Options: validate"))
+)]
+pub fn derive_branded_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+
+    let Data::TypeAlias(type_alias) = &input.data else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(Branded) can only be used on type aliases, e.g. `type UserId = string`",
+        ));
+    };
+    if !type_alias.type_params().is_empty() {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!("@derive(Branded) does not support generic type aliases like '{type_name}'"),
+        ));
+    }
+    let Some(base) = type_alias
+        .as_alias()
+        .map(str::trim)
+        .filter(|base| BRANDABLE_TYPES.contains(base))
+    else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!(
+                "@derive(Branded) on '{type_name}' requires an alias of string, number, bigint or boolean"
+            ),
+        ));
+    };
+
+    let decorators = &type_alias.inner.decorators;
+    let serde = SerdeFieldOptions::from_decorators(decorators, type_name);
+    if serde.diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(serde.diagnostics.into_vec()).into());
+    }
+
+    let serialize = !derives_macro(decorators, "Serialize");
+    let deserialize = !derives_macro(decorators, "Deserialize");
+    let mut source = generate_branded(type_name, base, &serde.options.validators);
+    if serialize || deserialize {
+        source.push_str("\n\n");
+        source.push_str(&generate_serde_functions(type_name, serialize, deserialize));
+    }
+
+    let mut output = TsStream::from_string(source);
+    output.replace_span(
+        type_alias.inner.span,
+        &branded_declaration(type_name, base),
+        "Branded",
+    );
+    if deserialize {
+        output.add_aliased_import("DeserializeError", "macroforge/serde");
+    }
+    if serialize {
+        output.add_aliased_type_import("SerializeContext", "macroforge/serde");
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branded_declaration() {
+        assert_eq!(
+            branded_declaration("UserId", "string"),
+            r#"type UserId = string & { readonly __brand: "UserId" };"#
+        );
+    }
+
+    #[test]
+    fn test_generate_branded_runs_validators() {
+        let code = generate_branded(
+            "UserId",
+            "string",
+            &[
+                ValidatorSpec {
+                    validator: Validator::MaxLength(36),
+                    custom_message: None,
+                },
+                ValidatorSpec {
+                    validator: Validator::Custom("isKnownId".into()),
+                    custom_message: Some("unknown id".into()),
+                },
+            ],
+        );
+        assert!(code.contains("if (typeof raw !== \"string\") {"));
+        assert!(code.contains("if (raw.length > 36) {"));
+        assert!(code.contains(
+            "if (isKnownId(raw) === false) {\n        errors.push({ field: \"_root\", message: \"unknown id\" });"
+        ));
+        assert!(code.contains("return { success: true, value: raw as UserId };"));
+        assert!(code.contains("export function userIdUnsafe(raw: string): UserId {"));
+        assert!(code.contains("export function userIdIs(raw: unknown): raw is UserId {"));
+    }
+
+    #[test]
+    fn test_generate_serde_functions() {
+        let code = generate_serde_functions("UserId", false, true);
+        assert!(code.contains("throw new __mf_DeserializeError(result.errors);"));
+        assert!(!code.contains("userIdSerializeWithContext"));
+        assert!(
+            generate_serde_functions("UserId", true, false)
+                .contains("export function userIdSerializeWithContext(")
+        );
+    }
+}
@@ -0,0 +1,674 @@
+//! # Builder Macro Implementation
+//!
+//! The `Builder` macro generates a typed fluent builder for classes, interfaces
+//! and object type aliases. Each field gets a setter that returns the builder,
+//! and `build()` assembles the final value.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `ClassNameBuilder` + `classNameBuilder()` + `static builder()` | Builder class, standalone factory and static wrapper |
+//! | Interface | `InterfaceNameBuilder` + `interfaceNameBuilder()` | Builder class and standalone factory |
+//! | Type Alias | `TypeNameBuilder` + `typeNameBuilder()` | Builder class and standalone factory (object types only) |
+//!
+//! ## Required Fields
+//!
+//! The builder tracks which fields have been set in a type parameter. A field
+//! is required when it is neither optional nor given a `@default`, and
+//! `build()` only type-checks once every required field has been set:
+//!
+//! ```typescript
+//! User.builder().name("Ada").build();
+//! //             ~~~~~~~~~~~~~~~~~~~~ error: missingRequiredFields: "email"
+//! ```
+//!
+//! Tracking follows the chain of calls, so keep the builder in a single
+//! expression (or reassign it) rather than calling setters as statements.
+//!
+//! ## Defaults
+//!
+//! Fields with a `@default(...)` (the same options the `Default` derive reads,
+//! including `factory`/`from`) are optional in the builder and fall back to
+//! that value when no setter was called.
+//!
+//! ## Options
+//!
+//! Container-level `@builder`:
+//!
+//! - `validate` - Run the `Deserialize` validators in `build()` and throw a
+//!   `DeserializeError` if any fail (requires `@derive(Deserialize)`)
+//!
+//! Field-level `@builder`:
+//!
+//! - `skip` - Don't generate a setter (the field must be optional or have a `@default`)
+//!
+//! Non-public class fields never get a setter.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Builder) */
+//! class User {
+//!     name: string;
+//!     email: string;
+//!     /** @default("member") */
+//!     role: string;
+//!     nickname?: string;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! class User {
+//!     name: string;
+//!     email: string;
+//!     role: string;
+//!     nickname?: string;
+//!
+//!     static builder(): UserBuilder {
+//!         return userBuilder();
+//!     }
+//! }
+//!
+//! export class UserBuilder<__Set extends string = never> {
+//!     private readonly __values: { -readonly [__K in keyof User]?: User[__K] } = {};
+//!
+//!     name(value: string): UserBuilder<__Set | "name"> {
+//!         this.__values.name = value;
+//!         return this as unknown as UserBuilder<__Set | "name">;
+//!     }
+//!
+//!     // ... email, role and nickname setters
+//!
+//!     build(
+//!         this: [Exclude<"name" | "email", __Set>] extends [never]
+//!             ? UserBuilder<__Set>
+//!             : { missingRequiredFields: Exclude<"name" | "email", __Set> }
+//!     ): User {
+//!         const values = (this as unknown as UserBuilder<__Set>).__values;
+//!         const resolved = {
+//!             ...values,
+//!             role: values.role !== undefined ? values.role : "member",
+//!         } as User;
+//!         return Object.assign(new User(), resolved);
+//!     }
+//! }
+//!
+//! export function userBuilder(): UserBuilder {
+//!     return new UserBuilder();
+//! }
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    DefaultFieldOptions, TypeParams, derives_macro, has_flag, target_decorators,
+};
+use crate::builtin::return_types::DESERIALIZE_ERROR;
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, SpanIR, Visibility};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Options parsed from `@builder` decorators
+#[derive(Default, Clone)]
+struct BuilderOptions {
+    /// Field level: don't generate a setter
+    skip: bool,
+    /// Container level: run Deserialize validators in `build()`
+    validate: bool,
+}
+
+impl BuilderOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("builder") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if has_flag(args, "skip") {
+                opts.skip = true;
+            }
+            if has_flag(args, "validate") {
+                opts.validate = true;
+            }
+        }
+        opts
+    }
+}
+
+/// A field the builder sets or fills in with its default.
+struct BuilderField {
+    name: String,
+    ts_type: String,
+    /// Must be set before `build()` type-checks
+    required: bool,
+    /// Expression used when the setter wasn't called
+    default: Option<String>,
+    /// Whether the builder exposes a setter (false for `@builder(skip)`)
+    setter: bool,
+}
+
+/// How `build()` turns the collected values into the target type.
+enum BuildTarget {
+    /// `Object.assign(new ClassName(), resolved)`
+    Class,
+    /// Return the resolved object literal
+    Object,
+}
+
+/// Collects the builder fields, along with any factory imports their defaults need.
+fn collect_builder_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, bool, &'a [DecoratorIR], SpanIR)>,
+    imports: &mut Vec<(String, String)>,
+) -> Result<Vec<BuilderField>, MacroforgeError> {
+    let mut builder_fields = Vec::new();
+    for (name, ts_type, optional, decorators, span) in fields {
+        let default_opts = DefaultFieldOptions::from_decorators(decorators);
+        let opts = BuilderOptions::from_decorators(decorators);
+
+        if name == "build" {
+            return Err(MacroforgeError::new(
+                span,
+                "@derive(Builder): a field named 'build' conflicts with the builder's build() method",
+            ));
+        }
+
+        if opts.skip {
+            if !optional && default_opts.value.is_none() {
+                return Err(MacroforgeError::new(
+                    span,
+                    format!(
+                        "@derive(Builder): '{name}' is skipped but required. Make it optional or add @default(value)"
+                    ),
+                ));
+            }
+            if default_opts.value.is_none() {
+                continue;
+            }
+        }
+
+        if let Some(import) = default_opts.factory_import()
+            && !imports.contains(&import)
+        {
+            imports.push(import);
+        }
+
+        builder_fields.push(BuilderField {
+            name: name.to_string(),
+            ts_type: ts_type.to_string(),
+            required: !optional && default_opts.value.is_none(),
+            default: default_opts.value,
+            setter: !opts.skip,
+        });
+    }
+    Ok(builder_fields)
+}
+
+/// Generates the builder class for `type_name`.
+///
+/// `validate_call` is the expression (taking `resolved`) that returns the
+/// Deserialize validation errors, when validation is enabled. A generic
+/// target's builder takes its type parameters before `__Set`.
+fn generate_builder_class(
+    type_name: &str,
+    type_params: &TypeParams,
+    fields: &[BuilderField],
+    target: BuildTarget,
+    validate_call: Option<&str>,
+) -> String {
+    let builder_name = format!("{type_name}Builder");
+    let target_type = type_params.apply(type_name);
+    // `UserBuilder<__Set>`, or `BoxBuilder<T, __Set>` for a generic target
+    let type_args = type_params
+        .names()
+        .iter()
+        .map(|name| format!("{name}, "))
+        .collect::<String>();
+    let builder_type = |set: &str| format!("{builder_name}<{type_args}{set}>");
+    let builder_params = type_params
+        .params
+        .iter()
+        .map(|param| format!("{param}, "))
+        .collect::<String>();
+
+    let setters = fields
+        .iter()
+        .filter(|f| f.setter)
+        .map(|f| {
+            let name = &f.name;
+            let next = builder_type(&format!("__Set | \"{name}\""));
+            format!(
+                "{name}(value: {ts_type}): {next} {{\n        this.__values.{name} = value;\n        return this as unknown as {next};\n    }}",
+                ts_type = f.ts_type
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n    ");
+
+    let required = fields
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| format!("\"{}\"", f.name))
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    let this_param = if required.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "this: [Exclude<{required}, __Set>] extends [never] ? {} : {{ missingRequiredFields: Exclude<{required}, __Set> }}",
+            builder_type("__Set")
+        )
+    };
+
+    let defaults = fields
+        .iter()
+        .filter_map(|f| {
+            f.default.as_ref().map(|default| {
+                format!(
+                    "\n            {name}: values.{name} !== undefined ? values.{name} : {default},",
+                    name = f.name
+                )
+            })
+        })
+        .collect::<String>();
+
+    let validation = match validate_call {
+        Some(call) => format!(
+            "\n        const errors = {call};\n        if (errors.length > 0) {{\n            throw new {DESERIALIZE_ERROR}(errors);\n        }}"
+        ),
+        None => String::new(),
+    };
+
+    let result = match target {
+        BuildTarget::Class => format!("Object.assign(new {target_type}(), resolved)"),
+        BuildTarget::Object => "resolved".to_string(),
+    };
+
+    format!(
+        "export class {builder_name}<{builder_params}__Set extends string = never> {{
+    private readonly __values: {{ -readonly [__K in keyof {target_type}]?: {target_type}[__K] }} = {{}};
+
+    {setters}
+
+    build({this_param}): {target_type} {{
+        const values = (this as unknown as {set_builder}).__values;
+        const resolved = {{
+            ...values,{defaults}
+        }} as {target_type};{validation}
+        return {result};
+    }}
+}}
+
+export function {camel_name}Builder{decl}(): {new_builder} {{
+    return new {new_builder}();
+}}",
+        camel_name = type_name.to_case(Case::Camel),
+        set_builder = builder_type("__Set"),
+        decl = type_params.decl(),
+        new_builder = type_params.apply(&builder_name),
+    )
+}
+
+#[ts_macro_derive(
+    Builder,
+    description = "Generates a typed fluent builder with required-field tracking",
+    attributes((builder, "Configure the builder. Container: validate (run Deserialize validators in build()). Field: skip"))
+)]
+pub fn derive_builder_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let container_opts = BuilderOptions::from_decorators(&container_decorators);
+
+    if container_opts.validate && !derives_macro(target_decorators(&input), "Deserialize") {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@builder({ validate: true }) requires @derive(Deserialize) to provide the validators",
+        ));
+    }
+
+    let type_name = input.name();
+    let camel_name = type_name.to_case(Case::Camel);
+    let (type_params, _) = TypeParams::declared(&input.context.target_source, type_name);
+    let mut imports: Vec<(String, String)> = Vec::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            if let Some(ctor) = class.method("constructor")
+                && !ctor.params_src.trim().is_empty()
+            {
+                return Err(MacroforgeError::new(
+                    ctor.span,
+                    format!(
+                        "@derive(Builder) cannot be used on class '{type_name}' with constructor parameters. \
+                        build() needs to call `new {type_name}()`."
+                    ),
+                ));
+            }
+
+            let fields = collect_builder_fields(
+                class
+                    .fields()
+                    .iter()
+                    .filter(|f| f.visibility == Visibility::Public)
+                    .map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.optional,
+                            f.decorators.as_slice(),
+                            f.span,
+                        )
+                    }),
+                &mut imports,
+            )?;
+
+            let validate_call = format!("{type_name}.validateFields(resolved)");
+            let builder = generate_builder_class(
+                type_name,
+                &type_params,
+                &fields,
+                BuildTarget::Class,
+                container_opts.validate.then_some(validate_call.as_str()),
+            );
+
+            // Static members can't use the class's type parameters, so a
+            // generic class's `builder()` declares its own
+            let builder_type = type_params.apply(&format!("{type_name}Builder"));
+            let fn_name = format!("{camel_name}Builder");
+            let fn_args = type_params.args();
+            let decl = type_params.decl();
+            let class_body = body! {
+                static builder@{decl}(): @{builder_type} {
+                    return @{fn_name}@{fn_args}();
+                }
+            };
+
+            // The standalone output (no marker) must come FIRST so it defaults to "below" (after class)
+            let combined_source = format!("{}\n{}", builder, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = collect_builder_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &mut imports,
+            )?;
+
+            let validate_call = format!("{camel_name}ValidateFields(resolved)");
+            TsStream::from_string(generate_builder_class(
+                type_name,
+                &type_params,
+                &fields,
+                BuildTarget::Object,
+                container_opts.validate.then_some(validate_call.as_str()),
+            ))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_object() => {
+            let fields = collect_builder_fields(
+                type_alias.as_object().unwrap().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &mut imports,
+            )?;
+
+            let validate_call = format!("{camel_name}ValidateFields(resolved)");
+            TsStream::from_string(generate_builder_class(
+                type_name,
+                &type_params,
+                &fields,
+                BuildTarget::Object,
+                container_opts.validate.then_some(validate_call.as_str()),
+            ))
+        }
+        Data::TypeAlias(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                format!(
+                    "@derive(Builder) on type '{type_name}' requires an object type; unions and other aliases have no fields to set"
+                ),
+            ));
+        }
+        Data::Enum(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(Builder) can only be used on classes, interfaces and object type aliases",
+            ));
+        }
+    };
+
+    if container_opts.validate {
+        output.add_aliased_import("DeserializeError", "macroforge/serde");
+    }
+    for (specifier, module) in &imports {
+        output.add_import(specifier, module);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    /// Builder fields and the imports they need.
+    type Collected = (Vec<BuilderField>, Vec<(String, String)>);
+
+    fn collect(
+        fields: &[(&str, &str, bool, Vec<DecoratorIR>)],
+    ) -> Result<Collected, MacroforgeError> {
+        let mut imports = Vec::new();
+        let builder_fields = collect_builder_fields(
+            fields.iter().map(|(name, ts_type, optional, decs)| {
+                (
+                    *name,
+                    *ts_type,
+                    *optional,
+                    decs.as_slice(),
+                    SpanIR::new(0, 0),
+                )
+            }),
+            &mut imports,
+        )?;
+        Ok((builder_fields, imports))
+    }
+
+    #[test]
+    fn test_required_fields_exclude_optional_and_defaulted() {
+        let (fields, _) = collect(&[
+            ("name", "string", false, vec![]),
+            (
+                "role",
+                "string",
+                false,
+                vec![make_decorator("default", r#""member""#)],
+            ),
+            ("nickname", "string", true, vec![]),
+        ])
+        .unwrap();
+
+        let required: Vec<_> = fields
+            .iter()
+            .filter(|f| f.required)
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(required, vec!["name"]);
+        assert_eq!(fields[1].default.as_deref(), Some(r#""member""#));
+    }
+
+    #[test]
+    fn test_factory_default_records_import() {
+        let (fields, imports) = collect(&[(
+            "id",
+            "string",
+            false,
+            vec![make_decorator(
+                "default",
+                r#"{ factory: "nanoid", from: "nanoid" }"#,
+            )],
+        )])
+        .unwrap();
+        assert_eq!(fields[0].default.as_deref(), Some("nanoid()"));
+        assert_eq!(imports, vec![("nanoid".to_string(), "nanoid".to_string())]);
+    }
+
+    #[test]
+    fn test_skipped_required_field_errors() {
+        let result = collect(&[(
+            "secret",
+            "string",
+            false,
+            vec![make_decorator("builder", "skip")],
+        )]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_skipped_optional_field_has_no_setter() {
+        let (fields, _) = collect(&[
+            ("name", "string", false, vec![]),
+            (
+                "cache",
+                "string",
+                true,
+                vec![make_decorator("builder", "skip")],
+            ),
+        ])
+        .unwrap();
+        assert_eq!(fields.len(), 1);
+    }
+
+    #[test]
+    fn test_skipped_defaulted_field_keeps_default_without_setter() {
+        let (fields, _) = collect(&[(
+            "createdAt",
+            "Date",
+            false,
+            vec![
+                make_decorator("builder", "skip"),
+                make_decorator("default", "new Date()"),
+            ],
+        )])
+        .unwrap();
+        let code = generate_builder_class(
+            "Post",
+            &TypeParams::default(),
+            &fields,
+            BuildTarget::Object,
+            None,
+        );
+        assert!(!code.contains("createdAt(value"));
+        assert!(code.contains(
+            "createdAt: values.createdAt !== undefined ? values.createdAt : new Date(),"
+        ));
+    }
+
+    #[test]
+    fn test_field_named_build_errors() {
+        assert!(collect(&[("build", "number", false, vec![])]).is_err());
+    }
+
+    #[test]
+    fn test_builder_class_tracks_required_fields() {
+        let (fields, _) = collect(&[
+            ("name", "string", false, vec![]),
+            ("email", "string", false, vec![]),
+            (
+                "role",
+                "string",
+                false,
+                vec![make_decorator("default", r#""member""#)],
+            ),
+        ])
+        .unwrap();
+        let code = generate_builder_class(
+            "User",
+            &TypeParams::default(),
+            &fields,
+            BuildTarget::Class,
+            None,
+        );
+
+        assert!(code.contains("export class UserBuilder<__Set extends string = never>"));
+        assert!(code.contains("name(value: string): UserBuilder<__Set | \"name\">"));
+        assert!(code.contains("[Exclude<\"name\" | \"email\", __Set>] extends [never]"));
+        assert!(code.contains("role: values.role !== undefined ? values.role : \"member\","));
+        assert!(code.contains("return Object.assign(new User(), resolved);"));
+        assert!(code.contains("export function userBuilder(): UserBuilder"));
+    }
+
+    #[test]
+    fn test_builder_for_generic_target() {
+        let (fields, _) = collect(&[("value", "T", false, vec![])]).unwrap();
+        let (type_params, _) = TypeParams::declared("class Box<T extends object = {}> {}", "Box");
+        let code = generate_builder_class("Box", &type_params, &fields, BuildTarget::Class, None);
+
+        assert!(code.contains(
+            "export class BoxBuilder<T extends object = {}, __Set extends string = never>"
+        ));
+        assert!(code.contains("{ -readonly [__K in keyof Box<T>]?: Box<T>[__K] }"));
+        assert!(code.contains("value(value: T): BoxBuilder<T, __Set | \"value\">"));
+        assert!(code.contains("? BoxBuilder<T, __Set> :"));
+        assert!(code.contains("): Box<T> {"));
+        assert!(code.contains("return Object.assign(new Box<T>(), resolved);"));
+        assert!(code.contains(
+            "export function boxBuilder<T extends object = {}>(): BoxBuilder<T> {\n    return new BoxBuilder<T>();"
+        ));
+    }
+
+    #[test]
+    fn test_builder_without_required_fields_has_plain_build() {
+        let (fields, _) = collect(&[("nickname", "string", true, vec![])]).unwrap();
+        let code = generate_builder_class(
+            "Profile",
+            &TypeParams::default(),
+            &fields,
+            BuildTarget::Object,
+            None,
+        );
+        assert!(code.contains("build(): Profile {"));
+        assert!(code.contains("return resolved;"));
+    }
+
+    #[test]
+    fn test_builder_runs_validators() {
+        let (fields, _) = collect(&[("email", "string", false, vec![])]).unwrap();
+        let code = generate_builder_class(
+            "User",
+            &TypeParams::default(),
+            &fields,
+            BuildTarget::Object,
+            Some("userValidateFields(resolved)"),
+        );
+        assert!(code.contains("const errors = userValidateFields(resolved);"));
+        assert!(code.contains("throw new __mf_DeserializeError(errors);"));
+    }
+}
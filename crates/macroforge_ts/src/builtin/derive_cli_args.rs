@@ -0,0 +1,835 @@
+//! # CliArgs Macro Implementation
+//!
+//! The `CliArgs` macro generates command-line parsers, in the spirit of
+//! Rust's `clap` derive. Parsing and help rendering are done by the
+//! `macroforge/cli` runtime from a generated command descriptor.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameCli`, `classNameParseArgs(argv?)`, `static parseArgs(argv?)`, `static help()` | A command |
+//! | Interface | `interfaceNameCli`, `interfaceNameParseArgs(argv?)` | A command building a plain object |
+//! | Type Alias | `typeNameCli`, `typeNameParseArgs(argv?)` | Subcommands from a discriminated union |
+//!
+//! `argv` defaults to `process.argv.slice(2)`. Parsing returns the same
+//! shape as `Deserialize`: `{ success: true, value }` or
+//! `{ success: false, errors: [{ field, message }] }`. For `--help` and
+//! `--version` the failure carries the text to print in `display`.
+//! `classNameCli.parseOrExit()` prints help or errors and exits instead.
+//!
+//! ## Arguments
+//!
+//! | Field Type | Argument |
+//! |------------|----------|
+//! | `boolean` | Flag: `--verbose`, `-v`, `--no-verbose` |
+//! | `string`, `number`, `bigint` | Option: `--port 8080`, `--port=8080`, `-p 8080` |
+//! | `"a" \| "b"` | Option with a choice list |
+//! | Enum type | Option accepting member values or names |
+//! | Arrays of the above | Repeatable option: `--tag a --tag b` |
+//!
+//! Long names are the kebab-cased field names; short names are the first
+//! letter of the field name unless an earlier field took it. Help comes from
+//! the field's JSDoc, defaults from `@default` (as for `Default`). Fields are
+//! required unless optional, nullable, defaulted, flags or arrays.
+//!
+//! ## Subcommands
+//!
+//! On a union of command classes, the macro generates a subcommand group. A
+//! command's name is its discriminant (a field whose type is a single string
+//! literal, set automatically), `@command({ name })`, or its kebab-cased type
+//! name. A field marked `@arg({ subcommand: true })` parses the rest of the
+//! arguments with the group of its type.
+//!
+//! ## Options
+//!
+//! - `@command({ name, about, version })` on the type
+//! - `@arg({ positional: true })` - Positional argument; arrays are variadic
+//! - `@arg({ short: "x", long: "name" })` - Override the flag names
+//! - `@arg({ valueName: "FILE" })` - Placeholder in help
+//! - `@arg({ help: "..." })` - Help text instead of the JSDoc
+//! - `@arg({ hidden: true })` - Leave out of help
+//! - `@arg({ skip: true })` - Not an argument; uses its `@default`
+//! - `@arg({ subcommand: true })` - Holds the subcommand
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(CliArgs) @command({ name: "app", version: "1.0.0" }) */
+//! class AppArgs {
+//!     /** Print more output */
+//!     verbose: boolean;
+//!     /** @arg({ subcommand: true }) */
+//!     command: Command;
+//! }
+//!
+//! /** @derive(CliArgs) */
+//! type Command = BuildCommand | ServeCommand;
+//!
+//! /** @derive(CliArgs) @command({ about: "Start the dev server" }) */
+//! class ServeCommand {
+//!     command: "serve";
+//!     /**
+//!      * Port to listen on
+//!      * @default(8080)
+//!      */
+//!     port: number;
+//! }
+//!
+//! const args = appArgsCli.parseOrExit();
+//! // app -v serve --port 3000
+//! ```
+
+use std::collections::HashSet;
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    DefaultFieldOptions, extract_named_string, has_flag, is_nullable_type, member_doc,
+    split_top_level, strip_nullable, strip_parens,
+};
+use crate::builtin::serde::RenameAll;
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR, TypeMemberKind};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Options parsed from `@command` decorators on the type.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct CommandOptions {
+    name: Option<String>,
+    about: Option<String>,
+    version: Option<String>,
+}
+
+impl CommandOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("command") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.name = extract_named_string(args, "name").or(opts.name);
+            opts.about = extract_named_string(args, "about").or(opts.about);
+            opts.version = extract_named_string(args, "version").or(opts.version);
+        }
+        opts
+    }
+}
+
+/// Options parsed from `@arg` decorators on a field.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ArgOptions {
+    positional: bool,
+    subcommand: bool,
+    skip: bool,
+    hidden: bool,
+    short: Option<String>,
+    long: Option<String>,
+    value_name: Option<String>,
+    help: Option<String>,
+}
+
+impl ArgOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("arg") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.positional |= has_flag(args, "positional");
+            opts.subcommand |= has_flag(args, "subcommand");
+            opts.skip |= has_flag(args, "skip");
+            opts.hidden |= has_flag(args, "hidden");
+            opts.short = extract_named_string(args, "short").or(opts.short);
+            opts.long = extract_named_string(args, "long").or(opts.long);
+            opts.value_name = extract_named_string(args, "valueName").or(opts.value_name);
+            opts.help = extract_named_string(args, "help").or(opts.help);
+        }
+        opts
+    }
+}
+
+/// How the value of an argument is parsed.
+#[derive(Debug, Clone, PartialEq)]
+enum ArgKind {
+    Flag,
+    String,
+    Number,
+    BigInt,
+    Choice(Vec<String>),
+    /// A TypeScript enum, referenced by name at runtime.
+    Enum(String),
+}
+
+impl ArgKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ArgKind::Flag => "flag",
+            ArgKind::String => "string",
+            ArgKind::Number => "number",
+            ArgKind::BigInt => "bigint",
+            ArgKind::Choice(_) => "choice",
+            ArgKind::Enum(_) => "enum",
+        }
+    }
+}
+
+fn is_type_name(ts_type: &str) -> bool {
+    ts_type.chars().next().is_some_and(char::is_uppercase)
+        && ts_type
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// The strings of a union of string literals.
+fn string_literals(ts_type: &str) -> Option<Vec<String>> {
+    let members = split_top_level(ts_type, '|');
+    if members.is_empty() {
+        return None;
+    }
+    members
+        .iter()
+        .map(|m| {
+            let quoted = (m.starts_with('"') && m.ends_with('"'))
+                || (m.starts_with('\'') && m.ends_with('\''));
+            (quoted && m.len() >= 2).then(|| m[1..m.len() - 1].to_string())
+        })
+        .collect()
+}
+
+fn scalar_kind(ts_type: &str) -> Option<ArgKind> {
+    match ts_type {
+        "string" => Some(ArgKind::String),
+        "number" => Some(ArgKind::Number),
+        "bigint" => Some(ArgKind::BigInt),
+        // Built-in classes are not enums.
+        "Date" | "URL" | "RegExp" | "Object" | "String" | "Number" | "Boolean" | "BigInt" => None,
+        _ if is_type_name(ts_type) => Some(ArgKind::Enum(ts_type.to_string())),
+        _ => string_literals(ts_type).map(ArgKind::Choice),
+    }
+}
+
+/// The kind of an argument and whether it collects an array.
+fn arg_kind(ts_type: &str) -> Option<(ArgKind, bool)> {
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = ts_type.trim();
+    if ts_type == "boolean" {
+        return Some((ArgKind::Flag, false));
+    }
+    let item = ts_type.strip_suffix("[]").or_else(|| {
+        ts_type
+            .strip_prefix("Array<")
+            .and_then(|rest| rest.strip_suffix('>'))
+    });
+    match item {
+        Some(item) => scalar_kind(strip_parens(item)).map(|kind| (kind, true)),
+        None => scalar_kind(ts_type).map(|kind| (kind, false)),
+    }
+}
+
+/// The value of a discriminant field, whose type is a single string literal.
+fn tag_value(ts_type: &str) -> Option<String> {
+    string_literals(ts_type.trim()).and_then(|values| match values.as_slice() {
+        [value] => Some(value.clone()),
+        _ => None,
+    })
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// A property key, quoted unless it is an identifier.
+fn js_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        js_string(name)
+    }
+}
+
+/// A field of a command.
+struct CliField<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+/// Reads help text from the JSDoc before a field.
+struct Docs<'a> {
+    source: &'a str,
+    /// Offset of the target's source within the file.
+    start: u32,
+}
+
+impl Docs<'_> {
+    /// The first paragraph of the field's JSDoc, on one line.
+    fn help(&self, span: SpanIR) -> Option<String> {
+        let doc = member_doc(self.source, self.start, span)?;
+        let paragraph = doc.split("\n\n").next().unwrap_or_default();
+        Some(paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// The parts of a command descriptor built from its fields.
+#[derive(Default)]
+struct CommandParts {
+    /// The value of the discriminant field, if any.
+    tag: Option<String>,
+    options: Vec<String>,
+    subcommand: Option<String>,
+    /// Properties set by `create` rather than parsed.
+    fixed: Vec<String>,
+    /// `(specifier, module)` imports of `@default` factories.
+    imports: Vec<(String, String)>,
+}
+
+fn command_parts(
+    fields: &[CliField<'_>],
+    version: bool,
+    docs: &Docs<'_>,
+    diagnostics: &mut DiagnosticCollector,
+) -> CommandParts {
+    let mut parts = CommandParts::default();
+    let mut shorts: HashSet<String> = ["h".to_string()].into();
+    if version {
+        shorts.insert("V".to_string());
+    }
+    let mut longs: HashSet<String> = ["help".to_string(), "version".to_string()].into();
+    let mut seen_optional_positional = false;
+    let mut seen_variadic = false;
+
+    let opts = fields
+        .iter()
+        .map(|f| ArgOptions::from_decorators(f.decorators))
+        .collect::<Vec<_>>();
+
+    // Explicit short names win over the ones derived from field names.
+    for (field, opts) in fields.iter().zip(&opts) {
+        if let Some(short) = &opts.short {
+            if short.chars().count() != 1 {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "short name '{short}' of field '{}' must be a single character",
+                        field.name
+                    ),
+                );
+            } else if !shorts.insert(short.clone()) {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "short name '-{short}' of field '{}' is already used",
+                        field.name
+                    ),
+                );
+            }
+        }
+    }
+
+    for (field, opts) in fields.iter().zip(opts) {
+        let defaults = DefaultFieldOptions::from_decorators(field.decorators);
+        if let Some(import) = defaults.factory_import()
+            && !parts.imports.contains(&import)
+        {
+            parts.imports.push(import);
+        }
+
+        if opts.skip {
+            if let Some(value) = defaults.value {
+                parts.fixed.push(format!("{}: {value}", js_key(field.name)));
+            }
+            continue;
+        }
+        if parts.tag.is_none()
+            && let Some(tag) = tag_value(field.ts_type)
+        {
+            parts
+                .fixed
+                .push(format!("{}: {}", js_key(field.name), js_string(&tag)));
+            parts.tag = Some(tag);
+            continue;
+        }
+
+        let required = !field.optional && !is_nullable_type(field.ts_type);
+
+        if opts.subcommand {
+            let ts_type = strip_nullable(field.ts_type);
+            if !is_type_name(ts_type.trim()) {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "subcommand field '{}' must have the type of a union deriving CliArgs",
+                        field.name
+                    ),
+                );
+            } else if parts.subcommand.is_some() {
+                diagnostics.error(field.span, "a command can have only one subcommand field");
+            } else {
+                parts.subcommand = Some(format!(
+                    "{{ field: {}, required: {required}, command: () => {}Cli }}",
+                    js_string(field.name),
+                    ts_type.trim().to_case(Case::Camel)
+                ));
+            }
+            continue;
+        }
+
+        let Some((kind, multiple)) = arg_kind(field.ts_type) else {
+            diagnostics.error(
+                field.span,
+                format!(
+                    "@derive(CliArgs) can't parse field '{}' of type '{}'; supported are boolean, string, number, bigint, string literal unions, enums and arrays of those",
+                    field.name, field.ts_type
+                ),
+            );
+            continue;
+        };
+        let flag = kind == ArgKind::Flag;
+        let required = required && defaults.value.is_none() && !flag && !multiple;
+
+        let mut props = vec![
+            ("field", js_string(field.name)),
+            ("kind", js_string(kind.name())),
+        ];
+        if opts.positional {
+            if flag {
+                diagnostics.error(
+                    field.span,
+                    format!("boolean field '{}' can't be positional", field.name),
+                );
+            } else if seen_variadic {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "positional '{}' comes after a variadic positional",
+                        field.name
+                    ),
+                );
+            } else if required && seen_optional_positional {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "required positional '{}' comes after an optional one",
+                        field.name
+                    ),
+                );
+            }
+            seen_variadic |= multiple;
+            seen_optional_positional |= !required;
+            props.push(("positional", "true".to_string()));
+        } else {
+            let long = opts
+                .long
+                .clone()
+                .unwrap_or_else(|| field.name.to_case(Case::Kebab));
+            if !longs.insert(long.clone()) {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "long name '--{long}' of field '{}' is already used",
+                        field.name
+                    ),
+                );
+            }
+            props.push(("long", js_string(&long)));
+
+            let short = opts.short.clone().or_else(|| {
+                let first = field
+                    .name
+                    .chars()
+                    .next()
+                    .filter(char::is_ascii_alphabetic)?;
+                let short = first.to_ascii_lowercase().to_string();
+                shorts.insert(short.clone()).then_some(short)
+            });
+            if let Some(short) = short {
+                props.push(("short", js_string(&short)));
+            }
+        }
+        if multiple {
+            props.push(("multiple", "true".to_string()));
+        }
+        if required {
+            props.push(("required", "true".to_string()));
+        }
+        match &kind {
+            ArgKind::Choice(values) => {
+                let values = values.iter().map(|v| js_string(v)).collect::<Vec<_>>();
+                props.push(("choices", format!("[{}]", values.join(", "))));
+            }
+            ArgKind::Enum(name) => props.push(("enum", name.clone())),
+            _ => {}
+        }
+        let value_name = opts
+            .value_name
+            .clone()
+            .unwrap_or_else(|| RenameAll::ScreamingSnakeCase.apply(field.name));
+        props.push(("valueName", js_string(&value_name)));
+        if let Some(help) = opts.help.clone().or_else(|| docs.help(field.span)) {
+            props.push(("help", js_string(&help)));
+        }
+        if let Some(value) = defaults.value {
+            props.push(("default", format!("() => ({value})")));
+        }
+        if opts.hidden {
+            props.push(("hidden", "true".to_string()));
+        }
+
+        let props = props
+            .into_iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect::<Vec<_>>();
+        parts.options.push(format!("{{ {} }}", props.join(", ")));
+    }
+    parts
+}
+
+/// Shared header properties of a command or group descriptor.
+fn header(name: &str, command: &CommandOptions) -> String {
+    let mut header = format!("    name: {},\n", js_string(name));
+    if let Some(about) = &command.about {
+        header.push_str(&format!("    about: {},\n", js_string(about)));
+    }
+    if let Some(version) = &command.version {
+        header.push_str(&format!("    version: {},\n", js_string(version)));
+    }
+    header
+}
+
+fn parse_args_function(type_name: &str) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    format!(
+        r#"/**
+ * Parses command-line arguments.
+ * @param argv - Arguments without the program, defaults to `process.argv.slice(2)`
+ */
+export function {prefix}ParseArgs(argv: readonly string[] = __mf_processArgv()): __mf_CliResult<{type_name}> {{
+    return {prefix}Cli.parse(argv);
+}}"#
+    )
+}
+
+fn generate_command(
+    type_name: &str,
+    command: &CommandOptions,
+    parts: &CommandParts,
+    construct: impl Fn(&str) -> String,
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let name = command
+        .name
+        .clone()
+        .or_else(|| parts.tag.clone())
+        .unwrap_or_else(|| type_name.to_case(Case::Kebab));
+    let options = parts
+        .options
+        .iter()
+        .map(|o| format!("        {o},\n"))
+        .collect::<String>();
+    let subcommand = parts
+        .subcommand
+        .as_ref()
+        .map(|s| format!("    subcommand: {s},\n"))
+        .unwrap_or_default();
+    let mut properties = parts.fixed.clone();
+    properties.push("...values".to_string());
+    let value = construct(&format!("{{ {} }}", properties.join(", ")));
+
+    format!(
+        "export const {prefix}Cli: __mf_CliCommand<{type_name}> = /*#__PURE__*/ __mf_defineCommand<{type_name}>({{\n{}    options: [\n{options}    ],\n{subcommand}    create: (values) => {value},\n}});\n\n{}",
+        header(&name, command),
+        parse_args_function(type_name)
+    )
+}
+
+#[ts_macro_derive(
+    CliArgs,
+    description = "Generates a command-line argument parser with help text and subcommands",
+    attributes(
+        (arg, "Configure an argument. Options: positional, short, long, valueName, help, hidden, skip, subcommand"),
+        (command, "Configure the command. Options: name, about, version")
+    )
+)]
+pub fn derive_cli_args_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let command = CommandOptions::from_decorators(&container_decorators);
+    let docs = Docs {
+        source: &input.context.target_source,
+        start: input.context.target_span.start,
+    };
+    let mut diagnostics = DiagnosticCollector::new();
+    let mut imports = Vec::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields = class
+                .fields()
+                .iter()
+                .map(|f| CliField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let parts = command_parts(&fields, command.version.is_some(), &docs, &mut diagnostics);
+            imports.extend(parts.imports.iter().cloned());
+            let standalone = generate_command(type_name, &command, &parts, |properties| {
+                format!("Object.assign(Object.create({type_name}.prototype), {properties})")
+            });
+            let cli = format!("{}Cli", type_name.to_case(Case::Camel));
+            let result_type = format!("__mf_CliResult<{type_name}>");
+            let class_body = body! {
+                static parseArgs(argv: readonly string[] = __mf_processArgv()): @{result_type} {
+                    return @{cli}.parse(argv);
+                }
+
+                static help(): string {
+                    return @{cli}.help();
+                }
+            };
+
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = interface
+                .fields()
+                .iter()
+                .map(|f| CliField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let parts = command_parts(&fields, command.version.is_some(), &docs, &mut diagnostics);
+            imports.extend(parts.imports.iter().cloned());
+            TsStream::from_string(generate_command(
+                type_name,
+                &command,
+                &parts,
+                |properties| format!("({properties}) as {type_name}"),
+            ))
+        }
+        Data::TypeAlias(type_alias) => {
+            let Some(members) = type_alias.as_union() else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    "@derive(CliArgs) on a type alias requires a union of commands",
+                ));
+            };
+            let mut commands = Vec::new();
+            for member in members {
+                match &member.kind {
+                    TypeMemberKind::TypeRef(name) if is_type_name(name) => {
+                        commands.push(format!("{}Cli", name.to_case(Case::Camel)));
+                    }
+                    _ => diagnostics.error(
+                        input.decorator_span(),
+                        "subcommands must be named types deriving CliArgs",
+                    ),
+                }
+            }
+            let prefix = type_name.to_case(Case::Camel);
+            let name = command
+                .name
+                .clone()
+                .unwrap_or_else(|| type_name.to_case(Case::Kebab));
+            TsStream::from_string(format!(
+                "export const {prefix}Cli: __mf_CliCommand<{type_name}> = /*#__PURE__*/ __mf_defineSubcommands<{type_name}>({{\n{}    commands: () => [{}],\n}});\n\n{}",
+                header(&name, &command),
+                commands.join(", "),
+                parse_args_function(type_name)
+            ))
+        }
+        Data::Enum(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(CliArgs) can only be used on classes, interfaces and union type aliases",
+            ));
+        }
+    };
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    if matches!(input.data, Data::TypeAlias(_)) {
+        output.add_aliased_import("defineSubcommands", "macroforge/cli");
+    } else {
+        output.add_aliased_import("defineCommand", "macroforge/cli");
+    }
+    output.add_aliased_import("processArgv", "macroforge/cli");
+    output.add_aliased_type_import("CliCommand", "macroforge/cli");
+    output.add_aliased_type_import("CliResult", "macroforge/cli");
+    for (specifier, module) in imports {
+        output.add_import(&specifier, &module);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "arg".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn field<'a>(name: &'a str, ts_type: &'a str, decorators: &'a [DecoratorIR]) -> CliField<'a> {
+        CliField {
+            name,
+            ts_type,
+            optional: false,
+            decorators,
+            span: SpanIR::new(0, 0),
+        }
+    }
+
+    const NO_DOCS: Docs<'static> = Docs {
+        source: "",
+        start: 0,
+    };
+
+    #[test]
+    fn test_help_reads_the_first_paragraph_of_the_jsdoc() {
+        let source = "class Args {\n    /**\n     * Port to\n     * listen on\n     *\n     * Details.\n     */\n    port: number;\n}";
+        let docs = Docs {
+            source: &source[1..],
+            start: 1,
+        };
+        let port = source.find("port").unwrap() as u32 + 1;
+        assert_eq!(
+            docs.help(SpanIR::new(port, port + 13)).as_deref(),
+            Some("Port to listen on")
+        );
+        assert_eq!(NO_DOCS.help(SpanIR::new(1, 2)), None);
+    }
+
+    #[test]
+    fn test_arg_kind() {
+        assert_eq!(arg_kind("boolean"), Some((ArgKind::Flag, false)));
+        assert_eq!(
+            arg_kind("number | undefined"),
+            Some((ArgKind::Number, false))
+        );
+        assert_eq!(
+            arg_kind("(\"dev\" | \"prod\")[]"),
+            Some((ArgKind::Choice(vec!["dev".into(), "prod".into()]), true))
+        );
+        assert_eq!(
+            arg_kind("Array<LogLevel>"),
+            Some((ArgKind::Enum("LogLevel".into()), true))
+        );
+        assert_eq!(arg_kind("Map<string, string>"), None);
+        assert_eq!(arg_kind("Date"), None);
+        assert_eq!(arg_kind("boolean[]"), None);
+    }
+
+    #[test]
+    fn test_tag_value() {
+        assert_eq!(tag_value("\"serve\"").as_deref(), Some("serve"));
+        assert_eq!(tag_value("\"a\" | \"b\""), None);
+        assert_eq!(tag_value("string"), None);
+    }
+
+    #[test]
+    fn test_short_names_prefer_explicit_then_first_letter() {
+        let explicit = [arg("{ short: \"p\" }")];
+        let fields = [
+            field("path", "string", &[]),
+            field("port", "number", &explicit),
+            field("help", "boolean", &[]),
+        ];
+        let mut diagnostics = DiagnosticCollector::new();
+        let parts = command_parts(&fields, false, &NO_DOCS, &mut diagnostics);
+        assert!(diagnostics.has_errors(), "--help is reserved");
+        assert!(!parts.options[0].contains("short:"));
+        assert!(parts.options[1].contains("short: \"p\""));
+        assert!(!parts.options[2].contains("short:"), "-h is reserved");
+    }
+
+    #[test]
+    fn test_command_parts() {
+        let positional = [arg("{ positional: true }")];
+        let subcommand = [arg("{ subcommand: true }")];
+        let defaulted = [DecoratorIR {
+            name: "default".into(),
+            args_src: "8080".into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }];
+        let fields = [
+            field("command", "\"serve\"", &[]),
+            field("input", "string", &positional),
+            field("listenPort", "number", &defaulted),
+            field("next", "Command", &subcommand),
+        ];
+        let mut diagnostics = DiagnosticCollector::new();
+        let parts = command_parts(&fields, false, &NO_DOCS, &mut diagnostics);
+        assert!(diagnostics.is_empty());
+        assert_eq!(parts.tag.as_deref(), Some("serve"));
+        assert_eq!(parts.fixed, vec!["command: \"serve\"".to_string()]);
+        assert_eq!(
+            parts.options,
+            vec![
+                "{ field: \"input\", kind: \"string\", positional: true, required: true, valueName: \"INPUT\" }".to_string(),
+                "{ field: \"listenPort\", kind: \"number\", long: \"listen-port\", short: \"l\", valueName: \"LISTEN_PORT\", default: () => (8080) }".to_string(),
+            ]
+        );
+        assert_eq!(
+            parts.subcommand.as_deref(),
+            Some("{ field: \"next\", required: true, command: () => commandCli }")
+        );
+    }
+
+    #[test]
+    fn test_positional_order_is_checked() {
+        let positional = [arg("{ positional: true }")];
+        let fields = [
+            field("files", "string[]", &positional),
+            field("output", "string", &positional),
+        ];
+        let mut diagnostics = DiagnosticCollector::new();
+        command_parts(&fields, false, &NO_DOCS, &mut diagnostics);
+        assert!(diagnostics.has_errors());
+    }
+
+    #[test]
+    fn test_help_from_docs() {
+        let source = "class A {\n    /**\n     * Port to\n     * listen on.\n     *\n     * Details.\n     */\n    port: number;\n}";
+        let docs = Docs { source, start: 0 };
+        let start = source.find("port:").unwrap() as u32;
+        assert_eq!(
+            docs.help(SpanIR::new(start, start + 4)).as_deref(),
+            Some("Port to listen on.")
+        );
+    }
+}
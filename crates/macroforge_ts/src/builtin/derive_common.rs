@@ -6,6 +6,7 @@
 //! - **Field options parsing**: `CompareFieldOptions`, `DefaultFieldOptions`
 //! - **Type utilities**: Type checking and default value generation
 //! - **Decorator parsing**: Flag extraction and named argument parsing
+//! - **JSDoc**: Member descriptions and return type annotations
 //!
 //! ## Field Options
 //!
@@ -43,13 +44,16 @@
 use convert_case::{Case, Casing};
 
 use crate::builtin::serde::{TypeCategory, get_foreign_types};
-use crate::ts_syn::abi::DecoratorIR;
+use crate::ts_syn::abi::{DecoratorIR, SpanIR};
+use crate::ts_syn::{Data, DeriveInput};
 
 /// Options parsed from field-level decorators for comparison macros
-/// Supports @partialEq(skip), @hash(skip), @ord(skip)
+/// Supports @partialEq(skip), @hash(skip), @ord(skip) and `with: "fn"`
 #[derive(Default, Clone)]
 pub struct CompareFieldOptions {
     pub skip: bool,
+    /// Custom function replacing the generated logic (e.g. `@hash({ with: "hashPoint" })`)
+    pub with: Option<String>,
 }
 
 impl CompareFieldOptions {
@@ -64,9 +68,212 @@ impl CompareFieldOptions {
             if has_flag(args, "skip") {
                 opts.skip = true;
             }
+            if let Some(with) = extract_exact_named_string(args, "with") {
+                opts.with = Some(with);
+            }
+        }
+        opts
+    }
+}
+
+// ============================================================================
+// Field Options for PartialEq Macro
+// ============================================================================
+
+/// Options parsed from @partialEq decorators
+///
+/// Supports `@partialEq({ skip, epsilon: 1e-9, nanEqual: true, with: "fn" })`.
+/// On the container, `epsilon` and `nanEqual` set the defaults for every
+/// `number` field, nullable or not. The Hash derive reads these too, so that `hashCode` stays
+/// consistent with the equality they define.
+#[derive(Default, Clone)]
+pub struct EqFieldOptions {
+    pub skip: bool,
+    /// Absolute tolerance for `number` comparison
+    pub epsilon: Option<f64>,
+    /// Treat `NaN` as equal to `NaN`
+    pub nan_equal: bool,
+    /// Custom equality function `(a, b) => boolean`
+    pub with: Option<String>,
+}
+
+impl EqFieldOptions {
+    pub fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("partialEq") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if has_flag(args, "skip") {
+                opts.skip = true;
+            }
+            if has_flag(args, "nanEqual") {
+                opts.nan_equal = true;
+            }
+            if let Some(epsilon) = extract_named_number(args, "epsilon") {
+                opts.epsilon = Some(epsilon);
+            }
+            if let Some(with) = extract_exact_named_string(args, "with") {
+                opts.with = Some(with);
+            }
         }
         opts
     }
+
+    /// Applies container-level numeric defaults the field doesn't set itself
+    pub fn inherit(&mut self, container: &EqFieldOptions) {
+        if self.epsilon.is_none() {
+            self.epsilon = container.epsilon;
+        }
+        self.nan_equal |= container.nan_equal;
+    }
+
+    /// Whether `epsilon` and `nanEqual` apply to a field of type `ts_type`:
+    /// `number`, possibly with `| null` or `| undefined`
+    pub fn applies_to(ts_type: &str) -> bool {
+        strip_nullable(ts_type) == "number"
+    }
+
+    /// Whether equality for this field is something `hashCode` cannot mirror,
+    /// so the Hash derive must leave the field out
+    pub fn excludes_from_hash(&self, ts_type: &str) -> bool {
+        self.with.is_some() || (Self::applies_to(ts_type) && self.epsilon.is_some_and(|e| e > 0.0))
+    }
+}
+
+/// The decorators written on the derive target, including the `@derive`
+/// itself, which `DeriveInput::attrs` leaves out
+pub fn target_decorators(input: &DeriveInput) -> &[DecoratorIR] {
+    match &input.data {
+        Data::Class(class) => &class.inner.decorators,
+        Data::Enum(enum_data) => &enum_data.inner.decorators,
+        Data::Interface(interface) => &interface.inner.decorators,
+        Data::TypeAlias(type_alias) => &type_alias.inner.decorators,
+    }
+}
+
+/// Returns true if a `@derive(...)` decorator in `decorators` lists `macro_name`
+pub fn derives_macro(decorators: &[DecoratorIR], macro_name: &str) -> bool {
+    decorators
+        .iter()
+        .filter(|d| d.name.eq_ignore_ascii_case("derive"))
+        .flat_map(|d| d.args_src.split(','))
+        .any(|name| name.trim().eq_ignore_ascii_case(macro_name))
+}
+
+// ============================================================================
+// Field Options for Ord / PartialOrd Macros
+// ============================================================================
+
+/// Where `null`/`undefined` field values sort relative to present values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+/// Options parsed from @ord decorators, shared by `Ord` and `PartialOrd`
+///
+/// Supports `@ord({ skip, priority: 1, reverse: true, nulls: "first" | "last",
+/// by: "fn", locale: "de", collator: { numeric: true } })`. On the container,
+/// `locale` and `collator` set the defaults for every string field.
+#[derive(Default, Clone)]
+pub struct OrdFieldOptions {
+    pub skip: bool,
+    /// Fields with a priority are compared first, lowest value first
+    pub priority: Option<f64>,
+    /// Invert the ordering of this field
+    pub reverse: bool,
+    /// Explicit placement of null/undefined values (unaffected by `reverse`)
+    pub nulls: Option<NullsOrder>,
+    /// Key extractor function applied to the field value before comparing
+    pub by: Option<String>,
+    /// BCP 47 locale passed to `Intl.Collator`
+    pub locale: Option<String>,
+    /// `Intl.Collator` options object, kept as source text
+    pub collator: Option<String>,
+}
+
+impl OrdFieldOptions {
+    /// Parse options from decorators for a specific attribute name
+    pub fn from_decorators(decorators: &[DecoratorIR], attr_name: &str) -> Result<Self, String> {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case(attr_name) {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if has_flag(args, "skip") {
+                opts.skip = true;
+            }
+            if has_flag(args, "reverse") {
+                opts.reverse = true;
+            }
+            if let Some(priority) = extract_named_number(args, "priority") {
+                opts.priority = Some(priority);
+            }
+            if let Some(nulls) = extract_exact_named_string(args, "nulls") {
+                opts.nulls = Some(match nulls.as_str() {
+                    "first" => NullsOrder::First,
+                    "last" => NullsOrder::Last,
+                    other => {
+                        return Err(format!(
+                            "@{attr_name}: invalid `nulls` value \"{other}\", expected \"first\" or \"last\""
+                        ));
+                    }
+                });
+            }
+            if let Some(by) = extract_exact_named_string(args, "by") {
+                opts.by = Some(by);
+            }
+            if let Some(locale) = extract_exact_named_string(args, "locale") {
+                opts.locale = Some(locale);
+            }
+            if let Some(collator) = extract_named_object(args, "collator") {
+                opts.collator = Some(collator);
+            }
+        }
+        Ok(opts)
+    }
+
+    /// Fills collation settings the field doesn't set from container-level options
+    pub fn inherit(&mut self, container: &OrdFieldOptions) {
+        if self.locale.is_none() {
+            self.locale = container.locale.clone();
+        }
+        if self.collator.is_none() {
+            self.collator = container.collator.clone();
+        }
+    }
+
+    /// Whether string comparison should go through `Intl.Collator`
+    pub fn uses_collator(&self) -> bool {
+        self.locale.is_some() || self.collator.is_some()
+    }
+
+    /// Returns a `new Intl.Collator(...)` expression for these options
+    pub fn collator_expr(&self) -> String {
+        let locale = self
+            .locale
+            .as_ref()
+            .map(|l| format!("\"{l}\""))
+            .unwrap_or_else(|| "undefined".to_string());
+        match &self.collator {
+            Some(options) => format!("new Intl.Collator({locale}, {options})"),
+            None => format!("new Intl.Collator({locale})"),
+        }
+    }
+}
+
+/// Stable-sorts fields so that those with a priority come first (lowest first),
+/// keeping declaration order for ties and for fields without a priority
+pub fn sort_by_priority<T>(fields: &mut [T], priority: impl Fn(&T) -> Option<f64>) {
+    fields.sort_by(|a, b| {
+        let pa = priority(a).unwrap_or(f64::INFINITY);
+        let pb = priority(b).unwrap_or(f64::INFINITY);
+        pa.total_cmp(&pb)
+    });
 }
 
 // ============================================================================
@@ -74,12 +281,19 @@ impl CompareFieldOptions {
 // ============================================================================
 
 /// Options parsed from @default decorator on fields
+///
+/// Besides a literal value, `@default({ factory: "makeId", from: "./ids" })`
+/// calls `makeId()` for each default and imports it from `./ids`.
 #[derive(Default, Clone)]
 pub struct DefaultFieldOptions {
     /// The default value expression (e.g., "0", "\"\"", "[]")
     pub value: Option<String>,
     /// Whether this field has a @default decorator
     pub has_default: bool,
+    /// Module the factory function is imported from
+    pub from: Option<String>,
+    /// Factory function called to produce the default (e.g., `nanoid`, `crypto.randomUUID`)
+    pub factory: Option<String>,
 }
 
 impl DefaultFieldOptions {
@@ -92,8 +306,13 @@ impl DefaultFieldOptions {
             opts.has_default = true;
             let args = decorator.args_src.trim();
 
-            // Check for @default("value") or @default({ value: "..." })
-            if let Some(value) = extract_default_value(args) {
+            // Check for @default({ factory: "fn", from: "module" })
+            if let Some(factory) = extract_exact_named_string(args, "factory") {
+                opts.value = Some(format!("{factory}()"));
+                opts.from = extract_exact_named_string(args, "from");
+                opts.factory = Some(factory);
+            } else if let Some(value) = extract_default_value(args) {
+                // @default("value") or @default({ value: "..." })
                 opts.value = Some(value);
             } else if !args.is_empty() {
                 // Treat the args directly as the value if not empty
@@ -103,6 +322,17 @@ impl DefaultFieldOptions {
         }
         opts
     }
+
+    /// The `(specifier, module)` import the factory needs, if it comes from a module.
+    ///
+    /// Only the root binding is imported, so `{ factory: "ids.make", from: "./ids" }`
+    /// imports `ids`.
+    pub fn factory_import(&self) -> Option<(String, String)> {
+        let factory = self.factory.as_deref()?;
+        let module = self.from.clone()?;
+        let specifier = factory.split('.').next().unwrap_or(factory).trim();
+        Some((specifier.to_string(), module))
+    }
 }
 
 /// Extract default value from decorator arguments
@@ -144,6 +374,71 @@ pub fn is_nullable_type(ts_type: &str) -> bool {
     normalized.contains("|null") || normalized.contains("|undefined")
 }
 
+/// Removes `null` and `undefined` members from a union type, e.g.
+/// `Date | null` becomes `Date`
+pub fn strip_nullable(ts_type: &str) -> String {
+    if !is_nullable_type(ts_type) {
+        return ts_type.to_string();
+    }
+    ts_type
+        .split('|')
+        .map(str::trim)
+        .filter(|member| *member != "null" && *member != "undefined")
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Splits `s` at every top-level `separator`, ignoring separators inside
+/// brackets and string literals. The `>` of a function type's `=>` does not
+/// close a bracket.
+pub fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in s.char_indices() {
+        let prev = previous.replace(c);
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '>') if prev == Some('=') => {}
+            (None, '<' | '(' | '[' | '{') => depth += 1,
+            (None, '>' | ')' | ']' | '}') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Removes parentheses that wrap the whole type, e.g. `(A | B)`.
+pub fn strip_parens(ts_type: &str) -> &str {
+    let trimmed = ts_type.trim();
+    if !trimmed.starts_with('(') || !trimmed.ends_with(')') {
+        return trimmed;
+    }
+    let mut depth = 0i32;
+    for (i, c) in trimmed.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        // The opening parenthesis closes before the end, e.g. `(A) | (B)`
+        if depth == 0 && i + 1 < trimmed.len() {
+            return trimmed;
+        }
+    }
+    strip_parens(&trimmed[1..trimmed.len() - 1])
+}
+
 /// Check if a type name contains generic parameters (e.g., "RecordLink<Service>")
 /// This is used to detect generic type instantiations that need special handling.
 pub fn is_generic_type(type_name: &str) -> bool {
@@ -166,6 +461,145 @@ pub fn parse_generic_type(type_name: &str) -> Option<(&str, &str)> {
     }
 }
 
+/// Type parameters declared by a generic class, interface or type alias,
+/// e.g. `<T extends Entity, K = keyof T>`.
+///
+/// The IR only keeps parameter names (and none for classes and interfaces),
+/// so macros that generate types and functions over a generic target read
+/// the declaration from its source to keep constraints and defaults.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TypeParams {
+    /// Each parameter as declared, e.g. `T extends Entity`
+    pub params: Vec<String>,
+}
+
+impl TypeParams {
+    /// The type parameters `name` declares in the target's `source`, and the
+    /// source after them. The declared name is the first occurrence of
+    /// `name` as a word, since the source starts with the declaration.
+    pub fn declared<'a>(source: &'a str, name: &str) -> (Self, &'a str) {
+        let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+        let declaration = source.match_indices(name).find_map(|(at, _)| {
+            let rest = &source[at + name.len()..];
+            let word = !source[..at].ends_with(is_ident_char) && !rest.starts_with(is_ident_char);
+            word.then_some(rest)
+        });
+        let Some(rest) = declaration.map(str::trim_start) else {
+            return (Self::default(), "");
+        };
+        match rest.strip_prefix('<').zip(closing_angle(rest)) {
+            Some((inner, end)) => (
+                Self {
+                    params: split_top_level(&inner[..end - 2], ',')
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect(),
+                },
+                &rest[end..],
+            ),
+            None => (Self::default(), rest),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// The parameter names, e.g. `["T", "K"]`
+    pub fn names(&self) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter_map(|param| {
+                param
+                    .split([' ', '='])
+                    .find(|word| !word.is_empty() && !matches!(*word, "const" | "in" | "out"))
+            })
+            .collect()
+    }
+
+    /// The declaration as written, e.g. `<T extends Entity = User>`, or an
+    /// empty string.
+    pub fn decl(&self) -> String {
+        if self.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.params.join(", "))
+        }
+    }
+
+    /// The declaration for a generic function, followed by the function's
+    /// own parameters in `extra`. Defaults are dropped, as they are inferred
+    /// from the arguments and would otherwise have to come last.
+    pub fn fn_decl(&self, extra: &[&str]) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|param| strip_type_param_default(param))
+            .chain(extra.iter().copied())
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    }
+
+    /// The parameters as type arguments, e.g. `<T, K>`, or an empty string.
+    pub fn args(&self) -> String {
+        if self.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.names().join(", "))
+        }
+    }
+
+    /// `type_name` applied to the parameters, e.g. `Box<T>`
+    pub fn apply(&self, type_name: &str) -> String {
+        format!("{type_name}{}", self.args())
+    }
+}
+
+/// Removes the `= Default` of a type parameter declaration.
+fn strip_type_param_default(param: &str) -> &str {
+    let mut depth = 0i32;
+    let mut chars = param.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, c)| c);
+        match c {
+            '=' if next == Some('>') => {
+                chars.next();
+            }
+            '=' if depth == 0 => return param[..i].trim_end(),
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    param
+}
+
+/// Index just past the `>` closing the `<` at the start of `s`.
+pub fn closing_angle(s: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut previous = ' ';
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            // `=>` in a function type is not a closing bracket
+            '>' if previous == '=' => {}
+            '>' | ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
 /// Check if a type has a known default value.
 /// All types are assumed to implement Default - primitives/collections have built-in defaults,
 /// unknown types are assumed to have a defaultValue() static method (Rust's derive(Default) philosophy).
@@ -254,6 +688,7 @@ pub fn has_flag(args: &str, flag: &str) -> bool {
 
 fn flag_explicit_false(args: &str, flag: &str) -> bool {
     let lower = args.to_ascii_lowercase();
+    let flag = flag.to_ascii_lowercase();
     let condensed: String = lower.chars().filter(|c| !c.is_whitespace()).collect();
     condensed.contains(&format!("{flag}:false")) || condensed.contains(&format!("{flag}=false"))
 }
@@ -279,7 +714,102 @@ pub fn extract_named_string(args: &str, name: &str) -> Option<String> {
     None
 }
 
-fn parse_string_literal(input: &str) -> Option<String> {
+/// Extract a numeric named argument, e.g. `{ maxDepth: 3 }` or `maxDepth = 3`.
+///
+/// The name is matched case-insensitively and only as a whole word, so
+/// `items` does not match inside `maxItems`.
+pub fn extract_named_number(args: &str, name: &str) -> Option<f64> {
+    let value = find_named_value(args, name)?;
+    let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_')))
+        .unwrap_or(value.len());
+    let literal: String = value[..end].chars().filter(|c| *c != '_').collect();
+    match literal.as_str() {
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        _ => literal.parse::<f64>().ok(),
+    }
+}
+
+/// Like [`extract_named_string`], but only matches `name` as a whole word, so
+/// short option names such as `by` are not found inside other identifiers.
+pub fn extract_exact_named_string(args: &str, name: &str) -> Option<String> {
+    find_named_value(args, name).and_then(parse_string_literal)
+}
+
+/// Extract an object-literal named argument as source text, e.g. the
+/// `{ numeric: true }` in `{ collator: { numeric: true } }`.
+pub fn extract_named_object(args: &str, name: &str) -> Option<String> {
+    let value = find_named_value(args, name)?;
+    if !value.starts_with('{') {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (idx, c) in value.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => quote = Some(c),
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(value[..=idx].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Returns the source text following `name:` / `name =` in decorator arguments.
+fn find_named_value<'a>(args: &'a str, name: &str) -> Option<&'a str> {
+    let lower = args.to_ascii_lowercase();
+    let needle = name.to_ascii_lowercase();
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+
+    let mut search_from = 0;
+    while let Some(pos) = lower[search_from..].find(&needle) {
+        let start = search_from + pos;
+        let end = start + needle.len();
+        search_from = end;
+
+        let before_ok = lower[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_ident(c));
+        let after_ok = lower[end..].chars().next().is_none_or(|c| !is_ident(c));
+        if !before_ok || !after_ok {
+            continue;
+        }
+
+        let remainder = args[end..].trim_start();
+        if let Some(value) = remainder
+            .strip_prefix(':')
+            .or_else(|| remainder.strip_prefix('='))
+        {
+            return Some(value.trim_start());
+        }
+    }
+
+    None
+}
+
+/// Parse a single- or double-quoted string literal at the start of `input`.
+pub fn parse_string_literal(input: &str) -> Option<String> {
     let trimmed = input.trim();
     let mut chars = trimmed.chars();
     let quote = chars.next()?;
@@ -307,6 +837,56 @@ fn parse_string_literal(input: &str) -> Option<String> {
     None
 }
 
+// ============================================================================
+// JSDoc Utilities
+// ============================================================================
+
+/// The JSDoc description directly before byte offset `end` of `source`:
+/// the text of the adjacent `/** */` comments up to their first `@` tag.
+pub fn leading_doc(source: &str, end: usize) -> Option<String> {
+    let mut rest = source.get(..end)?.trim_end();
+    let mut comments = Vec::new();
+    while let Some(before_close) = rest.strip_suffix("*/") {
+        let open = before_close.rfind("/**")?;
+        comments.push(&before_close[open + 3..]);
+        rest = before_close[..open].trim_end();
+    }
+
+    let descriptions = comments
+        .iter()
+        .rev()
+        .map(|body| {
+            body.lines()
+                .map(|line| line.trim().trim_start_matches('*').trim())
+                .take_while(|line| !line.starts_with('@'))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .filter(|description| !description.is_empty())
+        .collect::<Vec<_>>();
+    (!descriptions.is_empty()).then(|| descriptions.join("\n\n"))
+}
+
+/// The JSDoc description of the member at `span`, read from the source of
+/// the target starting at `target_start`.
+///
+/// Spans are 1-based, so the member starts at byte `span.start - 1` of the
+/// file, while the target's source is sliced from byte `target_start`.
+pub fn member_doc(target_source: &str, target_start: u32, span: SpanIR) -> Option<String> {
+    let offset = span.start.checked_sub(1)?.checked_sub(target_start)? as usize;
+    leading_doc(target_source, offset)
+}
+
+/// A method's return type without the `:` of its annotation.
+pub fn return_type(return_type_src: &str) -> &str {
+    return_type_src
+        .trim_start()
+        .trim_start_matches(':')
+        .trim_start()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -314,12 +894,66 @@ fn parse_string_literal(input: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ts_syn::abi::SpanIR;
 
     fn span() -> SpanIR {
         SpanIR::new(0, 0)
     }
 
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(
+            split_top_level("Map<string, number> | \"a|b\" | null", '|'),
+            ["Map<string, number>", "\"a|b\"", "null"]
+        );
+    }
+
+    #[test]
+    fn test_split_top_level_function_types() {
+        assert_eq!(
+            split_top_level("((x: number) => void) | null", '|'),
+            ["((x: number) => void)", "null"]
+        );
+        assert_eq!(
+            split_top_level("(a: Map<string, number>) => string | undefined", '|'),
+            ["(a: Map<string, number>) => string", "undefined"]
+        );
+        assert_eq!(
+            split_top_level("cb: (x: number) => void, n: number", ','),
+            ["cb: (x: number) => void", "n: number"]
+        );
+    }
+
+    #[test]
+    fn test_declared_type_params() {
+        let (params, rest) = TypeParams::declared(
+            "/** @derive(Builder) */\nclass Box<T extends { id: string }, K = keyof T> extends Base<T> {}",
+            "Box",
+        );
+        assert_eq!(params.params, ["T extends { id: string }", "K = keyof T"]);
+        assert_eq!(params.names(), ["T", "K"]);
+        assert_eq!(rest.trim(), "extends Base<T> {}");
+        assert_eq!(params.apply("Box"), "Box<T, K>");
+        assert_eq!(params.fn_decl(&["P"]), "<T extends { id: string }, K, P>");
+
+        let (params, _) = TypeParams::declared("type Box = Boxed<string>;", "Box");
+        assert!(params.is_empty());
+        assert_eq!(params.apply("Box"), "Box");
+        assert_eq!(params.fn_decl(&[]), "");
+
+        let (params, _) = TypeParams::declared(
+            "type Handler<const F extends (x: number) => void = () => void> = { f: F };",
+            "Handler",
+        );
+        assert_eq!(params.names(), ["F"]);
+        assert_eq!(params.fn_decl(&[]), "<const F extends (x: number) => void>");
+    }
+
+    #[test]
+    fn test_strip_parens() {
+        assert_eq!(strip_parens(" ((A | B)) "), "A | B");
+        assert_eq!(strip_parens("(A) | (B)"), "(A) | (B)");
+    }
+
     fn make_decorator(name: &str, args: &str) -> DecoratorIR {
         DecoratorIR {
             name: name.into(),
@@ -382,6 +1016,37 @@ mod tests {
         assert_eq!(opts.value.as_deref(), Some("test"));
     }
 
+    #[test]
+    fn test_default_field_with_factory() {
+        let decorator = make_decorator("default", r#"{ factory: "makeId", from: "./ids" }"#);
+        let opts = DefaultFieldOptions::from_decorators(&[decorator]);
+        assert!(opts.has_default);
+        assert_eq!(opts.value.as_deref(), Some("makeId()"));
+        assert_eq!(
+            opts.factory_import(),
+            Some(("makeId".to_string(), "./ids".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_default_field_with_global_factory() {
+        let decorator = make_decorator("default", r#"{ factory: "crypto.randomUUID" }"#);
+        let opts = DefaultFieldOptions::from_decorators(&[decorator]);
+        assert_eq!(opts.value.as_deref(), Some("crypto.randomUUID()"));
+        assert_eq!(opts.factory_import(), None);
+    }
+
+    #[test]
+    fn test_default_factory_imports_root_binding() {
+        let decorator = make_decorator("default", r#"{ factory: "ids.make", from: "./ids" }"#);
+        let opts = DefaultFieldOptions::from_decorators(&[decorator]);
+        assert_eq!(opts.value.as_deref(), Some("ids.make()"));
+        assert_eq!(
+            opts.factory_import(),
+            Some(("ids".to_string(), "./ids".to_string()))
+        );
+    }
+
     #[test]
     fn test_is_primitive_type() {
         assert!(is_primitive_type("string"));
@@ -466,4 +1131,120 @@ mod tests {
         // Malformed (no closing bracket)
         assert_eq!(parse_generic_type("Array<User"), None);
     }
+
+    #[test]
+    fn test_extract_named_number() {
+        assert_eq!(
+            extract_named_number("{ maxDepth: 3 }", "maxDepth"),
+            Some(3.0)
+        );
+        assert_eq!(
+            extract_named_number("{ epsilon: 1e-9 }", "epsilon"),
+            Some(1e-9)
+        );
+        assert_eq!(
+            extract_named_number("maxitems = 1_000", "maxItems"),
+            Some(1000.0)
+        );
+        assert_eq!(
+            extract_named_number("{ priority: -2 }", "priority"),
+            Some(-2.0)
+        );
+
+        // Whole-word matching only
+        assert_eq!(extract_named_number("{ maxItems: 5 }", "items"), None);
+        // Non-numeric values are ignored
+        assert_eq!(
+            extract_named_number(r#"{ maxDepth: "3" }"#, "maxDepth"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_strip_nullable() {
+        assert_eq!(strip_nullable("Date | null"), "Date");
+        assert_eq!(strip_nullable("string | undefined | null"), "string");
+        assert_eq!(strip_nullable("number"), "number");
+    }
+
+    #[test]
+    fn test_eq_field_options() {
+        let decorator = make_decorator("partialEq", r#"{ epsilon: 1e-6, nanEqual: true }"#);
+        let mut opts = EqFieldOptions::from_decorators(&[decorator]);
+        assert_eq!(opts.epsilon, Some(1e-6));
+        assert!(opts.nan_equal);
+        assert!(opts.excludes_from_hash("number"));
+        assert!(opts.excludes_from_hash("number | null"));
+        assert!(opts.excludes_from_hash("number | undefined"));
+        assert!(!opts.excludes_from_hash("string"));
+        assert!(!opts.excludes_from_hash("string | null"));
+
+        let disabled = make_decorator("partialEq", "{ nanEqual: false }");
+        assert!(!EqFieldOptions::from_decorators(&[disabled]).nan_equal);
+
+        let container = EqFieldOptions {
+            epsilon: Some(0.5),
+            ..Default::default()
+        };
+        opts.inherit(&container);
+        assert_eq!(opts.epsilon, Some(1e-6), "field-level epsilon wins");
+    }
+
+    #[test]
+    fn test_derives_macro() {
+        let decorator = make_decorator("derive", "Debug, PartialEq, Hash");
+        assert!(derives_macro(std::slice::from_ref(&decorator), "partialEq"));
+        assert!(!derives_macro(&[decorator], "Ord"));
+    }
+
+    #[test]
+    fn test_leading_doc() {
+        let source = "class User {\n    /**\n     * Login address.\n     * Must be unique.\n     * @serde({ rename: \"mail\" })\n     */\n    email: string;\n}";
+        let offset = source.find("email").unwrap();
+        assert_eq!(
+            leading_doc(source, offset).as_deref(),
+            Some("Login address.\nMust be unique.")
+        );
+
+        let directives_only = "/** @serde({ skip: true }) */ x: number;";
+        assert_eq!(
+            leading_doc(directives_only, directives_only.find('x').unwrap()),
+            None
+        );
+
+        let no_comment = "a: string; b: number;";
+        assert_eq!(leading_doc(no_comment, no_comment.find('b').unwrap()), None);
+    }
+
+    #[test]
+    fn test_member_doc_uses_one_based_spans() {
+        let source = "class User {\n    /** Login address. */\n    email: string;\n}";
+        let target_start = 1;
+        let target_source = &source[target_start..];
+        let email = source.find("email").unwrap() as u32 + 1;
+        assert_eq!(
+            member_doc(
+                target_source,
+                target_start as u32,
+                SpanIR::new(email, email + 14)
+            )
+            .as_deref(),
+            Some("Login address.")
+        );
+    }
+
+    #[test]
+    fn test_return_type_strips_annotation_colon() {
+        assert_eq!(return_type(": Promise<void>"), "Promise<void>");
+        assert_eq!(return_type("string"), "string");
+    }
+
+    #[test]
+    fn test_adjacent_comments_are_joined() {
+        let source = "/** First. */\n/** @reflect */\n/** Second. */\nx: number;";
+        assert_eq!(
+            leading_doc(source, source.find("x:").unwrap()).as_deref(),
+            Some("First.\n\nSecond.")
+        );
+    }
 }
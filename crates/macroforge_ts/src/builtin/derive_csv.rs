@@ -0,0 +1,692 @@
+//! # Csv Macro Implementation
+//!
+//! The `Csv` macro maps a type to CSV rows, for data export and import.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameCsvHeader()`, `classNameToCsvRow(value)`, `classNameFromCsvRow(record)`, `classNameCsvLines(rows)`, `classNameToCsv(rows)`, `classNameParseCsv(text)` + static methods | Rows are created with the class prototype |
+//! | Interface | The same standalone functions | Plain objects |
+//!
+//! `fromCsvRow` and `parseCsv` return `{ success: true, value }` or
+//! `{ success: false, errors }` like `Deserialize`, collecting every error
+//! with its column (and row, for `parseCsv`). `csvLines` yields one CRLF
+//! terminated line at a time, and the `CsvReader` of `macroforge/csv` reads
+//! text in chunks, so large files can be streamed through
+//! `csvLines`/`fromCsvRow`. Quoting follows RFC 4180.
+//!
+//! ## Columns
+//!
+//! Columns are named after the fields, respecting `@serde({ rename })` and
+//! `@serde({ renameAll })`. Fields whose type is another type deriving
+//! `Csv` are flattened into dotted columns (`address: Address` →
+//! `address.street`, `address.city`); with `@serde({ flatten: true })` the
+//! nested columns are used without prefix. Fields with `@serde({ skip })`,
+//! `skipSerializing` or `skipDeserializing` have no column.
+//!
+//! ## Cells
+//!
+//! | Field Type | Cell |
+//! |------------|------|
+//! | `string`, `number`, `bigint`, `boolean` | The value |
+//! | `Date` | ISO string |
+//! | `"a" \| "b"` | One of the literals |
+//! | `Map`, `Set` | JSON array |
+//! | Arrays, records, object literals, other unions | JSON |
+//!
+//! Empty cells count as missing, except for required strings. Optional
+//! fields (`?`, `| undefined`) read missing cells as `undefined`, nullable
+//! fields (`| null`) as `null`, and fields with `@serde({ default })` use
+//! the default.
+//!
+//! ## Options
+//!
+//! - `@serde({ serializeWith: "fn" })` - Formats the cell from `fn(value)`
+//! - `@serde({ deserializeWith: "fn" })` - Parses the cell with `fn(raw)`;
+//!   exceptions become errors
+//! - `@serde({ validate: [...] })` - Validators run on the parsed value
+//! - `@csv({ cell: "number" })` - Parse the cell as `string`, `number`,
+//!   `bigint`, `boolean`, `date` or `json`, e.g. for enums or branded types
+//! - `@csv({ skip: true })` - No column for the field
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Csv) @serde({ renameAll: "snake_case" }) */
+//! class Customer {
+//!     id: number;
+//!     fullName: string;
+//!     /** @serde({ validate: ["email"] }) */
+//!     email: string;
+//!     billing: Address;
+//!     signedUp?: Date;
+//! }
+//!
+//! const text = Customer.toCsv(customers);
+//! // id,full_name,email,billing.street,billing.city,signed_up
+//!
+//! const result = Customer.parseCsv(text);
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    extract_named_string, has_flag, split_top_level, strip_nullable, strip_parens,
+};
+use crate::builtin::serde::derive_deserialize::{
+    generate_validation_condition, get_validator_message,
+};
+use crate::builtin::serde::{SerdeContainerOptions, SerdeFieldOptions, Validator, ValidatorSpec};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Options parsed from `@csv` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct CsvOptions {
+    cell: Option<String>,
+    skip: bool,
+}
+
+impl CsvOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("csv") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.cell = extract_named_string(args, "cell").or(opts.cell);
+            opts.skip |= has_flag(args, "skip");
+        }
+        opts
+    }
+}
+
+/// How a field maps to columns.
+#[derive(Debug, Clone, PartialEq)]
+enum Column {
+    /// One cell, parsed with a `csvDecoders` expression.
+    Cell(String),
+    /// Another type deriving `Csv`, flattened into its own columns.
+    Nested(String),
+}
+
+/// The `csvDecoders` expression named by `@csv({ cell })`.
+fn named_decoder(cell: &str) -> Option<String> {
+    matches!(
+        cell,
+        "string" | "number" | "bigint" | "boolean" | "date" | "json"
+    )
+    .then(|| format!("__mf_csvDecoders.{cell}"))
+}
+
+fn column(ts_type: &str) -> Column {
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = strip_parens(ts_type.trim());
+    let decoder = match ts_type {
+        "string" => "__mf_csvDecoders.string".to_string(),
+        "number" => "__mf_csvDecoders.number".to_string(),
+        "bigint" => "__mf_csvDecoders.bigint".to_string(),
+        "boolean" => "__mf_csvDecoders.boolean".to_string(),
+        "Date" => "__mf_csvDecoders.date".to_string(),
+        _ if ts_type.starts_with("Map<") => "__mf_csvDecoders.map".to_string(),
+        _ if ts_type.starts_with("Set<") => "__mf_csvDecoders.set".to_string(),
+        _ => {
+            let members = split_top_level(ts_type, '|');
+            let all_literals = !members.is_empty()
+                && members.iter().all(|m| {
+                    (m.starts_with('"') && m.ends_with('"'))
+                        || (m.starts_with('\'') && m.ends_with('\''))
+                });
+            if all_literals {
+                let values = members
+                    .iter()
+                    .map(|m| js_string(&m[1..m.len() - 1]))
+                    .collect::<Vec<_>>();
+                format!("__mf_csvDecoders.oneOf([{}])", values.join(", "))
+            } else if is_type_name(ts_type) {
+                return Column::Nested(ts_type.to_string());
+            } else {
+                "__mf_csvDecoders.json".to_string()
+            }
+        }
+    };
+    Column::Cell(decoder)
+}
+
+/// A plain type reference such as `Address`, excluding the builtins that
+/// are encoded as JSON.
+fn is_type_name(ts_type: &str) -> bool {
+    ts_type.chars().next().is_some_and(char::is_uppercase)
+        && ts_type
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        && !matches!(ts_type, "Object" | "RegExp" | "URL" | "Uint8Array")
+}
+
+/// What a missing cell decodes to, following the optionality of the field.
+fn missing_mode(ts_type: &str, optional: bool, has_default: bool) -> &'static str {
+    let members = split_top_level(ts_type, '|');
+    if members.contains(&"null") {
+        "nullable"
+    } else if optional || has_default || members.contains(&"undefined") {
+        "optional"
+    } else {
+        "required"
+    }
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// A field to map.
+struct CsvField<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+/// The generated code for one field.
+#[derive(Debug, Default, PartialEq)]
+struct FieldCode {
+    /// Entries of the header array.
+    header: String,
+    /// Entries of the row array.
+    cells: String,
+    /// Statements reading the field into its local.
+    read: String,
+}
+
+/// Generates the code for one field, or `None` when it has no column.
+fn field_code(
+    field: &CsvField<'_>,
+    container: &SerdeContainerOptions,
+    local: &str,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<FieldCode> {
+    let opts = CsvOptions::from_decorators(field.decorators);
+    let serde = SerdeFieldOptions::from_decorators(field.decorators, field.name).options;
+    if opts.skip || !serde.should_serialize() || !serde.should_deserialize() {
+        return None;
+    }
+
+    let column_name = serde
+        .rename
+        .clone()
+        .unwrap_or_else(|| container.rename_all.apply(field.name));
+    let access = if is_identifier(field.name) {
+        format!("value.{}", field.name)
+    } else {
+        format!("value[{}]", js_string(field.name))
+    };
+    let missing = missing_mode(
+        field.ts_type,
+        field.optional,
+        serde.default || serde.default_expr.is_some(),
+    );
+    let missing_arg = if missing == "required" {
+        String::new()
+    } else {
+        format!(", \"{missing}\"")
+    };
+
+    let mut column = column(field.ts_type);
+    if let Some(cell) = &opts.cell {
+        match named_decoder(cell) {
+            Some(decoder) => column = Column::Cell(decoder),
+            None => {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "@csv cell '{cell}' on field '{}' is not one of string, number, bigint, boolean, date or json",
+                        field.name
+                    ),
+                );
+                return None;
+            }
+        }
+    }
+    if let Some(deserialize_with) = &serde.deserialize_with {
+        column = Column::Cell(format!("__mf_csvDecoders.with({deserialize_with})"));
+    }
+
+    let code = match column {
+        Column::Nested(type_name) => {
+            let prefix = type_name.to_case(Case::Camel);
+            let column_prefix = if serde.flatten {
+                String::new()
+            } else {
+                format!("{column_name}.")
+            };
+            let header = if serde.flatten {
+                format!("...{prefix}CsvHeader()")
+            } else {
+                format!(
+                    "...{prefix}CsvHeader().map((column) => {} + column)",
+                    js_string(&column_prefix)
+                )
+            };
+            FieldCode {
+                header,
+                cells: format!(
+                    "...__mf_csvNested({access}, {prefix}CsvHeader(), {prefix}ToCsvRow)"
+                ),
+                read: format!(
+                    "const {local} = __mf_readNested(record, {}, {prefix}FromCsvRow, errors{missing_arg});",
+                    js_string(&column_prefix)
+                ),
+            }
+        }
+        Column::Cell(decoder) => {
+            if serde.flatten {
+                diagnostics.error(
+                    field.span,
+                    format!(
+                        "@serde flatten on field '{}' requires a type deriving Csv",
+                        field.name
+                    ),
+                );
+                return None;
+            }
+            let cell = match &serde.serialize_with {
+                Some(serialize_with) => format!("__mf_csvCell({serialize_with}({access}))"),
+                None => format!("__mf_csvCell({access})"),
+            };
+            let column = js_string(&column_name);
+            let fallback = serde
+                .default_expr
+                .as_ref()
+                .map(|d| format!(" ?? ({d})"))
+                .unwrap_or_default();
+            let mut read = format!(
+                "const {local} = __mf_readCell(record, {column}, {decoder}, errors{missing_arg}){fallback};"
+            );
+            let checks = validations(&serde.validators, local, &column);
+            if !checks.is_empty() {
+                read.push_str(&format!(
+                    "\n    if ({local} !== undefined && {local} !== null) {{{checks}\n    }}"
+                ));
+            }
+            FieldCode {
+                header: column,
+                cells: cell,
+                read,
+            }
+        }
+    };
+    Some(code)
+}
+
+/// Runs the serde validators on `local`, reporting failures for `column`.
+fn validations(validators: &[ValidatorSpec], local: &str, column: &str) -> String {
+    validators
+        .iter()
+        .map(|spec| {
+            let condition = match &spec.validator {
+                Validator::Custom(function) => format!("{function}({local}) === false"),
+                other => generate_validation_condition(other, local),
+            };
+            let message = spec
+                .custom_message
+                .clone()
+                .unwrap_or_else(|| get_validator_message(&spec.validator));
+            format!(
+                "\n        if ({condition}) {{\n            errors.push({{ field: {column}, message: {} }});\n        }}",
+                js_string(&message)
+            )
+        })
+        .collect()
+}
+
+fn generate_csv_functions(
+    type_name: &str,
+    container: &SerdeContainerOptions,
+    fields: &[CsvField<'_>],
+    construct: impl Fn(&str) -> String,
+    diagnostics: &mut DiagnosticCollector,
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let mut header = Vec::new();
+    let mut cells = Vec::new();
+    let mut reads = Vec::new();
+    let mut properties = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let (local, key) = if is_identifier(field.name) {
+            (format!("__{}", field.name), field.name.to_string())
+        } else {
+            (format!("__field{i}"), js_string(field.name))
+        };
+        let Some(code) = field_code(field, container, &local, diagnostics) else {
+            continue;
+        };
+        header.push(code.header);
+        cells.push(code.cells);
+        reads.push(code.read);
+        properties.push(format!("{key}: {local}"));
+    }
+    let value = construct(&format!("{{ {} }}", properties.join(", ")));
+
+    format!(
+        r#"/** The CSV columns, in row order. */
+export function {prefix}CsvHeader(): string[] {{
+    return [{}];
+}}
+
+/** Encodes a value as the cells of one CSV row. */
+export function {prefix}ToCsvRow(value: {type_name}): string[] {{
+    return [{}];
+}}
+
+/**
+ * Decodes one CSV record, collecting every missing or invalid cell.
+ * @param record - The cells, keyed by column
+ */
+export function {prefix}FromCsvRow(record: __mf_CsvRecord): __mf_CsvResult<{type_name}> {{
+    const errors: __mf_CsvError[] = [];
+    {}
+    if (errors.length > 0) {{
+        return {{ success: false, errors }};
+    }}
+    return {{ success: true, value: {value} as {type_name} }};
+}}
+
+/** Yields the header line and one line per row, each ending with CRLF. */
+export function {prefix}CsvLines(rows: Iterable<{type_name}>): Generator<string> {{
+    return __mf_csvLines({prefix}CsvHeader(), rows, {prefix}ToCsvRow);
+}}
+
+/** Formats rows as a CSV document with a header line. */
+export function {prefix}ToCsv(rows: Iterable<{type_name}>): string {{
+    return __mf_toCsv({prefix}CsvHeader(), rows, {prefix}ToCsvRow);
+}}
+
+/** Parses a CSV document with a header line. */
+export function {prefix}ParseCsv(text: string): __mf_CsvResult<{type_name}[]> {{
+    return __mf_parseCsv(text, {prefix}FromCsvRow);
+}}"#,
+        header.join(", "),
+        cells.join(", "),
+        reads.join("\n    ")
+    )
+}
+
+#[ts_macro_derive(
+    Csv,
+    description = "Generates CSV row encoding and decoding with RFC 4180 quoting and header mapping",
+    attributes((csv, "Configure a column. Options: cell, skip"))
+)]
+pub fn derive_csv_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let container = SerdeContainerOptions::from_decorators(&container_decorators);
+    let mut diagnostics = DiagnosticCollector::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields = class
+                .fields()
+                .iter()
+                .map(|f| CsvField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let standalone = generate_csv_functions(
+                type_name,
+                &container,
+                &fields,
+                |properties| {
+                    format!("Object.assign(Object.create({type_name}.prototype), {properties})")
+                },
+                &mut diagnostics,
+            );
+            let prefix = type_name.to_case(Case::Camel);
+            let header_fn = format!("{prefix}CsvHeader");
+            let to_row_fn = format!("{prefix}ToCsvRow");
+            let from_row_fn = format!("{prefix}FromCsvRow");
+            let to_csv_fn = format!("{prefix}ToCsv");
+            let parse_fn = format!("{prefix}ParseCsv");
+            let row_result = format!("__mf_CsvResult<{type_name}>");
+            let rows_result = format!("__mf_CsvResult<{type_name}[]>");
+            let rows_type = format!("Iterable<{type_name}>");
+            let class_body = body! {
+                static csvHeader(): string[] {
+                    return @{header_fn}();
+                }
+
+                static toCsvRow(value: @{type_name}): string[] {
+                    return @{to_row_fn}(value);
+                }
+
+                static fromCsvRow(record: __mf_CsvRecord): @{row_result} {
+                    return @{from_row_fn}(record);
+                }
+
+                static toCsv(rows: @{rows_type}): string {
+                    return @{to_csv_fn}(rows);
+                }
+
+                static parseCsv(text: string): @{rows_result} {
+                    return @{parse_fn}(text);
+                }
+            };
+
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = interface
+                .fields()
+                .iter()
+                .map(|f| CsvField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            TsStream::from_string(generate_csv_functions(
+                type_name,
+                &container,
+                &fields,
+                str::to_string,
+                &mut diagnostics,
+            ))
+        }
+        Data::Enum(_) | Data::TypeAlias(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(Csv) can only be used on classes and interfaces",
+            ));
+        }
+    };
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    output.add_aliased_import("csvCell", "macroforge/csv");
+    output.add_aliased_import("csvNested", "macroforge/csv");
+    output.add_aliased_import("csvDecoders", "macroforge/csv");
+    output.add_aliased_import("readCell", "macroforge/csv");
+    output.add_aliased_import("readNested", "macroforge/csv");
+    output.add_aliased_import("csvLines", "macroforge/csv");
+    output.add_aliased_import("toCsv", "macroforge/csv");
+    output.add_aliased_import("parseCsv", "macroforge/csv");
+    output.add_aliased_type_import("CsvRecord", "macroforge/csv");
+    output.add_aliased_type_import("CsvResult", "macroforge/csv");
+    output.add_aliased_type_import("CsvError", "macroforge/csv");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn field<'a>(name: &'a str, ts_type: &'a str, decorators: &'a [DecoratorIR]) -> CsvField<'a> {
+        CsvField {
+            name,
+            ts_type,
+            optional: false,
+            decorators,
+            span: SpanIR::new(0, 0),
+        }
+    }
+
+    #[test]
+    fn test_column() {
+        assert_eq!(
+            column("number | undefined"),
+            Column::Cell("__mf_csvDecoders.number".into())
+        );
+        assert_eq!(
+            column("\"open\" | \"closed\""),
+            Column::Cell("__mf_csvDecoders.oneOf([\"open\", \"closed\"])".into())
+        );
+        assert_eq!(
+            column("Map<string, number>"),
+            Column::Cell("__mf_csvDecoders.map".into())
+        );
+        assert_eq!(
+            column("string[]"),
+            Column::Cell("__mf_csvDecoders.json".into())
+        );
+        assert_eq!(column("Address | null"), Column::Nested("Address".into()));
+    }
+
+    #[test]
+    fn test_missing_mode() {
+        assert_eq!(missing_mode("string", false, false), "required");
+        assert_eq!(missing_mode("string", true, false), "optional");
+        assert_eq!(missing_mode("string | undefined", false, false), "optional");
+        assert_eq!(missing_mode("Date | null", false, false), "nullable");
+        assert_eq!(missing_mode("number", false, true), "optional");
+    }
+
+    #[test]
+    fn test_rename_all_and_nested_columns() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let container = SerdeContainerOptions::from_decorators(&[decorator(
+            "serde",
+            "{ renameAll: \"snake_case\" }",
+        )]);
+        let code = field_code(
+            &field("fullName", "string", &[]),
+            &container,
+            "__fullName",
+            &mut diagnostics,
+        )
+        .unwrap();
+        assert_eq!(code.header, "\"full_name\"");
+        assert_eq!(code.cells, "__mf_csvCell(value.fullName)");
+        assert_eq!(
+            code.read,
+            "const __fullName = __mf_readCell(record, \"full_name\", __mf_csvDecoders.string, errors);"
+        );
+
+        let code = field_code(
+            &field("billingAddress", "Address | undefined", &[]),
+            &container,
+            "__billingAddress",
+            &mut diagnostics,
+        )
+        .unwrap();
+        assert_eq!(
+            code.header,
+            "...addressCsvHeader().map((column) => \"billing_address.\" + column)"
+        );
+        assert_eq!(
+            code.cells,
+            "...__mf_csvNested(value.billingAddress, addressCsvHeader(), addressToCsvRow)"
+        );
+        assert_eq!(
+            code.read,
+            "const __billingAddress = __mf_readNested(record, \"billing_address.\", addressFromCsvRow, errors, \"optional\");"
+        );
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_serde_options() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let container = SerdeContainerOptions::default();
+        let decorators = [decorator(
+            "serde",
+            "{ rename: \"total\", serializeWith: \"formatCents\", deserializeWith: \"parseCents\", validate: [\"positive\"] }",
+        )];
+        let code = field_code(
+            &field("amount", "number", &decorators),
+            &container,
+            "__amount",
+            &mut diagnostics,
+        )
+        .unwrap();
+        assert_eq!(code.cells, "__mf_csvCell(formatCents(value.amount))");
+        assert!(code.read.contains(
+            "__mf_readCell(record, \"total\", __mf_csvDecoders.with(parseCents), errors);"
+        ));
+        assert!(
+            code.read
+                .contains("errors.push({ field: \"total\", message: \"must be positive\" });")
+        );
+
+        let decorators = [decorator("serde", "{ skip: true }")];
+        assert_eq!(
+            field_code(
+                &field("secret", "string", &decorators),
+                &container,
+                "__secret",
+                &mut diagnostics,
+            ),
+            None
+        );
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_cell_is_reported() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let decorators = [decorator("csv", "{ cell: \"money\" }")];
+        field_code(
+            &field("amount", "Cents", &decorators),
+            &SerdeContainerOptions::default(),
+            "__amount",
+            &mut diagnostics,
+        );
+        assert!(diagnostics.has_errors());
+    }
+}
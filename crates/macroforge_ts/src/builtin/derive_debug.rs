@@ -5,17 +5,41 @@
 //!
 //! ## Generated Output
 //!
-//! **Classes**: Generates a standalone function `classNameToString(value)` and a static wrapper
-//! method `static toString(value)` returning a string like `"ClassName { field1: value1, field2: value2 }"`.
+//! **Classes**: Generates a standalone function `classNameToString(value, pretty?, ctx?)` and a static wrapper
+//! method `static toString(value, pretty?, ctx?)` returning a string like `"ClassName { field1: value1, field2: value2 }"`.
+//! The class is also tagged with the `debugDerived` marker from `macroforge/debug`.
 //!
 //! **Enums**: Generates a standalone function `enumNameToString(value)` that performs
-//! reverse lookup on numeric enums.
+//! reverse lookup on numeric enums. Enums that derive `Bitflags` are formatted
+//! as their set flags, e.g. `Read | Write`.
 //!
-//! **Interfaces**: Generates a standalone function `interfaceNameToString(value)`.
+//! **Interfaces**: Generates a standalone function `interfaceNameToString(value, pretty?, ctx?)`.
 //!
 //! **Type Aliases**: Generates a standalone function using JSON.stringify for
 //! complex types, or field enumeration for object types.
 //!
+//! Nested objects, arrays, `Map`s and `Set`s are formatted recursively by
+//! `debugFormat` from `macroforge/debug`. Classes tagged with `debugDerived`
+//! are formatted through their static `toString`, which receives the outer
+//! call's context as its `ctx` argument, so their field options (skip,
+//! redact, ...) also apply when they are nested, while the depth, limits and
+//! seen objects of the outer call carry over. Other classes are formatted
+//! field by field, even if they define a static `toString` of their own.
+//! Cyclic references, also across classes, print as `[Circular]`.
+//!
+//! ## Pretty Output
+//!
+//! Passing `true` as the second argument produces an indented multi-line
+//! string, similar to Rust's `{:#?}`:
+//!
+//! ```text
+//! User {
+//!     id: 1,
+//!     tags: [
+//!         admin,
+//!     ],
+//! }
+//! ```
 //!
 //! ## Field-Level Options
 //!
@@ -23,18 +47,35 @@
 //!
 //! - `skip` - Exclude the field from debug output
 //! - `rename = "label"` - Use a custom label instead of the field name
+//! - `redact` - Keep the field label but print `<redacted>` instead of the value
+//! - `format = "fn"` - Format the value with a custom function `(value) => string`
+//!
+//! ## Container-Level Options
+//!
+//! A `@debug` decorator next to `@derive(Debug)` supports:
+//!
+//! - `maxDepth = n` - Nested values more than `n` levels below the type are
+//!   printed as `{ … }` / `[…]`
+//! - `maxItems = n` - Arrays, maps, sets and objects show at most `n` entries,
+//!   followed by `…42 more`
 //!
 //! ## Example
 //!
 //! ```typescript
-//! /** @derive(Debug) */
+//! /**
+//!  * @derive(Debug)
+//!  * @debug({ maxItems: 10 })
+//!  */
 //! class User {
 //!     /** @debug({ rename: "id" }) */
 //!     userId: number;
 //!
-//!     /** @debug({ skip: true }) */
+//!     /** @debug({ redact: true }) */
 //!     password: string;
 //!
+//!     /** @debug({ skip: true }) */
+//!     cache: Map<string, string>;
+//!
 //!     email: string;
 //! }
 //! ```
@@ -44,27 +85,42 @@
 //! ```typescript
 //! class User {
 //!     userId: number;
-//! 
+//!
 //!     password: string;
-//! 
+//!
+//!     cache: Map<string, string>;
+//!
 //!     email: string;
-//! 
-//!     static toString(value: User): string {
-//!         return userToString(value);
+//!
+//!     static readonly [__mf_debugDerived] = true;
+//!
+//!     static toString(value: User, pretty?: boolean, ctx?: __mf_DebugContext): string {
+//!         return userToString(value, pretty, ctx);
 //!     }
 //! }
-//! 
-//! export function userToString(value: User): string {
+//!
+//! export function userToString(value: User, pretty?: boolean, ctx?: __mf_DebugContext): string {
+//!     const __c = __mf_debugContext(pretty, ctx);
+//!     const __indent = __c.pretty ? __c.indent + "    " : "";
+//!     const __inner = { ...__c, depth: __c.depth + 1, indent: __indent, maxItems: Math.min(__c.maxItems, 10) };
+//!     __c.seen.add(value);
 //!     const parts: string[] = [];
-//!     parts.push('id: ' + value.userId);
-//!     parts.push('email: ' + value.email);
-//!     return 'User { ' + parts.join(', ') + ' }';
+//!     parts.push("id: " + __mf_debugFormat(value.userId, __inner));
+//!     parts.push("password: <redacted>");
+//!     parts.push("email: " + __mf_debugFormat(value.email, __inner));
+//!     __c.seen.delete(value);
+//!     if (__c.pretty) {
+//!         return "User {\n" + parts.map((p) => __indent + p + ",\n").join("") + __c.indent + "}";
+//!     }
+//!     return "User { " + parts.join(", ") + " }";
 //! }
 //! ```
 
 use convert_case::{Case, Casing};
 
+use super::derive_common::{derives_macro, extract_named_number};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::DecoratorIR;
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
 /// Options parsed from @Debug decorator on fields
@@ -72,10 +128,12 @@ use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro
 struct DebugFieldOptions {
     skip: bool,
     rename: Option<String>,
+    redact: bool,
+    format: Option<String>,
 }
 
 impl DebugFieldOptions {
-    fn from_decorators(decorators: &[crate::ts_syn::abi::DecoratorIR]) -> Self {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
         let mut opts = DebugFieldOptions::default();
         for decorator in decorators {
             if !decorator.name.eq_ignore_ascii_case("debug") {
@@ -94,6 +152,41 @@ impl DebugFieldOptions {
             if let Some(rename) = extract_named_string(args, "rename") {
                 opts.rename = Some(rename);
             }
+
+            if has_flag(args, "redact") {
+                opts.redact = true;
+            }
+
+            if let Some(format) = extract_named_string(args, "format") {
+                opts.format = Some(format);
+            }
+        }
+        opts
+    }
+}
+
+/// Options parsed from a container-level @debug decorator
+#[derive(Default)]
+struct DebugContainerOptions {
+    max_depth: Option<usize>,
+    max_items: Option<usize>,
+}
+
+impl DebugContainerOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = DebugContainerOptions::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("debug") {
+                continue;
+            }
+
+            let args = decorator.args_src.trim();
+            if let Some(depth) = extract_named_number(args, "maxDepth") {
+                opts.max_depth = Some(depth.max(0.0) as usize);
+            }
+            if let Some(items) = extract_named_number(args, "maxItems") {
+                opts.max_items = Some(items.max(0.0) as usize);
+            }
         }
         opts
     }
@@ -163,17 +256,105 @@ fn parse_string_literal(input: &str) -> Option<String> {
     None
 }
 
-/// Debug field info: (label, field_name)
-type DebugField = (String, String);
+/// A field included in debug output
+struct DebugField {
+    /// Label printed before the value (the field name unless renamed)
+    label: String,
+    /// Property name on the value
+    name: String,
+    redact: bool,
+    format: Option<String>,
+}
+
+/// Collects a field for debug output, or `None` if it is skipped
+fn collect_debug_field(name: &str, decorators: &[DecoratorIR]) -> Option<DebugField> {
+    let opts = DebugFieldOptions::from_decorators(decorators);
+    if opts.skip {
+        return None;
+    }
+    Some(DebugField {
+        label: opts.rename.unwrap_or_else(|| name.to_string()),
+        name: name.to_string(),
+        redact: opts.redact,
+        format: opts.format,
+    })
+}
+
+/// Generates the `parts.push(...)` statement for a single field
+fn generate_field_part(field: &DebugField) -> String {
+    let DebugField {
+        label,
+        name,
+        redact,
+        format,
+    } = field;
+
+    if *redact {
+        format!("parts.push(\"{label}: <redacted>\");")
+    } else if let Some(format_fn) = format {
+        format!("parts.push(\"{label}: \" + String(({format_fn})(value.{name})));")
+    } else {
+        format!("parts.push(\"{label}: \" + __mf_debugFormat(value.{name}, __inner));")
+    }
+}
+
+/// Generates the body of a `toString` function for a type with named fields.
+///
+/// When the function formats a nested value it continues the caller's `ctx`;
+/// the type's own `maxDepth` / `maxItems` can only tighten the inherited limits.
+fn generate_fields_to_string_body(
+    type_name: &str,
+    fields: &[DebugField],
+    opts: &DebugContainerOptions,
+) -> String {
+    if fields.is_empty() {
+        return format!("return \"{type_name} {{}}\";");
+    }
+
+    let limits: String = [("maxDepth", opts.max_depth), ("maxItems", opts.max_items)]
+        .into_iter()
+        .filter_map(|(name, limit)| Some(format!(", {name}: Math.min(__c.{name}, {})", limit?)))
+        .collect();
+    let parts: Vec<String> = fields.iter().map(generate_field_part).collect();
+    let parts = parts.join("\n");
+
+    format!(
+        r#"const __c = __mf_debugContext(pretty, ctx);
+const __indent = __c.pretty ? __c.indent + "    " : "";
+const __inner = {{ ...__c, depth: __c.depth + 1, indent: __indent{limits} }};
+__c.seen.add(value);
+const parts: string[] = [];
+{parts}
+__c.seen.delete(value);
+if (__c.pretty) {{
+    return "{type_name} {{\n" + parts.map((p) => __indent + p + ",\n").join("") + __c.indent + "}}";
+}}
+return "{type_name} {{ " + parts.join(", ") + " }}";"#
+    )
+}
+
+/// Imports the `macroforge/debug` helpers used by a fields `toString` function.
+fn with_runtime_imports(mut output: TsStream, fields: &[DebugField]) -> TsStream {
+    output.add_aliased_type_import("DebugContext", "macroforge/debug");
+    if !fields.is_empty() {
+        output.add_aliased_import("debugContext", "macroforge/debug");
+        output.add_aliased_import("debugFormat", "macroforge/debug");
+    }
+    output
+}
 
 #[ts_macro_derive(
     Debug,
     description = "Generates a toString() method for debugging",
-    attributes((debug, "Configure debug output for this field. Options: skip (exclude from output), rename (custom label)"))
+    attributes((debug, "Configure debug output. Field options: skip (exclude from output), rename (custom label), redact (print <redacted>), format (custom formatter function). Container options: maxDepth, maxItems"))
 )]
 pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let container_opts = DebugContainerOptions::from_decorators(&container_decorators);
+
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
@@ -182,40 +363,29 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
             let debug_fields: Vec<DebugField> = class
                 .fields()
                 .iter()
-                .filter_map(|field| {
-                    let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                    if opts.skip {
-                        return None;
-                    }
-                    let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                    Some((label, field.name.clone()))
-                })
+                .filter_map(|field| collect_debug_field(&field.name, &field.decorators))
                 .collect();
 
-            let has_fields = !debug_fields.is_empty();
+            let fn_body =
+                generate_fields_to_string_body(class_name, &debug_fields, &container_opts);
 
             // Generate function name (always prefix style)
             let fn_name = format!("{}ToString", class_name.to_case(Case::Camel));
 
             // Generate standalone function with value parameter
             let standalone = ts_template! {
-                export function @{fn_name}(value: @{class_name}): string {
-                    {#if has_fields}
-                        const parts: string[] = [];
-                        {#for (label, name) in debug_fields}
-                            parts.push("@{label}: " + value.@{name});
-                        {/for}
-                        return "@{class_name} { " + parts.join(", ") + " }";
-                    {:else}
-                        return "@{class_name} {}";
-                    {/if}
+                export function @{fn_name}(value: @{class_name}, pretty?: boolean, ctx?: __mf_DebugContext): string {
+                    @{fn_body}
                 }
             };
 
-            // Generate static wrapper method that delegates to standalone function
+            // Generate the marker `debugFormat` looks for and a static wrapper
+            // method that delegates to the standalone function
             let class_body = body! {
-                static toString(value: @{class_name}): string {
-                    return @{fn_name}(value);
+                static readonly [__mf_debugDerived] = true;
+
+                static toString(value: @{class_name}, pretty?: boolean, ctx?: __mf_DebugContext): string {
+                    return @{fn_name}(value, pretty, ctx);
                 }
             };
 
@@ -227,7 +397,9 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
             combined.runtime_patches = standalone.runtime_patches;
             combined.runtime_patches.extend(class_body.runtime_patches);
 
-            Ok(combined)
+            combined.add_aliased_import("debugDerived", "macroforge/debug");
+
+            Ok(with_runtime_imports(combined, &debug_fields))
         }
         Data::Enum(enum_data) => {
             let enum_name = input.name();
@@ -239,6 +411,16 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
             let has_variants = !variants.is_empty();
 
             let fn_name = format!("{}ToString", enum_name.to_case(Case::Camel));
+
+            if derives_macro(&enum_data.inner.decorators, "Bitflags") {
+                let format_fn = format!("{}Format", enum_name.to_case(Case::Camel));
+                return Ok(ts_template! {
+                    export function @{fn_name}(value: @{enum_name}): string {
+                        return @{format_fn}(value);
+                    }
+                });
+            }
+
             Ok(ts_template! {
                 export function @{fn_name}(value: @{enum_name}): string {
                     {#if has_variants}
@@ -260,32 +442,19 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
             let debug_fields: Vec<DebugField> = interface
                 .fields()
                 .iter()
-                .filter_map(|field| {
-                    let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                    if opts.skip {
-                        return None;
-                    }
-                    let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                    Some((label, field.name.clone()))
-                })
+                .filter_map(|field| collect_debug_field(&field.name, &field.decorators))
                 .collect();
 
-            let has_fields = !debug_fields.is_empty();
+            let fn_body =
+                generate_fields_to_string_body(interface_name, &debug_fields, &container_opts);
             let fn_name = format!("{}ToString", interface_name.to_case(Case::Camel));
 
-            Ok(ts_template! {
-                export function @{fn_name}(value: @{interface_name}): string {
-                    {#if has_fields}
-                        const parts: string[] = [];
-                        {#for (label, name) in debug_fields}
-                            parts.push("@{label}: " + value.@{name});
-                        {/for}
-                        return "@{interface_name} { " + parts.join(", ") + " }";
-                    {:else}
-                        return "@{interface_name} {}";
-                    {/if}
+            let output = ts_template! {
+                export function @{fn_name}(value: @{interface_name}, pretty?: boolean, ctx?: __mf_DebugContext): string {
+                    @{fn_body}
                 }
-            })
+            };
+            Ok(with_runtime_imports(output, &debug_fields))
         }
         Data::TypeAlias(type_alias) => {
             let type_name = input.name();
@@ -297,39 +466,26 @@ pub fn derive_debug_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErr
                     .as_object()
                     .unwrap()
                     .iter()
-                    .filter_map(|field| {
-                        let opts = DebugFieldOptions::from_decorators(&field.decorators);
-                        if opts.skip {
-                            return None;
-                        }
-                        let label = opts.rename.unwrap_or_else(|| field.name.clone());
-                        Some((label, field.name.clone()))
-                    })
+                    .filter_map(|field| collect_debug_field(&field.name, &field.decorators))
                     .collect();
 
-                let has_fields = !debug_fields.is_empty();
+                let fn_body =
+                    generate_fields_to_string_body(type_name, &debug_fields, &container_opts);
                 let fn_name = format!("{}ToString", type_name.to_case(Case::Camel));
 
-                Ok(ts_template! {
-                    export function @{fn_name}(value: @{type_name}): string {
-                        {#if has_fields}
-                            const parts: string[] = [];
-                            {#for (label, name) in debug_fields}
-                                parts.push("@{label}: " + value.@{name});
-                            {/for}
-                            return "@{type_name} { " + parts.join(", ") + " }";
-                        {:else}
-                            return "@{type_name} {}";
-                        {/if}
+                let output = ts_template! {
+                    export function @{fn_name}(value: @{type_name}, pretty?: boolean, ctx?: __mf_DebugContext): string {
+                        @{fn_body}
                     }
-                })
+                };
+                Ok(with_runtime_imports(output, &debug_fields))
             } else {
                 // Union, intersection, tuple, or simple alias: use JSON.stringify
                 let fn_name = format!("{}ToString", type_name.to_case(Case::Camel));
 
                 Ok(ts_template! {
-                    export function @{fn_name}(value: @{type_name}): string {
-                        return "@{type_name}(" + JSON.stringify(value) + ")";
+                    export function @{fn_name}(value: @{type_name}, pretty?: boolean): string {
+                        return "@{type_name}(" + JSON.stringify(value, null, pretty ? 4 : undefined) + ")";
                     }
                 })
             }
@@ -384,4 +540,79 @@ mod tests {
         let opts = DebugFieldOptions::from_decorators(&[decorator]);
         assert_eq!(opts.rename.as_deref(), Some("identifier"));
     }
+
+    #[test]
+    fn test_redact_and_format_options() {
+        let decorator = DecoratorIR {
+            name: "debug".into(),
+            args_src: r#"{ redact: true, format: "formatDate" }"#.into(),
+            span: span(),
+            node: None,
+        };
+
+        let opts = DebugFieldOptions::from_decorators(&[decorator]);
+        assert!(opts.redact, "redact flag should be true");
+        assert_eq!(opts.format.as_deref(), Some("formatDate"));
+    }
+
+    #[test]
+    fn test_container_limits() {
+        let decorator = DecoratorIR {
+            name: "debug".into(),
+            args_src: "{ maxDepth: 2, maxItems: 10 }".into(),
+            span: span(),
+            node: None,
+        };
+
+        let opts = DebugContainerOptions::from_decorators(&[decorator]);
+        assert_eq!(opts.max_depth, Some(2));
+        assert_eq!(opts.max_items, Some(10));
+    }
+
+    #[test]
+    fn test_field_parts() {
+        let field = |redact, format: Option<&str>| DebugField {
+            label: "secret".into(),
+            name: "apiKey".into(),
+            redact,
+            format: format.map(String::from),
+        };
+
+        assert_eq!(
+            generate_field_part(&field(true, None)),
+            r#"parts.push("secret: <redacted>");"#
+        );
+        assert!(generate_field_part(&field(false, Some("mask"))).contains("(mask)(value.apiKey)"));
+        assert!(
+            generate_field_part(&field(false, None))
+                .contains("__mf_debugFormat(value.apiKey, __inner)")
+        );
+    }
+
+    #[test]
+    fn test_body_respects_limits_and_pretty() {
+        let fields = vec![DebugField {
+            label: "tags".into(),
+            name: "tags".into(),
+            redact: false,
+            format: None,
+        }];
+        let opts = DebugContainerOptions {
+            max_depth: Some(3),
+            max_items: Some(5),
+        };
+
+        let body = generate_fields_to_string_body("User", &fields, &opts);
+        assert!(body.contains(
+            "const __inner = { ...__c, depth: __c.depth + 1, indent: __indent, maxDepth: Math.min(__c.maxDepth, 3), maxItems: Math.min(__c.maxItems, 5) };"
+        ));
+        assert!(body.starts_with("const __c = __mf_debugContext(pretty, ctx);"));
+        assert!(body.contains("if (__c.pretty)"));
+
+        let unlimited = generate_fields_to_string_body("User", &fields, &Default::default());
+        assert!(!unlimited.contains("Math.min"));
+
+        let empty = generate_fields_to_string_body("Empty", &[], &opts);
+        assert_eq!(empty, r#"return "Empty {}";"#);
+    }
 }
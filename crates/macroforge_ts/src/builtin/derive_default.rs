@@ -40,6 +40,9 @@
 //! - `@default("hello")` - Use "hello" as the default
 //! - `@default([])` - Use empty array as the default
 //! - `@default({ value: "test" })` - Named form for complex values
+//! - `@default({ factory: "nanoid", from: "nanoid" })` - Call a factory for each
+//!   default, importing it automatically (omit `from` for globals such as
+//!   `crypto.randomUUID`)
 //!
 //! ## Example
 //!
@@ -61,11 +64,11 @@
 //! ```typescript
 //! class UserSettings {
 //!     theme: string;
-//! 
+//!
 //!     pageSize: number;
-//! 
+//!
 //!     notifications: boolean; // Uses type default: false
-//! 
+//!
 //!     static defaultValue(): UserSettings {
 //!         const instance = new UserSettings();
 //!         instance.theme = 'light';
@@ -99,16 +102,38 @@
 //!     Active,
 //!     Completed
 //! }
-//! 
+//!
 //! export function statusDefaultValue(): Status {
 //!     return Status.Pending;
 //! }
-//! 
+//!
 //! namespace Status {
 //!     export const defaultValue = statusDefaultValue;
 //! }
 //! ```
 //!
+//! ## Union Defaults
+//!
+//! For union type aliases, mark one member with `@default`. Named members use
+//! their own derived default, and inline object members (as in discriminated
+//! unions) get each field filled from its type default or `@default`:
+//!
+//! ```typescript
+//! /** @derive(Default) */
+//! type Shape =
+//!     /** @default */
+//!     | { kind: "circle"; radius: number }
+//!     | { kind: "square"; size: number };
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export function shapeDefaultValue(): Shape {
+//!     return { kind: "circle", radius: 0 };
+//! }
+//! ```
+//!
 //! ## Error Handling
 //!
 //! The macro will return an error if:
@@ -121,6 +146,7 @@ use convert_case::{Case, Casing};
 
 use crate::builtin::derive_common::{DefaultFieldOptions, get_type_default, has_known_default};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, InterfaceFieldIR};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
 /// Contains field information needed for default value generation.
@@ -138,15 +164,77 @@ struct DefaultField {
     value: String,
 }
 
+/// Builds the defaults for all non-optional fields.
+///
+/// Factory defaults that come from a module record their `(specifier, module)`
+/// import in `imports`.
+fn build_default_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, bool, &'a [DecoratorIR])>,
+    imports: &mut Vec<(String, String)>,
+) -> Vec<DefaultField> {
+    fields
+        .into_iter()
+        .filter(|(_, _, optional, _)| !optional)
+        .map(|(name, ts_type, _, decorators)| {
+            let opts = DefaultFieldOptions::from_decorators(decorators);
+            if let Some(import) = opts.factory_import()
+                && !imports.contains(&import)
+            {
+                imports.push(import);
+            }
+            DefaultField {
+                name: name.to_string(),
+                value: opts.value.unwrap_or_else(|| get_type_default(ts_type)),
+            }
+        })
+        .collect()
+}
+
+/// Builds an object literal default for an inline object union member.
+fn object_member_default(
+    fields: &[InterfaceFieldIR],
+    imports: &mut Vec<(String, String)>,
+) -> String {
+    let default_fields = build_default_fields(
+        fields.iter().map(|f| {
+            (
+                f.name.as_str(),
+                f.ts_type.as_str(),
+                f.optional,
+                f.decorators.as_slice(),
+            )
+        }),
+        imports,
+    );
+    if default_fields.is_empty() {
+        return "{}".to_string();
+    }
+    let entries = default_fields
+        .iter()
+        .map(|f| format!("{}: {}", f.name, f.value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{ {entries} }}")
+}
+
 #[ts_macro_derive(
     Default,
     description = "Generates a static defaultValue() factory method",
-    attributes(default)
+    attributes((default, "Set the default value. Options: a literal value, value, factory (function called for each default), from (module to import the factory from)"))
 )]
 pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
-    match &input.data {
+    let container_opts = DefaultFieldOptions::from_decorators(
+        &input
+            .attrs
+            .iter()
+            .map(|a| a.inner.clone())
+            .collect::<Vec<_>>(),
+    );
+    let mut imports: Vec<(String, String)> = Vec::new();
+
+    let mut output = match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
 
@@ -184,18 +272,17 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
             }
 
             // Build defaults for ALL non-optional fields
-            let default_fields: Vec<DefaultField> = class
-                .fields()
-                .iter()
-                .filter(|field| !field.optional)
-                .map(|field| {
-                    let opts = DefaultFieldOptions::from_decorators(&field.decorators);
-                    DefaultField {
-                        name: field.name.clone(),
-                        value: opts.value.unwrap_or_else(|| get_type_default(&field.ts_type)),
-                    }
-                })
-                .collect();
+            let default_fields = build_default_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &mut imports,
+            );
 
             let has_defaults = !default_fields.is_empty();
 
@@ -229,7 +316,7 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
             };
             class_body.runtime_patches.extend(sibling.runtime_patches);
 
-            Ok(class_body)
+            class_body
         }
         Data::Enum(enum_data) => {
             let enum_name = input.name();
@@ -245,20 +332,22 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                 Some(variant) => {
                     let variant_name = &variant.name;
                     let fn_name = format!("{}DefaultValue", enum_name.to_case(Case::Camel));
-                    Ok(ts_template! {
+                    ts_template! {
                         export function @{fn_name}(): @{enum_name} {
                             return @{enum_name}.@{variant_name};
                         }
-                    })
+                    }
                 }
-                None => Err(MacroforgeError::new(
-                    input.decorator_span(),
-                    format!(
-                        "@derive(Default) on enum requires exactly one variant with @default attribute. \
+                None => {
+                    return Err(MacroforgeError::new(
+                        input.decorator_span(),
+                        format!(
+                            "@derive(Default) on enum requires exactly one variant with @default attribute. \
                         Add @default to one variant of {}",
-                        enum_name
-                    ),
-                )),
+                            enum_name
+                        ),
+                    ));
+                }
             }
         }
         Data::Interface(interface) => {
@@ -298,18 +387,17 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
             }
 
             // Build defaults for ALL non-optional fields
-            let default_fields: Vec<DefaultField> = interface
-                .fields()
-                .iter()
-                .filter(|field| !field.optional)
-                .map(|field| {
-                    let opts = DefaultFieldOptions::from_decorators(&field.decorators);
-                    DefaultField {
-                        name: field.name.clone(),
-                        value: opts.value.unwrap_or_else(|| get_type_default(&field.ts_type)),
-                    }
-                })
-                .collect();
+            let default_fields = build_default_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &mut imports,
+            );
 
             let has_defaults = !default_fields.is_empty();
 
@@ -325,7 +413,7 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
             };
 
             let fn_name = format!("{}DefaultValue", interface_name.to_case(Case::Camel));
-            Ok(ts_template! {
+            ts_template! {
                 export function @{fn_name}(): @{interface_name} {
                     return {
                         {#if has_defaults}
@@ -333,7 +421,7 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                         {/if}
                     } as @{interface_name};
                 }
-            })
+            }
         }
         Data::TypeAlias(type_alias) => {
             let type_name = input.name();
@@ -384,19 +472,17 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                 }
 
                 // Build defaults for ALL non-optional fields
-                let default_fields: Vec<DefaultField> = fields
-                    .iter()
-                    .filter(|field| !field.optional)
-                    .map(|field| {
-                        let opts = DefaultFieldOptions::from_decorators(&field.decorators);
-                        DefaultField {
-                            name: field.name.clone(),
-                            value: opts
-                                .value
-                                .unwrap_or_else(|| get_type_default(&field.ts_type)),
-                        }
-                    })
-                    .collect();
+                let default_fields = build_default_fields(
+                    fields.iter().map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.optional,
+                            f.decorators.as_slice(),
+                        )
+                    }),
+                    &mut imports,
+                );
 
                 let has_defaults = !default_fields.is_empty();
 
@@ -411,7 +497,7 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                 };
 
                 let fn_name = format!("{}DefaultValue", type_name.to_case(Case::Camel));
-                Ok(ts_template! {
+                ts_template! {
                     export function {|@{fn_name}@{generic_decl}|}(): @{full_type_name} {
                         return {
                             {#if has_defaults}
@@ -419,7 +505,7 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                             {/if}
                         } as @{full_type_name};
                     }
-                })
+                }
             } else if type_alias.is_union() {
                 // Union type: check for @default on a variant OR @default(...) on the type
                 let members = type_alias.as_union().unwrap();
@@ -445,28 +531,29 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                     ));
                 }
 
-                // First, look for a variant with @default decorator
-                let default_variant_from_member = members.iter().find_map(|member| {
-                    if member.has_decorator("default") {
-                        member.type_name().map(String::from)
-                    } else {
-                        None
-                    }
-                });
+                // First, look for a variant with @default decorator. Inline object
+                // members are filled in directly from their own field defaults.
+                let default_member = members
+                    .iter()
+                    .find(|member| member.has_decorator("default"));
+                let object_member_expr = default_member
+                    .and_then(|member| member.as_object())
+                    .map(|fields| object_member_default(fields, &mut imports));
+                let default_variant_from_member =
+                    default_member.and_then(|member| member.type_name().map(String::from));
 
                 // Fall back to @default(...) on the type alias itself
                 let default_variant = default_variant_from_member.or_else(|| {
-                    let default_opts = DefaultFieldOptions::from_decorators(
-                        &input
-                            .attrs
-                            .iter()
-                            .map(|a| a.inner.clone())
-                            .collect::<Vec<_>>(),
-                    );
-                    default_opts.value
+                    if default_member.is_some() {
+                        return None;
+                    }
+                    imports.extend(container_opts.factory_import());
+                    container_opts.value.clone()
                 });
 
-                if let Some(variant) = default_variant {
+                let default_expr = if let Some(expr) = object_member_expr {
+                    Some(expr)
+                } else if let Some(variant) = default_variant {
                     // Determine the default expression based on variant type
                     // Use as-is if it's already an expression, a literal, or a primitive value
                     let is_expression = variant.contains('.') || variant.contains('(');
@@ -478,16 +565,20 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                         || variant == "false"
                         || variant == "null";
 
-                    let default_expr = if is_expression || is_string_literal || is_primitive_value {
-                        variant // Use as-is
+                    if is_expression || is_string_literal || is_primitive_value {
+                        Some(variant) // Use as-is
                     } else {
                         // Use get_type_default which properly handles all types:
                         // - Primitives (string, number, boolean, bigint)
                         // - Generic types (RecordLink<Service>)
                         // - Named types (CompanyName, PersonName - interfaces/classes)
-                        get_type_default(&variant)
-                    };
+                        Some(get_type_default(&variant))
+                    }
+                } else {
+                    None
+                };
 
+                if let Some(default_expr) = default_expr {
                     // Handle generic type aliases (e.g., type RecordLink<T> = ...)
                     let type_params = type_alias.type_params();
                     let has_generics = !type_params.is_empty();
@@ -503,50 +594,48 @@ pub fn derive_default_macro(mut input: TsStream) -> Result<TsStream, MacroforgeE
                     };
 
                     let fn_name = format!("{}DefaultValue", type_name.to_case(Case::Camel));
-                    Ok(ts_template! {
+                    ts_template! {
                         export function @{fn_name}@{generic_params}(): @{return_type} {
                             return @{default_expr};
                         }
-                    })
+                    }
                 } else {
-                    Err(MacroforgeError::new(
+                    return Err(MacroforgeError::new(
                         input.decorator_span(),
                         format!(
                             "@derive(Default) on union type '{}' requires @default on one variant \
                             or @default(VariantName.defaultValue()) on the type.",
                             type_name
                         ),
-                    ))
+                    ));
                 }
             } else {
                 // Tuple or simple alias: check for explicit @default(value)
-                let default_opts = DefaultFieldOptions::from_decorators(
-                    &input
-                        .attrs
-                        .iter()
-                        .map(|a| a.inner.clone())
-                        .collect::<Vec<_>>(),
-                );
-
-                if let Some(default_variant) = default_opts.value {
+                if let Some(default_variant) = container_opts.value.clone() {
+                    imports.extend(container_opts.factory_import());
                     let fn_name = format!("{}DefaultValue", type_name.to_case(Case::Camel));
-                    Ok(ts_template! {
+                    ts_template! {
                         export function {|@{fn_name}@{generic_decl}|}(): @{full_type_name} {
                             return @{default_variant};
                         }
-                    })
+                    }
                 } else {
-                    Err(MacroforgeError::new(
+                    return Err(MacroforgeError::new(
                         input.decorator_span(),
                         format!(
                             "@derive(Default) on type '{}' requires @default(value) to specify the default.",
                             type_name
                         ),
-                    ))
+                    ));
                 }
             }
         }
+    };
+
+    for (specifier, module) in &imports {
+        output.add_import(specifier, module);
     }
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -624,4 +713,65 @@ mod tests {
         assert!(assignments.contains("instance.count = 42;"));
         assert!(assignments.contains("instance.items = [];"));
     }
+
+    fn make_decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: crate::ts_syn::abi::SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn make_field(name: &str, ts_type: &str, decorators: Vec<DecoratorIR>) -> InterfaceFieldIR {
+        InterfaceFieldIR {
+            name: name.to_string(),
+            span: crate::ts_syn::abi::SpanIR::new(0, 0),
+            ts_type: ts_type.to_string(),
+            optional: false,
+            readonly: false,
+            decorators,
+        }
+    }
+
+    #[test]
+    fn test_object_member_default() {
+        let fields = vec![
+            make_field("kind", r#""circle""#, vec![]),
+            make_field("radius", "number", vec![]),
+            make_field(
+                "label",
+                "string",
+                vec![make_decorator("default", r#""unit""#)],
+            ),
+        ];
+        let mut imports = Vec::new();
+        let expr = object_member_default(&fields, &mut imports);
+        assert_eq!(expr, r#"{ kind: "circle", radius: 0, label: "unit" }"#);
+        assert!(imports.is_empty());
+    }
+
+    #[test]
+    fn test_factory_imports_are_deduplicated() {
+        let decorator = make_decorator("default", r#"{ factory: "nanoid", from: "nanoid" }"#);
+        let fields = [
+            make_field("id", "string", vec![decorator.clone()]),
+            make_field("slug", "string", vec![decorator]),
+        ];
+        let mut imports = Vec::new();
+        let default_fields = build_default_fields(
+            fields.iter().map(|f| {
+                (
+                    f.name.as_str(),
+                    f.ts_type.as_str(),
+                    f.optional,
+                    f.decorators.as_slice(),
+                )
+            }),
+            &mut imports,
+        );
+        assert_eq!(default_fields[0].value, "nanoid()");
+        assert_eq!(default_fields[1].value, "nanoid()");
+        assert_eq!(imports, vec![("nanoid".to_string(), "nanoid".to_string())]);
+    }
 }
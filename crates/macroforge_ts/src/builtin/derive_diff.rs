@@ -0,0 +1,652 @@
+//! # Diff Macro Implementation
+//!
+//! The `Diff` macro computes what changed between two versions of a value,
+//! both as a typed change list and as an RFC 6902 JSON Patch, and generates
+//! a function that applies such a patch. Typical uses are audit logs and
+//! reconciling optimistic UI state with the server.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameDiff`, `classNameDiffChanges`, `classNameApplyPatch` + static wrappers | Standalone functions + static wrapper methods |
+//! | Enum | `enumNameDiff`, `enumNameDiffChanges`, `enumNameApplyPatch` | Standalone functions; the value is replaced as a whole |
+//! | Interface | `interfaceNameDiff`, `interfaceNameDiffChanges`, `interfaceNameApplyPatch` | Standalone functions |
+//! | Type Alias | `typeNameDiff`, `typeNameDiffChanges`, `typeNameApplyPatch` | Standalone functions |
+//!
+//! For a type `User`, the generated functions are:
+//!
+//! - `userDiff(a, b)` - Returns `{ changes, patch }`
+//! - `userDiffChanges(a, b, path?)` - Just the change list, with paths prefixed
+//!   by `path`; this is what nested types call
+//! - `userApplyPatch(value, patch)` - Applies a JSON Patch without mutating `value`
+//!
+//! The runtime helpers and the `DiffChange`, `JsonPatchOperation` and `Diff`
+//! types come from `macroforge/diff`.
+//!
+//! ## Field Comparison
+//!
+//! Each field is first checked with the same expression `PartialEq` would
+//! generate, honoring `@partialEq({ skip, epsilon, nanEqual, with })` and the
+//! container-level defaults. Only fields that are not equal are diffed:
+//!
+//! | Type | Diff Strategy |
+//! |------|---------------|
+//! | Primitives, `Date`, `Set`, custom `with` equality | Replaced as a whole |
+//! | Arrays | Element by element, or matched by `key` |
+//! | `Map` | Entry by entry, addressed by `String(key)` |
+//! | Other named types | That type's generated `*DiffChanges` |
+//! | Everything else | Structural diff at runtime |
+//!
+//! Like `Default`, named field types are assumed to derive `Diff` as well.
+//! Mark a field `@diff({ atomic: true })` to replace it as a whole instead.
+//!
+//! ## Field-Level Options
+//!
+//! The `@diff` decorator supports:
+//!
+//! - `skip` - Exclude the field from diffs (`@partialEq({ skip: true })` does too)
+//! - `key` - Identity property for array elements, e.g. `@diff({ key: "id" })`.
+//!   Inserted, removed and reordered elements then become `add`, `remove` and
+//!   `move` changes instead of a replacement per index
+//! - `atomic` - Never look inside the value; replace it as a whole
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Diff) */
+//! interface Order {
+//!     status: string;
+//!     /** @diff({ key: "sku" }) */
+//!     lines: OrderLine[];
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export function orderDiffChanges(a: Order, b: Order, path: string = ""): __mf_DiffChange[] {
+//!     const changes: __mf_DiffChange[] = [];
+//!     if (a === b) return changes;
+//!     if (!(a.status === b.status)) __mf_diffLeaf(a.status, b.status, path + "/status", changes);
+//!     if (!(/* PartialEq array check */)) __mf_diffArray(a.lines, b.lines, path + "/lines", changes, { key: "sku", diff: orderLineDiffChanges });
+//!     return changes;
+//! }
+//!
+//! export function orderDiff(a: Order, b: Order): __mf_Diff {
+//!     const changes = orderDiffChanges(a, b);
+//!     return { changes, patch: __mf_toJsonPatch(changes) };
+//! }
+//!
+//! export function orderApplyPatch(value: Order, patch: readonly __mf_JsonPatchOperation[]): Order {
+//!     return __mf_applyPatch(value, patch);
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    EqFieldOptions, derives_macro, extract_named_string, has_flag, is_primitive_type,
+    parse_generic_type, strip_nullable,
+};
+use crate::builtin::derive_partial_eq::{
+    EqField, collect_eq_fields, generate_field_equality_with_options,
+};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Global types that never have a generated differ of their own.
+const BUILTIN_TYPES: &[&str] = &[
+    "Array",
+    "ArrayBuffer",
+    "BigInt",
+    "Boolean",
+    "Date",
+    "Error",
+    "Function",
+    "Map",
+    "Number",
+    "Object",
+    "Partial",
+    "Promise",
+    "Readonly",
+    "ReadonlyArray",
+    "ReadonlyMap",
+    "ReadonlySet",
+    "Record",
+    "RegExp",
+    "Set",
+    "String",
+    "Symbol",
+    "URL",
+    "Uint8Array",
+    "WeakMap",
+    "WeakSet",
+];
+
+/// Options parsed from `@diff` decorators.
+#[derive(Default)]
+struct DiffFieldOptions {
+    skip: bool,
+    /// Identity property for array elements
+    key: Option<String>,
+    /// Replace the value as a whole
+    atomic: bool,
+}
+
+impl DiffFieldOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("diff") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            if has_flag(args, "skip") {
+                opts.skip = true;
+            }
+            if has_flag(args, "atomic") {
+                opts.atomic = true;
+            }
+            if let Some(key) = extract_named_string(args, "key") {
+                opts.key = Some(key);
+            }
+        }
+        opts
+    }
+}
+
+/// How a field that changed is diffed.
+#[derive(Debug, PartialEq)]
+enum DiffKind {
+    /// Replaced as a whole (`diffLeaf`)
+    Leaf,
+    /// `T[]` / `Array<T>`, with the element differ if `T` is a named type
+    Array { item: Option<String> },
+    /// `Map<K, V>`, with the value differ if `V` is a named type
+    Map { value: Option<String> },
+    /// A named type, diffed with its generated `*DiffChanges`
+    Nested(String),
+    /// Anything else (`diffValue`)
+    Structural,
+}
+
+impl DiffKind {
+    /// The `macroforge/diff` helper this kind calls.
+    fn helper(&self) -> &'static str {
+        match self {
+            DiffKind::Leaf => "diffLeaf",
+            DiffKind::Array { .. } => "diffArray",
+            DiffKind::Map { .. } => "diffMap",
+            DiffKind::Nested(_) => "diffNested",
+            DiffKind::Structural => "diffValue",
+        }
+    }
+}
+
+/// The generated differ for a named type, e.g. `addressDiffChanges` for
+/// `Address`, or `None` for primitives, built-ins and anything that isn't a
+/// plain type name.
+fn differ_for(ts_type: &str) -> Option<String> {
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = ts_type.trim();
+    let mut chars = ts_type.chars();
+    let named = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !named || BUILTIN_TYPES.contains(&ts_type) {
+        return None;
+    }
+    Some(format!("{}DiffChanges", ts_type.to_case(Case::Camel)))
+}
+
+/// Splits `K, V` at its first top-level comma.
+fn split_type_args(args: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => return Some((args[..i].trim(), args[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Picks the diff strategy for a field from its type and options.
+fn classify(ts_type: &str, eq: &EqFieldOptions, opts: &DiffFieldOptions) -> DiffKind {
+    if opts.atomic || eq.with.is_some() {
+        return DiffKind::Leaf;
+    }
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = ts_type.trim();
+
+    if is_primitive_type(ts_type) || ts_type == "Date" {
+        return DiffKind::Leaf;
+    }
+    if let Some(element) = ts_type.strip_suffix("[]") {
+        return DiffKind::Array {
+            item: differ_for(element),
+        };
+    }
+    if let Some((base, args)) = parse_generic_type(ts_type) {
+        return match base {
+            "Array" | "ReadonlyArray" => DiffKind::Array {
+                item: differ_for(args),
+            },
+            "Map" | "ReadonlyMap" => DiffKind::Map {
+                value: split_type_args(args).and_then(|(_, v)| differ_for(v)),
+            },
+            "Set" | "ReadonlySet" => DiffKind::Leaf,
+            _ => DiffKind::Structural,
+        };
+    }
+    match differ_for(ts_type) {
+        Some(differ) => DiffKind::Nested(differ),
+        None => DiffKind::Structural,
+    }
+}
+
+/// A field included in diffs.
+struct DiffField {
+    eq: EqField,
+    eq_opts: EqFieldOptions,
+    kind: DiffKind,
+    key: Option<String>,
+}
+
+/// Collects the fields that participate in diffs.
+///
+/// Fields skipped by either `@diff` or `@partialEq` are left out. Equality
+/// warnings are only reported here when the type doesn't also derive
+/// `PartialEq`, which reports them itself.
+fn collect_diff_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, &'a [DecoratorIR], SpanIR)>,
+    container_decorators: &[DecoratorIR],
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<DiffField> {
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|(name, ts_type, decorators, span)| {
+            (
+                (name, ts_type, decorators, span),
+                DiffFieldOptions::from_decorators(decorators),
+            )
+        })
+        .filter(|(_, opts)| !opts.skip)
+        .collect();
+
+    let container = EqFieldOptions::from_decorators(container_decorators);
+    let mut eq_diagnostics = DiagnosticCollector::new();
+    let eq_fields = collect_eq_fields(
+        fields.iter().map(|(field, _)| *field),
+        &container,
+        &mut eq_diagnostics,
+    );
+    if !derives_macro(container_decorators, "PartialEq") {
+        diagnostics.extend(eq_diagnostics);
+    }
+
+    eq_fields
+        .into_iter()
+        .filter_map(|(eq, eq_opts)| {
+            let ((_, ts_type, _, span), opts) =
+                fields.iter().find(|((name, ..), _)| *name == eq.name)?;
+            let kind = classify(ts_type, &eq_opts, opts);
+            let key = match (&opts.key, &kind) {
+                (Some(_), DiffKind::Array { .. }) => opts.key.clone(),
+                (Some(key), _) => {
+                    diagnostics.warning(
+                        *span,
+                        format!(
+                            "@diff key \"{key}\" only applies to array fields; '{}: {ts_type}' is diffed without it",
+                            eq.name
+                        ),
+                    );
+                    None
+                }
+                (None, _) => None,
+            };
+            Some(DiffField {
+                eq,
+                eq_opts,
+                kind,
+                key,
+            })
+        })
+        .collect()
+}
+
+/// The statement that records the changes of one field, guarded by its
+/// `PartialEq` check.
+fn generate_field_diff(field: &DiffField) -> String {
+    let name = &field.eq.name;
+    let args = format!("a.{name}, b.{name}, path + \"/{name}\", changes");
+    let call = match &field.kind {
+        DiffKind::Leaf | DiffKind::Structural => {
+            format!("__mf_{}({args})", field.kind.helper())
+        }
+        DiffKind::Array { item } => {
+            let mut options = Vec::new();
+            if let Some(key) = &field.key {
+                options.push(format!("key: \"{key}\""));
+            }
+            if let Some(item) = item {
+                options.push(format!("diff: {item}"));
+            }
+            if options.is_empty() {
+                format!("__mf_diffArray({args})")
+            } else {
+                format!("__mf_diffArray({args}, {{ {} }})", options.join(", "))
+            }
+        }
+        DiffKind::Map {
+            value: Some(differ),
+        }
+        | DiffKind::Nested(differ) => {
+            format!("__mf_{}({args}, {differ})", field.kind.helper())
+        }
+        DiffKind::Map { value: None } => format!("__mf_diffMap({args})"),
+    };
+    let equal = generate_field_equality_with_options(&field.eq, &field.eq_opts, "a", "b");
+    format!("if (!({equal})) {call};")
+}
+
+/// Generates the standalone functions for `type_name`. `changes_body` fills
+/// in `*DiffChanges` after `changes` has been declared.
+fn generate_diff_functions(type_name: &str, changes_body: &str) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    format!(
+        "export function {prefix}DiffChanges(a: {type_name}, b: {type_name}, path: string = \"\"): __mf_DiffChange[] {{
+    const changes: __mf_DiffChange[] = [];
+    {changes_body}
+    return changes;
+}}
+
+export function {prefix}Diff(a: {type_name}, b: {type_name}): __mf_Diff {{
+    const changes = {prefix}DiffChanges(a, b);
+    return {{ changes, patch: __mf_toJsonPatch(changes) }};
+}}
+
+export function {prefix}ApplyPatch(value: {type_name}, patch: readonly __mf_JsonPatchOperation[]): {type_name} {{
+    return __mf_applyPatch(value, patch);
+}}"
+    )
+}
+
+/// The `*DiffChanges` body for an object-shaped type.
+fn generate_fields_body(fields: &[DiffField]) -> String {
+    let mut lines = vec!["if (a === b) return changes;".to_string()];
+    lines.extend(fields.iter().map(generate_field_diff));
+    lines.join("\n    ")
+}
+
+/// Generates the functions for an object-shaped type, recording the helpers
+/// its fields use.
+fn object_output(
+    type_name: &str,
+    fields: &[DiffField],
+    helpers: &mut BTreeSet<&'static str>,
+) -> String {
+    helpers.extend(fields.iter().map(|f| f.kind.helper()));
+    generate_diff_functions(type_name, &generate_fields_body(fields))
+}
+
+/// Generates the static wrapper methods that delegate to the standalone functions.
+fn generate_static_wrappers(type_name: &str) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    format!(
+        "static diff(a: {type_name}, b: {type_name}): __mf_Diff {{ return {prefix}Diff(a, b); }}
+static applyPatch(value: {type_name}, patch: readonly __mf_JsonPatchOperation[]): {type_name} {{ return {prefix}ApplyPatch(value, patch); }}"
+    )
+}
+
+/// Adds the `macroforge/diff` imports; `helpers` are the field helpers in use.
+fn add_diff_imports(stream: &mut TsStream, helpers: &BTreeSet<&'static str>) {
+    for helper in helpers {
+        stream.add_aliased_import(helper, "macroforge/diff");
+    }
+    stream.add_aliased_import("toJsonPatch", "macroforge/diff");
+    stream.add_aliased_import("applyPatch", "macroforge/diff");
+    stream.add_aliased_type_import("Diff", "macroforge/diff");
+    stream.add_aliased_type_import("DiffChange", "macroforge/diff");
+    stream.add_aliased_type_import("JsonPatchOperation", "macroforge/diff");
+}
+
+#[ts_macro_derive(
+    Diff,
+    description = "Generates diff() returning a change list and JSON Patch, plus applyPatch()",
+    attributes((diff, "Configure diffs. Options: skip, key (array identity property), atomic"))
+)]
+pub fn derive_diff_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let mut diagnostics = DiagnosticCollector::new();
+    let mut helpers = BTreeSet::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields = collect_diff_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_decorators,
+                &mut diagnostics,
+            );
+            let standalone = object_output(type_name, &fields, &mut helpers);
+            let wrappers = generate_static_wrappers(type_name);
+            let class_body = body! {
+                @{wrappers}
+            };
+
+            // The standalone output (no marker) must come FIRST so it defaults to "below" (after class)
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = collect_diff_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_decorators,
+                &mut diagnostics,
+            );
+            TsStream::from_string(object_output(type_name, &fields, &mut helpers))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_object() => {
+            let fields = collect_diff_fields(
+                type_alias.as_object().unwrap().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_decorators,
+                &mut diagnostics,
+            );
+            TsStream::from_string(object_output(type_name, &fields, &mut helpers))
+        }
+        Data::TypeAlias(_) => {
+            // Unions and other aliases: structural diff of the whole value
+            helpers.insert("diffValue");
+            TsStream::from_string(generate_diff_functions(
+                type_name,
+                "__mf_diffValue(a, b, path, changes);",
+            ))
+        }
+        Data::Enum(_) => {
+            helpers.insert("diffLeaf");
+            TsStream::from_string(generate_diff_functions(
+                type_name,
+                "if (a !== b) __mf_diffLeaf(a, b, path, changes);",
+            ))
+        }
+    };
+
+    add_diff_imports(&mut output, &helpers);
+    output.add_diagnostics(diagnostics.into_vec());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn collect(fields: &[(&str, &str, Vec<DecoratorIR>)]) -> (Vec<DiffField>, usize) {
+        let mut diagnostics = DiagnosticCollector::new();
+        let collected = collect_diff_fields(
+            fields
+                .iter()
+                .map(|(n, t, d)| (*n, *t, d.as_slice(), SpanIR::new(0, 0))),
+            &[],
+            &mut diagnostics,
+        );
+        (collected, diagnostics.len())
+    }
+
+    #[test]
+    fn test_classify() {
+        let eq = EqFieldOptions::default();
+        let opts = DiffFieldOptions::default();
+        assert_eq!(classify("string", &eq, &opts), DiffKind::Leaf);
+        assert_eq!(classify("Date | null", &eq, &opts), DiffKind::Leaf);
+        assert_eq!(classify("Set<string>", &eq, &opts), DiffKind::Leaf);
+        assert_eq!(
+            classify("string[]", &eq, &opts),
+            DiffKind::Array { item: None }
+        );
+        assert_eq!(
+            classify("Array<OrderLine>", &eq, &opts),
+            DiffKind::Array {
+                item: Some("orderLineDiffChanges".into())
+            }
+        );
+        assert_eq!(
+            classify("Map<string, Address>", &eq, &opts),
+            DiffKind::Map {
+                value: Some("addressDiffChanges".into())
+            }
+        );
+        assert_eq!(
+            classify("Address | undefined", &eq, &opts),
+            DiffKind::Nested("addressDiffChanges".into())
+        );
+        assert_eq!(
+            classify("Record<string, number>", &eq, &opts),
+            DiffKind::Structural
+        );
+        assert_eq!(classify("{ x: number }", &eq, &opts), DiffKind::Structural);
+    }
+
+    #[test]
+    fn test_atomic_and_custom_equality_are_leaves() {
+        let atomic = DiffFieldOptions::from_decorators(&[decorator("diff", "{ atomic: true }")]);
+        assert_eq!(
+            classify("Address", &EqFieldOptions::default(), &atomic),
+            DiffKind::Leaf
+        );
+        let with = EqFieldOptions::from_decorators(&[decorator("partialEq", "{ with: \"same\" }")]);
+        assert_eq!(
+            classify("Address", &with, &DiffFieldOptions::default()),
+            DiffKind::Leaf
+        );
+    }
+
+    #[test]
+    fn test_skip_options() {
+        let (fields, _) = collect(&[
+            ("id", "string", vec![]),
+            ("cache", "string", vec![decorator("diff", "skip")]),
+            (
+                "secret",
+                "string",
+                vec![decorator("partialEq", "{ skip: true }")],
+            ),
+        ]);
+        let names: Vec<_> = fields.iter().map(|f| f.eq.name.as_str()).collect();
+        assert_eq!(names, ["id"]);
+    }
+
+    #[test]
+    fn test_key_on_non_array_warns() {
+        let (fields, warnings) =
+            collect(&[("owner", "User", vec![decorator("diff", "{ key: \"id\" }")])]);
+        assert_eq!(warnings, 1);
+        assert!(fields[0].key.is_none());
+    }
+
+    #[test]
+    fn test_field_diff_statements() {
+        let (fields, warnings) = collect(&[
+            ("name", "string", vec![]),
+            (
+                "lines",
+                "OrderLine[]",
+                vec![decorator("diff", "{ key: \"sku\" }")],
+            ),
+            (
+                "price",
+                "number",
+                vec![decorator("partialEq", "{ epsilon: 0.01 }")],
+            ),
+        ]);
+        assert_eq!(warnings, 0);
+        let body = generate_fields_body(&fields);
+        assert!(body.starts_with("if (a === b) return changes;"));
+        assert!(body.contains(
+            "if (!(a.name === b.name)) __mf_diffLeaf(a.name, b.name, path + \"/name\", changes);"
+        ));
+        assert!(body.contains(
+            "__mf_diffArray(a.lines, b.lines, path + \"/lines\", changes, { key: \"sku\", diff: orderLineDiffChanges });"
+        ));
+        assert!(body.contains("Math.abs(a.price - b.price) <= 0.01"));
+    }
+
+    #[test]
+    fn test_generated_functions() {
+        let code = generate_diff_functions("Order", "if (a === b) return changes;");
+        assert!(code.contains(
+            "export function orderDiffChanges(a: Order, b: Order, path: string = \"\"): __mf_DiffChange[]"
+        ));
+        assert!(code.contains("return { changes, patch: __mf_toJsonPatch(changes) };"));
+        assert!(code.contains(
+            "export function orderApplyPatch(value: Order, patch: readonly __mf_JsonPatchOperation[]): Order"
+        ));
+
+        let wrappers = generate_static_wrappers("Order");
+        assert!(
+            wrappers
+                .contains("static diff(a: Order, b: Order): __mf_Diff { return orderDiff(a, b); }")
+        );
+        assert!(wrappers.contains("return orderApplyPatch(value, patch);"));
+    }
+}
@@ -0,0 +1,368 @@
+//! # EnumUtils Macro Implementation
+//!
+//! The `EnumUtils` macro generates the everyday helpers for working with a
+//! closed set of values: iteration, parsing, type guards and stepping through
+//! the variants. It works on TypeScript enums and on type aliases that are a
+//! union of literals, so `type Color = "red" | "green"` gets the same helpers
+//! as `enum Color { Red, Green }`.
+//!
+//! ## Generated Output
+//!
+//! For a type `Color`:
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `colorValues(): readonly Color[]` | All variants, in declaration order |
+//! | `colorNames(): readonly string[]` | Variant names (enum keys, or the literals themselves) |
+//! | `colorCount` | Number of variants |
+//! | `colorFromString(input, options?)` | Parses a name or value, `undefined` if unknown |
+//! | `isColor(value): value is Color` | Type guard for the whole type |
+//! | `isColorRed(value): value is Color.Red` | Type guard per variant |
+//! | `colorNext(value, options?)`, `colorPrev(value, options?)` | Neighbouring variant, `undefined` at the ends unless `wrap` is set |
+//!
+//! Per-variant guards are generated for variants whose name is a valid
+//! identifier once converted to PascalCase (`"in-progress"` becomes
+//! `isStatusInProgress`). Enums with computed members only get the
+//! whole-type guard, since computed members have no literal type.
+//!
+//! ## Parsing
+//!
+//! `fromString` accepts both variant names and values, so `Color.Red = "RED"`
+//! parses from `"Red"` and `"RED"`. Pass `{ ignoreCase: true }` to compare
+//! case-insensitively, or make that the default for the type with
+//! `@enumUtils({ ignoreCase: true })`.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(EnumUtils) */
+//! type Color = "red" | "green";
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export const colorCount = 2;
+//!
+//! export function colorValues(): readonly Color[] {
+//!     return ["red", "green"];
+//! }
+//!
+//! export function colorNames(): readonly string[] {
+//!     return ["red", "green"];
+//! }
+//!
+//! export function isColor(value: unknown): value is Color {
+//!     return (colorValues() as readonly unknown[]).includes(value);
+//! }
+//!
+//! export function isColorRed(value: unknown): value is "red" {
+//!     return value === "red";
+//! }
+//! // ... colorFromString, colorNext, colorPrev
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::has_flag;
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{DecoratorIR, EnumValue, EnumVariantIR, TypeMemberKind};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// One variant of the type, as the generated code refers to it.
+#[derive(Debug, PartialEq)]
+struct Variant {
+    /// Name reported by `names()` and accepted by `fromString`.
+    name: String,
+    /// JavaScript expression for the variant's value.
+    value: String,
+    /// Type of the variant for its guard, when it has a literal type.
+    literal_type: Option<String>,
+}
+
+/// Encodes `s` as a JavaScript string literal.
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// The text of a literal type: the contents of string literals, the source of
+/// anything else (`42`, `true`).
+fn literal_name(literal: &str) -> String {
+    let literal = literal.trim();
+    let quoted = literal.len() >= 2
+        && (literal.starts_with('"') || literal.starts_with('\'') || literal.starts_with('`'))
+        && literal.ends_with(&literal[..1]);
+    if quoted {
+        literal[1..literal.len() - 1].to_string()
+    } else {
+        literal.to_string()
+    }
+}
+
+/// `isTypeVariant` for a variant name, if the name makes a valid identifier.
+fn guard_name(type_name: &str, variant_name: &str) -> Option<String> {
+    let suffix = variant_name.to_case(Case::Pascal);
+    let valid = suffix.starts_with(|c: char| c.is_ascii_alphabetic())
+        && suffix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| format!("is{type_name}{suffix}"))
+}
+
+fn enum_variants(type_name: &str, variants: &[EnumVariantIR]) -> Vec<Variant> {
+    // Computed members make the enum a non-union enum, so `Type.Member` is not a type
+    let literal = !variants
+        .iter()
+        .any(|v| matches!(v.value, EnumValue::Expr(_)));
+    variants
+        .iter()
+        .map(|v| {
+            let value = format!("{type_name}.{}", v.name);
+            Variant {
+                name: v.name.clone(),
+                literal_type: literal.then(|| value.clone()),
+                value,
+            }
+        })
+        .collect()
+}
+
+/// Whether `fromString` ignores case by default (`@enumUtils({ ignoreCase: true })`).
+fn ignore_case_default(decorators: &[DecoratorIR]) -> bool {
+    decorators
+        .iter()
+        .filter(|d| d.name.eq_ignore_ascii_case("enumUtils"))
+        .any(|d| has_flag(d.args_src.trim(), "ignoreCase"))
+}
+
+fn generate_enum_utils(type_name: &str, variants: &[Variant], ignore_case: bool) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let values = variants
+        .iter()
+        .map(|v| v.value.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let names = variants
+        .iter()
+        .map(|v| js_string(&v.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let count = variants.len();
+
+    let mut guards = String::new();
+    let mut seen = Vec::new();
+    for variant in variants {
+        let (Some(literal_type), Some(guard)) =
+            (&variant.literal_type, guard_name(type_name, &variant.name))
+        else {
+            continue;
+        };
+        // Names differing only in case or punctuation would collide
+        if seen.contains(&guard) {
+            continue;
+        }
+        guards.push_str(&format!(
+            "\nexport function {guard}(value: unknown): value is {literal_type} {{\n    return value === {value};\n}}\n",
+            value = variant.value,
+        ));
+        seen.push(guard);
+    }
+
+    format!(
+        r#"export const {prefix}Count = {count};
+
+export function {prefix}Values(): readonly {type_name}[] {{
+    return [{values}];
+}}
+
+export function {prefix}Names(): readonly string[] {{
+    return [{names}];
+}}
+
+export function {prefix}FromString(input: string, options?: {{ ignoreCase?: boolean }}): {type_name} | undefined {{
+    const ignoreCase = options?.ignoreCase ?? {ignore_case};
+    const normalize = (s: string) => (ignoreCase ? s.toLowerCase() : s);
+    const target = normalize(input);
+    const names = {prefix}Names();
+    const values = {prefix}Values();
+    for (let i = 0; i < values.length; i++) {{
+        if (normalize(names[i]) === target || normalize(String(values[i])) === target) {{
+            return values[i];
+        }}
+    }}
+    return undefined;
+}}
+
+export function is{type_name}(value: unknown): value is {type_name} {{
+    return ({prefix}Values() as readonly unknown[]).includes(value);
+}}
+{guards}
+export function {prefix}Next(value: {type_name}, options?: {{ wrap?: boolean }}): {type_name} | undefined {{
+    const values = {prefix}Values();
+    const index = values.indexOf(value);
+    if (index === -1) {{
+        return undefined;
+    }}
+    if (index + 1 < values.length) {{
+        return values[index + 1];
+    }}
+    return options?.wrap ? values[0] : undefined;
+}}
+
+export function {prefix}Prev(value: {type_name}, options?: {{ wrap?: boolean }}): {type_name} | undefined {{
+    const values = {prefix}Values();
+    const index = values.indexOf(value);
+    if (index === -1) {{
+        return undefined;
+    }}
+    if (index > 0) {{
+        return values[index - 1];
+    }}
+    return options?.wrap ? values[values.length - 1] : undefined;
+}}"#
+    )
+}
+
+#[ts_macro_derive(
+    EnumUtils,
+    description = "Generates iteration, parsing, type guard and next/prev helpers for enums and literal unions",
+    attributes((enumUtils, "Configure the helpers. Options: ignoreCase (fromString ignores case by default)"))
+)]
+pub fn derive_enum_utils_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let ignore_case = ignore_case_default(&container_decorators);
+
+    let variants = match &input.data {
+        Data::Enum(enum_data) => enum_variants(type_name, enum_data.variants()),
+        Data::TypeAlias(type_alias) => {
+            let Some(members) = type_alias.as_union() else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    format!(
+                        "@derive(EnumUtils) on type alias '{type_name}' requires a union of literal types"
+                    ),
+                ));
+            };
+            let mut variants = Vec::new();
+            for member in members {
+                let TypeMemberKind::Literal(literal) = &member.kind else {
+                    return Err(MacroforgeError::new(
+                        input.decorator_span(),
+                        format!(
+                            "@derive(EnumUtils) on type alias '{type_name}' requires every union member to be a literal type"
+                        ),
+                    ));
+                };
+                variants.push(Variant {
+                    name: literal_name(literal),
+                    value: literal.clone(),
+                    literal_type: Some(literal.clone()),
+                });
+            }
+            variants
+        }
+        Data::Class(_) | Data::Interface(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(EnumUtils) can only be used on enums and literal union type aliases",
+            ));
+        }
+    };
+
+    Ok(TsStream::from_string(generate_enum_utils(
+        type_name,
+        &variants,
+        ignore_case,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn variant(name: &str, value: EnumValue) -> EnumVariantIR {
+        EnumVariantIR {
+            name: name.into(),
+            span: SpanIR::new(0, 0),
+            value,
+            decorators: vec![],
+        }
+    }
+
+    #[test]
+    fn test_literal_name() {
+        assert_eq!(literal_name("\"red\""), "red");
+        assert_eq!(literal_name("'in-progress'"), "in-progress");
+        assert_eq!(literal_name("42"), "42");
+        assert_eq!(literal_name("true"), "true");
+    }
+
+    #[test]
+    fn test_guard_name() {
+        assert_eq!(
+            guard_name("Status", "in-progress").as_deref(),
+            Some("isStatusInProgress")
+        );
+        assert_eq!(guard_name("Color", "Red").as_deref(), Some("isColorRed"));
+        assert_eq!(guard_name("Level", "42"), None);
+        assert_eq!(guard_name("Level", ""), None);
+    }
+
+    #[test]
+    fn test_computed_enum_members_have_no_literal_type() {
+        let plain = enum_variants("Color", &[variant("Red", EnumValue::Auto)]);
+        assert_eq!(plain[0].literal_type.as_deref(), Some("Color.Red"));
+
+        let computed = enum_variants(
+            "Flags",
+            &[
+                variant("A", EnumValue::Number(1.0)),
+                variant("B", EnumValue::Expr("A << 1".into())),
+            ],
+        );
+        assert!(computed.iter().all(|v| v.literal_type.is_none()));
+        assert!(!generate_enum_utils("Flags", &computed, false).contains("isFlagsA"));
+    }
+
+    #[test]
+    fn test_generate_for_literal_union() {
+        let variants = ["\"red\"", "\"green\""]
+            .iter()
+            .map(|literal| Variant {
+                name: literal_name(literal),
+                value: literal.to_string(),
+                literal_type: Some(literal.to_string()),
+            })
+            .collect::<Vec<_>>();
+        let code = generate_enum_utils("Color", &variants, true);
+
+        assert!(code.contains("export const colorCount = 2;"));
+        assert!(code.contains("return [\"red\", \"green\"];"));
+        assert!(code.contains("const ignoreCase = options?.ignoreCase ?? true;"));
+        assert!(code.contains("export function isColor(value: unknown): value is Color"));
+        assert!(code.contains("export function isColorGreen(value: unknown): value is \"green\""));
+        assert!(code.contains("export function colorNext(value: Color"));
+        assert!(code.contains("export function colorPrev(value: Color"));
+    }
+
+    #[test]
+    fn test_colliding_guards_are_generated_once() {
+        let variants = ["\"in-progress\"", "\"in_progress\""]
+            .iter()
+            .map(|literal| Variant {
+                name: literal_name(literal),
+                value: literal.to_string(),
+                literal_type: Some(literal.to_string()),
+            })
+            .collect::<Vec<_>>();
+        let code = generate_enum_utils("Status", &variants, false);
+        assert_eq!(code.matches("function isStatusInProgress(").count(), 1);
+    }
+}
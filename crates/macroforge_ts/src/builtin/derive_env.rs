@@ -0,0 +1,556 @@
+//! # Env Macro Implementation
+//!
+//! The `Env` macro generates a typed loader for configuration held in
+//! environment variables, replacing hand-written `process.env` parsing.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameLoadFromEnv(env?)` + `static loadFromEnv(env?)` | Instance created with the class prototype |
+//! | Interface | `interfaceNameLoadFromEnv(env?)` | Plain object |
+//!
+//! `env` defaults to `process.env`. The loader reads every field, collects
+//! every missing or invalid variable, and throws one `EnvError` listing
+//! them all. A `typeNameReadEnv(env, prefix, issues)` function does the
+//! collecting and is used for nested configuration.
+//!
+//! ## Variable Names
+//!
+//! Fields map to `SCREAMING_SNAKE_CASE` (`databaseUrl` → `DATABASE_URL`),
+//! after the prefix of the container. Fields whose type is another type
+//! deriving `Env` are read from that type's variables under the prefix
+//! `FIELD_NAME_` (`db: DbConfig` → `DB_HOST`, `DB_PORT`, ...).
+//!
+//! ## Coercion
+//!
+//! | Field Type | Accepted Values |
+//! |------------|-----------------|
+//! | `string` | Any value |
+//! | `number`, `bigint` | Numbers, integers |
+//! | `boolean` | `true`/`false`, `1`/`0`, `yes`/`no`, `on`/`off` |
+//! | `Date`, `URL` | ISO dates, absolute URLs |
+//! | `"a" \| "b"` | One of the literals |
+//! | `T[]` of the above | Comma-separated list |
+//!
+//! Empty variables count as missing. Optional fields (`?`, `| undefined`,
+//! `| null`) and fields with a default may be missing. The serde validators
+//! of a field (`@serde({ validate: [...] })`) run on the coerced value.
+//!
+//! ## Options
+//!
+//! - `@env({ prefix: "APP_" })` on the type - Prefix for every variable
+//! - `@env({ name: "DATABASE_URL" })` - Exact variable name, without prefix
+//! - `@env({ prefix: "PG_" })` on a field - Added to the prefix for this
+//!   field, or used instead of `FIELD_NAME_` for nested configuration
+//! - `@env({ secret: true })` - Never show the value in messages
+//! - `@env({ default: "8080" })` - Default expression; `@serde({ default })`
+//!   works as well
+//! - `@env({ separator: ";" })` - List separator
+//! - `@env({ skip: true })` - Don't read the field
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Env) @env({ prefix: "APP_" }) */
+//! interface AppConfig {
+//!     /** @env({ default: "8080" }) @serde({ validate: ["int", "positive"] }) */
+//!     port: number;
+//!     /** @env({ name: "DATABASE_URL", secret: true }) */
+//!     databaseUrl: URL;
+//!     allowedOrigins: string[];
+//! }
+//!
+//! const config = appConfigLoadFromEnv();
+//! // Reads APP_PORT, DATABASE_URL and APP_ALLOWED_ORIGINS
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    extract_named_string, has_flag, is_nullable_type, split_top_level, strip_nullable, strip_parens,
+};
+use crate::builtin::serde::derive_deserialize::{
+    generate_validation_condition, get_validator_message,
+};
+use crate::builtin::serde::{RenameAll, SerdeFieldOptions, Validator, ValidatorSpec};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Options parsed from `@env` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct EnvOptions {
+    name: Option<String>,
+    prefix: Option<String>,
+    secret: bool,
+    skip: bool,
+    default: Option<String>,
+    separator: Option<String>,
+}
+
+impl EnvOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("env") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.name = extract_named_string(args, "name").or(opts.name);
+            opts.prefix = extract_named_string(args, "prefix").or(opts.prefix);
+            opts.default = extract_named_string(args, "default").or(opts.default);
+            opts.separator = extract_named_string(args, "separator").or(opts.separator);
+            opts.secret |= has_flag(args, "secret");
+            opts.skip |= has_flag(args, "skip");
+        }
+        opts
+    }
+}
+
+/// How a field is read.
+#[derive(Debug, Clone, PartialEq)]
+enum Reader {
+    /// One variable, parsed with an `envParsers` expression.
+    Variable(String),
+    /// Another type deriving `Env`, read under its own prefix.
+    Nested(String),
+}
+
+/// The `envParsers` expression for a scalar type.
+fn scalar_coercion(ts_type: &str) -> Option<String> {
+    let coercion = match ts_type {
+        "string" => "__mf_envParsers.string".to_string(),
+        "number" => "__mf_envParsers.number".to_string(),
+        "bigint" => "__mf_envParsers.bigint".to_string(),
+        "boolean" => "__mf_envParsers.boolean".to_string(),
+        "Date" => "__mf_envParsers.date".to_string(),
+        "URL" => "__mf_envParsers.url".to_string(),
+        _ => {
+            let members = split_top_level(ts_type, '|');
+            let all_literals = !members.is_empty()
+                && members.iter().all(|m| {
+                    (m.starts_with('"') && m.ends_with('"'))
+                        || (m.starts_with('\'') && m.ends_with('\''))
+                });
+            if !all_literals {
+                return None;
+            }
+            let values = members
+                .iter()
+                .map(|m| js_string(&m[1..m.len() - 1]))
+                .collect::<Vec<_>>();
+            format!("__mf_envParsers.oneOf([{}])", values.join(", "))
+        }
+    };
+    Some(coercion)
+}
+
+fn reader(ts_type: &str, separator: Option<&str>) -> Option<Reader> {
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = ts_type.trim();
+    if let Some(coercion) = scalar_coercion(ts_type) {
+        return Some(Reader::Variable(coercion));
+    }
+    let item = ts_type
+        .strip_suffix("[]")
+        .or_else(|| {
+            ts_type
+                .strip_prefix("Array<")
+                .and_then(|rest| rest.strip_suffix('>'))
+        })
+        .map(strip_parens);
+    if let Some(item) = item {
+        let coercion = scalar_coercion(item)?;
+        let separator = separator
+            .map(|s| format!(", {}", js_string(s)))
+            .unwrap_or_default();
+        return Some(Reader::Variable(format!(
+            "__mf_envParsers.list({coercion}{separator})"
+        )));
+    }
+    let is_type_name = ts_type.chars().next().is_some_and(char::is_uppercase)
+        && ts_type
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    is_type_name.then(|| Reader::Nested(ts_type.to_string()))
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// A field to read.
+struct EnvField<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+/// Generates the statements reading one field into `local`, or reports why
+/// the field can't be read.
+fn read_field(field: &EnvField<'_>, local: &str, diagnostics: &mut DiagnosticCollector) -> String {
+    let opts = EnvOptions::from_decorators(field.decorators);
+    let serde = SerdeFieldOptions::from_decorators(field.decorators, field.name).options;
+    let default = opts.default.clone().or(serde.default_expr);
+
+    let Some(reader) = reader(field.ts_type, opts.separator.as_deref()) else {
+        diagnostics.error(
+            field.span,
+            format!(
+                "@derive(Env) can't read field '{}' of type '{}'; supported are string, number, bigint, boolean, Date, URL, string literal unions, arrays of those and types deriving Env",
+                field.name, field.ts_type
+            ),
+        );
+        return String::new();
+    };
+
+    let screaming = RenameAll::ScreamingSnakeCase.apply(field.name);
+    let field_prefix = opts.prefix.as_deref().unwrap_or_default();
+    match reader {
+        Reader::Nested(type_name) => {
+            let group = match &opts.prefix {
+                Some(prefix) => js_string(prefix),
+                None => js_string(&format!("{screaming}_")),
+            };
+            format!(
+                "const {local} = {}ReadEnv(env, prefix + {group}, issues);",
+                type_name.to_case(Case::Camel)
+            )
+        }
+        Reader::Variable(coercion) => {
+            let variable = match &opts.name {
+                Some(name) => js_string(name),
+                None => format!(
+                    "prefix + {}",
+                    js_string(&format!("{field_prefix}{screaming}"))
+                ),
+            };
+            let optional = field.optional || is_nullable_type(field.ts_type) || default.is_some();
+            let mut flags = Vec::new();
+            if optional {
+                flags.push("optional: true");
+            }
+            if opts.secret {
+                flags.push("secret: true");
+            }
+            let flags = if flags.is_empty() {
+                String::new()
+            } else {
+                format!(", {{ {} }}", flags.join(", "))
+            };
+            let fallback = default.map(|d| format!(" ?? ({d})")).unwrap_or_default();
+
+            let mut code = format!(
+                "const {local} = __mf_readEnv(env, {variable}, {coercion}, issues{flags}){fallback};"
+            );
+            let checks = validations(&serde.validators, local, &variable);
+            if !checks.is_empty() {
+                code.push_str(&format!(
+                    "\n    if ({local} !== undefined) {{{checks}\n    }}"
+                ));
+            }
+            code
+        }
+    }
+}
+
+/// Runs the serde validators on `local`, reporting failures for `variable`.
+fn validations(validators: &[ValidatorSpec], local: &str, variable: &str) -> String {
+    validators
+        .iter()
+        .map(|spec| {
+            let condition = match &spec.validator {
+                Validator::Custom(function) => format!("{function}({local}) === false"),
+                other => generate_validation_condition(other, local),
+            };
+            let message = spec
+                .custom_message
+                .clone()
+                .unwrap_or_else(|| get_validator_message(&spec.validator));
+            format!(
+                "\n        if ({condition}) {{\n            issues.push({{ variable: {variable}, message: {} }});\n        }}",
+                js_string(&message)
+            )
+        })
+        .collect()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn generate_env_functions(
+    type_name: &str,
+    container_prefix: &str,
+    fields: &[EnvField<'_>],
+    construct: impl Fn(&str) -> String,
+    diagnostics: &mut DiagnosticCollector,
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let mut statements = Vec::new();
+    let mut properties = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        if EnvOptions::from_decorators(field.decorators).skip {
+            continue;
+        }
+        let (local, key) = if is_identifier(field.name) {
+            (format!("__{}", field.name), field.name.to_string())
+        } else {
+            (format!("__field{i}"), js_string(field.name))
+        };
+        statements.push(read_field(field, &local, diagnostics));
+        properties.push(format!("{key}: {local}"));
+    }
+    let value = construct(&format!("{{ {} }}", properties.join(", ")));
+
+    format!(
+        r#"/** @internal Reads every field under `prefix`, collecting problems in `issues`. */
+export function {prefix}ReadEnv(env: __mf_EnvSource, prefix: string, issues: __mf_EnvIssue[]): {type_name} {{
+    {}
+    return {value} as {type_name};
+}}
+
+/**
+ * Loads the configuration from environment variables.
+ * @param env - The variables; defaults to `process.env`
+ * @throws EnvError listing every missing or invalid variable
+ */
+export function {prefix}LoadFromEnv(env: __mf_EnvSource = __mf_processEnv()): {type_name} {{
+    const issues: __mf_EnvIssue[] = [];
+    const value = {prefix}ReadEnv(env, {}, issues);
+    if (issues.length > 0) {{
+        throw new __mf_EnvError(issues);
+    }}
+    return value;
+}}"#,
+        statements.join("\n    "),
+        js_string(container_prefix)
+    )
+}
+
+#[ts_macro_derive(
+    Env,
+    description = "Generates a typed loader that reads and validates configuration from environment variables",
+    attributes((env, "Configure a variable. Options: name, prefix, secret, default, separator, skip"))
+)]
+pub fn derive_env_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let container_prefix = EnvOptions::from_decorators(&container_decorators)
+        .prefix
+        .unwrap_or_default();
+    let mut diagnostics = DiagnosticCollector::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields = class
+                .fields()
+                .iter()
+                .map(|f| EnvField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let standalone = generate_env_functions(
+                type_name,
+                &container_prefix,
+                &fields,
+                |properties| {
+                    format!("Object.assign(Object.create({type_name}.prototype), {properties})")
+                },
+                &mut diagnostics,
+            );
+            let fn_name = format!("{}LoadFromEnv", type_name.to_case(Case::Camel));
+            let class_body = body! {
+                static loadFromEnv(env: __mf_EnvSource = __mf_processEnv()): @{type_name} {
+                    return @{fn_name}(env);
+                }
+            };
+
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = interface
+                .fields()
+                .iter()
+                .map(|f| EnvField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            TsStream::from_string(generate_env_functions(
+                type_name,
+                &container_prefix,
+                &fields,
+                str::to_string,
+                &mut diagnostics,
+            ))
+        }
+        Data::Enum(_) | Data::TypeAlias(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(Env) can only be used on classes and interfaces",
+            ));
+        }
+    };
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    output.add_aliased_import("readEnv", "macroforge/env");
+    output.add_aliased_import("envParsers", "macroforge/env");
+    output.add_aliased_import("processEnv", "macroforge/env");
+    output.add_aliased_import("EnvError", "macroforge/env");
+    output.add_aliased_type_import("EnvSource", "macroforge/env");
+    output.add_aliased_type_import("EnvIssue", "macroforge/env");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decorator(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "env".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    #[test]
+    fn test_reader() {
+        assert_eq!(
+            reader("number | undefined", None),
+            Some(Reader::Variable("__mf_envParsers.number".into()))
+        );
+        assert_eq!(
+            reader("\"dev\" | \"prod\"", None),
+            Some(Reader::Variable(
+                "__mf_envParsers.oneOf([\"dev\", \"prod\"])".into()
+            ))
+        );
+        assert_eq!(
+            reader("Array<number>", Some(";")),
+            Some(Reader::Variable(
+                "__mf_envParsers.list(__mf_envParsers.number, \";\")".into()
+            ))
+        );
+        assert_eq!(
+            reader("DbConfig", None),
+            Some(Reader::Nested("DbConfig".into()))
+        );
+        assert_eq!(reader("Record<string, string>", None), None);
+        assert_eq!(reader("DbConfig[]", None), None);
+    }
+
+    #[test]
+    fn test_read_field_names_and_flags() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let decorators = [decorator("{ secret: true, default: \"8080\" }")];
+        let code = read_field(
+            &EnvField {
+                name: "databasePort",
+                ts_type: "number",
+                optional: false,
+                decorators: &decorators,
+                span: SpanIR::new(0, 0),
+            },
+            "__databasePort",
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty());
+        assert_eq!(
+            code,
+            "const __databasePort = __mf_readEnv(env, prefix + \"DATABASE_PORT\", __mf_envParsers.number, issues, { optional: true, secret: true }) ?? (8080);"
+        );
+
+        let decorators = [decorator("{ prefix: \"PG_\" }")];
+        let code = read_field(
+            &EnvField {
+                name: "db",
+                ts_type: "DbConfig",
+                optional: false,
+                decorators: &decorators,
+                span: SpanIR::new(0, 0),
+            },
+            "__db",
+            &mut diagnostics,
+        );
+        assert_eq!(
+            code,
+            "const __db = dbConfigReadEnv(env, prefix + \"PG_\", issues);"
+        );
+    }
+
+    #[test]
+    fn test_validators_report_the_variable() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let decorators = [
+            decorator("{ name: \"PORT\" }"),
+            DecoratorIR {
+                name: "serde".into(),
+                args_src: "{ validate: [\"positive\"] }".into(),
+                span: SpanIR::new(0, 0),
+                node: None,
+            },
+        ];
+        let code = read_field(
+            &EnvField {
+                name: "port",
+                ts_type: "number",
+                optional: false,
+                decorators: &decorators,
+                span: SpanIR::new(0, 0),
+            },
+            "__port",
+            &mut diagnostics,
+        );
+        assert!(code.contains("__mf_readEnv(env, \"PORT\", __mf_envParsers.number, issues);"));
+        assert!(code.contains("if (__port !== undefined) {"));
+        assert!(
+            code.contains("issues.push({ variable: \"PORT\", message: \"must be positive\" });")
+        );
+    }
+
+    #[test]
+    fn test_unsupported_type_is_reported() {
+        let mut diagnostics = DiagnosticCollector::new();
+        read_field(
+            &EnvField {
+                name: "headers",
+                ts_type: "Map<string, string>",
+                optional: false,
+                decorators: &[],
+                span: SpanIR::new(3, 9),
+            },
+            "__headers",
+            &mut diagnostics,
+        );
+        assert!(diagnostics.has_errors());
+    }
+}
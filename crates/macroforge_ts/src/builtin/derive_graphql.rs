@@ -0,0 +1,869 @@
+//! # GraphQL Macro Implementation
+//!
+//! The `GraphQL` macro generates the GraphQL SDL of a type from its
+//! TypeScript declaration, so the schema no longer duplicates the models.
+//!
+//! ## Generated Output
+//!
+//! | Type | GraphQL | Generated Code |
+//! |------|---------|----------------|
+//! | Class, interface, object type alias | `type` (or `input`/`interface`) | `typeNameGraphQL`, `typeNameGraphQLFields()`, `TypeNameResolvers<TContext>` |
+//! | Enum, string literal union | `enum` | `typeNameGraphQL` |
+//! | Union of type references | `union` | `typeNameGraphQL`, `TypeNameResolvers<TContext>` |
+//!
+//! `typeNameGraphQL` is the SDL string. Join the strings of all types to
+//! build the schema. `TypeNameResolvers` types a resolver for every field,
+//! and `__resolveType` for unions.
+//!
+//! ## Fields
+//!
+//! Properties become fields, and methods become fields with arguments
+//! taken from their parameters. Public, non-static class methods are
+//! included, and `Promise` return types are unwrapped. JSDoc descriptions
+//! become GraphQL descriptions.
+//!
+//! | TypeScript | GraphQL |
+//! |------------|---------|
+//! | `string`, `boolean` | `String`, `Boolean` |
+//! | `number` | `Float` (use `@graphql({ type: "Int" })` for integers) |
+//! | `bigint`, `Date` | `BigInt`, `DateTime` (custom scalars) |
+//! | `"a" \| "b"` | `String` |
+//! | `T[]`, `Array<T>` | `[T]` |
+//! | Other type names | The same name |
+//!
+//! Fields are non-null (`!`) unless they are optional (`?`) or include
+//! `null` or `undefined`; the same applies to list items and arguments.
+//! Arguments that are optional or have a default are nullable, and literal
+//! defaults are kept (`limit: number = 10` → `limit: Float = 10`).
+//!
+//! ## Interfaces
+//!
+//! Extended interfaces and implemented classes become `implements`. Their
+//! fields are merged into the type, as GraphQL requires, so every type in
+//! the heritage must derive `GraphQL` too. Mark a TypeScript interface with
+//! `@graphql({ interface: true })` to make it a GraphQL interface.
+//!
+//! ## Options
+//!
+//! - `@graphql({ description: "..." })` on the type - Type description
+//! - `@graphql({ input: true })` on the type - Generate an `input` type
+//! - `@graphql({ interface: true })` on the type - Generate an `interface`
+//! - `@graphql({ type: "ID" })` - GraphQL type name; lists and nullability
+//!   still follow the TypeScript type
+//! - `@graphql({ skip: true })` - Leave the field out
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(GraphQL) */
+//! class User implements Node {
+//!     /** @graphql({ type: "ID" }) */
+//!     id: string;
+//!     /** Login address. */
+//!     email: string;
+//!     nickname?: string;
+//!
+//!     posts(limit: number = 10): Promise<Post[]> { ... }
+//! }
+//! ```
+//!
+//! Generated SDL:
+//!
+//! ```graphql
+//! type User implements Node {
+//!   id: ID!
+//!   """Login address."""
+//!   email: String!
+//!   nickname: String
+//!   posts(limit: Float = 10): [Post!]!
+//! }
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    extract_exact_named_string, has_flag, member_doc, return_type, split_top_level, strip_parens,
+};
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{
+    DecoratorIR, DiagnosticCollector, InterfaceFieldIR, SpanIR, TypeMember, TypeMemberKind,
+    Visibility,
+};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Options parsed from `@graphql` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct GraphQLOptions {
+    description: Option<String>,
+    type_name: Option<String>,
+    input: bool,
+    interface: bool,
+    skip: bool,
+}
+
+impl GraphQLOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("graphql") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.description = extract_exact_named_string(args, "description").or(opts.description);
+            opts.type_name = extract_exact_named_string(args, "type").or(opts.type_name);
+            opts.input |= has_flag(args, "input");
+            opts.interface |= has_flag(args, "interface");
+            opts.skip |= has_flag(args, "skip");
+        }
+        opts
+    }
+}
+
+/// The element type of an array type, if `ts_type` is one.
+fn array_item(ts_type: &str) -> Option<&str> {
+    let ts_type = ts_type.strip_prefix("readonly ").unwrap_or(ts_type).trim();
+    ts_type
+        .strip_suffix("[]")
+        .or_else(|| {
+            ["Array<", "ReadonlyArray<"].iter().find_map(|prefix| {
+                ts_type
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_suffix('>'))
+            })
+        })
+        .map(|item| strip_parens(item.trim()))
+}
+
+/// Splits off `null` and `undefined`, returning the rest and whether any
+/// were present.
+fn split_nullable(ts_type: &str) -> (String, bool) {
+    let members = split_top_level(ts_type, '|');
+    let rest = members
+        .iter()
+        .filter(|m| **m != "null" && **m != "undefined")
+        .copied()
+        .collect::<Vec<_>>();
+    (rest.join(" | "), rest.len() != members.len())
+}
+
+fn is_type_name(ts_type: &str) -> bool {
+    ts_type.chars().next().is_some_and(char::is_uppercase)
+        && ts_type
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Whether `name` is a valid GraphQL name.
+fn is_graphql_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The GraphQL type reference for `ts_type`. `named` replaces the innermost
+/// named type.
+fn graphql_type(ts_type: &str, nullable: bool, named: Option<&str>) -> Result<String, String> {
+    let (inner, has_null) = split_nullable(strip_parens(ts_type.trim()));
+    let inner = strip_parens(inner.trim());
+    let reference = if let Some(item) = array_item(inner) {
+        format!("[{}]", graphql_type(item, false, named)?)
+    } else if let Some(named) = named {
+        named.to_string()
+    } else {
+        match inner {
+            "string" => "String".to_string(),
+            "number" => "Float".to_string(),
+            "boolean" => "Boolean".to_string(),
+            "bigint" => "BigInt".to_string(),
+            "Date" => "DateTime".to_string(),
+            _ if is_string_literal_union(inner) => "String".to_string(),
+            _ if is_type_name(inner) => inner.to_string(),
+            _ => {
+                return Err(format!(
+                    "type '{ts_type}' has no GraphQL equivalent; use a named type or @graphql({{ type }})"
+                ));
+            }
+        }
+    };
+    Ok(if nullable || has_null {
+        reference
+    } else {
+        format!("{reference}!")
+    })
+}
+
+fn is_string_literal_union(ts_type: &str) -> bool {
+    let members = split_top_level(ts_type, '|');
+    !members.is_empty()
+        && members.iter().all(|m| {
+            (m.starts_with('"') && m.ends_with('"')) || (m.starts_with('\'') && m.ends_with('\''))
+        })
+}
+
+/// `T` of a `Promise<T>` type, or the type itself.
+fn unwrap_promise(ts_type: &str) -> &str {
+    ts_type
+        .trim()
+        .strip_prefix("Promise<")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .map_or(ts_type.trim(), str::trim)
+}
+
+/// A method parameter, e.g. `limit: number = 10`.
+#[derive(Debug, Clone, PartialEq)]
+struct Arg {
+    name: String,
+    ts_type: String,
+    optional: bool,
+    default: Option<String>,
+}
+
+/// Splits `param` at its default value, ignoring the `=` of arrow types.
+fn split_default(param: &str) -> (&str, Option<&str>) {
+    let bytes = param.as_bytes();
+    let mut depth = 0i32;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'(' | b'<' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b'>' if i == 0 || bytes[i - 1] != b'=' => depth -= 1,
+            b'=' if depth == 0 && bytes.get(i + 1) != Some(&b'>') => {
+                return (param[..i].trim(), Some(param[i + 1..].trim()));
+            }
+            _ => {}
+        }
+    }
+    (param.trim(), None)
+}
+
+fn parse_args(params_src: &str) -> Result<Vec<Arg>, String> {
+    split_top_level(params_src, ',')
+        .into_iter()
+        .map(|param| {
+            let (declaration, default) = split_default(param);
+            let (name, ts_type) = match declaration.split_once(':') {
+                Some((name, ts_type)) => (name.trim(), ts_type.trim()),
+                None => (declaration, ""),
+            };
+            let (name, optional) = match name.strip_suffix('?') {
+                Some(name) => (name.trim(), true),
+                None => (name, false),
+            };
+            if !is_graphql_name(name) {
+                return Err(format!(
+                    "parameter '{param}' must be a plain named parameter"
+                ));
+            }
+            if ts_type.is_empty() {
+                return Err(format!("parameter '{name}' needs a type annotation"));
+            }
+            Ok(Arg {
+                name: name.to_string(),
+                ts_type: ts_type.to_string(),
+                optional: optional || default.is_some(),
+                default: default.map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// A literal default value in GraphQL syntax.
+fn graphql_default(default: &str) -> Option<String> {
+    let default = default.trim();
+    if matches!(default, "true" | "false" | "null") || default.parse::<f64>().is_ok() {
+        return Some(default.to_string());
+    }
+    let quoted = (default.starts_with('"') && default.ends_with('"'))
+        || (default.starts_with('\'') && default.ends_with('\''));
+    (quoted && default.len() >= 2).then(|| js_string(&default[1..default.len() - 1]))
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// A GraphQL description block at `indent`.
+fn description(text: &str, indent: &str) -> String {
+    let text = text.replace("\"\"\"", "\\\"\"\"");
+    if text.contains('\n') {
+        let lines = text
+            .lines()
+            .map(|line| format!("{indent}{line}").trim_end().to_string())
+            .collect::<Vec<_>>();
+        format!("{indent}\"\"\"\n{}\n{indent}\"\"\"\n", lines.join("\n"))
+    } else {
+        format!("{indent}\"\"\"{text}\"\"\"\n")
+    }
+}
+
+/// Reads member descriptions from the JSDoc in the target's source text.
+struct Docs<'a> {
+    source: &'a str,
+    /// Offset of the target's source within the file.
+    start: u32,
+}
+
+impl Docs<'_> {
+    fn get(&self, span: SpanIR) -> Option<String> {
+        member_doc(self.source, self.start, span)
+    }
+}
+
+/// A property or method of an object type.
+struct Member<'a> {
+    name: &'a str,
+    /// The property type, or the method return type.
+    ts_type: &'a str,
+    optional: bool,
+    /// The parameters of a method.
+    params: Option<&'a str>,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+/// A generated field: the SDL line (with description) and the resolver
+/// signature.
+#[derive(Debug, PartialEq)]
+struct Field {
+    name: String,
+    sdl: String,
+    resolver: String,
+}
+
+fn field(
+    member: &Member<'_>,
+    parent: &str,
+    docs: &Docs<'_>,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<Field> {
+    let opts = GraphQLOptions::from_decorators(member.decorators);
+    if opts.skip {
+        return None;
+    }
+    let mut report = |message: String| {
+        diagnostics.error(
+            member.span,
+            format!("@derive(GraphQL) field '{}': {message}", member.name),
+        );
+    };
+    if !is_graphql_name(member.name) {
+        report("not a valid GraphQL name".to_string());
+        return None;
+    }
+    let result_type = unwrap_promise(member.ts_type);
+    if result_type.is_empty() || result_type == "void" {
+        report("methods need a return type".to_string());
+        return None;
+    }
+    let reference = match graphql_type(result_type, member.optional, opts.type_name.as_deref()) {
+        Ok(reference) => reference,
+        Err(message) => {
+            report(message);
+            return None;
+        }
+    };
+
+    let args = match member.params.map(parse_args).transpose() {
+        Ok(args) => args.unwrap_or_default(),
+        Err(message) => {
+            report(message);
+            return None;
+        }
+    };
+    let mut sdl_args = Vec::new();
+    let mut arg_types = Vec::new();
+    for arg in &args {
+        let arg_reference = match graphql_type(&arg.ts_type, arg.optional, None) {
+            Ok(reference) => reference,
+            Err(message) => {
+                report(message);
+                return None;
+            }
+        };
+        let default = arg
+            .default
+            .as_deref()
+            .and_then(graphql_default)
+            .map(|d| format!(" = {d}"))
+            .unwrap_or_default();
+        sdl_args.push(format!("{}: {arg_reference}{default}", arg.name));
+        let optional = if arg.optional { "?" } else { "" };
+        arg_types.push(format!("{}{optional}: {}", arg.name, arg.ts_type));
+    }
+
+    let mut sdl = docs
+        .get(member.span)
+        .map(|doc| description(&doc, "  "))
+        .unwrap_or_default();
+    let sdl_args = if sdl_args.is_empty() {
+        String::new()
+    } else {
+        format!("({})", sdl_args.join(", "))
+    };
+    sdl.push_str(&format!("  {}{sdl_args}: {reference}", member.name));
+
+    let args_type = if arg_types.is_empty() {
+        "Record<string, never>".to_string()
+    } else {
+        format!("{{ {} }}", arg_types.join("; "))
+    };
+    let value_type = if member.optional {
+        format!("{result_type} | undefined")
+    } else {
+        result_type.to_string()
+    };
+    Some(Field {
+        name: member.name.to_string(),
+        sdl,
+        resolver: format!(
+            "{}?(parent: {parent}, args: {args_type}, context: TContext): {value_type} | Promise<{value_type}>;",
+            member.name
+        ),
+    })
+}
+
+/// The heritage names without type arguments.
+fn heritage_names(heritage: &[String]) -> Vec<String> {
+    heritage
+        .iter()
+        .map(|h| h.split('<').next().unwrap_or_default().trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+fn generate_object_type(
+    type_name: &str,
+    opts: &GraphQLOptions,
+    heritage: &[String],
+    fields: &[Field],
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let keyword = if opts.input {
+        "input"
+    } else if opts.interface {
+        "interface"
+    } else {
+        "type"
+    };
+    let implements = if heritage.is_empty() || opts.input {
+        String::new()
+    } else {
+        format!(" implements {}", heritage.join(" & "))
+    };
+    let mut header = opts
+        .description
+        .as_deref()
+        .map(|d| description(d, ""))
+        .unwrap_or_default();
+    header.push_str(&format!("{keyword} {type_name}{implements} {{\n"));
+
+    let mut entries = heritage
+        .iter()
+        .map(|h| format!("...{}GraphQLFields()", h.to_case(Case::Camel)))
+        .collect::<Vec<_>>();
+    entries.extend(
+        fields
+            .iter()
+            .map(|f| format!("{}: {}", js_string(&f.name), js_string(&f.sdl))),
+    );
+
+    let mut code = format!(
+        r#"/** The GraphQL fields of `{type_name}`, including inherited ones, keyed by name. */
+export function {prefix}GraphQLFields(): Record<string, string> {{
+    return {{ {} }};
+}}
+
+/** The GraphQL SDL of `{type_name}`. */
+export const {prefix}GraphQL: string = {} + Object.values({prefix}GraphQLFields()).join("\n") + "\n}}";"#,
+        entries.join(", "),
+        js_string(&header)
+    );
+    if !opts.input {
+        let extends = if heritage.is_empty() {
+            String::new()
+        } else {
+            let parents = heritage
+                .iter()
+                .map(|h| format!("{h}Resolvers<TContext>"))
+                .collect::<Vec<_>>();
+            format!(" extends {}", parents.join(", "))
+        };
+        let resolvers = fields
+            .iter()
+            .map(|f| format!("\n    {}", f.resolver))
+            .collect::<String>();
+        code.push_str(&format!(
+            r#"
+
+/** Resolvers for the fields of `{type_name}`. */
+export interface {type_name}Resolvers<TContext = unknown>{extends} {{{resolvers}
+}}"#
+        ));
+    }
+    code
+}
+
+fn generate_sdl_constant(type_name: &str, sdl: &str) -> String {
+    format!(
+        r#"/** The GraphQL SDL of `{type_name}`. */
+export const {}GraphQL: string = {};"#,
+        type_name.to_case(Case::Camel),
+        js_string(sdl)
+    )
+}
+
+fn object_members(fields: &[InterfaceFieldIR]) -> Vec<Member<'_>> {
+    fields
+        .iter()
+        .map(|f| Member {
+            name: &f.name,
+            ts_type: &f.ts_type,
+            optional: f.optional,
+            params: None,
+            decorators: &f.decorators,
+            span: f.span,
+        })
+        .collect()
+}
+
+/// The SDL and resolvers of a union alias, or why it can't be one.
+fn generate_union(
+    type_name: &str,
+    opts: &GraphQLOptions,
+    members: &[TypeMember],
+) -> Result<String, String> {
+    let members = members
+        .iter()
+        .filter(
+            |m| !matches!(&m.kind, TypeMemberKind::Literal(l) if l == "null" || l == "undefined"),
+        )
+        .collect::<Vec<_>>();
+    let header = opts
+        .description
+        .as_deref()
+        .map(|d| description(d, ""))
+        .unwrap_or_default();
+
+    let literals = members
+        .iter()
+        .filter_map(|m| match &m.kind {
+            TypeMemberKind::Literal(l)
+                if l.len() >= 2 && (l.starts_with('"') || l.starts_with('\'')) =>
+            {
+                Some(&l[1..l.len() - 1])
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !literals.is_empty() && literals.len() == members.len() {
+        if let Some(invalid) = literals.iter().find(|l| !is_graphql_name(l)) {
+            return Err(format!("'{invalid}' is not a valid GraphQL enum value"));
+        }
+        let values = literals
+            .iter()
+            .map(|l| format!("  {l}\n"))
+            .collect::<String>();
+        return Ok(generate_sdl_constant(
+            type_name,
+            &format!("{header}enum {type_name} {{\n{values}}}"),
+        ));
+    }
+
+    let names = members
+        .iter()
+        .map(|m| match &m.kind {
+            TypeMemberKind::TypeRef(name) if is_type_name(name) => Ok(name.as_str()),
+            _ => Err(
+                "GraphQL unions can only contain named object types; use string literals only for an enum"
+                    .to_string(),
+            ),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let typenames = names
+        .iter()
+        .map(|n| js_string(n))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    Ok(format!(
+        r#"{}
+
+/** Resolves the concrete type of a `{type_name}`. */
+export interface {type_name}Resolvers<TContext = unknown> {{
+    __resolveType(value: {type_name}, context: TContext): {typenames} | Promise<{typenames}>;
+}}"#,
+        generate_sdl_constant(
+            type_name,
+            &format!("{header}union {type_name} = {}", names.join(" | "))
+        )
+    ))
+}
+
+#[ts_macro_derive(
+    GraphQL,
+    description = "Generates GraphQL SDL and resolver types from classes, interfaces, enums and unions",
+    attributes((graphql, "Configure the GraphQL output. Options: description, input, interface, type, skip"))
+)]
+pub fn derive_graphql_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let docs = Docs {
+        source: &input.context.target_source,
+        start: input.context.target_span.start,
+    };
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let opts = GraphQLOptions::from_decorators(&container_decorators);
+    let mut diagnostics = DiagnosticCollector::new();
+    let generic_error = || {
+        MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(GraphQL) can't be used on generic types; GraphQL has no type parameters",
+        )
+    };
+
+    let code = match &input.data {
+        Data::Class(class) => {
+            if !class.inner.type_params.is_empty() {
+                return Err(generic_error());
+            }
+            let mut members = class
+                .fields()
+                .iter()
+                .filter(|f| f.visibility == Visibility::Public)
+                .map(|f| Member {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    params: None,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            members.extend(
+                class
+                    .methods()
+                    .iter()
+                    .filter(|m| {
+                        !m.is_static
+                            && m.visibility == Visibility::Public
+                            && m.name != "constructor"
+                    })
+                    .map(|m| Member {
+                        name: &m.name,
+                        ts_type: return_type(&m.return_type_src),
+                        optional: false,
+                        params: Some(&m.params_src),
+                        decorators: &m.decorators,
+                        span: m.span,
+                    }),
+            );
+            let fields = members
+                .iter()
+                .filter_map(|m| field(m, type_name, &docs, &mut diagnostics))
+                .collect::<Vec<_>>();
+            generate_object_type(
+                type_name,
+                &opts,
+                &heritage_names(&class.inner.heritage),
+                &fields,
+            )
+        }
+        Data::Interface(interface) => {
+            if !interface.inner.type_params.is_empty() {
+                return Err(generic_error());
+            }
+            let mut members = object_members(interface.fields());
+            members.extend(interface.methods().iter().map(|m| Member {
+                name: &m.name,
+                ts_type: return_type(&m.return_type_src),
+                optional: m.optional,
+                params: Some(&m.params_src),
+                decorators: &m.decorators,
+                span: m.span,
+            }));
+            let fields = members
+                .iter()
+                .filter_map(|m| field(m, type_name, &docs, &mut diagnostics))
+                .collect::<Vec<_>>();
+            generate_object_type(
+                type_name,
+                &opts,
+                &heritage_names(&interface.inner.heritage),
+                &fields,
+            )
+        }
+        Data::Enum(enum_data) => {
+            let mut sdl = opts
+                .description
+                .as_deref()
+                .map(|d| description(d, ""))
+                .unwrap_or_default();
+            sdl.push_str(&format!("enum {type_name} {{\n"));
+            for variant in enum_data.variants() {
+                if !is_graphql_name(&variant.name) {
+                    diagnostics.error(
+                        variant.span,
+                        format!(
+                            "@derive(GraphQL) '{}' is not a valid GraphQL enum value",
+                            variant.name
+                        ),
+                    );
+                }
+                if let Some(doc) = docs.get(variant.span) {
+                    sdl.push_str(&description(&doc, "  "));
+                }
+                sdl.push_str(&format!("  {}\n", variant.name));
+            }
+            sdl.push('}');
+            generate_sdl_constant(type_name, &sdl)
+        }
+        Data::TypeAlias(type_alias) => {
+            if !type_alias.inner.type_params.is_empty() {
+                return Err(generic_error());
+            }
+            if let Some(fields) = type_alias.as_object() {
+                let fields = object_members(fields)
+                    .iter()
+                    .filter_map(|m| field(m, type_name, &docs, &mut diagnostics))
+                    .collect::<Vec<_>>();
+                generate_object_type(type_name, &opts, &[], &fields)
+            } else if let Some(members) = type_alias.as_union() {
+                generate_union(type_name, &opts, members).map_err(|message| {
+                    MacroforgeError::new(
+                        input.decorator_span(),
+                        format!("@derive(GraphQL) on '{type_name}': {message}"),
+                    )
+                })?
+            } else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    "@derive(GraphQL) type aliases must be object types or unions",
+                ));
+            }
+        }
+    };
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    Ok(TsStream::from_string(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_type() {
+        assert_eq!(graphql_type("string", false, None).unwrap(), "String!");
+        assert_eq!(graphql_type("number | null", false, None).unwrap(), "Float");
+        assert_eq!(graphql_type("string", true, None).unwrap(), "String");
+        assert_eq!(
+            graphql_type("(Post | null)[]", false, None).unwrap(),
+            "[Post]!"
+        );
+        assert_eq!(
+            graphql_type("Array<string>", false, Some("ID")).unwrap(),
+            "[ID!]!"
+        );
+        assert!(graphql_type("Record<string, number>", false, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args =
+            parse_args("limit: number = 10, after?: string, sort: (a: A) => number").unwrap();
+        assert_eq!(args[0].default.as_deref(), Some("10"));
+        assert!(args[0].optional);
+        assert!(args[1].optional && args[1].default.is_none());
+        assert_eq!(args[2].ts_type, "(a: A) => number");
+        assert!(parse_args("{ id }: { id: string }").is_err());
+        assert_eq!(graphql_default("'new'").as_deref(), Some("\"new\""));
+        assert_eq!(graphql_default("Date.now()"), None);
+    }
+
+    #[test]
+    fn test_method_field() {
+        let docs = Docs {
+            source: "",
+            start: 0,
+        };
+        let mut diagnostics = DiagnosticCollector::new();
+        let field = field(
+            &Member {
+                name: "posts",
+                ts_type: "Promise<Post[]>",
+                optional: false,
+                params: Some("limit: number = 10"),
+                decorators: &[],
+                span: SpanIR::new(0, 0),
+            },
+            "User",
+            &docs,
+            &mut diagnostics,
+        )
+        .unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(field.sdl, "  posts(limit: Float = 10): [Post!]!");
+        assert_eq!(
+            field.resolver,
+            "posts?(parent: User, args: { limit?: number }, context: TContext): Post[] | Promise<Post[]>;"
+        );
+    }
+
+    #[test]
+    fn test_docs_read_member_jsdoc() {
+        let source = "class User {\n    /** Login address. */\n    email: string;\n}";
+        let docs = Docs {
+            source: &source[1..],
+            start: 1,
+        };
+        let email = source.find("email").unwrap() as u32 + 1;
+        assert_eq!(
+            docs.get(SpanIR::new(email, email + 14)).as_deref(),
+            Some("Login address.")
+        );
+    }
+
+    #[test]
+    fn test_description() {
+        assert_eq!(
+            description("Login address.", "  "),
+            "  \"\"\"Login address.\"\"\"\n"
+        );
+        assert_eq!(
+            description("First.\n\nSecond.", "  "),
+            "  \"\"\"\n  First.\n\n  Second.\n  \"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_union() {
+        let members = [
+            TypeMember::new(TypeMemberKind::TypeRef("User".into())),
+            TypeMember::new(TypeMemberKind::TypeRef("Post".into())),
+        ];
+        let code = generate_union("SearchResult", &GraphQLOptions::default(), &members).unwrap();
+        assert!(code.contains("\"union SearchResult = User | Post\""));
+        assert!(code.contains(
+            "__resolveType(value: SearchResult, context: TContext): \"User\" | \"Post\""
+        ));
+
+        let members = [
+            TypeMember::new(TypeMemberKind::Literal("\"draft\"".into())),
+            TypeMember::new(TypeMemberKind::Literal("\"published\"".into())),
+        ];
+        let code = generate_union("Status", &GraphQLOptions::default(), &members).unwrap();
+        assert!(code.contains("\"enum Status {\\n  draft\\n  published\\n}\""));
+
+        let members = [TypeMember::new(TypeMemberKind::TypeRef("string".into()))];
+        assert!(generate_union("Id", &GraphQLOptions::default(), &members).is_err());
+    }
+}
@@ -45,6 +45,7 @@
 //! The `@hash` decorator supports:
 //!
 //! - `skip` - Exclude the field from hash calculation
+//! - `with = "fn"` - Hash with a custom function `(value) => number`
 //!
 //! ## Example
 //!
@@ -65,18 +66,18 @@
 //! class User {
 //!     id: number;
 //!     name: string;
-//! 
+//!
 //!     cachedScore: number;
-//! 
+//!
 //!     static hashCode(value: User): number {
 //!         return userHashCode(value);
 //!     }
-//! 
+//!
 //!     static equals(a: User, b: User): boolean {
 //!         return userEquals(a, b);
 //!     }
 //! }
-//! 
+//!
 //! export function userHashCode(value: User): number {
 //!     let hash = 17;
 //!     hash =
@@ -94,7 +95,7 @@
 //!         0;
 //!     return hash;
 //! }
-//! 
+//!
 //! export function userEquals(a: User, b: User): boolean {
 //!     if (a === b) return true;
 //!     return a.id === b.id && a.name === b.name && a.cachedScore === b.cachedScore;
@@ -106,11 +107,25 @@
 //! Objects that are equal (`PartialEq`) should produce the same hash code.
 //! When using `@hash(skip)`, ensure the same fields are skipped in both
 //! `Hash` and `PartialEq` to maintain this contract.
+//!
+//! `Hash` reads the `@partialEq` options (field and container level) to keep
+//! the contract on its own where it can:
+//!
+//! - Fields compared with `epsilon` or a custom `with` function are left out of
+//!   the hash, unless `@hash({ with: "fn" })` supplies a matching hash function.
+//! - `nanEqual` needs nothing special, since every `NaN` hashes the same.
+//!
+//! When the type also derives `PartialEq`, a warning is emitted for fields that
+//! `PartialEq` skips but `Hash` still includes, and for `epsilon` fields given a
+//! custom hash (tolerance equality is not transitive, so no hash can match it).
 
 use convert_case::{Case, Casing};
 
-use crate::builtin::derive_common::{CompareFieldOptions, is_primitive_type};
+use crate::builtin::derive_common::{
+    CompareFieldOptions, EqFieldOptions, derives_macro, is_primitive_type, target_decorators,
+};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
 /// Contains field information needed for hash code generation.
@@ -236,33 +251,119 @@ pub fn generate_field_hash_for_interface(field: &HashField, var: &str) -> String
     }
 }
 
+/// Equality settings the Hash derive needs to stay consistent with `PartialEq`.
+struct EqContext {
+    /// Container-level `@partialEq` defaults
+    container: EqFieldOptions,
+    /// Whether the type also derives `PartialEq`, which enables contract warnings
+    derives_partial_eq: bool,
+}
+
+/// Collects the fields that participate in hashing, each paired with an
+/// optional custom hash function from `@hash({ with: "fn" })`.
+///
+/// Fields whose `@partialEq` options cannot be mirrored by a hash are left
+/// out, and combinations that break the Hash/Eq contract produce warnings.
+fn collect_hash_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, &'a [DecoratorIR], SpanIR)>,
+    eq: &EqContext,
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<(HashField, Option<String>)> {
+    let mut hash_fields = Vec::new();
+    for (name, ts_type, decorators, span) in fields {
+        let opts = CompareFieldOptions::from_decorators(decorators, "hash");
+        if opts.skip {
+            continue;
+        }
+
+        let mut eq_opts = EqFieldOptions::from_decorators(decorators);
+        eq_opts.inherit(&eq.container);
+
+        if eq.derives_partial_eq && eq_opts.skip {
+            diagnostics.warning(
+                span,
+                format!(
+                    "'{name}' is skipped by PartialEq but included in hashCode; equal values may hash differently. Add @hash({{ skip: true }})"
+                ),
+            );
+        }
+
+        if eq_opts.excludes_from_hash(ts_type) {
+            if opts.with.is_none() {
+                continue;
+            }
+            if eq.derives_partial_eq
+                && EqFieldOptions::applies_to(ts_type)
+                && eq_opts.epsilon.is_some()
+            {
+                diagnostics.warning(
+                    span,
+                    format!(
+                        "'{name}' is compared with an epsilon tolerance, which is not transitive; no custom hash can be consistent with it"
+                    ),
+                );
+            }
+        }
+
+        hash_fields.push((
+            HashField {
+                name: name.to_string(),
+                ts_type: ts_type.to_string(),
+            },
+            opts.with,
+        ));
+    }
+    hash_fields
+}
+
+/// Builds the `hash = (hash * 31 + ...) | 0;` statements for the collected fields.
+fn generate_hash_body(hash_fields: &[(HashField, Option<String>)], separator: &str) -> String {
+    hash_fields
+        .iter()
+        .map(|(f, with)| {
+            let expr = match with {
+                Some(with) => format!("(({with})(value.{}) | 0)", f.name),
+                None => generate_field_hash_for_interface(f, "value"),
+            };
+            format!("hash = (hash * 31 + {expr}) | 0;")
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
 #[ts_macro_derive(
     Hash,
     description = "Generates a hashCode() method for hashing",
-    attributes(hash)
+    attributes((hash, "Configure hashing. Options: skip, with (custom (value) => number)"))
 )]
 pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
-    match &input.data {
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let eq = EqContext {
+        container: EqFieldOptions::from_decorators(&container_decorators),
+        derives_partial_eq: derives_macro(target_decorators(&input), "PartialEq"),
+    };
+    let mut diagnostics = DiagnosticCollector::new();
+
+    let mut output = match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
 
             // Collect fields that should be included in hash
-            let hash_fields: Vec<HashField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(HashField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let hash_fields = collect_hash_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &eq,
+                &mut diagnostics,
+            );
 
             let has_fields = !hash_fields.is_empty();
 
@@ -270,20 +371,7 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
             let fn_name = format!("{}HashCode", class_name.to_case(Case::Camel));
 
             // Build hash computation using value parameter instead of this
-            let hash_body = if has_fields {
-                hash_fields
-                    .iter()
-                    .map(|f| {
-                        format!(
-                            "hash = (hash * 31 + {}) | 0;",
-                            generate_field_hash_for_interface(f, "value")
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                    ")
-            } else {
-                String::new()
-            };
+            let hash_body = generate_hash_body(&hash_fields, "\n                    ");
 
             // Generate standalone function with value parameter
             let standalone = ts_template! {
@@ -310,21 +398,18 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
             combined.runtime_patches = standalone.runtime_patches;
             combined.runtime_patches.extend(class_body.runtime_patches);
 
-            Ok(combined)
+            combined
         }
         Data::Enum(enum_data) => {
             let enum_name = input.name();
             let fn_name = format!("{}HashCode", enum_name.to_case(Case::Camel));
 
             // Check if all variants are string values
-            let is_string_enum = enum_data
-                .variants()
-                .iter()
-                .all(|v| v.value.is_string());
+            let is_string_enum = enum_data.variants().iter().all(|v| v.value.is_string());
 
             if is_string_enum {
                 // String enum: hash the string value
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(value: @{enum_name}): number {
                         let hash = 0;
                         for (let i = 0; i < value.length; i++) {
@@ -332,54 +417,39 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
                         }
                         return hash;
                     }
-                })
+                }
             } else {
                 // Numeric enum: use the number value directly
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(value: @{enum_name}): number {
                         return value as number;
                     }
-                })
+                }
             }
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
 
-            let hash_fields: Vec<HashField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(HashField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let hash_fields = collect_hash_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &eq,
+                &mut diagnostics,
+            );
 
             let has_fields = !hash_fields.is_empty();
 
-            let hash_body = if has_fields {
-                hash_fields
-                    .iter()
-                    .map(|f| {
-                        format!(
-                            "hash = (hash * 31 + {}) | 0;",
-                            generate_field_hash_for_interface(f, "value")
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                        ")
-            } else {
-                String::new()
-            };
+            let hash_body = generate_hash_body(&hash_fields, "\n                        ");
 
             let fn_name = format!("{}HashCode", interface_name.to_case(Case::Camel));
 
-            Ok(ts_template! {
+            ts_template! {
                 export function @{fn_name}(value: @{interface_name}): number {
                     let hash = 17;
                     {#if has_fields}
@@ -387,48 +457,32 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
                     {/if}
                     return hash;
                 }
-            })
+            }
         }
         Data::TypeAlias(type_alias) => {
             let type_name = input.name();
 
             if type_alias.is_object() {
-                let hash_fields: Vec<HashField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "hash");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(HashField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
+                let hash_fields = collect_hash_fields(
+                    type_alias.as_object().unwrap().iter().map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.decorators.as_slice(),
+                            f.span,
+                        )
+                    }),
+                    &eq,
+                    &mut diagnostics,
+                );
 
                 let has_fields = !hash_fields.is_empty();
 
-                let hash_body = if has_fields {
-                    hash_fields
-                        .iter()
-                        .map(|f| {
-                            format!(
-                                "hash = (hash * 31 + {}) | 0;",
-                                generate_field_hash_for_interface(f, "value")
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n                        ")
-                } else {
-                    String::new()
-                };
+                let hash_body = generate_hash_body(&hash_fields, "\n                        ");
 
                 let fn_name = format!("{}HashCode", type_name.to_case(Case::Camel));
 
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(value: @{type_name}): number {
                         let hash = 17;
                         {#if has_fields}
@@ -436,12 +490,12 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
                         {/if}
                         return hash;
                     }
-                })
+                }
             } else {
                 // Union, tuple, or simple alias: use JSON hash
                 let fn_name = format!("{}HashCode", type_name.to_case(Case::Camel));
 
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(value: @{type_name}): number {
                         const str = JSON.stringify(value);
                         let hash = 0;
@@ -450,10 +504,13 @@ pub fn derive_hash_macro(mut input: TsStream) -> Result<TsStream, MacroforgeErro
                         }
                         return hash;
                     }
-                })
+                }
             }
         }
-    }
+    };
+
+    output.add_diagnostics(diagnostics.into_vec());
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -557,4 +614,147 @@ mod tests {
         let result = generate_field_hash_for_interface(&field, "value");
         assert!(result.contains("hashCode"));
     }
+
+    fn make_decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn collect(
+        fields: &[(&str, &str, Vec<DecoratorIR>)],
+        eq: &EqContext,
+    ) -> (Vec<(HashField, Option<String>)>, DiagnosticCollector) {
+        let mut diagnostics = DiagnosticCollector::new();
+        let hash_fields = collect_hash_fields(
+            fields
+                .iter()
+                .map(|(name, ts_type, decs)| (*name, *ts_type, decs.as_slice(), SpanIR::new(0, 0))),
+            eq,
+            &mut diagnostics,
+        );
+        (hash_fields, diagnostics)
+    }
+
+    fn eq_context(container: &str) -> EqContext {
+        let decorators = vec![make_decorator("partialEq", container)];
+        EqContext {
+            container: EqFieldOptions::from_decorators(&decorators),
+            derives_partial_eq: true,
+        }
+    }
+
+    #[test]
+    fn test_epsilon_field_excluded_from_hash() {
+        let fields = vec![
+            ("id", "number", vec![]),
+            (
+                "x",
+                "number",
+                vec![make_decorator("partialEq", "{ epsilon: 1e-9 }")],
+            ),
+        ];
+        let (hash_fields, diagnostics) = collect(&fields, &eq_context(""));
+        let names: Vec<_> = hash_fields.iter().map(|(f, _)| f.name.as_str()).collect();
+        assert_eq!(names, vec!["id"]);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_container_epsilon_excludes_number_fields_only() {
+        let fields = vec![
+            ("x", "number", vec![]),
+            ("y", "number | null", vec![]),
+            ("z", "number | undefined", vec![]),
+            ("label", "string", vec![]),
+        ];
+        let (hash_fields, _) = collect(&fields, &eq_context("{ epsilon: 0.001 }"));
+        let names: Vec<_> = hash_fields.iter().map(|(f, _)| f.name.as_str()).collect();
+        assert_eq!(names, vec!["label"]);
+    }
+
+    #[test]
+    fn test_nan_equal_field_still_hashed() {
+        let fields = vec![(
+            "x",
+            "number",
+            vec![make_decorator("partialEq", "{ nanEqual: true }")],
+        )];
+        let (hash_fields, diagnostics) = collect(&fields, &eq_context(""));
+        assert_eq!(hash_fields.len(), 1);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_custom_equality_uses_custom_hash() {
+        let fields = vec![
+            (
+                "p",
+                "Point",
+                vec![make_decorator("partialEq", "{ with: \"pointEq\" }")],
+            ),
+            (
+                "q",
+                "Point",
+                vec![
+                    make_decorator("partialEq", "{ with: \"pointEq\" }"),
+                    make_decorator("hash", "{ with: \"pointHash\" }"),
+                ],
+            ),
+        ];
+        let (hash_fields, diagnostics) = collect(&fields, &eq_context(""));
+        assert_eq!(hash_fields.len(), 1);
+        assert_eq!(hash_fields[0].0.name, "q");
+        assert!(diagnostics.is_empty());
+
+        let body = generate_hash_body(&hash_fields, "\n");
+        assert_eq!(body, "hash = (hash * 31 + ((pointHash)(value.q) | 0)) | 0;");
+    }
+
+    #[test]
+    fn test_warns_when_partial_eq_skips_hashed_field() {
+        let fields = vec![("cache", "string", vec![make_decorator("partialEq", "skip")])];
+        let (hash_fields, diagnostics) = collect(&fields, &eq_context(""));
+        assert_eq!(hash_fields.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics.diagnostics()[0]
+                .message
+                .contains("@hash({ skip: true })")
+        );
+    }
+
+    #[test]
+    fn test_warns_on_custom_hash_for_epsilon_field() {
+        let fields = vec![(
+            "x",
+            "number",
+            vec![
+                make_decorator("partialEq", "{ epsilon: 1e-9 }"),
+                make_decorator("hash", "{ with: \"roundHash\" }"),
+            ],
+        )];
+        let (hash_fields, diagnostics) = collect(&fields, &eq_context(""));
+        assert_eq!(hash_fields.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics.diagnostics()[0]
+                .message
+                .contains("not transitive")
+        );
+    }
+
+    #[test]
+    fn test_no_warnings_without_partial_eq_derive() {
+        let fields = vec![("cache", "string", vec![make_decorator("partialEq", "skip")])];
+        let eq = EqContext {
+            container: EqFieldOptions::default(),
+            derives_partial_eq: false,
+        };
+        let (_, diagnostics) = collect(&fields, &eq);
+        assert!(diagnostics.is_empty());
+    }
 }
@@ -0,0 +1,723 @@
+//! # HttpClient Macro Implementation
+//!
+//! The `HttpClient` macro turns a service interface into a typed RPC layer:
+//! a fetch-based client class and a framework-agnostic server dispatcher.
+//! Each method declares its route with a `@route` decorator.
+//!
+//! ## Generated Output
+//!
+//! For an interface `UserService`:
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `class UserServiceClient implements UserService` | Sends one request per call; `new UserServiceClient({ baseUrl })` |
+//! | `userServiceDispatcher(impl)` | `(request: HttpRequest) => Promise<HttpResponse>` calling `impl` |
+//!
+//! `fetchHandler()` from `macroforge/http` adapts a dispatcher to the Fetch
+//! API; other frameworks only need to pass method, path, query and the
+//! parsed JSON body.
+//!
+//! ## Parameters
+//!
+//! - Parameters named in the path (`/users/:id`) are sent as path segments
+//! - Other parameters of `GET`, `HEAD` and `DELETE` routes are sent as query
+//!   parameters
+//! - Other parameters of all other routes are sent as properties of a JSON
+//!   body object, keyed by parameter name
+//!
+//! Path and query parameters must be `string`, `number` or `boolean`.
+//! Body parameters and results may be any JSON value; `Date`s are sent as
+//! ISO strings, arrays element by element, and types deriving `Serialize`
+//! and `Deserialize` go through their generated functions. Invalid input is
+//! answered with a `400` listing each invalid field.
+//!
+//! ## Envelopes
+//!
+//! Both sides use the envelopes of `macroforge/http`: `{ data }` on
+//! success, an empty `204` for `Promise<void>`, and
+//! `{ error: { code, message, details? } }` on failure. An `HttpError`
+//! thrown by the implementation keeps its status and reaches the client as
+//! the same `HttpError`; any other error becomes a `500`.
+//!
+//! ## Method-Level Options
+//!
+//! - `@route({ method: "POST", path: "/users/:id" })` - Required on every
+//!   method; `method` defaults to `"POST"`
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(HttpClient) */
+//! interface UserService {
+//!     /** @route({ method: "GET", path: "/users/:id" }) */
+//!     getUser(id: string): Promise<User>;
+//!
+//!     /** @route({ method: "PUT", path: "/users/:id" }) */
+//!     saveUser(id: string, user: User): Promise<void>;
+//! }
+//!
+//! const users = new UserServiceClient({ baseUrl: "https://api.example.com" });
+//! await users.saveUser("42", user); // PUT /users/42 with body { user: ... }
+//! ```
+
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{extract_named_string, split_top_level};
+use crate::builtin::serde::TypeCategory;
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, InterfaceMethodIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Route declared by a `@route` decorator.
+#[derive(Debug, Clone, PartialEq)]
+struct RouteOptions {
+    method: String,
+    path: String,
+}
+
+impl RouteOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Option<Self> {
+        let decorator = decorators
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case("route"))?;
+        let args = decorator.args_src.trim();
+        Some(Self {
+            method: extract_named_string(args, "method")
+                .map_or_else(|| "POST".to_string(), |m| m.to_uppercase()),
+            path: extract_named_string(args, "path")?,
+        })
+    }
+
+    /// Names of the `:name` segments.
+    fn path_params(&self) -> Vec<&str> {
+        self.path
+            .split('/')
+            .filter_map(|segment| segment.strip_prefix(':'))
+            .collect()
+    }
+
+    fn uses_query(&self) -> bool {
+        matches!(self.method.as_str(), "GET" | "HEAD" | "DELETE")
+    }
+}
+
+/// A method parameter, e.g. `limit?: number`.
+#[derive(Debug, Clone, PartialEq)]
+struct Param {
+    name: String,
+    ts_type: String,
+    optional: bool,
+}
+
+impl Param {
+    /// The type including `undefined` for optional parameters.
+    fn full_type(&self) -> String {
+        if self.optional {
+            format!("{} | undefined", self.ts_type)
+        } else {
+            self.ts_type.clone()
+        }
+    }
+}
+
+/// Where a parameter travels in the request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Placement {
+    Path,
+    Query,
+    Body,
+}
+
+fn parse_params(params_src: &str) -> Result<Vec<Param>, String> {
+    split_top_level(params_src, ',')
+        .into_iter()
+        .map(|param| {
+            let (name, ts_type) = match param.split_once(':') {
+                Some((name, ts_type)) => (name.trim(), ts_type.trim()),
+                None => (param.trim(), "unknown"),
+            };
+            let (name, optional) = match name.strip_suffix('?') {
+                Some(name) => (name.trim(), true),
+                None => (name, false),
+            };
+            if name.starts_with("...") || name.starts_with('{') || name.starts_with('[') {
+                return Err(format!(
+                    "parameter '{param}' must be a plain named parameter"
+                ));
+            }
+            Ok(Param {
+                name: name.to_string(),
+                ts_type: ts_type.to_string(),
+                optional,
+            })
+        })
+        .collect()
+}
+
+/// `T` of a `Promise<T>` return type.
+fn promise_payload(return_type: &str) -> Option<&str> {
+    return_type
+        .trim()
+        .strip_prefix("Promise<")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .map(str::trim)
+}
+
+fn is_void(ts_type: &str) -> bool {
+    matches!(ts_type, "void" | "undefined")
+}
+
+/// The `typeof` of a path or query parameter type.
+fn scalar_kind(ts_type: &str) -> Option<&'static str> {
+    match ts_type.trim() {
+        "string" => Some("string"),
+        "number" => Some("number"),
+        "boolean" => Some("boolean"),
+        t if !t.is_empty()
+            && split_top_level(t, '|')
+                .iter()
+                .all(|m| m.starts_with('"') || m.starts_with('\'')) =>
+        {
+            Some("string")
+        }
+        _ => None,
+    }
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// Builds the encode and decode expressions, recording the runtime helpers
+/// they use.
+#[derive(Default)]
+struct Codec {
+    helpers: BTreeSet<&'static str>,
+    uses_serialize_context: bool,
+}
+
+impl Codec {
+    /// Converts `expr` of type `ts_type` to its JSON form.
+    fn encode(&mut self, ts_type: &str, expr: &str) -> String {
+        match TypeCategory::from_ts_type(ts_type) {
+            TypeCategory::Date => format!("{expr}.toISOString()"),
+            TypeCategory::Array(inner) => {
+                let item = self.encode(&inner, "item");
+                if item == "item" {
+                    expr.to_string()
+                } else {
+                    format!("{expr}.map((item) => {item})")
+                }
+            }
+            TypeCategory::Optional(inner) | TypeCategory::Nullable(inner) => {
+                let encoded = self.encode(&inner, expr);
+                if encoded == expr {
+                    encoded
+                } else {
+                    format!("({expr} == null ? {expr} : {encoded})")
+                }
+            }
+            TypeCategory::Set(_) => format!("Array.from({expr})"),
+            TypeCategory::Map(_, _) => format!("Object.fromEntries({expr})"),
+            TypeCategory::Serializable(name) if !name.contains('<') => {
+                self.uses_serialize_context = true;
+                format!(
+                    "{}SerializeWithContext({expr}, __mf_SerializeContext.create())",
+                    name.to_case(Case::Camel)
+                )
+            }
+            _ => expr.to_string(),
+        }
+    }
+
+    /// Checks and converts the JSON value `expr` to `ts_type`. `field` is a
+    /// JavaScript string literal naming the value in errors.
+    fn decode(&mut self, ts_type: &str, expr: &str, field: &str) -> String {
+        let ts_type = ts_type.trim();
+        if matches!(ts_type, "string" | "number" | "boolean") {
+            self.helpers.insert("expect");
+            return format!("__mf_expect<{ts_type}>({expr}, \"{ts_type}\", {field})");
+        }
+        match TypeCategory::from_ts_type(ts_type) {
+            TypeCategory::Date => {
+                self.helpers.insert("expectDate");
+                format!("__mf_expectDate({expr}, {field})")
+            }
+            TypeCategory::Array(inner) => {
+                self.helpers.insert("expectArray");
+                let item = self.decode(&inner, "item", field);
+                format!("__mf_expectArray({expr}, {field}).map((item) => {item})")
+            }
+            TypeCategory::Set(inner) => {
+                self.helpers.insert("expectArray");
+                let item = self.decode(&inner, "item", field);
+                format!("new Set(__mf_expectArray({expr}, {field}).map((item) => {item}))")
+            }
+            TypeCategory::Map(_, _) => {
+                format!("new Map(Object.entries({expr} as Record<string, unknown>)) as {ts_type}")
+            }
+            TypeCategory::Optional(inner) => {
+                let decoded = self.decode(&inner, expr, field);
+                format!("({expr} == null ? undefined : {decoded})")
+            }
+            TypeCategory::Nullable(inner) => {
+                let decoded = self.decode(&inner, expr, field);
+                format!("({expr} === null ? null : {decoded})")
+            }
+            TypeCategory::Serializable(name) if !name.contains('<') => {
+                self.helpers.insert("unwrap");
+                format!(
+                    "__mf_unwrap({}Deserialize({expr}), {field})",
+                    name.to_case(Case::Camel)
+                )
+            }
+            _ => format!("{expr} as {ts_type}"),
+        }
+    }
+}
+
+/// A method with its validated route.
+#[derive(Debug)]
+struct Endpoint<'a> {
+    method: &'a InterfaceMethodIR,
+    route: RouteOptions,
+    params: Vec<(Param, Placement)>,
+    /// `T` of `Promise<T>`.
+    payload: &'a str,
+}
+
+fn endpoint<'a>(method: &'a InterfaceMethodIR) -> Result<Endpoint<'a>, String> {
+    let route = RouteOptions::from_decorators(&method.decorators).ok_or_else(|| {
+        format!(
+            "method '{}' needs a route, e.g. @route({{ method: \"GET\", path: \"/{}\" }})",
+            method.name,
+            method.name.to_case(Case::Kebab)
+        )
+    })?;
+    let payload = promise_payload(&method.return_type_src).ok_or_else(|| {
+        format!(
+            "method '{}' must return a Promise, found '{}'",
+            method.name, method.return_type_src
+        )
+    })?;
+    let params = parse_params(&method.params_src)
+        .map_err(|message| format!("method '{}': {message}", method.name))?;
+
+    for name in route.path_params() {
+        if !params.iter().any(|p| p.name == name) {
+            return Err(format!(
+                "route '{}' of method '{}' uses ':{name}', but the method has no parameter '{name}'",
+                route.path, method.name
+            ));
+        }
+    }
+
+    let path_params = route.path_params();
+    let params = params
+        .into_iter()
+        .map(|param| {
+            let placement = if path_params.contains(&param.name.as_str()) {
+                Placement::Path
+            } else if route.uses_query() {
+                Placement::Query
+            } else {
+                Placement::Body
+            };
+            if placement != Placement::Body && scalar_kind(&param.ts_type).is_none() {
+                return Err(format!(
+                    "parameter '{}' of method '{}' is sent in the URL and must be a string, number or boolean",
+                    param.name, method.name
+                ));
+            }
+            Ok((param, placement))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Endpoint {
+        method,
+        route,
+        params,
+        payload,
+    })
+}
+
+fn generate_client(interface_name: &str, endpoints: &[Endpoint<'_>], codec: &mut Codec) -> String {
+    let methods = endpoints
+        .iter()
+        .map(|endpoint| {
+            let method = endpoint.method;
+            let mut path_args = Vec::new();
+            let mut query = Vec::new();
+            let mut body = Vec::new();
+            for (param, placement) in &endpoint.params {
+                match placement {
+                    Placement::Path => path_args.push(param.name.clone()),
+                    Placement::Query => query.push(param.name.clone()),
+                    Placement::Body => body.push(format!(
+                        "{}: {}",
+                        param.name,
+                        codec.encode(&param.full_type(), &param.name)
+                    )),
+                }
+            }
+
+            let path = if path_args.is_empty() {
+                js_string(&endpoint.route.path)
+            } else {
+                codec.helpers.insert("buildPath");
+                format!(
+                    "__mf_buildPath({}, {{ {} }})",
+                    js_string(&endpoint.route.path),
+                    path_args.join(", ")
+                )
+            };
+            let mut init = vec![
+                format!("method: {}", js_string(&endpoint.route.method)),
+                format!("path: {path}"),
+            ];
+            if !query.is_empty() {
+                init.push(format!("query: {{ {} }}", query.join(", ")));
+            }
+            if !body.is_empty() {
+                init.push(format!("body: {{ {} }}", body.join(", ")));
+            }
+            let decode = if is_void(endpoint.payload) {
+                String::new()
+            } else {
+                format!(
+                    ", (data) => {}",
+                    codec.decode(endpoint.payload, "data", "\"result\"")
+                )
+            };
+
+            format!(
+                "    {}{}({}): {} {{\n        return __mf_request(this.#options, {{ {} }}{decode});\n    }}",
+                method.name,
+                method.type_params_src,
+                method.params_src,
+                method.return_type_src,
+                init.join(", ")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "export class {interface_name}Client implements {interface_name} {{\n    readonly #options: __mf_HttpClientOptions;\n\n    constructor(options: __mf_HttpClientOptions) {{\n        this.#options = options;\n    }}\n\n{}\n}}",
+        methods.join("\n\n")
+    )
+}
+
+fn generate_dispatcher(
+    interface_name: &str,
+    endpoints: &[Endpoint<'_>],
+    codec: &mut Codec,
+) -> String {
+    let routes = endpoints
+        .iter()
+        .map(|endpoint| {
+            let mut statements = Vec::new();
+            let (mut uses_params, mut uses_query, mut uses_body) = (false, false, false);
+            if endpoint.params.iter().any(|(_, p)| *p == Placement::Body) {
+                uses_body = true;
+                codec.helpers.insert("expectBody");
+                statements.push("const input = __mf_expectBody(body);".to_string());
+            }
+            for (param, placement) in &endpoint.params {
+                let field = js_string(&param.name);
+                let value = match placement {
+                    Placement::Path | Placement::Query => {
+                        codec.helpers.insert("fromString");
+                        let kind = scalar_kind(&param.ts_type).unwrap_or("string");
+                        let raw = if *placement == Placement::Path {
+                            uses_params = true;
+                            format!("params[{field}]")
+                        } else {
+                            uses_query = true;
+                            format!("query.get({field})")
+                        };
+                        let parsed = format!(
+                            "__mf_fromString<{}>({raw}, \"{kind}\", {field})",
+                            param.ts_type
+                        );
+                        if param.optional && *placement == Placement::Query {
+                            format!("query.has({field}) ? {parsed} : undefined")
+                        } else {
+                            parsed
+                        }
+                    }
+                    Placement::Body => {
+                        codec.decode(&param.full_type(), &format!("input[{field}]"), &field)
+                    }
+                };
+                statements.push(format!("const {} = {value};", param.name));
+            }
+
+            let args = endpoint
+                .params
+                .iter()
+                .map(|(p, _)| p.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let call = format!("impl.{}({args})", endpoint.method.name);
+            if is_void(endpoint.payload) {
+                statements.push(format!("await {call};"));
+                statements.push("return undefined;".to_string());
+            } else {
+                statements.push(format!("const result = await {call};"));
+                statements.push(format!(
+                    "return {} ?? null;",
+                    codec.encode(endpoint.payload, "result")
+                ));
+            }
+
+            let unused = |used: bool, name: &str| {
+                if used {
+                    name.to_string()
+                } else {
+                    format!("_{name}")
+                }
+            };
+            format!(
+                "        {{\n            method: {},\n            path: {},\n            handle: async ({}, {}, {}) => {{\n{}\n            }},\n        }},",
+                js_string(&endpoint.route.method),
+                js_string(&endpoint.route.path),
+                unused(uses_params, "params"),
+                unused(uses_query, "query"),
+                unused(uses_body, "body"),
+                statements
+                    .iter()
+                    .map(|s| format!("                {s}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "export function {}Dispatcher(impl: {interface_name}): (request: __mf_HttpRequest) => Promise<__mf_HttpResponse> {{\n    return __mf_createDispatcher([\n{}\n    ]);\n}}",
+        interface_name.to_case(Case::Camel),
+        routes.join("\n")
+    )
+}
+
+#[ts_macro_derive(
+    HttpClient,
+    description = "Generates a typed fetch client and a server dispatcher from a service interface",
+    attributes((route, "Route of a method. Options: method (HTTP method, default \"POST\"), path (e.g. \"/users/:id\")"))
+)]
+pub fn derive_http_client_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let interface_name = input.name();
+
+    let Data::Interface(interface) = &input.data else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(HttpClient) can only be used on interfaces",
+        ));
+    };
+
+    let mut diagnostics = DiagnosticCollector::new();
+    for field in interface.fields() {
+        diagnostics.error(
+            field.span,
+            format!(
+                "@derive(HttpClient) interfaces may only declare methods, found property '{}'",
+                field.name
+            ),
+        );
+    }
+    let mut endpoints: Vec<Endpoint<'_>> = Vec::new();
+    for method in interface.methods() {
+        match endpoint(method) {
+            Ok(endpoint) => {
+                if let Some(other) = endpoints.iter().find(|e| e.route == endpoint.route) {
+                    diagnostics.error(
+                        method.span,
+                        format!(
+                            "method '{}' has the same route as '{}': {} {}",
+                            method.name,
+                            other.method.name,
+                            endpoint.route.method,
+                            endpoint.route.path
+                        ),
+                    );
+                } else if endpoints.iter().any(|e| e.method.name == method.name) {
+                    diagnostics.error(
+                        method.span,
+                        format!(
+                            "method '{}' is overloaded; overloads cannot be routed",
+                            method.name
+                        ),
+                    );
+                } else {
+                    endpoints.push(endpoint);
+                }
+            }
+            Err(message) => {
+                diagnostics.error(method.span, format!("@derive(HttpClient) {message}"))
+            }
+        }
+    }
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    let mut codec = Codec::default();
+    let client = generate_client(interface_name, &endpoints, &mut codec);
+    let dispatcher = generate_dispatcher(interface_name, &endpoints, &mut codec);
+
+    let mut output = TsStream::from_string(format!("{client}\n\n{dispatcher}"));
+    output.add_aliased_import("request", "macroforge/http");
+    output.add_aliased_import("createDispatcher", "macroforge/http");
+    for helper in &codec.helpers {
+        output.add_aliased_import(helper, "macroforge/http");
+    }
+    output.add_aliased_type_import("HttpClientOptions", "macroforge/http");
+    output.add_aliased_type_import("HttpRequest", "macroforge/http");
+    output.add_aliased_type_import("HttpResponse", "macroforge/http");
+    if codec.uses_serialize_context {
+        output.add_aliased_import("SerializeContext", "macroforge/serde");
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn method(
+        name: &str,
+        params: &str,
+        return_type: &str,
+        route: Option<&str>,
+    ) -> InterfaceMethodIR {
+        InterfaceMethodIR {
+            name: name.into(),
+            span: SpanIR::new(0, 0),
+            type_params_src: String::new(),
+            params_src: params.into(),
+            return_type_src: return_type.into(),
+            optional: false,
+            decorators: route
+                .map(|args| DecoratorIR {
+                    name: "route".into(),
+                    args_src: args.into(),
+                    span: SpanIR::new(0, 0),
+                    node: None,
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_params() {
+        assert_eq!(
+            parse_params("id: string, filter?: Map<string, number>").unwrap(),
+            [
+                Param {
+                    name: "id".into(),
+                    ts_type: "string".into(),
+                    optional: false
+                },
+                Param {
+                    name: "filter".into(),
+                    ts_type: "Map<string, number>".into(),
+                    optional: true
+                },
+            ]
+        );
+        assert!(parse_params("...ids: string[]").is_err());
+        assert!(parse_params("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_placement() {
+        let get = method(
+            "list",
+            "team: string, limit?: number",
+            "Promise<User[]>",
+            Some(r#"{ method: "get", path: "/teams/:team/users" }"#),
+        );
+        let list = endpoint(&get).unwrap();
+        assert_eq!(list.route.method, "GET");
+        assert_eq!(list.payload, "User[]");
+        let placements = list.params.iter().map(|(_, p)| *p).collect::<Vec<_>>();
+        assert_eq!(placements, [Placement::Path, Placement::Query]);
+
+        let post = method(
+            "save",
+            "user: User",
+            "Promise<void>",
+            Some(r#"{ path: "/users" }"#),
+        );
+        let save = endpoint(&post).unwrap();
+        assert_eq!(save.route.method, "POST");
+        assert_eq!(save.params[0].1, Placement::Body);
+    }
+
+    #[test]
+    fn test_endpoint_errors() {
+        let no_route = method("get", "", "Promise<void>", None);
+        assert!(endpoint(&no_route).unwrap_err().contains("needs a route"));
+
+        let sync = method("get", "", "User", Some(r#"{ path: "/u" }"#));
+        assert!(
+            endpoint(&sync)
+                .unwrap_err()
+                .contains("must return a Promise")
+        );
+
+        let missing = method("get", "", "Promise<User>", Some(r#"{ path: "/u/:id" }"#));
+        assert!(
+            endpoint(&missing)
+                .unwrap_err()
+                .contains("no parameter 'id'")
+        );
+
+        let object_query = method(
+            "find",
+            "filter: Filter",
+            "Promise<User[]>",
+            Some(r#"{ method: "GET", path: "/users" }"#),
+        );
+        assert!(
+            endpoint(&object_query)
+                .unwrap_err()
+                .contains("sent in the URL")
+        );
+    }
+
+    #[test]
+    fn test_codec() {
+        let mut codec = Codec::default();
+        assert_eq!(codec.encode("string[]", "ids"), "ids");
+        assert_eq!(
+            codec.encode("Date[]", "days"),
+            "days.map((item) => item.toISOString())"
+        );
+        assert_eq!(
+            codec.encode("User | undefined", "user"),
+            "(user == null ? user : userSerializeWithContext(user, __mf_SerializeContext.create()))"
+        );
+        assert!(codec.uses_serialize_context);
+
+        assert_eq!(
+            codec.decode("number", "data", "\"result\""),
+            "__mf_expect<number>(data, \"number\", \"result\")"
+        );
+        assert_eq!(
+            codec.decode("User[]", "data", "\"result\""),
+            "__mf_expectArray(data, \"result\").map((item) => __mf_unwrap(userDeserialize(item), \"result\"))"
+        );
+        assert_eq!(
+            codec.helpers.iter().copied().collect::<Vec<_>>(),
+            ["expect", "expectArray", "unwrap"]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! # Mock Macro Implementation
+//!
+//! The `Mock` macro generates a test double class for an interface. Every
+//! method becomes a `MockFunction` from `macroforge/mock` that records its
+//! calls and can be stubbed, all typed from the method's own signature.
+//!
+//! ## Generated Output
+//!
+//! For an interface `UserService`, a class `UserServiceMock implements
+//! UserService` with:
+//!
+//! | Member | Description |
+//! |--------|-------------|
+//! | One `MockFunction` per method | `calls()`, `returns()`, `resolves()`, `rejects()`, `reset()`, ... |
+//! | One property per data field | Set from the constructor's `init` argument |
+//! | `mockReset()` | Resets every method |
+//!
+//! Function-typed properties (`onChange: (value: string) => void`) are
+//! mocked like methods.
+//!
+//! ## Unstubbed Calls
+//!
+//! Mocks are strict: calling a method that returns a value without stubbing
+//! it throws a `MockError`. Methods returning `void` return `undefined` and
+//! methods returning `Promise<void>` resolve, so they need no stub.
+//!
+//! ## Limitations
+//!
+//! Only the interface's own members are mocked. Members inherited through
+//! `extends` are not visible to the macro, so a warning is reported and the
+//! mock won't satisfy the interface until they are added by hand.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Mock) */
+//! interface UserService {
+//!     getUser(id: string): Promise<User>;
+//!     save(user: User): Promise<void>;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export class UserServiceMock implements UserService {
+//!     readonly getUser = __mf_mockFunction<UserService["getUser"]>("UserService.getUser", "throw");
+//!     readonly save = __mf_mockFunction<UserService["save"]>("UserService.save", "resolve");
+//!
+//!     mockReset(): void {
+//!         this.getUser.reset();
+//!         this.save.reset();
+//!     }
+//! }
+//! ```
+//!
+//! In a test:
+//!
+//! ```typescript
+//! const users = new UserServiceMock();
+//! users.getUser.resolves({ id: "1", name: "Ada" });
+//! await handler(users);
+//! users.getUser.calls(); // [["1"]]
+//! ```
+
+use std::collections::BTreeSet;
+
+use crate::builtin::derive_common::{TypeParams, strip_parens};
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::DiagnosticCollector;
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// A mocked method or function-typed property.
+struct MockedMethod<'a> {
+    name: &'a str,
+    return_type: &'a str,
+    optional: bool,
+}
+
+/// A plain data property.
+struct DataField<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    readonly: bool,
+}
+
+/// Generic parameters and `extends` clause of an interface, from its source.
+#[derive(Debug, Default, PartialEq)]
+struct InterfaceHeader {
+    /// Type parameters as declared, e.g. `<T extends Entity = User>`.
+    type_params: Option<String>,
+    /// Type parameter names, e.g. `["T"]`.
+    type_param_names: Vec<String>,
+    /// The `extends` clause, e.g. `Base, Disposable`.
+    extends: Option<String>,
+}
+
+fn parse_header(source: &str, name: &str) -> InterfaceHeader {
+    let (type_params, rest) = TypeParams::declared(source, name);
+    InterfaceHeader {
+        type_params: (!type_params.is_empty()).then(|| type_params.decl()),
+        type_param_names: type_params
+            .names()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        extends: rest
+            .split('{')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .strip_prefix("extends")
+            .map(|clause| clause.trim().to_string())
+            .filter(|clause| !clause.is_empty()),
+    }
+}
+
+/// The return type of a function type such as `(id: string) => User`.
+fn arrow_return_type(ts_type: &str) -> Option<&str> {
+    let ts_type = strip_parens(ts_type);
+    if !ts_type.starts_with('(') && !ts_type.starts_with('<') {
+        return None;
+    }
+    let mut depth = 0i32;
+    let mut previous = ' ';
+    for (i, c) in ts_type.char_indices() {
+        match c {
+            '>' if previous == '=' && depth == 0 => return Some(ts_type[i + 1..].trim()),
+            '>' if previous == '=' => {}
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// What an unstubbed call returns, as a `MockFallback`.
+fn fallback(return_type: &str) -> &'static str {
+    match return_type.replace(' ', "").as_str() {
+        "void" | "undefined" => "undefined",
+        "Promise<void>" | "Promise<undefined>" => "resolve",
+        _ => "throw",
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn property_key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap_or_default()
+    }
+}
+
+fn member_access(name: &str) -> String {
+    if is_identifier(name) {
+        format!(".{name}")
+    } else {
+        format!("[{}]", property_key(name))
+    }
+}
+
+fn generate_mock(
+    interface_name: &str,
+    header: &InterfaceHeader,
+    fields: &[DataField<'_>],
+    methods: &[MockedMethod<'_>],
+) -> String {
+    let interface_type = if header.type_param_names.is_empty() {
+        interface_name.to_string()
+    } else {
+        format!("{interface_name}<{}>", header.type_param_names.join(", "))
+    };
+    let mut members = Vec::new();
+
+    for field in fields {
+        members.push(format!(
+            "    {}{}{}: {};",
+            if field.readonly { "readonly " } else { "" },
+            property_key(field.name),
+            if field.optional { "?" } else { "!" },
+            field.ts_type
+        ));
+    }
+
+    let mut reset_name = "mockReset".to_string();
+    let taken = fields
+        .iter()
+        .map(|f| f.name)
+        .chain(methods.iter().map(|m| m.name))
+        .collect::<BTreeSet<_>>();
+    while taken.contains(reset_name.as_str()) {
+        reset_name.insert(0, '_');
+    }
+
+    for method in methods {
+        let member = format!(
+            "{interface_type}[{}]",
+            serde_json::to_string(method.name).unwrap_or_default()
+        );
+        let function_type = if method.optional {
+            format!("NonNullable<{member}>")
+        } else {
+            member
+        };
+        members.push(format!(
+            "    readonly {} = __mf_mockFunction<{function_type}>({}, \"{}\");",
+            property_key(method.name),
+            serde_json::to_string(&format!("{interface_name}.{}", method.name)).unwrap_or_default(),
+            fallback(method.return_type)
+        ));
+    }
+
+    if !fields.is_empty() {
+        let names = fields
+            .iter()
+            .map(|f| serde_json::to_string(f.name).unwrap_or_default())
+            .collect::<Vec<_>>();
+        members.push(format!(
+            "\n    constructor(init: Partial<Pick<{interface_type}, {}>> = {{}}) {{\n        Object.assign(this, init);\n    }}",
+            names.join(" | ")
+        ));
+    }
+
+    let resets = methods
+        .iter()
+        .map(|m| format!("        this{}.reset();\n", member_access(m.name)))
+        .collect::<String>();
+    members.push(format!(
+        "\n    /** Forgets recorded calls and stubs of every method. */\n    {reset_name}(): void {{\n{resets}    }}"
+    ));
+
+    format!(
+        "export class {interface_name}Mock{} implements {interface_type} {{\n{}\n}}",
+        header.type_params.as_deref().unwrap_or_default(),
+        members.join("\n")
+    )
+}
+
+#[ts_macro_derive(
+    Mock,
+    description = "Generates a recording, stubbable test double class for an interface"
+)]
+pub fn derive_mock_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let interface_name = input.name();
+
+    let Data::Interface(interface) = &input.data else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(Mock) can only be used on interfaces",
+        ));
+    };
+
+    let mut fields = Vec::new();
+    let mut methods = Vec::new();
+    for field in interface.fields() {
+        match arrow_return_type(&field.ts_type) {
+            Some(return_type) => methods.push(MockedMethod {
+                name: &field.name,
+                return_type,
+                optional: field.optional,
+            }),
+            None => fields.push(DataField {
+                name: &field.name,
+                ts_type: &field.ts_type,
+                optional: field.optional,
+                readonly: field.readonly,
+            }),
+        }
+    }
+    let mut seen = BTreeSet::new();
+    for method in interface.methods() {
+        // Overloads share one mock typed with all of their signatures
+        if seen.insert(method.name.as_str()) {
+            methods.push(MockedMethod {
+                name: &method.name,
+                return_type: &method.return_type_src,
+                optional: method.optional,
+            });
+        }
+    }
+
+    let header = parse_header(&input.context.target_source, interface_name);
+    let mut output =
+        TsStream::from_string(generate_mock(interface_name, &header, &fields, &methods));
+    output.add_aliased_import("mockFunction", "macroforge/mock");
+    if let Some(extends) = &header.extends {
+        let mut diagnostics = DiagnosticCollector::new();
+        diagnostics.warning(
+            input.decorator_span(),
+            format!(
+                "@derive(Mock) only mocks the members declared in '{interface_name}'; members inherited from {extends} must be added to {interface_name}Mock by hand"
+            ),
+        );
+        output.add_diagnostics(diagnostics.into_vec());
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("interface UserService {\n}", "UserService"),
+            InterfaceHeader::default()
+        );
+
+        let header = parse_header(
+            "interface Repo<T extends { id: string }, K = keyof T> extends Base<T>, Disposable {}",
+            "Repo",
+        );
+        assert_eq!(
+            header.type_params.as_deref(),
+            Some("<T extends { id: string }, K = keyof T>")
+        );
+        assert_eq!(header.type_param_names, ["T", "K"]);
+        assert_eq!(header.extends.as_deref(), Some("Base<T>, Disposable"));
+    }
+
+    #[test]
+    fn test_arrow_return_type() {
+        assert_eq!(arrow_return_type("(id: string) => User"), Some("User"));
+        assert_eq!(
+            arrow_return_type("(cb: () => void) => Promise<void>"),
+            Some("Promise<void>")
+        );
+        assert_eq!(arrow_return_type("<T>(value: T) => T"), Some("T"));
+        assert_eq!(arrow_return_type("((value: string) => void)"), Some("void"));
+        assert_eq!(arrow_return_type("Map<string, number>"), None);
+        assert_eq!(arrow_return_type("(string | number)[]"), None);
+    }
+
+    #[test]
+    fn test_fallback() {
+        assert_eq!(fallback("void"), "undefined");
+        assert_eq!(fallback("Promise< void >"), "resolve");
+        assert_eq!(fallback("Promise<User>"), "throw");
+    }
+
+    #[test]
+    fn test_reset_name_avoids_members() {
+        let code = generate_mock(
+            "Store",
+            &InterfaceHeader::default(),
+            &[],
+            &[MockedMethod {
+                name: "mockReset",
+                return_type: "void",
+                optional: false,
+            }],
+        );
+        assert!(code.contains("_mockReset(): void"));
+        assert!(code.contains("this.mockReset.reset();"));
+    }
+}
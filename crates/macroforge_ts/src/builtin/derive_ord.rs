@@ -50,6 +50,19 @@
 //! The `@ord` decorator supports:
 //!
 //! - `skip` - Exclude the field from ordering comparison
+//! - `priority = n` - Compare this field before others; fields with a priority
+//!   come first (lowest value first), the rest follow in declaration order
+//! - `reverse` - Invert the ordering of this field (descending)
+//! - `nulls = "first" | "last"` - Sort `null`/`undefined` before or after all
+//!   other values, regardless of `reverse`
+//! - `by = "fn"` - Compare the keys returned by `fn(value)` instead of the field itself
+//! - `locale = "de"` / `collator = { ... }` - Compare strings with an
+//!   `Intl.Collator` built from this locale and options
+//!
+//! `locale` and `collator` can also be set on a container-level `@ord` decorator
+//! to apply to every string field. Each collator is created on first use and
+//! cached in a module-level `var`, so the compare function also works when it
+//! is called before the module reaches the generated code.
 //!
 //! ## Example
 //!
@@ -69,12 +82,12 @@
 //!     major: number;
 //!     minor: number;
 //!     patch: number;
-//! 
+//!
 //!     static compareTo(a: Version, b: Version): number {
 //!         return versionCompare(a, b);
 //!     }
 //! }
-//! 
+//!
 //! export function versionCompare(a: Version, b: Version): number {
 //!     if (a === b) return 0;
 //!     const cmp0 = a.major < b.major ? -1 : a.major > b.major ? 1 : 0;
@@ -87,6 +100,27 @@
 //! }
 //! ```
 //!
+//! Sorting by a different key order without reordering the class:
+//!
+//! ```typescript
+//! /**
+//!  * @derive(Ord)
+//!  * @ord({ collator: { sensitivity: "base" } })
+//!  */
+//! class Task {
+//!     title: string;
+//!
+//!     /** @ord({ priority: 1, reverse: true }) */
+//!     urgency: number;
+//!
+//!     /** @ord({ priority: 2, nulls: "last" }) */
+//!     dueDate: Date | null;
+//! }
+//! ```
+//!
+//! compares `urgency` (descending), then `dueDate` (missing dates last), then
+//! `title` (case-insensitively).
+//!
 //! ## Ord vs PartialOrd
 //!
 //! - Use **Ord** when all values are comparable (total ordering)
@@ -94,24 +128,138 @@
 
 use convert_case::{Case, Casing};
 
-use crate::builtin::derive_common::{CompareFieldOptions, is_numeric_type, is_primitive_type};
+use crate::builtin::derive_common::{
+    NullsOrder, OrdFieldOptions, is_numeric_type, is_primitive_type, sort_by_priority,
+    strip_nullable,
+};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, SpanIR};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
 /// Contains field information needed for ordering comparison generation.
 ///
 /// Each field that participates in ordering is represented by this struct,
 /// which captures both the field name (for access) and its TypeScript type
-/// (to select the appropriate comparison strategy).
-struct OrdField {
+/// (to select the appropriate comparison strategy). Shared with `PartialOrd`,
+/// which reads the same `@ord` options.
+#[derive(Default)]
+pub struct OrdField {
     /// The field name as it appears in the source TypeScript class.
     /// Used to generate property access expressions like `this.name`.
-    name: String,
+    pub name: String,
 
     /// The TypeScript type annotation for this field.
     /// Used to determine which comparison strategy to apply
     /// (e.g., numeric comparison, string localeCompare, recursive compareTo).
-    ts_type: String,
+    pub ts_type: String,
+
+    /// Options from the field's `@ord` decorator, with container-level
+    /// collation defaults applied.
+    pub options: OrdFieldOptions,
+
+    /// Name of the module-level `Intl.Collator` cache used for this
+    /// field's string comparisons, if it has collation options.
+    pub collator: Option<String>,
+}
+
+impl OrdField {
+    /// Returns an expression comparing the strings `x` and `y`, through the
+    /// field's collator (created on first use) or `localeCompare()`.
+    pub fn string_compare(&self, x: &str, y: &str) -> String {
+        match &self.collator {
+            Some(collator) => format!(
+                "({collator} ??= {}).compare({x}, {y})",
+                self.options.collator_expr()
+            ),
+            None => format!("{x}.localeCompare({y})"),
+        }
+    }
+}
+
+/// Collects the fields that participate in ordering, sorted by `priority`.
+///
+/// Collator caches are named after `fn_name` so that `Ord` and `PartialOrd`
+/// on the same type don't collide.
+pub fn collect_ord_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, &'a [DecoratorIR], SpanIR)>,
+    container: &OrdFieldOptions,
+    fn_name: &str,
+) -> Result<Vec<OrdField>, MacroforgeError> {
+    let mut ord_fields = Vec::new();
+    for (name, ts_type, decorators, span) in fields {
+        let mut options = OrdFieldOptions::from_decorators(decorators, "ord")
+            .map_err(|msg| MacroforgeError::new(span, msg))?;
+        if options.skip {
+            continue;
+        }
+        options.inherit(container);
+        ord_fields.push(OrdField {
+            name: name.to_string(),
+            ts_type: ts_type.to_string(),
+            options,
+            collator: None,
+        });
+    }
+
+    sort_by_priority(&mut ord_fields, |f| f.options.priority);
+
+    for (i, field) in ord_fields.iter_mut().enumerate() {
+        if field.options.uses_collator() {
+            field.collator = Some(format!("__{fn_name}Collator{i}"));
+        }
+    }
+
+    Ok(ord_fields)
+}
+
+/// Generates the module-level `Intl.Collator` caches used by `fields`.
+///
+/// They are `var`s so that they are hoisted along with the compare function,
+/// which fills them on first use.
+pub fn generate_collators(fields: &[OrdField]) -> String {
+    fields
+        .iter()
+        .filter_map(|f| {
+            f.collator
+                .as_ref()
+                .map(|name| format!("var {name}: Intl.Collator | undefined;\n"))
+        })
+        .collect()
+}
+
+/// Generates the comparison statements for all fields.
+///
+/// Each field produces `const cmpN = ...; if (cmpN !== 0) return cmpN;`, preceded
+/// by a null-placement guard when `nulls` is set. `reverse` negates the returned
+/// result so that null placement is unaffected by it.
+fn generate_compare_body(fields: &[OrdField], self_var: &str, other_var: &str) -> String {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let var_name = format!("cmp{}", i);
+            let sign = if f.options.reverse { "-" } else { "" };
+            let mut compare = generate_field_compare_for_interface(f, self_var, other_var);
+            let mut stmts = String::new();
+            if let Some(nulls) = f.options.nulls {
+                let a = format!("{self_var}.{}", f.name);
+                let b = format!("{other_var}.{}", f.name);
+                let (a_null, b_null) = match nulls {
+                    NullsOrder::First => (-1, 1),
+                    NullsOrder::Last => (1, -1),
+                };
+                stmts.push_str(&format!(
+                    "if (({a} == null) !== ({b} == null)) return {a} == null ? {a_null} : {b_null};\n"
+                ));
+                compare = format!("{a} == null ? 0 : {compare}");
+            }
+            stmts.push_str(&format!(
+                "const {var_name} = {compare};\nif ({var_name} !== 0) return {sign}{var_name};"
+            ));
+            stmts
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Generates JavaScript code that compares fields for ordering.
@@ -143,23 +291,40 @@ struct OrdField {
 /// - **Arrays**: Lexicographic comparison with fallback to 0 for incomparable elements
 /// - **Date**: Timestamp comparison via `getTime()`
 /// - **Objects**: Calls `compareTo()` if available (with `?? 0` fallback), else 0
+///
+/// A `by` key extractor replaces the type-based strategy with a comparison of the
+/// extracted keys, and a collator replaces `localeCompare()` for strings.
 fn generate_field_compare_for_interface(
     field: &OrdField,
     self_var: &str,
     other_var: &str,
 ) -> String {
     let field_name = &field.name;
-    let ts_type = &field.ts_type;
+    // With explicit null placement, nulls are handled before this comparison runs
+    let ts_type = &if field.options.nulls.is_some() {
+        strip_nullable(&field.ts_type)
+    } else {
+        field.ts_type.clone()
+    };
 
-    if is_numeric_type(ts_type) {
+    if let Some(by) = &field.options.by {
+        let key_compare = field.string_compare("ka", "kb");
+        format!(
+            "((ka: any, kb: any) => typeof ka === 'string' && typeof kb === 'string' \
+                ? ((cmp: number) => cmp < 0 ? -1 : cmp > 0 ? 1 : 0)({key_compare}) \
+                : ka < kb ? -1 : ka > kb ? 1 : 0)(({by})({self_var}.{field_name}), ({by})({other_var}.{field_name})))"
+        )
+    } else if is_numeric_type(ts_type) {
         format!(
             "({self_var}.{field_name} < {other_var}.{field_name} ? -1 : \
              {self_var}.{field_name} > {other_var}.{field_name} ? 1 : 0)"
         )
     } else if ts_type == "string" {
-        format!(
-            "((cmp => cmp < 0 ? -1 : cmp > 0 ? 1 : 0)({self_var}.{field_name}.localeCompare({other_var}.{field_name})))"
-        )
+        let compare = field.string_compare(
+            &format!("{self_var}.{field_name}"),
+            &format!("{other_var}.{field_name}"),
+        );
+        format!("((cmp => cmp < 0 ? -1 : cmp > 0 ? 1 : 0)({compare}))")
     } else if ts_type == "boolean" {
         format!(
             "({self_var}.{field_name} === {other_var}.{field_name} ? 0 : \
@@ -202,56 +367,46 @@ fn generate_field_compare_for_interface(
 #[ts_macro_derive(
     Ord,
     description = "Generates a compareTo() method for total ordering (returns -1, 0, or 1, never null)",
-    attributes(ord)
+    attributes((ord, "Configure ordering. Options: skip, priority, reverse, nulls (\"first\" | \"last\"), by (key extractor), locale and collator (Intl.Collator options)"))
 )]
 pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let container_opts = OrdFieldOptions::from_decorators(&container_decorators, "ord")
+        .map_err(|msg| MacroforgeError::new(input.decorator_span(), msg))?;
+
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
 
+            // Generate function name (always prefix style)
+            let fn_name = format!("{}Compare", class_name.to_case(Case::Camel));
+
             // Collect fields for comparison
-            let ord_fields: Vec<OrdField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = collect_ord_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &fn_name,
+            )?;
 
             let has_fields = !ord_fields.is_empty();
-
-            // Generate function name (always prefix style)
-            let fn_name = format!("{}Compare", class_name.to_case(Case::Camel));
+            let collators = generate_collators(&ord_fields);
 
             // Build comparison logic using a and b parameters
-            let compare_body = if has_fields {
-                ord_fields
-                    .iter()
-                    .enumerate()
-                    .map(|(i, f)| {
-                        let var_name = format!("cmp{}", i);
-                        format!(
-                            "const {var_name} = {};\n                    if ({var_name} !== 0) return {var_name};",
-                            generate_field_compare_for_interface(f, "a", "b")
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                    ")
-            } else {
-                String::new()
-            };
+            let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
             // Generate standalone function with two parameters
             let standalone = ts_template! {
+                @{collators}
                 export function @{fn_name}(a: @{class_name}, b: @{class_name}): number {
                     if (a === b) return 0;
                     {#if has_fields}
@@ -297,44 +452,27 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
+            let fn_name = format!("{}Compare", interface_name.to_case(Case::Camel));
 
-            let ord_fields: Vec<OrdField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = collect_ord_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &fn_name,
+            )?;
 
             let has_fields = !ord_fields.is_empty();
-
-            let compare_body = if has_fields {
-                ord_fields
-                    .iter()
-                    .enumerate()
-                    .map(|(i, f)| {
-                        let var_name = format!("cmp{}", i);
-                        format!(
-                            "const {var_name} = {};\n                        if ({var_name} !== 0) return {var_name};",
-                            generate_field_compare_for_interface(f, "a", "b")
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                        ")
-            } else {
-                String::new()
-            };
-
-            let fn_name = format!("{}Compare", interface_name.to_case(Case::Camel));
+            let collators = generate_collators(&ord_fields);
+            let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
             Ok(ts_template! {
+                @{collators}
                 export function @{fn_name}(a: @{interface_name}, b: @{interface_name}): number {
                     if (a === b) return 0;
                     {#if has_fields}
@@ -348,44 +486,27 @@ pub fn derive_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError
             let type_name = input.name();
 
             if type_alias.is_object() {
-                let ord_fields: Vec<OrdField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(OrdField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
-
-                let has_fields = !ord_fields.is_empty();
+                let fn_name = format!("{}Compare", type_name.to_case(Case::Camel));
 
-                let compare_body = if has_fields {
-                    ord_fields
-                        .iter()
-                        .enumerate()
-                        .map(|(i, f)| {
-                            let var_name = format!("cmp{}", i);
-                            format!(
-                                "const {var_name} = {};\n                        if ({var_name} !== 0) return {var_name};",
-                                generate_field_compare_for_interface(f, "a", "b")
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n                        ")
-                } else {
-                    String::new()
-                };
+                let ord_fields = collect_ord_fields(
+                    type_alias.as_object().unwrap().iter().map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.decorators.as_slice(),
+                            f.span,
+                        )
+                    }),
+                    &container_opts,
+                    &fn_name,
+                )?;
 
-                let fn_name = format!("{}Compare", type_name.to_case(Case::Camel));
+                let has_fields = !ord_fields.is_empty();
+                let collators = generate_collators(&ord_fields);
+                let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
                 Ok(ts_template! {
+                    @{collators}
                     export function @{fn_name}(a: @{type_name}, b: @{type_name}): number {
                         if (a === b) return 0;
                         {#if has_fields}
@@ -428,6 +549,7 @@ mod tests {
         let ord_fields: Vec<OrdField> = vec![OrdField {
             name: "id".to_string(),
             ts_type: "number".to_string(),
+            ..Default::default()
         }];
         let has_fields = !ord_fields.is_empty();
 
@@ -480,6 +602,7 @@ mod tests {
         let field = OrdField {
             name: "id".to_string(),
             ts_type: "number".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b");
         assert!(result.contains("a.id < b.id"));
@@ -492,6 +615,7 @@ mod tests {
         let field = OrdField {
             name: "name".to_string(),
             ts_type: "string".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b");
         assert!(result.contains("localeCompare"));
@@ -505,10 +629,110 @@ mod tests {
         let field = OrdField {
             name: "user".to_string(),
             ts_type: "User".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b");
         assert!(result.contains("compareTo"));
         // Should fallback to 0 instead of null
         assert!(result.contains("?? 0"));
     }
+
+    fn ord_decorator(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "ord".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    #[test]
+    fn test_fields_sorted_by_priority() {
+        let decorators = [
+            vec![],
+            vec![ord_decorator("{ priority: 2 }")],
+            vec![ord_decorator("{ priority: 1 }")],
+            vec![ord_decorator("skip")],
+        ];
+        let names = ["title", "dueDate", "urgency", "cache"];
+        let fields = collect_ord_fields(
+            names
+                .iter()
+                .zip(decorators.iter())
+                .map(|(name, decs)| (*name, "number", decs.as_slice(), SpanIR::new(0, 0))),
+            &OrdFieldOptions::default(),
+            "taskCompare",
+        )
+        .unwrap();
+
+        let order: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(order, vec!["urgency", "dueDate", "title"]);
+    }
+
+    #[test]
+    fn test_invalid_nulls_is_error() {
+        let decorators = vec![ord_decorator(r#"{ nulls: "middle" }"#)];
+        let result = collect_ord_fields(
+            [("value", "number", decorators.as_slice(), SpanIR::new(0, 0))],
+            &OrdFieldOptions::default(),
+            "taskCompare",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reverse_and_nulls_body() {
+        let field = OrdField {
+            name: "dueDate".to_string(),
+            ts_type: "Date | null".to_string(),
+            options: OrdFieldOptions {
+                reverse: true,
+                nulls: Some(NullsOrder::Last),
+                ..Default::default()
+            },
+            collator: None,
+        };
+        let body = generate_compare_body(&[field], "a", "b");
+        assert!(body.contains(
+            "if ((a.dueDate == null) !== (b.dueDate == null)) return a.dueDate == null ? 1 : -1;"
+        ));
+        assert!(body.contains("const cmp0 = a.dueDate == null ? 0 :"));
+        assert!(body.contains("if (cmp0 !== 0) return -cmp0;"));
+    }
+
+    #[test]
+    fn test_field_compare_by_and_collator() {
+        let container = OrdFieldOptions::from_decorators(
+            &[ord_decorator(
+                r#"{ locale: "de", collator: { sensitivity: "base" } }"#,
+            )],
+            "ord",
+        )
+        .unwrap();
+        let decorators = vec![ord_decorator(r#"{ by: "lastName" }"#)];
+        let fields = collect_ord_fields(
+            [
+                ("name", "string", &[][..], SpanIR::new(0, 0)),
+                ("person", "Person", decorators.as_slice(), SpanIR::new(0, 0)),
+            ],
+            &container,
+            "userCompare",
+        )
+        .unwrap();
+
+        assert_eq!(
+            generate_collators(&fields[..1]),
+            "var __userCompareCollator0: Intl.Collator | undefined;\n"
+        );
+        let name_cmp = generate_field_compare_for_interface(&fields[0], "a", "b");
+        assert!(name_cmp.contains(
+            "(__userCompareCollator0 ??= new Intl.Collator(\"de\", { sensitivity: \"base\" })).compare(a.name, b.name)"
+        ));
+
+        let by_cmp = generate_field_compare_for_interface(&fields[1], "a", "b");
+        assert!(by_cmp.contains("(lastName)(a.person)"));
+        assert!(by_cmp.contains("(lastName)(b.person)"));
+        assert!(by_cmp.contains(").compare(ka, kb)"));
+        assert!(by_cmp.contains("(__userCompareCollator1 ??= new Intl.Collator("));
+    }
 }
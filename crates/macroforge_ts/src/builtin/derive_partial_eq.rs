@@ -36,6 +36,15 @@
 //! The `@partialEq` decorator supports:
 //!
 //! - `skip` - Exclude the field from equality comparison
+//! - `epsilon = 1e-9` - Treat `number` values within this absolute tolerance as equal
+//! - `nanEqual` - Treat `NaN` as equal to `NaN` (`number` fields)
+//!
+//! Both also apply to `number | null` and `number | undefined` fields, where
+//! `null` and `undefined` still only equal themselves.
+//! - `with = "fn"` - Compare with a custom function `(a, b) => boolean`
+//!
+//! `epsilon` and `nanEqual` can also be set on a container-level `@partialEq`
+//! decorator, where they apply to every `number` field.
 //!
 //! ## Example
 //!
@@ -56,23 +65,23 @@
 //! class User {
 //!     id: number;
 //!     name: string;
-//! 
+//!
 //!     cachedScore: number;
-//! 
+//!
 //!     static equals(a: User, b: User): boolean {
 //!         return userEquals(a, b);
 //!     }
-//! 
+//!
 //!     static hashCode(value: User): number {
 //!         return userHashCode(value);
 //!     }
 //! }
-//! 
+//!
 //! export function userEquals(a: User, b: User): boolean {
 //!     if (a === b) return true;
 //!     return a.id === b.id && a.name === b.name;
 //! }
-//! 
+//!
 //! export function userHashCode(value: User): number {
 //!     let hash = 17;
 //!     hash =
@@ -100,6 +109,11 @@
 //! - **Symmetry**: `a.equals(b)` implies `b.equals(a)`
 //! - **Hash consistency**: Equal objects must have equal hash codes
 //!
+//! `Hash` reads the `@partialEq` options: fields compared with `epsilon` or `with`
+//! are left out of `hashCode`, since no per-field hash can agree with them. `Hash`
+//! also warns when a combination of options can still break the contract, e.g. a
+//! field skipped by `PartialEq` but still hashed.
+//!
 //! To maintain the hash contract, skip the same fields in both `PartialEq` and `Hash`:
 //!
 //! ```typescript
@@ -119,23 +133,23 @@
 //! class User {
 //!     id: number;
 //!     name: string;
-//! 
+//!
 //!     cachedScore: number;
-//! 
+//!
 //!     static equals(a: User, b: User): boolean {
 //!         return userEquals(a, b);
 //!     }
-//! 
+//!
 //!     static hashCode(value: User): number {
 //!         return userHashCode(value);
 //!     }
 //! }
-//! 
+//!
 //! export function userEquals(a: User, b: User): boolean {
 //!     if (a === b) return true;
 //!     return a.id === b.id && a.name === b.name;
 //! }
-//! 
+//!
 //! export function userHashCode(value: User): number {
 //!     let hash = 17;
 //!     hash =
@@ -157,8 +171,9 @@
 
 use convert_case::{Case, Casing};
 
-use crate::builtin::derive_common::{CompareFieldOptions, is_primitive_type};
+use crate::builtin::derive_common::{EqFieldOptions, is_primitive_type};
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
 /// Contains field information needed for equality comparison generation.
@@ -263,47 +278,128 @@ pub fn generate_field_equality_for_interface(
     }
 }
 
+/// Generates the equality expression for a field, honoring its `@partialEq` options.
+///
+/// `with` replaces the comparison entirely; `epsilon` and `nanEqual` relax the
+/// strict equality used for `number` fields. Everything else falls back to
+/// [`generate_field_equality_for_interface`].
+pub(crate) fn generate_field_equality_with_options(
+    field: &EqField,
+    opts: &EqFieldOptions,
+    self_var: &str,
+    other_var: &str,
+) -> String {
+    let a = format!("{self_var}.{}", field.name);
+    let b = format!("{other_var}.{}", field.name);
+
+    if let Some(with) = &opts.with {
+        return format!("({with})({a}, {b})");
+    }
+
+    if EqFieldOptions::applies_to(&field.ts_type) && (opts.epsilon.is_some() || opts.nan_equal) {
+        let mut relaxed = Vec::new();
+        if let Some(epsilon) = opts.epsilon {
+            relaxed.push(format!("Math.abs({a} - {b}) <= {epsilon}"));
+        }
+        if opts.nan_equal {
+            relaxed.push(format!("(Number.isNaN({a}) && Number.isNaN({b}))"));
+        }
+        let relaxed = relaxed.join(" || ");
+        // `null`/`undefined` only equal themselves, so the tolerance only
+        // applies once both values are numbers
+        return if field.ts_type == "number" {
+            format!("({a} === {b} || {relaxed})")
+        } else {
+            format!("({a} === {b} || ({a} != null && {b} != null && ({relaxed})))")
+        };
+    }
+
+    generate_field_equality_for_interface(field, self_var, other_var)
+}
+
+/// Collects the fields that participate in equality along with their options.
+///
+/// Container-level defaults are applied to each field. Numeric options set
+/// directly on a non-`number` field have no effect and produce a warning.
+pub(crate) fn collect_eq_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str, &'a [DecoratorIR], SpanIR)>,
+    container: &EqFieldOptions,
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<(EqField, EqFieldOptions)> {
+    let mut eq_fields = Vec::new();
+    for (name, ts_type, decorators, span) in fields {
+        let mut opts = EqFieldOptions::from_decorators(decorators);
+        if opts.skip {
+            continue;
+        }
+        if !EqFieldOptions::applies_to(ts_type) && (opts.epsilon.is_some() || opts.nan_equal) {
+            diagnostics.warning(
+                span,
+                format!(
+                    "@partialEq epsilon/nanEqual only apply to `number` fields; they have no effect on '{name}: {ts_type}'"
+                ),
+            );
+        }
+        opts.inherit(container);
+        eq_fields.push((
+            EqField {
+                name: name.to_string(),
+                ts_type: ts_type.to_string(),
+            },
+            opts,
+        ));
+    }
+    eq_fields
+}
+
+/// Joins the per-field equality checks with `&&` (or `true` if there are none).
+fn generate_comparison(eq_fields: &[(EqField, EqFieldOptions)]) -> String {
+    if eq_fields.is_empty() {
+        return "true".to_string();
+    }
+    eq_fields
+        .iter()
+        .map(|(f, opts)| generate_field_equality_with_options(f, opts, "a", "b"))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
 #[ts_macro_derive(
     PartialEq,
     description = "Generates an equals() method for field-by-field comparison",
-    attributes(partialEq)
+    attributes((partialEq, "Configure equality. Options: skip, epsilon (number tolerance), nanEqual, with (custom (a, b) => boolean)"))
 )]
 pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
-    match &input.data {
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let container_opts = EqFieldOptions::from_decorators(&container_decorators);
+    let mut diagnostics = DiagnosticCollector::new();
+
+    let mut output = match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
 
             // Collect fields that should be included in equality comparison
-            let eq_fields: Vec<EqField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(EqField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let eq_fields = collect_eq_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &mut diagnostics,
+            );
 
             // Generate function name (always prefix style)
             let fn_name = format!("{}Equals", class_name.to_case(Case::Camel));
 
             // Build comparison expression using a and b parameters
-            let comparison = if eq_fields.is_empty() {
-                "true".to_string()
-            } else {
-                eq_fields
-                    .iter()
-                    .map(|f| generate_field_equality_for_interface(f, "a", "b"))
-                    .collect::<Vec<_>>()
-                    .join(" && ")
-            };
+            let comparison = generate_comparison(&eq_fields);
 
             // Generate standalone function with two parameters
             let standalone = ts_template! {
@@ -327,103 +423,81 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
             combined.runtime_patches = standalone.runtime_patches;
             combined.runtime_patches.extend(class_body.runtime_patches);
 
-            Ok(combined)
+            combined
         }
         Data::Enum(_) => {
             // Enums: direct comparison with ===
             let enum_name = input.name();
             let fn_name = format!("{}Equals", enum_name.to_case(Case::Camel));
 
-            Ok(ts_template! {
+            ts_template! {
                 export function @{fn_name}(a: @{enum_name}, b: @{enum_name}): boolean {
                     return a === b;
                 }
-            })
+            }
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
 
             // Collect fields for comparison
-            let eq_fields: Vec<EqField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(EqField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let eq_fields = collect_eq_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &mut diagnostics,
+            );
 
             // Build comparison expression
-            let comparison = if eq_fields.is_empty() {
-                "true".to_string()
-            } else {
-                eq_fields
-                    .iter()
-                    .map(|f| generate_field_equality_for_interface(f, "a", "b"))
-                    .collect::<Vec<_>>()
-                    .join(" && ")
-            };
+            let comparison = generate_comparison(&eq_fields);
 
             let fn_name = format!("{}Equals", interface_name.to_case(Case::Camel));
 
-            Ok(ts_template! {
+            ts_template! {
                 export function @{fn_name}(a: @{interface_name}, b: @{interface_name}): boolean {
                     if (a === b) return true;
                     return @{comparison};
                 }
-            })
+            }
         }
         Data::TypeAlias(type_alias) => {
             let type_name = input.name();
 
             if type_alias.is_object() {
                 // Object type: field-by-field comparison
-                let eq_fields: Vec<EqField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts =
-                            CompareFieldOptions::from_decorators(&field.decorators, "partialEq");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(EqField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
-
-                let comparison = if eq_fields.is_empty() {
-                    "true".to_string()
-                } else {
-                    eq_fields
-                        .iter()
-                        .map(|f| generate_field_equality_for_interface(f, "a", "b"))
-                        .collect::<Vec<_>>()
-                        .join(" && ")
-                };
+                let eq_fields = collect_eq_fields(
+                    type_alias.as_object().unwrap().iter().map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.decorators.as_slice(),
+                            f.span,
+                        )
+                    }),
+                    &container_opts,
+                    &mut diagnostics,
+                );
+
+                let comparison = generate_comparison(&eq_fields);
 
                 let fn_name = format!("{}Equals", type_name.to_case(Case::Camel));
 
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(a: @{type_name}, b: @{type_name}): boolean {
                         if (a === b) return true;
                         return @{comparison};
                     }
-                })
+                }
             } else {
                 // Union, tuple, or simple alias: use strict equality and JSON fallback
                 let fn_name = format!("{}Equals", type_name.to_case(Case::Camel));
 
-                Ok(ts_template! {
+                ts_template! {
                     export function @{fn_name}(a: @{type_name}, b: @{type_name}): boolean {
                         if (a === b) return true;
                         if (typeof a === "object" && typeof b === "object" && a !== null && b !== null) {
@@ -431,10 +505,13 @@ pub fn derive_partial_eq_macro(mut input: TsStream) -> Result<TsStream, Macrofor
                         }
                         return false;
                     }
-                })
+                }
             }
         }
-    }
+    };
+
+    output.add_diagnostics(diagnostics.into_vec());
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -522,4 +599,177 @@ mod tests {
         let result = generate_field_equality_for_interface(&field, "a", "b");
         assert!(result.contains("getTime"));
     }
+
+    fn make_decorator(name: &str, args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn number_field(name: &str) -> EqField {
+        EqField {
+            name: name.to_string(),
+            ts_type: "number".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_field_equality_epsilon() {
+        let opts = EqFieldOptions {
+            epsilon: Some(1e-9),
+            ..Default::default()
+        };
+        let result = generate_field_equality_with_options(&number_field("x"), &opts, "a", "b");
+        assert_eq!(
+            result,
+            "(a.x === b.x || Math.abs(a.x - b.x) <= 0.000000001)"
+        );
+    }
+
+    #[test]
+    fn test_field_equality_epsilon_and_nan_equal() {
+        let opts = EqFieldOptions {
+            epsilon: Some(0.5),
+            nan_equal: true,
+            ..Default::default()
+        };
+        let result = generate_field_equality_with_options(&number_field("x"), &opts, "a", "b");
+        assert_eq!(
+            result,
+            "(a.x === b.x || Math.abs(a.x - b.x) <= 0.5 || (Number.isNaN(a.x) && Number.isNaN(b.x)))"
+        );
+    }
+
+    #[test]
+    fn test_field_equality_nullable_number() {
+        let opts = EqFieldOptions {
+            epsilon: Some(0.5),
+            nan_equal: true,
+            ..Default::default()
+        };
+        for ts_type in ["number | null", "number | undefined"] {
+            let field = EqField {
+                name: "x".to_string(),
+                ts_type: ts_type.to_string(),
+            };
+            let result = generate_field_equality_with_options(&field, &opts, "a", "b");
+            assert_eq!(
+                result,
+                "(a.x === b.x || (a.x != null && b.x != null && (Math.abs(a.x - b.x) <= 0.5 || (Number.isNaN(a.x) && Number.isNaN(b.x)))))"
+            );
+        }
+    }
+
+    #[test]
+    fn test_field_equality_with_custom_fn() {
+        let field = EqField {
+            name: "pos".to_string(),
+            ts_type: "Point".to_string(),
+        };
+        let opts = EqFieldOptions {
+            with: Some("pointEq".to_string()),
+            ..Default::default()
+        };
+        let result = generate_field_equality_with_options(&field, &opts, "a", "b");
+        assert_eq!(result, "(pointEq)(a.pos, b.pos)");
+    }
+
+    #[test]
+    fn test_numeric_options_ignored_for_non_numbers() {
+        let field = EqField {
+            name: "name".to_string(),
+            ts_type: "string".to_string(),
+        };
+        let opts = EqFieldOptions {
+            epsilon: Some(0.1),
+            ..Default::default()
+        };
+        let result = generate_field_equality_with_options(&field, &opts, "a", "b");
+        assert_eq!(
+            result,
+            generate_field_equality_for_interface(&field, "a", "b")
+        );
+    }
+
+    #[test]
+    fn test_collect_eq_fields_inherits_container_defaults() {
+        let fields = [
+            ("x", "number", vec![]),
+            (
+                "y",
+                "number",
+                vec![make_decorator("partialEq", "{ epsilon: 0.5 }")],
+            ),
+            (
+                "hidden",
+                "number",
+                vec![make_decorator("partialEq", "skip")],
+            ),
+        ];
+        let container = EqFieldOptions {
+            epsilon: Some(0.01),
+            nan_equal: true,
+            ..Default::default()
+        };
+        let mut diagnostics = DiagnosticCollector::new();
+        let eq_fields = collect_eq_fields(
+            fields
+                .iter()
+                .map(|(name, ts_type, decs)| (*name, *ts_type, decs.as_slice(), SpanIR::new(0, 0))),
+            &container,
+            &mut diagnostics,
+        );
+
+        assert_eq!(eq_fields.len(), 2);
+        assert_eq!(eq_fields[0].1.epsilon, Some(0.01));
+        assert_eq!(eq_fields[1].1.epsilon, Some(0.5));
+        assert!(eq_fields.iter().all(|(_, opts)| opts.nan_equal));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_collect_eq_fields_inherits_defaults_for_nullable_numbers() {
+        let fields = [
+            ("x", "number | null", vec![]),
+            ("y", "number | undefined", vec![]),
+        ];
+        let container = EqFieldOptions {
+            epsilon: Some(0.01),
+            ..Default::default()
+        };
+        let mut diagnostics = DiagnosticCollector::new();
+        let eq_fields = collect_eq_fields(
+            fields
+                .iter()
+                .map(|(name, ts_type, decs)| (*name, *ts_type, decs.as_slice(), SpanIR::new(0, 0))),
+            &container,
+            &mut diagnostics,
+        );
+
+        let code = generate_comparison(&eq_fields);
+        assert!(code.contains("Math.abs(a.x - b.x) <= 0.01"), "{code}");
+        assert!(code.contains("Math.abs(a.y - b.y) <= 0.01"), "{code}");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_collect_eq_fields_warns_on_numeric_options_for_non_numbers() {
+        let fields = [(
+            "name",
+            "string",
+            vec![make_decorator("partialEq", "{ epsilon: 0.5 }")],
+        )];
+        let mut diagnostics = DiagnosticCollector::new();
+        collect_eq_fields(
+            fields
+                .iter()
+                .map(|(name, ts_type, decs)| (*name, *ts_type, decs.as_slice(), SpanIR::new(0, 0))),
+            &EqFieldOptions::default(),
+            &mut diagnostics,
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
 }
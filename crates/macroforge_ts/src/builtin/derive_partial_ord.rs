@@ -55,9 +55,16 @@
 //!
 //! ## Field-Level Options
 //!
-//! The `@ord` decorator supports:
+//! The `@ord` decorator supports the same options as `Ord`:
 //!
 //! - `skip` - Exclude the field from ordering comparison
+//! - `priority = n` - Compare this field before others (lowest value first)
+//! - `reverse` - Invert the ordering of this field
+//! - `nulls = "first" | "last"` - Make `null`/`undefined` comparable, sorting
+//!   them before or after all other values instead of returning `null`
+//! - `by = "fn"` - Compare the keys returned by `fn(value)`
+//! - `locale = "de"` / `collator = { ... }` - Compare strings with `Intl.Collator`
+//!   (also accepted on a container-level `@ord`)
 //!
 //! ## Example
 //!
@@ -75,12 +82,12 @@
 //! class Temperature {
 //!     value: number | null;
 //!     unit: string;
-//! 
+//!
 //!     static compareTo(a: Temperature, b: Temperature): number | null {
 //!         return temperaturePartialCompare(a, b);
 //!     }
 //! }
-//! 
+//!
 //! export function temperaturePartialCompare(a: Temperature, b: Temperature): number | null {
 //!     if (a === b) return 0;
 //!     const cmp0 = (() => {
@@ -105,27 +112,58 @@
 
 use convert_case::{Case, Casing};
 
-use crate::builtin::derive_common::{CompareFieldOptions, is_numeric_type, is_primitive_type};
+use crate::builtin::derive_common::{
+    NullsOrder, OrdFieldOptions, is_numeric_type, is_primitive_type, strip_nullable,
+};
+use crate::builtin::derive_ord::{OrdField, collect_ord_fields, generate_collators};
 use crate::builtin::return_types::{
     is_none_check, partial_ord_return_type, unwrap_option_or_null, wrap_none, wrap_some,
 };
 use crate::macros::{body, ts_macro_derive, ts_template};
+use crate::ts_syn::abi::DecoratorIR;
 use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
 
-/// Contains field information needed for partial ordering comparison generation.
+/// Generates the comparison statements for all fields.
 ///
-/// Each field that participates in ordering is represented by this struct,
-/// which captures both the field name (for access) and its TypeScript type
-/// (to select the appropriate comparison strategy).
-struct OrdField {
-    /// The field name as it appears in the source TypeScript class.
-    /// Used to generate property access expressions like `this.name`.
-    name: String,
-
-    /// The TypeScript type annotation for this field.
-    /// Used to determine which comparison strategy to apply
-    /// (e.g., numeric comparison, string localeCompare, recursive compareTo).
-    ts_type: String,
+/// Each field produces `const cmpN = ...;` followed by an incomparable check and
+/// an early return. With `nulls` set, a null-placement guard runs first; `reverse`
+/// negates the returned result so that null placement is unaffected by it.
+fn generate_compare_body(fields: &[OrdField], self_var: &str, other_var: &str) -> String {
+    let none_val = wrap_none();
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let var_name = format!("cmp{}", i);
+            let result = if f.options.reverse {
+                format!("-{var_name}")
+            } else {
+                var_name.clone()
+            };
+            let some_var = wrap_some(&result);
+            let mut compare = generate_field_compare_for_interface(f, self_var, other_var, true);
+            let mut stmts = String::new();
+            if let Some(nulls) = f.options.nulls {
+                let a = format!("{self_var}.{}", f.name);
+                let b = format!("{other_var}.{}", f.name);
+                let (a_null, b_null) = match nulls {
+                    NullsOrder::First => ("-1", "1"),
+                    NullsOrder::Last => ("1", "-1"),
+                };
+                let some_a_null = wrap_some(a_null);
+                let some_b_null = wrap_some(b_null);
+                stmts.push_str(&format!(
+                    "if (({a} == null) !== ({b} == null)) return {a} == null ? {some_a_null} : {some_b_null};\n"
+                ));
+                compare = format!("{a} == null ? 0 : {compare}");
+            }
+            stmts.push_str(&format!(
+                "const {var_name} = {compare};\nif ({var_name} === null) return {none_val};\nif ({var_name} !== 0) return {some_var};"
+            ));
+            stmts
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Generates JavaScript code that compares fields for partial ordering.
@@ -155,6 +193,9 @@ struct OrdField {
 /// - **Arrays**: Returns null if element comparison returns null
 /// - **Date**: Returns null if either value is not a valid Date
 /// - **Objects**: Unwraps `Option` from nested `compareTo()` calls
+///
+/// A `by` key extractor replaces the type-based strategy with a comparison of the
+/// extracted keys, and a collator replaces `localeCompare()` for strings.
 fn generate_field_compare_for_interface(
     field: &OrdField,
     self_var: &str,
@@ -162,16 +203,31 @@ fn generate_field_compare_for_interface(
     allow_null: bool,
 ) -> String {
     let field_name = &field.name;
-    let ts_type = &field.ts_type;
+    // With explicit null placement, nulls are handled before this comparison runs
+    let ts_type = &if field.options.nulls.is_some() {
+        strip_nullable(&field.ts_type)
+    } else {
+        field.ts_type.clone()
+    };
     let null_return = if allow_null { "null" } else { "0" };
 
-    if is_numeric_type(ts_type) {
+    if let Some(by) = &field.options.by {
+        let key_compare = field.string_compare("ka", "kb");
+        format!(
+            "((ka: any, kb: any) => typeof ka === 'string' && typeof kb === 'string' \
+                ? {key_compare} \
+                : ka < kb ? -1 : ka > kb ? 1 : ka === kb ? 0 : {null_return})(({by})({self_var}.{field_name}), ({by})({other_var}.{field_name})))"
+        )
+    } else if is_numeric_type(ts_type) {
         format!(
             "({self_var}.{field_name} < {other_var}.{field_name} ? -1 : \
              {self_var}.{field_name} > {other_var}.{field_name} ? 1 : 0)"
         )
     } else if ts_type == "string" {
-        format!("{self_var}.{field_name}.localeCompare({other_var}.{field_name})")
+        field.string_compare(
+            &format!("{self_var}.{field_name}"),
+            &format!("{other_var}.{field_name}"),
+        )
     } else if ts_type == "boolean" {
         format!(
             "({self_var}.{field_name} === {other_var}.{field_name} ? 0 : \
@@ -232,62 +288,50 @@ fn generate_field_compare_for_interface(
 #[ts_macro_derive(
     PartialOrd,
     description = "Generates a compareTo() method for partial ordering (returns Option<number>: some(-1), some(0), some(1), or none())",
-    attributes(ord)
+    attributes((ord, "Configure ordering. Options: skip, priority, reverse, nulls (\"first\" | \"last\"), by (key extractor), locale and collator (Intl.Collator options)"))
 )]
 pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
     let input = parse_ts_macro_input!(input as DeriveInput);
 
+    let container_decorators: Vec<DecoratorIR> =
+        input.attrs.iter().map(|attr| attr.inner.clone()).collect();
+    let container_opts = OrdFieldOptions::from_decorators(&container_decorators, "ord")
+        .map_err(|msg| MacroforgeError::new(input.decorator_span(), msg))?;
+
     match &input.data {
         Data::Class(class) => {
             let class_name = input.name();
 
+            // Generate function name (always prefix style)
+            let fn_name = format!("{}PartialCompare", class_name.to_case(Case::Camel));
+
             // Collect fields for comparison
-            let ord_fields: Vec<OrdField> = class
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = collect_ord_fields(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &fn_name,
+            )?;
 
             let has_fields = !ord_fields.is_empty();
-
-            // Generate function name (always prefix style)
-            let fn_name = format!("{}PartialCompare", class_name.to_case(Case::Camel));
+            let collators = generate_collators(&ord_fields);
 
             // Get return type and wrappers
             let return_type = partial_ord_return_type();
             let some_zero = wrap_some("0");
-            let none_val = wrap_none();
 
             // Build comparison logic using a and b parameters
-            let compare_body = if has_fields {
-                ord_fields
-                    .iter()
-                    .enumerate()
-                    .map(|(i, f)| {
-                        let var_name = format!("cmp{}", i);
-                        let some_var = wrap_some(&var_name);
-                        format!(
-                            "const {var_name} = {};\n                    if ({var_name} === null) return {none_val};\n                    if ({var_name} !== 0) return {some_var};",
-                            generate_field_compare_for_interface(f, "a", "b", true)
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                    ")
-            } else {
-                String::new()
-            };
+            let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
             // Generate standalone function with two parameters
             let standalone = ts_template! {
+                @{collators}
                 export function @{fn_name}(a: @{class_name}, b: @{class_name}): @{return_type} {
                     if (a === b) return @{some_zero};
                     {#if has_fields}
@@ -341,50 +385,32 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
         }
         Data::Interface(interface) => {
             let interface_name = input.name();
+            let fn_name = format!("{}PartialCompare", interface_name.to_case(Case::Camel));
 
-            let ord_fields: Vec<OrdField> = interface
-                .fields()
-                .iter()
-                .filter_map(|field| {
-                    let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                    if opts.skip {
-                        return None;
-                    }
-                    Some(OrdField {
-                        name: field.name.clone(),
-                        ts_type: field.ts_type.clone(),
-                    })
-                })
-                .collect();
+            let ord_fields = collect_ord_fields(
+                interface.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.decorators.as_slice(),
+                        f.span,
+                    )
+                }),
+                &container_opts,
+                &fn_name,
+            )?;
 
             let has_fields = !ord_fields.is_empty();
+            let collators = generate_collators(&ord_fields);
 
             // Get return type and wrappers
             let return_type = partial_ord_return_type();
             let some_zero = wrap_some("0");
-            let none_val = wrap_none();
 
-            let compare_body = if has_fields {
-                ord_fields
-                    .iter()
-                    .enumerate()
-                    .map(|(i, f)| {
-                        let var_name = format!("cmp{}", i);
-                        let some_var = wrap_some(&var_name);
-                        format!(
-                            "const {var_name} = {};\n                        if ({var_name} === null) return {none_val};\n                        if ({var_name} !== 0) return {some_var};",
-                            generate_field_compare_for_interface(f, "a", "b", true)
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n                        ")
-            } else {
-                String::new()
-            };
-
-            let fn_name = format!("{}PartialCompare", interface_name.to_case(Case::Camel));
+            let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
             let result = ts_template! {
+                @{collators}
                 export function @{fn_name}(a: @{interface_name}, b: @{interface_name}): @{return_type} {
                     if (a === b) return @{some_zero};
                     {#if has_fields}
@@ -405,45 +431,27 @@ pub fn derive_partial_ord_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             let none_val = wrap_none();
 
             if type_alias.is_object() {
-                let ord_fields: Vec<OrdField> = type_alias
-                    .as_object()
-                    .unwrap()
-                    .iter()
-                    .filter_map(|field| {
-                        let opts = CompareFieldOptions::from_decorators(&field.decorators, "ord");
-                        if opts.skip {
-                            return None;
-                        }
-                        Some(OrdField {
-                            name: field.name.clone(),
-                            ts_type: field.ts_type.clone(),
-                        })
-                    })
-                    .collect();
-
-                let has_fields = !ord_fields.is_empty();
+                let fn_name = format!("{}PartialCompare", type_name.to_case(Case::Camel));
 
-                let compare_body = if has_fields {
-                    ord_fields
-                        .iter()
-                        .enumerate()
-                        .map(|(i, f)| {
-                            let var_name = format!("cmp{}", i);
-                            let some_var = wrap_some(&var_name);
-                            format!(
-                                "const {var_name} = {};\n                        if ({var_name} === null) return {none_val};\n                        if ({var_name} !== 0) return {some_var};",
-                                generate_field_compare_for_interface(f, "a", "b", true)
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n                        ")
-                } else {
-                    String::new()
-                };
+                let ord_fields = collect_ord_fields(
+                    type_alias.as_object().unwrap().iter().map(|f| {
+                        (
+                            f.name.as_str(),
+                            f.ts_type.as_str(),
+                            f.decorators.as_slice(),
+                            f.span,
+                        )
+                    }),
+                    &container_opts,
+                    &fn_name,
+                )?;
 
-                let fn_name = format!("{}PartialCompare", type_name.to_case(Case::Camel));
+                let has_fields = !ord_fields.is_empty();
+                let collators = generate_collators(&ord_fields);
+                let compare_body = generate_compare_body(&ord_fields, "a", "b");
 
                 let result = ts_template! {
+                    @{collators}
                     export function @{fn_name}(a: @{type_name}, b: @{type_name}): @{return_type} {
                         if (a === b) return @{some_zero};
                         {#if has_fields}
@@ -490,6 +498,7 @@ mod tests {
         let ord_fields: Vec<OrdField> = vec![OrdField {
             name: "id".to_string(),
             ts_type: "number".to_string(),
+            ..Default::default()
         }];
         let has_fields = !ord_fields.is_empty();
 
@@ -546,6 +555,7 @@ mod tests {
         let field = OrdField {
             name: "id".to_string(),
             ts_type: "number".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         assert!(result.contains("a.id < b.id"));
@@ -557,6 +567,7 @@ mod tests {
         let field = OrdField {
             name: "name".to_string(),
             ts_type: "string".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         assert!(result.contains("localeCompare"));
@@ -567,6 +578,7 @@ mod tests {
         let field = OrdField {
             name: "active".to_string(),
             ts_type: "boolean".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         // false < true: false returns -1, true returns 1
@@ -579,6 +591,7 @@ mod tests {
         let field = OrdField {
             name: "createdAt".to_string(),
             ts_type: "Date".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         assert!(result.contains("getTime"));
@@ -589,6 +602,7 @@ mod tests {
         let field = OrdField {
             name: "user".to_string(),
             ts_type: "User".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         assert!(result.contains("compareTo"));
@@ -601,9 +615,45 @@ mod tests {
         let field = OrdField {
             name: "items".to_string(),
             ts_type: "Item[]".to_string(),
+            ..Default::default()
         };
         let result = generate_field_compare_for_interface(&field, "a", "b", true);
         // optResult is already the value
         assert!(result.contains("cmp = optResult"));
     }
+
+    #[test]
+    fn test_nulls_make_nullable_fields_comparable() {
+        let field = OrdField {
+            name: "score".to_string(),
+            ts_type: "number | null".to_string(),
+            options: OrdFieldOptions {
+                nulls: Some(NullsOrder::First),
+                reverse: true,
+                ..Default::default()
+            },
+            collator: None,
+        };
+        let body = generate_compare_body(&[field], "a", "b");
+        assert!(body.contains("return a.score == null ? -1 : 1;"));
+        // The non-null part uses the numeric strategy instead of compareTo
+        assert!(body.contains("a.score < b.score"));
+        assert!(body.contains("if (cmp0 !== 0) return -cmp0;"));
+    }
+
+    #[test]
+    fn test_field_compare_by_key() {
+        let field = OrdField {
+            name: "version".to_string(),
+            ts_type: "Version".to_string(),
+            options: OrdFieldOptions {
+                by: Some("v => v.major".to_string()),
+                ..Default::default()
+            },
+            collator: None,
+        };
+        let result = generate_field_compare_for_interface(&field, "a", "b", true);
+        assert!(result.contains("(v => v.major)(a.version)"));
+        assert!(result.contains("ka === kb ? 0 : null"));
+    }
 }
@@ -0,0 +1,555 @@
+//! # Reflect Macro Implementation
+//!
+//! The `Reflect` macro records the shape of a type at expansion time so that
+//! generic tooling (admin UIs, table renderers, ORM mappers) can read it at
+//! runtime, without `reflect-metadata` or `emitDecoratorMetadata`.
+//!
+//! ## Generated Output
+//!
+//! For a type `User`:
+//!
+//! - `userReflection` - The metadata, deep-frozen by `defineReflection`
+//! - `userReflect(): TypeReflection<User>` - Typed accessor
+//!
+//! The metadata is created in a `/*#__PURE__*/` call, so bundlers drop it when
+//! nothing reads it. For the same reason classes don't get a static wrapper:
+//! it would keep the metadata alive wherever the class is used.
+//!
+//! ## Recorded Metadata
+//!
+//! | Type | Metadata |
+//! |------|----------|
+//! | Class | Fields, method signatures, type parameters, heritage, `abstract` |
+//! | Interface | Fields, method signatures, type parameters, heritage |
+//! | Enum | Variants with their values |
+//! | Type Alias | Fields for object types, otherwise the aliased type as written |
+//!
+//! Fields record their name, declared type, `optional`, `readonly` and
+//! visibility. Types, fields, methods and variants record their decorators
+//! (name and argument source) and members record their JSDoc description.
+//! The `TypeReflection`, `FieldReflection`, etc. types and the `fieldOf`,
+//! `methodOf` and `decoratorOf` lookups come from `macroforge/reflect`.
+//!
+//! ## Field-Level Options
+//!
+//! - `@reflect({ skip: true })` - Leave a field or method out of the metadata
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(Reflect) */
+//! interface User {
+//!     /** Login address. */
+//!     email: string;
+//!     nickname?: string;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export const userReflection = /*#__PURE__*/ __mf_defineReflection<User>({
+//!     name: "User",
+//!     kind: "interface",
+//!     // ...
+//!     fields: [
+//!         { name: "email", type: "string", optional: false, readonly: false, visibility: "public", decorators: [], doc: "Login address." },
+//!         { name: "nickname", type: "string", optional: true, readonly: false, visibility: "public", decorators: [] },
+//!     ],
+//!     methods: [],
+//!     variants: [],
+//! });
+//!
+//! export function userReflect(): __mf_TypeReflection<User> {
+//!     return userReflection;
+//! }
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{has_flag, member_doc, return_type};
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{
+    DecoratorIR, EnumValue, InterfaceFieldIR, SpanIR, TypeBody, TypeMember, TypeMemberKind,
+    Visibility,
+};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// A JavaScript value in the generated metadata.
+#[derive(Debug, Clone, PartialEq)]
+enum Js {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Array(Vec<Js>),
+    /// Properties in insertion order; `None` values are left out.
+    Object(Vec<(&'static str, Option<Js>)>),
+}
+
+impl Js {
+    fn str(s: impl AsRef<str>) -> Self {
+        Js::Str(s.as_ref().to_string())
+    }
+
+    fn strings<'a>(items: impl IntoIterator<Item = &'a String>) -> Self {
+        Js::Array(items.into_iter().map(Js::str).collect())
+    }
+
+    fn render(&self, indent: usize) -> String {
+        let pad = "    ".repeat(indent + 1);
+        let close = "    ".repeat(indent);
+        match self {
+            Js::Str(s) => serde_json::to_string(s).unwrap_or_default(),
+            Js::Num(n) => n.to_string(),
+            Js::Bool(b) => b.to_string(),
+            Js::Array(items) if items.is_empty() => "[]".to_string(),
+            Js::Array(items) if items.iter().all(|i| matches!(i, Js::Str(_))) => {
+                let items = items.iter().map(|i| i.render(0)).collect::<Vec<_>>();
+                format!("[{}]", items.join(", "))
+            }
+            Js::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(|i| format!("{pad}{},\n", i.render(indent + 1)))
+                    .collect::<String>();
+                format!("[\n{items}{close}]")
+            }
+            Js::Object(props) => {
+                let props = props
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let value = value.as_ref()?;
+                        Some(format!("{pad}{key}: {},\n", value.render(indent + 1)))
+                    })
+                    .collect::<String>();
+                format!("{{\n{props}{close}}}")
+            }
+        }
+    }
+}
+
+/// Reads member docs from the target's source text.
+struct DocSource<'a> {
+    source: &'a str,
+    /// Offset of the target's source within the file.
+    start: u32,
+}
+
+impl DocSource<'_> {
+    fn doc(&self, span: SpanIR) -> Option<Js> {
+        member_doc(self.source, self.start, span).map(Js::Str)
+    }
+}
+
+fn is_skipped(decorators: &[DecoratorIR]) -> bool {
+    decorators
+        .iter()
+        .any(|d| d.name.eq_ignore_ascii_case("reflect") && has_flag(d.args_src.trim(), "skip"))
+}
+
+fn decorators(decorators: &[DecoratorIR]) -> Js {
+    Js::Array(
+        decorators
+            .iter()
+            .map(|d| {
+                Js::Object(vec![
+                    ("name", Some(Js::str(&d.name))),
+                    ("args", Some(Js::str(d.args_src.trim()))),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn visibility(visibility: &Visibility) -> Js {
+    Js::str(match visibility {
+        Visibility::Public => "public",
+        Visibility::Protected => "protected",
+        Visibility::Private => "private",
+    })
+}
+
+/// Metadata for one field.
+struct FieldMeta<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    readonly: bool,
+    visibility: Js,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+fn field(meta: FieldMeta<'_>, docs: &DocSource<'_>) -> Js {
+    Js::Object(vec![
+        ("name", Some(Js::str(meta.name))),
+        ("type", Some(Js::str(meta.ts_type))),
+        ("optional", Some(Js::Bool(meta.optional))),
+        ("readonly", Some(Js::Bool(meta.readonly))),
+        ("visibility", Some(meta.visibility)),
+        ("decorators", Some(decorators(meta.decorators))),
+        ("doc", docs.doc(meta.span)),
+    ])
+}
+
+fn interface_fields(fields: &[InterfaceFieldIR], docs: &DocSource<'_>) -> Js {
+    Js::Array(
+        fields
+            .iter()
+            .filter(|f| !is_skipped(&f.decorators))
+            .map(|f| {
+                field(
+                    FieldMeta {
+                        name: &f.name,
+                        ts_type: &f.ts_type,
+                        optional: f.optional,
+                        readonly: f.readonly,
+                        visibility: Js::str("public"),
+                        decorators: &f.decorators,
+                        span: f.span,
+                    },
+                    docs,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Metadata for one method.
+struct MethodMeta<'a> {
+    name: &'a str,
+    type_params: &'a str,
+    params: &'a str,
+    return_type: &'a str,
+    is_static: bool,
+    is_async: bool,
+    optional: bool,
+    visibility: Js,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+fn method(meta: MethodMeta<'_>, docs: &DocSource<'_>) -> Js {
+    Js::Object(vec![
+        ("name", Some(Js::str(meta.name))),
+        ("typeParams", Some(Js::str(meta.type_params))),
+        ("params", Some(Js::str(meta.params))),
+        ("returnType", Some(Js::str(return_type(meta.return_type)))),
+        ("static", Some(Js::Bool(meta.is_static))),
+        ("async", Some(Js::Bool(meta.is_async))),
+        ("optional", Some(Js::Bool(meta.optional))),
+        ("visibility", Some(meta.visibility)),
+        ("decorators", Some(decorators(meta.decorators))),
+        ("doc", docs.doc(meta.span)),
+    ])
+}
+
+/// The type of a union or intersection member as written.
+fn member_type(member: &TypeMember) -> String {
+    match &member.kind {
+        TypeMemberKind::Literal(s) | TypeMemberKind::TypeRef(s) => s.clone(),
+        TypeMemberKind::Object { fields } => object_type(fields),
+    }
+}
+
+fn object_type(fields: &[InterfaceFieldIR]) -> String {
+    let fields = fields
+        .iter()
+        .map(|f| {
+            format!(
+                "{}{}{}: {};",
+                if f.readonly { "readonly " } else { "" },
+                f.name,
+                if f.optional { "?" } else { "" },
+                f.ts_type
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{ {} }}", fields.join(" "))
+}
+
+/// The aliased type as written, for non-object aliases. Types the lowering
+/// does not model, such as mapped or conditional types, are opaque and
+/// reflected as their source, or `unknown` if it is not available.
+fn alias_type(body: &TypeBody) -> Option<String> {
+    let join = |members: &[TypeMember], separator: &str| {
+        members
+            .iter()
+            .map(member_type)
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+    match body {
+        TypeBody::Union(members) => Some(join(members, " | ")),
+        TypeBody::Intersection(members) => Some(join(members, " & ")),
+        TypeBody::Tuple(elements) => Some(format!("[{}]", elements.join(", "))),
+        TypeBody::Alias(aliased) => Some(aliased.clone()),
+        TypeBody::Object { .. } => None,
+        TypeBody::Other(source) if !source.trim().is_empty() => Some(source.trim().to_string()),
+        TypeBody::Other(_) => Some("unknown".to_string()),
+    }
+}
+
+/// The reflected type, e.g. `Box<any>` for `Box<T>`.
+fn reflected_type(type_name: &str, type_params: &[String]) -> String {
+    if type_params.is_empty() {
+        type_name.to_string()
+    } else {
+        let any = vec!["any"; type_params.len()];
+        format!("{type_name}<{}>", any.join(", "))
+    }
+}
+
+/// Type parameter names without constraints or defaults, e.g. `T` for `T extends string`.
+fn type_param_names(type_params: &[String]) -> Js {
+    Js::Array(
+        type_params
+            .iter()
+            .map(|p| {
+                let name = p.split(['=', ' ']).next().unwrap_or(p);
+                Js::str(name.trim())
+            })
+            .collect(),
+    )
+}
+
+fn generate_reflection(type_name: &str, reflected: &str, metadata: &Js) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    format!(
+        "export const {prefix}Reflection = /*#__PURE__*/ __mf_defineReflection<{reflected}>({});\n\n\
+         export function {prefix}Reflect(): __mf_TypeReflection<{reflected}> {{\n    return {prefix}Reflection;\n}}",
+        metadata.render(0)
+    )
+}
+
+#[ts_macro_derive(
+    Reflect,
+    description = "Generates frozen runtime metadata for fields, methods, decorators and JSDoc",
+    attributes((reflect, "Configure reflection. Options: skip (leave the field or method out)"))
+)]
+pub fn derive_reflect_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let docs = DocSource {
+        source: &input.context.target_source,
+        start: input.context.target_span.start,
+    };
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+
+    let header = |kind: &str, type_params: &[String], heritage: &[String], is_abstract: bool| {
+        vec![
+            ("name", Some(Js::str(type_name))),
+            ("kind", Some(Js::str(kind))),
+            ("typeParams", Some(type_param_names(type_params))),
+            ("heritage", Some(Js::strings(heritage))),
+            ("abstract", Some(Js::Bool(is_abstract))),
+            ("decorators", Some(decorators(&container_decorators))),
+        ]
+    };
+    let empty = || Some(Js::Array(Vec::new()));
+
+    let (reflected, metadata) = match &input.data {
+        Data::Class(class) => {
+            let inner = &class.inner;
+            let fields = class
+                .fields()
+                .iter()
+                .filter(|f| !is_skipped(&f.decorators))
+                .map(|f| {
+                    field(
+                        FieldMeta {
+                            name: &f.name,
+                            ts_type: &f.ts_type,
+                            optional: f.optional,
+                            readonly: f.readonly,
+                            visibility: visibility(&f.visibility),
+                            decorators: &f.decorators,
+                            span: f.span,
+                        },
+                        &docs,
+                    )
+                })
+                .collect();
+            let methods = class
+                .methods()
+                .iter()
+                .filter(|m| !is_skipped(&m.decorators))
+                .map(|m| {
+                    method(
+                        MethodMeta {
+                            name: &m.name,
+                            type_params: &m.type_params_src,
+                            params: &m.params_src,
+                            return_type: &m.return_type_src,
+                            is_static: m.is_static,
+                            is_async: m.is_async,
+                            optional: false,
+                            visibility: visibility(&m.visibility),
+                            decorators: &m.decorators,
+                            span: m.span,
+                        },
+                        &docs,
+                    )
+                })
+                .collect();
+            let mut props = header(
+                "class",
+                &inner.type_params,
+                &inner.heritage,
+                inner.is_abstract,
+            );
+            props.push(("fields", Some(Js::Array(fields))));
+            props.push(("methods", Some(Js::Array(methods))));
+            props.push(("variants", empty()));
+            (
+                reflected_type(type_name, &inner.type_params),
+                Js::Object(props),
+            )
+        }
+        Data::Interface(interface) => {
+            let inner = &interface.inner;
+            let methods = interface
+                .methods()
+                .iter()
+                .filter(|m| !is_skipped(&m.decorators))
+                .map(|m| {
+                    method(
+                        MethodMeta {
+                            name: &m.name,
+                            type_params: &m.type_params_src,
+                            params: &m.params_src,
+                            return_type: &m.return_type_src,
+                            is_static: false,
+                            is_async: false,
+                            optional: m.optional,
+                            visibility: Js::str("public"),
+                            decorators: &m.decorators,
+                            span: m.span,
+                        },
+                        &docs,
+                    )
+                })
+                .collect();
+            let mut props = header("interface", &inner.type_params, &inner.heritage, false);
+            props.push(("fields", Some(interface_fields(interface.fields(), &docs))));
+            props.push(("methods", Some(Js::Array(methods))));
+            props.push(("variants", empty()));
+            (
+                reflected_type(type_name, &inner.type_params),
+                Js::Object(props),
+            )
+        }
+        Data::Enum(enum_data) => {
+            let mut previous: Option<f64> = None;
+            let variants = enum_data
+                .variants()
+                .iter()
+                .map(|v| {
+                    let value = match &v.value {
+                        EnumValue::String(s) => Js::str(s),
+                        EnumValue::Number(n) => Js::Num(*n),
+                        EnumValue::Auto => Js::Num(previous.map_or(0.0, |p| p + 1.0)),
+                        EnumValue::Expr(expr) => Js::str(expr),
+                    };
+                    previous = match value {
+                        Js::Num(n) => Some(n),
+                        _ => None,
+                    };
+                    Js::Object(vec![
+                        ("name", Some(Js::str(&v.name))),
+                        ("value", Some(value)),
+                        ("decorators", Some(decorators(&v.decorators))),
+                        ("doc", docs.doc(v.span)),
+                    ])
+                })
+                .collect();
+            let mut props = header("enum", &[], &[], false);
+            props.push(("fields", empty()));
+            props.push(("methods", empty()));
+            props.push(("variants", Some(Js::Array(variants))));
+            (type_name.to_string(), Js::Object(props))
+        }
+        Data::TypeAlias(type_alias) => {
+            let type_params = &type_alias.inner.type_params;
+            let fields = match type_alias.as_object() {
+                Some(fields) => interface_fields(fields, &docs),
+                None => Js::Array(Vec::new()),
+            };
+            let mut props = header("typeAlias", type_params, &[], false);
+            props.push(("fields", Some(fields)));
+            props.push(("methods", empty()));
+            props.push(("variants", empty()));
+            props.push(("type", alias_type(type_alias.body()).map(Js::Str)));
+            (reflected_type(type_name, type_params), Js::Object(props))
+        }
+    };
+
+    let mut output = TsStream::from_string(generate_reflection(type_name, &reflected, &metadata));
+    output.add_aliased_import("defineReflection", "macroforge/reflect");
+    output.add_aliased_type_import("TypeReflection", "macroforge/reflect");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_skips_missing_properties() {
+        let js = Js::Object(vec![
+            ("name", Some(Js::str("id"))),
+            ("doc", None),
+            ("tags", Some(Js::Array(vec![Js::str("a"), Js::str("b")]))),
+            (
+                "nested",
+                Some(Js::Array(vec![Js::Object(vec![("x", Some(Js::Num(1.0)))])])),
+            ),
+        ]);
+        assert_eq!(
+            js.render(0),
+            "{\n    name: \"id\",\n    tags: [\"a\", \"b\"],\n    nested: [\n        {\n            x: 1,\n        },\n    ],\n}"
+        );
+    }
+
+    #[test]
+    fn test_alias_type() {
+        let members = vec![
+            TypeMember::new(TypeMemberKind::Literal("\"a\"".into())),
+            TypeMember::new(TypeMemberKind::TypeRef("User".into())),
+        ];
+        assert_eq!(
+            alias_type(&TypeBody::Union(members)).as_deref(),
+            Some("\"a\" | User")
+        );
+        assert_eq!(
+            alias_type(&TypeBody::Tuple(vec!["string".into(), "number".into()])).as_deref(),
+            Some("[string, number]")
+        );
+        assert_eq!(alias_type(&TypeBody::Object { fields: vec![] }), None);
+        assert_eq!(
+            alias_type(&TypeBody::Other("Partial<User>".into())).as_deref(),
+            Some("Partial<User>")
+        );
+        assert_eq!(
+            alias_type(&TypeBody::Other(String::new())).as_deref(),
+            Some("unknown")
+        );
+    }
+
+    #[test]
+    fn test_reflected_type_erases_type_params() {
+        assert_eq!(reflected_type("User", &[]), "User");
+        assert_eq!(
+            reflected_type("Pair", &["K extends string".into(), "V = unknown".into()]),
+            "Pair<any, any>"
+        );
+        assert_eq!(
+            type_param_names(&["K extends string".into(), "V = unknown".into()]),
+            Js::Array(vec![Js::str("K"), Js::str("V")])
+        );
+    }
+}
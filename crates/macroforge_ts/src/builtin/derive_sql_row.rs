@@ -0,0 +1,720 @@
+//! # SqlRow Macro Implementation
+//!
+//! The `SqlRow` macro maps a type to database rows. It only generates code:
+//! column lists, SQL text and conversions between values and the rows and
+//! parameters of a driver such as `pg`.
+//!
+//! ## Generated Output
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `typeNameTable`, `typeNameColumns`, `typeNamePrimaryKey` | Table name and column names |
+//! | `typeNameFromRow(row)` | Value from a driver row, with type coercion |
+//! | `typeNameToParams(value)` | Parameters in column order |
+//! | `typeNameInsertSql`, `typeNameUpdateSql` | Statements using the parameters of `toParams` |
+//! | `typeNameCreateTableSql` | `CREATE TABLE` statement |
+//!
+//! Classes also get `static fromRow(row)` and `toParams()`, and rows are
+//! created with the class prototype. `typeNameUpdateSql` sets every other
+//! column by primary key, so it is only generated when the type has one.
+//! Both statements use the same parameters, `$1` being the first column.
+//!
+//! ## Column Types
+//!
+//! | Field Type | Column | Read From |
+//! |------------|--------|-----------|
+//! | `string` | `TEXT` | Strings |
+//! | `number` | `DOUBLE PRECISION` | Numbers and numeric strings (`NUMERIC`) |
+//! | `bigint` | `BIGINT` | Bigints, integers and integer strings |
+//! | `boolean` | `BOOLEAN` | Booleans, `t`/`f` |
+//! | `Date` | `TIMESTAMPTZ` | Dates and timestamp strings |
+//! | `"a" \| "b"` | `TEXT` with a `CHECK` | One of the literals |
+//! | Anything else | `JSONB` | JSON text or parsed values |
+//!
+//! Fields whose type is a configured foreign type are converted with its
+//! `serialize` and `deserialize` functions and stored as `TEXT` unless
+//! `@column({ type })` says otherwise.
+//!
+//! Optional fields (`?`, `| undefined`) read `NULL` as `undefined` and
+//! nullable fields (`| null`) as `null`; both are nullable columns. Other
+//! fields are `NOT NULL`, and reading `NULL` throws a `SqlRowError`.
+//!
+//! ## Options
+//!
+//! - `@table({ name: "users" })` - Table name (defaults to the snake_case
+//!   type name)
+//! - `@table({ renameAll: "snake_case" })` - Naming convention for columns
+//! - `@column({ name: "created_at" })` - Column name
+//! - `@column({ primaryKey: true })` - Part of the primary key
+//! - `@column({ nullable: true })` - Nullable column; `NULL` reads as `null`
+//! - `@column({ type: "INTEGER" })` - Column type in the DDL
+//! - `@column({ skip: true })` - Not stored
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(SqlRow) @table({ name: "users", renameAll: "snake_case" }) */
+//! class User {
+//!     /** @column({ primaryKey: true }) */
+//!     id: bigint;
+//!     fullName: string;
+//!     createdAt: Date;
+//!     deletedAt: Date | null;
+//! }
+//!
+//! const { rows } = await pool.query(`SELECT * FROM users`);
+//! const users = rows.map(User.fromRow);
+//! await pool.query(userInsertSql, user.toParams());
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{
+    extract_exact_named_string, has_flag, split_top_level, strip_nullable, strip_parens,
+};
+use crate::builtin::serde::{
+    RenameAll, TypeCategory, get_foreign_types, rewrite_expression_namespaces,
+};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, SpanIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Options parsed from `@table` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TableOptions {
+    name: Option<String>,
+    rename_all: Option<String>,
+}
+
+impl TableOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("table") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.name = extract_exact_named_string(args, "name").or(opts.name);
+            opts.rename_all = extract_exact_named_string(args, "renameAll").or(opts.rename_all);
+        }
+        opts
+    }
+}
+
+/// Options parsed from `@column` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ColumnOptions {
+    name: Option<String>,
+    sql_type: Option<String>,
+    primary_key: bool,
+    nullable: bool,
+    skip: bool,
+}
+
+impl ColumnOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("column") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.name = extract_exact_named_string(args, "name").or(opts.name);
+            opts.sql_type = extract_exact_named_string(args, "type").or(opts.sql_type);
+            opts.primary_key |= has_flag(args, "primaryKey");
+            opts.nullable |= has_flag(args, "nullable");
+            opts.skip |= has_flag(args, "skip");
+        }
+        opts
+    }
+}
+
+/// How a field type is stored.
+#[derive(Debug, Clone, PartialEq)]
+struct SqlType {
+    /// The `sqlDecoders` expression reading the column.
+    decoder: String,
+    /// The column type in the DDL.
+    ddl: &'static str,
+    /// The allowed values of a literal union.
+    choices: Vec<String>,
+}
+
+fn sql_type(ts_type: &str) -> SqlType {
+    let ts_type = strip_nullable(ts_type);
+    let ts_type = strip_parens(ts_type.trim());
+    let (decoder, ddl) = match ts_type {
+        "string" => ("string", "TEXT"),
+        "number" => ("number", "DOUBLE PRECISION"),
+        "bigint" => ("bigint", "BIGINT"),
+        "boolean" => ("boolean", "BOOLEAN"),
+        "Date" => ("date", "TIMESTAMPTZ"),
+        _ => {
+            let members = split_top_level(ts_type, '|');
+            let all_literals = !members.is_empty()
+                && members.iter().all(|m| {
+                    (m.starts_with('"') && m.ends_with('"'))
+                        || (m.starts_with('\'') && m.ends_with('\''))
+                });
+            if !all_literals {
+                return SqlType {
+                    decoder: "__mf_sqlDecoders.json".to_string(),
+                    ddl: "JSONB",
+                    choices: Vec::new(),
+                };
+            }
+            let choices = members
+                .iter()
+                .map(|m| m[1..m.len() - 1].to_string())
+                .collect::<Vec<_>>();
+            let values = choices.iter().map(|c| js_string(c)).collect::<Vec<_>>();
+            return SqlType {
+                decoder: format!("__mf_sqlDecoders.oneOf([{}])", values.join(", ")),
+                ddl: "TEXT",
+                choices,
+            };
+        }
+    };
+    SqlType {
+        decoder: format!("__mf_sqlDecoders.{decoder}"),
+        ddl,
+        choices: Vec::new(),
+    }
+}
+
+/// What `NULL` reads as, following the optionality of the field.
+fn missing_mode(ts_type: &str, optional: bool, nullable: bool) -> &'static str {
+    let members = split_top_level(ts_type, '|');
+    if members.contains(&"null") {
+        "nullable"
+    } else if optional || members.contains(&"undefined") {
+        "optional"
+    } else if nullable {
+        "nullable"
+    } else {
+        "required"
+    }
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Quotes an SQL identifier.
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quotes an SQL string literal.
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// A field to map.
+struct SqlField<'a> {
+    name: &'a str,
+    ts_type: &'a str,
+    optional: bool,
+    decorators: &'a [DecoratorIR],
+    span: SpanIR,
+}
+
+/// A stored field.
+#[derive(Debug, PartialEq)]
+struct Column {
+    field: String,
+    name: String,
+    primary_key: bool,
+    /// The column definition in the DDL.
+    definition: String,
+    /// Reads the column from `row`.
+    read: String,
+    /// The parameter for the field of `value`.
+    param: String,
+}
+
+fn column(
+    field: &SqlField<'_>,
+    rename_all: RenameAll,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<Column> {
+    let opts = ColumnOptions::from_decorators(field.decorators);
+    if opts.skip {
+        return None;
+    }
+    let name = opts
+        .name
+        .clone()
+        .unwrap_or_else(|| rename_all.apply(field.name));
+    let access = if is_identifier(field.name) {
+        format!("value.{}", field.name)
+    } else {
+        format!("value[{}]", js_string(field.name))
+    };
+    let missing = missing_mode(field.ts_type, field.optional, opts.nullable);
+
+    let mut sql_type = sql_type(field.ts_type);
+    let foreign_types = get_foreign_types();
+    let ft_match = TypeCategory::match_foreign_type(&strip_nullable(field.ts_type), &foreign_types);
+    if let Some(error) = ft_match.error {
+        diagnostics.error(field.span, error);
+    }
+    if let Some(warning) = ft_match.warning {
+        diagnostics.warning(field.span, warning);
+    }
+    let mut param = format!("__mf_sqlParam({access})");
+    if let Some(foreign) = ft_match.config {
+        sql_type.ddl = "TEXT";
+        sql_type.choices.clear();
+        if let Some(deserialize) = &foreign.deserialize_expr {
+            sql_type.decoder = format!(
+                "__mf_sqlDecoders.with({})",
+                rewrite_expression_namespaces(deserialize)
+            );
+        }
+        if let Some(serialize) = &foreign.serialize_expr {
+            let serialize = rewrite_expression_namespaces(serialize);
+            param = if missing == "required" {
+                format!("__mf_sqlParam(({serialize})({access}))")
+            } else {
+                format!("__mf_sqlParam({access} == null ? null : ({serialize})({access}))")
+            };
+        }
+    }
+
+    let mut definition = format!(
+        "{} {}",
+        quote_ident(&name),
+        opts.sql_type.as_deref().unwrap_or(sql_type.ddl)
+    );
+    if missing == "required" {
+        definition.push_str(" NOT NULL");
+    }
+    if !sql_type.choices.is_empty() {
+        let choices = sql_type
+            .choices
+            .iter()
+            .map(|c| quote_literal(c))
+            .collect::<Vec<_>>();
+        definition.push_str(&format!(
+            " CHECK ({} IN ({}))",
+            quote_ident(&name),
+            choices.join(", ")
+        ));
+    }
+    let missing_arg = if missing == "required" {
+        String::new()
+    } else {
+        format!(", \"{missing}\"")
+    };
+
+    Some(Column {
+        field: field.name.to_string(),
+        read: format!(
+            "__mf_readColumn(row, {}, {}{missing_arg})",
+            js_string(&name),
+            sql_type.decoder
+        ),
+        name,
+        primary_key: opts.primary_key,
+        definition,
+        param,
+    })
+}
+
+fn create_table_sql(table: &str, columns: &[Column]) -> String {
+    let mut definitions = columns
+        .iter()
+        .map(|c| format!("  {}", c.definition))
+        .collect::<Vec<_>>();
+    let keys = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| quote_ident(&c.name))
+        .collect::<Vec<_>>();
+    if !keys.is_empty() {
+        definitions.push(format!("  PRIMARY KEY ({})", keys.join(", ")));
+    }
+    format!(
+        "CREATE TABLE {} (\n{}\n)",
+        quote_ident(table),
+        definitions.join(",\n")
+    )
+}
+
+fn insert_sql(table: &str, columns: &[Column]) -> String {
+    let names = columns
+        .iter()
+        .map(|c| quote_ident(&c.name))
+        .collect::<Vec<_>>();
+    let placeholders = (1..=columns.len())
+        .map(|i| format!("${i}"))
+        .collect::<Vec<_>>();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote_ident(table),
+        names.join(", "),
+        placeholders.join(", ")
+    )
+}
+
+/// The `UPDATE` by primary key, or `None` without one. Placeholders follow
+/// the column order, like `INSERT`.
+fn update_sql(table: &str, columns: &[Column]) -> Option<String> {
+    let assignment = |(i, c): (usize, &Column)| format!("{} = ${}", quote_ident(&c.name), i + 1);
+    let keys = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.primary_key)
+        .map(assignment)
+        .collect::<Vec<_>>();
+    if keys.is_empty() {
+        return None;
+    }
+    let sets = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.primary_key)
+        .map(assignment)
+        .collect::<Vec<_>>();
+    Some(format!(
+        "UPDATE {} SET {} WHERE {}",
+        quote_ident(table),
+        sets.join(", "),
+        keys.join(" AND ")
+    ))
+}
+
+fn generate_sql_row_functions(
+    type_name: &str,
+    table: &str,
+    columns: &[Column],
+    construct: impl Fn(&str) -> String,
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let names = columns
+        .iter()
+        .map(|c| js_string(&c.name))
+        .collect::<Vec<_>>();
+    let keys = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| js_string(&c.name))
+        .collect::<Vec<_>>();
+    let properties = columns
+        .iter()
+        .map(|c| {
+            let key = if is_identifier(&c.field) {
+                c.field.clone()
+            } else {
+                js_string(&c.field)
+            };
+            format!("{key}: {}", c.read)
+        })
+        .collect::<Vec<_>>();
+    let params = columns.iter().map(|c| c.param.as_str()).collect::<Vec<_>>();
+    let value = construct(&format!("{{ {} }}", properties.join(", ")));
+    let update = update_sql(table, columns)
+        .map(|sql| {
+            format!(
+                "\n\n/** Updates every other column by primary key; use with `{prefix}ToParams`. */\nexport const {prefix}UpdateSql = {};",
+                js_string(&sql)
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"/** The table name. */
+export const {prefix}Table = {};
+
+/** The column names, in parameter order. */
+export const {prefix}Columns: readonly string[] = [{}];
+
+/** The primary key columns. */
+export const {prefix}PrimaryKey: readonly string[] = [{}];
+
+/** The `CREATE TABLE` statement. */
+export const {prefix}CreateTableSql = {};
+
+/** Inserts a row; use with `{prefix}ToParams`. */
+export const {prefix}InsertSql = {};{update}
+
+/**
+ * Reads a row returned by the database driver.
+ * @throws SqlRowError if a column is missing, unexpectedly `NULL` or invalid
+ */
+export function {prefix}FromRow(row: __mf_SqlRow): {type_name} {{
+    return {value} as {type_name};
+}}
+
+/** The query parameters, in column order. */
+export function {prefix}ToParams(value: {type_name}): unknown[] {{
+    return [{}];
+}}"#,
+        js_string(table),
+        names.join(", "),
+        keys.join(", "),
+        js_string(&create_table_sql(table, columns)),
+        js_string(&insert_sql(table, columns)),
+        params.join(", ")
+    )
+}
+
+/// Collects the stored fields, reporting duplicate column names.
+fn columns(
+    fields: &[SqlField<'_>],
+    rename_all: RenameAll,
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<Column> {
+    let mut columns: Vec<Column> = Vec::new();
+    for field in fields {
+        let Some(column) = column(field, rename_all, diagnostics) else {
+            continue;
+        };
+        if columns.iter().any(|c| c.name == column.name) {
+            diagnostics.error(
+                field.span,
+                format!(
+                    "@derive(SqlRow) column '{}' of field '{}' is already used by another field",
+                    column.name, field.name
+                ),
+            );
+        }
+        columns.push(column);
+    }
+    columns
+}
+
+#[ts_macro_derive(
+    SqlRow,
+    description = "Generates column lists, row conversions, parameters and DDL for database tables",
+    attributes(
+        (table, "Configure the table. Options: name, renameAll"),
+        (column, "Configure a column. Options: name, primaryKey, nullable, type, skip")
+    )
+)]
+pub fn derive_sql_row_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let table_opts = TableOptions::from_decorators(&container_decorators);
+    let table = table_opts
+        .name
+        .clone()
+        .unwrap_or_else(|| type_name.to_case(Case::Snake));
+    let rename_all = match &table_opts.rename_all {
+        Some(convention) => convention.parse::<RenameAll>().map_err(|_| {
+            MacroforgeError::new(
+                input.decorator_span(),
+                format!(
+                    "@table renameAll '{convention}' is not one of camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case or PascalCase"
+                ),
+            )
+        })?,
+        None => RenameAll::None,
+    };
+    let mut diagnostics = DiagnosticCollector::new();
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields = class
+                .fields()
+                .iter()
+                .map(|f| SqlField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let columns = columns(&fields, rename_all, &mut diagnostics);
+            let standalone =
+                generate_sql_row_functions(type_name, &table, &columns, |properties| {
+                    format!("Object.assign(Object.create({type_name}.prototype), {properties})")
+                });
+            let prefix = type_name.to_case(Case::Camel);
+            let from_row_fn = format!("{prefix}FromRow");
+            let to_params_fn = format!("{prefix}ToParams");
+            let class_body = body! {
+                static fromRow(row: __mf_SqlRow): @{type_name} {
+                    return @{from_row_fn}(row);
+                }
+
+                toParams(): unknown[] {
+                    return @{to_params_fn}(this);
+                }
+            };
+
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields = interface
+                .fields()
+                .iter()
+                .map(|f| SqlField {
+                    name: &f.name,
+                    ts_type: &f.ts_type,
+                    optional: f.optional,
+                    decorators: &f.decorators,
+                    span: f.span,
+                })
+                .collect::<Vec<_>>();
+            let columns = columns(&fields, rename_all, &mut diagnostics);
+            TsStream::from_string(generate_sql_row_functions(
+                type_name,
+                &table,
+                &columns,
+                str::to_string,
+            ))
+        }
+        Data::Enum(_) | Data::TypeAlias(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(SqlRow) can only be used on classes and interfaces",
+            ));
+        }
+    };
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+
+    output.add_aliased_import("readColumn", "macroforge/sql");
+    output.add_aliased_import("sqlDecoders", "macroforge/sql");
+    output.add_aliased_import("sqlParam", "macroforge/sql");
+    output.add_aliased_type_import("SqlRow", "macroforge/sql");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decorator(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "column".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn field<'a>(name: &'a str, ts_type: &'a str, decorators: &'a [DecoratorIR]) -> SqlField<'a> {
+        SqlField {
+            name,
+            ts_type,
+            optional: false,
+            decorators,
+            span: SpanIR::new(0, 0),
+        }
+    }
+
+    fn columns_of(fields: &[SqlField<'_>]) -> Vec<Column> {
+        let mut diagnostics = DiagnosticCollector::new();
+        let columns = columns(fields, RenameAll::SnakeCase, &mut diagnostics);
+        assert!(diagnostics.is_empty());
+        columns
+    }
+
+    #[test]
+    fn test_sql_type() {
+        assert_eq!(sql_type("bigint").decoder, "__mf_sqlDecoders.bigint");
+        assert_eq!(sql_type("Date | null").ddl, "TIMESTAMPTZ");
+        assert_eq!(
+            sql_type("\"active\" | \"banned\"").decoder,
+            "__mf_sqlDecoders.oneOf([\"active\", \"banned\"])"
+        );
+        assert_eq!(sql_type("string[]").ddl, "JSONB");
+        assert_eq!(sql_type("Address").decoder, "__mf_sqlDecoders.json");
+    }
+
+    #[test]
+    fn test_missing_mode() {
+        assert_eq!(missing_mode("string", false, false), "required");
+        assert_eq!(missing_mode("string", true, false), "optional");
+        assert_eq!(missing_mode("Date | null", false, false), "nullable");
+        assert_eq!(missing_mode("string", false, true), "nullable");
+    }
+
+    #[test]
+    fn test_column_definitions_and_reads() {
+        let key = [decorator("{ primaryKey: true }")];
+        let columns = columns_of(&[
+            field("id", "bigint", &key),
+            field("fullName", "string", &[]),
+            field("status", "\"active\" | \"banned\"", &[]),
+            field("deletedAt", "Date | null", &[]),
+        ]);
+        assert_eq!(
+            columns[1].read,
+            "__mf_readColumn(row, \"full_name\", __mf_sqlDecoders.string)"
+        );
+        assert_eq!(
+            columns[3].read,
+            "__mf_readColumn(row, \"deleted_at\", __mf_sqlDecoders.date, \"nullable\")"
+        );
+        assert_eq!(columns[3].param, "__mf_sqlParam(value.deletedAt)");
+        assert_eq!(
+            create_table_sql("users", &columns),
+            "CREATE TABLE \"users\" (\n  \"id\" BIGINT NOT NULL,\n  \"full_name\" TEXT NOT NULL,\n  \"status\" TEXT NOT NULL CHECK (\"status\" IN ('active', 'banned')),\n  \"deleted_at\" TIMESTAMPTZ,\n  PRIMARY KEY (\"id\")\n)"
+        );
+    }
+
+    #[test]
+    fn test_insert_and_update_share_parameters() {
+        let key = [decorator("{ primaryKey: true }")];
+        let columns = columns_of(&[field("name", "string", &[]), field("id", "number", &key)]);
+        assert_eq!(
+            insert_sql("users", &columns),
+            "INSERT INTO \"users\" (\"name\", \"id\") VALUES ($1, $2)"
+        );
+        assert_eq!(
+            update_sql("users", &columns).as_deref(),
+            Some("UPDATE \"users\" SET \"name\" = $1 WHERE \"id\" = $2")
+        );
+        let columns = columns_of(&[field("name", "string", &[])]);
+        assert_eq!(update_sql("users", &columns), None);
+    }
+
+    #[test]
+    fn test_column_options() {
+        let renamed = [decorator("{ name: \"created\", type: \"TIMESTAMP\" }")];
+        let skipped = [decorator("{ skip: true }")];
+        let columns = columns_of(&[
+            field("createdAt", "Date", &renamed),
+            field("cache", "Map<string, string>", &skipped),
+        ]);
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].name, "created");
+        assert_eq!(columns[0].definition, "\"created\" TIMESTAMP NOT NULL");
+    }
+
+    #[test]
+    fn test_duplicate_columns_are_reported() {
+        let renamed = [decorator("{ name: \"id\" }")];
+        let fields = [
+            field("id", "number", &[]),
+            field("legacyId", "number", &renamed),
+        ];
+        let mut diagnostics = DiagnosticCollector::new();
+        columns(&fields, RenameAll::None, &mut diagnostics);
+        assert!(diagnostics.has_errors());
+    }
+}
@@ -0,0 +1,479 @@
+//! # StateMachine Macro Implementation
+//!
+//! The `StateMachine` macro turns an enum of workflow states into a state
+//! machine. Each variant lists the states it may move to with a
+//! `@transition` decorator, so the rules live next to the states instead of
+//! in the services that change them.
+//!
+//! ## Generated Output
+//!
+//! For an enum `OrderStatus`:
+//!
+//! | Generated Code | Description |
+//! |----------------|-------------|
+//! | `orderStatusTransitions` | The allowed targets of every state |
+//! | `orderStatusCanTransition(from, to)` | Whether `from -> to` is allowed |
+//! | `orderStatusTransition(from, to)` | `{ success: true, value: to }` or `{ success: false, error }` |
+//! | `orderStatusAllowedFrom(state)` | The states reachable in one step |
+//! | `orderStatusMermaid` | A Mermaid `stateDiagram-v2` of the machine |
+//! | `orderStatusDot` | A Graphviz `digraph` of the machine |
+//!
+//! ## Decorators
+//!
+//! - `@transition({ to: ["Shipped", "Cancelled"] })` on a variant lists its
+//!   targets by variant name; `to: "Shipped"` is accepted for a single one.
+//!   A variant may carry several `@transition` decorators.
+//! - `@transition({ final: true })` marks a terminal state.
+//! - `@stateMachine({ initial: "Pending" })` on the enum names the initial
+//!   state; it defaults to the first variant.
+//!
+//! ## Validation
+//!
+//! Targets that are not variants of the enum are errors. States that cannot
+//! be reached from the initial state, and states with no transitions out
+//! that are not marked `final`, are reported as warnings at the variant's
+//! span.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(StateMachine) */
+//! enum OrderStatus {
+//!     /** @transition({ to: ["Shipped", "Cancelled"] }) */
+//!     Pending = "pending",
+//!     /** @transition({ to: "Delivered" }) */
+//!     Shipped = "shipped",
+//!     /** @transition({ final: true }) */
+//!     Delivered = "delivered",
+//!     /** @transition({ final: true }) */
+//!     Cancelled = "cancelled",
+//! }
+//!
+//! orderStatusCanTransition(OrderStatus.Pending, OrderStatus.Delivered); // false
+//! orderStatusAllowedFrom(OrderStatus.Pending); // [Shipped, Cancelled]
+//! ```
+
+use std::collections::VecDeque;
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::extract_exact_named_string;
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{DecoratorIR, DiagnosticCollector, EnumVariantIR, SpanIR};
+use crate::ts_syn::{
+    Data, DeriveInput, MacroforgeError, MacroforgeErrors, TsStream, parse_ts_macro_input,
+};
+
+/// Transitions declared on one variant with `@transition`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct TransitionOptions {
+    /// Target variant names, in declaration order.
+    pub to: Vec<String>,
+    /// The state is terminal; no warning for missing transitions.
+    pub is_final: bool,
+}
+
+impl TransitionOptions {
+    pub(crate) fn from_decorators(decorators: &[DecoratorIR]) -> Result<Self, String> {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("transition") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            for target in extract_targets(args)? {
+                if !opts.to.contains(&target) {
+                    opts.to.push(target);
+                }
+            }
+            let condensed: String = args.chars().filter(|c| !c.is_whitespace()).collect();
+            opts.is_final |= condensed.contains("final:true");
+        }
+        Ok(opts)
+    }
+}
+
+/// Reads the `to` option: a string or an array of strings.
+fn extract_targets(args: &str) -> Result<Vec<String>, String> {
+    if let Some(target) = extract_exact_named_string(args, "to") {
+        return Ok(vec![target]);
+    }
+    let Some(list) = find_to_array(args) else {
+        return Ok(Vec::new());
+    };
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let unquoted = item
+                .strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .or_else(|| item.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')));
+            unquoted.map(str::to_string).ok_or_else(|| {
+                format!("@transition targets must be variant names as strings, got {item}")
+            })
+        })
+        .collect()
+}
+
+/// Returns the contents of the brackets in `to: [...]`.
+fn find_to_array(args: &str) -> Option<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let mut search = 0;
+    while let Some(pos) = args[search..].find("to") {
+        let start = search + pos;
+        let end = start + 2;
+        search = end;
+        if args[..start].chars().next_back().is_some_and(is_ident)
+            || args[end..].chars().next().is_some_and(is_ident)
+        {
+            continue;
+        }
+        let rest = args[end..].trim_start();
+        let Some(rest) = rest.strip_prefix(':').or_else(|| rest.strip_prefix('=')) else {
+            continue;
+        };
+        let inner = rest.trim_start().strip_prefix('[')?;
+        return inner.find(']').map(|close| &inner[..close]);
+    }
+    None
+}
+
+/// One state of the machine.
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    name: String,
+    span: SpanIR,
+    /// Indices of the target states.
+    targets: Vec<usize>,
+    is_final: bool,
+}
+
+/// Resolves the `@transition` decorators of every variant, reporting
+/// targets that are not variants.
+fn resolve_states(
+    enum_name: &str,
+    variants: &[EnumVariantIR],
+    diagnostics: &mut DiagnosticCollector,
+) -> Vec<State> {
+    let mut states = Vec::new();
+    for variant in variants {
+        let opts = match TransitionOptions::from_decorators(&variant.decorators) {
+            Ok(opts) => opts,
+            Err(message) => {
+                diagnostics.error(variant.span, message);
+                TransitionOptions::default()
+            }
+        };
+        let mut targets = Vec::new();
+        for target in &opts.to {
+            match variants.iter().position(|v| v.name == *target) {
+                Some(index) => targets.push(index),
+                None => diagnostics.error(
+                    variant.span,
+                    format!(
+                        "@transition on '{}' targets '{target}', which is not a variant of {enum_name}",
+                        variant.name
+                    ),
+                ),
+            }
+        }
+        states.push(State {
+            name: variant.name.clone(),
+            span: variant.span,
+            targets,
+            is_final: opts.is_final,
+        });
+    }
+    states
+}
+
+/// Warns about states that cannot be reached from `initial` and states
+/// that cannot be left without being marked `final`.
+fn check_reachability(states: &[State], initial: usize, diagnostics: &mut DiagnosticCollector) {
+    let mut reachable = vec![false; states.len()];
+    reachable[initial] = true;
+    let mut queue = VecDeque::from([initial]);
+    while let Some(index) = queue.pop_front() {
+        for &target in &states[index].targets {
+            if !reachable[target] {
+                reachable[target] = true;
+                queue.push_back(target);
+            }
+        }
+    }
+
+    let initial_name = &states[initial].name;
+    for (state, reachable) in states.iter().zip(reachable) {
+        if !reachable {
+            diagnostics.warning(
+                state.span,
+                format!(
+                    "state '{}' is unreachable from the initial state '{initial_name}'",
+                    state.name
+                ),
+            );
+        }
+        if state.targets.is_empty() && !state.is_final {
+            diagnostics.warning(
+                state.span,
+                format!(
+                    "state '{}' has no transitions out; mark it with @transition({{ final: true }}) if it is terminal",
+                    state.name
+                ),
+            );
+        }
+    }
+}
+
+fn generate_state_machine(enum_name: &str, states: &[State], initial: usize) -> String {
+    let prefix = enum_name.to_case(Case::Camel);
+    let member = |index: usize| format!("{enum_name}.{}", states[index].name);
+    let entries = (0..states.len())
+        .map(|index| {
+            let targets = states[index]
+                .targets
+                .iter()
+                .map(|&t| member(t))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("    [{}]: [{targets}],", member(index))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mermaid = js_string(&mermaid_diagram(states, initial));
+    let dot = js_string(&dot_diagram(enum_name, states));
+
+    format!(
+        r#"export const {prefix}Transitions: Readonly<Record<{enum_name}, readonly {enum_name}[]>> = {{
+{entries}
+}};
+
+export function {prefix}AllowedFrom(state: {enum_name}): readonly {enum_name}[] {{
+    return {prefix}Transitions[state] ?? [];
+}}
+
+export function {prefix}CanTransition(from: {enum_name}, to: {enum_name}): boolean {{
+    return {prefix}AllowedFrom(from).includes(to);
+}}
+
+export function {prefix}Transition(from: {enum_name}, to: {enum_name}): {{ success: true; value: {enum_name} }} | {{ success: false; error: string }} {{
+    if ({prefix}CanTransition(from, to)) {{
+        return {{ success: true, value: to }};
+    }}
+    return {{ success: false, error: `{enum_name} cannot transition from ${{String(from)}} to ${{String(to)}}` }};
+}}
+
+export const {prefix}Mermaid: string = {mermaid};
+
+export const {prefix}Dot: string = {dot};"#
+    )
+}
+
+fn mermaid_diagram(states: &[State], initial: usize) -> String {
+    let mut lines = vec![
+        "stateDiagram-v2".to_string(),
+        format!("    [*] --> {}", states[initial].name),
+    ];
+    for state in states {
+        for &target in &state.targets {
+            lines.push(format!("    {} --> {}", state.name, states[target].name));
+        }
+        if state.is_final {
+            lines.push(format!("    {} --> [*]", state.name));
+        }
+    }
+    lines.join("\n")
+}
+
+fn dot_diagram(enum_name: &str, states: &[State]) -> String {
+    let mut lines = vec![format!("digraph {} {{", js_string(enum_name))];
+    for state in states {
+        let shape = if state.is_final {
+            "doublecircle"
+        } else {
+            "circle"
+        };
+        lines.push(format!("    {} [shape={shape}];", js_string(&state.name)));
+    }
+    for state in states {
+        for &target in &state.targets {
+            lines.push(format!(
+                "    {} -> {};",
+                js_string(&state.name),
+                js_string(&states[target].name)
+            ));
+        }
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[ts_macro_derive(
+    StateMachine,
+    description = "Generates transition checks and diagrams for enums of workflow states",
+    attributes(
+        (transition, "Allowed transitions of a variant. Options: to (variant name or array of names), final"),
+        (stateMachine, "Configure the machine. Options: initial (variant name, defaults to the first variant)")
+    )
+)]
+pub fn derive_state_machine_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let enum_name = input.name();
+
+    let Data::Enum(enum_data) = &input.data else {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            "@derive(StateMachine) can only be used on enums",
+        ));
+    };
+    let variants = enum_data.variants();
+    if variants.is_empty() {
+        return Err(MacroforgeError::new(
+            input.decorator_span(),
+            format!("@derive(StateMachine) on enum '{enum_name}' requires at least one variant"),
+        ));
+    }
+
+    let initial_name = input
+        .attrs
+        .iter()
+        .map(|a| &a.inner)
+        .filter(|d| d.name.eq_ignore_ascii_case("stateMachine"))
+        .find_map(|d| extract_exact_named_string(d.args_src.trim(), "initial"));
+    let initial = match initial_name {
+        Some(name) => variants
+            .iter()
+            .position(|v| v.name == name)
+            .ok_or_else(|| {
+                MacroforgeError::new(
+                    input.decorator_span(),
+                    format!("@stateMachine initial state '{name}' is not a variant of {enum_name}"),
+                )
+            })?,
+        None => 0,
+    };
+
+    let mut diagnostics = DiagnosticCollector::new();
+    let states = resolve_states(enum_name, variants, &mut diagnostics);
+    if diagnostics.has_errors() {
+        return Err(MacroforgeErrors::new(diagnostics.into_vec()).into());
+    }
+    check_reachability(&states, initial, &mut diagnostics);
+
+    let mut output = TsStream::from_string(generate_state_machine(enum_name, &states, initial));
+    output.add_diagnostics(diagnostics.into_vec());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::EnumValue;
+
+    fn transition(args: &str) -> DecoratorIR {
+        DecoratorIR {
+            name: "transition".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        }
+    }
+
+    fn variant(name: &str, args: Option<&str>, start: u32) -> EnumVariantIR {
+        EnumVariantIR {
+            name: name.into(),
+            span: SpanIR::new(start, start + 1),
+            value: EnumValue::Auto,
+            decorators: args.map(transition).into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_transition_options() {
+        let opts = TransitionOptions::from_decorators(&[
+            transition("{ to: [\"Shipped\", 'Cancelled'] }"),
+            transition("{ to: \"Shipped\" }"),
+        ])
+        .unwrap();
+        assert_eq!(opts.to, ["Shipped", "Cancelled"]);
+        assert!(!opts.is_final);
+
+        let opts = TransitionOptions::from_decorators(&[transition("{ final: true }")]).unwrap();
+        assert!(opts.to.is_empty());
+        assert!(opts.is_final);
+
+        assert!(TransitionOptions::from_decorators(&[transition("{ to: [Shipped] }")]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_states_reports_unknown_targets() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let states = resolve_states(
+            "OrderStatus",
+            &[
+                variant("Pending", Some("{ to: [\"Shipped\", \"Lost\"] }"), 0),
+                variant("Shipped", Some("{ final: true }"), 10),
+            ],
+            &mut diagnostics,
+        );
+        assert_eq!(states[0].targets, [1]);
+        let diagnostics = diagnostics.into_vec();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("targets 'Lost'"));
+    }
+
+    #[test]
+    fn test_check_reachability_warns_at_variant_spans() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let states = resolve_states(
+            "OrderStatus",
+            &[
+                variant("Pending", Some("{ to: \"Shipped\" }"), 0),
+                variant("Shipped", None, 10),
+                variant("Archived", Some("{ final: true }"), 20),
+            ],
+            &mut diagnostics,
+        );
+        check_reachability(&states, 0, &mut diagnostics);
+        assert!(!diagnostics.has_errors());
+        let diagnostics = diagnostics.into_vec();
+        let starts = diagnostics
+            .iter()
+            .map(|d| d.span.unwrap().start)
+            .collect::<Vec<_>>();
+        assert_eq!(starts, [10, 20]);
+        assert!(diagnostics[0].message.contains("no transitions out"));
+        assert!(diagnostics[1].message.contains("unreachable"));
+    }
+
+    #[test]
+    fn test_generate_state_machine() {
+        let mut diagnostics = DiagnosticCollector::new();
+        let states = resolve_states(
+            "OrderStatus",
+            &[
+                variant("Pending", Some("{ to: [\"Shipped\", \"Cancelled\"] }"), 0),
+                variant("Shipped", Some("{ final: true }"), 10),
+                variant("Cancelled", Some("{ final: true }"), 20),
+            ],
+            &mut diagnostics,
+        );
+        let code = generate_state_machine("OrderStatus", &states, 0);
+        assert!(
+            code.contains("[OrderStatus.Pending]: [OrderStatus.Shipped, OrderStatus.Cancelled],")
+        );
+        assert!(code.contains("[OrderStatus.Shipped]: [],"));
+        assert!(code.contains(
+            "export function orderStatusCanTransition(from: OrderStatus, to: OrderStatus): boolean"
+        ));
+        assert!(code.contains(
+            r#"export const orderStatusMermaid: string = "stateDiagram-v2\n    [*] --> Pending\n    Pending --> Shipped\n    Pending --> Cancelled\n    Shipped --> [*]\n    Cancelled --> [*]";"#
+        ));
+        assert!(code.contains(r#"\n    \"Pending\" -> \"Shipped\";"#));
+    }
+}
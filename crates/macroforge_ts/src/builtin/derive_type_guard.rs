@@ -0,0 +1,667 @@
+//! # TypeGuard Macro Implementation
+//!
+//! The `TypeGuard` macro generates cheap structural type predicates, such as
+//! `isUser(value): value is User`, for narrowing `unknown` values. Unlike the
+//! `is` functions generated by `Deserialize`, these never construct or
+//! allocate anything: they only check that fields are present and have the
+//! right primitive types.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `isClassName(value)` | Structural check of the class fields |
+//! | Interface | `isInterfaceName(value)` | Structural check of the interface fields |
+//! | Type Alias | `isTypeName(value)` | Object fields, union members, tuple elements or aliased type |
+//!
+//! Enums are not supported; `Deserialize` already generates `enumNameIs` and
+//! `EnumUtils` generates `isEnumName`. Classes don't get a static wrapper
+//! because `Deserialize` owns `static is`.
+//!
+//! ## Checks
+//!
+//! Field types are classified like `Deserialize` does:
+//!
+//! | Type | Check |
+//! |------|-------|
+//! | `string`, `number`, `boolean`, `bigint` | `typeof` |
+//! | Literals, `null`, `undefined` | `===` |
+//! | Unions, optional fields | Any member matches |
+//! | `Date`, `Map`, `Set` | `instanceof` |
+//! | Arrays | `Array.isArray` |
+//! | Function types | `typeof` |
+//! | Records, other named types | Non-null object |
+//!
+//! With `deep`, array, set, map and record elements are checked too, and
+//! named types are checked with their own `is*` guard (which they are then
+//! assumed to derive, like `Default`). Elements are checked by module-level
+//! helpers such as `__isUser0`, which loop over the collection and stop at the
+//! first mismatch; object types inside unions get a helper of their own.
+//!
+//! ## Container Options
+//!
+//! - `deep` - Recurse into nested types and collection elements
+//! - `strict` - Reject objects with enumerable keys that are not fields of the type
+//!
+//! ## Field-Level Options
+//!
+//! - `skip` - Don't check the field
+//! - `deep` - Recurse for this field only (`deep: false` opts out)
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(TypeGuard) @typeGuard({ strict: true }) */
+//! interface User {
+//!     id: number;
+//!     role: "admin" | "member";
+//!     nickname?: string;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export function isUser(value: unknown): value is User {
+//!     if (typeof value !== "object" || value === null || Array.isArray(value)) {
+//!         return false;
+//!     }
+//!     const o = value as Record<string, unknown>;
+//!     for (const key in o) {
+//!         switch (key) {
+//!             case "id":
+//!             case "role":
+//!             case "nickname":
+//!                 break;
+//!             default:
+//!                 return false;
+//!         }
+//!     }
+//!     return "id" in o && typeof o.id === "number"
+//!         && "role" in o && (o.role === "admin" || o.role === "member")
+//!         && (o.nickname === undefined || typeof o.nickname === "string");
+//! }
+//! ```
+
+use crate::builtin::derive_common::{has_flag, split_top_level, strip_parens};
+use crate::builtin::serde::TypeCategory;
+use crate::macros::ts_macro_derive;
+use crate::ts_syn::abi::{DecoratorIR, InterfaceFieldIR, TypeMemberKind};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// Condition that `value` is a non-null object.
+fn object_check(value: &str) -> String {
+    format!("typeof {value} === \"object\" && {value} !== null")
+}
+
+/// Encodes `s` as a JavaScript string literal.
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// `o.name`, or `o["name"]` when the name is not an identifier.
+fn property_access(object: &str, name: &str) -> String {
+    let identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        format!("{object}.{name}")
+    } else {
+        format!("{object}[{}]", js_string(name))
+    }
+}
+
+/// Options parsed from `@typeGuard` decorators.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TypeGuardOptions {
+    skip: bool,
+    strict: bool,
+    /// `Some` when set explicitly, so fields can override the container.
+    deep: Option<bool>,
+}
+
+impl TypeGuardOptions {
+    fn from_decorators(decorators: &[DecoratorIR]) -> Self {
+        let mut opts = Self::default();
+        for decorator in decorators {
+            if !decorator.name.eq_ignore_ascii_case("typeGuard") {
+                continue;
+            }
+            let args = decorator.args_src.trim();
+            opts.skip |= has_flag(args, "skip");
+            opts.strict |= has_flag(args, "strict");
+            if args.contains("deep") {
+                opts.deep = Some(has_flag(args, "deep"));
+            }
+        }
+        opts
+    }
+}
+
+/// Module-level functions backing the checks that need a loop.
+///
+/// Collection elements and inline object types are checked by helpers like
+/// `__isUser0(value)`, so that a check stays a single expression without
+/// allocating callbacks or copies on every call.
+struct Helpers {
+    prefix: String,
+    functions: Vec<String>,
+}
+
+impl Helpers {
+    fn new(type_name: &str) -> Self {
+        Self {
+            prefix: format!("__is{type_name}"),
+            functions: Vec::new(),
+        }
+    }
+
+    /// Adds a helper with the given body and returns a call of it on `value`.
+    fn call(&mut self, value: &str, body: String) -> String {
+        let name = format!("{}{}", self.prefix, self.functions.len());
+        self.functions.push(format!(
+            "function {name}(value: unknown): boolean {{\n{body}\n}}"
+        ));
+        format!("{name}({value})")
+    }
+
+    /// A helper returning whether `value` is a collection, i.e. `mismatch` is
+    /// false, and every element visited by the `for` loop `head` satisfies
+    /// `check`.
+    fn every(&mut self, value: &str, mismatch: &str, head: &str, check: &str) -> String {
+        self.call(
+            value,
+            format!(
+                r#"    if ({mismatch}) {{
+        return false;
+    }}
+    for ({head}) {{
+        if (!({check})) {{
+            return false;
+        }}
+    }}
+    return true;"#
+            ),
+        )
+    }
+
+    /// The helpers, in the order they were added.
+    fn source(&self) -> String {
+        self.functions.iter().map(|f| format!("\n\n{f}")).collect()
+    }
+}
+
+/// The condition that `value` is a `ts_type`.
+fn type_check(ts_type: &str, value: &str, deep: bool, helpers: &mut Helpers) -> String {
+    let ts_type = strip_parens(ts_type);
+
+    // Unions are checked member by member; `TypeCategory` only separates out
+    // `null`/`undefined` and would treat a union of string literals as one literal
+    let members = split_top_level(ts_type, '|');
+    if members.len() > 1 {
+        let checks = members
+            .iter()
+            .map(|member| type_check(member, value, deep, helpers))
+            .collect::<Vec<_>>();
+        return format!("({})", checks.join(" || "));
+    }
+
+    // `TypeCategory` only recognizes string literals
+    if matches!(ts_type, "true" | "false") || ts_type.parse::<f64>().is_ok() {
+        return format!("{value} === {ts_type}");
+    }
+    // Function types, e.g. `(x: number) => void`
+    if ts_type.starts_with(['(', '<']) && ts_type.contains("=>") && !ts_type.ends_with("[]") {
+        return format!("typeof {value} === \"function\"");
+    }
+    if ts_type.starts_with('{') || ts_type == "object" {
+        return format!("({})", object_check(value));
+    }
+    if let Some(elements) = ts_type
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .filter(|_| !ts_type.ends_with("[]"))
+    {
+        let elements = split_top_level(elements, ',');
+        let mut checks = vec![
+            format!("Array.isArray({value})"),
+            format!("{value}.length === {}", elements.len()),
+        ];
+        if deep {
+            checks.extend(
+                elements.iter().enumerate().map(|(i, element)| {
+                    type_check(element, &format!("{value}[{i}]"), deep, helpers)
+                }),
+            );
+        }
+        return format!("({})", checks.join(" && "));
+    }
+
+    match TypeCategory::from_ts_type(ts_type) {
+        TypeCategory::Primitive => match ts_type {
+            "string" | "number" | "boolean" | "bigint" => {
+                format!("typeof {value} === \"{ts_type}\"")
+            }
+            literal => format!("{value} === {literal}"),
+        },
+        TypeCategory::Date => format!("{value} instanceof Date"),
+        TypeCategory::Array(inner) => {
+            if deep {
+                let check = type_check(&inner, "item", deep, helpers);
+                helpers.every(
+                    value,
+                    "!Array.isArray(value)",
+                    "const item of value",
+                    &check,
+                )
+            } else {
+                format!("Array.isArray({value})")
+            }
+        }
+        TypeCategory::Set(inner) => {
+            if deep {
+                let check = type_check(&inner, "item", deep, helpers);
+                helpers.every(
+                    value,
+                    "!(value instanceof Set)",
+                    "const item of value",
+                    &check,
+                )
+            } else {
+                format!("{value} instanceof Set")
+            }
+        }
+        TypeCategory::Map(key, inner) => {
+            if deep {
+                let check = format!(
+                    "{} && {}",
+                    type_check(&key, "key", deep, helpers),
+                    type_check(&inner, "item", deep, helpers)
+                );
+                helpers.every(
+                    value,
+                    "!(value instanceof Map)",
+                    "const [key, item] of value",
+                    &check,
+                )
+            } else {
+                format!("{value} instanceof Map")
+            }
+        }
+        TypeCategory::Record(_, inner) => {
+            if deep {
+                let check = type_check(
+                    &inner,
+                    "(value as Record<string, unknown>)[key]",
+                    deep,
+                    helpers,
+                );
+                helpers.every(
+                    value,
+                    r#"typeof value !== "object" || value === null || Array.isArray(value)"#,
+                    "const key in value",
+                    &check,
+                )
+            } else {
+                format!("({} && !Array.isArray({value}))", object_check(value))
+            }
+        }
+        TypeCategory::Optional(inner) => {
+            format!(
+                "({value} === undefined || {})",
+                type_check(&inner, value, deep, helpers)
+            )
+        }
+        TypeCategory::Nullable(inner) => {
+            format!(
+                "({value} === null || {})",
+                type_check(&inner, value, deep, helpers)
+            )
+        }
+        TypeCategory::Serializable(name) => {
+            let base = name.split('<').next().unwrap_or(&name).trim();
+            if deep && !name.contains('<') {
+                format!("is{base}({value})")
+            } else {
+                format!("({})", object_check(value))
+            }
+        }
+        TypeCategory::Wrapper(_) => format!("({})", object_check(value)),
+        TypeCategory::Unknown => "true".to_string(),
+    }
+}
+
+/// A field to check: name, type, optional, decorators.
+type GuardField<'a> = (&'a str, &'a str, bool, &'a [DecoratorIR]);
+
+/// The body of a function checking that `value` is a non-array object with
+/// `fields`.
+fn object_guard_body<'a>(
+    fields: impl IntoIterator<Item = GuardField<'a>>,
+    container: &TypeGuardOptions,
+    helpers: &mut Helpers,
+) -> String {
+    let mut names = Vec::new();
+    let mut checks = Vec::new();
+    for (name, ts_type, optional, decorators) in fields {
+        // `#private` fields are not properties
+        if name.starts_with('#') {
+            continue;
+        }
+        names.push(js_string(name));
+        let opts = TypeGuardOptions::from_decorators(decorators);
+        if opts.skip {
+            continue;
+        }
+        let deep = opts.deep.or(container.deep).unwrap_or(false);
+        let access = property_access("o", name);
+        let check = type_check(ts_type, &access, deep, helpers);
+        checks.push(if optional {
+            format!("({access} === undefined || {check})")
+        } else {
+            format!("{} in o && {check}", js_string(name))
+        });
+    }
+
+    let mut body = String::from(
+        r#"    if (typeof value !== "object" || value === null || Array.isArray(value)) {
+        return false;
+    }
+    const o = value as Record<string, unknown>;
+"#,
+    );
+    if container.strict {
+        let cases: String = names
+            .iter()
+            .map(|name| format!("            case {name}:\n"))
+            .collect();
+        let fields_break = if cases.is_empty() {
+            String::new()
+        } else {
+            format!("{cases}                break;\n")
+        };
+        body.push_str(&format!(
+            "    for (const key in o) {{\n        switch (key) {{\n{fields_break}            default:\n                return false;\n        }}\n    }}\n"
+        ));
+    }
+    let check = if checks.is_empty() {
+        "true".to_string()
+    } else {
+        checks.join("\n        && ")
+    };
+    body.push_str(&format!("    return {check};"));
+    body
+}
+
+fn interface_fields(fields: &[InterfaceFieldIR]) -> impl Iterator<Item = GuardField<'_>> {
+    fields.iter().map(|f| {
+        (
+            f.name.as_str(),
+            f.ts_type.as_str(),
+            f.optional,
+            f.decorators.as_slice(),
+        )
+    })
+}
+
+fn generate_object_guard(type_name: &str, body: &str) -> String {
+    format!("export function is{type_name}(value: unknown): value is {type_name} {{\n{body}\n}}")
+}
+
+fn generate_value_guard(type_name: &str, check: &str) -> String {
+    format!(
+        "export function is{type_name}(value: unknown): value is {type_name} {{\n    return {check};\n}}"
+    )
+}
+
+#[ts_macro_derive(
+    TypeGuard,
+    description = "Generates structural isType(value) type guards",
+    attributes((typeGuard, "Configure the guard. Container options: strict, deep. Field options: skip, deep"))
+)]
+pub fn derive_type_guard_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let container_decorators = input
+        .attrs
+        .iter()
+        .map(|a| a.inner.clone())
+        .collect::<Vec<DecoratorIR>>();
+    let container = TypeGuardOptions::from_decorators(&container_decorators);
+    let deep = container.deep.unwrap_or(false);
+    let mut helpers = Helpers::new(type_name);
+
+    let guard = match &input.data {
+        Data::Class(class) => {
+            let body = object_guard_body(
+                class.fields().iter().map(|f| {
+                    (
+                        f.name.as_str(),
+                        f.ts_type.as_str(),
+                        f.optional,
+                        f.decorators.as_slice(),
+                    )
+                }),
+                &container,
+                &mut helpers,
+            );
+            generate_object_guard(type_name, &body)
+        }
+        Data::Interface(interface) => {
+            let body = object_guard_body(
+                interface_fields(interface.fields()),
+                &container,
+                &mut helpers,
+            );
+            generate_object_guard(type_name, &body)
+        }
+        Data::Enum(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(TypeGuard) does not support enums; use the enumNameIs guard from Deserialize or isEnumName from EnumUtils",
+            ));
+        }
+        Data::TypeAlias(type_alias) => {
+            if let Some(fields) = type_alias.as_object() {
+                let body = object_guard_body(interface_fields(fields), &container, &mut helpers);
+                generate_object_guard(type_name, &body)
+            } else if let Some(members) = type_alias.as_union() {
+                let checks = members
+                    .iter()
+                    .map(|member| match &member.kind {
+                        TypeMemberKind::Literal(literal) => format!("value === {literal}"),
+                        TypeMemberKind::TypeRef(ts_type) => {
+                            type_check(ts_type, "value", deep, &mut helpers)
+                        }
+                        TypeMemberKind::Object { fields } => {
+                            let body = object_guard_body(
+                                interface_fields(fields),
+                                &container,
+                                &mut helpers,
+                            );
+                            helpers.call("value", body)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                generate_value_guard(type_name, &checks.join("\n        || "))
+            } else if let Some(elements) = type_alias.as_tuple() {
+                let tuple = format!("[{}]", elements.join(", "));
+                // Tuple elements are the whole point of a tuple alias, so always check them
+                generate_value_guard(type_name, &type_check(&tuple, "value", true, &mut helpers))
+            } else if let Some(aliased) = type_alias.as_alias() {
+                generate_value_guard(type_name, &type_check(aliased, "value", deep, &mut helpers))
+            } else {
+                return Err(MacroforgeError::new(
+                    input.decorator_span(),
+                    format!(
+                        "@derive(TypeGuard) cannot generate a guard for intersection type '{type_name}'"
+                    ),
+                ));
+            }
+        }
+    };
+
+    Ok(TsStream::from_string(format!(
+        "{guard}{}",
+        helpers.source()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    /// `type_check` on `v`, followed by the helpers it added.
+    fn check(ts_type: &str, deep: bool) -> String {
+        let mut helpers = Helpers::new("T");
+        let check = type_check(ts_type, "v", deep, &mut helpers);
+        format!("{check}{}", helpers.source())
+    }
+
+    #[test]
+    fn test_primitive_and_literal_checks() {
+        assert_eq!(check("string", false), "typeof v === \"string\"");
+        assert_eq!(
+            check("\"a\" | \"b\"", false),
+            "(v === \"a\" || v === \"b\")"
+        );
+        assert_eq!(
+            check("number | null", false),
+            "(typeof v === \"number\" || v === null)"
+        );
+        assert_eq!(check("Date", false), "v instanceof Date");
+        assert_eq!(check("1 | true", false), "(v === 1 || v === true)");
+    }
+
+    #[test]
+    fn test_function_union_members() {
+        assert_eq!(
+            check("((x: number) => void) | null", false),
+            "(typeof v === \"function\" || v === null)"
+        );
+        assert_eq!(
+            check("(() => string) | string", false),
+            "(typeof v === \"function\" || typeof v === \"string\")"
+        );
+    }
+
+    #[test]
+    fn test_deep_checks_recurse() {
+        assert_eq!(check("Address[]", false), "Array.isArray(v)");
+        assert_eq!(
+            check("Address[]", true),
+            r#"__isT0(v)
+
+function __isT0(value: unknown): boolean {
+    if (!Array.isArray(value)) {
+        return false;
+    }
+    for (const item of value) {
+        if (!(isAddress(item))) {
+            return false;
+        }
+    }
+    return true;
+}"#
+        );
+        assert_eq!(
+            check("Address", false),
+            "(typeof v === \"object\" && v !== null)"
+        );
+        assert_eq!(
+            check("[string, number]", true),
+            "(Array.isArray(v) && v.length === 2 && typeof v[0] === \"string\" && typeof v[1] === \"number\")"
+        );
+    }
+
+    #[test]
+    fn test_deep_collections_loop_without_copying() {
+        let set = check("Set<string>", true);
+        assert!(set.starts_with("__isT0(v)"));
+        assert!(set.contains("if (!(value instanceof Set))"));
+        assert!(set.contains("for (const item of value)"));
+
+        let map = check("Map<string, Address[]>", true);
+        assert!(map.starts_with("__isT1(v)"));
+        assert!(map.contains("for (const [key, item] of value)"));
+        assert!(map.contains("if (!(typeof key === \"string\" && __isT0(item)))"));
+
+        let record = check("Record<string, number>", true);
+        assert!(record.contains("for (const key in value)"));
+        assert!(record.contains("typeof (value as Record<string, unknown>)[key] === \"number\""));
+
+        for source in [set, map, record] {
+            assert!(!source.contains("..."), "{source}");
+            assert!(!source.contains(".every("), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_fields_check() {
+        let skip = DecoratorIR {
+            name: "typeGuard".into(),
+            args_src: "{ skip: true }".into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        let container = TypeGuardOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let body = object_guard_body(
+            [
+                ("id", "number", false, &[][..]),
+                ("nickname", "string", true, &[][..]),
+                (
+                    "cache",
+                    "Map<string, number>",
+                    false,
+                    std::slice::from_ref(&skip),
+                ),
+            ],
+            &container,
+            &mut Helpers::new("T"),
+        );
+        assert_eq!(
+            body,
+            r#"    if (typeof value !== "object" || value === null || Array.isArray(value)) {
+        return false;
+    }
+    const o = value as Record<string, unknown>;
+    for (const key in o) {
+        switch (key) {
+            case "id":
+            case "nickname":
+            case "cache":
+                break;
+            default:
+                return false;
+        }
+    }
+    return "id" in o && typeof o.id === "number"
+        && (o.nickname === undefined || typeof o.nickname === "string");"#
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let decorator = |args: &str| DecoratorIR {
+            name: "typeGuard".into(),
+            args_src: args.into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        let opts = TypeGuardOptions::from_decorators(&[decorator("{ deep: false, strict: true }")]);
+        assert_eq!(opts.deep, Some(false));
+        assert!(opts.strict);
+        assert_eq!(TypeGuardOptions::from_decorators(&[]).deep, None);
+    }
+
+    #[test]
+    fn test_property_access() {
+        assert_eq!(property_access("o", "id"), "o.id");
+        assert_eq!(property_access("o", "content-type"), "o[\"content-type\"]");
+    }
+}
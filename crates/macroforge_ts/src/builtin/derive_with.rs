@@ -0,0 +1,391 @@
+//! # With Macro Implementation
+//!
+//! The `With` macro generates immutable copy-on-write updaters. Instead of
+//! writing `{ ...state, user: { ...state.user, name } }` by hand, each field
+//! gets a `withField(value, next)` function that returns an updated copy.
+//!
+//! ## Generated Output
+//!
+//! | Type | Generated Code | Description |
+//! |------|----------------|-------------|
+//! | Class | `classNameWith*`, `classNameUpdate`, `classNameSetIn` + static wrappers | Standalone functions + static wrapper methods |
+//! | Interface | `interfaceNameWith*`, `interfaceNameUpdate`, `interfaceNameSetIn` | Standalone functions |
+//! | Type Alias | `typeNameWith*`, `typeNameUpdate`, `typeNameSetIn` | Standalone functions (object types only) |
+//!
+//! For a type `User`, the generated functions are:
+//!
+//! - `userWith(value, patch)` - Copy with a `Partial<User>` merged in
+//! - `userWithName(value, next)` - One per field; copy with that field replaced
+//! - `userUpdate(value, fn)` - Copy with the patch returned by `fn(value)` merged in
+//! - `userSetIn(value, path, next)` - Copy with a nested value replaced, e.g.
+//!   `userSetIn(u, ["address", "city"], "Berlin")`
+//!
+//! `setIn` paths and values are type-checked with the `Path` and `PathValue`
+//! types from `macroforge/with`; only the objects along the path are copied.
+//!
+//! For a generic type such as `Box<T>`, every function and static wrapper
+//! declares the type's parameters, e.g. `boxWith<T>(value: Box<T>, ...)`.
+//!
+//! ## Copy Strategy
+//!
+//! - **Classes**: A new instance is created with
+//!   `Object.create(Object.getPrototypeOf(value))` and every field is copied,
+//!   exactly like the `Clone` macro, so `instanceof` checks keep working
+//! - **Interfaces/Type Aliases**: A spread-based copy (`{ ...value, ...patch }`)
+//!
+//! Copies are shallow: unchanged fields are shared with the original.
+//!
+//! ## Field-Level Options
+//!
+//! The `@with` decorator supports:
+//!
+//! - `skip` - Don't generate a `with*` updater for the field
+//!
+//! Non-public class fields are copied but get no updater.
+//!
+//! ## Example
+//!
+//! ```typescript
+//! /** @derive(With) */
+//! interface User {
+//!     name: string;
+//!     address: Address;
+//! }
+//! ```
+//!
+//! Generated output:
+//!
+//! ```typescript
+//! export function userWith(value: User, patch: Partial<User>): User {
+//!     return { ...value, ...patch };
+//! }
+//!
+//! export function userWithName(value: User, next: User["name"]): User {
+//!     return userWith(value, { name: next });
+//! }
+//!
+//! export function userWithAddress(value: User, next: User["address"]): User {
+//!     return userWith(value, { address: next });
+//! }
+//!
+//! export function userUpdate(value: User, fn: (value: Readonly<User>) => Partial<User>): User {
+//!     return userWith(value, fn(value));
+//! }
+//!
+//! export function userSetIn<P extends __mf_Path<User>>(
+//!     value: User,
+//!     path: P,
+//!     next: __mf_PathValue<User, P>
+//! ): User {
+//!     const [key, ...rest] = path as unknown as [keyof User & string, ...PropertyKey[]];
+//!     return userWith(value, { [key]: __mf_setPath(value[key], rest, next) } as Partial<User>);
+//! }
+//! ```
+
+use convert_case::{Case, Casing};
+
+use crate::builtin::derive_common::{TypeParams, has_flag};
+use crate::macros::{body, ts_macro_derive};
+use crate::ts_syn::abi::{DecoratorIR, Visibility};
+use crate::ts_syn::{Data, DeriveInput, MacroforgeError, TsStream, parse_ts_macro_input};
+
+/// A field that can be updated through the generated functions.
+struct WithField {
+    name: String,
+    /// Whether a `with*` updater is generated for this field
+    updater: bool,
+}
+
+impl WithField {
+    fn new(name: &str, decorators: &[DecoratorIR], public: bool) -> Self {
+        let skip = decorators
+            .iter()
+            .filter(|d| d.name.eq_ignore_ascii_case("with"))
+            .any(|d| has_flag(d.args_src.trim(), "skip"));
+        Self {
+            name: name.to_string(),
+            updater: public && !skip,
+        }
+    }
+
+    /// The updater name, e.g. `withName` for `name`.
+    fn method_name(&self) -> String {
+        format!("with{}", self.name.to_case(Case::Pascal))
+    }
+}
+
+/// How `*With` copies the value before merging the patch.
+enum CopyStrategy {
+    /// `Object.create(Object.getPrototypeOf(value))` plus a field-by-field copy
+    Class,
+    /// `{ ...value, ...patch }`
+    Spread,
+}
+
+/// Generates the standalone updater functions for `type_name`.
+///
+/// The functions over a generic type declare its type parameters, e.g.
+/// `boxWith<T>(value: Box<T>, patch: Partial<Box<T>>): Box<T>`.
+fn generate_with_functions(
+    type_name: &str,
+    type_params: &TypeParams,
+    fields: &[WithField],
+    strategy: CopyStrategy,
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let with_fn = format!("{prefix}With");
+    let decl = type_params.fn_decl(&[]);
+    let target = type_params.apply(type_name);
+
+    let with_body = match strategy {
+        CopyStrategy::Class => {
+            let copies = fields
+                .iter()
+                .map(|f| format!("\n    next.{name} = value.{name};", name = f.name))
+                .collect::<String>();
+            format!(
+                "const next = Object.create(Object.getPrototypeOf(value));{copies}\n    return Object.assign(next, patch);"
+            )
+        }
+        CopyStrategy::Spread => "return { ...value, ...patch };".to_string(),
+    };
+
+    let mut out = vec![format!(
+        "export function {with_fn}{decl}(value: {target}, patch: Partial<{target}>): {target} {{\n    {with_body}\n}}"
+    )];
+
+    for field in fields.iter().filter(|f| f.updater) {
+        out.push(format!(
+            "export function {prefix}{method}{decl}(value: {target}, next: {target}[\"{name}\"]): {target} {{\n    return {with_fn}(value, {{ {name}: next }});\n}}",
+            method = field.method_name().to_case(Case::Pascal),
+            name = field.name,
+        ));
+    }
+
+    out.push(format!(
+        "export function {prefix}Update{decl}(value: {target}, fn: (value: Readonly<{target}>) => Partial<{target}>): {target} {{\n    return {with_fn}(value, fn(value));\n}}"
+    ));
+
+    out.push(format!(
+        "export function {prefix}SetIn{set_in_decl}(value: {target}, path: P, next: __mf_PathValue<{target}, P>): {target} {{
+    const [key, ...rest] = path as unknown as [keyof {target} & string, ...PropertyKey[]];
+    return {with_fn}(value, {{ [key]: __mf_setPath(value[key], rest, next) }} as Partial<{target}>);
+}}",
+        set_in_decl = type_params.fn_decl(&[&format!("P extends __mf_Path<{target}>")]),
+    ));
+
+    out.join("\n\n")
+}
+
+/// Generates the static wrapper methods that delegate to the standalone functions.
+///
+/// Static members can't use the class's type parameters, so each wrapper of
+/// a generic class declares them itself.
+fn generate_static_wrappers(
+    type_name: &str,
+    type_params: &TypeParams,
+    fields: &[WithField],
+) -> String {
+    let prefix = type_name.to_case(Case::Camel);
+    let decl = type_params.fn_decl(&[]);
+    let target = type_params.apply(type_name);
+
+    let mut methods = vec![format!(
+        "static with{decl}(value: {target}, patch: Partial<{target}>): {target} {{ return {prefix}With(value, patch); }}"
+    )];
+    for field in fields.iter().filter(|f| f.updater) {
+        let method = field.method_name();
+        methods.push(format!(
+            "static {method}{decl}(value: {target}, next: {target}[\"{name}\"]): {target} {{ return {prefix}{pascal}(value, next); }}",
+            name = field.name,
+            pascal = method.to_case(Case::Pascal),
+        ));
+    }
+    methods.push(format!(
+        "static update{decl}(value: {target}, fn: (value: Readonly<{target}>) => Partial<{target}>): {target} {{ return {prefix}Update(value, fn); }}"
+    ));
+    methods.push(format!(
+        "static setIn{set_in_decl}(value: {target}, path: P, next: __mf_PathValue<{target}, P>): {target} {{ return {prefix}SetIn(value, path, next); }}",
+        set_in_decl = type_params.fn_decl(&[&format!("P extends __mf_Path<{target}>")]),
+    ));
+    methods.join("\n")
+}
+
+/// Adds the `macroforge/with` runtime imports used by `setIn`.
+fn add_with_imports(stream: &mut TsStream) {
+    stream.add_aliased_import("setPath", "macroforge/with");
+    stream.add_aliased_type_import("Path", "macroforge/with");
+    stream.add_aliased_type_import("PathValue", "macroforge/with");
+}
+
+#[ts_macro_derive(
+    With,
+    description = "Generates immutable copy-on-write updaters (with*, update, setIn)",
+    attributes((with, "Configure updaters. Options: skip"))
+)]
+pub fn derive_with_macro(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+    let input = parse_ts_macro_input!(input as DeriveInput);
+    let type_name = input.name();
+    let (type_params, _) = TypeParams::declared(&input.context.target_source, type_name);
+
+    let mut output = match &input.data {
+        Data::Class(class) => {
+            let fields: Vec<WithField> = class
+                .fields()
+                .iter()
+                .map(|f| WithField::new(&f.name, &f.decorators, f.visibility == Visibility::Public))
+                .collect();
+
+            let standalone =
+                generate_with_functions(type_name, &type_params, &fields, CopyStrategy::Class);
+            let wrappers = generate_static_wrappers(type_name, &type_params, &fields);
+            let class_body = body! {
+                @{wrappers}
+            };
+
+            // The standalone output (no marker) must come FIRST so it defaults to "below" (after class)
+            let combined_source = format!("{}\n{}", standalone, class_body.source());
+            let mut combined = TsStream::from_string(combined_source);
+            combined.runtime_patches = class_body.runtime_patches;
+            combined
+        }
+        Data::Interface(interface) => {
+            let fields: Vec<WithField> = interface
+                .fields()
+                .iter()
+                .map(|f| WithField::new(&f.name, &f.decorators, true))
+                .collect();
+            TsStream::from_string(generate_with_functions(
+                type_name,
+                &type_params,
+                &fields,
+                CopyStrategy::Spread,
+            ))
+        }
+        Data::TypeAlias(type_alias) if type_alias.is_object() => {
+            let fields: Vec<WithField> = type_alias
+                .as_object()
+                .unwrap()
+                .iter()
+                .map(|f| WithField::new(&f.name, &f.decorators, true))
+                .collect();
+            TsStream::from_string(generate_with_functions(
+                type_name,
+                &type_params,
+                &fields,
+                CopyStrategy::Spread,
+            ))
+        }
+        Data::TypeAlias(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                format!(
+                    "@derive(With) on type '{type_name}' requires an object type; unions and other aliases have no fields to update"
+                ),
+            ));
+        }
+        Data::Enum(_) => {
+            return Err(MacroforgeError::new(
+                input.decorator_span(),
+                "@derive(With) can only be used on classes, interfaces and object type aliases",
+            ));
+        }
+    };
+
+    add_with_imports(&mut output);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_syn::abi::SpanIR;
+
+    fn fields(names: &[&str]) -> Vec<WithField> {
+        names.iter().map(|n| WithField::new(n, &[], true)).collect()
+    }
+
+    #[test]
+    fn test_method_name() {
+        let field = WithField::new("createdAt", &[], true);
+        assert_eq!(field.method_name(), "withCreatedAt");
+    }
+
+    #[test]
+    fn test_skip_and_private_fields_have_no_updater() {
+        let skip = DecoratorIR {
+            name: "with".into(),
+            args_src: "skip".into(),
+            span: SpanIR::new(0, 0),
+            node: None,
+        };
+        assert!(!WithField::new("cache", &[skip], true).updater);
+        assert!(!WithField::new("secret", &[], false).updater);
+    }
+
+    #[test]
+    fn test_spread_functions() {
+        let code = generate_with_functions(
+            "User",
+            &TypeParams::default(),
+            &fields(&["name"]),
+            CopyStrategy::Spread,
+        );
+        assert!(code.contains("return { ...value, ...patch };"));
+        assert!(code.contains(
+            "export function userWithName(value: User, next: User[\"name\"]): User {\n    return userWith(value, { name: next });"
+        ));
+        assert!(code.contains("export function userUpdate("));
+        assert!(code.contains("export function userSetIn<P extends __mf_Path<User>>("));
+        assert!(code.contains("__mf_setPath(value[key], rest, next)"));
+    }
+
+    #[test]
+    fn test_generic_functions_declare_type_params() {
+        let (type_params, _) =
+            TypeParams::declared("interface Box<T extends object = {}> {}", "Box");
+        let code = generate_with_functions(
+            "Box",
+            &type_params,
+            &fields(&["value"]),
+            CopyStrategy::Spread,
+        );
+        assert!(code.contains(
+            "export function boxWith<T extends object>(value: Box<T>, patch: Partial<Box<T>>): Box<T> {"
+        ));
+        assert!(code.contains(
+            "export function boxWithValue<T extends object>(value: Box<T>, next: Box<T>[\"value\"]): Box<T> {"
+        ));
+        assert!(code.contains(
+            "export function boxSetIn<T extends object, P extends __mf_Path<Box<T>>>(value: Box<T>, path: P, next: __mf_PathValue<Box<T>, P>): Box<T> {"
+        ));
+
+        let wrappers = generate_static_wrappers("Box", &type_params, &fields(&["value"]));
+        assert!(wrappers.contains(
+            "static withValue<T extends object>(value: Box<T>, next: Box<T>[\"value\"]): Box<T> {"
+        ));
+    }
+
+    #[test]
+    fn test_class_copy_preserves_prototype() {
+        let code = generate_with_functions(
+            "Point",
+            &TypeParams::default(),
+            &fields(&["x", "y"]),
+            CopyStrategy::Class,
+        );
+        assert!(code.contains("const next = Object.create(Object.getPrototypeOf(value));"));
+        assert!(code.contains("next.x = value.x;"));
+        assert!(code.contains("next.y = value.y;"));
+        assert!(code.contains("return Object.assign(next, patch);"));
+    }
+
+    #[test]
+    fn test_static_wrappers() {
+        let code = generate_static_wrappers("Point", &TypeParams::default(), &fields(&["x"]));
+        assert!(code.contains("static with(value: Point, patch: Partial<Point>): Point"));
+        assert!(code.contains("static withX(value: Point, next: Point[\"x\"]): Point { return pointWithX(value, next); }"));
+        assert!(code.contains("static update("));
+        assert!(code.contains("return pointSetIn(value, path, next);"));
+    }
+}
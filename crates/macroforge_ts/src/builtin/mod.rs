@@ -32,6 +32,22 @@
 //! | Macro | Generated Method | Description |
 //! |-------|------------------|-------------|
 //! | `Default` | `static default(): T` | Factory method with default values |
+//! | `Builder` | `static builder(): TBuilder` | Typed fluent builder with required-field tracking |
+//!
+//! ### Immutable Updates
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `With` | `withField(value, next): T`, `update`, `setIn` | Copy-on-write updaters |
+//! | `Diff` | `static diff(a, b)`, `static applyPatch(value, patch)` | Change lists and RFC 6902 JSON Patch |
+//!
+//! ### Enums
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `EnumUtils` | `values()`, `names()`, `fromString`, `isX`, `next`/`prev` | Helpers for enums and literal unions |
+//! | `Bitflags` | `has`, `add`, `remove`, `toggle`, `toArray`, `fromArray`, `format` | Flag sets for numeric enums |
+//! | `StateMachine` | `canTransition`, `transition`, `allowedFrom`, Mermaid/DOT diagrams | Workflow transitions declared on enum variants |
 //!
 //! ### Serialization (Serde)
 //!
@@ -39,6 +55,73 @@
 //! |-------|------------------|-------------|
 //! | `Serialize` | `toJSON(): Record<string, unknown>` | JSON serialization |
 //! | `Deserialize` | `static fromJSON(json): T` | JSON deserialization with validation |
+//! | `Csv` | `static toCsv(rows)`, `static parseCsv(text)`, `toCsvRow`/`fromCsvRow` | RFC 4180 rows with dotted columns for nested types |
+//!
+//! ### Type Guards
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `TypeGuard` | `isT(value): value is T` | Allocation-free structural runtime checks |
+//! | `Branded` | `T.parse(raw)`, `T.unsafe(raw)`, `T.is(raw)` | Nominal types from primitive aliases |
+//!
+//! ### Reflection
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `Reflect` | `tReflection`, `tReflect(): TypeReflection<T>` | Frozen runtime metadata for fields, methods and decorators |
+//!
+//! ### Services
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `HttpClient` | `class TClient implements T`, `tDispatcher(impl)` | Typed fetch client and server dispatcher from `@route` methods |
+//! | `GraphQL` | `tGraphQL`, `tGraphQLFields()`, `TResolvers<TContext>` | GraphQL SDL and resolver types |
+//!
+//! ### Configuration
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `Env` | `tLoadFromEnv(env?)`, `static loadFromEnv(env?)` | Typed, validated configuration from environment variables |
+//! | `CliArgs` | `tCli`, `tParseArgs(argv?)`, `static parseArgs(argv?)` | Command-line parsers with help text and subcommands |
+//!
+//! ### Persistence
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `SqlRow` | `tColumns`, `tFromRow(row)`, `tToParams(value)`, `tCreateTableSql` | Database row mapping, parameters and DDL |
+//!
+//! ### Testing
+//!
+//! | Macro | Generated Method | Description |
+//! |-------|------------------|-------------|
+//! | `Arbitrary` | `static arbitrary(rng): T` | Seeded, validator-aware random values for property tests |
+//! | `Mock` | `class TMock implements T` | Recording, stubbable test doubles for interfaces |
+//!
+//! ### Attribute Macros
+//!
+//! Applied to a single method (`@trace`) or top-level function (`/** @trace */`
+//! with `trace` imported from `macroforge`) and rewrite its body. See
+//! [`attributes`] for details.
+//!
+//! | Macro | Effect |
+//! |-------|--------|
+//! | `deprecated` | Warns once when called |
+//! | `memoize` | Caches results by argument values |
+//! | `retry` | Re-runs the body when it throws, with optional delay and backoff |
+//! | `trace` | Logs calls, results and errors |
+//!
+//! ### Call Macros
+//!
+//! Compile-time intrinsics imported from `macroforge` and replaced by their
+//! result at the call site. See [`calls`] for details.
+//!
+//! | Macro | Effect |
+//! |-------|--------|
+//! | `includeStr` | Inlines a file as a string literal |
+//! | `includeJson` | Inlines a JSON file as a literal |
+//! | `buildEnv` | Inlines a build-time environment variable |
+//! | `buildInfo` | Inlines the package name, version and build time |
+//! | `concatIdents` | Joins parts into one identifier |
 //!
 //! ## Field-Level Decorators
 //!
@@ -73,21 +156,63 @@
 //! }
 //! ```
 
+/// Attribute macros (deprecated, memoize, retry, trace).
+mod attributes;
+
+/// Call macros (includeStr, includeJson, buildEnv, buildInfo, concatIdents).
+mod calls;
+
+/// Arbitrary macro implementation (property-test generators).
+mod derive_arbitrary;
+
+/// Bitflags macro implementation (flag sets for numeric enums).
+mod derive_bitflags;
+
+/// Branded macro implementation (nominal types with validated constructors).
+mod derive_branded;
+
+/// Builder macro implementation (fluent builder).
+mod derive_builder;
+
+/// CliArgs macro implementation (command-line parsers).
+mod derive_cli_args;
+
 /// Clone macro implementation (deep copy).
 pub mod derive_clone;
 
 /// Shared utilities for comparison macros.
 mod derive_common;
 
+/// Csv macro implementation (CSV rows and documents).
+mod derive_csv;
+
 /// Debug macro implementation (toString).
 mod derive_debug;
 
+/// Diff macro implementation (change lists and JSON Patch).
+mod derive_diff;
+
 /// Default macro implementation (factory method).
 mod derive_default;
 
+/// EnumUtils macro implementation (iteration, parsing and type guards).
+mod derive_enum_utils;
+
+/// Env macro implementation (typed configuration from environment variables).
+mod derive_env;
+
+/// GraphQL macro implementation (SDL and resolver types).
+mod derive_graphql;
+
 /// Hash macro implementation (hashCode).
 pub mod derive_hash;
 
+/// HttpClient macro implementation (RPC clients and dispatchers).
+mod derive_http_client;
+
+/// Mock macro implementation (test doubles for interfaces).
+mod derive_mock;
+
 /// Ord macro implementation (total ordering).
 mod derive_ord;
 
@@ -97,6 +222,21 @@ pub mod derive_partial_eq;
 /// PartialOrd macro implementation (partial ordering).
 mod derive_partial_ord;
 
+/// Reflect macro implementation (runtime type metadata).
+mod derive_reflect;
+
+/// SqlRow macro implementation (database row mapping and DDL).
+mod derive_sql_row;
+
+/// StateMachine macro implementation (workflow transitions for enums).
+mod derive_state_machine;
+
+/// TypeGuard macro implementation (structural type predicates).
+mod derive_type_guard;
+
+/// With macro implementation (immutable updaters).
+mod derive_with;
+
 /// Serialization macros (Serialize, Deserialize).
 pub mod serde;
 
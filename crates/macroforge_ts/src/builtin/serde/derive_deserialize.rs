@@ -327,6 +327,7 @@ use super::{
     SerdeContainerOptions, SerdeFieldOptions, TypeCategory, Validator, ValidatorSpec,
     get_foreign_types, rewrite_expression_namespaces,
 };
+use crate::builtin::derive_common::derives_macro;
 use crate::builtin::return_types::{
     DESERIALIZE_CONTEXT, DESERIALIZE_ERROR, DESERIALIZE_OPTIONS, PENDING_REF,
     deserialize_return_type, is_ok_check, wrap_error, wrap_success,
@@ -663,7 +664,7 @@ pub fn generate_validation_condition(validator: &Validator, value_var: &str) ->
 /// - `Validator::Email` → "must be a valid email"
 /// - `Validator::MaxLength(100)` → "must have at most 100 characters"
 /// - `Validator::Between(1, 10)` → "must be between 1 and 10"
-fn get_validator_message(validator: &Validator) -> String {
+pub(crate) fn get_validator_message(validator: &Validator) -> String {
     match validator {
         Validator::Email => "must be a valid email".to_string(),
         Validator::Url => "must be a valid URL".to_string(),
@@ -1409,12 +1410,56 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
             combined.runtime_patches.extend(result.runtime_patches);
             Ok(combined)
         }
-        Data::Enum(_) => {
+        Data::Enum(enum_data) => {
             let enum_name = input.name();
             let fn_deserialize = format!("{}Deserialize", enum_name.to_case(Case::Camel));
             let fn_deserialize_internal =
                 format!("{}DeserializeWithContext", enum_name.to_case(Case::Camel));
             let fn_is = format!("{}Is", enum_name.to_case(Case::Camel));
+
+            // Bitflags enums accept any combination of flags, as a number or
+            // as an array of flag names
+            if derives_macro(&enum_data.inner.decorators, "Bitflags") {
+                let fn_to_array = format!("{}ToArray", enum_name.to_case(Case::Camel));
+                let fn_from_array = format!("{}FromArray", enum_name.to_case(Case::Camel));
+                let mut result = ts_template! {
+                    {>> "Deserializes input to a flag set.\nAccepts a number or an array of flag names, as a JSON string or value.\n@param input - JSON string or value to deserialize\n@returns The flag set\n@throws Error if the input contains unknown flags" <<}
+                    export function @{fn_deserialize}(input: unknown): @{enum_name} {
+                        const data = typeof input === "string" ? JSON.parse(input) : input;
+                        return @{fn_deserialize_internal}(data);
+                    }
+
+                    {>> "Deserializes with an existing context (for consistency with other types)." <<}
+                    export function @{fn_deserialize_internal}(data: unknown): @{enum_name} {
+                        let value: unknown = data;
+                        if (Array.isArray(data)) {
+                            let flags = 0;
+                            for (const name of data) {
+                                const flag = typeof name === "string" ? @{enum_name}[name as keyof typeof @{enum_name}] : undefined;
+                                if (typeof flag !== "number") {
+                                    throw new Error("Invalid @{enum_name} flag: " + JSON.stringify(name));
+                                }
+                                flags |= flag;
+                            }
+                            value = flags;
+                        }
+                        if (@{fn_is}(value)) {
+                            return value;
+                        }
+                        throw new Error("Invalid @{enum_name} value: " + JSON.stringify(data));
+                    }
+
+                    export function @{fn_is}(value: unknown): value is @{enum_name} {
+                        return typeof value === "number"
+                            && Number.isInteger(value)
+                            && @{fn_from_array}(@{fn_to_array}(value)) === value;
+                    }
+                };
+
+                result.add_aliased_import("DeserializeContext", "macroforge/serde");
+                return Ok(result);
+            }
+
             let mut result = ts_template! {
                 {>> "Deserializes input to an enum value.\nAutomatically detects whether input is a JSON string or value.\n@param input - JSON string or value to deserialize\n@returns The enum value\n@throws Error if the value is not a valid enum member" <<}
                 export function @{fn_deserialize}(input: unknown): @{enum_name} {
@@ -2681,6 +2726,38 @@ pub fn derive_deserialize_macro(mut input: TsStream) -> Result<TsStream, Macrofo
                 result.add_aliased_type_import("DeserializeOptions", "macroforge/serde");
                 result.add_aliased_import("PendingRef", "macroforge/serde");
                 Ok(result)
+            } else if derives_macro(&type_alias.inner.decorators, "Branded") {
+                // Branded aliases are checked by the brand's parse, which also
+                // provides the `Is` guard
+                let fn_deserialize = format!("{}Deserialize", type_name.to_case(Case::Camel));
+                let fn_deserialize_internal = nested_deserialize_fn_name(type_name);
+                let fn_parse = format!("{}Parse", type_name.to_case(Case::Camel));
+                let return_type = deserialize_return_type(type_name);
+                let error_generic_message = wrap_error(r#"[{ field: "_root", message }]"#);
+
+                let mut result = ts_template! {
+                    {>> "Deserializes input to this branded type.\nAutomatically detects whether input is a JSON string or value.\n@param input - JSON string or value to deserialize\n@returns Result containing the branded value or validation errors" <<}
+                    export function @{fn_deserialize}(input: unknown): @{return_type} {
+                        try {
+                            return @{fn_parse}(typeof input === "string" ? JSON.parse(input) : input);
+                        } catch (e) {
+                            const message = e instanceof Error ? e.message : String(e);
+                            return @{error_generic_message};
+                        }
+                    }
+
+                    {>> "Deserializes with an existing context (for consistency with other types).\n@throws DeserializeError if the value is not a valid brand" <<}
+                    export function @{fn_deserialize_internal}(value: unknown, _ctx?: @{DESERIALIZE_CONTEXT}): @{type_name} {
+                        const result = @{fn_parse}(value);
+                        if (!result.success) {
+                            throw new @{DESERIALIZE_ERROR}(result.errors);
+                        }
+                        return result.value;
+                    }
+                };
+                result.add_aliased_import("DeserializeContext", "macroforge/serde");
+                result.add_aliased_import("DeserializeError", "macroforge/serde");
+                Ok(result)
             } else {
                 // Fallback for other type alias forms (simple alias, tuple, etc.)
                 let (
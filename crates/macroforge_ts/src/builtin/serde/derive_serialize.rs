@@ -148,6 +148,8 @@ use super::{
     SerdeContainerOptions, SerdeFieldOptions, TypeCategory, get_foreign_types,
     rewrite_expression_namespaces,
 };
+use crate::builtin::derive_bitflags::BitflagsOptions;
+use crate::builtin::derive_common::derives_macro;
 use crate::builtin::return_types::SERIALIZE_CONTEXT;
 
 fn nested_serialize_fn_name(type_name: &str) -> String {
@@ -913,13 +915,33 @@ pub fn derive_serialize_macro(mut input: TsStream) -> Result<TsStream, Macroforg
 
             Ok(combined)
         }
-        Data::Enum(_) => {
+        Data::Enum(enum_data) => {
             // Enums: return the underlying value directly
             let enum_name = input.name();
 
             let fn_name = format!("{}Serialize", enum_name.to_case(Case::Camel));
             let fn_name_internal =
                 format!("{}SerializeWithContext", enum_name.to_case(Case::Camel));
+
+            // Bitflags enums can serialize as the names of their set flags
+            let decorators = &enum_data.inner.decorators;
+            if derives_macro(decorators, "Bitflags")
+                && BitflagsOptions::from_decorators(decorators).serialize_names
+            {
+                let fn_to_array = format!("{}ToArray", enum_name.to_case(Case::Camel));
+                return Ok(ts_template! {
+                    {>> "Serializes this flag set to a JSON array of flag names." <<}
+                    export function @{fn_name}(value: @{enum_name}): string {
+                        return JSON.stringify(@{fn_to_array}(value).map((flag) => @{enum_name}[flag]));
+                    }
+
+                    {>> "Serializes with an existing context for nested/cyclic object graphs." <<}
+                    export function @{fn_name_internal}(value: @{enum_name}, _ctx: @{SERIALIZE_CONTEXT}): string[] {
+                        return @{fn_to_array}(value).map((flag) => @{enum_name}[flag]);
+                    }
+                });
+            }
+
             Ok(ts_template! {
                 {>> "Serializes this enum value to a JSON string." <<}
                 export function @{fn_name}(value: @{enum_name}): string {
@@ -24,6 +24,8 @@
 //! export default {
 //!   keepDecorators: false,
 //!   generateConvenienceConst: true,
+//!   externalMacroWorkers: 4,
+//!   externalMacroTimeout: 10000,
 //!   foreignTypes: {
 //!     "DateTime.DateTime": {
 //!       from: ["effect"],
@@ -305,6 +307,20 @@ pub struct MacroforgeConfig {
     #[serde(default)]
     pub foreign_types: Vec<ForeignTypeConfig>,
 
+    /// Number of Node.js worker processes that run external macros.
+    ///
+    /// When `None` (default), the number of available CPUs is used, up to 4.
+    /// Workers are started on demand and keep macro packages loaded between files.
+    #[serde(default)]
+    pub external_macro_workers: Option<usize>,
+
+    /// How long an external macro may run, in milliseconds.
+    ///
+    /// When `None` (default), 5000 ms. The time a worker takes to load the
+    /// macro package is not counted.
+    #[serde(default)]
+    pub external_macro_timeout: Option<u64>,
+
     /// Import sources from the config file itself.
     ///
     /// Maps imported names (e.g., "DateTime", "Option") to their import info
@@ -325,6 +341,8 @@ impl Default for MacroforgeConfig {
             keep_decorators: false,
             generate_convenience_const: true, // Default to true
             foreign_types: Vec::new(),
+            external_macro_workers: None,
+            external_macro_timeout: None,
             config_imports: HashMap::new(),
         }
     }
@@ -622,6 +640,16 @@ fn parse_config_object(
                         config.foreign_types = parse_foreign_types(ft_obj, imports, cm)?;
                     }
                 }
+                "externalMacroWorkers" => {
+                    config.external_macro_workers = get_number_value(&kv.value)
+                        .filter(|n| n.fract() == 0.0 && *n >= 1.0)
+                        .map(|n| n as usize);
+                }
+                "externalMacroTimeout" => {
+                    config.external_macro_timeout = get_number_value(&kv.value)
+                        .filter(|n| n.fract() == 0.0 && *n >= 1.0)
+                        .map(|n| n as u64);
+                }
                 _ => {}
             }
         }
@@ -799,6 +827,14 @@ fn get_bool_value(expr: &Expr) -> Option<bool> {
     }
 }
 
+/// Extract number value from expression.
+fn get_number_value(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Lit(Lit::Num(n)) => Some(n.value),
+        _ => None,
+    }
+}
+
 /// Extract string or array of strings from expression.
 fn extract_string_or_array(expr: &Expr) -> Vec<String> {
     match expr {
@@ -1115,9 +1151,18 @@ impl Default for MacroConfig {
 
 impl From<MacroforgeConfig> for MacroConfig {
     fn from(cfg: MacroforgeConfig) -> Self {
+        let mut limits = ResourceLimits::default();
+        if let Some(workers) = cfg.external_macro_workers {
+            limits.max_external_workers = workers;
+        }
+        if let Some(timeout) = cfg.external_macro_timeout {
+            limits.max_execution_time_ms = timeout;
+        }
+
         MacroConfig {
             keep_decorators: cfg.keep_decorators,
             generate_convenience_const: cfg.generate_convenience_const,
+            limits,
             ..Default::default()
         }
     }
@@ -1167,6 +1212,10 @@ pub struct ResourceLimits {
     /// Maximum number of diagnostics a single macro can emit.
     #[serde(default = "default_max_diagnostics")]
     pub max_diagnostics: usize,
+
+    /// Maximum number of Node.js worker processes running external macros.
+    #[serde(default = "default_max_external_workers")]
+    pub max_external_workers: usize,
 }
 
 impl Default for ResourceLimits {
@@ -1176,6 +1225,7 @@ impl Default for ResourceLimits {
             max_memory_bytes: default_max_memory(),
             max_output_size: default_max_output_size(),
             max_diagnostics: default_max_diagnostics(),
+            max_external_workers: default_max_external_workers(),
         }
     }
 }
@@ -1196,6 +1246,10 @@ fn default_max_diagnostics() -> usize {
     100
 }
 
+fn default_max_external_workers() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get().min(4))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1290,11 +1344,37 @@ mod tests {
             keep_decorators: true,
             generate_convenience_const: false,
             foreign_types: vec![],
+            external_macro_workers: Some(2),
+            external_macro_timeout: Some(30000),
             config_imports: HashMap::new(),
         };
 
         let legacy: MacroConfig = mf_config.into();
         assert!(legacy.keep_decorators);
         assert!(!legacy.generate_convenience_const);
+        assert_eq!(legacy.limits.max_external_workers, 2);
+        assert_eq!(legacy.limits.max_execution_time_ms, 30000);
+    }
+
+    #[test]
+    fn test_parse_external_macro_workers() {
+        let content = "export default { externalMacroWorkers: 2 }";
+        let config = MacroforgeConfig::from_config_file(content, "macroforge.config.js").unwrap();
+        assert_eq!(config.external_macro_workers, Some(2));
+
+        let content = "export default { externalMacroWorkers: 0 }";
+        let config = MacroforgeConfig::from_config_file(content, "macroforge.config.js").unwrap();
+        assert_eq!(config.external_macro_workers, None);
+    }
+
+    #[test]
+    fn test_parse_external_macro_timeout() {
+        let content = "export default { externalMacroTimeout: 30000 }";
+        let config = MacroforgeConfig::from_config_file(content, "macroforge.config.js").unwrap();
+        assert_eq!(config.external_macro_timeout, Some(30000));
+
+        let content = "export default { externalMacroTimeout: 0 }";
+        let config = MacroforgeConfig::from_config_file(content, "macroforge.config.js").unwrap();
+        assert_eq!(config.external_macro_timeout, None);
     }
 }
@@ -323,6 +323,19 @@ pub fn macro_names() -> Vec<&'static str> {
         .collect()
 }
 
+/// Returns the names of all macros of one kind registered in this binary.
+///
+/// Used for the cheap pre-parse check in `expandSync`, since attribute and
+/// call macros are written as `@name` and `name(...)` rather than inside
+/// `@derive(...)`.
+pub fn macro_names_of_kind(kind: MacroKind) -> Vec<&'static str> {
+    inventory::iter::<DerivedMacroRegistration>
+        .into_iter()
+        .filter(|entry| entry.descriptor.kind == kind)
+        .map(|entry| entry.descriptor.name)
+        .collect()
+}
+
 /// Looks up a macro by name only, ignoring the module path.
 ///
 /// This is used for dynamic module resolution when the import path
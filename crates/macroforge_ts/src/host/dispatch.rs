@@ -182,6 +182,7 @@ impl MacroDispatcher {
                         }],
                         tokens: None,
                         debug: None,
+                        dependencies: vec![],
                     };
                 }
 
@@ -204,6 +205,7 @@ impl MacroDispatcher {
                                 }],
                                 tokens: None,
                                 debug: None,
+                                dependencies: vec![],
                             };
                         }
                     };
@@ -236,6 +238,7 @@ impl MacroDispatcher {
                             }],
                             tokens: None,
                             debug: None,
+                            dependencies: vec![],
                         }
                     }
                 }
@@ -257,6 +260,7 @@ impl MacroDispatcher {
                     }],
                     tokens: None,
                     debug: None,
+                    dependencies: vec![],
                 }
             }
         }
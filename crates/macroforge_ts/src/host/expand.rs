@@ -81,17 +81,22 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
 
 use convert_case::{Case, Casing};
 
 use crate::ts_syn::abi::{
-    ClassIR, Diagnostic, DiagnosticLevel, EnumIR, InterfaceIR, MacroContextIR, MacroResult, Patch,
-    PatchCode, SourceMapping, SpanIR, TargetIR, TypeAliasIR,
+    CallIR, ClassIR, DecoratorIR, Diagnostic, DiagnosticLevel, EnumIR, FunctionIR, InterfaceIR,
+    MacroContextIR, MacroKind, MacroResult, Patch, PatchCode, SourceMapping, SpanIR, TargetIR,
+    TypeAliasIR,
+};
+use crate::ts_syn::{
+    TsSynError, lower_calls, lower_classes, lower_enums, lower_functions, lower_interfaces,
+    lower_type_aliases,
 };
-use crate::ts_syn::{lower_classes, lower_enums, lower_interfaces, lower_type_aliases};
 use anyhow::Context;
 use napi::Status;
 use swc_core::{
@@ -102,6 +107,8 @@ use swc_core::{
     },
 };
 
+use super::config::ResourceLimits;
+use super::worker_pool::WorkerPool;
 use super::{
     MacroConfig, MacroDispatcher, MacroError, MacroRegistry, PatchCollector, Result, derived,
 };
@@ -109,6 +116,10 @@ use super::{
 /// Default module path for built-in derive macros
 const DERIVE_MODULE_PATH: &str = "@macro/derive";
 
+/// The package declaring built-in call and attribute macros such as
+/// `includeStr` and `memoize`
+const MACRO_PACKAGE: &str = "macroforge";
+
 /// Special marker for dynamic module resolution
 const DYNAMIC_MODULE_MARKER: &str = "__DYNAMIC_MODULE__";
 
@@ -123,6 +134,23 @@ const BUILTIN_MACRO_NAMES: &[&str] = &[
     "PartialOrd",
     "Serialize",
     "Deserialize",
+    "Builder",
+    "With",
+    "Diff",
+    "Arbitrary",
+    "EnumUtils",
+    "Bitflags",
+    "TypeGuard",
+    "Reflect",
+    "Mock",
+    "HttpClient",
+    "Env",
+    "CliArgs",
+    "Csv",
+    "SqlRow",
+    "GraphQL",
+    "StateMachine",
+    "Branded",
 ];
 
 /// Result of macro expansion
@@ -138,6 +166,8 @@ pub struct MacroExpansion {
     pub type_aliases: Vec<TypeAliasIR>,
     /// Source mapping between original and expanded code positions
     pub source_mapping: Option<SourceMapping>,
+    /// Files macros read while expanding, e.g. by `includeStr`
+    pub dependencies: Vec<String>,
 }
 
 /// Core macro expansion engine
@@ -163,6 +193,10 @@ pub(crate) struct LoweredItems {
     pub interfaces: Vec<InterfaceIR>,
     pub enums: Vec<EnumIR>,
     pub type_aliases: Vec<TypeAliasIR>,
+    /// Functions and methods carrying at least one decorator
+    pub functions: Vec<FunctionIR>,
+    /// Call macro invocations
+    pub calls: Vec<CallIR>,
 }
 
 impl LoweredItems {
@@ -171,9 +205,18 @@ impl LoweredItems {
             && self.interfaces.is_empty()
             && self.enums.is_empty()
             && self.type_aliases.is_empty()
+            && self.functions.is_empty()
+            && self.calls.is_empty()
     }
 }
 
+/// The output of a call macro, which replaces its invocation.
+struct CallExpansion {
+    span: SpanIR,
+    code: String,
+    macro_name: String,
+}
+
 impl MacroExpander {
     /// Create a new expander with the local registry populated from inventory
     pub fn new() -> anyhow::Result<Self> {
@@ -246,13 +289,14 @@ impl MacroExpander {
         );
 
         let keep_decorators = config.keep_decorators;
+        let external_loader = ExternalMacroLoader::new(&root_dir, &config.limits);
 
         Ok(Self {
             dispatcher: MacroDispatcher::new(registry),
             config,
             keep_decorators,
             external_decorator_modules: Vec::new(),
-            external_loader: Some(ExternalMacroLoader::new(root_dir)),
+            external_loader: Some(external_loader),
         })
     }
 
@@ -288,11 +332,21 @@ impl MacroExpander {
         let type_aliases = lower_type_aliases(&module, source)
             .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
 
+        let mut functions = lower_functions(&module, source)
+            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+        functions.retain(|function| !function.decorators.is_empty());
+
+        let calls = self
+            .lower_call_sites(&module, source)
+            .map_err(|e| MacroError::InvalidConfig(format!("Lower error: {:?}", e)))?;
+
         let items = LoweredItems {
             classes,
             interfaces,
             enums,
             type_aliases,
+            functions,
+            calls,
         };
         if items.is_empty() {
             return Ok(MacroExpansion {
@@ -305,6 +359,7 @@ impl MacroExpander {
                 enums: Vec::new(),
                 type_aliases: Vec::new(),
                 source_mapping: None,
+                dependencies: Vec::new(),
             });
         }
 
@@ -313,6 +368,8 @@ impl MacroExpander {
             interfaces: items.interfaces.clone(),
             enums: items.enums.clone(),
             type_aliases: items.type_aliases.clone(),
+            functions: Vec::new(),
+            calls: Vec::new(),
         };
 
         let (mut collector, mut diagnostics) =
@@ -341,6 +398,7 @@ impl MacroExpander {
                     enums: Vec::new(),
                     type_aliases: Vec::new(),
                     source_mapping: None,
+                    dependencies: Vec::new(),
                 });
             }
         };
@@ -350,6 +408,8 @@ impl MacroExpander {
             interfaces: items.interfaces.clone(),
             enums: items.enums.clone(),
             type_aliases: items.type_aliases.clone(),
+            functions: Vec::new(),
+            calls: Vec::new(),
         };
 
         let (mut collector, mut diagnostics) =
@@ -391,11 +451,21 @@ impl MacroExpander {
         let type_aliases = lower_type_aliases(&module, source)
             .context("failed to lower type aliases for macro processing")?;
 
+        let mut functions = lower_functions(&module, source)
+            .context("failed to lower functions for macro processing")?;
+        functions.retain(|function| !function.decorators.is_empty());
+
+        let calls = self
+            .lower_call_sites(&module, source)
+            .context("failed to lower call macros for macro processing")?;
+
         let items = LoweredItems {
             classes,
             interfaces,
             enums,
             type_aliases,
+            functions,
+            calls,
         };
         if items.is_empty() {
             return Ok(None);
@@ -404,6 +474,35 @@ impl MacroExpander {
         Ok(Some((module, items)))
     }
 
+    /// Names of the registered macros of a kind.
+    fn macro_names(&self, kind: MacroKind) -> HashSet<String> {
+        self.dispatcher
+            .registry()
+            .all_macros()
+            .into_iter()
+            .filter(|(_, macro_impl)| macro_impl.kind() == kind)
+            .map(|(key, _)| key.name)
+            .collect()
+    }
+
+    /// Lowers the invocations of registered call macros.
+    ///
+    /// A call site invokes a macro only through an import of it, so an
+    /// import from `macroforge` (under any local name) must name a built-in
+    /// and an import from another module a macro registered under it.
+    fn lower_call_sites(
+        &self,
+        module: &Module,
+        source: &str,
+    ) -> std::result::Result<Vec<CallIR>, TsSynError> {
+        let registry = self.dispatcher.registry();
+        lower_calls(module, source, |module_source, name| {
+            registry
+                .lookup_by_kind(macro_module_path(module_source), name, MacroKind::Call)
+                .is_ok()
+        })
+    }
+
     pub(crate) fn collect_macro_patches(
         &self,
         module: &Module,
@@ -416,6 +515,8 @@ impl MacroExpander {
             interfaces,
             enums,
             type_aliases,
+            functions,
+            calls,
         } = items;
         let mut collector = PatchCollector::new();
         let mut diagnostics = Vec::new();
@@ -424,8 +525,10 @@ impl MacroExpander {
         diagnostics.extend(check_builtin_import_warnings(module, source));
 
         // Used for external type function imports (e.g. serializeWithContextFoo) based on existing type imports.
-        let import_result = collect_import_sources(module, source);
-        let import_sources = import_result.sources;
+        let ImportCollectionResult {
+            sources: import_sources,
+            aliases: import_aliases,
+        } = collect_import_sources(module, source);
 
         let class_map: HashMap<SpanKey, ClassIR> = classes
             .into_iter()
@@ -456,6 +559,59 @@ impl MacroExpander {
             source,
         );
 
+        // Method bodies of derived classes are replaced by signatures in the
+        // type output, so attribute and call patches must stay out of those
+        // spans there.
+        let type_rewritten_spans: Vec<SpanIR> = derive_targets
+            .iter()
+            .filter_map(|target| match &target.target_ir {
+                DeriveTargetIR::Class(class_ir) => Some(class_ir),
+                _ => None,
+            })
+            .flat_map(|class_ir| class_ir.methods.iter().map(|method| method.span))
+            .collect();
+
+        let mut call_expansions = self.collect_call_expansions(
+            calls,
+            &type_rewritten_spans,
+            &import_sources,
+            file_name,
+            source,
+            &mut collector,
+            &mut diagnostics,
+        );
+
+        self.collect_attribute_patches(
+            module,
+            functions,
+            &mut call_expansions,
+            &type_rewritten_spans,
+            &import_sources,
+            &import_aliases,
+            file_name,
+            source,
+            &mut collector,
+            &mut diagnostics,
+        );
+
+        // Invocations not inlined into an attribute macro's body replace
+        // themselves in the runtime output.
+        collector.add_runtime_patches(
+            call_expansions
+                .into_iter()
+                .map(|expansion| Patch::Replace {
+                    span: expansion.span,
+                    code: PatchCode::Text(expansion.code),
+                    source_macro: Some(expansion.macro_name),
+                })
+                .collect(),
+        );
+        let mut declared_macros = self.macro_names(MacroKind::Call);
+        if !self.keep_decorators {
+            declared_macros.extend(self.macro_names(MacroKind::Attribute));
+        }
+        collector.add_runtime_patches(macro_import_patches(module, source, &declared_macros));
+
         if derive_targets.is_empty() {
             return (collector, diagnostics);
         }
@@ -747,6 +903,7 @@ impl MacroExpander {
 
                 collector.add_runtime_patches(result.runtime_patches);
                 collector.add_type_patches(result.type_patches);
+                collector.add_dependencies(result.dependencies);
             }
 
             // Generate convenience const for non-class types (Prefix style)
@@ -785,6 +942,317 @@ impl MacroExpander {
         (collector, diagnostics)
     }
 
+    /// Runs call macros on their invocations.
+    ///
+    /// The type output gets each replacement straight away. The runtime
+    /// replacements are returned, since invocations inside a function that
+    /// an attribute macro rewrites become part of that function's body.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_call_expansions(
+        &self,
+        calls: Vec<CallIR>,
+        type_rewritten_spans: &[SpanIR],
+        import_sources: &HashMap<String, String>,
+        file_name: &str,
+        source: &str,
+        collector: &mut PatchCollector,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Vec<CallExpansion> {
+        let outside_rewritten = |span: SpanIR| {
+            !type_rewritten_spans
+                .iter()
+                .any(|rewritten| span.start < rewritten.end && rewritten.start < span.end)
+        };
+        let mut expansions = Vec::new();
+
+        for call in calls {
+            let target_source = source
+                .get(
+                    call.span.start.saturating_sub(1) as usize
+                        ..call.span.end.saturating_sub(1) as usize,
+                )
+                .unwrap_or("")
+                .to_string();
+            let local_name = source
+                .get(
+                    call.callee_span.start.saturating_sub(1) as usize
+                        ..call.callee_span.end.saturating_sub(1) as usize,
+                )
+                .unwrap_or(&call.name);
+            let module_path = import_sources
+                .get(local_name)
+                .map_or(DERIVE_MODULE_PATH, |module_source| {
+                    macro_module_path(module_source)
+                })
+                .to_string();
+            let ctx = MacroContextIR::new_call(
+                call.name.clone(),
+                module_path,
+                file_name.to_string(),
+                call.clone(),
+                target_source,
+            );
+
+            let result = self.dispatcher.dispatch(ctx);
+            diagnostics.extend(result.diagnostics);
+            collector.add_runtime_patches(result.runtime_patches);
+            collector.add_type_patches(result.type_patches);
+            collector.add_dependencies(result.dependencies);
+
+            let Some(tokens) = result.tokens else {
+                continue;
+            };
+            let macro_name = Some(call.name.clone());
+            let mut replacement = Vec::new();
+            for (location, code) in split_by_markers(&tokens) {
+                let code = code.trim();
+                if code.is_empty() {
+                    continue;
+                }
+                if location == "above" {
+                    let at = SpanIR::new(call.stmt_span.start, call.stmt_span.start);
+                    let indent = line_indent(source, call.stmt_span.start);
+                    let patch = Patch::Insert {
+                        at,
+                        code: PatchCode::Text(format!("{code}\n{indent}")),
+                        source_macro: macro_name.clone(),
+                    };
+                    collector.add_runtime_patches(vec![patch.clone()]);
+                    if outside_rewritten(at) {
+                        collector.add_type_patches(vec![patch]);
+                    }
+                } else {
+                    replacement.push(code.to_string());
+                }
+            }
+            if replacement.is_empty() {
+                diagnostics.push(Diagnostic {
+                    level: DiagnosticLevel::Error,
+                    message: format!("Call macro '{}' produced no expression", call.name),
+                    span: Some(call.span),
+                    notes: vec![],
+                    help: None,
+                });
+                continue;
+            }
+
+            let code = replacement.join("\n");
+            if outside_rewritten(call.span) {
+                collector.add_type_patches(vec![Patch::Replace {
+                    span: call.span,
+                    code: PatchCode::Text(code.clone()),
+                    source_macro: macro_name,
+                }]);
+            }
+            expansions.push(CallExpansion {
+                span: call.span,
+                code,
+                macro_name: call.name,
+            });
+        }
+        expansions
+    }
+
+    /// Resolves a decorator on a function to the attribute macro it invokes,
+    /// as `(name, module_path)`.
+    ///
+    /// Like derive macros, an imported name resolves through its import, so
+    /// it must be registered under the imported module (`macroforge` standing
+    /// for the built-ins). A decorator without an import invokes a built-in
+    /// unless the module declares the name itself. JSDoc tags never do: they
+    /// double as documentation, so `@deprecated` in a doc comment only runs
+    /// the macro when `deprecated` is imported.
+    fn resolve_attribute(
+        &self,
+        decorator: &DecoratorIR,
+        import_sources: &HashMap<String, String>,
+        import_aliases: &HashMap<String, String>,
+        declared: &HashSet<String>,
+    ) -> Option<(String, String)> {
+        let name = import_aliases
+            .get(&decorator.name)
+            .unwrap_or(&decorator.name)
+            .clone();
+        let module_path = match import_sources.get(&decorator.name) {
+            Some(module_source) => macro_module_path(module_source).to_string(),
+            None if decorator.node.is_some() && !declared.contains(&decorator.name) => {
+                DERIVE_MODULE_PATH.to_string()
+            }
+            None => return None,
+        };
+        self.dispatcher
+            .registry()
+            .lookup_by_kind(&module_path, &name, MacroKind::Attribute)
+            .ok()?;
+        Some((name, module_path))
+    }
+
+    /// Runs attribute macros on decorated functions and methods.
+    ///
+    /// Decorators are matched against registered `MacroKind::Attribute`
+    /// macros (see [`Self::resolve_attribute`]); any other decorator is left
+    /// in place. Stacked attributes run from the one closest to the function
+    /// outwards, each seeing the body produced by the previous one, and the
+    /// final body replaces the original in the runtime output. Call macro
+    /// expansions inside the body are applied first and taken out of
+    /// `call_expansions`.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_attribute_patches(
+        &self,
+        module: &Module,
+        functions: Vec<FunctionIR>,
+        call_expansions: &mut Vec<CallExpansion>,
+        type_rewritten_spans: &[SpanIR],
+        import_sources: &HashMap<String, String>,
+        import_aliases: &HashMap<String, String>,
+        file_name: &str,
+        source: &str,
+        collector: &mut PatchCollector,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let outside_rewritten = |span: SpanIR| {
+            !type_rewritten_spans
+                .iter()
+                .any(|rewritten| span.start < rewritten.end && rewritten.start < span.end)
+        };
+        let declared = top_level_declarations(module);
+
+        for function in functions {
+            let attributes: Vec<(&DecoratorIR, String, String)> = function
+                .decorators
+                .iter()
+                .rev()
+                .filter_map(|decorator| {
+                    let (name, module_path) = self.resolve_attribute(
+                        decorator,
+                        import_sources,
+                        import_aliases,
+                        &declared,
+                    )?;
+                    Some((decorator, name, module_path))
+                })
+                .collect();
+            if attributes.is_empty() {
+                continue;
+            }
+
+            let target_source = source
+                .get(
+                    function.span.start.saturating_sub(1) as usize
+                        ..function.span.end.saturating_sub(1) as usize,
+                )
+                .unwrap_or("")
+                .to_string();
+            // Code above the function goes before its decorators and doc
+            // comments, which stay attached to the function.
+            let leading_start = leading_start(&function, source);
+            let indent = line_indent(source, leading_start);
+            let mut current = function.clone();
+            let mut body_macro = inline_call_expansions(&mut current, call_expansions);
+
+            for (decorator, name, module_path) in &attributes {
+                // The macro sees its decorator under the macro's name, and a
+                // JSDoc tag's description as its argument.
+                let description = directive_description(decorator, source);
+                if let Some(own) = current.decorators.iter_mut().find(|own| *own == *decorator) {
+                    own.name = name.clone();
+                    if let Some(description) = description {
+                        own.args_src = description;
+                    }
+                }
+                let ctx = MacroContextIR::new_attribute_function(
+                    name.clone(),
+                    module_path.clone(),
+                    decorator.span,
+                    file_name.to_string(),
+                    current.clone(),
+                    target_source.clone(),
+                );
+
+                let mut result = self.dispatcher.dispatch(ctx);
+                for diag in &mut result.diagnostics {
+                    if let Some(span) = diag.span {
+                        diag.span = Some(diagnostic_span_for_derive(span, source));
+                    }
+                }
+                diagnostics.extend(result.diagnostics);
+                collector.add_runtime_patches(result.runtime_patches);
+                collector.add_type_patches(result.type_patches);
+                collector.add_dependencies(result.dependencies);
+
+                let Some(tokens) = result.tokens else {
+                    continue;
+                };
+                let macro_name = Some(name.clone());
+                for (location, code) in split_by_markers(&tokens) {
+                    match location {
+                        "body" => {
+                            current.body_src = code;
+                            body_macro = macro_name.clone();
+                        }
+                        _ if code.trim().is_empty() => {}
+                        "above" => {
+                            let at = SpanIR::new(leading_start, leading_start);
+                            let patch = Patch::Insert {
+                                at,
+                                code: PatchCode::Text(format!("{}\n{indent}", code.trim())),
+                                source_macro: macro_name.clone(),
+                            };
+                            collector.add_runtime_patches(vec![patch.clone()]);
+                            if outside_rewritten(at) {
+                                collector.add_type_patches(vec![patch]);
+                            }
+                        }
+                        _ => {
+                            let at = SpanIR::new(function.span.end, function.span.end);
+                            let patch = Patch::Insert {
+                                at,
+                                code: PatchCode::Text(format!("\n\n{indent}{}", code.trim())),
+                                source_macro: macro_name.clone(),
+                            };
+                            collector.add_runtime_patches(vec![patch.clone()]);
+                            if outside_rewritten(at) {
+                                collector.add_type_patches(vec![patch]);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Removals come after the insertions above, so code inserted at
+            // the start of a removed decorator or comment is kept.
+            if !self.keep_decorators {
+                let (directives, decorators): (Vec<&DecoratorIR>, Vec<&DecoratorIR>) = attributes
+                    .iter()
+                    .map(|(decorator, ..)| *decorator)
+                    .partition(|decorator| decorator.node.is_none());
+                for decorator in decorators {
+                    let (runtime_span, type_span) = attribute_removal_spans(decorator, source);
+                    collector.add_runtime_patches(vec![Patch::Delete { span: runtime_span }]);
+                    if outside_rewritten(type_span) {
+                        collector.add_type_patches(vec![Patch::Delete { span: type_span }]);
+                    }
+                }
+                for (runtime_patch, type_patch, span) in
+                    directive_removal_patches(&directives, source)
+                {
+                    collector.add_runtime_patches(vec![runtime_patch]);
+                    if outside_rewritten(span) {
+                        collector.add_type_patches(vec![type_patch]);
+                    }
+                }
+            }
+
+            if body_macro.is_some() {
+                collector.add_runtime_patches(vec![Patch::Replace {
+                    span: function.body_span,
+                    code: PatchCode::Text(format!("{{{}}}", current.body_src)),
+                    source_macro: body_macro,
+                }]);
+            }
+        }
+    }
+
     pub(crate) fn process_macro_output(
         &self,
         result: &mut MacroResult,
@@ -1070,6 +1538,8 @@ impl MacroExpander {
             interfaces,
             enums,
             type_aliases,
+            functions: _,
+            calls: _,
         } = items;
         let has_patches = collector.has_patches();
         let runtime_result = collector
@@ -1127,6 +1597,7 @@ impl MacroExpander {
             enums,
             type_aliases,
             source_mapping,
+            dependencies: collector.dependencies().to_vec(),
         };
 
         self.enforce_diagnostic_limit(&mut expansion.diagnostics);
@@ -1230,221 +1701,23 @@ fn strip_decorators(code: &str, external_decorator_modules: &[&str]) -> String {
 // External Macro Loader
 // ============================================================================
 
+/// Runs macros exported by npm packages on the project's shared
+/// [`WorkerPool`].
 struct ExternalMacroLoader {
-    root_dir: std::path::PathBuf,
+    pool: Arc<WorkerPool>,
+    timeout: Duration,
 }
 
 impl ExternalMacroLoader {
-    fn new(root_dir: std::path::PathBuf) -> Self {
-        Self { root_dir }
+    fn new(root_dir: &Path, limits: &ResourceLimits) -> Self {
+        Self {
+            pool: WorkerPool::shared(root_dir, limits.max_external_workers),
+            timeout: Duration::from_millis(limits.max_execution_time_ms),
+        }
     }
 
     fn run_macro(&self, ctx: &MacroContextIR) -> napi::Result<MacroResult> {
-        let fn_name = format!("__macroforgeRun{}", ctx.macro_name);
-        let ctx_json =
-            serde_json::to_string(ctx).map_err(|e| napi::Error::new(Status::InvalidArg, e))?;
-
-        let script = r#"
-const [modulePath, fnName, ctxJson, rootDir] = process.argv.slice(1);
-const path = require('path');
-const fs = require('fs');
-const { pathToFileURL } = require('url');
-
-const normalizeWorkspaces = (val) =>
-  Array.isArray(val) ? val : (val && Array.isArray(val.packages) ? val.packages : []);
-
-const toImportSpecifier = (id) => {
-  if (id.startsWith('.') || id.startsWith('/')) {
-    return pathToFileURL(path.resolve(rootDir, id)).href;
-  }
-  return id;
-};
-
-const expandWorkspace = (pattern) => {
-  if (typeof pattern !== 'string') return [];
-  const absolute = path.resolve(rootDir, pattern);
-  if (!pattern.includes('*')) {
-    return [absolute];
-  }
-
-  const starIdx = pattern.indexOf('*');
-  const baseDir = path.resolve(rootDir, pattern.slice(0, starIdx));
-  const suffix = pattern.slice(starIdx + 1);
-  if (!fs.existsSync(baseDir)) return [];
-
-  return fs
-    .readdirSync(baseDir, { withFileTypes: true })
-    .filter((entry) => entry.isDirectory())
-    .map((entry) => path.join(baseDir, entry.name + suffix));
-};
-
-const candidates = [];
-const seen = new Set();
-const addCandidate = (id) => {
-  if (!id) return;
-  const key = id.startsWith('.') || id.startsWith('/') ? path.resolve(rootDir, id) : id;
-  if (seen.has(key)) return;
-  seen.add(key);
-  candidates.push(id);
-};
-
-const addPackageDir = (dir) => {
-  try {
-    const pkgJsonPath = path.join(dir, 'package.json');
-    if (!fs.existsSync(pkgJsonPath)) return;
-    const pkgJson = JSON.parse(fs.readFileSync(pkgJsonPath, 'utf8'));
-    addCandidate(pkgJson.name || dir);
-    addCandidate(dir);
-  } catch {}
-};
-
-let ctx;
-try {
-  ctx = JSON.parse(ctxJson);
-} catch {}
-
-// Prefer node_modules near the file being processed (walk upward toward rootDir)
-if (ctx?.file_name) {
-  let current = path.dirname(ctx.file_name);
-  const rootResolved = path.resolve(rootDir);
-  while (true) {
-    addCandidate(path.join(current, 'node_modules', modulePath));
-    const parent = path.dirname(current);
-    if (parent === current || !path.resolve(parent).startsWith(rootResolved)) break;
-    current = parent;
-  }
-}
-
-// Fallbacks: requested specifier and its absolute form
-addCandidate(modulePath);
-addCandidate(path.resolve(rootDir, modulePath));
-
-// Heuristic: check monorepo subpaths even without a root package.json
-addPackageDir(path.join(rootDir, 'playground', 'macro'));
-const packagesDir = path.join(rootDir, 'packages');
-if (fs.existsSync(packagesDir)) {
-  for (const entry of fs.readdirSync(packagesDir, { withFileTypes: true })) {
-    if (!entry.isDirectory()) continue;
-    addPackageDir(path.join(packagesDir, entry.name));
-  }
-}
-
-try {
-  const rootPkg = JSON.parse(fs.readFileSync(path.join(rootDir, 'package.json'), 'utf8'));
-  const workspaces = normalizeWorkspaces(rootPkg.workspaces);
-
-  for (const ws of workspaces) {
-    for (const pkgDir of expandWorkspace(ws)) {
-      try {
-        const pkgJsonPath = path.join(pkgDir, 'package.json');
-        if (!fs.existsSync(pkgJsonPath)) continue;
-
-        const pkgJson = JSON.parse(fs.readFileSync(pkgJsonPath, 'utf8'));
-        addCandidate(pkgJson.name || pkgDir);
-        addCandidate(pkgDir);
-      } catch {}
-    }
-  }
-} catch {}
-
-const tryRequire = (id) => {
-  try {
-    return { module: require(id), loader: 'require' };
-  } catch (error) {
-    return { error };
-  }
-};
-
-const tryImport = async (id) => {
-  try {
-    return { module: await import(toImportSpecifier(id)), loader: 'import' };
-  } catch (error) {
-    return { error };
-  }
-};
-
-(async () => {
-  const errors = [];
-
-  for (const id of candidates) {
-    let loaded = tryRequire(id);
-
-    if (!loaded.module) {
-      const imported = await tryImport(id);
-      if (imported.module) {
-        loaded = imported;
-      } else {
-        errors.push(
-          `Failed to load '${id}' via require/import: ${
-            imported.error?.message || loaded.error?.message || 'unknown error'
-          }`
-        );
-        continue;
-      }
-    }
-
-    const mod = loaded.module;
-    const fn =
-      mod?.[fnName] ||
-      mod?.default?.[fnName] ||
-      (typeof mod?.default === 'object' ? mod.default[fnName] : undefined);
-
-    if (typeof fn !== 'function') {
-      errors.push(`Module '${id}' loaded via ${loaded.loader} but missing export '${fnName}'`);
-      continue;
-    }
-
-    const out = await fn(ctxJson);
-    if (typeof out === 'string') {
-      process.stdout.write(out);
-      process.exit(0);
-    }
-
-    errors.push(`Macro '${fnName}' in '${id}' returned ${typeof out}, expected string`);
-  }
-
-  if (errors.length === 0) {
-    errors.push('Macro not found in any workspace candidate');
-  }
-
-  console.error(errors.join('\n'));
-  process.exit(2);
-})().catch((err) => {
-  console.error(err?.stack || String(err));
-  process.exit(1);
-});
-"#;
-
-        let child = Command::new("node")
-            .current_dir(&self.root_dir)
-            .arg("-e")
-            .arg(script)
-            .arg(&ctx.module_path)
-            .arg(&fn_name)
-            .arg(&ctx_json)
-            .arg(self.root_dir.to_string_lossy().as_ref())
-            .output()
-            .map_err(|e| {
-                napi::Error::new(
-                    Status::GenericFailure,
-                    format!("Failed to spawn node for external macro: {e}"),
-                )
-            })?;
-
-        if !child.status.success() {
-            let stderr = String::from_utf8_lossy(&child.stderr);
-            return Err(napi::Error::new(
-                Status::GenericFailure,
-                format!("External macro runner failed: {stderr}"),
-            ));
-        }
-
-        let result_json = String::from_utf8(child.stdout).map_err(|e| {
-            napi::Error::new(
-                Status::InvalidArg,
-                format!("Macro runner returned non-UTF8 output: {e}"),
-            )
-        })?;
+        let result_json = self.pool.run(ctx, self.timeout)?;
 
         let host_result: crate::ts_syn::abi::MacroResult = serde_json::from_str(&result_json)
             .map_err(|e| {
@@ -1460,6 +1733,7 @@ const tryImport = async (id) => {
             diagnostics: host_result.diagnostics,
             tokens: host_result.tokens,
             debug: host_result.debug,
+            dependencies: host_result.dependencies,
         })
     }
 }
@@ -1884,6 +2158,68 @@ fn external_type_function_import_patches(
         .collect()
 }
 
+/// The registry module of the macros imported from `module_source`; the
+/// built-ins imported from `macroforge` are registered under the derive path.
+fn macro_module_path(module_source: &str) -> &str {
+    if module_source == MACRO_PACKAGE {
+        DERIVE_MODULE_PATH
+    } else {
+        module_source
+    }
+}
+
+/// Deletes imports of call and attribute macros from the `macroforge`
+/// package.
+///
+/// The package only declares these macros for the type checker; once the
+/// invocations are expanded, importing it at runtime would load the native
+/// bindings. Imports that also bind anything else are kept.
+fn macro_import_patches(
+    module: &Module,
+    source: &str,
+    macro_names: &HashSet<String>,
+) -> Vec<Patch> {
+    use swc_core::ecma::ast::{
+        ImportDecl, ImportSpecifier, ModuleDecl, ModuleExportName, ModuleItem,
+    };
+
+    let mut patches = Vec::new();
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span,
+            specifiers,
+            src,
+            type_only: false,
+            ..
+        })) = item
+        else {
+            continue;
+        };
+        let only_macros = !specifiers.is_empty()
+            && specifiers.iter().all(|specifier| match specifier {
+                ImportSpecifier::Named(named) => match &named.imported {
+                    Some(ModuleExportName::Ident(imported)) => macro_names.contains(&*imported.sym),
+                    Some(ModuleExportName::Str(_)) => false,
+                    None => macro_names.contains(&*named.local.sym),
+                },
+                _ => false,
+            });
+        if src.value.to_string_lossy() != MACRO_PACKAGE || !only_macros {
+            continue;
+        }
+
+        // Take the line break along, so no empty line is left behind.
+        let mut end = span.hi.0;
+        if source.as_bytes().get(end.saturating_sub(1) as usize) == Some(&b'\n') {
+            end += 1;
+        }
+        patches.push(Patch::Delete {
+            span: SpanIR::new(span.lo.0, end),
+        });
+    }
+    patches
+}
+
 /// Check for imports of built-in macros and return warnings
 /// Built-in macros like Debug, Clone, Serialize don't need to be imported
 fn check_builtin_import_warnings(module: &Module, _source: &str) -> Vec<Diagnostic> {
@@ -2360,6 +2696,244 @@ fn find_macro_comment_span(source: &str, target_start: u32) -> Option<SpanIR> {
     Some(SpanIR::new(start_idx as u32 + 1, end_idx as u32 + 1))
 }
 
+/// Applies the call expansions inside `function`'s body to its `body_src`
+/// and removes them from `expansions`.
+///
+/// Returns the name of an inlined macro, if any, for attributing the body.
+fn inline_call_expansions(
+    function: &mut FunctionIR,
+    expansions: &mut Vec<CallExpansion>,
+) -> Option<String> {
+    // `body_src` starts right after the opening brace
+    let body_start = function.body_span.start + 1;
+    let body_end = function.body_span.end.saturating_sub(1);
+    let (mut inside, outside): (Vec<_>, Vec<_>) = expansions.drain(..).partition(|expansion| {
+        expansion.span.start >= body_start && expansion.span.end <= body_end
+    });
+    *expansions = outside;
+
+    // Back to front, so earlier offsets stay valid
+    inside.sort_by_key(|expansion| std::cmp::Reverse(expansion.span.start));
+    for expansion in &inside {
+        let start = (expansion.span.start - body_start) as usize;
+        let end = (expansion.span.end - body_start) as usize;
+        function.body_src.replace_range(start..end, &expansion.code);
+    }
+    inside.pop().map(|expansion| expansion.macro_name)
+}
+
+/// Spans to delete for an attribute decorator, as `(runtime, type)`.
+///
+/// Both cover the decorator including its `@`; the runtime span also takes
+/// the whitespace up to the next token so no blank line is left behind.
+fn attribute_removal_spans(decorator: &DecoratorIR, source: &str) -> (SpanIR, SpanIR) {
+    let mut exact = match &decorator.node {
+        Some(node) => SpanIR::new(node.span.lo.0, node.span.hi.0),
+        None => decorator.span,
+    };
+    let bytes = source.as_bytes();
+    let start = exact.start.saturating_sub(1) as usize;
+    if start > 0 && bytes.get(start) != Some(&b'@') && bytes[start - 1] == b'@' {
+        exact.start -= 1;
+    }
+
+    let rest = source
+        .get(exact.end.saturating_sub(1) as usize..)
+        .unwrap_or("");
+    let trailing = (rest.len() - rest.trim_start().len()) as u32;
+    (SpanIR::new(exact.start, exact.end + trailing), exact)
+}
+
+/// Patches taking attribute directives out of their JSDoc comments, as
+/// `(runtime, type, comment span)`.
+///
+/// Only the directive tags are removed, so the description and other tags
+/// of a comment stay; a comment left without content is deleted.
+fn directive_removal_patches(
+    directives: &[&DecoratorIR],
+    source: &str,
+) -> Vec<(Patch, Patch, SpanIR)> {
+    let mut comments: Vec<(u32, Vec<&str>)> = Vec::new();
+    for directive in directives {
+        match comments
+            .iter_mut()
+            .find(|(start, _)| *start == directive.span.start)
+        {
+            Some((_, names)) => names.push(&directive.name),
+            None => comments.push((directive.span.start, vec![&directive.name])),
+        }
+    }
+
+    let mut patches = Vec::new();
+    for (start, names) in comments {
+        let begin = start.saturating_sub(1) as usize;
+        let Some(len) = source.get(begin..).and_then(|rest| rest.find("*/")) else {
+            continue;
+        };
+        let span = SpanIR::new(start, start + len as u32 + 2);
+        let comment = &source[begin..begin + len + 2];
+
+        match strip_directives(comment, &names) {
+            Some(text) => {
+                let patch = Patch::Replace {
+                    span,
+                    code: PatchCode::Text(text),
+                    source_macro: None,
+                };
+                patches.push((patch.clone(), patch, span));
+            }
+            None => {
+                let rest = &source[begin + len + 2..];
+                let trailing = (rest.len() - rest.trim_start().len()) as u32;
+                let runtime_span = SpanIR::new(span.start, span.end + trailing);
+                let patch = Patch::Delete { span: runtime_span };
+                patches.push((patch.clone(), patch, span));
+            }
+        }
+    }
+    patches
+}
+
+/// The byte range of the `@name` tag in a JSDoc comment, including its
+/// `(...)` arguments or, for a tag without them, the description following
+/// it on the line.
+fn directive_tag_range(comment: &str, name: &str) -> Option<std::ops::Range<usize>> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let tag = format!("@{name}");
+    let at = comment.match_indices(&tag).map(|(at, _)| at).find(|&at| {
+        !comment[..at].ends_with(is_ident_char)
+            && !comment[at + tag.len()..].starts_with(is_ident_char)
+    })?;
+
+    let name_end = at + tag.len();
+    let after = &comment[name_end..];
+    let args = after.trim_start_matches([' ', '\t']);
+    if !args.starts_with('(') {
+        let line = &after[..after.find('\n').unwrap_or(after.len())];
+        let description = line.find("*/").map_or(line, |end| &line[..end]);
+        return Some(at..name_end + description.trim_end().len());
+    }
+
+    let open = name_end + (after.len() - args.len());
+    let mut depth = 0usize;
+    for (i, c) in comment[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(at..open + i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(at..name_end)
+}
+
+/// The description of a JSDoc directive written without arguments, e.g.
+/// `"Use load instead."` for `@deprecated Use load instead.`, as a string
+/// literal argument.
+fn directive_description(directive: &DecoratorIR, source: &str) -> Option<String> {
+    if directive.node.is_some() || !directive.args_src.is_empty() {
+        return None;
+    }
+    let begin = directive.span.start.saturating_sub(1) as usize;
+    let comment = &source[begin..begin + source.get(begin..)?.find("*/")?];
+    let range = directive_tag_range(comment, &directive.name)?;
+    let description = comment[range.start + 1 + directive.name.len()..range.end].trim();
+    (!description.is_empty()).then(|| serde_json::to_string(description).unwrap())
+}
+
+/// Removes the directive tags of `names` from a JSDoc comment, dropping
+/// lines they leave empty. Returns `None` when nothing but the comment
+/// delimiters would remain.
+fn strip_directives(comment: &str, names: &[&str]) -> Option<String> {
+    let mut text = comment.to_string();
+
+    for name in names {
+        let Some(std::ops::Range { start: at, end }) = directive_tag_range(&text, name) else {
+            continue;
+        };
+
+        let line_start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i + 1);
+        let prefix = text[line_start..at].trim();
+        let suffix = text[end..line_end].trim();
+        if (prefix.is_empty() || prefix == "*") && suffix.is_empty() && line_end < text.len() {
+            // The tag was alone on its line.
+            text.replace_range(line_start..line_end, "");
+        } else {
+            let rest = &text[end..];
+            let end = end + (rest.len() - rest.trim_start_matches([' ', '\t']).len());
+            text.replace_range(at..end, "");
+        }
+    }
+
+    let content = text.trim_start_matches("/**").trim_end_matches("*/");
+    let is_empty = content
+        .lines()
+        .all(|line| line.trim().trim_start_matches('*').trim().is_empty());
+    (!is_empty).then_some(text)
+}
+
+/// Where the declaration of a function starts, including its decorators and
+/// the JSDoc comments directly above it.
+fn leading_start(function: &FunctionIR, source: &str) -> u32 {
+    let mut start = function
+        .decorators
+        .iter()
+        .map(|decorator| attribute_removal_spans(decorator, source).1.start)
+        .fold(function.span.start, u32::min);
+
+    loop {
+        let before = source[..start.saturating_sub(1) as usize].trim_end();
+        let Some(open) = before.strip_suffix("*/").and_then(|body| body.rfind("/**")) else {
+            return start;
+        };
+        start = open as u32 + 1;
+    }
+}
+
+/// Names declared by top-level functions, classes and variables.
+fn top_level_declarations(module: &Module) -> HashSet<String> {
+    use swc_core::ecma::ast::{Decl, ModuleDecl, ModuleItem, Pat, Stmt};
+
+    let mut declared = HashSet::new();
+    for item in &module.body {
+        let decl = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+            _ => continue,
+        };
+        match decl {
+            Decl::Fn(f) => {
+                declared.insert(f.ident.sym.to_string());
+            }
+            Decl::Class(c) => {
+                declared.insert(c.ident.sym.to_string());
+            }
+            Decl::Var(var) => {
+                for declarator in &var.decls {
+                    if let Pat::Ident(binding) = &declarator.name {
+                        declared.insert(binding.id.sym.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    declared
+}
+
+/// The leading whitespace of the line containing `pos`.
+fn line_indent(source: &str, pos: u32) -> &str {
+    let idx = (pos.saturating_sub(1) as usize).min(source.len());
+    let line_start = source[..idx].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &source[line_start..idx];
+    &prefix[..prefix.len() - prefix.trim_start().len()]
+}
+
 fn split_by_markers(source: &str) -> Vec<(&str, String)> {
     let markers = [
         ("above", "/* @macroforge:above */"),
@@ -2604,9 +3178,10 @@ fn register_packages(
 
 #[cfg(test)]
 mod external_macro_loader_tests {
-    use super::ExternalMacroLoader;
+    use super::{ExternalMacroLoader, MacroExpander};
+    use crate::host::config::{MacroConfig, ResourceLimits};
     use crate::ts_syn::abi::{ClassIR, MacroContextIR, SpanIR};
-    use std::{fs, path::Path};
+    use std::{fs, path::Path, sync::Arc};
     use tempfile::tempdir;
 
     fn write(path: &Path, contents: &str) {
@@ -2660,7 +3235,7 @@ export function __macroforgeRunDebug(ctxJson) {
 "#,
         );
 
-        let loader = ExternalMacroLoader::new(root.to_path_buf());
+        let loader = ExternalMacroLoader::new(root, &ResourceLimits::default());
         let ctx = MacroContextIR::new_derive_class(
             "Debug".into(),
             "@ext/macro".into(),
@@ -2710,7 +3285,7 @@ export function __macroforgeRunDebug(ctxJson) {
         let file_path = nested_root.join("src/file.ts");
         fs::create_dir_all(file_path.parent().unwrap()).unwrap();
 
-        let loader = ExternalMacroLoader::new(root.to_path_buf());
+        let loader = ExternalMacroLoader::new(root, &ResourceLimits::default());
         let ctx = MacroContextIR::new_derive_class(
             "Debug".into(),
             "@ext/macro".into(),
@@ -2727,6 +3302,200 @@ export function __macroforgeRunDebug(ctxJson) {
 
         assert!(result.diagnostics.is_empty());
     }
+
+    fn debug_ctx(macro_name: &str, root: &Path) -> MacroContextIR {
+        MacroContextIR::new_derive_class(
+            macro_name.into(),
+            "@ext/macro".into(),
+            SpanIR::new(0, 1),
+            SpanIR::new(0, 1),
+            root.join("src/file.ts").to_string_lossy().to_string(),
+            test_class(),
+            "class Temp {}".into(),
+        )
+    }
+
+    /// A workspace whose `@ext/macro` package counts its invocations, loops
+    /// forever or exits, depending on the macro.
+    fn write_stateful_workspace(root: &Path) {
+        write(
+            &root.join("package.json"),
+            r#"{"name":"root","workspaces":["packages/*"]}"#,
+        );
+        write(
+            &root.join("packages/macro/package.json"),
+            r#"{"name":"@ext/macro","main":"index.js"}"#,
+        );
+        write(
+            &root.join("packages/macro/index.js"),
+            r#"
+let calls = 0;
+exports.__macroforgeRunCount = () => {
+  calls += 1;
+  console.log(`call ${calls}`);
+  return JSON.stringify({ runtime_patches: [], type_patches: [], diagnostics: [], debug: String(calls) });
+};
+exports.__macroforgeRunHang = () => {
+  while (true) {}
+};
+exports.__macroforgeRunExit = () => process.exit(3);
+"#,
+        );
+    }
+
+    fn single_worker_limits() -> ResourceLimits {
+        ResourceLimits {
+            max_execution_time_ms: 2000,
+            max_external_workers: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keeps_macro_modules_loaded_between_invocations() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write_stateful_workspace(root);
+
+        let loader = ExternalMacroLoader::new(root, &single_worker_limits());
+        let ctx = debug_ctx("Count", root);
+
+        let first = loader.run_macro(&ctx).unwrap();
+        let second = loader.run_macro(&ctx).unwrap();
+
+        assert_eq!(first.debug.as_deref(), Some("1"));
+        assert_eq!(second.debug.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn restarts_workers_that_time_out() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write_stateful_workspace(root);
+
+        let limits = ResourceLimits {
+            max_execution_time_ms: 500,
+            ..single_worker_limits()
+        };
+        let loader = ExternalMacroLoader::new(root, &limits);
+
+        let err = loader.run_macro(&debug_ctx("Hang", root)).unwrap_err();
+        assert!(
+            err.reason.contains("timed out after 500 ms"),
+            "{}",
+            err.reason
+        );
+
+        let result = loader.run_macro(&debug_ctx("Count", root)).unwrap();
+        assert_eq!(result.debug.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn restarts_workers_that_exit() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write_stateful_workspace(root);
+
+        let loader = ExternalMacroLoader::new(root, &single_worker_limits());
+        assert_eq!(
+            loader
+                .run_macro(&debug_ctx("Count", root))
+                .unwrap()
+                .debug
+                .as_deref(),
+            Some("1")
+        );
+
+        let err = loader.run_macro(&debug_ctx("Exit", root)).unwrap_err();
+        assert!(err.reason.contains("exited"), "{}", err.reason);
+
+        let result = loader.run_macro(&debug_ctx("Count", root)).unwrap();
+        assert_eq!(result.debug.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn reports_macro_errors_without_restarting() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write_stateful_workspace(root);
+
+        let loader = ExternalMacroLoader::new(root, &single_worker_limits());
+        loader.run_macro(&debug_ctx("Count", root)).unwrap();
+
+        let err = loader.run_macro(&debug_ctx("Missing", root)).unwrap_err();
+        assert!(
+            err.reason
+                .contains("missing export '__macroforgeRunMissing'"),
+            "{}",
+            err.reason
+        );
+
+        let result = loader.run_macro(&debug_ctx("Count", root)).unwrap();
+        assert_eq!(result.debug.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn does_not_count_loading_against_the_timeout() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("package.json"),
+            r#"{"name":"root","workspaces":["packages/*"]}"#,
+        );
+        write(
+            &root.join("packages/macro/package.json"),
+            r#"{"name":"@ext/macro","main":"index.js"}"#,
+        );
+        write(
+            &root.join("packages/macro/index.js"),
+            r#"
+const loadedAt = Date.now() + 1000;
+while (Date.now() < loadedAt) {}
+exports.__macroforgeRunCount = () =>
+  JSON.stringify({ runtime_patches: [], type_patches: [], diagnostics: [], debug: "loaded" });
+"#,
+        );
+
+        let limits = ResourceLimits {
+            max_execution_time_ms: 500,
+            ..single_worker_limits()
+        };
+        let loader = ExternalMacroLoader::new(root, &limits);
+        let result = loader
+            .run_macro(&debug_ctx("Count", root))
+            .expect("loading the package should not use up the macro's timeout");
+        assert_eq!(result.debug.as_deref(), Some("loaded"));
+    }
+
+    #[test]
+    fn expanders_sharing_a_root_never_shrink_its_pool() {
+        let dir = tempdir().unwrap();
+        let config = |workers| MacroConfig {
+            limits: ResourceLimits {
+                max_external_workers: workers,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pool_of = |expander: &MacroExpander| {
+            expander
+                .external_loader
+                .as_ref()
+                .expect("expanders run external macros")
+                .pool
+                .clone()
+        };
+
+        let first = MacroExpander::with_config(config(3), dir.path().to_path_buf()).unwrap();
+        let second = MacroExpander::with_config(config(1), dir.path().to_path_buf()).unwrap();
+
+        assert!(Arc::ptr_eq(&pool_of(&first), &pool_of(&second)));
+        assert_eq!(pool_of(&first).size(), 3);
+
+        let third = MacroExpander::with_config(config(5), dir.path().to_path_buf()).unwrap();
+        assert_eq!(pool_of(&third).size(), 5);
+        assert_eq!(pool_of(&first).size(), 5);
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +43,7 @@
 //! - [`patch_applicator`] - Applies code patches with source mapping
 //! - [`registry`] - Thread-safe macro storage (`MacroRegistry`)
 //! - [`traits`] - Core traits (`Macroforge`, `MacroPackage`)
+//! - [`worker_pool`] - Long-lived Node.js workers that run external macros
 //!
 //! ## Key Types
 //!
@@ -105,6 +106,9 @@ pub mod registry;
 /// Core traits for macro implementations.
 pub mod traits;
 
+/// Long-lived Node.js workers for external macros.
+pub(crate) mod worker_pool;
+
 // Primary exports for convenience
 pub use config::{
     clear_config_cache, ForeignTypeConfig, ImportInfo, MacroConfig, MacroforgeConfig,
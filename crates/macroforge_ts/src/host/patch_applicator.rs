@@ -271,12 +271,15 @@ impl<'a> PatchApplicator<'a> {
                     let gen_len = rendered.len() as u32;
 
                     result.push_str(&rendered);
-                    // Create 0-based generated region
-                    mapping.add_generated(GeneratedRegion::new(
-                        expanded_pos - 1,
-                        expanded_pos - 1 + gen_len,
-                        macro_attribution,
-                    ));
+                    // Create 0-based generated region, remembering what it replaced
+                    mapping.add_generated(
+                        GeneratedRegion::new(
+                            expanded_pos - 1,
+                            expanded_pos - 1 + gen_len,
+                            macro_attribution,
+                        )
+                        .replacing(span.start.saturating_sub(1), span.end.saturating_sub(1)),
+                    );
 
                     expanded_pos += gen_len;
                     original_pos = span.end;
@@ -291,12 +294,15 @@ impl<'a> PatchApplicator<'a> {
 
                     let gen_len = code.len() as u32;
                     result.push_str(code);
-                    // Create 0-based generated region
-                    mapping.add_generated(GeneratedRegion::new(
-                        expanded_pos - 1,
-                        expanded_pos - 1 + gen_len,
-                        macro_attribution,
-                    ));
+                    // Create 0-based generated region, remembering what it replaced
+                    mapping.add_generated(
+                        GeneratedRegion::new(
+                            expanded_pos - 1,
+                            expanded_pos - 1 + gen_len,
+                            macro_attribution,
+                        )
+                        .replacing(span.start.saturating_sub(1), span.end.saturating_sub(1)),
+                    );
                     expanded_pos += gen_len;
                     original_pos = span.end;
                 }
@@ -433,6 +439,7 @@ impl<'a> PatchApplicator<'a> {
 pub struct PatchCollector {
     runtime_patches: Vec<Patch>,
     type_patches: Vec<Patch>,
+    dependencies: Vec<String>,
 }
 
 impl PatchCollector {
@@ -440,6 +447,7 @@ impl PatchCollector {
         Self {
             runtime_patches: Vec::new(),
             type_patches: Vec::new(),
+            dependencies: Vec::new(),
         }
     }
 
@@ -451,6 +459,19 @@ impl PatchCollector {
         self.type_patches.extend(patches);
     }
 
+    /// Records files a macro read while generating its output.
+    pub fn add_dependencies(&mut self, dependencies: Vec<String>) {
+        for dependency in dependencies {
+            if !self.dependencies.contains(&dependency) {
+                self.dependencies.push(dependency);
+            }
+        }
+    }
+
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+
     pub fn has_type_patches(&self) -> bool {
         !self.type_patches.is_empty()
     }
@@ -964,6 +985,7 @@ mod tests {
         let generated = &result.mapping.generated_regions[0];
         assert_eq!(generated.start, 8);
         assert_eq!(generated.end, 11);
+        assert_eq!(result.mapping.replaced_span(9), Some((8, 3)));
 
         // ";" unchanged (0-based: 11-12 original -> 11-12 expanded, same length replacement)
         let seg2 = &result.mapping.segments[1];
@@ -21,6 +21,7 @@
 //! for duplicates atomically to prevent race conditions during parallel loading.
 
 use super::{MacroError, Macroforge, error::Result};
+use crate::ts_syn::abi::MacroKind;
 use dashmap::DashMap;
 use std::sync::Arc;
 
@@ -246,6 +247,30 @@ impl MacroRegistry {
         })
     }
 
+    /// Looks up a macro of a specific kind by its exact module and name.
+    ///
+    /// Used to decide whether a decorator on a function is an attribute
+    /// macro, since a derive macro may share its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MacroError::MacroNotFound` if no macro of that kind is
+    /// registered under the module and name.
+    pub fn lookup_by_kind(
+        &self,
+        module: &str,
+        name: &str,
+        kind: MacroKind,
+    ) -> Result<Arc<dyn Macroforge>> {
+        self.lookup(module, name)
+            .ok()
+            .filter(|macro_impl| macro_impl.kind() == kind)
+            .ok_or_else(|| MacroError::MacroNotFound {
+                module: module.to_string(),
+                name: name.to_string(),
+            })
+    }
+
     /// Looks up a macro with fallback to name-only resolution.
     ///
     /// This is the primary lookup method used by the dispatcher.
@@ -0,0 +1,617 @@
+//! # External Macro Worker Pool
+//!
+//! Macros published as npm packages run in Node.js. Starting `node` and
+//! importing the macro package for every invocation dominates build time on
+//! large projects, so invocations are instead sent to a pool of long-lived
+//! worker processes that keep resolved macro modules loaded.
+//!
+//! ## Protocol
+//!
+//! Each worker is a `node` process running [`WORKER_SCRIPT`] that speaks
+//! framed JSON over stdio. A frame is a big-endian `u32` byte length followed
+//! by that many bytes of UTF-8 JSON:
+//!
+//! | Direction | Frame |
+//! |-----------|-------|
+//! | Request | `{ id, modulePath, fnName, fileName, ctx }` |
+//! | Loaded | `{ id, loaded: true }` |
+//! | Response | `{ id, output }` or `{ id, error }` |
+//!
+//! `ctx` is the serialized [`MacroContextIR`] and `output` is the string the
+//! macro returned. The worker reports `loaded` once it has found the macro
+//! function, before running it. Anything a macro writes to stdout is
+//! redirected to stderr so that it cannot corrupt the stream.
+//!
+//! ## Lifecycle
+//!
+//! - Workers are started on demand, up to the pool size, and run one
+//!   invocation at a time.
+//! - A worker that does not answer within the timeout is killed, as it may
+//!   be stuck in a loop. The timeout starts once the macro is loaded:
+//!   starting `node`, finding the workspace packages and importing the macro
+//!   package have their own, more generous budget ([`LOAD_TIMEOUT`]).
+//! - A worker that exits is replaced on the next invocation. If it exited
+//!   while idle, the invocation is retried on its replacement.
+//! - Workers exit when their stdin closes, so they never outlive the host.
+//!
+//! Expanders are short-lived, so pools are shared process-wide, one per
+//! project root (see [`WorkerPool::shared`]). A pool only ever grows, so an
+//! expander asking for fewer workers never takes them from another.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use napi::Status;
+use serde::{Deserialize, Serialize};
+
+use crate::ts_syn::abi::MacroContextIR;
+
+/// How long a worker may take to load a macro before running it.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Pools shared by all expanders in the process, keyed by project root.
+static POOLS: LazyLock<Mutex<HashMap<PathBuf, Arc<WorkerPool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A macro invocation, as sent to a worker.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request<'a> {
+    id: u64,
+    module_path: &'a str,
+    fn_name: &'a str,
+    file_name: &'a str,
+    ctx: &'a str,
+}
+
+/// A worker's answer to a [`Request`], or its report that the macro loaded.
+#[derive(Deserialize)]
+struct Response {
+    id: u64,
+    #[serde(default)]
+    loaded: bool,
+    #[serde(default)]
+    output: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Why a worker could not answer a request.
+enum CallError {
+    /// The request could not be sent because the worker had already exited.
+    Unavailable(io::Error),
+    /// The worker did not load the macro within [`LOAD_TIMEOUT`].
+    LoadTimedOut,
+    /// The macro did not return within the timeout.
+    TimedOut,
+    /// The worker exited, or broke the protocol, before answering.
+    Crashed(String),
+}
+
+/// A long-lived `node` process running [`WORKER_SCRIPT`].
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    /// Frames read from the worker's stdout by a background thread, so that
+    /// waiting for a response can time out.
+    responses: Receiver<io::Result<Vec<u8>>>,
+    next_id: u64,
+}
+
+impl Worker {
+    fn spawn(root_dir: &Path) -> io::Result<Self> {
+        let mut child = Command::new("node")
+            .current_dir(root_dir)
+            .arg("-e")
+            .arg(WORKER_SCRIPT)
+            .arg(root_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("worker stdin is piped");
+        let stdout = child.stdout.take().expect("worker stdout is piped");
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            let mut stdout = BufReader::new(stdout);
+            loop {
+                let frame = read_frame(&mut stdout);
+                let closed = frame.is_err();
+                if sender.send(frame).is_err() || closed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            responses,
+            next_id: 0,
+        })
+    }
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Sends an invocation and waits for its response: up to
+    /// [`LOAD_TIMEOUT`] for the macro to load, then up to `timeout` for it
+    /// to return.
+    fn call(
+        &mut self,
+        ctx: &MacroContextIR,
+        fn_name: &str,
+        ctx_json: &str,
+        timeout: Duration,
+    ) -> Result<Response, CallError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = serde_json::to_vec(&Request {
+            id,
+            module_path: &ctx.module_path,
+            fn_name,
+            file_name: &ctx.file_name,
+            ctx: ctx_json,
+        })
+        .expect("requests serialize to JSON");
+        write_frame(&mut self.stdin, &request).map_err(CallError::Unavailable)?;
+
+        let response = self.receive(id, LOAD_TIMEOUT, CallError::LoadTimedOut)?;
+        if response.loaded {
+            self.receive(id, timeout, CallError::TimedOut)
+        } else {
+            Ok(response)
+        }
+    }
+
+    /// Waits up to `timeout` for the next frame about request `id`.
+    fn receive(
+        &mut self,
+        id: u64,
+        timeout: Duration,
+        timed_out: CallError,
+    ) -> Result<Response, CallError> {
+        match self.responses.recv_timeout(timeout) {
+            Ok(Ok(frame)) => match serde_json::from_slice::<Response>(&frame) {
+                Ok(response) if response.id == id => Ok(response),
+                Ok(response) => Err(CallError::Crashed(format!(
+                    "answered request {} while request {id} was pending",
+                    response.id
+                ))),
+                Err(err) => Err(CallError::Crashed(format!(
+                    "sent a malformed response: {err}"
+                ))),
+            },
+            Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => {
+                Err(CallError::Crashed(self.exit_status()))
+            }
+            Err(RecvTimeoutError::Timeout) => Err(timed_out),
+        }
+    }
+
+    /// Describes how the worker exited, once its output has closed.
+    fn exit_status(&mut self) -> String {
+        let _ = self.child.kill();
+        match self.child.wait() {
+            Ok(status) => format!("exited with {status}"),
+            Err(err) => format!("closed its output ({err})"),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Reads one length-prefixed frame.
+fn read_frame(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut header = [0; 4];
+    reader.read_exact(&mut header)?;
+    let mut frame = vec![0; u32::from_be_bytes(header) as usize];
+    reader.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+/// Writes one length-prefixed frame.
+fn write_frame(writer: &mut impl Write, frame: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(frame.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame exceeds 4 GiB"))?;
+    writer.write_all(&length.to_be_bytes())?;
+    writer.write_all(frame)?;
+    writer.flush()
+}
+
+struct PoolState {
+    idle: Vec<Worker>,
+    /// Workers that are idle, running an invocation or starting.
+    live: usize,
+    size: usize,
+}
+
+/// A bounded pool of [`Worker`]s for one project root.
+pub(crate) struct WorkerPool {
+    root_dir: PathBuf,
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+impl WorkerPool {
+    fn new(root_dir: PathBuf, size: usize) -> Self {
+        Self {
+            root_dir,
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                live: 0,
+                size: size.max(1),
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// The process-wide pool for `root_dir`, grown to at least `size`
+    /// workers.
+    pub(crate) fn shared(root_dir: &Path, size: usize) -> Arc<Self> {
+        let pool = POOLS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(root_dir.to_path_buf())
+            .or_insert_with(|| Arc::new(Self::new(root_dir.to_path_buf(), size)))
+            .clone();
+        pool.grow(size);
+        pool
+    }
+
+    /// The maximum number of workers.
+    #[cfg(test)]
+    pub(crate) fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn grow(&self, size: usize) {
+        let mut state = self.lock();
+        if size <= state.size {
+            return;
+        }
+        state.size = size;
+        drop(state);
+        self.available.notify_all();
+    }
+
+    /// Takes an idle worker, starting one if the pool has room, or waits for
+    /// one to be released.
+    fn acquire(&self) -> napi::Result<Worker> {
+        let mut state = self.lock();
+        loop {
+            while let Some(mut worker) = state.idle.pop() {
+                if worker.is_running() {
+                    return Ok(worker);
+                }
+                state.live -= 1;
+            }
+
+            if state.live < state.size {
+                state.live += 1;
+                drop(state);
+                return Worker::spawn(&self.root_dir).map_err(|err| {
+                    self.retire(None);
+                    napi::Error::new(
+                        Status::GenericFailure,
+                        format!("Failed to spawn node for external macro: {err}"),
+                    )
+                });
+            }
+
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Returns a healthy worker to the pool.
+    fn release(&self, worker: Worker) {
+        self.lock().idle.push(worker);
+        self.available.notify_one();
+    }
+
+    /// Removes a worker from the pool, killing it if it is still running.
+    fn retire(&self, worker: Option<Worker>) {
+        drop(worker);
+        self.lock().live -= 1;
+        self.available.notify_one();
+    }
+
+    /// Runs the macro described by `ctx` and returns its output.
+    pub(crate) fn run(&self, ctx: &MacroContextIR, timeout: Duration) -> napi::Result<String> {
+        let fn_name = format!("__macroforgeRun{}", ctx.macro_name);
+        let ctx_json =
+            serde_json::to_string(ctx).map_err(|e| napi::Error::new(Status::InvalidArg, e))?;
+
+        let mut retried = false;
+        loop {
+            let mut worker = self.acquire()?;
+            let error = match worker.call(ctx, &fn_name, &ctx_json, timeout) {
+                Ok(response) => {
+                    self.release(worker);
+                    return match (response.output, response.error) {
+                        (Some(output), None) => Ok(output),
+                        (_, error) => Err(napi::Error::new(
+                            Status::GenericFailure,
+                            format!(
+                                "External macro runner failed: {}",
+                                error.unwrap_or_else(|| "no output".to_string())
+                            ),
+                        )),
+                    };
+                }
+                Err(CallError::Unavailable(_)) if !retried => {
+                    self.retire(Some(worker));
+                    retried = true;
+                    continue;
+                }
+                Err(CallError::Unavailable(err)) => {
+                    format!("External macro worker for '{fn_name}' is unavailable: {err}")
+                }
+                Err(CallError::LoadTimedOut) => format!(
+                    "External macro '{fn_name}' did not load within {} s",
+                    LOAD_TIMEOUT.as_secs()
+                ),
+                Err(CallError::TimedOut) => format!(
+                    "External macro '{fn_name}' timed out after {} ms",
+                    timeout.as_millis()
+                ),
+                Err(CallError::Crashed(reason)) => {
+                    format!("External macro worker {reason} while running '{fn_name}'")
+                }
+            };
+            self.retire(Some(worker));
+            return Err(napi::Error::new(Status::GenericFailure, error));
+        }
+    }
+}
+
+/// The worker's main loop. Macro modules and the workspace packages to look
+/// for them in are resolved once and kept for the lifetime of the worker.
+const WORKER_SCRIPT: &str = r#"
+const path = require('path');
+const fs = require('fs');
+const { pathToFileURL } = require('url');
+
+const rootDir = process.argv[1];
+
+// Frames own stdout; anything a macro prints goes to stderr instead.
+const writeFrameBytes = process.stdout.write.bind(process.stdout);
+process.stdout.write = process.stderr.write.bind(process.stderr);
+
+const send = (message) => {
+  const body = Buffer.from(JSON.stringify(message), 'utf8');
+  const header = Buffer.alloc(4);
+  header.writeUInt32BE(body.length, 0);
+  writeFrameBytes(Buffer.concat([header, body]));
+};
+
+const normalizeWorkspaces = (val) =>
+  Array.isArray(val) ? val : (val && Array.isArray(val.packages) ? val.packages : []);
+
+const toImportSpecifier = (id) => {
+  if (id.startsWith('.') || id.startsWith('/')) {
+    return pathToFileURL(path.resolve(rootDir, id)).href;
+  }
+  return id;
+};
+
+const expandWorkspace = (pattern) => {
+  if (typeof pattern !== 'string') return [];
+  const absolute = path.resolve(rootDir, pattern);
+  if (!pattern.includes('*')) {
+    return [absolute];
+  }
+
+  const starIdx = pattern.indexOf('*');
+  const baseDir = path.resolve(rootDir, pattern.slice(0, starIdx));
+  const suffix = pattern.slice(starIdx + 1);
+  if (!fs.existsSync(baseDir)) return [];
+
+  return fs
+    .readdirSync(baseDir, { withFileTypes: true })
+    .filter((entry) => entry.isDirectory())
+    .map((entry) => path.join(baseDir, entry.name + suffix));
+};
+
+// Package names and directories of the workspace, found on first use.
+let workspacePackages;
+const findWorkspacePackages = () => {
+  if (workspacePackages) return workspacePackages;
+  workspacePackages = [];
+
+  const addPackageDir = (dir) => {
+    try {
+      const pkgJsonPath = path.join(dir, 'package.json');
+      if (!fs.existsSync(pkgJsonPath)) return;
+      const pkgJson = JSON.parse(fs.readFileSync(pkgJsonPath, 'utf8'));
+      workspacePackages.push(pkgJson.name || dir, dir);
+    } catch {}
+  };
+
+  // Heuristic: check monorepo subpaths even without a root package.json
+  addPackageDir(path.join(rootDir, 'playground', 'macro'));
+  const packagesDir = path.join(rootDir, 'packages');
+  if (fs.existsSync(packagesDir)) {
+    for (const entry of fs.readdirSync(packagesDir, { withFileTypes: true })) {
+      if (!entry.isDirectory()) continue;
+      addPackageDir(path.join(packagesDir, entry.name));
+    }
+  }
+
+  try {
+    const rootPkg = JSON.parse(fs.readFileSync(path.join(rootDir, 'package.json'), 'utf8'));
+    for (const ws of normalizeWorkspaces(rootPkg.workspaces)) {
+      for (const pkgDir of expandWorkspace(ws)) {
+        addPackageDir(pkgDir);
+      }
+    }
+  } catch {}
+
+  return workspacePackages;
+};
+
+const candidatesFor = (modulePath, fileName) => {
+  const candidates = [];
+  const seen = new Set();
+  const addCandidate = (id) => {
+    if (!id) return;
+    const key = id.startsWith('.') || id.startsWith('/') ? path.resolve(rootDir, id) : id;
+    if (seen.has(key)) return;
+    seen.add(key);
+    candidates.push(id);
+  };
+
+  // Prefer node_modules near the file being processed (walk upward toward rootDir)
+  if (fileName) {
+    let current = path.dirname(fileName);
+    const rootResolved = path.resolve(rootDir);
+    while (true) {
+      addCandidate(path.join(current, 'node_modules', modulePath));
+      const parent = path.dirname(current);
+      if (parent === current || !path.resolve(parent).startsWith(rootResolved)) break;
+      current = parent;
+    }
+  }
+
+  // Fallbacks: requested specifier and its absolute form
+  addCandidate(modulePath);
+  addCandidate(path.resolve(rootDir, modulePath));
+
+  for (const id of findWorkspacePackages()) {
+    addCandidate(id);
+  }
+  return candidates;
+};
+
+const tryRequire = (id) => {
+  try {
+    return { module: require(id), loader: 'require' };
+  } catch (error) {
+    return { error };
+  }
+};
+
+const tryImport = async (id) => {
+  try {
+    return { module: await import(toImportSpecifier(id)), loader: 'import' };
+  } catch (error) {
+    return { error };
+  }
+};
+
+// Loaded modules by candidate id. Failures are not cached, so packages
+// installed while the worker runs are picked up.
+const modules = new Map();
+const load = async (id) => {
+  if (modules.has(id)) return modules.get(id);
+
+  let loaded = tryRequire(id);
+  if (!loaded.module) {
+    const imported = await tryImport(id);
+    if (!imported.module) {
+      return {
+        error: `Failed to load '${id}' via require/import: ${
+          imported.error?.message || loaded.error?.message || 'unknown error'
+        }`,
+      };
+    }
+    loaded = imported;
+  }
+
+  modules.set(id, loaded);
+  return loaded;
+};
+
+// Macro functions by module path, export name and calling directory.
+const macros = new Map();
+const findMacro = async (modulePath, fnName, fileName) => {
+  const key = JSON.stringify([modulePath, fnName, fileName ? path.dirname(fileName) : '']);
+  if (macros.has(key)) return { macro: macros.get(key) };
+
+  const errors = [];
+  for (const id of candidatesFor(modulePath, fileName)) {
+    const loaded = await load(id);
+    if (loaded.error) {
+      errors.push(loaded.error);
+      continue;
+    }
+
+    const mod = loaded.module;
+    const fn =
+      mod?.[fnName] ||
+      mod?.default?.[fnName] ||
+      (typeof mod?.default === 'object' ? mod.default[fnName] : undefined);
+
+    if (typeof fn !== 'function') {
+      errors.push(`Module '${id}' loaded via ${loaded.loader} but missing export '${fnName}'`);
+      continue;
+    }
+
+    const macro = { id, fn };
+    macros.set(key, macro);
+    return { macro };
+  }
+
+  if (errors.length === 0) {
+    errors.push('Macro not found in any workspace candidate');
+  }
+  return { error: errors.join('\n') };
+};
+
+const handle = async ({ id, modulePath, fnName, fileName, ctx }) => {
+  try {
+    const { macro, error } = await findMacro(modulePath, fnName, fileName);
+    if (error) {
+      send({ id, error });
+      return;
+    }
+
+    send({ id, loaded: true });
+    const output = await macro.fn(ctx);
+    if (typeof output === 'string') {
+      send({ id, output });
+    } else {
+      send({
+        id,
+        error: `Macro '${fnName}' in '${macro.id}' returned ${typeof output}, expected string`,
+      });
+    }
+  } catch (err) {
+    send({ id, error: err?.stack || String(err) });
+  }
+};
+
+let buffered = Buffer.alloc(0);
+let queue = Promise.resolve();
+process.stdin.on('data', (chunk) => {
+  buffered = Buffer.concat([buffered, chunk]);
+  while (buffered.length >= 4) {
+    const length = buffered.readUInt32BE(0);
+    if (buffered.length < 4 + length) break;
+    const request = JSON.parse(buffered.subarray(4, 4 + length).toString('utf8'));
+    buffered = buffered.subarray(4 + length);
+    queue = queue.then(() => handle(request));
+  }
+});
+process.stdin.on('end', () => process.exit(0));
+"#;
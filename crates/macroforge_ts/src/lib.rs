@@ -87,8 +87,8 @@ pub use macroforge_ts_syn as ts_syn;
 /// Macro attributes and quote templates
 /// Use: `use macroforge_ts::macros::*;`
 pub mod macros {
-    // Re-export the ts_macro_derive attribute
-    pub use macroforge_ts_macros::ts_macro_derive;
+    // Re-export the ts_macro_derive, ts_macro_attribute and ts_macro_call attributes
+    pub use macroforge_ts_macros::{ts_macro_attribute, ts_macro_call, ts_macro_derive};
 
     // Re-export all quote macros
     pub use macroforge_ts_quote::{above, below, body, signature, ts_template};
@@ -236,6 +236,11 @@ pub struct GeneratedRegionResult {
     pub end: u32,
     /// Name of the macro that generated this region (e.g., "Debug", "Clone").
     pub source_macro: String,
+    /// Start of the original source range this region replaced, if it is a
+    /// replacement (e.g., a call macro invocation).
+    pub original_start: Option<u32>,
+    /// End of the original source range this region replaced (exclusive).
+    pub original_end: Option<u32>,
 }
 
 /// Complete source mapping information for a macro expansion.
@@ -277,6 +282,7 @@ pub struct SourceMappingResult {
 ///     metadata: None,
 ///     diagnostics: vec![],
 ///     source_mapping: None,
+///     dependencies: vec![],
 /// };
 ///
 /// // Check for errors
@@ -297,6 +303,9 @@ pub struct ExpandResult {
     pub diagnostics: Vec<MacroDiagnostic>,
     /// Source mapping for position translation between original and expanded code.
     pub source_mapping: Option<SourceMappingResult>,
+    /// Files read by macros (e.g. `includeStr`); the source must be expanded
+    /// again when one of them changes.
+    pub dependencies: Vec<String>,
 }
 
 impl ExpandResult {
@@ -322,6 +331,7 @@ impl ExpandResult {
             metadata: None,
             diagnostics: vec![],
             source_mapping: None,
+            dependencies: vec![],
         }
     }
 }
@@ -575,6 +585,33 @@ impl NativePositionMapper {
             .map(|r| r.source_macro.clone())
     }
 
+    /// Returns the original span replaced by the generated code at the given position.
+    ///
+    /// Call macro invocations and other replacements map every position of
+    /// their output to the code they replaced, so diagnostics inside the
+    /// output can be reported at the invocation.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Byte offset in the expanded source
+    ///
+    /// # Returns
+    ///
+    /// `Some(SpanResult)` with the replaced span in original source,
+    /// `None` if the position is not inside replacement code.
+    #[napi]
+    pub fn replaced_span(&self, pos: u32) -> Option<SpanResult> {
+        let region = self
+            .generated_regions
+            .iter()
+            .find(|r| pos >= r.start && pos < r.end)?;
+        let (start, end) = (region.original_start?, region.original_end?);
+        Some(SpanResult {
+            start,
+            length: end.saturating_sub(start),
+        })
+    }
+
     /// Maps a span (start + length) from expanded source to original source.
     ///
     /// # Arguments
@@ -681,6 +718,13 @@ impl NativeMapper {
         self.inner.generated_by(pos)
     }
 
+    /// Returns the original span replaced by the generated code at the given position.
+    /// See [`NativePositionMapper::replaced_span`] for details.
+    #[napi]
+    pub fn replaced_span(&self, pos: u32) -> Option<SpanResult> {
+        self.inner.replaced_span(pos)
+    }
+
     /// Maps a span from expanded source to original source.
     /// See [`NativePositionMapper::map_span_to_original`] for details.
     #[napi]
@@ -1057,7 +1101,11 @@ impl NativePlugin {
             })??;
 
         // Update Cache: Store the result for future requests with the same version.
+        // Results that read other files are stored without a version, so they
+        // still back `get_mapper` but are never reused: the version only
+        // covers this file.
         if let Ok(mut guard) = self.cache.lock() {
+            let version = version.filter(|_| expand_result.dependencies.is_empty());
             guard.insert(
                 filepath.clone(),
                 CachedResult {
@@ -1211,6 +1259,119 @@ pub fn parse_import_sources(code: String, filepath: String) -> Result<Vec<Import
 )]
 pub fn derive_decorator() {}
 
+/// Error thrown by the call macro declarations when code runs unexpanded.
+fn unexpanded_call_macro(name: &str) -> Error {
+    Error::new(
+        Status::GenericFailure,
+        format!("{name}() is a macroforge call macro and must be expanded at build time"),
+    )
+}
+
+/// Declares the `includeStr` call macro for TypeScript type checking.
+///
+/// Calls are replaced during macro expansion; see `builtin::calls`.
+#[napi(
+    js_name = "includeStr",
+    ts_args_type = "path: string",
+    ts_return_type = "string"
+)]
+pub fn include_str_decl() -> Result<()> {
+    Err(unexpanded_call_macro("includeStr"))
+}
+
+/// Declares the `includeJson` call macro for TypeScript type checking.
+///
+/// Calls are replaced during macro expansion; see `builtin::calls`.
+#[napi(
+    js_name = "includeJson",
+    ts_generic_types = "T = any",
+    ts_args_type = "path: string",
+    ts_return_type = "T"
+)]
+pub fn include_json_decl() -> Result<()> {
+    Err(unexpanded_call_macro("includeJson"))
+}
+
+/// Declares the `buildEnv` call macro for TypeScript type checking.
+///
+/// Calls are replaced during macro expansion; see `builtin::calls`.
+#[napi(
+    js_name = "buildEnv",
+    ts_generic_types = "T = never",
+    ts_args_type = "name: string, fallback?: T",
+    ts_return_type = "string | T"
+)]
+pub fn build_env_decl() -> Result<()> {
+    Err(unexpanded_call_macro("buildEnv"))
+}
+
+/// Declares the `buildInfo` call macro for TypeScript type checking.
+///
+/// Calls are replaced during macro expansion; see `builtin::calls`.
+#[napi(
+    js_name = "buildInfo",
+    ts_return_type = "{ name: string | undefined; version: string; timestamp: string }"
+)]
+pub fn build_info_decl() -> Result<()> {
+    Err(unexpanded_call_macro("buildInfo"))
+}
+
+/// Declares the `concatIdents` call macro for TypeScript type checking.
+///
+/// Calls are replaced during macro expansion; see `builtin::calls`.
+#[napi(
+    js_name = "concatIdents",
+    ts_args_type = "...parts: any[]",
+    ts_return_type = "any"
+)]
+pub fn concat_idents_decl() -> Result<()> {
+    Err(unexpanded_call_macro("concatIdents"))
+}
+
+/// Declares the `deprecated` attribute macro for TypeScript type checking.
+///
+/// Decorators and JSDoc tags using it are replaced during macro expansion;
+/// see `builtin::attributes`.
+#[napi(
+    js_name = "deprecated",
+    ts_args_type = "...args: any[]",
+    ts_return_type = "any"
+)]
+pub fn deprecated_decl() {}
+
+/// Declares the `memoize` attribute macro for TypeScript type checking.
+///
+/// Decorators and JSDoc tags using it are replaced during macro expansion;
+/// see `builtin::attributes`.
+#[napi(
+    js_name = "memoize",
+    ts_args_type = "...args: any[]",
+    ts_return_type = "any"
+)]
+pub fn memoize_decl() {}
+
+/// Declares the `retry` attribute macro for TypeScript type checking.
+///
+/// Decorators and JSDoc tags using it are replaced during macro expansion;
+/// see `builtin::attributes`.
+#[napi(
+    js_name = "retry",
+    ts_args_type = "...args: any[]",
+    ts_return_type = "any"
+)]
+pub fn retry_decl() {}
+
+/// Declares the `trace` attribute macro for TypeScript type checking.
+///
+/// Decorators and JSDoc tags using it are replaced during macro expansion;
+/// see `builtin::attributes`.
+#[napi(
+    js_name = "trace",
+    ts_args_type = "...args: any[]",
+    ts_return_type = "any"
+)]
+pub fn trace_decl() {}
+
 /// Result of loading a macroforge configuration file.
 ///
 /// Returned by [`load_config`] after parsing a `macroforge.config.js/ts` file.
@@ -1418,6 +1579,48 @@ pub fn expand_sync(
 // Inner Logic (Optimized)
 // ============================================================================
 
+/// Cheap textual check for anything [`expand_inner`] could expand: a
+/// `@derive` directive, an `@name` reference to a registered attribute
+/// macro, or the name of a registered call macro.
+///
+/// Attribute names in comments only count when the code imports from
+/// `macroforge`, since JSDoc tags like `@deprecated` are plain
+/// documentation otherwise. A call macro is only invoked through an import
+/// naming it, so any occurrence of its name as a word counts, which also
+/// covers call sites using an alias.
+fn may_contain_macros(code: &str) -> bool {
+    if code.contains("@derive") {
+        return true;
+    }
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let imports_macroforge = code.contains("\"macroforge\"") || code.contains("'macroforge'");
+    let in_comment = |at: usize| {
+        let line_start = code[..at].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = &code[line_start..at];
+        let prefix = prefix.rsplit_once("*/").map_or(prefix, |(_, code)| code);
+        let prefix = prefix.trim_start();
+        prefix.starts_with('*') || prefix.contains("/*") || prefix.contains("//")
+    };
+
+    let attribute_names = derived::macro_names_of_kind(ts_syn::MacroKind::Attribute);
+    let has_attribute = attribute_names.iter().any(|name| {
+        code.match_indices(&format!("@{name}")).any(|(at, _)| {
+            !code[at + 1 + name.len()..].starts_with(is_ident_char)
+                && (imports_macroforge || !in_comment(at))
+        })
+    });
+
+    let call_names = derived::macro_names_of_kind(ts_syn::MacroKind::Call);
+    let has_call = call_names.iter().any(|name| {
+        code.match_indices(name).any(|(at, _)| {
+            !code[..at].ends_with(is_ident_char)
+                && !code[at + name.len()..].starts_with(is_ident_char)
+        })
+    });
+
+    has_attribute || has_call
+}
+
 /// Core macro expansion logic, decoupled from NAPI Env to allow threading.
 ///
 /// This function contains the actual expansion implementation and is called
@@ -1441,7 +1644,7 @@ pub fn expand_sync(
 ///
 /// # Algorithm
 ///
-/// 1. **Early bailout**: If code doesn't contain `@derive`, return unchanged
+/// 1. **Early bailout**: If code contains no `@derive`, attribute or call macro, return unchanged
 /// 2. **Parse**: Convert code to SWC AST
 /// 3. **Expand**: Run all registered macros on decorated classes
 /// 4. **Collect**: Gather diagnostics and source mapping
@@ -1451,11 +1654,11 @@ fn expand_inner(
     filepath: &str,
     options: Option<ExpandOptions>,
 ) -> Result<ExpandResult> {
-    // Early bailout: Skip files without @derive decorator.
+    // Early bailout: Skip files without @derive, attribute or call macros.
     // This optimization avoids expensive parsing for files that don't use macros
     // and prevents issues with Svelte runes ($state, $derived, etc.) that use
     // similar syntax but aren't macroforge decorators.
-    if !code.contains("@derive") {
+    if !may_contain_macros(code) {
         return Ok(ExpandResult::unchanged(code));
     }
 
@@ -1521,6 +1724,7 @@ fn expand_inner(
                     end: None,
                 }],
                 source_mapping: None,
+                dependencies: vec![],
             });
         }
     };
@@ -1581,6 +1785,8 @@ fn expand_inner(
                 start: region.start,
                 end: region.end,
                 source_macro: region.source_macro,
+                original_start: region.original_start,
+                original_end: region.original_end,
             })
             .collect(),
     });
@@ -1612,6 +1818,7 @@ fn expand_inner(
         },
         diagnostics,
         source_mapping,
+        dependencies: expansion.dependencies,
     })
 }
 
@@ -16,6 +16,23 @@
 //! - **Hash** - Generates `hashCode()` methods for hash-based collections
 //! - **Ord/PartialOrd** - Generates `compareTo()` methods for ordering
 //! - **Default** - Generates `defaultValue()` factory methods
+//! - **Builder** - Generates typed fluent builders
+//! - **With** - Generates immutable copy-on-write updaters
+//! - **Diff** - Generates structural diffs and JSON Patch support
+//! - **Arbitrary** - Generates seeded random values for property tests
+//! - **EnumUtils** - Generates iteration, parsing and type guards for enums and literal unions
+//! - **Bitflags** - Generates flag-set helpers for numeric enums
+//! - **TypeGuard** - Generates structural `isType` runtime guards
+//! - **Reflect** - Generates frozen runtime type metadata
+//! - **Mock** - Generates recording, stubbable test doubles for interfaces
+//! - **HttpClient** - Generates typed fetch clients and server dispatchers
+//! - **Env** - Generates typed loaders for environment variable configuration
+//! - **CliArgs** - Generates command-line parsers and subcommand groups
+//! - **Csv** - Generates CSV row encoding and decoding with header mapping
+//! - **SqlRow** - Generates database row mapping, query parameters and DDL
+//! - **GraphQL** - Generates GraphQL SDL and resolver types
+//! - **StateMachine** - Generates transition checks and diagrams for enums
+//! - **Branded** - Turns primitive type aliases into nominal types
 //! - **Serialize** - Generates JSON serialization methods
 //! - **Deserialize** - Generates JSON deserialization methods with validation
 //!
@@ -139,11 +156,98 @@ class Data {
 
         assert!(result.changed, "expand() should report changes");
         // Debug macro adds static toString method and standalone function
-        assert!(result.code.contains("static toString(value: Data)"));
+        assert!(
+            result.code.contains(
+                "static toString(value: Data, pretty?: boolean, ctx?: __mf_DebugContext)"
+            )
+        );
         assert!(result.code.contains("dataToString"));
     });
 }
 
+#[test]
+fn test_derive_debug_threads_context_through_cross_class_cycles() {
+    let source = r#"
+/**
+ * @derive(Debug)
+ * @debug({ maxDepth: 4 })
+ */
+class Parent {
+    child: Child;
+}
+
+/** @derive(Debug) */
+class Child {
+    parent: Parent;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        for fn_name in ["parentToString", "childToString"] {
+            assert!(
+                result
+                    .code
+                    .contains(&format!("export function {fn_name}(value:")),
+                "{fn_name} should be generated. Got:\n{}",
+                result.code
+            );
+        }
+        assert_eq!(
+            result
+                .code
+                .matches("const __c = __mf_debugContext(pretty, ctx);")
+                .count(),
+            2,
+            "Each class should continue the caller's context. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                r#"import { debugContext as __mf_debugContext } from "macroforge/debug";"#
+            ) && result
+                .code
+                .contains(r#"import { debugFormat as __mf_debugFormat } from "macroforge/debug";"#),
+            "The formatter should come from the runtime module. Got:\n{}",
+            result.code
+        );
+        assert_eq!(
+            result
+                .code
+                .matches("static readonly [__mf_debugDerived] = true;")
+                .count(),
+            2,
+            "Each class should carry the marker debugFormat looks for. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("return childToString(value, pretty, ctx);"),
+            "The static wrapper should forward the caller's context. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("const __debugFmt"),
+            "The formatter should not be inlined. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("maxDepth: Math.min(__c.maxDepth, 4)"),
+            "The container limit should tighten the inherited one. Got:\n{}",
+            result.code
+        );
+    });
+}
+
 #[test]
 fn test_derive_debug_dts_output() {
     let source = r#"
@@ -165,13 +269,19 @@ class User {
 
         // New format: static method + standalone function
         assert!(
-            type_output.contains("static toString(value: User): string"),
+            type_output.contains(
+                "static toString(value: User, pretty?: boolean, ctx?: __mf_DebugContext): string"
+            ),
             "should have static toString method"
         );
         assert!(
             type_output.contains("export function userToString"),
             "should have standalone function"
         );
+        assert!(
+            !type_output.contains("__debugFmt") && !type_output.contains("__ctx"),
+            "the formatting context should not be part of the public surface"
+        );
     });
 }
 
@@ -245,6 +355,34 @@ class User {
     });
 }
 
+#[test]
+fn test_derive_hash_warns_when_partial_eq_skips_a_hashed_field() {
+    let source = r#"
+/** @derive(PartialEq, Hash) */
+class User {
+    name: string;
+    /** @partialEq({ skip: true }) */
+    cachedAt: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.level == DiagnosticLevel::Warning
+                    && d.message.contains("'cachedAt' is skipped by PartialEq")),
+            "Hash should warn about fields PartialEq skips. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
 #[test]
 fn test_derive_debug_complex_dts_output() {
     let source = r#"
@@ -290,7 +428,7 @@ class MacroUser {
 
         // New format: static method + standalone function
         assert!(
-            type_output.contains("static toString(value: MacroUser): string"),
+            type_output.contains("static toString(value: MacroUser, pretty?: boolean, ctx?: __mf_DebugContext): string"),
             "should have static toString method"
         );
         assert!(
@@ -528,14 +666,15 @@ fn test_collect_derive_debug_patch() {
 
     let type_patches = collector.get_type_patches();
 
-    // Expecting 3 patches:
+    // Expecting 4 patches:
     // 1. Decorator removal for /** @derive(Debug) */
-    // 2. Static toString method insertion inside class (from Derive(Debug) macro)
-    // 3. Standalone function userToString insertion after class
+    // 2. Static debugDerived marker insertion inside class (from Derive(Debug) macro)
+    // 3. Static toString method insertion inside class
+    // 4. Standalone function userToString insertion after class
     assert_eq!(
         type_patches.len(),
-        3,
-        "Expected 3 patches, got {}",
+        4,
+        "Expected 4 patches, got {}",
         type_patches.len()
     );
 
@@ -569,6 +708,8 @@ fn test_apply_and_finalize_expansion_no_type_patches() {
                 interfaces: Vec::new(),
                 enums: Vec::new(),
                 type_aliases: Vec::new(),
+                functions: Vec::new(),
+                calls: Vec::new(),
             },
         )
         .unwrap();
@@ -613,7 +754,9 @@ class Product {
         let type_output = result.type_output.expect("should have type output");
 
         // Check for static methods on class
-        assert!(type_output.contains("static toString(value: Product): string"));
+        assert!(type_output.contains(
+            "static toString(value: Product, pretty?: boolean, ctx?: __mf_DebugContext): string"
+        ));
         assert!(type_output.contains("static clone(value: Product): Product"));
         assert!(type_output.contains("static equals(a: Product, b: Product): boolean"));
         assert!(type_output.contains("static hashCode(value: Product): number"));
@@ -664,7 +807,9 @@ class API {
         let type_output = result.type_output.expect("should have type output");
 
         // Check for static method and standalone function
-        assert!(type_output.contains("static toString(value: API): string"));
+        assert!(type_output.contains(
+            "static toString(value: API, pretty?: boolean, ctx?: __mf_DebugContext): string"
+        ));
         assert!(type_output.contains("export function apiToString"));
         // Original methods should still be present
         assert!(type_output.contains("async fetch<T>"));
@@ -753,7 +898,9 @@ class Config {
         let type_output = result.type_output.expect("should have type output");
 
         // Check for static methods and standalone functions
-        assert!(type_output.contains("static toString(value: Config): string"));
+        assert!(type_output.contains(
+            "static toString(value: Config, pretty?: boolean, ctx?: __mf_DebugContext): string"
+        ));
         assert!(type_output.contains("static equals(a: Config, b: Config): boolean"));
         assert!(type_output.contains("static hashCode(value: Config): number"));
         assert!(type_output.contains("export function configToString"));
@@ -797,7 +944,9 @@ class Singleton {
         let type_output = result.type_output.expect("should have type output");
 
         // Check for static method and standalone function
-        assert!(type_output.contains("static toString(value: Singleton): string"));
+        assert!(type_output.contains(
+            "static toString(value: Singleton, pretty?: boolean, ctx?: __mf_DebugContext): string"
+        ));
         assert!(type_output.contains("export function singletonToString"));
     });
 }
@@ -834,7 +983,10 @@ class ValidationExample {
         let type_output = result.type_output.expect("should have type output");
 
         // Check for static method and standalone function
-        assert!(type_output.contains("static toString(value: ValidationExample): string"));
+        assert!(
+            type_output
+                .contains("static toString(value: ValidationExample, pretty?: boolean, ctx?: __mf_DebugContext): string")
+        );
         assert!(type_output.contains("export function validationExampleToString"));
     });
 }
@@ -870,7 +1022,11 @@ class User {
         // Find the static toString line
         let tostring_line = lines
             .iter()
-            .position(|l| l.contains("static toString(value: User)"))
+            .position(|l| {
+                l.contains(
+                    "static toString(value: User, pretty?: boolean, ctx?: __mf_DebugContext)",
+                )
+            })
             .expect("should have static toString");
         // Find the static clone line
         let clone_line = lines
@@ -914,12 +1070,18 @@ class User {
         // Find the static toString line
         let tostring_line = type_output
             .lines()
-            .find(|l| l.contains("static toString(value: User)"))
+            .find(|l| {
+                l.contains(
+                    "static toString(value: User, pretty?: boolean, ctx?: __mf_DebugContext)",
+                )
+            })
             .expect("should have static toString method");
 
         // Verify it has proper indentation (static method inside class)
         assert!(
-            tostring_line.contains("static toString(value: User)"),
+            tostring_line.contains(
+                "static toString(value: User, pretty?: boolean, ctx?: __mf_DebugContext)"
+            ),
             "should have static toString method, got: '{}'",
             tostring_line
         );
@@ -972,7 +1134,7 @@ class ServerConfig {
 
         // Check for static method in class
         assert!(
-            type_output.contains("static toString(value: ServerConfig): string"),
+            type_output.contains("static toString(value: ServerConfig, pretty?: boolean, ctx?: __mf_DebugContext): string"),
             "should have static toString method, got:\n{}",
             type_output
         );
@@ -1116,6 +1278,8 @@ fn native_position_mapper_matches_js_logic() {
             start: 10,
             end: 12,
             source_macro: "demo".into(),
+            original_start: None,
+            original_end: None,
         }],
     };
 
@@ -1755,6 +1919,47 @@ export interface Point {
     });
 }
 
+#[test]
+fn test_ord_collators_are_created_lazily() {
+    let source = r#"
+/**
+ * @derive(PartialOrd, Ord)
+ * @ord({ locale: "de" })
+ */
+export interface Person {
+    name: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        for fn_name in ["personCompare", "personPartialCompare"] {
+            assert!(
+                result.code.contains(&format!(
+                    "var __{fn_name}Collator0: Intl.Collator | undefined;"
+                )),
+                "{fn_name} should declare a hoisted collator cache. Got:\n{}",
+                result.code
+            );
+            assert!(
+                result.code.contains(&format!(
+                    "(__{fn_name}Collator0 ??= new Intl.Collator(\"de\")).compare(a.name, b.name)"
+                )),
+                "{fn_name} should create its collator on first use. Got:\n{}",
+                result.code
+            );
+        }
+        assert!(
+            !result.code.contains("const __person"),
+            "Collators should not be block-scoped constants. Got:\n{}",
+            result.code
+        );
+    });
+}
+
 #[test]
 fn test_external_type_function_imports_for_prefix_style() {
     let source = r#"
@@ -2630,6 +2835,89 @@ export type ActivityType = /** @default */ Created | Edited | Sent;
     });
 }
 
+#[test]
+fn test_derive_default_on_discriminated_union_object_member() {
+    let source = r#"
+/** @derive(Default) */
+export type Shape =
+  | /** @default */ { kind: "circle"; radius: number }
+  | { kind: "square"; size: number };
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        let error_count = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.level == DiagnosticLevel::Error)
+            .count();
+        assert_eq!(
+            error_count, 0,
+            "Should have no errors with @default on an object member. Got: {:?}",
+            result.diagnostics
+        );
+
+        assert!(
+            result.code.contains("shapeDefaultValue"),
+            "Should generate shapeDefaultValue function. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("radius: 0"),
+            "Should fill the variant's fields from their defaults. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("size: 0"),
+            "Should only build the @default variant. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_default_factory_adds_import() {
+    let source = r#"
+/** @derive(Default) */
+interface Entity {
+    /** @default({ factory: "makeId", from: "./ids" }) */
+    id: string;
+    /** @default({ factory: "crypto.randomUUID" }) */
+    token: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.code.contains("id: makeId()"),
+            "Should call the factory for the default. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("token: crypto.randomUUID()"),
+            "Should call global factories as-is. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("import { makeId } from \"./ids\""),
+            "Should import the factory from its module. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("import { crypto }"),
+            "Should not import global factories. Got:\n{}",
+            result.code
+        );
+    });
+}
+
 #[test]
 fn test_inline_jsdoc_with_export_interface() {
     // Test that /** @derive(X) */ export interface works on same line
@@ -2755,34 +3043,2255 @@ fn test_inline_jsdoc_with_export_type() {
     });
 }
 
-// ==================== EARLY BAILOUT TESTS ====================
+// ==================== BUILDER TESTS ====================
 
 #[test]
-fn test_early_bailout_no_derive_returns_unchanged() {
-    // Code without @derive should be returned unchanged immediately
-    use crate::expand_inner;
-
+fn test_derive_builder_on_class() {
     let source = r#"
+/** @derive(Builder) */
 class User {
     name: string;
-    constructor(name: string) {
-        this.name = name;
-    }
+    email: string;
+    /** @default("member") */
+    role: string;
+    nickname?: string;
 }
 "#;
 
-    let result = expand_inner(source, "test.ts", None).unwrap();
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
 
-    // Code should be returned exactly as-is
-    assert_eq!(
-        result.code, source,
-        "Code without @derive should be returned unchanged"
-    );
-    assert!(result.types.is_none(), "No type output expected");
-    assert!(result.diagnostics.is_empty(), "No diagnostics expected");
-    assert!(
-        result.source_mapping.is_none(),
-        "No source mapping expected"
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("static builder(): UserBuilder"),
+            "Should add a static builder() method. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("class UserBuilder<__Set extends string = never>"),
+            "Should generate the builder class. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("Exclude<\"name\" | \"email\", __Set>"),
+            "Only fields without defaults should be required. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_builder_on_generic_class() {
+    let source = r#"
+/** @derive(Builder) */
+class Page<T extends object, K extends keyof T = keyof T> {
+    items: T[];
+    sortBy?: K;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains(
+                "class PageBuilder<T extends object, K extends keyof T = keyof T, __Set extends string = never>"
+            ),
+            "The builder should declare the class's type parameters. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("static builder<T extends object, K extends keyof T = keyof T>(): PageBuilder<T, K>"),
+            "The static builder() should declare its own type parameters. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("keyof Page<T, K>") && result.code.contains("): Page<T, K> {"),
+            "Generated types should apply the type parameters. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_builder_validate_with_deserialize() {
+    let source = r#"
+/** @derive(Builder, Deserialize) @builder({ validate: true }) */
+class Config {
+    port: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            !result
+                .diagnostics
+                .iter()
+                .any(|d| d.level == DiagnosticLevel::Error),
+            "Should accept validate alongside Deserialize. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("const errors = Config.validateFields(resolved);"),
+            "build() should run the validators. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_builder_validate_requires_deserialize() {
+    let source = r#"
+/** @derive(Builder) @builder({ validate: true }) */
+interface Config {
+    port: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.level == DiagnosticLevel::Error
+                    && d.message.contains("@derive(Deserialize)")),
+            "Should require Deserialize for validation. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== WITH TESTS ====================
+
+#[test]
+fn test_derive_with_on_class_and_interface() {
+    let source = r#"
+/** @derive(With) */
+class Point {
+    x: number;
+    y: number;
+}
+
+/** @derive(With) */
+interface User {
+    name: string;
+    address: { city: string };
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("static withX(value: Point"),
+            "Should add static updaters to the class. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("Object.create(Object.getPrototypeOf(value))"),
+            "Class copies should preserve the prototype. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("userWithAddress"),
+            "Should generate interface updaters. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("macroforge/with"),
+            "Should import the setIn runtime helpers. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_with_on_generic_types() {
+    let source = r#"
+/** @derive(With) */
+class Slot<T> {
+    value: T;
+}
+
+/** @derive(With) */
+type Pair<A, B = A> = {
+    first: A;
+    second: B;
+};
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("static withValue<T>(value: Slot<T>, next: Slot<T>[\"value\"]): Slot<T>"),
+            "Static updaters should declare the class's type parameters. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "export function pairWith<A, B>(value: Pair<A, B>, patch: Partial<Pair<A, B>>): Pair<A, B>"
+            ),
+            "Updaters should be generic over the alias's type parameters. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== DIFF TESTS ====================
+
+#[test]
+fn test_derive_diff_recurses_into_nested_types() {
+    let source = r#"
+/** @derive(Diff) */
+interface OrderLine {
+    sku: string;
+    quantity: number;
+}
+
+/** @derive(Diff) */
+class Order {
+    status: string;
+    /** @diff({ key: "sku" }) */
+    lines: OrderLine[];
+    /** @partialEq({ skip: true }) */
+    updatedAt: Date;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("{ key: \"sku\", diff: orderLineDiffChanges }"),
+            "Array elements should be matched by key and diffed with their own differ. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("path + \"/updatedAt\""),
+            "Fields skipped by PartialEq should not be diffed. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("static applyPatch(value: Order"),
+            "Should add static wrappers to the class. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("macroforge/diff"),
+            "Should import the diff runtime helpers. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== ARBITRARY TESTS ====================
+
+#[test]
+fn test_derive_arbitrary_respects_validators_and_nested_types() {
+    let source = r#"
+/** @derive(Arbitrary) */
+interface Address {
+    city: string;
+}
+
+/** @derive(Arbitrary, Deserialize) */
+class User {
+    /** @serde({ validate: ["email"] }) */
+    email: string;
+    role: "admin" | "member";
+    /** @serde({ validate: ["int", "between(0, 130)"] }) */
+    age: number;
+    address: Address;
+    nickname?: string;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("format: \"email\""),
+            "Email fields should generate email addresses. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("{ int: true, min: 0, max: 130 }"),
+            "Numeric validators should bound the generated numbers. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("rng.pick([\"admin\", \"member\"])"),
+            "Literal unions should pick one of the literals. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("address: addressArbitrary(rng)"),
+            "Nested types should use their own generator. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("static arbitrary(rng: __mf_Random): User"),
+            "Should add a static wrapper to the class. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("macroforge/arbitrary"),
+            "Should import the arbitrary runtime helpers. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== ENUM UTILS TESTS ====================
+
+#[test]
+fn test_derive_enum_utils_on_enum_and_literal_union() {
+    let source = r#"
+/** @derive(EnumUtils) */
+enum Direction {
+    Up = "UP",
+    Down = "DOWN",
+}
+
+/** @derive(EnumUtils) @enumUtils({ ignoreCase: true }) */
+type Color = "red" | "green";
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("return [Direction.Up, Direction.Down];"),
+            "Enum values should list every member. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("export function isDirectionUp(value: unknown): value is Direction.Up"),
+            "Should generate per-variant guards for enums. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("return [\"red\", \"green\"];"),
+            "Literal union values should list every literal. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("options?.ignoreCase ?? true"),
+            "@enumUtils({{ ignoreCase: true }}) should make parsing case-insensitive by default. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("export function colorNext(value: Color"),
+            "Literal unions should get next/prev helpers. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_enum_utils_rejects_non_literal_unions() {
+    let source = r#"
+/** @derive(EnumUtils) */
+type Shape = Circle | Square;
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.iter().any(|d| d
+                .message
+                .contains("every union member to be a literal type")),
+            "Should reject unions of type references. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== BITFLAGS TESTS ====================
+
+#[test]
+fn test_derive_bitflags_integrates_with_debug_and_serde() {
+    let source = r#"
+/** @derive(Bitflags, Debug, Serialize, Deserialize) @bitflags({ serializeAs: "names" }) */
+enum Permission {
+    Read = 1,
+    Write = 2,
+    Execute = 4,
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("export function permissionToggle("),
+            "Should generate the flag helpers. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("return permissionFormat(value);"),
+            "Debug should format the set flags. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("permissionToArray(value).map((flag)=>Permission[flag])"),
+            "serializeAs: \"names\" should serialize flag names. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("permissionFromArray(permissionToArray(value))=== value"),
+            "Deserialize should accept combinations of flags. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_bitflags_reports_invalid_variants() {
+    let source = r#"
+/** @derive(Bitflags) */
+enum Permission {
+    Read = 1,
+    Write = 3,
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("'Write' has value 3")),
+            "Should report the variant that is not a power of two. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== TYPE GUARD TESTS ====================
+
+#[test]
+fn test_derive_type_guard_checks_fields_and_extra_keys() {
+    let source = r#"
+/** @derive(TypeGuard) @typeGuard({ strict: true, deep: true }) */
+interface User {
+    id: number;
+    role: "admin" | "member";
+    addresses: Address[];
+    tags: Set<string>;
+    nickname?: string;
+}
+
+/** @derive(TypeGuard) */
+type Shape = { kind: "circle"; radius: number } | { kind: "square"; size: number };
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("export function isUser(value: unknown): value is User"),
+            "Should generate the guard. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("for (const key in o) {")
+                && result.code.contains("case \"nickname\":\n                break;\n            default:\n                return false;"),
+            "strict should reject extra keys. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("\"addresses\" in o && __isUser0(o.addresses)")
+                && result.code.contains("function __isUser0(value: unknown): boolean {")
+                && result.code.contains("if (!(isAddress(item))) {"),
+            "deep should check array elements with their own guard. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("if (!(value instanceof Set)) {")
+                && !result.code.contains("[...")
+                && !result.code.contains("Object.keys("),
+            "Collections and keys should be checked without copying them. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("(o.nickname === undefined || typeof o.nickname === \"string\")"),
+            "Optional fields may be missing. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("export function isShape(value: unknown): value is Shape"),
+            "Union aliases should get a guard. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("return __isShape0(value)\n        || __isShape1(value);"),
+            "Object members should be checked by helpers. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== REFLECT TESTS ====================
+
+#[test]
+fn test_derive_reflect_records_fields_methods_and_docs() {
+    let source = r#"
+/** @derive(Reflect) */
+class User {
+    /** Login address. */
+    email: string;
+    /** @reflect({ skip: true }) */
+    password: string;
+    protected readonly nickname?: string;
+
+    async rename(name: string): Promise<void> {}
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("export function userReflect(): __mf_TypeReflection<User>"),
+            "Should generate the typed accessor. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("doc: \"Login address.\""),
+            "Should record field JSDoc. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("name: \"password\""),
+            "Skipped fields should be left out. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("visibility: \"protected\""),
+            "Should record visibility. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("returnType: \"Promise<void>\""),
+            "Should record method signatures. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== MOCK TESTS ====================
+
+#[test]
+fn test_derive_mock_generates_typed_test_double() {
+    let source = r#"
+/** @derive(Mock) */
+interface UserService {
+    readonly baseUrl: string;
+    onChange?: (user: User) => void;
+    getUser(id: string): Promise<User>;
+    save(user: User): Promise<void>;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("export class UserServiceMock implements UserService"),
+            "Should generate the mock class. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "__mf_mockFunction<UserService[\"getUser\"]>(\"UserService.getUser\", \"throw\")"
+            ),
+            "Methods returning values should be strict. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("(\"UserService.save\", \"resolve\")"),
+            "Promise<void> methods should resolve without a stub. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("__mf_mockFunction<NonNullable<UserService[\"onChange\"]>>"),
+            "Function-typed properties should be mocked. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("readonly baseUrl!: string;"),
+            "Data fields should be declared. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_mock_rejects_classes() {
+    let source = r#"
+/** @derive(Mock) */
+class UserService {
+    getUser(id: string): string { return id; }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("can only be used on interfaces")),
+            "Should reject non-interfaces. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== HTTP CLIENT TESTS ====================
+
+#[test]
+fn test_derive_http_client_generates_client_and_dispatcher() {
+    let source = r#"
+/** @derive(HttpClient) */
+interface UserService {
+    /** @route({ method: "GET", path: "/users/:id" }) */
+    getUser(id: string, fields?: string): Promise<User>;
+
+    /** @route({ method: "PUT", path: "/users/:id" }) */
+    saveUser(id: string, user: User, at: Date): Promise<void>;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("export class UserServiceClient implements UserService"),
+            "Should generate the client. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("query: { fields }"),
+            "GET parameters outside the path should be query parameters. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "body: { user: userSerializeWithContext(user, __mf_SerializeContext.create()), at: at.toISOString() }"
+            ),
+            "Body parameters should be serialized. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("export function userServiceDispatcher(impl: UserService)"),
+            "Should generate the dispatcher. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("const user = __mf_unwrap(userDeserialize(input[\"user\"]), \"user\");"),
+            "The dispatcher should deserialize body parameters. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_http_client_requires_routes() {
+    let source = r#"
+/** @derive(HttpClient) */
+interface UserService {
+    getUser(id: string): Promise<User>;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("method 'getUser' needs a route")),
+            "Should report the method without a route. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== ENV TESTS ====================
+
+#[test]
+fn test_derive_env_on_class() {
+    let source = r#"
+/** @derive(Env) @env({ prefix: "APP_" }) */
+class AppConfig {
+    /** @env({ default: "8080" }) @serde({ validate: ["positive"] }) */
+    port: number;
+    /** @env({ name: "DATABASE_URL", secret: true }) */
+    databaseUrl: URL;
+    allowedOrigins: string[];
+    mode: "dev" | "prod";
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("static loadFromEnv(env: __mf_EnvSource = __mf_processEnv()): AppConfig"),
+            "Should generate the static loader. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("appConfigReadEnv(env, \"APP_\", issues)"),
+            "Should read under the container prefix. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("prefix + \"ALLOWED_ORIGINS\""),
+            "Should map fields to SCREAMING_SNAKE_CASE. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "__mf_readEnv(env, \"DATABASE_URL\", __mf_envParsers.url, issues, { secret: true })"
+            ),
+            "Should use the exact name and hide the secret. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("throw new __mf_EnvError(issues)"),
+            "Should report all problems at once. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_env_rejects_unsupported_types() {
+    let source = r#"
+/** @derive(Env) */
+interface Config {
+    headers: Map<string, string>;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("can't read field 'headers'")),
+            "Should report the unsupported field. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== CLI ARGS TESTS ====================
+
+#[test]
+fn test_derive_cli_args_on_class() {
+    let source = r#"
+/** @derive(CliArgs) @command({ name: "serve", version: "1.0.0" }) */
+class ServeArgs {
+    /**
+     * Port to listen on
+     * @default(8080)
+     */
+    port: number;
+    /** @arg({ positional: true }) */
+    root: string;
+    verbose: boolean;
+    mode?: "dev" | "prod";
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("export const serveArgsCli: __mf_CliCommand<ServeArgs>"),
+            "Should generate the command descriptor. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("long: \"port\", short: \"p\", valueName: \"PORT\", help: \"Port to listen on\", default: () => (8080)"),
+            "Should derive names, help and default from the field. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("choices: [\"dev\", \"prod\"]"),
+            "Should turn literal unions into choices. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("static parseArgs("),
+            "Should generate the static parser. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_cli_args_subcommands_from_union() {
+    let source = r#"
+/** @derive(CliArgs) */
+type Command = BuildCommand | ServeCommand;
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .code
+                .contains("commands: () => [buildCommandCli, serveCommandCli]"),
+            "Should dispatch to the member commands. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== CSV TESTS ====================
+
+#[test]
+fn test_derive_csv_on_class() {
+    let source = r#"
+/** @derive(Csv) @serde({ renameAll: "snake_case" }) */
+class Customer {
+    id: number;
+    fullName: string;
+    /** @serde({ validate: ["email"] }) */
+    email: string;
+    billing: Address;
+    signedUp?: Date;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains(
+                "return [\"id\", \"full_name\", \"email\", ...addressCsvHeader().map((column) => \"billing.\" + column), \"signed_up\"];"
+            ),
+            "Should rename columns and flatten nested types. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "__mf_readCell(record, \"signed_up\", __mf_csvDecoders.date, errors, \"optional\")"
+            ),
+            "Should read optional dates. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("errors.push({ field: \"email\", message: \"must be a valid email\" });"),
+            "Should run serde validators. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("static parseCsv(text: string): __mf_CsvResult<Customer[]>"),
+            "Should generate the static parser. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_csv_rejects_unknown_cell() {
+    let source = r#"
+/** @derive(Csv) */
+interface Invoice {
+    /** @csv({ cell: "money" }) */
+    total: Cents;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("@csv cell 'money'")),
+            "Should report the unknown cell kind. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== SQL ROW TESTS ====================
+
+#[test]
+fn test_derive_sql_row_on_class() {
+    let source = r#"
+/** @derive(SqlRow) @table({ name: "users", renameAll: "snake_case" }) */
+class User {
+    /** @column({ primaryKey: true }) */
+    id: bigint;
+    fullName: string;
+    createdAt: Date;
+    deletedAt: Date | null;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains(
+                "export const userColumns: readonly string[] = [\"id\", \"full_name\", \"created_at\", \"deleted_at\"];"
+            ),
+            "Should rename the columns. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "createdAt: __mf_readColumn(row, \"created_at\", __mf_sqlDecoders.date)"
+            ),
+            "Should coerce timestamps. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "UPDATE \\\"users\\\" SET \\\"full_name\\\" = $2, \\\"created_at\\\" = $3, \\\"deleted_at\\\" = $4 WHERE \\\"id\\\" = $1"
+            ),
+            "Should update by primary key. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("\\\"deleted_at\\\" TIMESTAMPTZ,\\n"),
+            "Should make nullable fields nullable columns. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("toParams(): unknown[]"),
+            "Should generate the instance method. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_sql_row_rejects_duplicate_columns() {
+    let source = r#"
+/** @derive(SqlRow) */
+interface Account {
+    id: number;
+    /** @column({ name: "id" }) */
+    legacyId: number;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("column 'id' of field 'legacyId'")),
+            "Should report the duplicate column. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+// ==================== GRAPHQL TESTS ====================
+
+#[test]
+fn test_derive_graphql_on_class() {
+    let source = r#"
+/** @derive(GraphQL) */
+class User implements Node {
+    /** Login address. */
+    email: string;
+    nickname?: string;
+
+    async posts(limit: number = 10): Promise<Post[]> {
+        return [];
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("...nodeGraphQLFields()"),
+            "Should merge the fields of the implemented interface. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("\"type User implements Node {\\n\""),
+            "Should declare the implemented interface. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "\"email\": \"  \\\"\\\"\\\"Login address.\\\"\\\"\\\"\\n  email: String!\""
+            ),
+            "Should use the JSDoc as description. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("\"posts\": \"  posts(limit: Float = 10): [Post!]!\""),
+            "Should turn methods into fields with arguments. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "posts?(parent: User, args: { limit?: number }, context: TContext): Post[] | Promise<Post[]>;"
+            ),
+            "Should type resolvers with the method return type. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "export interface UserResolvers<TContext = unknown> extends NodeResolvers<TContext>"
+            ),
+            "Should generate resolver types. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_graphql_union_and_enum() {
+    let source = r#"
+/** @derive(GraphQL) */
+type SearchResult = User | Post;
+
+/** @derive(GraphQL) */
+enum Role {
+    /** Full access. */
+    Admin = "admin",
+    Member = "member",
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.code.contains(
+                "export const searchResultGraphQL: string = \"union SearchResult = User | Post\";"
+            ),
+            "Should generate a union. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "\"enum Role {\\n  \\\"\\\"\\\"Full access.\\\"\\\"\\\"\\n  Admin\\n  Member\\n}\""
+            ),
+            "Should generate an enum with descriptions. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== STATE MACHINE TESTS ====================
+
+#[test]
+fn test_derive_state_machine_generates_transitions() {
+    let source = r#"
+/** @derive(StateMachine) */
+enum OrderStatus {
+    /** @transition({ to: ["Shipped", "Cancelled"] }) */
+    Pending = "pending",
+    /** @transition({ to: "Delivered" }) */
+    Shipped = "shipped",
+    /** @transition({ final: true }) */
+    Delivered = "delivered",
+    /** @transition({ final: true }) */
+    Cancelled = "cancelled",
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("[OrderStatus.Pending]: [OrderStatus.Shipped, OrderStatus.Cancelled],"),
+            "Should generate the transition table. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("export function orderStatusTransition("),
+            "Should generate the transition function. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("Shipped --> Delivered"),
+            "Should generate a Mermaid diagram. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_state_machine_warns_about_dead_states() {
+    let source = r#"
+/** @derive(StateMachine) */
+enum Job {
+    /** @transition({ to: "Running" }) */
+    Queued,
+    Running,
+    /** @transition({ final: true }) */
+    Archived,
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        let warnings = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.level == DiagnosticLevel::Warning)
+            .map(|d| d.message.as_str())
+            .collect::<Vec<_>>();
+        assert!(
+            warnings
+                .iter()
+                .any(|m| m.contains("'Running' has no transitions out")),
+            "Should warn about a state that cannot be left. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|m| m.contains("'Archived' is unreachable")),
+            "Should warn about an unreachable state. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("export function jobCanTransition("),
+            "Warnings should not block expansion. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== BRANDED TESTS ====================
+
+#[test]
+fn test_derive_branded_rewrites_alias_and_validates() {
+    let source = r#"
+/** @derive(Branded) @serde({ validate: ["email"] }) */
+export type Email = string;
+
+/** @derive(Deserialize) */
+class Account {
+    email: Email;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains(r#"type Email = string & { readonly __brand: "Email" };"#),
+            "Should brand the alias. Got:\n{}",
+            result.code
+        );
+        let type_output = result.type_output.expect("should have type output");
+        assert!(
+            type_output.contains(r#"type Email = string & { readonly __brand: "Email" };"#),
+            "Should brand the alias in the type output. Got:\n{}",
+            type_output
+        );
+        assert!(
+            result
+                .code
+                .contains("export function emailParse(raw: unknown)"),
+            "Should generate parse. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains(r#"errors.push({ field: "_root", message: "must be a valid email" });"#),
+            "parse should run the serde validators. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("unsafe: emailUnsafe"),
+            "Should expose the constructors on the convenience const. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("export function emailDeserializeWithContext(value: unknown"),
+            "Deserialize of fields should go through the brand's parse. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_branded_with_deserialize_uses_parse() {
+    let source = r#"
+/** @derive(Branded, Deserialize) */
+type Quantity = number;
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert_eq!(
+            result
+                .code
+                .matches("export function quantityDeserializeWithContext(")
+                .count(),
+            1,
+            "Deserialize should replace the brand's own entry point. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("const result = quantityParse(value);"),
+            "Deserialize should check the brand. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_derive_branded_rejects_non_primitive_alias() {
+    let source = r#"
+/** @derive(Branded) */
+type Tags = string[];
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result
+                .diagnostics
+                .iter()
+                .any(|d| d.message.contains("requires an alias of string")),
+            "Should reject aliases of non-primitive types. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_attribute_macro_rewrites_method_body() {
+    let source = r#"
+class Api {
+    @trace
+    load(id: string): string {
+        return id;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            !result.code.contains("@trace"),
+            "Attribute decorator should be removed. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains(r#"console.debug("Api.load" + "(", Array.from(arguments), ")");"#),
+            "Body should log the call. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("const __mf_result = ((): string => {"),
+            "Original body should run inside the wrapper. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("load(id: string): string {"),
+            "Signature should be kept. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+/// A call macro for tests: `shout("hi")` and `` shout`hi` `` become `"HI"`.
+struct ShoutMacro;
+
+impl crate::host::Macroforge for ShoutMacro {
+    fn name(&self) -> &str {
+        "shout"
+    }
+
+    fn kind(&self) -> crate::ts_syn::abi::MacroKind {
+        crate::ts_syn::abi::MacroKind::Call
+    }
+
+    fn run(&self, input: crate::ts_syn::TsStream) -> MacroResult {
+        let ctx = input.context().expect("call context").clone();
+        let input = crate::ts_syn::CallInput::from_context(ctx).expect("call target");
+        let text = match &input.call().template {
+            Some(template) => Some(template.strings.concat()),
+            None => input.call().string_arg(0).map(str::to_string),
+        };
+        match text {
+            Some(text) => MacroResult {
+                tokens: Some(format!("{:?}", text.to_uppercase())),
+                ..Default::default()
+            },
+            None => MacroResult {
+                diagnostics: vec![crate::ts_syn::abi::Diagnostic {
+                    level: DiagnosticLevel::Error,
+                    message: "shout() expects a string literal".into(),
+                    span: Some(input.error_span()),
+                    notes: vec![],
+                    help: None,
+                }],
+                ..Default::default()
+            },
+        }
+    }
+}
+
+fn expander_with_shout() -> MacroExpander {
+    let host = MacroExpander::new().unwrap();
+    host.dispatcher
+        .registry()
+        .register(DERIVE_MODULE_PATH, "shout", std::sync::Arc::new(ShoutMacro))
+        .unwrap();
+    host
+}
+
+#[test]
+fn test_call_macro_replaces_invocation() {
+    let source = r#"import { shout } from "macroforge";
+const greeting = shout("hello") + shout`world`;"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = expander_with_shout();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains(r#"const greeting = "HELLO" + "WORLD";"#),
+            "Invocations should be replaced. Got:\n{}",
+            result.code
+        );
+
+        let mapping = result.source_mapping.expect("should have source mapping");
+        let generated = result.code.find("\"WORLD\"").unwrap() as u32;
+        let original = source.find("shout`world`").unwrap() as u32;
+        assert_eq!(
+            mapping.replaced_span(generated + 1),
+            Some((original, "shout`world`".len() as u32)),
+            "Generated code should map back to the invocation"
+        );
+        assert_eq!(mapping.generated_by(generated), Some("shout"));
+    });
+}
+
+#[test]
+fn test_call_macro_reports_errors_at_callee() {
+    let source = r#"import { shout } from "macroforge";
+const greeting = shout(name);"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = expander_with_shout();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        let diagnostic = result
+            .diagnostics
+            .iter()
+            .find(|d| d.message.contains("expects a string literal"))
+            .expect("should report the macro error");
+        let start = source.find("shout(name)").unwrap() as u32 + 1;
+        assert_eq!(diagnostic.span, Some(SpanIR::new(start, start + 5)));
+        assert!(
+            result.code.contains("shout(name)"),
+            "Failed invocations should be left alone. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_call_macro_shadowed_by_local_binding() {
+    let source = r#"import { shout } from "macroforge";
+function greet(shout: (text: string) => string) {
+    return shout("hello");
+}
+function quiet() {
+    const shout = (text: string) => text;
+    return shout("hello");
+}
+const greeting = shout("hello");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = expander_with_shout();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.code.contains(r#"const greeting = "HELLO";"#),
+            "The module-level call should expand. Got:\n{}",
+            result.code
+        );
+        assert_eq!(
+            result.code.matches(r#"return shout("hello");"#).count(),
+            2,
+            "Parameters and locals shadow the imported macro. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_call_macro_requires_import() {
+    let source = r#"const greeting = shout("hello");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = expander_with_shout();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            !result.changed,
+            "An unimported name is an ordinary call. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_call_macro_inside_attribute_macro_body() {
+    let source = r#"import { shout } from "macroforge";
+class Api {
+    @trace
+    greet(): string {
+        return shout("hello");
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = expander_with_shout();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains(r#"return "HELLO";"#) && !result.code.contains("shout("),
+            "The traced body should contain the expansion. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("console.debug(\"Api.greet\""),
+            "The attribute macro should still run. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_builtin_call_macro_includes_file_and_drops_import() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("schema.graphql"), "type Query {}\n").unwrap();
+    let file_name = dir.path().join("api.ts").to_string_lossy().to_string();
+    let source = r#"import { includeStr } from "macroforge";
+const schema = includeStr("./schema.graphql");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, &file_name).unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert_eq!(
+            result.code.trim_end(),
+            "const schema = \"type Query {}\\n\";",
+            "The call and the macroforge import should be replaced, keeping the file's trailing newline"
+        );
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dir.path()
+                    .join("schema.graphql")
+                    .to_string_lossy()
+                    .to_string()
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_builtin_call_macro_imported_under_alias() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("schema.graphql"), "type Query {}\n").unwrap();
+    let file_name = dir.path().join("api.ts").to_string_lossy().to_string();
+    let source = r#"import { includeStr as inc } from "macroforge";
+const schema = inc("./schema.graphql");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, &file_name).unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert_eq!(
+            result.code.trim_end(),
+            "const schema = \"type Query {}\\n\";",
+            "The aliased call and its import should be replaced"
+        );
+    });
+}
+
+#[test]
+fn test_builtin_call_macro_without_import_is_left_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("schema.graphql"), "type Query {}\n").unwrap();
+    let file_name = dir.path().join("api.ts").to_string_lossy().to_string();
+    let source = r#"import { includeStr } from "./files";
+const schema = includeStr("./schema.graphql");
+const other = buildEnv("API_URL");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, &file_name).unwrap();
+
+        assert!(
+            !result.changed,
+            "Names not imported from macroforge are ordinary calls. Got:\n{}",
+            result.code
+        );
+        assert!(result.dependencies.is_empty());
+    });
+}
+
+#[test]
+fn test_builtin_call_macro_reports_missing_env_at_argument() {
+    let source = r#"import { buildEnv, Derive } from "macroforge";
+const url = buildEnv("MACROFORGE_TEST_UNSET_VARIABLE");
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        let diagnostic = result
+            .diagnostics
+            .iter()
+            .find(|d| d.level == DiagnosticLevel::Error)
+            .expect("should report the unset variable");
+        assert!(
+            diagnostic
+                .message
+                .starts_with("Environment variable 'MACROFORGE_TEST_UNSET_VARIABLE' is not set"),
+            "Got: {}",
+            diagnostic.message
+        );
+        let start = source.find("\"MACROFORGE_TEST_UNSET_VARIABLE\"").unwrap() as u32 + 1;
+        assert_eq!(
+            diagnostic.span,
+            Some(SpanIR::new(
+                start,
+                start + "\"MACROFORGE_TEST_UNSET_VARIABLE\"".len() as u32
+            ))
+        );
+        assert!(
+            result
+                .code
+                .contains(r#"import { buildEnv, Derive } from "macroforge";"#),
+            "Imports binding anything else are kept. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_attribute_macro_on_function_via_jsdoc() {
+    let source = r#"import { memoize } from "macroforge";
+
+/**
+ * Fibonacci numbers.
+ * @memoize({ maxSize: 10 })
+ * @param n - The index
+ */
+export function fib(n: number): number {
+    return n < 2 ? n : fib(n - 1) + fib(n - 2);
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            !result.code.contains("@memoize") && !result.code.contains("macroforge"),
+            "JSDoc directive and its import should be removed. Got:\n{}",
+            result.code
+        );
+        let cache = result
+            .code
+            .find("const __mf_memo_fib: Map<string, number> = new Map();")
+            .expect("cache should be declared");
+        let doc = result
+            .code
+            .find("/**\n * Fibonacci numbers.\n * @param n - The index\n */\nexport function fib(")
+            .expect("the rest of the doc comment should stay on the function");
+        assert!(
+            cache < doc,
+            "Cache should be declared above the documented function. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("if (__mf_memo_fib.size > 10)"),
+            "maxSize should evict entries. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_stacked_attribute_macros_run_innermost_first() {
+    let source = r#"
+class Api {
+    @retry({ times: 2 })
+    @trace
+    async fetchUser(id: string): Promise<string> {
+        return id;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        let retry = result
+            .code
+            .find("if (__mf_attempt >= 2) throw __mf_error;")
+            .expect("retry loop should be generated");
+        let trace = result
+            .code
+            .find("console.debug(\"Api.fetchUser\"")
+            .expect("trace logging should be generated");
+        assert!(
+            retry > trace,
+            "retry should wrap the traced body. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_attribute_macro_leaves_other_decorators() {
+    let source = r#"
+class Api {
+    @Get("/users")
+    @trace
+    load(): void {}
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.code.contains("@Get(\"/users\")"),
+            "Runtime decorators should be kept. Got:\n{}",
+            result.code
+        );
+        assert!(
+            !result.code.contains("@trace"),
+            "Attribute decorator should be removed. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_attribute_macro_rejects_generators() {
+    let source = r#"
+class Api {
+    @memoize
+    *ids(): Generator<number> {
+        yield 1;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.iter().any(|d| d
+                .message
+                .contains("@memoize cannot be applied to generator 'ids'")),
+            "Should reject generator methods. Got: {:?}",
+            result.diagnostics
+        );
+    });
+}
+
+#[test]
+fn test_deprecated_attribute_warns_once_with_message() {
+    let source = r#"
+class Api {
+    @deprecated("use fetchUser instead")
+    getUser(id: string): string {
+        return id;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result
+                .code
+                .contains("private static __mf_deprecated_getUser: boolean = false;"),
+            "The warning flag should be shared by all instances. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains("/** @deprecated use fetchUser instead */"),
+            "The JSDoc tag should be kept for editors. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result.code.contains(
+                "if (!Api.__mf_deprecated_getUser) {\nApi.__mf_deprecated_getUser = true;\nconsole.warn(\"Api.getUser is deprecated: use fetchUser instead\");\n}\nreturn id;"
+            ),
+            "Body should warn once and keep the original statements. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_deprecated_jsdoc_tag_keeps_message_and_doc() {
+    let source = r#"import { deprecated } from "macroforge";
+
+class Api {
+    /**
+     * Loads a user by id.
+     * @deprecated Use fetchUser instead.
+     * @param id - The user id
+     */
+    getName(id: string): string {
+        return id;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains(
+                "    private static __mf_deprecated_getName: boolean = false;\n    /** @deprecated Use fetchUser instead. */\n    /**\n     * Loads a user by id.\n     * @param id - The user id\n     */\n    getName("
+            ),
+            "The flag should go above the doc comment, which keeps its description. Got:\n{}",
+            result.code
+        );
+        assert!(
+            result
+                .code
+                .contains("console.warn(\"Api.getName is deprecated: Use fetchUser instead.\");"),
+            "The tag text should be the message. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_jsdoc_tags_without_import_are_documentation() {
+    let source = r#"
+class Api {
+    /**
+     * Loads a user by id.
+     * @deprecated Use fetchUser instead.
+     * @param id - The user id
+     */
+    getName(id: string): string {
+        return id;
+    }
+}
+
+/**
+ * Fibonacci numbers.
+ * @deprecated
+ * @param n - The index
+ */
+export function fib(n: number): number {
+    return n;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should not report anything. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            !result.changed,
+            "Unimported JSDoc tags should not run attribute macros. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_locally_imported_decorators_are_not_attribute_macros() {
+    let source = r#"import { trace } from "./my-tracing";
+import { memoize as cached } from "./my-cache";
+
+class Api {
+    @trace
+    @cached
+    load(id: string): string {
+        return id;
+    }
+}
+
+/** @trace */
+export function fib(n: number): number {
+    return n;
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should not report anything. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            !result.changed,
+            "Decorators imported from elsewhere should be left untouched. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+#[test]
+fn test_attribute_macro_imported_from_macroforge_under_alias() {
+    let source = r#"import { trace as logged } from "macroforge";
+
+function trace(): void {}
+
+class Api {
+    @logged
+    load(id: string): string {
+        return id;
+    }
+}
+"#;
+
+    GLOBALS.set(&Default::default(), || {
+        let program = parse_module(source);
+        let host = MacroExpander::new().unwrap();
+        let result = host.expand(source, &program, "test.ts").unwrap();
+
+        assert!(
+            result.diagnostics.is_empty(),
+            "Should expand without diagnostics. Got: {:?}",
+            result.diagnostics
+        );
+        assert!(
+            result.code.contains("console.debug(\"Api.load\"")
+                && !result.code.contains("@logged")
+                && !result.code.contains("\"macroforge\""),
+            "The aliased import should run trace. Got:\n{}",
+            result.code
+        );
+    });
+}
+
+// ==================== EARLY BAILOUT TESTS ====================
+
+#[test]
+fn test_early_bailout_no_derive_returns_unchanged() {
+    // Code without @derive should be returned unchanged immediately
+    use crate::expand_inner;
+
+    let source = r#"
+class User {
+    name: string;
+    constructor(name: string) {
+        this.name = name;
+    }
+}
+"#;
+
+    let result = expand_inner(source, "test.ts", None).unwrap();
+
+    // Code should be returned exactly as-is
+    assert_eq!(
+        result.code, source,
+        "Code without @derive should be returned unchanged"
+    );
+    assert!(result.types.is_none(), "No type output expected");
+    assert!(result.diagnostics.is_empty(), "No diagnostics expected");
+    assert!(
+        result.source_mapping.is_none(),
+        "No source mapping expected"
+    );
+}
+
+#[test]
+fn test_early_bailout_skipped_for_attribute_macros() {
+    use crate::expand_inner;
+
+    let source = r#"import { trace } from "macroforge";
+
+/** @trace */
+function load(id: string): string {
+    return id;
+}
+"#;
+
+    let result = expand_inner(source, "test.ts", None).unwrap();
+
+    assert!(
+        result.code.contains("console.debug(\"load\""),
+        "Attribute macros should expand without @derive. Got:\n{}",
+        result.code
+    );
+}
+
+#[test]
+fn test_early_bailout_plain_jsdoc_tags_unchanged() {
+    use crate::expand_inner;
+
+    let source = r#"
+/**
+ * Loads a user.
+ * @deprecated Use fetchUser instead.
+ * @see trace
+ */
+function load(id: string): string {
+    return id; // @trace
+}
+"#;
+
+    let result = expand_inner(source, "test.ts", None).unwrap();
+
+    assert_eq!(result.code, source);
+    assert!(
+        result.source_mapping.is_none(),
+        "Comments mentioning attribute macros should not force a parse"
     );
 }
 
@@ -8,6 +8,8 @@
 //!
 //! The primary macro provided is [`ts_macro_derive`], which transforms a Rust function
 //! into a fully-fledged TypeScript macro that integrates with the Macroforge runtime.
+//! [`ts_macro_attribute`] does the same for attribute macros on methods and functions,
+//! and [`ts_macro_call`] for call macros at expression sites.
 //!
 //! ## Example
 //!
@@ -121,10 +123,85 @@ pub fn ts_macro_derive(attr: TokenStream, item: TokenStream) -> TokenStream {
         Err(err) => return err.to_compile_error().into(),
     };
 
+    expand_ts_macro(options, item)
+}
+
+/// A procedural macro attribute that transforms a function into a TypeScript attribute macro.
+///
+/// This is [`ts_macro_derive`] with `kind = "attribute"`. Attribute macros are
+/// applied to class methods and top-level functions, either as decorators or
+/// as JSDoc directives, and receive the function as `TargetIR::Function`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[ts_macro_attribute(trace, description = "Logs calls and return values")]
+/// fn trace_macro(input: TsStream) -> Result<TsStream, MacroError> {
+///     let input = parse_ts_macro_input!(input as AttributeInput);
+///     // Emit a replacement body with `body!`
+///     Ok(TsStream::from_string(String::new()))
+/// }
+/// ```
+///
+/// ```typescript
+/// class Api {
+///     @trace
+///     fetchUser(id: string) { ... }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ts_macro_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = match parse_macro_options(TokenStream2::from(attr)) {
+        Ok(opts) => opts,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    options.kind = MacroKindOption::Attribute;
+
+    expand_ts_macro(options, item)
+}
+
+/// A procedural macro attribute that transforms a function into a TypeScript call macro.
+///
+/// This is [`ts_macro_derive`] with `kind = "call"`. Call macros are invoked
+/// at expression sites, as a call or a tagged template, receive the
+/// invocation as `TargetIR::Call`, and their output replaces it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[ts_macro_call(includeStr, description = "Inlines a file as a string literal")]
+/// fn include_str_macro(input: TsStream) -> Result<TsStream, MacroError> {
+///     let input = parse_ts_macro_input!(input as CallInput);
+///     // Return the replacement expression
+///     Ok(TsStream::from_string(String::new()))
+/// }
+/// ```
+///
+/// ```typescript
+/// import { includeStr } from "macroforge";
+///
+/// const schema = includeStr("./schema.graphql");
+/// ```
+#[proc_macro_attribute]
+pub fn ts_macro_call(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = match parse_macro_options(TokenStream2::from(attr)) {
+        Ok(opts) => opts,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    options.kind = MacroKindOption::Call;
+
+    expand_ts_macro(options, item)
+}
+
+/// Generates the macro struct, descriptor, registration and NAPI bridge
+/// shared by [`ts_macro_derive`], [`ts_macro_attribute`] and [`ts_macro_call`].
+fn expand_ts_macro(options: MacroOptions, item: TokenStream) -> TokenStream {
     let mut function = parse_macro_input!(item as ItemFn);
-    function
-        .attrs
-        .retain(|attr| !attr.path().is_ident("ts_macro_derive"));
+    function.attrs.retain(|attr| {
+        !["ts_macro_derive", "ts_macro_attribute", "ts_macro_call"]
+            .iter()
+            .any(|name| attr.path().is_ident(name))
+    });
 
     let fn_ident = function.sig.ident.clone();
     let struct_ident = pascal_case_ident(&fn_ident);
@@ -0,0 +1,100 @@
+//! Call-site IR types for call macro invocations.
+//!
+//! Call macros are invoked inline, either as a call or as a tagged template:
+//!
+//! ```typescript
+//! const schema = includeStr("./schema.graphql");
+//! const users = sql`SELECT * FROM users WHERE id = ${id}`;
+//! ```
+//!
+//! Both are lowered to a [`CallIR`], which carries each argument as source
+//! text plus its span, so a macro can inspect literal arguments or splice
+//! the original expressions into its output.
+//!
+//! ## Example
+//!
+//! For `assertType<User>(value, "user")` the resulting [`CallIR`] contains:
+//! - `name`: `"assertType"`
+//! - `type_args_src`: `"<User>"`
+//! - `args`: `value` (no string value) and `"user"` (string value `user`)
+
+use serde::{Deserialize, Serialize};
+
+use crate::abi::SpanIR;
+
+/// Intermediate representation of a call macro invocation.
+///
+/// # Spans
+///
+/// - `span` covers the whole expression and is what the macro's output
+///   replaces.
+/// - `stmt_span` covers the top-level statement containing the call; code
+///   a macro places `above!` is inserted before it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CallIR {
+    /// The macro name, as imported; a call site using an alias is lowered
+    /// under the imported name.
+    pub name: String,
+
+    /// Source span of the whole call expression.
+    pub span: SpanIR,
+
+    /// Source span of the callee identifier.
+    pub callee_span: SpanIR,
+
+    /// Source span of the enclosing top-level statement.
+    pub stmt_span: SpanIR,
+
+    /// Type arguments as source string (e.g., `"<User>"`).
+    /// Empty string if there are none.
+    pub type_args_src: String,
+
+    /// The call arguments, in order. Empty for tagged templates.
+    pub args: Vec<CallArgIR>,
+
+    /// The template of a tagged template invocation.
+    pub template: Option<TemplateIR>,
+}
+
+impl CallIR {
+    /// Whether this is a tagged template (`` sql`...` ``) rather than a call.
+    pub fn is_tagged_template(&self) -> bool {
+        self.template.is_some()
+    }
+
+    /// The string value of the argument at `index`, if it is a string literal
+    /// or a template literal without substitutions.
+    pub fn string_arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index)?.string_value.as_deref()
+    }
+}
+
+/// Intermediate representation of a call argument or template substitution.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CallArgIR {
+    /// Source span of the expression (without a leading `...`).
+    pub span: SpanIR,
+
+    /// The expression as source string.
+    pub source: String,
+
+    /// The value, if the expression is a string literal or a template
+    /// literal without substitutions.
+    pub string_value: Option<String>,
+
+    /// Whether the argument is spread (`...args`).
+    pub is_spread: bool,
+}
+
+/// Intermediate representation of the template of a tagged template.
+///
+/// `strings` always has one more entry than `exprs`, as in JavaScript's
+/// `TemplateStringsArray`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TemplateIR {
+    /// The cooked string parts (the raw text where cooking fails).
+    pub strings: Vec<String>,
+
+    /// The substituted expressions.
+    pub exprs: Vec<CallArgIR>,
+}
@@ -50,7 +50,7 @@
 //! }
 //! ```
 
-use crate::abi::{ClassIR, EnumIR, InterfaceIR, SpanIR, TypeAliasIR};
+use crate::abi::{CallIR, ClassIR, EnumIR, FunctionIR, InterfaceIR, SpanIR, TypeAliasIR};
 use serde::{Deserialize, Serialize};
 
 /// The kind of macro being executed.
@@ -79,9 +79,9 @@ pub enum MacroKind {
 
     /// A call macro invoked inline.
     ///
-    /// Example: `sql!("SELECT * FROM users")`, `html!(<div>...</div>)`
+    /// Example: `includeStr("./schema.graphql")`, `` sql`SELECT * FROM users` ``
     ///
-    /// Call macros generate code at the invocation site.
+    /// Call macros replace the invocation with the code they generate.
     Call,
 }
 
@@ -119,8 +119,11 @@ pub enum TargetIR {
     /// Macro applied to a type alias declaration.
     TypeAlias(TypeAliasIR),
 
-    /// Macro applied to a function (reserved for future use).
-    Function,
+    /// Attribute macro applied to a class method or top-level function.
+    Function(FunctionIR),
+
+    /// Call macro invoked at an expression site.
+    Call(CallIR),
 
     /// Macro applied to an unsupported construct.
     Other,
@@ -264,6 +267,22 @@ impl MacroContextIR {
         }
     }
 
+    /// Get the function IR if the target is a function or method
+    pub fn as_function(&self) -> Option<&FunctionIR> {
+        match &self.target {
+            TargetIR::Function(function_ir) => Some(function_ir),
+            _ => None,
+        }
+    }
+
+    /// Get the call IR if the target is a call macro invocation
+    pub fn as_call(&self) -> Option<&CallIR> {
+        match &self.target {
+            TargetIR::Call(call_ir) => Some(call_ir),
+            _ => None,
+        }
+    }
+
     /// Get the type alias IR if the target is a type alias
     pub fn as_type_alias(&self) -> Option<&TypeAliasIR> {
         match &self.target {
@@ -343,4 +362,49 @@ impl MacroContextIR {
             target_source,
         }
     }
+
+    /// Create a new macro context for an attribute macro on a function or method
+    pub fn new_attribute_function(
+        macro_name: String,
+        module_path: String,
+        decorator_span: SpanIR,
+        file_name: String,
+        function: FunctionIR,
+        target_source: String,
+    ) -> Self {
+        Self {
+            abi_version: 1,
+            macro_kind: MacroKind::Attribute,
+            macro_name,
+            module_path,
+            decorator_span,
+            macro_name_span: None,
+            target_span: function.span,
+            file_name,
+            target: TargetIR::Function(function),
+            target_source,
+        }
+    }
+
+    /// Create a new macro context for a call macro invocation
+    pub fn new_call(
+        macro_name: String,
+        module_path: String,
+        file_name: String,
+        call: CallIR,
+        target_source: String,
+    ) -> Self {
+        Self {
+            abi_version: 1,
+            macro_kind: MacroKind::Call,
+            macro_name,
+            module_path,
+            decorator_span: call.span,
+            macro_name_span: Some(call.callee_span),
+            target_span: call.span,
+            file_name,
+            target: TargetIR::Call(call),
+            target_source,
+        }
+    }
 }
@@ -0,0 +1,118 @@
+//! Function-related IR types for attribute macro targets.
+//!
+//! Attribute macros are applied to class methods and top-level functions.
+//! Both are lowered to a [`FunctionIR`], which carries the signature pieces
+//! as source strings plus the body, so a macro can rebuild or wrap the
+//! function without re-parsing it.
+//!
+//! ## Example
+//!
+//! Given this TypeScript method:
+//!
+//! ```typescript
+//! class Api {
+//!     @retry({ times: 3 })
+//!     async fetchUser(id: string, force?: boolean): Promise<User> {
+//!         return http.get(`/users/${id}`);
+//!     }
+//! }
+//! ```
+//!
+//! The resulting [`FunctionIR`] would contain:
+//! - `name`: `"fetchUser"`
+//! - `class_name`: `Some("Api")`
+//! - `params`: Two [`ParamIR`] entries for `id` and `force`
+//! - `return_type_src`: `"Promise<User>"`
+//! - `body_src`: the statements between the braces
+
+use serde::{Deserialize, Serialize};
+
+use crate::abi::{DecoratorIR, SpanIR};
+
+/// Intermediate representation of a function or class method.
+///
+/// # Spans
+///
+/// - `span` covers the declaration itself, including `export`, `async`,
+///   `static` and access modifiers, but not its decorators or JSDoc comment.
+/// - `body_span` covers the body including its braces.
+///
+/// Only functions with a body are lowered; overload signatures and abstract
+/// methods are skipped.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FunctionIR {
+    /// The function or method name.
+    pub name: String,
+
+    /// Source span of the declaration (see the type-level docs).
+    pub span: SpanIR,
+
+    /// Source span of the body, braces included.
+    pub body_span: SpanIR,
+
+    /// The body source between the braces.
+    pub body_src: String,
+
+    /// Type parameters as source string (e.g., `"<T>"`).
+    /// Empty string if no type parameters.
+    pub type_params_src: String,
+
+    /// The parameters, in declaration order.
+    pub params: Vec<ParamIR>,
+
+    /// Parameters as source string (e.g., `"id: string, force?: boolean"`).
+    pub params_src: String,
+
+    /// Return type as source string (e.g., `"Promise<User>"`).
+    /// Empty string if the return type is inferred.
+    pub return_type_src: String,
+
+    /// Whether this is an `async` function.
+    pub is_async: bool,
+
+    /// Whether this is a generator (`function*`).
+    pub is_generator: bool,
+
+    /// Whether this is a `static` method.
+    pub is_static: bool,
+
+    /// Whether this is an exported top-level function.
+    pub is_exported: bool,
+
+    /// The enclosing class for methods, `None` for top-level functions.
+    pub class_name: Option<String>,
+
+    /// Decorators and JSDoc directives applied to this function.
+    pub decorators: Vec<DecoratorIR>,
+}
+
+impl FunctionIR {
+    /// Whether this is a class method.
+    pub fn is_method(&self) -> bool {
+        self.class_name.is_some()
+    }
+
+    /// The parameter names, e.g. for forwarding arguments.
+    pub fn param_names(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(|param| param.name.as_str())
+    }
+}
+
+/// Intermediate representation of a function parameter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParamIR {
+    /// The parameter name, or the pattern source for destructured parameters.
+    pub name: String,
+
+    /// Source span of the parameter.
+    pub span: SpanIR,
+
+    /// The type annotation as source string (`"any"` if not annotated).
+    pub ts_type: String,
+
+    /// Whether the parameter is optional (`x?: T` or has a default value).
+    pub optional: bool,
+
+    /// Whether this is a rest parameter (`...args`).
+    pub is_rest: bool,
+}
@@ -13,6 +13,8 @@
 //! - [`InterfaceIR`] - Represents a TypeScript interface with properties and method signatures
 //! - [`EnumIR`] - Represents a TypeScript enum with its variants and values
 //! - [`TypeAliasIR`] - Represents a type alias (`type X = ...`)
+//! - [`FunctionIR`] - Represents a function or method targeted by an attribute macro
+//! - [`CallIR`] - Represents a call macro invocation at an expression site
 //! - [`DecoratorIR`] - Represents a decorator/attribute applied to a declaration
 //! - [`MacroContextIR`] - The complete context passed to macro functions
 //!
@@ -58,19 +60,25 @@
 //! - [`interface`] - Interface-related IR types ([`InterfaceIR`], [`InterfaceFieldIR`])
 //! - [`enum_`] - Enum-related IR types ([`EnumIR`], [`EnumVariantIR`], [`EnumValue`])
 //! - [`type_alias`] - Type alias IR types ([`TypeAliasIR`], [`TypeBody`], [`TypeMember`])
+//! - [`function`] - Function and method IR types ([`FunctionIR`], [`ParamIR`])
+//! - [`call`] - Call site IR types ([`CallIR`], [`CallArgIR`], [`TemplateIR`])
 //! - [`decorators`] - Decorator representation ([`DecoratorIR`])
 //! - [`context`] - Macro execution context ([`MacroContextIR`], [`MacroKind`], [`TargetIR`])
 
+pub mod call;
 pub mod class;
 pub mod context;
 pub mod decorators;
 pub mod enum_;
+pub mod function;
 pub mod interface;
 pub mod type_alias;
 
+pub use call::*;
 pub use class::*;
 pub use context::*;
 pub use decorators::*;
 pub use enum_::*;
+pub use function::*;
 pub use interface::*;
 pub use type_alias::*;
@@ -371,6 +371,7 @@ impl From<Vec<swc_ast::ModuleItem>> for PatchCode {
 ///         diagnostics: vec![],
 ///         tokens: None,
 ///         debug: Some("Generated 2 methods".to_string()),
+///         dependencies: vec![],
 ///     }
 /// }
 ///
@@ -407,6 +408,11 @@ pub struct MacroResult {
     /// Optional debug information for development.
     /// Can be displayed in verbose mode or logged for debugging.
     pub debug: Option<String>,
+
+    /// Files the output was generated from (e.g. by `includeStr`).
+    /// Build tools re-expand the source file when one of them changes.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
 /// A diagnostic message from macro expansion.
@@ -61,7 +61,9 @@ impl MappingSegment {
 /// A region of generated code that has no corresponding original source.
 ///
 /// This is code that was inserted by a macro and does not map back
-/// to any position in the original source file.
+/// to any position in the original source file. When the code replaced
+/// original source (e.g. a call macro invocation), the replaced range is
+/// recorded so problems in the generated code can be reported there.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct GeneratedRegion {
     /// Start position in expanded source (byte offset)
@@ -70,6 +72,12 @@ pub struct GeneratedRegion {
     pub end: u32,
     /// Which macro generated this code (e.g., "Debug::toString", "JSON::toJSON")
     pub source_macro: String,
+    /// Start of the replaced range in original source, for replacements
+    #[serde(default)]
+    pub original_start: Option<u32>,
+    /// End of the replaced range in original source (exclusive), for replacements
+    #[serde(default)]
+    pub original_end: Option<u32>,
 }
 
 impl GeneratedRegion {
@@ -78,9 +86,18 @@ impl GeneratedRegion {
             start,
             end,
             source_macro: source_macro.into(),
+            original_start: None,
+            original_end: None,
         }
     }
 
+    /// Record the original range this region replaced.
+    pub fn replacing(mut self, original_start: u32, original_end: u32) -> Self {
+        self.original_start = Some(original_start);
+        self.original_end = Some(original_end);
+        self
+    }
+
     pub fn len(&self) -> u32 {
         self.end.saturating_sub(self.start)
     }
@@ -217,6 +234,20 @@ impl SourceMapping {
             .map(|r| r.source_macro.as_str())
     }
 
+    /// Find the original span (start, length) replaced by the generated code
+    /// at a position, if that code is a replacement.
+    ///
+    /// Unlike [`expanded_to_original`](Self::expanded_to_original), this maps
+    /// every position inside e.g. a call macro expansion to the invocation.
+    pub fn replaced_span(&self, expanded_pos: u32) -> Option<(u32, u32)> {
+        let region = self
+            .generated_regions
+            .iter()
+            .find(|r| r.contains(expanded_pos))?;
+        let (start, end) = (region.original_start?, region.original_end?);
+        Some((start, end.saturating_sub(start)))
+    }
+
     /// Map a span (start, length) from expanded to original coordinates.
     ///
     /// Returns `None` if either endpoint is in generated code.
@@ -357,4 +388,23 @@ mod tests {
         assert!(!region.contains(5));
         assert!(!region.contains(25));
     }
+
+    #[test]
+    fn test_replaced_span() {
+        // Original: `const s = includeStr("a.txt");` (call at 10..29)
+        // Expanded: `const s = "hello";` (literal at 10..17)
+        let mut mapping = SourceMapping::new();
+        mapping.add_segment(MappingSegment::new(0, 10, 0, 10));
+        mapping.add_generated(GeneratedRegion::new(10, 17, "includeStr").replacing(10, 29));
+        mapping.add_segment(MappingSegment::new(29, 30, 17, 18));
+
+        assert_eq!(mapping.replaced_span(12), Some((10, 19)));
+        assert_eq!(mapping.expanded_to_original(12), None);
+        assert_eq!(mapping.replaced_span(5), None);
+
+        // Inserted code has no replaced range
+        let mut mapping = SourceMapping::new();
+        mapping.add_generated(GeneratedRegion::new(0, 5, "Debug"));
+        assert_eq!(mapping.replaced_span(2), None);
+    }
 }
@@ -0,0 +1,236 @@
+//! Input type for attribute macros.
+//!
+//! Attribute macros are applied to class methods and top-level functions,
+//! either as decorators or as JSDoc directives:
+//!
+//! ```typescript
+//! import { memoize } from "macroforge";
+//!
+//! class Api {
+//!     @retry({ times: 3 })
+//!     async fetchUser(id: string): Promise<User> { ... }
+//! }
+//!
+//! /** @memoize */
+//! export function fib(n: number): number { ... }
+//! ```
+//!
+//! [`AttributeInput`] gives the macro the target [`FunctionIR`] and its own
+//! decorator, whose arguments are available through [`AttributeInput::args`].
+//!
+//! ## Output
+//!
+//! An attribute macro returns a [`TsStream`] whose source is split with the
+//! usual position markers:
+//!
+//! - `body!` - Replaces the function body (the statements between the braces)
+//! - `above!` - Inserted before the function
+//! - `below!` - Inserted after the function
+//!
+//! When several attribute macros are stacked, the one closest to the function
+//! runs first and each following macro sees the body produced by the
+//! previous one in [`FunctionIR::body_src`].
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use macroforge_ts_syn::{parse_ts_macro_input, AttributeInput, TsStream};
+//!
+//! pub fn trace(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+//!     let input = parse_ts_macro_input!(input as AttributeInput);
+//!     let function = input.function();
+//!     let body = format!(
+//!         "/* @macroforge:body */console.debug({:?});\n{}",
+//!         function.name, function.body_src
+//!     );
+//!     Ok(TsStream::from_string(body))
+//! }
+//! ```
+
+use crate::abi::{FunctionIR, MacroContextIR, SpanIR, TargetIR};
+use crate::derive::Attribute;
+
+use crate::TsSynError;
+
+#[cfg(feature = "swc")]
+use crate::TsStream;
+
+/// The input to an attribute macro.
+///
+/// Created with `parse_ts_macro_input!(input as AttributeInput)`.
+#[derive(Debug, Clone)]
+pub struct AttributeInput {
+    /// The decorator that invoked this macro.
+    pub attr: Attribute,
+
+    /// The other decorators on the function.
+    pub attrs: Vec<Attribute>,
+
+    /// The target function or method.
+    pub function: FunctionIR,
+
+    /// The macro context.
+    pub context: MacroContextIR,
+}
+
+impl AttributeInput {
+    /// Create an AttributeInput from a MacroContextIR
+    pub fn from_context(ctx: MacroContextIR) -> Result<Self, TsSynError> {
+        let TargetIR::Function(function) = &ctx.target else {
+            return Err(TsSynError::Unsupported(
+                "Attribute macros can only be applied to functions and methods".into(),
+            ));
+        };
+
+        // Prefer the decorator at the invocation span so repeated attributes
+        // each see their own arguments.
+        let own = function
+            .decorators
+            .iter()
+            .position(|d| d.name == ctx.macro_name && d.span == ctx.decorator_span)
+            .or_else(|| {
+                function
+                    .decorators
+                    .iter()
+                    .position(|d| d.name == ctx.macro_name)
+            })
+            .ok_or_else(|| {
+                TsSynError::Parse(format!(
+                    "No @{} decorator found on '{}'",
+                    ctx.macro_name, function.name
+                ))
+            })?;
+
+        let attr = Attribute {
+            inner: function.decorators[own].clone(),
+        };
+        let attrs = function
+            .decorators
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != own)
+            .map(|(_, d)| Attribute { inner: d.clone() })
+            .collect();
+
+        Ok(Self {
+            attr,
+            attrs,
+            function: function.clone(),
+            context: ctx,
+        })
+    }
+
+    /// Get the function or method name
+    pub fn name(&self) -> &str {
+        &self.function.name
+    }
+
+    /// Get the target function
+    pub fn function(&self) -> &FunctionIR {
+        &self.function
+    }
+
+    /// Get the raw arguments of the invoking decorator (e.g. `{ times: 3 }`)
+    pub fn args(&self) -> &str {
+        self.attr.args()
+    }
+
+    /// Get the decorator span
+    pub fn decorator_span(&self) -> SpanIR {
+        self.context.decorator_span
+    }
+
+    /// Get the best span for error reporting
+    pub fn error_span(&self) -> SpanIR {
+        self.context.error_span()
+    }
+
+    /// Get the span of the function body, braces included
+    pub fn body_span(&self) -> SpanIR {
+        self.function.body_span
+    }
+}
+
+#[cfg(feature = "swc")]
+impl crate::ParseTs for AttributeInput {
+    fn parse(input: &mut TsStream) -> Result<Self, TsSynError> {
+        let ctx = input
+            .context()
+            .ok_or_else(|| TsSynError::Parse("No macro context available".into()))?
+            .clone();
+
+        Self::from_context(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::{DecoratorIR, ParamIR};
+
+    fn decorator(name: &str, args: &str, start: u32) -> DecoratorIR {
+        DecoratorIR {
+            name: name.into(),
+            args_src: args.into(),
+            span: SpanIR::new(start, start + 10),
+            node: None,
+        }
+    }
+
+    fn make_function_context(macro_name: &str, decorator_span: SpanIR) -> MacroContextIR {
+        let function = FunctionIR {
+            name: "fetchUser".into(),
+            span: SpanIR::new(30, 90),
+            body_span: SpanIR::new(60, 90),
+            body_src: "return id;".into(),
+            type_params_src: String::new(),
+            params: vec![ParamIR {
+                name: "id".into(),
+                span: SpanIR::new(40, 50),
+                ts_type: "string".into(),
+                optional: false,
+                is_rest: false,
+            }],
+            params_src: "id: string".into(),
+            return_type_src: "string".into(),
+            is_async: false,
+            is_generator: false,
+            is_static: false,
+            is_exported: false,
+            class_name: Some("Api".into()),
+            decorators: vec![
+                decorator("retry", "{ times: 3 }", 1),
+                decorator("trace", "", 15),
+            ],
+        };
+        MacroContextIR::new_attribute_function(
+            macro_name.into(),
+            "@macro/derive".into(),
+            decorator_span,
+            "test.ts".into(),
+            function,
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_attribute_input_finds_own_decorator() {
+        let input =
+            AttributeInput::from_context(make_function_context("retry", SpanIR::new(1, 11)))
+                .unwrap();
+        assert_eq!(input.name(), "fetchUser");
+        assert_eq!(input.args(), "{ times: 3 }");
+        assert_eq!(input.attrs.len(), 1);
+        assert_eq!(input.attrs[0].name(), "trace");
+        assert!(input.function().is_method());
+        assert_eq!(input.function().param_names().collect::<Vec<_>>(), ["id"]);
+    }
+
+    #[test]
+    fn test_attribute_input_requires_decorator() {
+        let err =
+            AttributeInput::from_context(make_function_context("memoize", SpanIR::new(1, 11)))
+                .unwrap_err();
+        assert!(err.to_string().contains("No @memoize decorator"));
+    }
+}
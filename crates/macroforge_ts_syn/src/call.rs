@@ -0,0 +1,166 @@
+//! Input type for call macros.
+//!
+//! Call macros are invoked at expression sites, as a call or as a tagged
+//! template:
+//!
+//! ```typescript
+//! import { includeStr, sql } from "macroforge";
+//!
+//! const schema = includeStr("./schema.graphql");
+//! const users = sql`SELECT * FROM users WHERE id = ${id}`;
+//! ```
+//!
+//! A call site is recognised when the callee is a plain identifier bound by
+//! an import of a registered call macro, possibly under an alias, and no
+//! inner scope shadows it. The `macroforge` package declares the built-in
+//! call macros for the type checker; without an import, `includeStr(...)`
+//! is an ordinary call.
+//!
+//! [`CallInput`] gives the macro the [`CallIR`] with every argument as source
+//! text and span.
+//!
+//! ## Output
+//!
+//! The unmarked output of a call macro replaces the whole call expression.
+//! Code marked with `above!` is inserted before the top-level statement
+//! containing the call, e.g. to hoist a constant out of a loop.
+//!
+//! Call macros inside the arguments of another call macro are not expanded;
+//! the outer macro receives them as source.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use macroforge_ts_syn::{parse_ts_macro_input, CallInput, TsStream};
+//!
+//! pub fn upper(mut input: TsStream) -> Result<TsStream, MacroforgeError> {
+//!     let input = parse_ts_macro_input!(input as CallInput);
+//!     let Some(text) = input.call().string_arg(0) else {
+//!         return Err(MacroforgeError::new(input.error_span(), "upper() expects a string literal"));
+//!     };
+//!     Ok(TsStream::from_string(format!("{:?}", text.to_uppercase())))
+//! }
+//! ```
+
+use crate::abi::{CallArgIR, CallIR, MacroContextIR, SpanIR, TargetIR};
+
+use crate::TsSynError;
+
+#[cfg(feature = "swc")]
+use crate::TsStream;
+
+/// The input to a call macro.
+///
+/// Created with `parse_ts_macro_input!(input as CallInput)`.
+#[derive(Debug, Clone)]
+pub struct CallInput {
+    /// The invocation.
+    pub call: CallIR,
+
+    /// The macro context.
+    pub context: MacroContextIR,
+}
+
+impl CallInput {
+    /// Create a CallInput from a MacroContextIR
+    pub fn from_context(ctx: MacroContextIR) -> Result<Self, TsSynError> {
+        let TargetIR::Call(call) = &ctx.target else {
+            return Err(TsSynError::Unsupported(
+                "Call macros can only be invoked at expression sites".into(),
+            ));
+        };
+
+        Ok(Self {
+            call: call.clone(),
+            context: ctx,
+        })
+    }
+
+    /// Get the macro name as written at the call site
+    pub fn name(&self) -> &str {
+        &self.call.name
+    }
+
+    /// Get the invocation
+    pub fn call(&self) -> &CallIR {
+        &self.call
+    }
+
+    /// Get the call arguments
+    pub fn args(&self) -> &[CallArgIR] {
+        &self.call.args
+    }
+
+    /// Get the file containing the call, e.g. for resolving relative paths
+    pub fn file_name(&self) -> &str {
+        &self.context.file_name
+    }
+
+    /// Get the span of the whole call expression
+    pub fn span(&self) -> SpanIR {
+        self.call.span
+    }
+
+    /// Get the best span for error reporting
+    pub fn error_span(&self) -> SpanIR {
+        self.context.error_span()
+    }
+}
+
+#[cfg(feature = "swc")]
+impl crate::ParseTs for CallInput {
+    fn parse(input: &mut TsStream) -> Result<Self, TsSynError> {
+        let ctx = input
+            .context()
+            .ok_or_else(|| TsSynError::Parse("No macro context available".into()))?
+            .clone();
+
+        Self::from_context(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_call_context() -> MacroContextIR {
+        let call = CallIR {
+            name: "includeStr".into(),
+            span: SpanIR::new(15, 45),
+            callee_span: SpanIR::new(15, 25),
+            stmt_span: SpanIR::new(1, 46),
+            type_args_src: String::new(),
+            args: vec![CallArgIR {
+                span: SpanIR::new(26, 44),
+                source: "\"./schema.graphql\"".into(),
+                string_value: Some("./schema.graphql".into()),
+                is_spread: false,
+            }],
+            template: None,
+        };
+        MacroContextIR::new_call(
+            "includeStr".into(),
+            "@macro/derive".into(),
+            "src/api.ts".into(),
+            call,
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_call_input_from_context() {
+        let input = CallInput::from_context(make_call_context()).unwrap();
+        assert_eq!(input.name(), "includeStr");
+        assert_eq!(input.call().string_arg(0), Some("./schema.graphql"));
+        assert!(!input.call().is_tagged_template());
+        assert_eq!(input.file_name(), "src/api.ts");
+        assert_eq!(input.error_span(), SpanIR::new(15, 25));
+    }
+
+    #[test]
+    fn test_call_input_rejects_other_targets() {
+        let mut ctx = make_call_context();
+        ctx.target = TargetIR::Other;
+        assert!(CallInput::from_context(ctx).is_err());
+    }
+}
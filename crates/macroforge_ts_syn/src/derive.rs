@@ -792,9 +792,15 @@ impl DeriveInput {
                 });
                 (ident, type_alias.span, attrs, data)
             }
-            TargetIR::Function => {
+            TargetIR::Function(_) => {
                 return Err(TsSynError::Unsupported(
-                    "Function derive macros not yet supported".into(),
+                    "Function targets are attribute macro input; parse them as AttributeInput"
+                        .into(),
+                ));
+            }
+            TargetIR::Call(_) => {
+                return Err(TsSynError::Unsupported(
+                    "Call targets are call macro input; parse them as CallInput".into(),
                 ));
             }
             TargetIR::Other => {
@@ -12,6 +12,8 @@
 //!
 //! - [`abi`] - Application Binary Interface types for stable macro communication
 //! - [`derive`] - Derive input types that mirror Rust's `syn::DeriveInput`
+//! - [`attribute`] - [`AttributeInput`] for attribute macros on functions and methods
+//! - [`call`] - [`CallInput`] for call macros at expression sites
 //! - [`errors`] - Error types and diagnostics for macro expansion
 //! - [`lower`] - AST lowering from SWC types to IR representations
 //! - [`parse`] - TypeScript parsing utilities wrapping SWC
@@ -104,6 +106,8 @@
 //! - `quote!` - SWC's quote macro for AST generation
 
 pub mod abi;
+pub mod attribute;
+pub mod call;
 pub mod derive;
 pub mod errors;
 pub mod lower;
@@ -112,6 +116,8 @@ pub mod quote_helpers;
 pub mod stream;
 
 pub use abi::*;
+pub use attribute::*;
+pub use call::*;
 pub use derive::*;
 pub use errors::*;
 pub use lower::*;
@@ -9,7 +9,7 @@
 //!
 //! The lowering process:
 //! 1. Visits the SWC [`Module`](swc_core::ecma::ast::Module) AST
-//! 2. Extracts declarations (classes, interfaces, enums, type aliases)
+//! 2. Extracts declarations (classes, interfaces, enums, type aliases, functions)
 //! 3. Collects decorators from both TypeScript decorators and JSDoc comments
 //! 4. Computes source spans for each declaration
 //! 5. Returns IR types ready for macro processing
@@ -22,6 +22,8 @@
 //! | [`lower_interfaces`] | All interface declarations |
 //! | [`lower_enums`] | All enum declarations |
 //! | [`lower_type_aliases`] | All type alias declarations |
+//! | [`lower_functions`] | All function declarations and class methods with a body |
+//! | [`lower_calls`] | All call macro invocations at expression sites |
 //! | [`lower_targets`] | All supported declarations as [`LoweredTarget`] |
 //!
 //! ## Example
@@ -351,6 +353,112 @@ pub fn lower_type_aliases(module: &Module, source: &str) -> Result<Vec<TypeAlias
     Ok(v.out)
 }
 
+/// Extracts all functions and class methods from a module and converts them to IR.
+///
+/// These are the targets of attribute macros. Collects:
+/// - Function declarations, including `export` and `export default` ones
+/// - Methods of named classes (getters, setters and constructors are skipped)
+/// - Parameters, return type and body
+/// - Decorators from both TypeScript decorator syntax and JSDoc comments
+///
+/// Functions without a body (overload signatures, `declare function`,
+/// abstract methods) are skipped.
+///
+/// # Arguments
+///
+/// - `module` - The parsed SWC module AST
+/// - `source` - The original source code
+///
+/// # Example
+///
+/// ```rust
+/// use macroforge_ts_syn::{lower_functions, parse_ts_module, TsSynError};
+///
+/// fn main() -> Result<(), TsSynError> {
+///     let source = r#"
+///         /** @memoize */
+///         export function fib(n: number): number {
+///             return n < 2 ? n : fib(n - 1) + fib(n - 2);
+///         }
+///     "#;
+///
+///     let module = parse_ts_module(source)?;
+///     let functions = lower_functions(&module, source)?;
+///
+///     assert_eq!(functions[0].name, "fib");
+///     assert_eq!(functions[0].params[0].ts_type, "number");
+///     assert_eq!(functions[0].decorators[0].name, "memoize");
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "swc")]
+pub fn lower_functions(module: &Module, source: &str) -> Result<Vec<FunctionIR>, TsSynError> {
+    let mut v = FunctionCollector {
+        out: vec![],
+        source,
+    };
+    module.visit_with(&mut v);
+    Ok(v.out)
+}
+
+/// Extracts all call macro invocations from a module and converts them to IR.
+///
+/// Call macros are imported like functions. A call `name(...)` or tagged
+/// template `` name`...` `` is collected when `name` is the local binding of
+/// an import for which `is_macro(module, imported_name)` holds, and no
+/// other binding called `name` is in scope at the call: a function, class or
+/// variable declaration, a parameter or a catch binding. Its [`CallIR::name`]
+/// is the imported name, so aliased imports invoke the original macro.
+/// Arguments of a collected call are not searched for further invocations.
+///
+/// # Arguments
+///
+/// - `module` - The parsed SWC module AST
+/// - `source` - The original source code
+/// - `is_macro` - Whether a name exported by a module is a call macro
+///
+/// # Example
+///
+/// ```rust
+/// use macroforge_ts_syn::{lower_calls, parse_ts_module, TsSynError};
+///
+/// fn main() -> Result<(), TsSynError> {
+///     let source = r#"
+///         import { includeStr as inc } from "macroforge";
+///         const schema = inc("./schema.graphql");
+///     "#;
+///
+///     let module = parse_ts_module(source)?;
+///     let calls = lower_calls(&module, source, |module, name| {
+///         module == "macroforge" && name == "includeStr"
+///     })?;
+///
+///     assert_eq!(calls[0].name, "includeStr");
+///     assert_eq!(calls[0].string_arg(0), Some("./schema.graphql"));
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "swc")]
+pub fn lower_calls(
+    module: &Module,
+    source: &str,
+    is_macro: impl Fn(&str, &str) -> bool,
+) -> Result<Vec<CallIR>, TsSynError> {
+    let macros = macro_imports(module, is_macro);
+    if macros.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut v = CallCollector {
+        out: vec![],
+        source,
+        macros,
+        scopes: vec![module_bindings(module)],
+        stmt_span: module.span,
+    };
+    module.visit_with(&mut v);
+    Ok(v.out)
+}
+
 #[cfg(feature = "swc")]
 struct ClassCollector<'a> {
     out: Vec<ClassIR>,
@@ -384,13 +492,25 @@ impl<'a> Visit for ClassCollector<'a> {
 
         let (fields, methods) = lower_members(&n.class.body, self.source);
 
+        let mut heritage = Vec::new();
+        if let Some(super_class) = &n.class.super_class
+            && let Expr::Ident(ident) = &**super_class
+        {
+            heritage.push(ident.sym.to_string());
+        }
+        for item in &n.class.implements {
+            if let Expr::Ident(ident) = &*item.expr {
+                heritage.push(ident.sym.to_string());
+            }
+        }
+
         self.out.push(ClassIR {
             name,
             span,
             body_span,
             is_abstract: n.class.is_abstract,
             type_params: vec![],
-            heritage: vec![], // TODO: lower extends/implements
+            heritage,
             decorators,
             decorators_ast: n.class.decorators.clone(),
             fields,
@@ -445,6 +565,486 @@ impl<'a> Visit for TypeAliasCollector<'a> {
     }
 }
 
+#[cfg(feature = "swc")]
+struct FunctionCollector<'a> {
+    out: Vec<FunctionIR>,
+    source: &'a str,
+}
+
+#[cfg(feature = "swc")]
+impl<'a> FunctionCollector<'a> {
+    fn push_function(&mut self, ident: &Ident, function: &Function, span: Span, is_exported: bool) {
+        let decorators = collect_leading_macro_directives(self.source, span.lo.0 as usize);
+        if let Some(ir) = lower_function(
+            ident.sym.to_string(),
+            function,
+            span,
+            decorators,
+            self.source,
+        ) {
+            self.out.push(FunctionIR { is_exported, ..ir });
+        }
+    }
+
+    fn push_methods(&mut self, class_name: &Ident, class: &Class) {
+        for member in &class.body {
+            let ClassMember::Method(meth) = member else {
+                continue;
+            };
+            if meth.kind != MethodKind::Method {
+                continue;
+            }
+            let PropName::Ident(key) = &meth.key else {
+                continue;
+            };
+
+            // The member span includes its decorators; the declaration starts
+            // at the first token after them.
+            let decl_lo = meth
+                .function
+                .decorators
+                .iter()
+                .map(|d| skip_whitespace(self.source, d.span.hi))
+                .max()
+                .map_or(meth.span.lo, |lo| lo.max(meth.span.lo));
+            let leading_lo = meth
+                .function
+                .decorators
+                .first()
+                .map_or(meth.span.lo, |d| d.span.lo.min(meth.span.lo));
+
+            let mut decorators =
+                collect_leading_macro_directives(self.source, leading_lo.0 as usize);
+            decorators.extend(lower_decorators(&meth.function.decorators, self.source));
+
+            let span = Span::new(decl_lo, meth.span.hi);
+            if let Some(ir) = lower_function(
+                key.sym.to_string(),
+                &meth.function,
+                span,
+                decorators,
+                self.source,
+            ) {
+                self.out.push(FunctionIR {
+                    is_static: meth.is_static,
+                    class_name: Some(class_name.sym.to_string()),
+                    ..ir
+                });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "swc")]
+impl<'a> Visit for FunctionCollector<'a> {
+    fn visit_export_decl(&mut self, n: &ExportDecl) {
+        if let Decl::Fn(f) = &n.decl {
+            self.push_function(&f.ident, &f.function, n.span, true);
+            f.function.visit_children_with(self);
+        } else {
+            n.visit_children_with(self);
+        }
+    }
+
+    fn visit_export_default_decl(&mut self, n: &ExportDefaultDecl) {
+        if let DefaultDecl::Fn(f) = &n.decl
+            && let Some(ident) = &f.ident
+        {
+            self.push_function(ident, &f.function, n.span, true);
+            f.function.visit_children_with(self);
+        } else {
+            n.visit_children_with(self);
+        }
+    }
+
+    fn visit_fn_decl(&mut self, n: &FnDecl) {
+        self.push_function(&n.ident, &n.function, n.function.span, false);
+        n.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, n: &ClassDecl) {
+        self.push_methods(&n.ident, &n.class);
+        n.visit_children_with(self);
+    }
+
+    fn visit_class_expr(&mut self, n: &ClassExpr) {
+        if let Some(ident) = &n.ident {
+            self.push_methods(ident, &n.class);
+        }
+        n.visit_children_with(self);
+    }
+}
+
+#[cfg(feature = "swc")]
+struct CallCollector<'a> {
+    out: Vec<CallIR>,
+    source: &'a str,
+    /// Local names of imported call macros, mapped to the imported names.
+    macros: std::collections::HashMap<String, String>,
+    /// Names bound by the enclosing scopes, innermost last, which shadow
+    /// call macros.
+    scopes: Vec<std::collections::HashSet<String>>,
+    /// Span of the top-level statement being visited.
+    stmt_span: Span,
+}
+
+#[cfg(feature = "swc")]
+impl<'a> CallCollector<'a> {
+    /// Whether a callee refers to a call macro.
+    fn macro_name(&self, callee: &Expr) -> Option<(String, Span)> {
+        let Expr::Ident(ident) = callee else {
+            return None;
+        };
+        let name = ident.sym.as_ref();
+        let macro_name = self.macros.get(name)?;
+        let bound = self.scopes.iter().any(|scope| scope.contains(name));
+        (!bound).then(|| (macro_name.clone(), ident.span))
+    }
+
+    /// Visits `node` with `names` bound in a new innermost scope.
+    fn visit_scoped<N: VisitWith<Self> + ?Sized>(
+        &mut self,
+        names: std::collections::HashSet<String>,
+        node: &N,
+    ) {
+        self.scopes.push(names);
+        node.visit_children_with(self);
+        self.scopes.pop();
+    }
+
+    fn lower_arg(&self, expr: &Expr, is_spread: bool) -> CallArgIR {
+        CallArgIR {
+            span: swc_span_to_ir(expr.span()),
+            source: snippet(self.source, expr.span()),
+            string_value: match expr {
+                Expr::Lit(Lit::Str(s)) => {
+                    Some(String::from_utf8_lossy(s.value.as_bytes()).to_string())
+                }
+                Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl.quasis.first().map(template_string),
+                _ => None,
+            },
+            is_spread,
+        }
+    }
+}
+
+#[cfg(feature = "swc")]
+impl<'a> Visit for CallCollector<'a> {
+    fn visit_module_item(&mut self, n: &ModuleItem) {
+        self.stmt_span = n.span();
+        n.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, n: &Function) {
+        let mut names = var_bindings(&n.body);
+        for param in &n.params {
+            pat_bindings(&param.pat, &mut names);
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_constructor(&mut self, n: &Constructor) {
+        let mut names = var_bindings(&n.body);
+        for param in &n.params {
+            match param {
+                ParamOrTsParamProp::Param(param) => pat_bindings(&param.pat, &mut names),
+                ParamOrTsParamProp::TsParamProp(prop) => match &prop.param {
+                    TsParamPropParam::Ident(ident) => {
+                        names.insert(ident.id.sym.to_string());
+                    }
+                    TsParamPropParam::Assign(assign) => pat_bindings(&assign.left, &mut names),
+                },
+            }
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_arrow_expr(&mut self, n: &ArrowExpr) {
+        let mut names = var_bindings(&*n.body);
+        for param in &n.params {
+            pat_bindings(param, &mut names);
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_setter_prop(&mut self, n: &SetterProp) {
+        let mut names = var_bindings(&n.body);
+        pat_bindings(&n.param, &mut names);
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_getter_prop(&mut self, n: &GetterProp) {
+        self.visit_scoped(var_bindings(&n.body), n);
+    }
+
+    fn visit_fn_expr(&mut self, n: &FnExpr) {
+        let names = n.ident.iter().map(|ident| ident.sym.to_string()).collect();
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_class_expr(&mut self, n: &ClassExpr) {
+        let names = n.ident.iter().map(|ident| ident.sym.to_string()).collect();
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_block_stmt(&mut self, n: &BlockStmt) {
+        let mut names = Default::default();
+        for stmt in &n.stmts {
+            if let Stmt::Decl(decl) = stmt {
+                decl_bindings(decl, &mut names);
+            }
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_for_stmt(&mut self, n: &ForStmt) {
+        let mut names = Default::default();
+        if let Some(VarDeclOrExpr::VarDecl(var)) = &n.init {
+            var_decl_bindings(var, &mut names);
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_for_in_stmt(&mut self, n: &ForInStmt) {
+        self.visit_scoped(for_head_bindings(&n.left), n);
+    }
+
+    fn visit_for_of_stmt(&mut self, n: &ForOfStmt) {
+        self.visit_scoped(for_head_bindings(&n.left), n);
+    }
+
+    fn visit_catch_clause(&mut self, n: &CatchClause) {
+        let mut names = Default::default();
+        if let Some(param) = &n.param {
+            pat_bindings(param, &mut names);
+        }
+        self.visit_scoped(names, n);
+    }
+
+    fn visit_call_expr(&mut self, n: &CallExpr) {
+        let Callee::Expr(callee) = &n.callee else {
+            n.visit_children_with(self);
+            return;
+        };
+        let Some((name, callee_span)) = self.macro_name(callee) else {
+            n.visit_children_with(self);
+            return;
+        };
+
+        let args = n
+            .args
+            .iter()
+            .map(|arg| self.lower_arg(&arg.expr, arg.spread.is_some()))
+            .collect();
+        self.out.push(CallIR {
+            name,
+            span: swc_span_to_ir(n.span),
+            callee_span: swc_span_to_ir(callee_span),
+            stmt_span: swc_span_to_ir(self.stmt_span),
+            type_args_src: n
+                .type_args
+                .as_ref()
+                .map(|t| snippet(self.source, t.span))
+                .unwrap_or_default(),
+            args,
+            template: None,
+        });
+    }
+
+    fn visit_tagged_tpl(&mut self, n: &TaggedTpl) {
+        let Some((name, callee_span)) = self.macro_name(&n.tag) else {
+            n.visit_children_with(self);
+            return;
+        };
+
+        let template = TemplateIR {
+            strings: n.tpl.quasis.iter().map(template_string).collect(),
+            exprs: n
+                .tpl
+                .exprs
+                .iter()
+                .map(|expr| self.lower_arg(expr, false))
+                .collect(),
+        };
+        self.out.push(CallIR {
+            name,
+            span: swc_span_to_ir(n.span),
+            callee_span: swc_span_to_ir(callee_span),
+            stmt_span: swc_span_to_ir(self.stmt_span),
+            type_args_src: n
+                .type_params
+                .as_ref()
+                .map(|t| snippet(self.source, t.span))
+                .unwrap_or_default(),
+            args: Vec::new(),
+            template: Some(template),
+        });
+    }
+}
+
+/// The cooked text of a template part, or the raw text if it has an invalid
+/// escape.
+#[cfg(feature = "swc")]
+fn template_string(quasi: &TplElement) -> String {
+    quasi
+        .cooked
+        .as_ref()
+        .map(|cooked| String::from_utf8_lossy(cooked.as_bytes()).to_string())
+        .unwrap_or_else(|| quasi.raw.to_string())
+}
+
+/// Local names of the imports `is_macro` accepts, mapped to the imported
+/// names.
+#[cfg(feature = "swc")]
+fn macro_imports(
+    module: &Module,
+    is_macro: impl Fn(&str, &str) -> bool,
+) -> std::collections::HashMap<String, String> {
+    let mut macros = std::collections::HashMap::new();
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        if import.type_only {
+            continue;
+        }
+        let module_source = import.src.value.to_string_lossy();
+        for specifier in &import.specifiers {
+            let ImportSpecifier::Named(named) = specifier else {
+                continue;
+            };
+            if named.is_type_only {
+                continue;
+            }
+            let imported = match &named.imported {
+                Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                Some(ModuleExportName::Str(s)) => s.value.to_string_lossy().to_string(),
+                None => named.local.sym.to_string(),
+            };
+            if is_macro(&module_source, &imported) {
+                macros.insert(named.local.sym.to_string(), imported);
+            }
+        }
+    }
+    macros
+}
+
+/// Names bound by top-level declarations. Imports are left out: an import
+/// cannot share its name with another top-level binding.
+#[cfg(feature = "swc")]
+fn module_bindings(module: &Module) -> std::collections::HashSet<String> {
+    let mut bound = std::collections::HashSet::new();
+    for item in &module.body {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                decl_bindings(&export.decl, &mut bound);
+            }
+            ModuleItem::Stmt(Stmt::Decl(decl)) => decl_bindings(decl, &mut bound),
+            _ => {}
+        }
+    }
+    bound.extend(var_bindings(module));
+    bound
+}
+
+/// Adds the names a declaration binds in its scope.
+#[cfg(feature = "swc")]
+fn decl_bindings(decl: &Decl, bound: &mut std::collections::HashSet<String>) {
+    match decl {
+        Decl::Fn(f) => {
+            bound.insert(f.ident.sym.to_string());
+        }
+        Decl::Class(c) => {
+            bound.insert(c.ident.sym.to_string());
+        }
+        Decl::Var(var) => var_decl_bindings(var, bound),
+        Decl::TsEnum(e) => {
+            bound.insert(e.id.sym.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Adds the names a variable declaration binds.
+#[cfg(feature = "swc")]
+fn var_decl_bindings(var: &VarDecl, bound: &mut std::collections::HashSet<String>) {
+    for declarator in &var.decls {
+        pat_bindings(&declarator.name, bound);
+    }
+}
+
+/// Adds the names a binding pattern binds, e.g. `a` and `b` for
+/// `{ a, b: [b] }`.
+#[cfg(feature = "swc")]
+fn pat_bindings(pat: &Pat, bound: &mut std::collections::HashSet<String>) {
+    match pat {
+        Pat::Ident(binding) => {
+            bound.insert(binding.id.sym.to_string());
+        }
+        Pat::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                pat_bindings(elem, bound);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(kv) => pat_bindings(&kv.value, bound),
+                    ObjectPatProp::Assign(assign) => {
+                        bound.insert(assign.key.sym.to_string());
+                    }
+                    ObjectPatProp::Rest(rest) => pat_bindings(&rest.arg, bound),
+                }
+            }
+        }
+        Pat::Rest(rest) => pat_bindings(&rest.arg, bound),
+        Pat::Assign(assign) => pat_bindings(&assign.left, bound),
+        Pat::Expr(_) | Pat::Invalid(_) => {}
+    }
+}
+
+/// Names bound by the `let` / `const` declaration in a `for ... in` or
+/// `for ... of` head.
+#[cfg(feature = "swc")]
+fn for_head_bindings(head: &ForHead) -> std::collections::HashSet<String> {
+    let mut bound = std::collections::HashSet::new();
+    if let ForHead::VarDecl(var) = head {
+        var_decl_bindings(var, &mut bound);
+    }
+    bound
+}
+
+/// Names declared with `var` in a function body (or module), which are
+/// hoisted to it from nested blocks but not from nested functions.
+#[cfg(feature = "swc")]
+fn var_bindings<N: VisitWith<VarCollector> + ?Sized>(
+    body: &N,
+) -> std::collections::HashSet<String> {
+    let mut collector = VarCollector(Default::default());
+    body.visit_with(&mut collector);
+    collector.0
+}
+
+/// Collects `var` declarations for [`var_bindings`].
+#[cfg(feature = "swc")]
+struct VarCollector(std::collections::HashSet<String>);
+
+#[cfg(feature = "swc")]
+impl Visit for VarCollector {
+    fn visit_var_decl(&mut self, n: &VarDecl) {
+        if n.kind == VarDeclKind::Var {
+            var_decl_bindings(n, &mut self.0);
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
 #[cfg(feature = "swc")]
 struct TargetCollector<'a> {
     out: Vec<LoweredTarget>,
@@ -533,12 +1133,19 @@ fn lower_interface(n: &TsInterfaceDecl, source: &str) -> Option<InterfaceIR> {
 
     let (fields, methods) = lower_interface_members(&n.body.body, source);
 
+    let mut heritage = Vec::new();
+    for ext in &n.extends {
+        if let Expr::Ident(ident) = &*ext.expr {
+            heritage.push(ident.sym.to_string());
+        }
+    }
+
     Some(InterfaceIR {
         name,
         span,
         body_span,
         type_params: vec![], // TODO: extract type params
-        heritage: vec![],    // TODO: extract extends
+        heritage,
         decorators,
         fields,
         methods,
@@ -974,6 +1581,128 @@ fn lower_members(body: &[ClassMember], source: &str) -> (Vec<FieldIR>, Vec<Metho
     (fields, methods)
 }
 
+/// Lowers the parts of a function shared by declarations and methods.
+/// Returns `None` for functions without a body.
+#[cfg(feature = "swc")]
+fn lower_function(
+    name: String,
+    function: &Function,
+    span: Span,
+    decorators: Vec<DecoratorIR>,
+    source: &str,
+) -> Option<FunctionIR> {
+    let body = function.body.as_ref()?;
+    let body_text = snippet(source, body.span);
+    let body_src = body_text
+        .strip_prefix('{')
+        .and_then(|inner| inner.strip_suffix('}'))
+        .unwrap_or_default()
+        .to_string();
+
+    let params_src = match (function.params.first(), function.params.last()) {
+        (Some(first), Some(last)) => snippet(source, Span::new(first.span.lo, last.span.hi)),
+        _ => String::new(),
+    };
+
+    Some(FunctionIR {
+        name,
+        span: swc_span_to_ir(span),
+        body_span: swc_span_to_ir(body.span),
+        body_src,
+        type_params_src: function
+            .type_params
+            .as_ref()
+            .map(|t| snippet(source, t.span))
+            .unwrap_or_default(),
+        params: function
+            .params
+            .iter()
+            .map(|p| lower_param(&p.pat, p.span, source))
+            .collect(),
+        params_src,
+        return_type_src: function
+            .return_type
+            .as_ref()
+            .map(|t| snippet(source, t.type_ann.span()).trim().to_string())
+            .unwrap_or_default(),
+        is_async: function.is_async,
+        is_generator: function.is_generator,
+        is_static: false,
+        is_exported: false,
+        class_name: None,
+        decorators,
+    })
+}
+
+#[cfg(feature = "swc")]
+fn lower_param(pat: &Pat, span: Span, source: &str) -> ParamIR {
+    // `x = 1` is optional whatever its pattern
+    let (pat, has_default) = match pat {
+        Pat::Assign(assign) => (&*assign.left, true),
+        other => (other, false),
+    };
+
+    let (name, type_ann, optional, is_rest) = match pat {
+        Pat::Ident(binding) => (
+            binding.id.sym.to_string(),
+            binding.type_ann.as_ref(),
+            binding.id.optional,
+            false,
+        ),
+        Pat::Rest(rest) => (
+            snippet(source, rest.arg.span()),
+            rest.type_ann.as_ref(),
+            false,
+            true,
+        ),
+        Pat::Array(array) => (
+            pattern_source(source, array.span, array.type_ann.as_deref()),
+            array.type_ann.as_ref(),
+            array.optional,
+            false,
+        ),
+        Pat::Object(object) => (
+            pattern_source(source, object.span, object.type_ann.as_deref()),
+            object.type_ann.as_ref(),
+            object.optional,
+            false,
+        ),
+        other => (snippet(source, other.span()), None, false, false),
+    };
+
+    ParamIR {
+        name,
+        span: swc_span_to_ir(span),
+        ts_type: type_ann
+            .map(|t| snippet(source, t.type_ann.span()).trim().to_string())
+            .unwrap_or_else(|| "any".into()),
+        optional: optional || has_default,
+        is_rest,
+    }
+}
+
+/// Source of a destructuring pattern without its type annotation.
+#[cfg(feature = "swc")]
+fn pattern_source(source: &str, span: Span, type_ann: Option<&TsTypeAnn>) -> String {
+    let end = type_ann.map_or(span.hi, |t| t.span.lo);
+    snippet(source, Span::new(span.lo, end))
+        .trim_end()
+        .trim_end_matches(':')
+        .trim_end()
+        .trim_end_matches('?')
+        .to_string()
+}
+
+/// Returns the position of the first non-whitespace byte at or after `pos`.
+#[cfg(feature = "swc")]
+fn skip_whitespace(source: &str, pos: swc_core::common::BytePos) -> swc_core::common::BytePos {
+    let start = (pos.0 as usize).saturating_sub(1);
+    let skipped = source
+        .get(start..)
+        .map_or(0, |rest| rest.len() - rest.trim_start().len());
+    swc_core::common::BytePos(pos.0 + skipped as u32)
+}
+
 #[cfg(feature = "swc")]
 fn lower_decorators(decs: &[Decorator], source: &str) -> Vec<DecoratorIR> {
     decs.iter()
@@ -1531,6 +2260,25 @@ mod tests {
         });
     }
 
+    #[cfg(feature = "swc")]
+    #[test]
+    fn test_class_and_interface_heritage() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = "class User extends Base implements Node, Named<string> {}\ninterface Admin extends User, Named<number> {}";
+            let module = parse_module(source);
+            let classes = lower_classes(&module, source).expect("lowering to succeed");
+            assert_eq!(classes[0].heritage, ["Base", "Node", "Named"]);
+            let interfaces = lower_interfaces(&module, source).expect("lowering to succeed");
+            assert_eq!(interfaces[0].heritage, ["User", "Named"]);
+        });
+    }
+
+    /// Accepts the call macros the tests import from `macroforge`.
+    #[cfg(feature = "swc")]
+    fn is_builtin(module: &str, name: &str) -> bool {
+        module == "macroforge" && matches!(name, "includeStr" | "sql")
+    }
+
     #[cfg(feature = "swc")]
     fn parse_module(source: &str) -> Module {
         let cm: Lrc<SourceMap> = Default::default();
@@ -1837,4 +2585,184 @@ type ContactInfo = {
             }
         });
     }
+
+    #[cfg(feature = "swc")]
+    #[test]
+    fn lowers_decorated_methods() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            class Api {
+                @retry({ times: 3 })
+                @trace
+                public async fetchUser(id: string, { force }: Options = {}, ...rest: number[]): Promise<User> {
+                    return load(id);
+                }
+
+                get name(): string { return ""; }
+            }
+            "#;
+            let module = parse_module(source);
+            let functions = lower_functions(&module, source).expect("lowering to succeed");
+            assert_eq!(functions.len(), 1, "getters are not attribute targets");
+
+            let method = &functions[0];
+            assert_eq!(method.name, "fetchUser");
+            assert_eq!(method.class_name.as_deref(), Some("Api"));
+            assert!(method.is_async);
+            assert_eq!(method.return_type_src, "Promise<User>");
+            assert_eq!(method.body_src.trim(), "return load(id);");
+
+            let names: Vec<_> = method.decorators.iter().map(|d| d.name.as_str()).collect();
+            assert_eq!(names, ["retry", "trace"]);
+            assert_eq!(method.decorators[0].args_src, "{ times: 3 }");
+
+            let decl = &source[method.span.start as usize - 1..method.span.end as usize - 1];
+            assert!(decl.starts_with("public async fetchUser("), "got {decl:?}");
+
+            assert_eq!(method.params.len(), 3);
+            assert_eq!(method.params[0].ts_type, "string");
+            assert_eq!(method.params[1].name, "{ force }");
+            assert_eq!(method.params[1].ts_type, "Options");
+            assert!(method.params[1].optional);
+            assert_eq!(method.params[2].name, "rest");
+            assert!(method.params[2].is_rest);
+        });
+    }
+
+    #[cfg(feature = "swc")]
+    #[test]
+    fn lowers_functions_with_jsdoc_directives() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            /** @memoize */
+            export function fib(n: number): number {
+                return n < 2 ? n : fib(n - 1) + fib(n - 2);
+            }
+
+            function overload(x: string): string;
+            function overload(x: any) { return x; }
+            "#;
+            let module = parse_module(source);
+            let functions = lower_functions(&module, source).expect("lowering to succeed");
+            assert_eq!(functions.len(), 2, "overload signatures are skipped");
+
+            let fib = &functions[0];
+            assert!(fib.is_exported);
+            assert!(fib.class_name.is_none());
+            assert_eq!(fib.decorators.len(), 1);
+            assert_eq!(fib.decorators[0].name, "memoize");
+            assert_eq!(fib.params_src, "n: number");
+
+            let decl = &source[fib.span.start as usize - 1..fib.span.end as usize - 1];
+            assert!(decl.starts_with("export function fib"), "got {decl:?}");
+            assert!(decl.ends_with('}'));
+
+            let overload = &functions[1];
+            assert_eq!(overload.params[0].ts_type, "any");
+            assert!(overload.decorators.is_empty());
+            assert_eq!(overload.return_type_src, "");
+        });
+    }
+
+    #[test]
+    fn lowers_call_macros() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            import { includeStr, sql } from "macroforge";
+            const schema = includeStr("./schema.graphql", ...rest);
+            function load(id: string) {
+                return sql<User>`SELECT * FROM users WHERE id = ${id}`;
+            }
+            const outer = includeStr(includeStr("./nested.txt"));
+            "#;
+            let module = parse_module(source);
+            let calls = lower_calls(&module, source, is_builtin).expect("lowering to succeed");
+            assert_eq!(calls.len(), 3, "nested invocations are not collected");
+
+            let include = &calls[0];
+            assert_eq!(include.name, "includeStr");
+            assert_eq!(include.string_arg(0), Some("./schema.graphql"));
+            assert!(include.args[1].is_spread);
+            assert_eq!(include.args[1].source, "rest");
+            let stmt =
+                &source[include.stmt_span.start as usize - 1..include.stmt_span.end as usize - 1];
+            assert!(stmt.starts_with("const schema"), "got {stmt:?}");
+
+            let sql = &calls[1];
+            assert!(sql.is_tagged_template());
+            assert_eq!(sql.type_args_src, "<User>");
+            let template = sql.template.as_ref().unwrap();
+            assert_eq!(template.strings, ["SELECT * FROM users WHERE id = ", ""]);
+            assert_eq!(template.exprs[0].source, "id");
+            let stmt = &source[sql.stmt_span.start as usize - 1..sql.stmt_span.end as usize - 1];
+            assert!(stmt.starts_with("function load"), "got {stmt:?}");
+
+            assert_eq!(calls[2].args[0].source, r#"includeStr("./nested.txt")"#);
+        });
+    }
+
+    #[test]
+    fn call_macros_must_be_imported() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            import { sql } from "./db";
+            const a = sql`SELECT 1`;
+            const b = includeStr("./schema.graphql");
+            "#;
+            let module = parse_module(source);
+            let calls = lower_calls(&module, source, is_builtin).expect("lowering to succeed");
+            assert!(calls.is_empty(), "got {calls:?}");
+        });
+    }
+
+    #[test]
+    fn call_macros_imported_under_an_alias() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            import { includeStr as inc } from "macroforge";
+            const schema = inc("./schema.graphql");
+            const other = includeStr("./other.graphql");
+            "#;
+            let module = parse_module(source);
+            let calls = lower_calls(&module, source, is_builtin).expect("lowering to succeed");
+            assert_eq!(calls.len(), 1, "got {calls:?}");
+            assert_eq!(calls[0].name, "includeStr");
+            assert_eq!(calls[0].string_arg(0), Some("./schema.graphql"));
+        });
+    }
+
+    #[test]
+    fn call_macros_are_shadowed_by_local_bindings() {
+        GLOBALS.set(&Globals::new(), || {
+            let source = r#"
+            import { env } from "macroforge";
+            function param(env: (name: string) => string) { return env("A"); }
+            function local() {
+                const env = (name: string) => name;
+                return env("B");
+            }
+            function hoisted() {
+                if (true) { var env = String; }
+                return env("C");
+            }
+            const arrow = ({ env }: { env: (name: string) => string }) => env("D");
+            try {} catch (env) { env("E"); }
+            function inner() {
+                class env {}
+                return new env() && env("F");
+            }
+            function after() {
+                { const env = String; }
+                return env("G");
+            }
+            "#;
+            let module = parse_module(source);
+            let calls = lower_calls(&module, source, |module, name| {
+                module == "macroforge" && name == "env"
+            })
+            .expect("lowering to succeed");
+            assert_eq!(calls.len(), 1, "got {calls:?}");
+            assert_eq!(calls[0].string_arg(0), Some("G"));
+        });
+    }
 }
@@ -219,6 +219,12 @@ pub struct TsStream {
     pub ctx: Option<crate::abi::MacroContextIR>,
     /// Runtime patches to apply (e.g., imports at file level)
     pub runtime_patches: Vec<crate::abi::Patch>,
+    /// Patches applied only to the type output (`.d.ts`)
+    pub type_patches: Vec<crate::abi::Patch>,
+    /// Non-fatal diagnostics (e.g., warnings) reported alongside the output
+    pub diagnostics: Vec<crate::abi::Diagnostic>,
+    /// Files the output was generated from
+    pub dependencies: Vec<String>,
 }
 
 /// Formats TypeScript source code using SWC's emitter.
@@ -337,6 +343,9 @@ impl TsStream {
             file_name: file_name.to_string(),
             ctx: None,
             runtime_patches: vec![],
+            type_patches: vec![],
+            diagnostics: vec![],
+            dependencies: vec![],
         })
     }
 
@@ -348,6 +357,9 @@ impl TsStream {
             file_name: "macro_output.ts".to_string(),
             ctx: None,
             runtime_patches: vec![],
+            type_patches: vec![],
+            diagnostics: vec![],
+            dependencies: vec![],
         }
     }
 
@@ -369,6 +381,9 @@ impl TsStream {
             file_name: file_name.to_string(),
             ctx: Some(ctx),
             runtime_patches: vec![],
+            type_patches: vec![],
+            diagnostics: vec![],
+            dependencies: vec![],
         })
     }
 
@@ -381,13 +396,44 @@ impl TsStream {
     pub fn into_result(self) -> crate::abi::MacroResult {
         crate::abi::MacroResult {
             runtime_patches: self.runtime_patches,
-            type_patches: vec![],
-            diagnostics: vec![],
+            type_patches: self.type_patches,
+            diagnostics: self.diagnostics,
             tokens: Some(self.source),
             debug: None,
+            dependencies: self.dependencies,
         }
     }
 
+    /// Attach non-fatal diagnostics (e.g., warnings) to the macro output.
+    ///
+    /// Errors should be returned as `Err(MacroforgeError)` instead; diagnostics
+    /// added here are reported without failing the expansion.
+    pub fn add_diagnostics(
+        &mut self,
+        diagnostics: impl IntoIterator<Item = crate::abi::Diagnostic>,
+    ) {
+        self.diagnostics.extend(diagnostics);
+    }
+
+    /// Record a file the output was generated from, so build tools expand
+    /// the source again when it changes.
+    pub fn add_dependency(&mut self, path: impl Into<String>) {
+        self.dependencies.push(path.into());
+    }
+
+    /// Replace a span of the original source in both the runtime and the type
+    /// output, e.g. to rewrite the declaration a derive is attached to.
+    pub fn replace_span(&mut self, span: crate::abi::SpanIR, code: &str, source_macro: &str) {
+        use crate::abi::{Patch, PatchCode};
+        let patch = Patch::Replace {
+            span,
+            code: PatchCode::Text(code.to_string()),
+            source_macro: Some(source_macro.to_string()),
+        };
+        self.runtime_patches.push(patch.clone());
+        self.type_patches.push(patch);
+    }
+
     /// Add an import statement to be inserted at the top of the file.
     /// The import will be deduplicated if it already exists.
     pub fn add_import(&mut self, specifier: &str, module: &str) {
@@ -320,11 +320,15 @@ export function macroforgePreprocess(
     /*
      * STEP 2: Quick Scan Optimization
      *
-     * Before loading native bindings, do a cheap string check for "@derive".
+     * Before loading native bindings, do a cheap string check for "@derive"
+     * or an import of call macros such as `includeStr` from "macroforge".
      * Most components won't have macros, so this saves the cost of loading
      * and calling the native expansion engine in the common case.
      */
-    if (!content.includes("@derive")) {
+    if (
+      !content.includes("@derive") &&
+      !/from\s+["']macroforge["']/.test(content)
+    ) {
       return;
     }
 
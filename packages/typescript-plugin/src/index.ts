@@ -721,7 +721,8 @@ function hasMacroDirectives(text: string) {
   return (
     text.includes("@derive") ||
     /\/\*\*\s*@derive\s*\(/i.test(text) ||
-    /\/\*\*\s*import\s+macro\b/i.test(text)
+    /\/\*\*\s*import\s+macro\b/i.test(text) ||
+    /from\s+["']macroforge["']/.test(text)
   );
 }
 
@@ -1541,6 +1542,21 @@ function init(modules: { typescript: typeof ts }) {
               return { start: 0, length: 7 };
             }
 
+            // Replacements that no @derive accounts for (e.g. call macro
+            // invocations) are reported at the code they replaced
+            if (
+              region.originalStart != null &&
+              region.originalEnd != null &&
+              !deriveDecorators.some((dec) =>
+                dec.macros.some((m) => m.name === macroName),
+              )
+            ) {
+              return {
+                start: region.originalStart,
+                length: region.originalEnd - region.originalStart,
+              };
+            }
+
             // Find the segment that ends right before this generated region
             const segments = result.sourceMapping!.segments;
             let insertionPointInOriginal = 0;
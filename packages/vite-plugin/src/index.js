@@ -509,6 +509,11 @@ export async function macroforge() {
           }
         }
 
+        // Re-run the transform when a file read by a macro changes
+        for (const dependency of result.dependencies ?? []) {
+          /** @type {any} */ (this).addWatchFile(dependency);
+        }
+
         if (result && result.code) {
           // Remove macro-only imports so SSR output doesn't load native bindings
           result.code = result.code.replace(